@@ -17,6 +17,12 @@ defmodule IgniterJs.Application do
       # {IgniterJs.Worker, arg}
     ]
 
+    :ok = IgniterJs.Limits.set(IgniterJs.Limits.get())
+
+    if Application.get_env(:igniter_js, :telemetry, false) do
+      IgniterJs.Telemetry.enable()
+    end
+
     # See https://hexdocs.pm/elixir/Supervisor.html
     # for other strategies and supported options
     opts = [strategy: :one_for_one, name: IgniterJs.Supervisor]
@@ -0,0 +1,42 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.CodegenOptions do
+  @moduledoc """
+  Options of the JavaScript code generated by the functions that re-print a source, shared
+  by every function and sent with the arguments of each call.
+
+    - `:preserve_code_style` - quotes the strings added by an edit like most strings of the
+      source, and drops the `;` at the end of the statements when most statements of the
+      source have none, so editing a file written with `'` and without semicolons does not
+      change its style. `false` by default, the code is then printed with `"` and `;`.
+
+  They are read from the config:
+
+  ```elixir
+  config :igniter_js, preserve_code_style: true
+  ```
+  """
+
+  @doc """
+  The codegen options from the config, with their defaults.
+  """
+  def get do
+    %{preserve_code_style: Application.get_env(:igniter_js, :preserve_code_style, false)}
+  end
+
+  @doc """
+  Sets the codegen options of the NIF calls made after it. The missing options keep their
+  value from the config.
+
+  ```elixir
+  IgniterJs.CodegenOptions.set(preserve_code_style: true)
+  ```
+  """
+  def set(options) do
+    options = Map.merge(get(), Map.new(options))
+    Application.put_env(:igniter_js, :preserve_code_style, options.preserve_code_style)
+  end
+end
@@ -27,9 +27,13 @@ defmodule IgniterJs.Helpers do
     end
   end
 
+  @extensions [".js", ".ts", ".css", ".scss", ".less", ".json", ".yaml", ".yml"]
+
   @doc """
   Read and validate the file. It returns the file content if the file exists and the
-  extension is `.js` or `.ts`, otherwise, it returns an error tuple.
+  extension is `.js`, `.ts`, `.css`, `.scss`, `.less`, `.json`, `.yaml` or `.yml`,
+  otherwise, it returns an error tuple.
+  The SCSS and Less files are read so the CSS functions can report their dialect.
 
   ```elixir
   read_and_validate_file("/path/to/file.js")
@@ -38,7 +42,7 @@ defmodule IgniterJs.Helpers do
   # sobelow_skip ["Traversal.FileModule"]
   def read_and_validate_file(file_path) do
     with true <- File.exists?(file_path),
-         true <- Path.extname(file_path) in [".js", ".ts"],
+         true <- Path.extname(file_path) in @extensions,
          {:ok, file_content} <- File.read(file_path) do
       {:ok, file_content}
     else
@@ -60,19 +64,130 @@ defmodule IgniterJs.Helpers do
   def call_nif_fn(file_path, caller_function, processing_fn, type \\ :content)
 
   def call_nif_fn(file_content, caller_function, processing_fn, :content) do
-    processing_fn.(file_content)
-    |> normalize_output(caller_function)
+    run_nif_fn(file_content, caller_function, processing_fn)
   end
 
   def call_nif_fn(file_path, caller_function, processing_fn, :path) do
     case read_and_validate_file(file_path) do
       {:ok, file_content} ->
-        processing_fn.(file_content)
-        |> normalize_output(caller_function)
+        run_nif_fn(file_content, caller_function, processing_fn)
 
       reason ->
         Tuple.insert_at(reason, 1, :none)
         |> normalize_output(caller_function)
     end
   end
+
+  # Sources exceeding `:max_input_size` are refused before reaching the NIF, see
+  # `IgniterJs.Limits`.
+  defp run_nif_fn(file_content, caller_function, processing_fn) do
+    case IgniterJs.Limits.check_input_size(file_content) do
+      :ok ->
+        processing_fn.(file_content)
+        |> take_metrics(caller_function)
+        |> take_edit()
+        |> normalize_output(caller_function)
+
+      too_large ->
+        {:error, elem(caller_function, 0), too_large}
+    end
+  end
+
+  @doc """
+  Take the metrics a NIF returns after its result when they are enabled, see
+  `IgniterJs.Telemetry`, and emit them for the caller function. The output is returned as a
+  tuple of the status, the function atom and the result.
+
+  ```elixir
+  take_metrics({:ok, :fun_atom, result, metrics}, __ENV__.function)
+  # => {:ok, :fun_atom, result}
+  ```
+  """
+  def take_metrics({status, fn_atom, result, metrics}, caller_function) do
+    IgniterJs.Telemetry.emit(metrics, elem(caller_function, 0), status)
+    {status, fn_atom, result}
+  end
+
+  def take_metrics(output, _caller_function), do: output
+
+  @doc """
+  The name of the source in the source map of generated code. It is the `:source_name`
+  option, otherwise the file path when the type is `:path`, otherwise `"input.js"`.
+
+  ```elixir
+  source_name("/path/to/file.js", :path, [])
+  source_name("file content", :content, source_name: "app.js")
+  ```
+  """
+  def source_name(file_path_or_content, type, opts) do
+    Keyword.get_lazy(opts, :source_name, fn ->
+      if type == :path, do: file_path_or_content, else: "input.js"
+    end)
+  end
+
+  @doc """
+  The kind of module to parse a source as: the `:module_kind` option, otherwise `:module`
+  for `.mjs` and `:script` for `.cjs` files when the type is `:path`, otherwise `:auto`,
+  which detects CommonJS scripts from their `require` and `module.exports` usage.
+
+  ```elixir
+  module_kind("/path/to/postcss.config.cjs", :path, [])
+  module_kind("file content", :content, module_kind: :script)
+  ```
+  """
+  def module_kind(file_path_or_content, type, opts) do
+    Keyword.get_lazy(opts, :module_kind, fn ->
+      cond do
+        type == :path and String.ends_with?(file_path_or_content, ".mjs") -> :module
+        type == :path and String.ends_with?(file_path_or_content, ".cjs") -> :script
+        true -> :auto
+      end
+    end)
+  end
+
+  @doc """
+  The options of the formatting checks from a keyword list: `:normalize_newlines`, which
+  defaults to `true`, and `:final_newline`, `:ignore` (default) or `:require`.
+  """
+  def format_check_options(opts) do
+    %{
+      normalize_newlines: Keyword.get(opts, :normalize_newlines, true),
+      final_newline: Keyword.get(opts, :final_newline, :ignore)
+    }
+  end
+
+  @changed_key {__MODULE__, :with_changed}
+
+  @doc """
+  Tell whether the functions editing a source changed it. Within `fun`, their `{:ok, function,
+  code}` results become `{:ok, function, %{changed: boolean, code: code}}`, and errors are
+  returned as they are.
+
+  The edits report it themselves and return their input untouched when they have nothing to
+  do, e.g. adding a hook that is already registered, so `changed` is `false` exactly when
+  there is nothing to write.
+
+  ```elixir
+  with_changed(fn -> Parser.extend_hook_object(js_content, "CopyHook") end)
+  # => {:ok, :extend_hook_object, %{changed: false, code: js_content}}
+  ```
+  """
+  def with_changed(fun) do
+    previous = Process.put(@changed_key, true)
+
+    try do
+      fun.()
+    after
+      if previous, do: Process.put(@changed_key, previous), else: Process.delete(@changed_key)
+    end
+  end
+
+  # The edits return their code along with whether it changed, which is only kept within
+  # `with_changed/1`.
+  defp take_edit({:ok, fn_atom, %{code: code, changed: _} = edit} = output)
+       when map_size(edit) == 2 do
+    if Process.get(@changed_key), do: output, else: {:ok, fn_atom, code}
+  end
+
+  defp take_edit(output), do: output
 end
@@ -0,0 +1,134 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Limits do
+  @moduledoc """
+  Limits on the size of the sources, so pointing a function at a bundled vendor file, such
+  as a minified `app.js`, fails fast instead of stalling a scheduler.
+
+    - `:max_input_size` - the maximum size of a source in bytes, 10 MiB by default.
+    - `:max_node_count` - the maximum number of nodes of a parsed JavaScript syntax tree,
+      i.e. its statements, expressions and patterns, 1,000,000 by default.
+    - `:max_depth` - the maximum nesting depth of the brackets, braces and parentheses of a
+      source, and of the collections of a YAML file, 256 by default. The parsers recurse
+      into every level, and a stack overflow takes the whole VM down unlike an error.
+    - `:timeout` - the maximum time of a NIF call in milliseconds, none by default.
+
+  A limit of `0` is no limit. They are set at startup from the config:
+
+  ```elixir
+  config :igniter_js, max_input_size: 20_971_520, max_node_count: 0, timeout: 5_000
+  ```
+
+  A source exceeding a limit returns an error with the limit, its size and the maximum:
+
+  ```elixir
+  {:error, :extend_hook_object,
+   {:too_large, %{limit: :input_size, size: 11_534_336, max: 10_485_760}}}
+  ```
+
+  The time of a call is checked before and after its parses, and while a JavaScript source
+  is read, so a call on a pathological source stops at the first check past its timeout and
+  returns `:timeout`:
+
+  ```elixir
+  {:error, :insert_imports, :timeout}
+  ```
+
+  A call can have its own timeout instead, see `with_timeout/2`.
+  """
+
+  alias IgniterJs.Native
+
+  @default_max_input_size 10_485_760
+  @default_max_node_count 1_000_000
+  @default_max_depth 256
+  @default_timeout 0
+  @call_timeout_key {__MODULE__, :call_timeout}
+
+  @doc """
+  The limits from the config, with their defaults.
+  """
+  def get do
+    %{
+      max_input_size: Application.get_env(:igniter_js, :max_input_size, @default_max_input_size),
+      max_node_count: Application.get_env(:igniter_js, :max_node_count, @default_max_node_count),
+      max_depth: Application.get_env(:igniter_js, :max_depth, @default_max_depth),
+      timeout: Application.get_env(:igniter_js, :timeout, @default_timeout)
+    }
+  end
+
+  @doc """
+  Sets the limits of every NIF call, and of the size checks before them. The missing limits
+  keep their value from the config.
+
+  ```elixir
+  IgniterJs.Limits.set(max_input_size: 1_048_576)
+  ```
+  """
+  def set(limits) do
+    limits = Map.merge(get(), Map.new(limits))
+    Application.put_env(:igniter_js, :max_input_size, limits.max_input_size)
+    Application.put_env(:igniter_js, :max_node_count, limits.max_node_count)
+    Application.put_env(:igniter_js, :max_depth, limits.max_depth)
+    Application.put_env(:igniter_js, :timeout, limits.timeout)
+
+    {:ok, :set_limits_nif, _limits} =
+      IgniterJs.Helpers.take_metrics(Native.set_limits_nif(limits), __ENV__.function)
+
+    :ok
+  end
+
+  @doc """
+  Runs a function with the given timeout in milliseconds for the NIF calls it makes, instead
+  of the `:timeout` of the limits. A timeout of `0` is no timeout.
+
+  ```elixir
+  IgniterJs.Limits.with_timeout(500, fn ->
+    IgniterJs.Parsers.Javascript.Parser.insert_imports(js_content, imports)
+  end)
+  ```
+  """
+  def with_timeout(timeout, fun) when is_integer(timeout) and timeout >= 0 do
+    previous = Process.put(@call_timeout_key, timeout)
+
+    try do
+      fun.()
+    after
+      if previous,
+        do: Process.put(@call_timeout_key, previous),
+        else: Process.delete(@call_timeout_key)
+    end
+  end
+
+  @doc """
+  The timeout of `with_timeout/2` in the current process, or `nil` for the `:timeout` of the
+  limits. It is sent with the arguments of every NIF call.
+  """
+  def call_timeout, do: Process.get(@call_timeout_key)
+
+  @doc """
+  Checks the size of a source before it is sent to a NIF, returning the `:too_large` reason
+  when it exceeds `:max_input_size`.
+
+  ```elixir
+  check_input_size("let a = 1;")
+  # => :ok
+  ```
+  """
+  def check_input_size(content) when is_binary(content) do
+    max = get().max_input_size
+    size = byte_size(content)
+
+    if max == 0 or size <= max do
+      :ok
+    else
+      {:too_large, %{limit: :input_size, size: size, max: max}}
+    end
+  end
+
+  # A parsed `Document` or `SyntaxTree` is a reference, checked when its source was parsed
+  def check_input_size(_parsed), do: :ok
+end
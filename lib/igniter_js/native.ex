@@ -33,38 +33,549 @@ defmodule IgniterJs.Native do
         System.get_env("ASH_CI_BUILD") in ["1", "true"]
 
   # When your NIF is loaded, it will override this function.
-  def is_module_imported_from_ast_nif(_file_content, _module_name), do: error()
+  def is_module_imported_from_ast_nif(
+        _file_content,
+        _module_name,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def insert_import_to_ast_nif(_file_content, _import_lines, _call_options \\ call_options()),
+    do: error()
+
+  def remove_import_from_ast_nif(_file_content, _modules, _mode, _call_options \\ call_options()),
+    do: error()
+
+  def replace_import_nif(_file_content, _source, _import_lines, _call_options \\ call_options()),
+    do: error()
+
+  def organize_imports_nif(_file_content, _separate_groups, _call_options \\ call_options()),
+    do: error()
+
+  def find_live_socket_node_from_ast_nif(_file_content, _call_options \\ call_options()),
+    do: error()
+
+  def contains_variable_from_ast_nif(
+        _file_content,
+        _variable_name,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def extend_hook_object_to_ast_nif(
+        _file_content,
+        _names,
+        _order,
+        _key_position,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_objects_of_hooks_from_ast_nif(
+        _file_content,
+        _object_names,
+        _mode,
+        _prune,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def statistics_from_ast_nif(_file_content, _with_locations, _call_options \\ call_options()),
+    do: error()
+
+  def extend_var_object_property_by_names_to_ast_nif(
+        _file_content,
+        _var_name,
+        _object_names,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def format_js_nif(
+        _file_content,
+        _module_kind,
+        _template_literals,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def is_js_formatted_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def format_css_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def convert_ast_to_estree_nif(_file_content, _options, _call_options \\ call_options()),
+    do: error()
+
+  def convert_ast_to_estree_statements_nif(
+        _file_content,
+        _options,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def estree_to_source_nif(_estree_json, _call_options \\ call_options()), do: error()
+
+  def insert_ast_at_index_nif(
+        _file_content,
+        _insert_code,
+        _index,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def replace_ast_at_index_nif(
+        _file_content,
+        _replace_code,
+        _index,
+        _prune,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def is_css_formatted_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def apply_asset_patch_nif(
+        _js_content,
+        _js_ops,
+        _css_content,
+        _css_ops,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def find_sdk_initializations_from_ast_nif(_file_content, _call_options \\ call_options()),
+    do: error()
+
+  def ensure_colocated_hooks_to_ast_nif(
+        _file_content,
+        _app_name,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def list_hooks_from_ast_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def extend_live_socket_params_to_ast_nif(
+        _file_content,
+        _params,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def wrap_live_socket_hooks_to_ast_nif(
+        _file_content,
+        _callee,
+        _args,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def find_html_script_blocks_nif(_html, _call_options \\ call_options()), do: error()
+
+  def patch_html_script_blocks_nif(_html, _index, _ops, _call_options \\ call_options()),
+    do: error()
+
+  def find_variable_from_ast_nif(
+        _file_content,
+        _variable_name,
+        _top_level_only,
+        _kinds,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def merge_object_literal_nif(
+        _file_content,
+        _var_name,
+        _json_spec,
+        _overwrite,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def extend_object_property_by_names_to_ast_nif(
+        _file_content,
+        _target,
+        _object_names,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def insert_tailwind_directive_nif(
+        _file_content,
+        _kind,
+        _params,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_tailwind_directive_nif(_file_content, _kind, _path, _call_options \\ call_options()),
+    do: error()
+
+  def put_tailwind_theme_variables_nif(
+        _file_content,
+        _variables,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_tailwind_theme_variables_nif(_file_content, _names, _call_options \\ call_options()),
+    do: error()
+
+  def find_tailwind_layers_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def insert_tailwind_layer_directive_nif(_file_content, _layer, _call_options \\ call_options()),
+    do: error()
+
+  def insert_tailwind_layer_rule_nif(
+        _file_content,
+        _layer,
+        _rule,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def parse_document_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def query_document_nif(_document, _query, _call_options \\ call_options()), do: error()
+
+  def apply_document_nif(_document, _ops, _call_options \\ call_options()), do: error()
+
+  def document_to_source_nif(_document, _call_options \\ call_options()), do: error()
+
+  def minify_js_nif(_file_content, _options, _call_options \\ call_options()), do: error()
+
+  def minify_css_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def autoprefix_css_nif(_file_content, _targets, _call_options \\ call_options()), do: error()
+
+  def transpile_js_nif(_file_content, _target, _call_options \\ call_options()), do: error()
+
+  def transpile_js_with_source_map_nif(
+        _file_content,
+        _target,
+        _source_name,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def minify_js_with_source_map_nif(
+        _file_content,
+        _options,
+        _source_name,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def format_js_with_source_map_nif(
+        _file_content,
+        _source_name,
+        _module_kind,
+        _template_literals,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def analyze_imports_nif(_file_content, _path, _files, _call_options \\ call_options()),
+    do: error()
+
+  def extend_live_socket_option_to_ast_nif(
+        _file_content,
+        _option,
+        _entries,
+        _target,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_live_socket_option_entries_from_ast_nif(
+        _file_content,
+        _option,
+        _entries,
+        _target,
+        _mode,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def has_live_socket_connect_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def has_topbar_config_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def merge_topbar_config_nif(
+        _file_content,
+        _json_spec,
+        _overwrite,
+        _call_options \\ call_options()
+      ),
+      do: error()
 
-  def insert_import_to_ast_nif(_file_content, _import_lines), do: error()
+  def ensure_live_socket_boilerplate_nif(_file_content, _call_options \\ call_options()),
+    do: error()
+
+  def rename_identifier_nif(_file_content, _old_name, _new_name, _call_options \\ call_options()),
+    do: error()
+
+  def analyze_unused_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def list_top_level_bindings_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def extract_code_block_nif(_file_content, _name, _call_options \\ call_options()), do: error()
+
+  def move_declaration_between_sources_nif(
+        _source,
+        _target,
+        _name,
+        _import_from,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def check_js_format_nif(_file_content, _options, _call_options \\ call_options()), do: error()
+
+  def check_css_format_nif(_file_content, _options, _call_options \\ call_options()), do: error()
+
+  def validate_js_nif(_file_content, _engine, _call_options \\ call_options()), do: error()
+
+  def validate_css_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def ensure_alpine_import_nif(_file_content, _call_options \\ call_options()), do: error()
+  def register_alpine_plugin_nif(
+        _file_content,
+        _plugin,
+        _source,
+        _call_options \\ call_options()
+      ),
+      do: error()
+  def add_alpine_data_nif(_file_content, _name, _value, _call_options \\ call_options()),
+    do: error()
+  def ensure_alpine_start_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def compose_on_before_el_updated_to_ast_nif(
+        _file_content,
+        _snippet,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def list_live_sockets_from_ast_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def extend_live_socket_option_with_log_nif(
+        _file_content,
+        _option,
+        _entries,
+        _target,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_live_socket_option_entries_with_log_nif(
+        _file_content,
+        _option,
+        _entries,
+        _target,
+        _mode,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def insert_import_at_position_to_ast_nif(
+        _file_content,
+        _import_lines,
+        _position,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def add_export_to_ast_nif(_file_content, _export_lines, _call_options \\ call_options()),
+    do: error()
+
+  def remove_export_from_ast_nif(_file_content, _export_lines, _call_options \\ call_options()),
+    do: error()
+
+  def generate_hooks_index_nif(_file_content, _file_names, _call_options \\ call_options()),
+    do: error()
+
+  def query_and_patch_nif(_file_content, _spec, _call_options \\ call_options()), do: error()
+
+  def put_css_custom_properties_nif(
+        _file_content,
+        _selector,
+        _variables,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def rename_css_selector_nif(_file_content, _from, _to, _call_options \\ call_options()),
+    do: error()
+
+  def move_css_rule_nif(
+        _file_content,
+        _selector,
+        _anchor,
+        _position,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def get_css_declarations_nif(_file_content, _selector, _call_options \\ call_options()),
+    do: error()
+
+  def has_css_rule_nif(_file_content, _selector, _call_options \\ call_options()), do: error()
+
+  def apply_codemod_nif(_file_content, _pattern, _replacement, _call_options \\ call_options()),
+    do: error()
+
+  def parse_to_resource_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def get_node_children_nif(_tree, _node, _call_options \\ call_options()), do: error()
+
+  def get_node_source_nif(_tree, _node, _call_options \\ call_options()), do: error()
+
+  def replace_node_source_nif(_tree, _node, _code, _call_options \\ call_options()), do: error()
 
-  def remove_import_from_ast_nif(_file_content, _modules), do: error()
+  def serialize_resource_nif(_tree, _call_options \\ call_options()), do: error()
 
-  def find_live_socket_node_from_ast_nif(_file_content), do: error()
+  def apply_twice_equals_once_nif(_file_content, _plan, _call_options \\ call_options()),
+    do: error()
+
+  def format_js_range_nif(_file_content, _start, _end, _call_options \\ call_options()),
+    do: error()
+
+  def check_syntax_compatibility_nif(_file_content, _target, _call_options \\ call_options()),
+    do: error()
+
+  def get_jsonc_value_nif(_file_content, _path, _call_options \\ call_options()), do: error()
+
+  def set_jsonc_value_nif(_file_content, _path, _value, _call_options \\ call_options()),
+    do: error()
+
+  def remove_jsonc_value_nif(_file_content, _path, _call_options \\ call_options()), do: error()
+
+  def offset_to_position_nif(_file_content, _offset, _encoding, _call_options \\ call_options()),
+    do: error()
+
+  def position_to_offset_nif(
+        _file_content,
+        _line,
+        _column,
+        _encoding,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def is_dynamically_imported_nif(_file_content, _module_name, _call_options \\ call_options()),
+    do: error()
+
+  def insert_dynamic_import_to_ast_nif(
+        _file_content,
+        _binding,
+        _module_name,
+        _style,
+        _anchor,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def ensure_service_worker_registration_nif(
+        _file_content,
+        _path,
+        _options,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_service_worker_registration_nif(
+        _file_content,
+        _path,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def remove_var_object_properties_nif(
+        _file_content,
+        _var_name,
+        _keys,
+        _call_options \\ call_options()
+      ),
+      do: error()
 
-  def contains_variable_from_ast_nif(_file_content, _variable_name), do: error()
+  def ast_diff_nif(_old_source, _new_source, _call_options \\ call_options()), do: error()
 
-  def extend_hook_object_to_ast_nif(_file_content, _names), do: error()
+  def merge_generated_nif(_base, _generated, _user, _call_options \\ call_options()), do: error()
 
-  def remove_objects_of_hooks_from_ast_nif(_file_content, _object_names), do: error()
+  def insert_css_rule_in_media_nif(_file_content, _query, _rule, _call_options \\ call_options()),
+    do: error()
+
+  def has_live_socket_exposure_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def ensure_live_socket_exposed_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def remove_live_socket_exposure_nif(_file_content, _mode, _call_options \\ call_options()),
+    do: error()
+
+  def wrap_in_env_guard_nif(_file_content, _pattern, _guard, _call_options \\ call_options()),
+    do: error()
+
+  def remove_env_guard_nif(_file_content, _pattern, _call_options \\ call_options()), do: error()
+
+  def set_telemetry_nif(_enabled), do: error()
 
-  def statistics_from_ast_nif(_file_content), do: error()
+  def set_limits_nif(_limits), do: error()
 
-  def extend_var_object_property_by_names_to_ast_nif(_file_content, _var_name, _object_names),
+  def is_probably_minified_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def detect_file_role_nif(_file_content, _file_name, _call_options \\ call_options()),
     do: error()
 
-  def format_js_nif(_file_content), do: error()
+  def parse_with_engine_nif(_file_content, _engine, _call_options \\ call_options()), do: error()
 
-  def is_js_formatted_nif(_file_content), do: error()
+  def codegen_with_engine_nif(_file_content, _engine, _call_options \\ call_options()),
+    do: error()
 
-  def format_css_nif(_file_content), do: error()
+  def get_yaml_value_nif(_file_content, _path, _call_options \\ call_options()), do: error()
 
-  def convert_ast_to_estree_nif(_file_content), do: error()
+  def set_yaml_value_nif(_file_content, _path, _value, _call_options \\ call_options()),
+    do: error()
 
-  def insert_ast_at_index_nif(_file_content, _insert_code, _index), do: error()
+  def append_yaml_value_nif(_file_content, _path, _value, _call_options \\ call_options()),
+    do: error()
+
+  def list_managed_regions_nif(_file_content, _call_options \\ call_options()), do: error()
+
+  def find_managed_region_nif(_file_content, _name, _call_options \\ call_options()), do: error()
+
+  def upsert_managed_region_nif(
+        _file_content,
+        _name,
+        _content,
+        _position,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def get_variable_initializer_nif(
+        _file_content,
+        _path,
+        _top_level_only,
+        _kinds,
+        _call_options \\ call_options()
+      ),
+      do: error()
+
+  def install_integration_nif(_js_content, _package_json, _spec, _call_options \\ call_options()),
+    do: error()
+
+  def run_named_transform_nif(_file_content, _name, _json_args, _call_options \\ call_options()),
+    do: error()
 
-  def replace_ast_at_index_nif(_file_content, _replace_code, _index), do: error()
+  def list_named_transforms_nif(_call_options \\ call_options()), do: error()
 
-  def is_css_formatted_nif(_file_content), do: error()
+  defp call_options do
+    IgniterJs.ParserOptions.get()
+    |> Map.merge(IgniterJs.CodegenOptions.get())
+    |> Map.put(:timeout, IgniterJs.Limits.call_timeout())
+  end
 
   defp error, do: :erlang.nif_error(:nif_not_loaded)
 end
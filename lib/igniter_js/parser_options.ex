@@ -0,0 +1,41 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.ParserOptions do
+  @moduledoc """
+  Options of the JavaScript parsers, shared by every function and sent with the arguments of
+  each call.
+
+    - `:decorators` - parses decorators, e.g. `@customElement("copy-button")`, and the
+      `accessor` fields of the stage 3 decorators proposal, as used by Lit and Stimulus
+      components. `true` by default; when `false`, a source with decorators is an error.
+
+  They are read from the config:
+
+  ```elixir
+  config :igniter_js, decorators: false
+  ```
+  """
+
+  @doc """
+  The parser options from the config, with their defaults.
+  """
+  def get do
+    %{decorators: Application.get_env(:igniter_js, :decorators, true)}
+  end
+
+  @doc """
+  Sets the parser options of the NIF calls made after it. The missing options keep their
+  value from the config.
+
+  ```elixir
+  IgniterJs.ParserOptions.set(decorators: false)
+  ```
+  """
+  def set(options) do
+    options = Map.merge(get(), Map.new(options))
+    Application.put_env(:igniter_js, :decorators, options.decorators)
+  end
+end
@@ -0,0 +1,137 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.Asset.Patch do
+  @moduledoc """
+  Apply JavaScript and CSS patches together, as a single unit.
+
+  Component installers usually patch `app.js` and `app.css` at the same time. The functions
+  in this module return both updated sources only if every operation on both sides succeeds,
+  so an install is never left half-applied.
+
+  JavaScript operations:
+
+    - `{:insert_imports, imports_lines}`
+    - `{:remove_imports, imports_lines}`
+    - `{:extend_hook_object, names}`
+    - `{:remove_objects_of_hooks, names}`
+    - `{:extend_var_object, var_name, names}`
+    - `{:insert_at_index, code, index}`
+    - `{:replace_at_index, code, index}`
+    - `:format`
+
+  CSS operations:
+
+    - `{:append, css}`
+    - `{:prepend, css}`
+    - `:format`
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Apply the given JavaScript and CSS operations and return both updated sources.
+
+  ```elixir
+  alias IgniterJs.Parsers.Asset.Patch
+
+  Patch.apply_patch(
+    js_content,
+    [{:insert_imports, "import Tooltip from \\"./tooltip\\";"}, {:extend_hook_object, ["Tooltip"]}],
+    css_content,
+    [{:append, ".tooltip { color: red; }"}]
+  )
+  # => {:ok, :apply_patch, {updated_js, updated_css}}
+  ```
+  """
+  def apply_patch(js_content, js_ops, css_content, css_ops) do
+    call_nif_fn(
+      js_content,
+      __ENV__.function,
+      fn js_content ->
+        Native.apply_asset_patch_nif(js_content, js_ops, css_content, css_ops)
+      end,
+      :content
+    )
+  end
+
+  @doc """
+  Apply the JavaScript operations `{:js, js_ops}` or the CSS operations `{:css, css_ops}`
+  twice, and check the second run leaves the output of the first one as it is, which every
+  installer must do. The outputs are compared byte for byte.
+
+  The result is a map with `:idempotent`, the `:once` and `:twice` outputs and the
+  `:difference`, which is `nil` or the first line the second run changed, with the
+  `:expected` line of the first run and the `:actual` line of the second.
+
+  ```elixir
+  alias IgniterJs.Parsers.Asset.Patch
+
+  {:ok, :check_idempotent, %{idempotent: true}} =
+    Patch.check_idempotent(js_content, {:js, [{:extend_hook_object, ["Tooltip"]}]})
+  ```
+  """
+  def check_idempotent(file_path_or_content, plan, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.apply_twice_equals_once_nif(file_content, plan)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Integrate a JavaScript package into `app.js` and `package.json` in one pass, and return
+  the updated sources with a report of the changes.
+
+  The spec is a keyword list or a map with any of:
+
+    - `:imports` - the import declarations to ensure
+    - `:hooks` - the hooks to register on `liveSocket`
+    - `:statements` - the statements to append unless the file already has them
+    - `:dependencies` and `:dev_dependencies` - the packages to add to `package.json`, as
+      a map or a list of `{name, version}`, without changing the version of a package
+      already there
+
+  The `package_json` content may be `nil` when there are no dependencies. Each change is
+  one of `{:added_import, line}`, `{:skipped_import, line}`, `{:added_hook, name}`,
+  `{:skipped_hook, name}`, `{:appended_statement, code}`, `{:skipped_statement, code}`,
+  `{:added_dependency, name, version}` or `{:skipped_dependency, name}`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Asset.Patch
+
+  {:ok, :install, %{js: js, package_json: package_json, changes: changes}} =
+    Patch.install(app_js, package_json,
+      imports: ["import Sortable from \\"sortablejs\\";"],
+      hooks: ["Sortable"],
+      dependencies: %{"sortablejs" => "^1.15.0"}
+    )
+  ```
+  """
+  def install(js_content, package_json, spec) do
+    spec = Map.new(spec)
+
+    spec = %{
+      imports: Map.get(spec, :imports, []),
+      hooks: Map.get(spec, :hooks, []),
+      statements: Map.get(spec, :statements, []),
+      dependencies: Enum.to_list(Map.get(spec, :dependencies, [])),
+      dev_dependencies: Enum.to_list(Map.get(spec, :dev_dependencies, []))
+    }
+
+    call_nif_fn(
+      js_content,
+      __ENV__.function,
+      fn js_content ->
+        Native.install_integration_nif(js_content, package_json, spec)
+      end,
+      :content
+    )
+  end
+end
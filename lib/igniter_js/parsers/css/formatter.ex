@@ -13,7 +13,7 @@ defmodule IgniterJs.Parsers.CSS.Formatter do
   # We set version of biemojs based on https://github.com/brioche-dev/brioche/pull/184
 
   alias IgniterJs.Native
-  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+  import IgniterJs.Helpers, only: [call_nif_fn: 4, format_check_options: 1]
 
   @doc """
   Checks if the provided CSS content or file is formatted.
@@ -63,6 +63,56 @@ defmodule IgniterJs.Parsers.CSS.Formatter do
     )
   end
 
+  @doc """
+  Checks if the provided CSS content or file is formatted, and where it differs from its
+  formatted version when it is not.
+
+  The result is a map with `:formatted` and `:difference`, which is `nil` or the first
+  differing `:line` (1-based) with the `:expected` and `:actual` lines, `nil` past the end.
+
+  ## Options
+
+    - `normalize_newlines` - compare `\\r\\n` line endings as `\\n`. Defaults to `true`.
+    - `final_newline` - `:ignore` to ignore the whitespace around the content (default),
+      or `:require` to require the single final newline the formatter prints.
+
+  ## Examples
+
+      iex> IgniterJs.Parsers.CSS.Formatter.check_format("a {\\n  color: red;\\n}\\nb{}\\n")
+      {:ok, :check_format,
+       %{formatted: false, difference: %{line: 4, expected: "b {", actual: "b{}"}}}
+
+  """
+  def check_format(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.check_css_format_nif(file_content, format_check_options(opts))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Formats the provided CSS content or file.
+
+  A stylesheet with syntax errors is an error message listing them, each with its line,
+  column and code frame.
+
+  SCSS and Less are not supported. Instead of a syntax error, they are reported with
+  `{:unsupported_dialect, :scss}` or `{:unsupported_dialect, :less}`, so those files can be
+  skipped. `check_format/3` reports them the same way.
+
+  ## Examples
+
+      iex> IgniterJs.Parsers.CSS.Formatter.format("body { color: red; }")
+      {:ok, :format, "body {\\n  color: red;\\n}\\n"}
+
+      iex> IgniterJs.Parsers.CSS.Formatter.format("$c: red;\\n.a { .b { color: $c; } }")
+      {:error, :format, {:unsupported_dialect, :scss}}
+
+  """
   def format(file_path_or_content, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
@@ -0,0 +1,74 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.CSS.Minifier do
+  @moduledoc """
+  Minify stylesheets and add the vendor prefixes the target browsers need, without any
+  Node tooling. Framework at-rules such as Tailwind's `@theme` or `@plugin` are kept intact.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4, take_metrics: 2]
+
+  @doc """
+  Minify a stylesheet: comments (except `/*! ... */` license comments) and meaningless
+  whitespace are removed.
+
+  Pass `targets:` to add the vendor prefixes first, see `autoprefix/3`.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Minifier
+  Minifier.minify(".btn {\\n  color: red;\\n}\\n")
+  # => {:ok, :minify, ".btn{color:red}"}
+  Minifier.minify("/path/to/app.css", :path, targets: %{safari: 14})
+  ```
+  """
+  def minify(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        case Keyword.fetch(opts, :targets) do
+          {:ok, targets} ->
+            {:ok, _, prefixed} =
+              file_content
+              |> Native.autoprefix_css_nif(targets(targets))
+              |> take_metrics(__ENV__.function)
+            Native.minify_css_nif(prefixed)
+
+          :error ->
+            Native.minify_css_nif(file_content)
+        end
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Add the vendor prefixed declarations the target browsers need, e.g.
+  `-webkit-user-select: none` before `user-select: none` for Safari.
+
+  Targets map a browser (`:chrome`, `:edge`, `:firefox`, `:safari` or `:ios_saf`) to the
+  oldest version to support. Declarations a block already has are not added twice.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Minifier
+  Minifier.autoprefix(css_content, %{safari: 14, firefox: 78})
+  Minifier.autoprefix("/path/to/app.css", %{safari: 15.4}, :path)
+  ```
+  """
+  def autoprefix(file_path_or_content, targets, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.autoprefix_css_nif(file_content, targets(targets)) end,
+      type
+    )
+  end
+
+  defp targets(targets) do
+    Map.new(targets, fn {browser, version} -> {to_string(browser), version / 1} end)
+  end
+end
@@ -0,0 +1,44 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.CSS.Parser do
+  @moduledoc """
+  Parse stylesheets to check them before they are modified.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Parse the given CSS file or content without changing it, and return every syntax error
+  found, e.g. to refuse to patch a file that is already broken and show the user why.
+
+  The result is a list of diagnostics, maps with the `:message`, the `:severity`
+  (`:error`, `:warning` or `:advice`), the `:help` text or `nil`, the `:start` and `:end`
+  byte offsets, the 1-based `:line` and 0-based `:column`, and a `:code_frame` showing the
+  lines around the problem. The status is `:error` when any diagnostic is an error.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Parser
+  Parser.validate("a { color: red;")
+  Parser.validate("/path/to/file", :path)
+  ```
+  """
+  def validate(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.validate_css_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  Check if the given CSS file or content has no syntax error, see `validate/2`.
+  """
+  def valid?(file_path_or_content, type \\ :content) do
+    elem(validate(file_path_or_content, type), 0) == :ok
+  end
+end
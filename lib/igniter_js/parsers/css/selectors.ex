@@ -0,0 +1,122 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.CSS.Selectors do
+  @moduledoc """
+  Query, rename and move the rules of a stylesheet, and insert rules into `@media` blocks, e.g.
+  in the upgrade scripts of a component library. Only the edited selectors and rules change,
+  the rest of the stylesheet is left untouched.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Get the declarations of the rules of a selector, in source order, as maps with the
+  `:property`, the `:value` without its `!important` flag, `:important` and the `:start` and
+  `:end` byte offsets. A rule matches when its selector list or one of its selectors is the
+  selector, also inside `@media` or other rules, and an at-rule block like `@theme` is matched
+  by its prelude.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Selectors
+  {:ok, :declarations, [%{property: "--primary-color", value: "#fd4f00"}]} =
+    Selectors.declarations(":root { --primary-color: #fd4f00; }", ":root")
+  ```
+  """
+  def declarations(file_path_or_content, selector, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.get_css_declarations_nif(file_content, selector) end,
+      type
+    )
+  end
+
+  @doc """
+  Check if the stylesheet has a rule of a selector, matched as in `declarations/3`.
+  """
+  def has_rule?(file_path_or_content, selector, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.has_css_rule_nif(file_content, selector) end,
+      type
+    )
+    |> case do
+      {:ok, _, has_rule} -> has_rule
+      _ -> false
+    end
+  end
+
+  @doc """
+  Rename a selector. A single class or id, e.g. `.phx-modal`, is renamed everywhere it is
+  used, also in compound and complex selectors like `.page > .phx-modal:hover`. Any other
+  selector is renamed where it selects a rule on its own. It is an error when the selector
+  is not found.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Selectors
+  Selectors.rename(css_content, ".phx-modal", ".modal")
+  Selectors.rename("/path/to/app.css", ".modal > .title", ".modal-title", :path)
+  ```
+  """
+  def rename(file_path_or_content, from, to, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.rename_css_selector_nif(file_content, from, to) end,
+      type
+    )
+  end
+
+  @doc """
+  Move the rule of a selector `:before` or `:after` the rule of another selector. Rules are
+  looked up by their whole selector list, e.g. `".btn, .button"`. It is an error when either
+  rule is not found.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Selectors
+  Selectors.move_rule(css_content, ".modal", ".btn", :after)
+  Selectors.move_rule("/path/to/app.css", ".modal", ".btn", :before, :path)
+  ```
+  """
+  def move_rule(file_path_or_content, selector, anchor, position, type \\ :content)
+      when position in [:before, :after] do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.move_css_rule_nif(file_content, selector, anchor, position)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Insert a rule inside the `@media` block of a query, e.g. a dark mode override, creating the
+  block at the end of the stylesheet when it is missing. The query is matched ignoring case
+  and whitespace, with or without `@media`.
+
+  When the block already has a rule with the same selector, the declarations it does not have
+  are appended to it, and the ones it has are kept. It is an error when `rule` is not a single
+  rule.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Selectors
+  dark = "(prefers-color-scheme: dark)"
+  Selectors.insert_rule_in_media(css_content, dark, ".btn { color: white; }")
+  Selectors.insert_rule_in_media("/path/to/app.css", "print", ".nav { display: none; }", :path)
+  ```
+  """
+  def insert_rule_in_media(file_path_or_content, query, rule, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.insert_css_rule_in_media_nif(file_content, query, rule) end,
+      type
+    )
+  end
+end
@@ -0,0 +1,172 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.CSS.Tailwind do
+  @moduledoc """
+  Manage Tailwind v4 directives in a stylesheet, usually `assets/css/app.css`.
+
+  Tailwind v4 moves its configuration into CSS with `@import "tailwindcss"`, `@source`,
+  `@plugin` and `@theme { ... }`. Every function here is idempotent, keeps the conventional
+  order of the directives (imports, sources, plugins, then the theme) and leaves comments and
+  unrelated rules untouched.
+
+  Directive kinds are `:import`, `:source` and `:plugin`.
+
+  The `@tailwind base;` directives of Tailwind v3 and the `@layer components { ... }` blocks
+  are managed by layer, which is one of `:base`, `:components` and `:utilities`.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Insert a directive unless one of the same kind already points to the same path. A bare
+  path is quoted; pass the quoted form to add extra parameters.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.insert_directive(css_content, :plugin, "../vendor/heroicons")
+  Tailwind.insert_directive(css_content, :import, ~s("tailwindcss" source(none)))
+  Tailwind.insert_directive("/path/to/app.css", :source, "../js", :path)
+  ```
+  """
+  def insert_directive(file_path_or_content, kind, params, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.insert_tailwind_directive_nif(file_content, kind, params) end,
+      type
+    )
+  end
+
+  @doc """
+  Remove every directive of the given kind pointing to `path`.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.remove_directive(css_content, :plugin, "../vendor/heroicons")
+  ```
+  """
+  def remove_directive(file_path_or_content, kind, path, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.remove_tailwind_directive_nif(file_content, kind, path) end,
+      type
+    )
+  end
+
+  @doc """
+  Add variables to the `@theme` block, creating it when the stylesheet has none. Variables
+  that are already defined keep their value. It accepts a map or a keyword list.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.put_theme_variables(css_content, %{"--color-brand" => "#fd4f00"})
+  ```
+  """
+  def put_theme_variables(file_path_or_content, variables, type \\ :content) do
+    variables = Enum.map(variables, fn {name, value} -> {to_string(name), to_string(value)} end)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.put_tailwind_theme_variables_nif(file_content, variables) end,
+      type
+    )
+  end
+
+  @doc """
+  Remove variables from the `@theme` block, and the block itself once it is empty.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.remove_theme_variables(css_content, ["--color-brand"])
+  ```
+  """
+  def remove_theme_variables(file_path_or_content, names, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.remove_tailwind_theme_variables_nif(file_content, names) end,
+      type
+    )
+  end
+
+  @doc """
+  Find the layers the stylesheet has `@tailwind` directives and `@layer` blocks for.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  {:ok, :layers, %{directives: [:base, :components, :utilities], blocks: [:components]}} =
+    Tailwind.layers(css_content)
+  ```
+  """
+  def layers(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.find_tailwind_layers_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  Check if the stylesheet has the `@tailwind` directive of a layer, see `layers/2`.
+  """
+  def has_layer_directive?(file_path_or_content, layer, type \\ :content) do
+    case layers(file_path_or_content, type) do
+      {:ok, _, %{directives: directives}} -> layer in directives
+      _ -> false
+    end
+  end
+
+  @doc """
+  Check if the stylesheet has a `@layer` block for a layer, see `layers/2`.
+  """
+  def has_layer_block?(file_path_or_content, layer, type \\ :content) do
+    case layers(file_path_or_content, type) do
+      {:ok, _, %{blocks: blocks}} -> layer in blocks
+      _ -> false
+    end
+  end
+
+  @doc """
+  Insert the `@tailwind` directive of a layer unless the stylesheet has it, after the imports
+  and in the `base`, `components`, `utilities` order.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.insert_layer_directive(css_content, :components)
+  ```
+  """
+  def insert_layer_directive(file_path_or_content, layer, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.insert_tailwind_layer_directive_nif(file_content, layer) end,
+      type
+    )
+  end
+
+  @doc """
+  Insert a rule into the `@layer` block of a layer, creating the block at the end of the
+  stylesheet when it is missing. A rule whose selector is already in the block only gets the
+  declarations it lacks, so inserting it again changes nothing.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Tailwind
+  Tailwind.insert_layer_rule(css_content, :components, ".btn { @apply px-4 py-2; }")
+  ```
+  """
+  def insert_layer_rule(file_path_or_content, layer, rule, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.insert_tailwind_layer_rule_nif(file_content, layer, rule) end,
+      type
+    )
+  end
+end
@@ -0,0 +1,41 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.CSS.Variables do
+  @moduledoc """
+  Manage CSS custom properties in a stylesheet, e.g. the brand colors a theme installer sets
+  in the `:root` block of `assets/css/app.css`. Other declarations, comments and the
+  formatting are kept as they are.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Set custom properties in the block of a selector, `:root` unless the `:selector` option is
+  given. Existing properties get the new value, the others are appended to the block, which
+  is created after the top-level directives when the stylesheet has none. It accepts a map or
+  a keyword list, and names must start with `--`.
+
+  ```elixir
+  alias IgniterJs.Parsers.CSS.Variables
+  Variables.put(css_content, %{"--color-brand" => "#fd4f00"})
+  Variables.put("/path/to/app.css", %{"--color-brand" => "#fff"}, :path, selector: ".dark")
+  ```
+  """
+  def put(file_path_or_content, variables, type \\ :content, opts \\ []) do
+    variables = Enum.map(variables, fn {name, value} -> {to_string(name), to_string(value)} end)
+    selector = Keyword.get(opts, :selector, ":root")
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.put_css_custom_properties_nif(file_content, selector, variables)
+      end,
+      type
+    )
+  end
+end
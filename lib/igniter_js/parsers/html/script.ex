@@ -0,0 +1,60 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.HTML.Script do
+  @moduledoc """
+  Find and patch inline `<script>` blocks in HTML and HEEx documents, such as the
+  `root.html.heex` layout.
+
+  Only inline JavaScript blocks are considered: scripts with a `src` attribute and data
+  blocks like `type="application/json"` are skipped. The markup around a patched block is
+  left untouched.
+
+  Patch operations are the JavaScript operations of `IgniterJs.Parsers.Asset.Patch`.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  List the inline JavaScript `<script>` blocks of the given HTML content.
+
+  Every block is a map with its `:index`, the `:start` and `:end` byte offsets of its
+  content, the raw `:attributes` of its opening tag and its `:content`.
+
+  ```elixir
+  alias IgniterJs.Parsers.HTML.Script
+  Script.find_blocks(html_content)
+  # => {:ok, :find_blocks, [%{index: 0, start: 120, end: 180, attributes: "", content: "..."}]}
+  ```
+  """
+  def find_blocks(html_content) do
+    call_nif_fn(
+      html_content,
+      __ENV__.function,
+      fn html_content -> Native.find_html_script_blocks_nif(html_content) end,
+      :content
+    )
+  end
+
+  @doc """
+  Apply JavaScript operations to the inline script block at `index` (or to every inline
+  block when `index` is `nil`) and return the updated HTML content.
+
+  ```elixir
+  alias IgniterJs.Parsers.HTML.Script
+  Script.patch_blocks(html_content, 0, [{:insert_at_index, "gtag(\\"config\\", \\"G-XXXX\\");", 2}])
+  # => {:ok, :patch_blocks, updated_html}
+  ```
+  """
+  def patch_blocks(html_content, index, ops) do
+    call_nif_fn(
+      html_content,
+      __ENV__.function,
+      fn html_content -> Native.patch_html_script_blocks_nif(html_content, index, ops) end,
+      :content
+    )
+  end
+end
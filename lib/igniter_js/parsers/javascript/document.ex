@@ -0,0 +1,69 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.Javascript.Document do
+  @moduledoc """
+  Parse a JavaScript source once and run many queries and mutations against it.
+
+  Every function of `IgniterJs.Parsers.Javascript.Parser` parses its input from scratch.
+  A document is a reference to a parsed source kept on the native side: queries share the
+  parsed AST, mutations are applied in batches, and the source is serialized at the end.
+  The document is freed when it is garbage collected.
+
+  Queries:
+
+    - `:imports` - the sources of the import declarations
+    - `:hooks` - the hooks registered in the LiveSocket
+    - `{:find_variable, name}` - the declaration of `name`, like `Parser.var_info/4`
+
+  Mutations are the JavaScript operations of `IgniterJs.Parsers.Asset.Patch`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Document
+
+  {:ok, :parse, document} = Document.parse("/path/to/app.js", :path)
+  {:ok, :query, hooks} = Document.query(document, :hooks)
+  {:ok, :apply, document} = Document.apply(document, [{:extend_hook_object, ["Tooltip"]}])
+  {:ok, :to_source, code} = Document.to_source(document)
+  ```
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Parse the given content or file into a document.
+  """
+  def parse(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.parse_document_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  Run a query against the document.
+  """
+  def query(document, query) do
+    call_nif_fn(document, __ENV__.function, &Native.query_document_nif(&1, query), :content)
+  end
+
+  @doc """
+  Apply a batch of operations to the document. Either every operation succeeds, or the
+  document is left as it was and the error of the failing operation is returned.
+  """
+  def apply(document, ops) do
+    call_nif_fn(document, __ENV__.function, &Native.apply_document_nif(&1, ops), :content)
+  end
+
+  @doc """
+  Serialize the document back to JavaScript source.
+  """
+  def to_source(document) do
+    call_nif_fn(document, __ENV__.function, &Native.document_to_source_nif/1, :content)
+  end
+end
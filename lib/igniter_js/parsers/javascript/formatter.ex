@@ -11,7 +11,8 @@ defmodule IgniterJs.Parsers.Javascript.Formatter do
   """
 
   alias IgniterJs.Native
-  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+  import IgniterJs.Helpers,
+    only: [call_nif_fn: 4, format_check_options: 1, module_kind: 3, source_name: 3]
 
   @doc """
   Checks if the provided JavaScript content or file is formatted.
@@ -61,12 +62,110 @@ defmodule IgniterJs.Parsers.Javascript.Formatter do
     )
   end
 
-  def format(file_path_or_content, type \\ :content) do
+  @doc """
+  Checks if the provided JavaScript content or file is formatted, and where it differs from
+  its formatted version when it is not.
+
+  The result is a map with `:formatted` and `:difference`, which is `nil` or the first
+  differing `:line` (1-based) with the `:expected` and `:actual` lines, `nil` past the end.
+
+  ## Options
+
+    - `normalize_newlines` - compare `\\r\\n` line endings as `\\n`. Defaults to `true`.
+    - `final_newline` - `:ignore` to ignore the whitespace around the content (default),
+      or `:require` to require the single final newline the formatter prints.
+
+  ## Examples
+
+      iex> IgniterJs.Parsers.Javascript.Formatter.check_format("let a = 1;\\nlet b=2;\\n")
+      {:ok, :check_format,
+       %{formatted: false, difference: %{line: 2, expected: "let b = 2;", actual: "let b=2;"}}}
+
+  """
+  def check_format(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.check_js_format_nif(file_content, format_check_options(opts))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Formats the provided JavaScript content or file.
+
+  The type can either be `:content` (default) or `:path`. Content with syntax errors is an
+  error message listing them, each with its line, column and code frame.
+
+  ## Options
+
+    - `source_map` - when `true`, the result is a map with the formatted `code` and the
+      `source_map`, a JSON string in the source map v3 format. Defaults to `false`.
+    - `source_name` - the name of the source in the source map. Defaults to the file path,
+      or `"input.js"` for content.
+    - `module_kind` - `:module` to format an ES module, `:script` for a CommonJS script
+      like `postcss.config.js`, or `:auto` to detect it. Defaults to `:module` for `.mjs`
+      files, `:script` for `.cjs` files, and `:auto` otherwise.
+    - `template_literals` - `:format` to keep the text of template literals and format their
+      `${...}` expressions (default), `:preserve` to keep them exactly as they are, or
+      `:embedded` to also format the CSS of CSS-in-JS templates like `` css`...` `` and
+      `` styled.div`...` ``. The other embedded languages, like GraphQL, are preserved.
+
+  ## Examples
+
+      iex> IgniterJs.Parsers.Javascript.Formatter.format("let a=1")
+      {:ok, :format, "let a = 1;\\n"}
+
+      iex> IgniterJs.Parsers.Javascript.Formatter.format("let a=1", :content, source_map: true)
+      {:ok, :format, %{code: "let a = 1;\\n", source_map: "{\\"version\\":3,...}"}}
+
+  """
+  def format(file_path_or_content, type \\ :content, opts \\ []) do
+    source_name = source_name(file_path_or_content, type, opts)
+    module_kind = module_kind(file_path_or_content, type, opts)
+    template_literals = Keyword.get(opts, :template_literals, :format)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        if Keyword.get(opts, :source_map, false) do
+          Native.format_js_with_source_map_nif(
+            file_content,
+            source_name,
+            module_kind,
+            template_literals
+          )
+        else
+          Native.format_js_nif(file_content, module_kind, template_literals)
+        end
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Formats only the code between the byte offsets `start_offset` and `end_offset` of the
+  provided JavaScript content or file, e.g. the region a patch changed, and keeps the
+  formatting of the rest of the file.
+
+  The range is widened to the statements it touches. The type can either be `:content`
+  (default) or `:path`.
+
+  ## Examples
+
+      iex> IgniterJs.Parsers.Javascript.Formatter.format_range("let  a=1\nlet b=2\n", 9, 16)
+      {:ok, :format_range, "let  a=1\nlet b = 2;\n"}
+
+  """
+  def format_range(file_path_or_content, start_offset, end_offset, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.format_js_nif(file_content)
+        Native.format_js_range_nif(file_content, start_offset, end_offset)
       end,
       type
     )
@@ -0,0 +1,71 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.Javascript.Minifier do
+  @moduledoc """
+  This module minifies JavaScript, e.g. to produce compact snippets to inline into
+  HEEx templates. It interacts with the Native NIF (Native Implemented Functions) for
+  performing the actual minification.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4, source_name: 3]
+
+  @doc """
+  Minifies the provided JavaScript content or file.
+
+  Comments and whitespace are always removed. The type can either be `:content`
+  (default) or `:path`.
+
+  ## Options
+
+    - `mangle` - rename the bindings declared inside functions and blocks to short names.
+      Top-level bindings are never renamed, and nothing is renamed when the code calls
+      `eval`. Defaults to `true`.
+    - `compress` - apply the size reductions that keep the behavior of the code, up to the
+      `level`. Defaults to `true`.
+    - `level` - the compression level, from `1` to `3` (default): `1` removes `debugger` and
+      empty statements and shortens booleans to `!0` and `!1`, `2` also folds the operations
+      on literals, e.g. `60 * 1000`, and the conditions with a literal test, e.g.
+      `if (true)`, and `3` also removes the statements after a `return`, `throw`, `break` or
+      `continue`.
+    - `target` - the ES version the output is emitted for, e.g. `"es5"`, `"es2020"` or
+      `"esnext"` (default). It does not lower newer syntax.
+    - `source_map` - when `true`, the result is a map with the minified `code` and the
+      `source_map`, a JSON string in the source map v3 format. Defaults to `false`.
+    - `source_name` - the name of the source in the source map. Defaults to the file path,
+      or `"input.js"` for content.
+
+  ## Examples
+
+      iex> alias IgniterJs.Parsers.Javascript.Minifier
+      iex> Minifier.minify("function add(first, second) { return first + second; }")
+      {:ok, :minify, "function add(a,b){return a+b;}"}
+
+  """
+  def minify(file_path_or_content, type \\ :content, opts \\ []) do
+    options = %{
+      mangle: Keyword.get(opts, :mangle, true),
+      compress: Keyword.get(opts, :compress, true),
+      level: Keyword.get(opts, :level, 3),
+      target: to_string(Keyword.get(opts, :target, "esnext"))
+    }
+
+    source_name = source_name(file_path_or_content, type, opts)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        if Keyword.get(opts, :source_map, false) do
+          Native.minify_js_with_source_map_nif(file_content, options, source_name)
+        else
+          Native.minify_js_nif(file_content, options)
+        end
+      end,
+      type
+    )
+  end
+end
@@ -54,355 +54,2329 @@ defmodule IgniterJs.Parsers.Javascript.Parser do
   Parser.insert_imports(js_content, imports_lines)
   Parser.insert_imports(js_content, imports_lines, :content)
   Parser.insert_imports("/path/to/file.js", imports_lines, :path)
+  Parser.insert_imports(js_content, ~s(import "./polyfill";), :content, position: :top)
+  Parser.insert_imports(js_content, imports_lines, :content, position: {:before, "phoenix"})
   ```
+
+  An import is skipped when its source is already imported with all of its specifiers,
+  and a side-effect import like `import "./polyfill"` when its source is imported at all.
+
+  ## Options
+
+    * `:position` - `:top`, `:after_last_import` (the default) or `{:before, source}` to
+      insert before the first import of `source`, falling back to after the last import.
   """
-  def insert_imports(file_path_or_content, imports_lines, type \\ :content) do
+  def insert_imports(file_path_or_content, imports_lines, type \\ :content, opts \\ []) do
+    position = Keyword.get(opts, :position, :after_last_import)
+
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.insert_import_to_ast_nif(file_content, imports_lines)
+        Native.insert_import_at_position_to_ast_nif(file_content, imports_lines, position)
       end,
       type
     )
   end
 
   @doc """
-  Remove imports from the given file or content. it accepts a single module or a list of modules.
-  It returns a tuple.
+  Check if a module is loaded with a dynamic `import("module")` anywhere in the given file or
+  content, e.g. inside a hook, and returns boolean. Static imports do not count.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.remove_imports(js_content, "SomeModule")
-  Parser.remove_imports("/path/to/file.js", "SomeModule", :path)
+  Parser.dynamically_imported?(js_content, "chart.js")
+  Parser.dynamically_imported?("/path/to/file.js", "../vendor/chart", :path)
   ```
   """
-  def remove_imports(file_path_or_content, module, type \\ :content)
+  def dynamically_imported?(file_path_or_content, module, type \\ :content) do
+    match?({:ok, _, true}, dynamically_imported(file_path_or_content, module, type))
+  end
+
+  @doc """
+  Check if a module is loaded with a dynamic `import("module")` and returns tuple.
 
-  def remove_imports(file_path_or_content, modules, type) do
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.dynamically_imported(js_content, "chart.js")
+  # => {:ok, :dynamically_imported, true}
+  ```
+  """
+  def dynamically_imported(file_path_or_content, module, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.remove_import_from_ast_nif(file_content, modules)
+        Native.is_dynamically_imported_nif(file_content, module)
       end,
       type
     )
   end
 
   @doc """
-  Check if a LiveSocket var exists in the given file or content and returns boolean.
+  Insert a lazy-loading snippet for a module, e.g. `const Chart = await import("chart.js");`,
+  and returns tuple. Nothing is inserted when the module is already imported dynamically.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.exist_live_socket?(js_content)
-  Parser.exist_live_socket?(js_content, :content)
-  Parser.exist_live_socket?("/path/to/file.js", :path)
+  Parser.insert_dynamic_import(js_content, "Chart", "chart.js")
+  Parser.insert_dynamic_import(js_content, "Chart", "chart.js", :content,
+    style: :then,
+    anchor: {:before, "liveSocket"}
+  )
   ```
+
+  It returns an error when the binding is already declared or the anchor is not found.
+
+  ## Options
+
+    * `:style` - `:await` (the default) for `const X = await import("mod");`, which needs
+      top-level await, or `:then` for
+      `const X = import("mod").then((module) => module.default);`.
+    * `:anchor` - `:after_last_import` (the default), `:end`, or `{:before, name}` and
+      `{:after, name}` to insert next to the top-level declaration of `name`.
   """
-  def exist_live_socket?(file_path_or_content, type \\ :content) do
-    elem(exist_live_socket(file_path_or_content, type), 0) == :ok
+  def insert_dynamic_import(
+        file_path_or_content,
+        binding,
+        module,
+        type \\ :content,
+        opts \\ []
+      ) do
+    style = Keyword.get(opts, :style, :await)
+    anchor = Keyword.get(opts, :anchor, :after_last_import)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.insert_dynamic_import_to_ast_nif(file_content, binding, module, style, anchor)
+      end,
+      type
+    )
   end
 
   @doc """
-  Check if a LiveSocket var exists in the given file or content and returns tuple.
+  Remove imports from the given file or content. it accepts a single module or a list of modules.
+  It returns a tuple.
+
+  ## Options
+
+    * `:comment_out` - when `true`, the imports are commented out with a
+      `// removed by igniter:` marker instead of deleted, so they are easy to restore.
+      Defaults to `false`.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.exist_live_socket(js_content)
-  Parser.exist_live_socket(js_content, :content)
-  Parser.exist_live_socket("/path/to/file.js", :path)
+  Parser.remove_imports(js_content, "SomeModule")
+  Parser.remove_imports("/path/to/file.js", "SomeModule", :path)
+  Parser.remove_imports(js_content, ~s(import "topbar";), :content, comment_out: true)
   ```
   """
-  def exist_live_socket(file_path_or_content, type \\ :content) do
+  def remove_imports(file_path_or_content, module, type \\ :content, opts \\ [])
+
+  def remove_imports(file_path_or_content, modules, type, opts) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.find_live_socket_node_from_ast_nif(file_content)
+        Native.remove_import_from_ast_nif(file_content, modules, removal_mode(opts))
       end,
       type
     )
   end
 
   @doc """
-  Check if a specific var exists in the given file or content and returns boolean.
+  Replace the imports of a source in the given file or content with new import declarations,
+  e.g. to switch a JSON import from `assert { type: "json" }` to `with { type: "json" }`, or
+  a CSS module import to another stylesheet. The new declarations take the place of the first
+  import of the source. It returns a tuple, an error one when the source is not imported.
+
+  Import attributes round-trip through every transform, and are written with `assert` when
+  the file uses it.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.exist_live_socket?(js_content)
-  Parser.exist_live_socket?(js_content, :content)
-  Parser.exist_live_socket?("/path/to/file.js", :path)
+  new_import = ~s(import data from "./data.json" with { type: "json" };)
+  Parser.replace_import(js_content, "./data.json", new_import)
+  Parser.replace_import("/path/to/file.js", "./app.css", ~s(import "./theme.css";), :path)
   ```
   """
-  def var_exists?(file_path_or_content, type \\ :content) do
-    elem(exist_var(file_path_or_content, type), 0) == :ok
+  def replace_import(file_path_or_content, source, import_lines, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.replace_import_nif(file_content, source, import_lines) end,
+      type
+    )
   end
 
   @doc """
-  Check if an specific var exists in the given file or content and returns tuple.
+  Sort the imports of the given file or content and returns tuple. Imports are sorted from
+  the packages to the relative paths, then by source, and the named specifiers of each import
+  by name. Side-effect imports such as `import "phoenix_html"` are never moved.
+
+  Options:
+    * `:separate_groups` - separates the packages, aliases and relative paths with a blank
+      line. Defaults to `false`.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.exist_live_socket(js_content, var_name)
-  Parser.exist_live_socket(js_content, var_name, :content)
-  Parser.exist_live_socket("/path/to/file.js", var_name, :path)
+  Parser.organize_imports(js_content)
+  Parser.organize_imports("/path/to/file.js", :path, separate_groups: true)
   ```
   """
-  def exist_var(file_path_or_content, var_name, type \\ :content) do
+  def organize_imports(file_path_or_content, type \\ :content, opts \\ []) do
+    separate_groups = Keyword.get(opts, :separate_groups, false)
+
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
-      fn file_content ->
-        Native.contains_variable_from_ast_nif(file_content, var_name)
-      end,
+      fn file_content -> Native.organize_imports_nif(file_content, separate_groups) end,
       type
     )
   end
 
   @doc """
-  Extend the hook object in the given file or content. It accepts a single object
-  or a list of objects.
-  It returns a tuple.
+  Add exports to the end of the given file or content, e.g. to a `hooks/index.js` aggregator,
+  and returns tuple. Names already exported the same way are skipped, and exporting a name
+  that is already exported differently is an error.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.extend_hook_object(js_content, "SomeObject")
-  Parser.extend_hook_object(js_content, ["SomeObject", "AnotherObject"], :content)
-  Parser.extend_hook_object("/path/to/file.js", "SomeObject", :path)
+  Parser.add_exports(js_content, ~s(export { default as Chart } from "./chart";))
+  Parser.add_exports("/path/to/hooks/index.js", "export { Chart };", :path)
   ```
   """
-  def extend_hook_object(file_path_or_content, object_name, type \\ :content)
-
-  def extend_hook_object(file_path_or_content, object_name, type) when is_binary(object_name) do
-    extend_hook_object(file_path_or_content, [object_name], type)
-  end
-
-  def extend_hook_object(file_path_or_content, objects_names, type) when is_list(objects_names) do
+  def add_exports(file_path_or_content, export_lines, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.extend_hook_object_to_ast_nif(file_content, objects_names)
+        Native.add_export_to_ast_nif(file_content, export_lines)
       end,
       type
     )
   end
 
   @doc """
-  Remove objects from the hooks in the given file or content. It accepts a single o
-  bject or a list of objects.
-  It returns a tuple.
+  Remove exports from the given file or content and returns tuple. `export { Name }` removes
+  the exported name, `export * from "source"` the re-exports of the source and
+  `export default ...` the default export.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.remove_objects_from_hooks(js_content, "SomeObject")
-  Parser.remove_objects_from_hooks(js_content, ["SomeObject", "AnotherObject"], :content)
-  Parser.remove_objects_from_hooks("/path/to/file.js", "SomeObject", :path)
+  Parser.remove_exports(js_content, ~s(export { Chart } from "./chart";))
+  Parser.remove_exports("/path/to/hooks/index.js", "export default 0;", :path)
   ```
   """
-  def remove_objects_from_hooks(file_path_or_content, object_name, type \\ :content)
-
-  def remove_objects_from_hooks(file_path_or_content, object_name, type)
-      when is_binary(object_name) do
-    remove_objects_from_hooks(file_path_or_content, [object_name], type)
+  def remove_exports(file_path_or_content, export_lines, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_export_from_ast_nif(file_content, export_lines)
+      end,
+      type
+    )
   end
 
-  def remove_objects_from_hooks(file_path_or_content, objects_names, type)
-      when is_list(objects_names) do
+  @doc """
+  Generate or update a `hooks/index.js` aggregator, which imports every hook file of the
+  directory and exports them as one object, and returns tuple. Entries added by hand are kept.
+  Pass an empty content to generate it.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.generate_hooks_index("", ["copy_to_clipboard.js", "chart.js"])
+  Parser.generate_hooks_index("/path/to/hooks/index.js", File.ls!("/path/to/hooks"), :path)
+  ```
+  """
+  def generate_hooks_index(file_path_or_content, file_names, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.remove_objects_of_hooks_from_ast_nif(file_content, objects_names)
+        Native.generate_hooks_index_nif(file_content, List.wrap(file_names))
       end,
       type
     )
   end
 
   @doc """
-  Retrieve statistical information about the JavaScript source code, such as the number of
-  functions, classes, debugger statements, imports, try-catch blocks, and throw statements.
+  Query the given file or content and patch what the query reached, from a declarative
+  spec, and returns tuple with `%{code: code, changed: changed, matches: matches}`, the
+  `matches` being the code of every reached expression before the patch.
 
-  This function accepts either the content of the JavaScript file or the path to the file,
-  and returns a tuple with the status, function atom, and the extracted data as a map.
+  ## Spec
 
-  ## Examples
+    * `:find` - `{:var, name}` for the initializer of a top-level variable, or
+      `{:callee, "topbar.config"}` for the calls and `new` expressions of a callee.
+    * `:path` - The keys to walk into the object literals, the object literal of a call
+      being its last object argument. Defaults to `[]`.
+    * `:operation` - `:query` (the default), `{:insert, key, code}`, `{:replace, code}` or
+      `:delete`, which removes the last key of the path, or the declaration or the call
+      statement when there is no path.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
+  Parser.query_and_patch(js_content, find: {:var, "liveSocket"}, path: ["hooks"])
 
-  # Analyze a JavaScript source file by providing its content
-  Parser.statistics(js_content)
-
-  # Analyze a JavaScript source file by providing its file path
-  Parser.statistics("/path/to/file.js", :path)
+  Parser.query_and_patch(js_content,
+    find: {:callee, "topbar.config"},
+    path: ["barColors"],
+    operation: {:insert, "1", ~s("#fff")}
+  )
   ```
   """
-  def statistics(file_path_or_content, type \\ :content) do
-    {status, fn_atom, {_, data}} =
-      call_nif_fn(
-        file_path_or_content,
-        __ENV__.function,
-        fn file_content ->
-          Native.statistics_from_ast_nif(file_content)
-        end,
-        type
-      )
+  def query_and_patch(file_path_or_content, spec, type \\ :content) do
+    spec =
+      spec
+      |> Map.new()
+      |> Map.put_new(:path, [])
+      |> Map.put_new(:operation, :query)
+      |> Map.update!(:path, fn path -> Enum.map(List.wrap(path), &to_string/1) end)
 
-    converted = if is_map(data), do: Map.drop(data, [:__struct__]), else: data
-    {status, fn_atom, converted}
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.query_and_patch_nif(file_content, spec)
+      end,
+      type
+    )
   end
 
   @doc """
-    Extend a variable of object type in the given file or content by adding additional objects to it,
-    based on their names.
-
-    This function ensures that duplicate entries are not added during the process.
-
-    This function accepts:
-    - The content or path of the JavaScript file.
-    - The name of the variable to be extended.
-    - A single object name or a list of object names to be added.
-    - The type indicating whether it's content (`:content`) or a path (`:path`).
-
-    It returns a tuple with the status, function atom, and the updated content or an error message
-    if the variable could not be found or modified.
-
-    ## Examples
-
-    ```elixir
-    alias IgniterJs.Parsers.Javascript.Parser
+  Replace every expression matching a pattern written as JavaScript in the given file or
+  content, and returns tuple with `%{code: code, changed: changed, replacements: count}`.
 
-    objects_names = ["OXCTestHook", "MishkaHooks", "MishkaHooks", "OXCTestHook"]
+  The pattern and the replacement are expressions with placeholders: `$NAME` matches any
+  expression and `$$$NAME` any number of arguments or array elements. Placeholder names are
+  upper case, so identifiers like `$el` are matched as they are. The rest of the pattern is
+  compared ignoring the formatting. The code is unchanged when nothing matched.
 
-    Parser.extend_var_object_by_object_names(js_content, "Components", "TestHook")
-    Parser.extend_var_object_by_object_names("/path/to/file.js", "Components", objects_names, :path)
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.codemod(js_content, "topbar.config($OPTS)", "topbar.setup($OPTS)")
 
-    {:error, :extend_var_object_by_object_names, _output} =
-      Parser.extend_var_object_by_object_names("None", "Components", objects_names)
-    ```
+  Parser.codemod(
+    "/path/to/app.js",
+    "new LiveSocket($PATH, Socket, $$$REST)",
+    "new LiveSocket($PATH, Socket, $$$REST, {debug: true})",
+    :path
+  )
+  ```
   """
-  def extend_var_object_by_object_names(file_path_or_content, var, object_names, type \\ :content)
-
-  def extend_var_object_by_object_names(file_path_or_content, var, object_name, type)
-      when is_binary(object_name) do
-    extend_var_object_by_object_names(file_path_or_content, var, [object_name], type)
-  end
-
-  def extend_var_object_by_object_names(file_path_or_content, var, object_names, type) do
+  def codemod(file_path_or_content, pattern, replacement, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.extend_var_object_property_by_names_to_ast_nif(file_content, var, object_names)
+        Native.apply_codemod_nif(file_content, pattern, replacement)
       end,
       type
     )
   end
 
   @doc """
-  Converts a JavaScript AST into the ESTree format.
+  Check if a LiveSocket var exists in the given file or content and returns boolean.
 
-  This function takes either a file path or raw JavaScript content, processes it
-  and returns the resulting ESTree Map structure.
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.exist_live_socket?(js_content)
+  Parser.exist_live_socket?(js_content, :content)
+  Parser.exist_live_socket?("/path/to/file.js", :path)
+  ```
+  """
+  def exist_live_socket?(file_path_or_content, type \\ :content) do
+    elem(exist_live_socket(file_path_or_content, type), 0) == :ok
+  end
+
+  @doc """
+  Check if a LiveSocket var exists in the given file or content and returns tuple.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
-  Parser.ast_to_estree(js_content)
-  Parser.ast_to_estree("/path/to/file.js", :path)
+  Parser.exist_live_socket(js_content)
+  Parser.exist_live_socket(js_content, :content)
+  Parser.exist_live_socket("/path/to/file.js", :path)
   ```
   """
-  def ast_to_estree(file_path_or_content, type \\ :content) do
+  def exist_live_socket(file_path_or_content, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.convert_ast_to_estree_nif(file_content)
+        Native.find_live_socket_node_from_ast_nif(file_content)
       end,
       type
     )
-    |> case do
-      {:ok, _, output} ->
-        {:ok, :ast_to_estree, Jason.decode!(output)}
-
-      {:error, :ast_to_estree, error} ->
-        {:error, :ast_to_estree, error}
-    end
   end
 
   @doc """
-    Inserts a JavaScript AST at the specified index within an existing AST.
-
-    This function takes either a file path or raw JavaScript content, along with the
-    JavaScript code to be inserted and the target index. It processes the AST and inserts
-    the new code at the specified position.
-
+  Check if the LiveSocket is connected with `liveSocket.connect()` in the given file or
+  content and returns boolean.
 
-    ```elixir
-    alias IgniterJs.Parsers.Javascript.Parser
-
-    # Insert after index 1
-    Parser.insert_at_index(js_content, "function newFunc() {}", 1)
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.live_socket_connect?(js_content)
+  Parser.live_socket_connect?("/path/to/file.js", :path)
+  ```
+  """
+  def live_socket_connect?(file_path_or_content, type \\ :content) do
+    elem(has_live_socket_connect(file_path_or_content, type), 0) == :ok
+  end
 
-    # Insert before all code
-    Parser.insert_at_index(js_content, "function newFunc() {}", 0)
+  @doc """
+  Check if the LiveSocket is connected with `liveSocket.connect()` in the given file or
+  content and returns tuple.
 
-    # Insert using a file path
-    Parser.insert_at_index("/path/to/file.js", "function newFunc() {}", 2, :path)
-    ```
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.has_live_socket_connect(js_content)
+  Parser.has_live_socket_connect("/path/to/file.js", :path)
+  ```
   """
-  def insert_at_index(
-        file_path_or_content,
-        insert_code,
-        index,
-        type \\ :content
-      ) do
+  def has_live_socket_connect(file_path_or_content, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.insert_ast_at_index_nif(file_content, insert_code, index)
+        Native.has_live_socket_connect_nif(file_content)
       end,
       type
     )
   end
 
   @doc """
-  Replaces a JavaScript AST node at the specified index with new JavaScript code.
-
-  This function takes either a file path or raw JavaScript content, along with the
-  JavaScript code that should replace an existing node at the given index. It processes
-  the AST and replaces the target node while preserving the surrounding structure.
-
-  If the `index` is out of bounds, an error is returned.
+  Check if the progress bar is configured with `topbar.config(...)` in the given file or
+  content and returns boolean.
 
   ```elixir
   alias IgniterJs.Parsers.Javascript.Parser
+  Parser.topbar_config?(js_content)
+  Parser.topbar_config?("/path/to/file.js", :path)
+  ```
+  """
+  def topbar_config?(file_path_or_content, type \\ :content) do
+    elem(has_topbar_config(file_path_or_content, type), 0) == :ok
+  end
 
-  # Replace node at index 1
-  Parser.replace_at_index(js_content, "function newFunc() {}", 1)
-
-  # Replace node at index 0 (first item)
-  Parser.replace_at_index(js_content, "function newFunc() {}", 0)
+  @doc """
+  Check if the progress bar is configured with `topbar.config(...)` in the given file or
+  content and returns tuple.
 
-  # Replace using a file path
-  Parser.replace_at_index("/path/to/file.js", "function newFunc() {}", 2, :path)
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.has_topbar_config(js_content)
+  Parser.has_topbar_config("/path/to/file.js", :path)
   ```
   """
-  def replace_at_index(
-        file_path_or_content,
-        replace_code,
-        index,
-        type \\ :content
-      ) do
+  def has_topbar_config(file_path_or_content, type \\ :content) do
     call_nif_fn(
       file_path_or_content,
       __ENV__.function,
       fn file_content ->
-        Native.replace_ast_at_index_nif(file_content, replace_code, index)
+        Native.has_topbar_config_nif(file_content)
       end,
       type
     )
   end
+
+  @doc """
+  Set values of the progress bar configuration, the object of `topbar.config({...})`, from
+  a map (or a JSON string) deep-merged into it. The values already set are replaced unless
+  `overwrite: false` is given, and the keys the spec does not have are kept. Without a
+  configuration call, one is added after the LiveSocket, along with the `topbar` import.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.merge_topbar_config(js_content, %{barColors: %{"0" => "#fd4f00"}, shadowColor: "none"})
+  Parser.merge_topbar_config("/path/to/file.js", %{barThickness: 4}, :path, overwrite: false)
+  ```
+  """
+  def merge_topbar_config(file_path_or_content, spec, type \\ :content, opts \\ []) do
+    json_spec = if is_binary(spec), do: spec, else: Jason.encode!(spec)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        overwrite = Keyword.get(opts, :overwrite, true)
+        Native.merge_topbar_config_nif(file_content, json_spec, overwrite)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Restore the standard tail of a Phoenix `app.js` in the given file or content. The topbar
+  configuration, `liveSocket.connect()` and `window.liveSocket = liveSocket` blocks are
+  appended when missing, with the `topbar` import along with its configuration. The
+  content is returned untouched when nothing is missing.
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_live_socket_boilerplate(js_content)
+  Parser.ensure_live_socket_boilerplate("/path/to/file.js", :path)
+  ```
+  """
+  def ensure_live_socket_boilerplate(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_live_socket_boilerplate_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Check if the LiveSocket is exposed for debugging with `window.liveSocket = ...` in the
+  given file or content, whatever the assigned variable is, and returns boolean.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.live_socket_exposed?(js_content)
+  Parser.live_socket_exposed?("/path/to/file.js", :path)
+  ```
+  """
+  def live_socket_exposed?(file_path_or_content, type \\ :content) do
+    elem(has_live_socket_exposure(file_path_or_content, type), 0) == :ok
+  end
+
+  @doc """
+  Check if the LiveSocket is exposed for debugging with `window.liveSocket = ...` in the
+  given file or content and returns tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.has_live_socket_exposure(js_content)
+  Parser.has_live_socket_exposure("/path/to/file.js", :path)
+  ```
+  """
+  def has_live_socket_exposure(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.has_live_socket_exposure_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Expose the LiveSocket on `window` for web console debugging in the given file or content.
+  The `window.liveSocket = liveSocket` block of the Phoenix boilerplate is appended, with the
+  variable the `new LiveSocket(...)` is bound to. The content is returned untouched when
+  `window.liveSocket` is already assigned.
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_live_socket_exposed(js_content)
+  Parser.ensure_live_socket_exposed("/path/to/file.js", :path)
+  ```
+  """
+  def ensure_live_socket_exposed(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_live_socket_exposed_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove every `window.liveSocket = ...` statement from the given file or content, whatever
+  the assigned variable is, e.g. to drop the debugging exposure in production.
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_live_socket_exposure(js_content)
+  Parser.remove_live_socket_exposure("/path/to/file.js", :path)
+  Parser.remove_live_socket_exposure(js_content, :content, comment_out: true)
+  ```
+
+  With `comment_out: true`, the statements are commented out instead of deleted, see
+  `remove_imports/4`.
+  """
+  def remove_live_socket_exposure(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_live_socket_exposure_nif(file_content, removal_mode(opts))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Wrap statements in an environment guard, e.g. to keep debugging code out of production,
+  and returns tuple. The first run of statements equal to the pattern, whatever their
+  formatting, is moved into a `if (...) { ... }` block. The content is returned untouched
+  when they are already guarded by the same condition.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.wrap_in_env_guard(js_content, "liveSocket.enableDebug()")
+  Parser.wrap_in_env_guard(
+    js_content,
+    ["liveSocket.enableDebug()", "liveSocket.enableLatencySim(1000)"],
+    :content,
+    guard: {:import_meta_env, "DEV"}
+  )
+  ```
+
+  It returns an error when the statements are not found.
+
+  ## Options
+
+    * `:guard` - `{:node_env, "development"}` (the default) for
+      `process.env.NODE_ENV === "development"`, `{:import_meta_env, "DEV"}` for
+      `import.meta.env.DEV`, or `{:condition, code}` for any expression.
+  """
+  def wrap_in_env_guard(file_path_or_content, pattern, type \\ :content, opts \\ []) do
+    guard = Keyword.get(opts, :guard, {:node_env, "development"})
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.wrap_in_env_guard_nif(file_content, statement_pattern(pattern), guard)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove the environment guards holding statements equal to the pattern, keeping the
+  statements in their place, and returns tuple. A guard is an `if` without `else` whose
+  condition reads `process.env` or `import.meta.env`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_env_guard(js_content, "liveSocket.enableDebug()")
+  Parser.remove_env_guard("/path/to/file.js", "liveSocket.enableDebug()", :path)
+  ```
+  """
+  def remove_env_guard(file_path_or_content, pattern, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_env_guard_nif(file_content, statement_pattern(pattern))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Ensure Alpine is imported with `import Alpine from "alpinejs"` in the given file or content.
+  An existing default import of `alpinejs` is kept whatever its name is.
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_alpine_import(js_content)
+  Parser.ensure_alpine_import("/path/to/file.js", :path)
+  ```
+  """
+  def ensure_alpine_import(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_alpine_import_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Register an Alpine plugin with `Alpine.plugin(plugin)` in the given file or content. The
+  `alpinejs` import is added when missing, and a plugin that is already registered is not
+  registered again.
+
+  ## Options
+
+    * `:from` - the module to default import the plugin from, e.g. `"@alpinejs/focus"`.
+      An existing default import of it is reused under its own name.
+
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.register_alpine_plugin(js_content, "focus", :content, from: "@alpinejs/focus")
+  Parser.register_alpine_plugin("/path/to/file.js", "persist", :path)
+  ```
+  """
+  def register_alpine_plugin(file_path_or_content, plugin, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.register_alpine_plugin_nif(file_content, plugin, Keyword.get(opts, :from))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Add an Alpine component with `Alpine.data(name, value)` in the given file or content,
+  where the value is a JavaScript expression. A component already defined under the same
+  name is kept as is. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.add_alpine_data(js_content, "dropdown", "() => ({ open: false })")
+  Parser.add_alpine_data("/path/to/file.js", "dropdown", "Dropdown", :path)
+  ```
+  """
+  def add_alpine_data(file_path_or_content, name, value, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.add_alpine_data_nif(file_content, name, value)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Ensure Alpine is exposed with `window.Alpine = Alpine` and started with `Alpine.start()`
+  in the given file or content. `Alpine.start()` is placed after the plugins and components
+  and before the LiveSocket is created, and an existing one is moved there when it runs too
+  early or too late. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_alpine_start(js_content)
+  Parser.ensure_alpine_start("/path/to/file.js", :path)
+  ```
+  """
+  def ensure_alpine_start(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_alpine_start_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Ensure a service worker is registered in the given file or content, appending the
+  feature-detected registration block when the script is not registered yet.
+
+  ```javascript
+  if ("serviceWorker" in navigator) {
+      navigator.serviceWorker.register("/sw.js", { scope: "/" });
+  }
+  ```
+
+  A registration is found by its script URL wherever it is, e.g. inside a `load` listener.
+
+  ## Options
+
+    * `:options` - the options of the registration as a JavaScript object, e.g.
+      `~s({ scope: "/" })`. They replace the options of an existing registration.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_service_worker_registration(js_content, "/sw.js")
+  Parser.ensure_service_worker_registration(js_content, "/sw.js", :content,
+    options: ~s({ scope: "/" })
+  )
+  ```
+  """
+  def ensure_service_worker_registration(
+        file_path_or_content,
+        path,
+        type \\ :content,
+        opts \\ []
+      ) do
+    options = Keyword.get(opts, :options)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_service_worker_registration_nif(file_content, path, options)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove the registrations of a service worker script from the given file or content, or of
+  every script when `path` is `nil`. A `if ("serviceWorker" in navigator)` block left empty
+  is removed with them.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_service_worker_registration(js_content, "/sw.js")
+  Parser.remove_service_worker_registration("/path/to/file.js", nil, :path)
+  ```
+  """
+  def remove_service_worker_registration(file_path_or_content, path, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_service_worker_registration_nif(file_content, path)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Check if a specific var exists in the given file or content and returns boolean.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.exist_live_socket?(js_content)
+  Parser.exist_live_socket?(js_content, :content)
+  Parser.exist_live_socket?("/path/to/file.js", :path)
+  ```
+  """
+  def var_exists?(file_path_or_content, type \\ :content) do
+    elem(exist_var(file_path_or_content, type), 0) == :ok
+  end
+
+  @doc """
+  Check if an specific var exists in the given file or content and returns tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.exist_live_socket(js_content, var_name)
+  Parser.exist_live_socket(js_content, var_name, :content)
+  Parser.exist_live_socket("/path/to/file.js", var_name, :path)
+  ```
+
+  Without options only top-level `let` declarations are matched. Passing options switches to
+  a scope-aware lookup over every declaration:
+
+    - `top_level: true` - only match declarations made directly in the module body.
+    - `kinds: [:const, :let, :var, :function, :class]` - only match these kinds.
+
+  ```elixir
+  Parser.exist_var(js_content, "Hooks", :content, top_level: true, kinds: [:const, :let])
+  ```
+  """
+  def exist_var(file_path_or_content, var_name, type \\ :content, opts \\ [])
+
+  def exist_var(file_path_or_content, var_name, type, []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.contains_variable_from_ast_nif(file_content, var_name)
+      end,
+      type
+    )
+  end
+
+  def exist_var(file_path_or_content, var_name, type, opts) do
+    case var_info(file_path_or_content, var_name, type, opts) do
+      {:ok, _, _} -> {:ok, :exist_var, true}
+      {:error, _, reason} -> {:error, :exist_var, reason}
+    end
+  end
+
+  @doc """
+  Find a declaration by name and describe it. It accepts the same options as `exist_var/4`.
+
+  The result tells the declaration kind, whether it is top-level, and the type of its
+  initializer (`:object`, `:array`, `:call`, `:new`, `:arrow_function`, `:function`,
+  `:class`, `:identifier`, `:member`, `:literal`, `:template` or `:other`; `nil` when the
+  variable has no initializer), so callers can branch on whether `hooks` is an object
+  literal or something else.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.var_info(js_content, "Hooks")
+  # => {:ok, :var_info, %{kind: :const, top_level: true, init: :object}}
+  Parser.var_info("/path/to/file.js", "Hooks", :path, top_level: true)
+  ```
+  """
+  def var_info(file_path_or_content, var_name, type \\ :content, opts \\ []) do
+    kinds = opts |> Keyword.get(:kinds, []) |> Enum.map(&to_string/1)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.find_variable_from_ast_nif(
+          file_content,
+          var_name,
+          Keyword.get(opts, :top_level, false),
+          kinds
+        )
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Read the initializer of a variable, and returns tuple with its source text and type, e.g.
+  to inspect the current value of an option before deciding how to patch it. It accepts the
+  same options as `exist_var/4`.
+
+  The name may be followed by object keys, which are read from the object literal the
+  variable is initialized with, or from the last argument of the call or `new` expression it
+  is initialized with. The result is a map with the `:kind` of the expression (like the
+  `:init` of `var_info/4`), its `:source`, whether the variable is `:top_level`, and the
+  byte offsets `:start` and `:end` of the expression.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.variable_initializer(js_content, "liveSocket.longPollFallbackMs")
+  # => {:ok, :variable_initializer, %{kind: :literal, source: "2500", top_level: true, ...}}
+  Parser.variable_initializer(js_content, "Hooks", :content, top_level: true)
+  ```
+  """
+  def variable_initializer(file_path_or_content, path, type \\ :content, opts \\ []) do
+    kinds = opts |> Keyword.get(:kinds, []) |> Enum.map(&to_string/1)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.get_variable_initializer_nif(
+          file_content,
+          path,
+          Keyword.get(opts, :top_level, false),
+          kinds
+        )
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Extend the hook object in the given file or content. It accepts a single object
+  or a list of objects.
+  It returns a tuple.
+
+  Besides hook names, a hook can be given with its value expression, either as a
+  `"Name: value"` string or as a `{name, value}` tuple, e.g. to register a hook defined
+  by a vendor library. The value can be any expression, so a tiny hook can be defined
+  inline without a file and an import of its own. Names that are not identifiers, e.g.
+  `"phx:scroll-top"`, are quoted. Hooks that are already registered are never added again.
+
+  The LiveSocket options can be an object literal or a variable holding one, e.g.
+  `new LiveSocket("/live", Socket, socketOptions)`, whose declaration is then updated.
+  Options built by a call, e.g. `buildOpts()`, can not be edited and return
+  `{:error, :extend_hook_object, {:unsupported_options_shape, "buildOpts"}}`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.extend_hook_object(js_content, "SomeObject")
+  Parser.extend_hook_object(js_content, ["SomeObject", "AnotherObject"], :content)
+  Parser.extend_hook_object("/path/to/file.js", "SomeObject", :path)
+  Parser.extend_hook_object(js_content, ["Sortable: window.Sortable"])
+  Parser.extend_hook_object(js_content, Chart: "Charts.LineChart")
+  Parser.extend_hook_object(js_content, ScrollTop: "{ mounted() { window.scrollTo(0, 0); } }")
+  Parser.extend_hook_object(js_content, ["Dropdown"], :content, sorted: true)
+  ```
+
+  ## Options
+
+    * `:sorted` - when `true`, the new hooks are inserted alphabetically among the existing
+      ones, and new spreads after the existing spreads, which stay first, so repeated installs
+      give the same, reviewable object. Defaults to `false`, which appends them.
+    * `:key_position` - where `hooks` goes when the LiveSocket options do not have it:
+      `:end` (the default), `:after_params`, right after `params` like the Phoenix generator
+      lays them out, or `:first`.
+  """
+  def extend_hook_object(file_path_or_content, object_name, type \\ :content, opts \\ [])
+
+  def extend_hook_object(file_path_or_content, object_name, type, opts)
+      when is_binary(object_name) do
+    extend_hook_object(file_path_or_content, [object_name], type, opts)
+  end
+
+  def extend_hook_object(file_path_or_content, objects_names, type, opts)
+      when is_list(objects_names) do
+    objects_names = live_socket_option_entries(objects_names)
+    order = if Keyword.get(opts, :sorted, false), do: :sorted, else: :append
+    key_position = Keyword.get(opts, :key_position, :end)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.extend_hook_object_to_ast_nif(file_content, objects_names, order, key_position)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove objects from the hooks in the given file or content. It accepts a single o
+  bject or a list of objects.
+  It returns a tuple.
+
+  Entries are matched by their key: the name of `Copy` and `Drag: DragHook`, the string or
+  number of `"phx-drag": DragHook` and `["phx-drag"]: DragHook`, and `...Hooks` for spreads.
+
+  ## Options
+
+    - `:prune_unused_imports` - when `true`, imports that became unused because of the
+      removal are removed too, or commented out with `:comment_out`, and the result is
+      `{updated_content, pruned_imports}`. A local binding of the same name, like a function
+      parameter, is not a use of the import. Defaults to `false`.
+    - `:comment_out` - when `true`, the entries are commented out with a
+      `// removed by igniter:` marker instead of deleted. Defaults to `false`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_objects_from_hooks(js_content, "SomeObject")
+  Parser.remove_objects_from_hooks(js_content, ["SomeObject", "AnotherObject"], :content)
+  Parser.remove_objects_from_hooks("/path/to/file.js", "SomeObject", :path)
+
+  Parser.remove_objects_from_hooks(js_content, "SomeObject", :content, prune_unused_imports: true)
+  # => {:ok, :remove_objects_from_hooks, {updated_content, ["SomeObject"]}}
+  ```
+  """
+  def remove_objects_from_hooks(file_path_or_content, object_name, type \\ :content, opts \\ [])
+
+  def remove_objects_from_hooks(file_path_or_content, object_name, type, opts)
+      when is_binary(object_name) do
+    remove_objects_from_hooks(file_path_or_content, [object_name], type, opts)
+  end
+
+  def remove_objects_from_hooks(file_path_or_content, objects_names, type, opts)
+      when is_list(objects_names) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_objects_of_hooks_from_ast_nif(
+          file_content,
+          objects_names,
+          removal_mode(opts),
+          Keyword.get(opts, :prune_unused_imports, false)
+        )
+      end,
+      type
+    )
+    |> take_pruned_imports()
+  end
+
+  @doc """
+  Add entries to an object option of the LiveSocket in the given file or content, like
+  `extend_hook_object/3` does for `hooks`. The option is one of `:hooks`, `:uploaders`,
+  `:dom` and `:metadata`, and is created when it is missing. Entries are names, spreads
+  (`"...Uploaders"`), `"Name: value"` strings or `{name, value}` tuples, and entries whose
+  key is already registered are skipped.
+  It returns a tuple.
+
+  ## Options
+
+    * `:socket` - the variable of the LiveSocket to edit, or `:all` for every LiveSocket of
+      the file, see `list_live_sockets/2`. Defaults to `"liveSocket"`.
+    * `:log` - when `true`, the result is `%{code: code, changed: changed, actions: actions}`,
+      where the actions taken are `{:created_key, option}`, `{:spread_reference, variable}`,
+      `{:added_entry, key}` and `{:skipped_entry, key}` tuples, in order. Defaults to
+      `false`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.extend_live_socket_option(js_content, :uploaders, "S3")
+  Parser.extend_live_socket_option(js_content, :dom, onBeforeElUpdated: "(from, to) => {}")
+  Parser.extend_live_socket_option(js_content, :hooks, "Admin", :content, socket: "adminSocket")
+  ```
+  """
+  def extend_live_socket_option(
+        file_path_or_content,
+        option,
+        entries,
+        type \\ :content,
+        opts \\ []
+      )
+      when option in [:hooks, :uploaders, :dom, :metadata] do
+    entries = live_socket_option_entries(entries)
+    target = live_socket_target(opts)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        if Keyword.get(opts, :log, false) do
+          Native.extend_live_socket_option_with_log_nif(file_content, option, entries, target)
+        else
+          Native.extend_live_socket_option_to_ast_nif(file_content, option, entries, target)
+        end
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove entries from an object option of the LiveSocket in the given file or content, like
+  `remove_objects_from_hooks/4` does for `hooks`. The option is one of `:hooks`,
+  `:uploaders`, `:dom` and `:metadata`. It accepts the `:socket` and `:log` options of
+  `extend_live_socket_option/5`, and logs the removed entries as `{:removed_entry, key}`.
+  With `comment_out: true`, the entries are commented out instead of deleted.
+  It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_live_socket_option_entries(js_content, :uploaders, ["S3", "...Uploaders"])
+  ```
+  """
+  def remove_live_socket_option_entries(
+        file_path_or_content,
+        option,
+        entries,
+        type \\ :content,
+        opts \\ []
+      )
+      when option in [:hooks, :uploaders, :dom, :metadata] do
+    entries = List.wrap(entries)
+    target = live_socket_target(opts)
+    mode = removal_mode(opts)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        if Keyword.get(opts, :log, false) do
+          Native.remove_live_socket_option_entries_with_log_nif(
+            file_content,
+            option,
+            entries,
+            target,
+            mode
+          )
+        else
+          Native.remove_live_socket_option_entries_from_ast_nif(
+            file_content,
+            option,
+            entries,
+            target,
+            mode
+          )
+        end
+      end,
+      type
+    )
+  end
+
+  @doc """
+  List the variables a `new LiveSocket(...)` is bound to in the given file or content, in
+  source order, e.g. `["liveSocket", "adminSocket"]`. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.list_live_sockets(js_content)
+  Parser.list_live_sockets("/path/to/file.js", :path)
+  ```
+  """
+  def list_live_sockets(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.list_live_sockets_from_ast_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  List the names of all hooks registered in the LiveSocket hooks object of the given file
+  or content. Shorthand and key-value entries are listed by their key and spreads as
+  `"...Name"`. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.list_hooks(js_content)
+  Parser.list_hooks("/path/to/file.js", :path)
+  # => {:ok, :list_hooks, ["...Hooks", "CopyMixInstallationHook"]}
+  ```
+  """
+  def list_hooks(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.list_hooks_from_ast_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Wire the Phoenix colocated hooks of the given application into the LiveSocket hooks.
+  It makes sure `import { hooks as colocatedHooks } from "phoenix-colocated/<app_name>"`
+  exists and `...colocatedHooks` is spread into the hooks object, for both inline hooks
+  objects and `hooks: hooks` references. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ensure_colocated_hooks(js_content, "my_app")
+  Parser.ensure_colocated_hooks("/path/to/file.js", :my_app, :path)
+  ```
+  """
+  def ensure_colocated_hooks(file_path_or_content, app_name, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.ensure_colocated_hooks_to_ast_nif(file_content, to_string(app_name))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Merge keys into the `params` option of the LiveSocket in the given file or content.
+  The params are a map or keyword list whose values are JavaScript expressions.
+  `params` can be an object literal, a variable declared as an object literal or an arrow
+  function returning an object; it is created when missing. Existing keys are kept as they
+  are. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.extend_live_socket_params(js_content, locale: "document.documentElement.lang")
+  Parser.extend_live_socket_params("/path/to/file.js", %{"locale" => "navigator.language"}, :path)
+  ```
+  """
+  def extend_live_socket_params(file_path_or_content, params, type \\ :content) do
+    params = Enum.map(params, fn {key, value} -> {to_string(key), value} end)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.extend_live_socket_params_to_ast_nif(file_content, params)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Wrap the `hooks` option of the LiveSocket in the given file or content in a call, as some
+  libraries require, e.g. `hooks: withLiveReact(Hooks)`. The arguments are JavaScript
+  expressions passed after the hooks, so `["Colocated"]` gives
+  `hooks: composeHooks(Hooks, Colocated)`. Hooks already passed to the function are left
+  untouched. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.wrap_live_socket_hooks(js_content, "withLiveReact")
+  Parser.wrap_live_socket_hooks("/path/to/file.js", "composeHooks", ["Colocated"], :path)
+  ```
+  """
+  def wrap_live_socket_hooks(file_path_or_content, callee, args \\ [], type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.wrap_live_socket_hooks_to_ast_nif(file_content, callee, args)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Compose a snippet into the `dom.onBeforeElUpdated` callback of the LiveSocket in the
+  given file or content, as libraries like Alpine.js and Shoelace require. The snippet is
+  the body of the callback and can use its `from` and `to` parameters.
+
+  The `dom` option and the callback are created when missing. An existing callback is never
+  replaced: the snippet is prepended to a `(from, to)` callback, and any other callback is
+  called from a new one running the snippet first. A callback already running the snippet
+  is left untouched. It returns a tuple.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  Parser.compose_on_before_el_updated(
+    js_content,
+    "if (from._x_dataStack) { window.Alpine.clone(from, to); }"
+  )
+  ```
+  """
+  def compose_on_before_el_updated(file_path_or_content, snippet, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.compose_on_before_el_updated_to_ast_nif(file_content, snippet)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Deep-merge a spec into the object literal a variable is declared with, creating nested
+  keys as needed. The spec is a map (or a JSON string); existing values are preserved unless
+  `overwrite: true` is given. Running it twice is a no-op.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.merge_object_literal(js_content, "chartOptions", %{plugins: %{legend: %{position: "top"}}})
+  Parser.merge_object_literal(js_content, "config", %{responsive: false}, :content, overwrite: true)
+  Parser.merge_object_literal("/path/to/file.js", "config", ~s({"a": 1}), :path)
+  ```
+  """
+  def merge_object_literal(file_path_or_content, var_name, spec, type \\ :content, opts \\ []) do
+    json_spec = if is_binary(spec), do: spec, else: Jason.encode!(spec)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.merge_object_literal_nif(
+          file_content,
+          var_name,
+          json_spec,
+          Keyword.get(opts, :overwrite, false)
+        )
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Retrieve statistical information about the JavaScript source code, such as the number of
+  functions, classes, debugger statements, imports, try-catch blocks, throw statements,
+  exports, arrow functions, async functions, top-level awaits, `console.*` calls and
+  `addEventListener` registrations.
+
+  This function accepts either the content of the JavaScript file or the path to the file,
+  and returns a tuple with the status, function atom, and the extracted data as a map.
+
+  With the `locations: true` option, the map also holds a `:locations` map with the
+  `%{start: _, end: _, line: _, column: _}` location of every counted node per category,
+  e.g. `statistics.locations.console_calls`. Otherwise `:locations` is `nil`.
+
+  ## Examples
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  # Analyze a JavaScript source file by providing its content
+  Parser.statistics(js_content)
+
+  # Analyze a JavaScript source file by providing its file path
+  Parser.statistics("/path/to/file.js", :path)
+
+  # Also return where every counted node is
+  Parser.statistics(js_content, :content, locations: true)
+  ```
+  """
+  def statistics(file_path_or_content, type \\ :content, opts \\ []) do
+    {status, fn_atom, {_, data}} =
+      call_nif_fn(
+        file_path_or_content,
+        __ENV__.function,
+        fn file_content ->
+          Native.statistics_from_ast_nif(file_content, Keyword.get(opts, :locations, false))
+        end,
+        type
+      )
+
+    converted = if is_map(data), do: Map.drop(data, [:__struct__]), else: data
+    {status, fn_atom, converted}
+  end
+
+  @doc """
+  List the sources imported by a JavaScript entrypoint, such as `app.js`, and by the files
+  it imports, e.g. to know which vendor files an installer is going to touch.
+
+  Every import is a map with the imported `:source`, its `:kind` (`:package` for bare
+  specifiers like `"phoenix"`, `:file` for relative and absolute paths), the `:statement`
+  importing it (`:import`, `:export`, `:dynamic` or `:require`), the `:importer` path, the
+  `:resolved` path of the imported file or `nil`, and the `:location` of the source string.
+
+  ## Options
+
+    - `path` - the path of the entrypoint, which file imports are resolved against.
+      Defaults to the file path, or `"app.js"` for content.
+    - `files` - a map of path to source of the other files. File imports that resolve to
+      one of them, trying the `.js`, `.mjs`, `.cjs`, `.ts` and `/index.js` suffixes, are
+      followed. Defaults to `%{}`.
+
+  ## Examples
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  Parser.analyze_imports(app_js, :content,
+    path: "assets/js/app.js",
+    files: %{"assets/vendor/topbar.js" => topbar_js}
+  )
+  ```
+  """
+  def analyze_imports(file_path_or_content, type \\ :content, opts \\ []) do
+    path =
+      Keyword.get_lazy(opts, :path, fn ->
+        if type == :path, do: file_path_or_content, else: "app.js"
+      end)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.analyze_imports_nif(file_content, path, Keyword.get(opts, :files, %{}))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Rename a binding declared at the top level of the given file or content, together with
+  every reference to it.
+
+  References in scopes that declare their own binding with the same name are left
+  untouched. Shorthand properties, destructuring, named imports and named exports keep
+  their key, e.g. `import { Socket }` becomes `import { Socket as PhoenixSocket }`.
+
+  An error is returned when `old_name` is not declared at the top level, `new_name` is not
+  a valid identifier, or `new_name` is already in use where it would shadow or be shadowed.
+
+  ## Examples
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.rename_identifier(js_content, "socket", "liveSocket")
+  Parser.rename_identifier(js_file, "socket", "liveSocket", :path)
+  ```
+  """
+  def rename_identifier(file_path_or_content, old_name, new_name, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.rename_identifier_nif(file_content, old_name, new_name) end,
+      type
+    )
+  end
+
+  @doc """
+  Report the imports of the given file or content that are never used, and the exports it
+  never references itself, e.g. to offer cleaning up what removing hooks left behind.
+
+  The result is a map with:
+
+    - `:unused_imports` - the import specifiers whose binding is never referenced, each with
+      its `:local` name, the `:imported` name (`"default"` and `"*"` for default and
+      namespace imports), the `:source` and the `:location` of the binding.
+    - `:unreferenced_exports` - the named exports the module does not reference besides
+      exporting them, each with its `:exported` and `:local` names and `:location`.
+
+  References are resolved through the scopes, so a parameter with the same name as an
+  import does not use it. Nothing is reported for a module calling `eval`.
+
+  ## Examples
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.analyze_unused(js_content)
+  Parser.analyze_unused(js_file, :path)
+  ```
+  """
+  def analyze_unused(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.analyze_unused_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  List the names declared at the top level of the given file or content, e.g. to check that
+  `csrfToken` or `hooks` is not declared yet before inserting it. It returns a tuple.
+
+  Each binding is a map with the `:name`, the `:kind` (`:const`, `:let`, `:var`,
+  `:function` or `:class`), whether it is `:exported` and the `:location` of the name.
+  Every name of a destructuring declaration is listed, imports are not.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.list_top_level_bindings(js_content)
+  Parser.list_top_level_bindings("/path/to/file.js", :path)
+  ```
+  """
+  def list_top_level_bindings(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.list_top_level_bindings_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  Return the original source of the top-level declaration of a function, class or variable
+  in the given file or content, e.g. to move a hook out of `app.js` as it is written. It
+  returns a tuple.
+
+  The result is a map with the `:code`, which includes the `export` keyword and the comments
+  on the lines right above the declaration, and its `:location`. A variable declared along
+  with others returns the whole declaration.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.extract_code_block(js_content, "CopyHook")
+  Parser.extract_code_block("/path/to/app.js", "CopyHook", :path)
+  ```
+  """
+  def extract_code_block(file_path_or_content, name, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.extract_code_block_nif(file_content, name) end,
+      type
+    )
+  end
+
+  @doc """
+  Move the top-level declaration of a function, class or variable from the `source` content
+  to the end of the `target` content, e.g. to split a hook out of `app.js`. The declaration
+  keeps its text and leading comments, and the bindings it imports in `source` are imported
+  in `target` from the same, unchanged, sources. It returns
+  `%{source: source, target: target}` with both updated contents.
+
+  When the rest of `source` still uses the declaration, the `:import_from` option is
+  required: `source` then imports the declaration from it, and `target` exports it.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  {:ok, :move_declaration, %{source: app_js, target: copy_js}} =
+    Parser.move_declaration(app_js, "", "CopyHook", import_from: "./hooks/copy")
+  ```
+  """
+  def move_declaration(source, target, name, opts \\ []) do
+    import_from = Keyword.get(opts, :import_from)
+
+    call_nif_fn(
+      source,
+      __ENV__.function,
+      &Native.move_declaration_between_sources_nif(&1, target, name, import_from),
+      :content
+    )
+  end
+
+  @doc """
+  Parse the given JavaScript file or content without changing it, and return every syntax error
+  found, e.g. to refuse to patch a file that is already broken and show the user why.
+
+  The result is a list of diagnostics, maps with the `:message`, the `:severity`
+  (`:error`, `:warning` or `:advice`), the `:help` text or `nil`, the `:start` and `:end`
+  byte offsets, the 1-based `:line` and 0-based `:column`, and a `:code_frame` showing the
+  lines around the problem. The status is `:error` when any diagnostic is an error.
+
+  ## Options
+
+    - `engine` - the parser, `:oxc` or `:swc`, see `parse_with_engine/3`. OXC recovers from
+      most errors and reports all of them, SWC stops at the first one it can not recover
+      from. Defaults to `:oxc`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.validate("let a = ;")
+  Parser.validate("/path/to/file", :path)
+  Parser.validate("let a = ;", :content, engine: :swc)
+  ```
+  """
+  def validate(file_path_or_content, type \\ :content, opts \\ []) do
+    engine = Keyword.get(opts, :engine, :oxc)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.validate_js_nif(file_content, engine) end,
+      type
+    )
+  end
+
+  @doc """
+  Check if the given JavaScript file or content has no syntax error, see `validate/2`.
+  """
+  def valid?(file_path_or_content, type \\ :content) do
+    elem(validate(file_path_or_content, type), 0) == :ok
+  end
+
+  @doc """
+  Parse the given JavaScript file or content with the chosen parser and return what it found,
+  in the same shape whatever the parser is:
+
+    - `:statements` - the top-level statements, maps with their ESTree `:kind`, e.g.
+      `"ImportDeclaration"`, and their `:start` and `:end` byte offsets.
+    - `:comments` - the comments, maps with their `:kind` (`:line` or `:block`), their
+      `:text` without the delimiters, and their `:start` and `:end` byte offsets.
+    - `:diagnostics` - the syntax errors and warnings, as returned by `validate/3`.
+
+  The status is `:error` when any diagnostic is an error, the statements and comments are
+  then empty.
+
+  ## Options
+
+    - `engine` - the parser, `:swc`, which backs the functions changing the code, or
+      `:oxc`, which backs the ESTree conversion. Defaults to `:swc`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.parse_with_engine(js_content)
+  Parser.parse_with_engine("/path/to/file.js", :path, engine: :oxc)
+  ```
+  """
+  def parse_with_engine(file_path_or_content, type \\ :content, opts \\ []) do
+    engine = Keyword.get(opts, :engine, :swc)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.parse_with_engine_nif(file_content, engine) end,
+      type
+    )
+  end
+
+  @doc """
+  Parse the given JavaScript file or content with the chosen parser and print it back from its
+  syntax tree, e.g. to check that the parser round-trips a file before patching it.
+  With `:oxc` the code is printed from its ESTree, which has no comments.
+
+  ## Options
+
+    - `engine` - the parser, `:swc` or `:oxc`, see `parse_with_engine/3`. Defaults to `:swc`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.codegen_with_engine(js_content)
+  Parser.codegen_with_engine("/path/to/file.js", :path, engine: :oxc)
+  ```
+  """
+  def codegen_with_engine(file_path_or_content, type \\ :content, opts \\ []) do
+    engine = Keyword.get(opts, :engine, :swc)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.codegen_with_engine_nif(file_content, engine) end,
+      type
+    )
+  end
+
+  @doc """
+    Extend a variable of object type in the given file or content by adding additional objects to it,
+    based on their names.
+
+    This function ensures that duplicate entries are not added during the process.
+
+    This function accepts:
+    - The content or path of the JavaScript file.
+    - The name of the variable to be extended, declared with `const`, `let` or `var` and
+      exported or not, or `:default_export` for the object of `export default {...}` or of
+      the variable exported by `export default Hooks`, as in a hooks aggregator file.
+    - A single object name or a list of object names to be added.
+    - The type indicating whether it's content (`:content`) or a path (`:path`).
+
+    It returns a tuple with the status, function atom, and the updated content or an error message
+    if the variable could not be found or modified.
+
+    ## Examples
+
+    ```elixir
+    alias IgniterJs.Parsers.Javascript.Parser
+
+    objects_names = ["OXCTestHook", "MishkaHooks", "MishkaHooks", "OXCTestHook"]
+
+    Parser.extend_var_object_by_object_names(js_content, "Components", "TestHook")
+    Parser.extend_var_object_by_object_names("/path/to/file.js", "Components", objects_names, :path)
+    Parser.extend_var_object_by_object_names(hooks_index_content, :default_export, "Chart")
+
+    {:error, :extend_var_object_by_object_names, _output} =
+      Parser.extend_var_object_by_object_names("None", "Components", objects_names)
+    ```
+  """
+  def extend_var_object_by_object_names(file_path_or_content, var, object_names, type \\ :content)
+
+  def extend_var_object_by_object_names(file_path_or_content, var, object_name, type)
+      when is_binary(object_name) do
+    extend_var_object_by_object_names(file_path_or_content, var, [object_name], type)
+  end
+
+  def extend_var_object_by_object_names(file_path_or_content, :default_export, names, type) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.extend_object_property_by_names_to_ast_nif(file_content, :export_default, names)
+      end,
+      type
+    )
+  end
+
+  def extend_var_object_by_object_names(file_path_or_content, var, object_names, type) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.extend_var_object_property_by_names_to_ast_nif(file_content, var, object_names)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Remove properties from the object a variable is declared with, the inverse of
+  `extend_var_object_by_object_names/4`, e.g. to uninstall an entry of a `Components`
+  registry.
+
+  Properties are matched by their key: `"Modal"` removes a shorthand `Modal` or a
+  `Modal: value` entry, and `"...Base"` a spread entry. Keys that are not in the object are
+  ignored, and an error is returned when the variable is not found or is not an object.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.remove_var_object_properties(js_content, "Components", ["Modal", "...Base"])
+  Parser.remove_var_object_properties("/path/to/file.js", "Components", "Modal", :path)
+  ```
+  """
+  def remove_var_object_properties(file_path_or_content, var, keys, type \\ :content)
+
+  def remove_var_object_properties(file_path_or_content, var, key, type) when is_binary(key) do
+    remove_var_object_properties(file_path_or_content, var, [key], type)
+  end
+
+  def remove_var_object_properties(file_path_or_content, var, keys, type) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.remove_var_object_properties_nif(file_content, var, keys)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Extend an object literal selected by a target spec with the given object names, like
+  `extend_var_object_by_object_names/4` does for variables. Names that are already present
+  are skipped, and names starting with `...` are added as spreads.
+
+  Targets:
+
+    - `{:var, "Components"}` - `const Components = {...}`
+    - `:export_default` - `export default {...}` or `export default defineConfig({...})`
+    - `:module_exports` - `module.exports = {...}`, as in tailwind/postcss configs
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.extend_object_by_object_names(js_content, :export_default, ["Tooltip"])
+  Parser.extend_object_by_object_names("/path/to/tailwind.config.js", :module_exports, "theme", :path)
+  ```
+  """
+  def extend_object_by_object_names(file_path_or_content, target, object_names, type \\ :content)
+
+  def extend_object_by_object_names(file_path_or_content, target, object_name, type)
+      when is_binary(object_name) do
+    extend_object_by_object_names(file_path_or_content, target, [object_name], type)
+  end
+
+  def extend_object_by_object_names(file_path_or_content, target, object_names, type) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.extend_object_property_by_names_to_ast_nif(file_content, target, object_names)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Converts a JavaScript AST into the ESTree format.
+
+  This function takes either a file path or raw JavaScript content, processes it
+  and returns the resulting ESTree Map structure.
+
+  Options:
+
+    - `compact: true` - build compact JSON on the native side, which is much smaller for
+      large files.
+    - `decode: false` - return the JSON string instead of decoding it.
+    - `spans: :utf16 | :utf8 | :none` - report node positions as UTF-16 offsets (the
+      default, as JavaScript tooling expects), as UTF-8 byte offsets to slice the Elixir
+      binary, or leave them out entirely.
+    - `node_types: ["ImportDeclaration", ...]` - only keep the top-level statements of these
+      ESTree types, so tasks that only need the top-level structure skip the rest.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ast_to_estree(js_content)
+  Parser.ast_to_estree("/path/to/file.js", :path)
+  Parser.ast_to_estree(js_content, :content, compact: true, decode: false)
+  Parser.ast_to_estree(js_content, :content, spans: :none, node_types: ["ImportDeclaration"])
+  ```
+  """
+  def ast_to_estree(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.convert_ast_to_estree_nif(file_content, estree_options(opts))
+      end,
+      type
+    )
+    |> case do
+      {:ok, _, output} ->
+        {:ok, :ast_to_estree, maybe_decode_json(output, opts)}
+
+      {:error, :ast_to_estree, error} ->
+        {:error, :ast_to_estree, error}
+    end
+  end
+
+  @doc """
+  Converts a JavaScript AST into ESTree, one document per top-level statement.
+
+  Unlike `ast_to_estree/3`, the statements are returned as a list, so large files can be
+  processed one statement at a time. It returns an error with the syntax errors when the
+  source can not be parsed. It accepts the same options as `ast_to_estree/3`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ast_to_estree_statements(js_content)
+  # => {:ok, :ast_to_estree_statements, [%{"type" => "ImportDeclaration", ...}, ...]}
+  Parser.ast_to_estree_statements("/path/to/bundle.js", :path, compact: true, decode: false)
+  ```
+  """
+  def ast_to_estree_statements(file_path_or_content, type \\ :content, opts \\ []) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.convert_ast_to_estree_statements_nif(file_content, estree_options(opts))
+      end,
+      type
+    )
+    |> case do
+      {:ok, fn_atom, fragments} ->
+        {:ok, fn_atom, Enum.map(fragments, &maybe_decode_json(&1, opts))}
+
+      error ->
+        error
+    end
+  end
+
+  @doc """
+  Generates JavaScript code from an ESTree document, the inverse of `ast_to_estree/3`.
+
+  The document can be the decoded output of `ast_to_estree/3`, a `Program` node, a single
+  statement or expression node, or a list of statements such as the output of
+  `ast_to_estree_statements/3`. It is given as a map or list, or as a JSON string.
+  Positions are ignored and comments are not kept, so nodes can be built, edited and
+  moved around freely before generating the code.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  {:ok, _, [import | rest]} = Parser.ast_to_estree_statements(js_content)
+  Parser.estree_to_source(rest ++ [import])
+  # => {:ok, :estree_to_source, "..."}
+  ```
+  """
+  def estree_to_source(estree) do
+    estree_json = if is_binary(estree), do: estree, else: Jason.encode!(estree)
+
+    call_nif_fn(estree_json, __ENV__.function, &Native.estree_to_source_nif/1, :content)
+  end
+
+  @doc """
+  Compares two JavaScript sources statement by statement, ignoring formatting, comments and
+  quotes, e.g. to detect the customizations of a generated file before overwriting it.
+
+  Top-level statements are paired by what they declare: imports and re-exports by their
+  source, declarations by their names and expression statements by their callee or
+  assignment target, e.g. `"liveSocket.connect"`. Each change is a map with:
+
+    - `:kind` - `:added`, `:removed` or `:changed`.
+    - `:category` - `:import`, `:export`, `:variable`, `:function`, `:class`, `:expression`
+      or `:other`.
+    - `:name` - what the statement declares, or `nil`.
+    - `:old_location` and `:new_location` - the location in each source, or `nil`.
+
+  Removed and changed statements come in the order of the old source, then the added ones.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.ast_diff(generated_content, File.read!("assets/js/app.js"))
+  # => {:ok, :ast_diff, [%{kind: :changed, category: :variable, name: "liveSocket", ...}]}
+  ```
+  """
+  def ast_diff(old_source, new_source) do
+    call_nif_fn(old_source, __ENV__.function, &Native.ast_diff_nif(&1, new_source), :content)
+  end
+
+  @doc """
+  Merges the changes between two generated versions of a file into the version the user
+  edited, e.g. to regenerate `app.js` across Phoenix versions without losing customizations.
+
+  The merge works on top-level statements, paired like `ast_diff/2` pairs them. A statement
+  the generator changed is updated unless the user changed it too, and a statement both
+  changed differently is a conflict, written between `<<<<<<< user`, `=======` and
+  `>>>>>>> generated` markers. The user's statements keep their text and comments.
+
+  It returns `%{code: merged, conflicts: [%{category: category, name: name}, ...]}`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  {:ok, :merge_generated, %{code: code, conflicts: []}} =
+    Parser.merge_generated(old_template, new_template, File.read!("assets/js/app.js"))
+  ```
+  """
+  def merge_generated(base, generated, user) do
+    call_nif_fn(
+      user,
+      __ENV__.function,
+      &Native.merge_generated_nif(base, generated, &1),
+      :content
+    )
+  end
+
+  @doc """
+  Converts an offset, such as the `start` or `end` of an ESTree node, to its line and column.
+
+  The line is 1-based and the column is 0-based, counted in the unit of the offset. The
+  `encoding: :utf16 | :utf8` option matches the `spans` option of `ast_to_estree/3`, and
+  defaults to `:utf16`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.offset_to_position(js_content, node["start"])
+  # => {:ok, :offset_to_position, %{line: 2, column: 4}}
+  Parser.offset_to_position("/path/to/file.js", 120, :path, encoding: :utf8)
+  ```
+  """
+  def offset_to_position(file_path_or_content, offset, type \\ :content, opts \\ []) do
+    encoding = Keyword.get(opts, :encoding, :utf16)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.offset_to_position_nif(file_content, offset, encoding)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Converts a line and column to an offset, the inverse of `offset_to_position/4`.
+
+  The position is a `%{line: line, column: column}` map or a `{line, column}` tuple. With
+  `encoding: :utf8` the offset is a byte offset, which can be used to split the binary.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.position_to_offset(js_content, %{line: 2, column: 4})
+  # => {:ok, :position_to_offset, 17}
+  {:ok, _, offset} = Parser.position_to_offset(js_content, {2, 4}, :content, encoding: :utf8)
+  binary_part(js_content, 0, offset)
+  ```
+  """
+  def position_to_offset(file_path_or_content, position, type \\ :content, opts \\ [])
+
+  def position_to_offset(file_path_or_content, %{line: line, column: column}, type, opts) do
+    position_to_offset(file_path_or_content, {line, column}, type, opts)
+  end
+
+  def position_to_offset(file_path_or_content, {line, column}, type, opts) do
+    encoding = Keyword.get(opts, :encoding, :utf16)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.position_to_offset_nif(file_content, line, column, encoding)
+      end,
+      type
+    )
+  end
+
+  @doc """
+    Inserts a JavaScript AST at the specified index within an existing AST.
+
+    This function takes either a file path or raw JavaScript content, along with the
+    JavaScript code to be inserted and the target index. It processes the AST and inserts
+    the new code at the specified position.
+
+
+    ```elixir
+    alias IgniterJs.Parsers.Javascript.Parser
+
+    # Insert after index 1
+    Parser.insert_at_index(js_content, "function newFunc() {}", 1)
+
+    # Insert before all code
+    Parser.insert_at_index(js_content, "function newFunc() {}", 0)
+
+    # Insert using a file path
+    Parser.insert_at_index("/path/to/file.js", "function newFunc() {}", 2, :path)
+    ```
+  """
+  def insert_at_index(
+        file_path_or_content,
+        insert_code,
+        index,
+        type \\ :content
+      ) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.insert_ast_at_index_nif(file_content, insert_code, index)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Replaces a JavaScript AST node at the specified index with new JavaScript code.
+
+  This function takes either a file path or raw JavaScript content, along with the
+  JavaScript code that should replace an existing node at the given index. It processes
+  the AST and replaces the target node while preserving the surrounding structure.
+
+  If the `index` is out of bounds, an error is returned.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  # Replace node at index 1
+  Parser.replace_at_index(js_content, "function newFunc() {}", 1)
+
+  # Replace node at index 0 (first item)
+  Parser.replace_at_index(js_content, "function newFunc() {}", 0)
+
+  # Replace using a file path
+  Parser.replace_at_index("/path/to/file.js", "function newFunc() {}", 2, :path)
+
+  # Remove the imports the replaced node was the last user of
+  Parser.replace_at_index(js_content, "function newFunc() {}", 1, :content,
+    prune_unused_imports: true
+  )
+  ```
+
+  Like `remove_objects_from_hooks/4`, it accepts the `:prune_unused_imports` option.
+  """
+  def replace_at_index(
+        file_path_or_content,
+        replace_code,
+        index,
+        type \\ :content,
+        opts \\ []
+      ) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.replace_ast_at_index_nif(
+          file_content,
+          replace_code,
+          index,
+          Keyword.get(opts, :prune_unused_imports, false)
+        )
+      end,
+      type
+    )
+    |> take_pruned_imports()
+  end
+
+  @doc """
+  Detect the initialization calls of common third-party SDKs (Sentry, PostHog, gtag,
+  Stripe, Mixpanel, Amplitude, LogRocket, Datadog, Bugsnag) in the given file or content.
+
+  Each detected call is returned as a map with the SDK name, the callee as written in the
+  source and its arguments, where statically known values are kept and everything else
+  (identifiers, function calls, ...) becomes `nil`. The `"static"` key tells whether all
+  arguments could be read.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.sdk_initializations(js_content)
+  Parser.sdk_initializations("/path/to/file.js", :path)
+  # => {:ok, :sdk_initializations,
+  #     [%{"sdk" => "sentry", "callee" => "Sentry.init", "args" => [%{"dsn" => "..."}], "static" => true}]}
+  ```
+  """
+  def sdk_initializations(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.find_sdk_initializations_from_ast_nif(file_content)
+      end,
+      type
+    )
+    |> case do
+      {:ok, fn_atom, output} -> {:ok, fn_atom, Jason.decode!(output)}
+      error -> error
+    end
+  end
+
+  @doc """
+  Check if the given file or content is probably a minified bundle, e.g.
+  `priv/static/assets/app.js` selected instead of `assets/js/app.js`, and returns boolean.
+  Such a file should not be patched, see `is_probably_minified/2` to guide the user to the
+  source file.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.probably_minified?(js_content)
+  Parser.probably_minified?("/path/to/file.js", :path)
+  ```
+  """
+  def probably_minified?(file_path_or_content, type \\ :content) do
+    match?({:ok, _, %{minified: true}}, is_probably_minified(file_path_or_content, type))
+  end
+
+  @doc """
+  Measure the given file or content to tell whether it is probably minified, from its line
+  lengths, whitespace and character entropy, and from the shape of its syntax tree.
+  It returns a tuple with a report:
+
+    - `:minified` - whether at least two of the signals are found.
+    - `:signals` - the signals found, among `:long_lines`, `:low_whitespace`,
+      `:high_entropy`, `:short_identifiers` (mangled bindings), `:bundle_wrapper` (a
+      single `(() => { ... })()` statement) and `:source_map_comment`.
+    - `:max_line_length`, `:average_line_length`, `:whitespace_ratio`, `:entropy` and
+      `:short_identifier_ratio` - the measures behind the signals.
+    - `:source_map_url` - the URL of the `//# sourceMappingURL=` comment, which leads to
+      the source file, or `nil`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.is_probably_minified("/path/to/priv/static/assets/app.js", :path)
+  # => {:ok, :is_probably_minified,
+  #     %{minified: true, signals: [:long_lines, :low_whitespace, :source_map_comment], ...}}
+  ```
+  """
+  def is_probably_minified(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.is_probably_minified_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Tell what the given file or content is for, so a task going over `assets/**/*.js` can pick
+  the transform of each file. It returns a tuple with a map of the `:role` and the
+  `:config_tool`:
+
+    - `:vendor` - a third-party file, minified or carrying a license banner.
+    - `:app_entry` - the entry point creating the LiveSocket, usually `app.js`.
+    - `:hooks_index` - an aggregator exporting the hooks it imports.
+    - `:config` - the configuration of a build tool, with the `:config_tool` (`:tailwind`,
+      `:postcss` or `:vite`) told from the file name or from the exported keys.
+    - `:unknown` - anything else.
+
+  ## Options
+
+    - `file_name` - the name of the file for the `:content` type, e.g.
+      `"tailwind.config.js"`. The path is used for the `:path` type.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.file_role("/path/to/assets/js/app.js", :path)
+  # => {:ok, :file_role, %{role: :app_entry, config_tool: nil}}
+  Parser.file_role(js_content, :content, file_name: "postcss.config.js")
+  ```
+  """
+  def file_role(file_path_or_content, type \\ :content, opts \\ []) do
+    file_name = Keyword.get(opts, :file_name, if(type == :path, do: file_path_or_content))
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.detect_file_role_nif(file_content, file_name) end,
+      type
+    )
+  end
+
+  @doc """
+  List the managed regions of the given file or content, i.e. the parts delimited by
+  `// <igniter:name>` and `// </igniter:name>` marker lines, and returns tuple. Each region is
+  a map with its `:name`, the `:content` between the markers, the byte offsets `:start` and
+  `:end` of the region, markers included, and the `:line` of its opening marker.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.list_managed_regions(js_content)
+  Parser.list_managed_regions("/path/to/file.js", :path)
+  ```
+
+  Unbalanced, nested or duplicate markers are an error.
+  """
+  def list_managed_regions(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.list_managed_regions_nif(file_content)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Find the managed region of the given name in the given file or content, see
+  `list_managed_regions/2`, and returns tuple. The result is `nil` when there is none.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.find_managed_region(js_content, "hooks")
+  # => {:ok, :find_managed_region, %{name: "hooks", content: "...", line: 4, ...}}
+  ```
+  """
+  def find_managed_region(file_path_or_content, name, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.find_managed_region_nif(file_content, to_string(name))
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Create or rewrite the managed region of the given name in the given file or content, and
+  returns tuple. Only the lines between the markers are rewritten, so the rest of the file
+  stays as it is written, and the content must parse as JavaScript.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.upsert_managed_region(js_content, "hooks", ~s(import Chart from "./hooks/chart";))
+  ```
+
+  ## Options
+
+    * `:position` - where a missing region is created, like `insert_imports/4`: `:top`,
+      `:after_last_import` (the default) or `{:before, source}`.
+  """
+  def upsert_managed_region(
+        file_path_or_content,
+        name,
+        content,
+        type \\ :content,
+        opts \\ []
+      ) do
+    position = Keyword.get(opts, :position, :after_last_import)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.upsert_managed_region_nif(file_content, to_string(name), content, position)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Run a transform registered by another crate compiled into the NIF (with the `plugins`
+  feature) on the given file or content, with the arguments as a map (or a JSON string).
+  It returns a tuple, an error one when no transform is registered under the name.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.run_named_transform(js_content, "my_ext:add_banner", %{text: "Generated"})
+  Parser.run_named_transform("/path/to/file.js", "my_ext:add_banner", %{}, :path)
+  ```
+  """
+  def run_named_transform(file_path_or_content, name, args \\ %{}, type \\ :content) do
+    json_args = if is_binary(args), do: args, else: Jason.encode!(args)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.run_named_transform_nif(file_content, name, json_args)
+      end,
+      type
+    )
+  end
+
+  @doc """
+  List the names of the transforms registered by other crates, see `run_named_transform/4`.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.Parser
+  Parser.named_transforms()
+  ```
+  """
+  def named_transforms do
+    {:ok, :list_named_transforms_nif, names} =
+      IgniterJs.Helpers.take_metrics(Native.list_named_transforms_nif(), __ENV__.function)
+
+    names
+  end
+
+  defp statement_pattern(statements) when is_list(statements), do: Enum.join(statements, "\n")
+  defp statement_pattern(statement), do: statement
+
+  defp live_socket_option_entries(entries) do
+    entries
+    |> List.wrap()
+    |> Enum.map(fn
+      {name, value} -> "#{entry_key(to_string(name))}: #{value}"
+      name -> name
+    end)
+  end
+
+  defp entry_key(name) do
+    if name =~ ~r/^[A-Za-z_$][A-Za-z0-9_$]*$/, do: name, else: Jason.encode!(name)
+  end
+
+  defp live_socket_target(opts) do
+    case Keyword.get(opts, :socket, "liveSocket") do
+      :all -> nil
+      name -> to_string(name)
+    end
+  end
+
+  defp removal_mode(opts) do
+    if Keyword.get(opts, :comment_out, false), do: :comment_out, else: :delete
+  end
+
+  # With `:prune_unused_imports`, the edits return the imports they pruned along with the code
+  defp take_pruned_imports({:ok, fn_atom, %{code: code, pruned_imports: pruned_imports}}) do
+    {:ok, fn_atom, {code, pruned_imports}}
+  end
+
+  defp take_pruned_imports(output), do: output
+
+  defp estree_options(opts) do
+    %{
+      compact: Keyword.get(opts, :compact, false),
+      spans: Keyword.get(opts, :spans, :utf16),
+      node_types: Keyword.get(opts, :node_types, [])
+    }
+  end
+
+  defp maybe_decode_json(json, opts) do
+    if Keyword.get(opts, :decode, true), do: Jason.decode!(json), else: json
+  end
 end
@@ -0,0 +1,77 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.Javascript.SyntaxTree do
+  @moduledoc """
+  Walk a parsed JavaScript source node by node, for installers that need more control than
+  the functions of `IgniterJs.Parsers.Javascript.Parser`.
+
+  The tree is a reference to a parsed source kept on the native side, so it is parsed once
+  however many nodes are looked at. It is freed when it is garbage collected.
+
+  A node is a map with its `:kind`, the SWC type of the node like `"ImportDecl"`,
+  `"CallExpr"` or `"KeyValueProp"`, its `:start` and `:end` byte offsets in the source and
+  the `:version` of the tree. The nodes are the statements, module declarations, variable
+  declarators, expressions, patterns, object properties and class members.
+
+  Replacing the source of a node makes a new version of the tree, and the nodes of the
+  previous versions are stale: get them again from the root.
+
+  ```elixir
+  alias IgniterJs.Parsers.Javascript.SyntaxTree
+
+  {:ok, :parse, tree} = SyntaxTree.parse("/path/to/app.js", :path)
+  {:ok, :children, [import | _]} = SyntaxTree.children(tree)
+  {:ok, :source, ~s(import "phoenix_html";)} = SyntaxTree.source(tree, import)
+  {:ok, :replace, tree} = SyntaxTree.replace(tree, import, ~s(import "./vendor/html";))
+  {:ok, :to_source, code} = SyntaxTree.to_source(tree)
+  ```
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Parse the given content or file into a tree.
+  """
+  def parse(file_path_or_content, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.parse_to_resource_nif(file_content) end,
+      type
+    )
+  end
+
+  @doc """
+  The nodes directly below a node, in source order. Without a node, the top-level
+  statements and declarations.
+  """
+  def children(tree, node \\ nil) do
+    call_nif_fn(tree, __ENV__.function, &Native.get_node_children_nif(&1, node), :content)
+  end
+
+  @doc """
+  The source of a node, as it is written.
+  """
+  def source(tree, node) do
+    call_nif_fn(tree, __ENV__.function, &Native.get_node_source_nif(&1, node), :content)
+  end
+
+  @doc """
+  Replace the source of a node, leaving the rest of the source as it is written. The tree is
+  left as it was when the new source can not be parsed.
+  """
+  def replace(tree, node, code) do
+    call_nif_fn(tree, __ENV__.function, &Native.replace_node_source_nif(&1, node, code), :content)
+  end
+
+  @doc """
+  Serialize the tree back to JavaScript source.
+  """
+  def to_source(tree) do
+    call_nif_fn(tree, __ENV__.function, &Native.serialize_resource_nif/1, :content)
+  end
+end
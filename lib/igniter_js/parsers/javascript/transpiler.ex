@@ -0,0 +1,107 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.Javascript.Transpiler do
+  @moduledoc """
+  This module lowers modern JavaScript syntax to an older ES version, so assets can
+  support older browsers without a Node toolchain. It interacts with the Native NIF
+  (Native Implemented Functions) for performing the actual transpilation.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4, source_name: 3]
+
+  @doc """
+  Transpiles the provided JavaScript content or file to the given ES target.
+
+  The target is an ES version from `"es2015"` to `"esnext"`. The syntax lowered is:
+
+    - class fields, static class fields and static blocks, below `es2022`
+    - logical assignments (`||=`, `&&=`, `??=`), below `es2021`
+    - optional chaining and nullish coalescing, below `es2020`
+    - object spread, below `es2018`
+    - the exponentiation operator, below `es2016`
+
+  Newer syntax that is not lowered, like private class members, async functions below
+  `es2017` or object rest patterns below `es2018`, returns an error. The type can either be
+  `:content` (default) or `:path`.
+
+  ## Options
+
+    - `source_map` - when `true`, the result is a map with the transpiled `code` and the
+      `source_map`, a JSON string in the source map v3 format. Defaults to `false`.
+    - `source_name` - the name of the source in the source map. Defaults to the file path,
+      or `"input.js"` for content.
+
+  ## Examples
+
+      iex> alias IgniterJs.Parsers.Javascript.Transpiler
+      iex> Transpiler.transpile("const area = side ** 2;", "es2015")
+      {:ok, :transpile, "const area = Math.pow(side, 2);\\n"}
+
+  """
+  def transpile(file_path_or_content, target, type \\ :content, opts \\ []) do
+    source_name = source_name(file_path_or_content, type, opts)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        if Keyword.get(opts, :source_map, false) do
+          Native.transpile_js_with_source_map_nif(file_content, to_string(target), source_name)
+        else
+          Native.transpile_js_nif(file_content, to_string(target))
+        end
+      end,
+      type
+    )
+  end
+
+  @doc """
+  Reports the ECMAScript features the provided JavaScript content or file uses, e.g.
+  `:optional_chaining`, `:top_level_await`, `:dynamic_import` or `:class_fields`, with the
+  ES version each requires and the location of its first use, along with the lowest ES
+  version supporting all of them.
+
+  When a `target` is given, e.g. the esbuild target of the project, `exceeds_target` tells
+  whether the file needs a newer one. The type can either be `:content` (default) or
+  `:path`.
+
+  ## Options
+
+    - `target` - the ES version to check against, e.g. `"es2017"`. Defaults to `nil`.
+
+  ## Examples
+
+      iex> alias IgniterJs.Parsers.Javascript.Transpiler
+      iex> Transpiler.check_compatibility("const port = config?.port;", :content, target: :es2017)
+      {:ok, :check_compatibility,
+       %{
+         features: [
+           %{feature: :block_scoping, target: "es2015", line: 1, column: 0},
+           %{feature: :optional_chaining, target: "es2020", line: 1, column: 13}
+         ],
+         min_target: "es2020",
+         exceeds_target: true
+       }}
+
+  """
+  def check_compatibility(file_path_or_content, type \\ :content, opts \\ []) do
+    target =
+      case Keyword.get(opts, :target) do
+        nil -> nil
+        target -> to_string(target)
+      end
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content ->
+        Native.check_syntax_compatibility_nif(file_content, target)
+      end,
+      type
+    )
+  end
+end
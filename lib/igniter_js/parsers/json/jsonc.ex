@@ -0,0 +1,80 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.JSON.JSONC do
+  @moduledoc """
+  Read and edit JSON with comments and trailing commas, e.g. the `compilerOptions` of
+  `tsconfig.json`. Values are addressed by a path of keys, like
+  `["compilerOptions", "paths"]`, and only the edited values change, so the comments and
+  formatting of the rest of the file are kept.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Get the value at a path of keys, decoded with `Jason`. It is an error when the path is
+  not in the document.
+
+  ```elixir
+  alias IgniterJs.Parsers.JSON.JSONC
+  JSONC.get(tsconfig, ["compilerOptions", "types"])
+  # => {:ok, :get, ["phoenix"]}
+  JSONC.get("/path/to/tsconfig.json", ["include"], :path)
+  ```
+  """
+  def get(file_path_or_content, path, type \\ :content) do
+    file_path_or_content
+    |> call_nif_fn(
+      __ENV__.function,
+      fn file_content -> Native.get_jsonc_value_nif(file_content, path) end,
+      type
+    )
+    |> case do
+      {:ok, fn_atom, value} -> {:ok, fn_atom, Jason.decode!(value)}
+      error -> error
+    end
+  end
+
+  @doc """
+  Set the value at a path of keys, creating the objects missing on the path. The value is
+  encoded with `Jason`, and a new key is added after the last key of its object with the
+  indentation of the file. Setting a value it already has leaves the document untouched.
+
+  ```elixir
+  alias IgniterJs.Parsers.JSON.JSONC
+  JSONC.set(tsconfig, ["compilerOptions", "types"], ["phoenix"])
+  JSONC.set(tsconfig, ["compilerOptions", "paths", "*"], ["../deps/*"])
+  ```
+  """
+  def set(file_path_or_content, path, value, type \\ :content) do
+    value = Jason.encode!(value)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.set_jsonc_value_nif(file_content, path, value) end,
+      type
+    )
+  end
+
+  @doc """
+  Remove the key at a path, with its comma and its line when nothing else is on it. A path
+  that is not in the document leaves it untouched.
+
+  ```elixir
+  alias IgniterJs.Parsers.JSON.JSONC
+  JSONC.remove(tsconfig, ["compilerOptions", "types"])
+  ```
+  """
+  def remove(file_path_or_content, path, type \\ :content) do
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.remove_jsonc_value_nif(file_content, path) end,
+      type
+    )
+  end
+end
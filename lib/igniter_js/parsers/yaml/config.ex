@@ -0,0 +1,92 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Parsers.YAML.Config do
+  @moduledoc """
+  Read and edit the YAML configs of front-end tools, e.g. the `packages` of
+  `pnpm-workspace.yaml` or the `rules` of `.stylelintrc.yaml`. Values are addressed by a
+  path of keys, like `["rules", "color-named"]`, where the key of a sequence is the index of
+  an item, and only the edited values change, so the comments and formatting of the rest of
+  the file are kept.
+
+  The block style of these files is supported, with flow collections like `[a, b]` and
+  block scalars like `|` as values. Anchors, aliases and tags are read as plain text, and a
+  file with several documents is an error.
+  """
+
+  alias IgniterJs.Native
+  import IgniterJs.Helpers, only: [call_nif_fn: 4]
+
+  @doc """
+  Get the value at a path of keys, decoded with `Jason`. It is an error when the path is
+  not in the document.
+
+  ```elixir
+  alias IgniterJs.Parsers.YAML.Config
+  Config.get(workspace, ["packages"])
+  # => {:ok, :get, ["assets", "deps/*"]}
+  Config.get("/path/to/.stylelintrc.yaml", ["rules", "color-named"], :path)
+  ```
+  """
+  def get(file_path_or_content, path, type \\ :content) do
+    file_path_or_content
+    |> call_nif_fn(
+      __ENV__.function,
+      fn file_content -> Native.get_yaml_value_nif(file_content, keys(path)) end,
+      type
+    )
+    |> case do
+      {:ok, fn_atom, value} -> {:ok, fn_atom, Jason.decode!(value)}
+      error -> error
+    end
+  end
+
+  @doc """
+  Set the value at a path of keys, creating the mappings missing on the path. The value is
+  encoded with `Jason` and written in block style with the indentation of the file, and a
+  new key is added after the last key of its mapping. Setting a value it already has leaves
+  the document untouched.
+
+  ```elixir
+  alias IgniterJs.Parsers.YAML.Config
+  Config.set(stylelintrc, ["rules", "color-named"], "never")
+  Config.set(stylelintrc, ["overrides", 0, "customSyntax"], "postcss-scss")
+  ```
+  """
+  def set(file_path_or_content, path, value, type \\ :content) do
+    value = Jason.encode!(value)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.set_yaml_value_nif(file_content, keys(path), value) end,
+      type
+    )
+  end
+
+  @doc """
+  Append a value to the sequence at a path of keys, creating it when it is missing. A value
+  the sequence already has leaves the document untouched, and a new string is quoted like
+  the last item of the sequence.
+
+  ```elixir
+  alias IgniterJs.Parsers.YAML.Config
+  Config.append(workspace, ["packages"], "deps/*")
+  Config.append("/path/to/pnpm-workspace.yaml", ["onlyBuiltDependencies"], "esbuild", :path)
+  ```
+  """
+  def append(file_path_or_content, path, value, type \\ :content) do
+    value = Jason.encode!(value)
+
+    call_nif_fn(
+      file_path_or_content,
+      __ENV__.function,
+      fn file_content -> Native.append_yaml_value_nif(file_content, keys(path), value) end,
+      type
+    )
+  end
+
+  defp keys(path), do: Enum.map(path, &to_string/1)
+end
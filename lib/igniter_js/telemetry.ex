@@ -0,0 +1,71 @@
+# SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJs.Telemetry do
+  @moduledoc """
+  Timings of the NIF calls, to see where the time goes when patching many assets.
+
+  The metrics are off by default. They are turned on with `enable/0`, or at startup with:
+
+  ```elixir
+  config :igniter_js, telemetry: true
+  ```
+
+  Each NIF call then emits a `[:igniter_js, :nif, :stop]` event, when the `:telemetry`
+  library is available, with the measurements, in microseconds except `:input_size`:
+
+    - `:parse_time` - parsing the sources, JavaScript or CSS.
+    - `:transform_time` - the rest of the call after the first parse, e.g. visiting the
+      syntax tree.
+    - `:codegen_time` - emitting or printing the code.
+    - `:input_size` - the size in bytes of the first source parsed, i.e. the file content
+      for most functions.
+
+  The metadata is `%{function: function, status: :ok | :error}`, where `function` is the
+  name of the called function, e.g. `:extend_hook_object`.
+
+  ```elixir
+  :telemetry.attach(
+    "igniter-js-timings",
+    [:igniter_js, :nif, :stop],
+    fn _event, measurements, metadata, _config ->
+      IO.inspect({metadata.function, measurements})
+    end,
+    nil
+  )
+  ```
+  """
+
+  alias IgniterJs.Native
+
+  @event [:igniter_js, :nif, :stop]
+
+  @doc """
+  Turns the metrics on for every NIF call.
+  """
+  def enable do
+    {:ok, :set_telemetry_nif, true, _metrics} = Native.set_telemetry_nif(true)
+    :ok
+  end
+
+  @doc """
+  Turns the metrics off.
+  """
+  def disable do
+    {:ok, :set_telemetry_nif, false} = Native.set_telemetry_nif(false)
+    :ok
+  end
+
+  @doc """
+  Emits the event of a NIF call with its metrics, if the `:telemetry` library is available.
+  """
+  def emit(metrics, function, status) do
+    if Code.ensure_loaded?(:telemetry) do
+      apply(:telemetry, :execute, [@event, metrics, %{function: function, status: status}])
+    end
+
+    :ok
+  end
+end
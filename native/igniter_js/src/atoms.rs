@@ -9,6 +9,10 @@ rustler::atoms! {
 
     // Error Atoms
     error,
+    unsupported_dialect,
+    unsupported_options_shape,
+    too_large,
+    timeout,
 
     // Nif Functions Atoms
     source_to_ast_nif,
@@ -28,5 +32,115 @@ rustler::atoms! {
     convert_ast_to_estree_nif,
     insert_ast_at_index_nif,
     replace_ast_at_index_nif,
+    apply_asset_patch_nif,
+    find_sdk_initializations_from_ast_nif,
+    ensure_colocated_hooks_to_ast_nif,
+    list_hooks_from_ast_nif,
+    extend_live_socket_params_to_ast_nif,
+    wrap_live_socket_hooks_to_ast_nif,
+    find_html_script_blocks_nif,
+    patch_html_script_blocks_nif,
+    find_variable_from_ast_nif,
+    merge_object_literal_nif,
+    extend_object_property_by_names_to_ast_nif,
+    insert_tailwind_directive_nif,
+    remove_tailwind_directive_nif,
+    put_tailwind_theme_variables_nif,
+    remove_tailwind_theme_variables_nif,
+    parse_document_nif,
+    query_document_nif,
+    apply_document_nif,
+    document_to_source_nif,
+    convert_ast_to_estree_statements_nif,
+    estree_to_source_nif,
+    minify_js_nif,
+    minify_css_nif,
+    autoprefix_css_nif,
+    transpile_js_nif,
+    transpile_js_with_source_map_nif,
+    minify_js_with_source_map_nif,
+    format_js_with_source_map_nif,
+    analyze_imports_nif,
+    extend_live_socket_option_to_ast_nif,
+    remove_live_socket_option_entries_from_ast_nif,
+    has_live_socket_connect_nif,
+    has_topbar_config_nif,
+    ensure_live_socket_boilerplate_nif,
+    rename_identifier_nif,
+    analyze_unused_nif,
+    check_js_format_nif,
+    check_css_format_nif,
+    validate_js_nif,
+    validate_css_nif,
+    ensure_alpine_import_nif,
+    register_alpine_plugin_nif,
+    add_alpine_data_nif,
+    ensure_alpine_start_nif,
+    compose_on_before_el_updated_to_ast_nif,
+    list_live_sockets_from_ast_nif,
+    extend_live_socket_option_with_log_nif,
+    remove_live_socket_option_entries_with_log_nif,
+    insert_import_at_position_to_ast_nif,
+    add_export_to_ast_nif,
+    remove_export_from_ast_nif,
+    generate_hooks_index_nif,
+    query_and_patch_nif,
+    put_css_custom_properties_nif,
+    rename_css_selector_nif,
+    move_css_rule_nif,
+    get_css_declarations_nif,
+    has_css_rule_nif,
+    apply_codemod_nif,
+    parse_to_resource_nif,
+    get_node_children_nif,
+    get_node_source_nif,
+    replace_node_source_nif,
+    serialize_resource_nif,
+    apply_twice_equals_once_nif,
+    format_js_range_nif,
+    check_syntax_compatibility_nif,
+    get_jsonc_value_nif,
+    set_jsonc_value_nif,
+    remove_jsonc_value_nif,
+    offset_to_position_nif,
+    position_to_offset_nif,
+    is_dynamically_imported_nif,
+    insert_dynamic_import_to_ast_nif,
+    ensure_service_worker_registration_nif,
+    remove_service_worker_registration_nif,
+    remove_var_object_properties_nif,
+    ast_diff_nif,
+    merge_generated_nif,
+    insert_css_rule_in_media_nif,
+    has_live_socket_exposure_nif,
+    ensure_live_socket_exposed_nif,
+    remove_live_socket_exposure_nif,
+    wrap_in_env_guard_nif,
+    remove_env_guard_nif,
+    set_telemetry_nif,
+    set_limits_nif,
+    is_probably_minified_nif,
+    parse_with_engine_nif,
+    codegen_with_engine_nif,
+    get_yaml_value_nif,
+    set_yaml_value_nif,
+    append_yaml_value_nif,
+    list_managed_regions_nif,
+    find_managed_region_nif,
+    upsert_managed_region_nif,
+    get_variable_initializer_nif,
+    install_integration_nif,
+    find_tailwind_layers_nif,
+    insert_tailwind_layer_directive_nif,
+    insert_tailwind_layer_rule_nif,
+    merge_topbar_config_nif,
+    run_named_transform_nif,
+    list_named_transforms_nif,
+    list_top_level_bindings_nif,
+    extract_code_block_nif,
+    move_declaration_between_sources_nif,
+    detect_file_role_nif,
+    replace_import_nif,
+    organize_imports_nif,
     // Resource Atoms
 }
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The transforms of the crate as a command line tool, for the projects that can not load
+//! the NIF, e.g. a locked-down CI. The source is read from stdin and the result written to
+//! stdout, so it can be run with `System.cmd/3`:
+//!
+//! ```sh
+//! igniter_js_cli insert_import_to_ast '{"import_lines": "import { Socket } from \"phoenix\";"}' < app.js
+//! igniter_js_cli list
+//! ```
+//!
+//! The transforms and their arguments are the ones of `igniter_js::core`. An updated source
+//! is written as is, the result of a query as JSON. On an error the message is written to
+//! stderr and the exit status is 1, or 2 when the command is invalid.
+
+use igniter_js::core::{run_transform, TRANSFORMS};
+use igniter_js::plugins::list_named_transforms;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "Usage: igniter_js_cli <transform> [json_args] < source\n       igniter_js_cli list";
+
+fn fail(message: &str, code: u8) -> ExitCode {
+    eprintln!("{}", message);
+    ExitCode::from(code)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (transform, json_args) = match args.as_slice() {
+        [command] if command == "list" => {
+            for name in TRANSFORMS
+                .iter()
+                .copied()
+                .map(String::from)
+                .chain(list_named_transforms())
+            {
+                println!("{}", name);
+            }
+            return ExitCode::SUCCESS;
+        }
+        [transform] => (transform, None),
+        [transform, json_args] => (transform, Some(json_args)),
+        _ => return fail(USAGE, 2),
+    };
+
+    let args: Value = match json_args.map(|json_args| serde_json::from_str(json_args)) {
+        None => Value::Null,
+        Some(Ok(args)) => args,
+        Some(Err(error)) => return fail(&format!("Invalid arguments: {}", error), 2),
+    };
+    let mut source = String::new();
+    if let Err(error) = std::io::stdin().read_to_string(&mut source) {
+        return fail(&format!("Failed to read the source: {}", error), 1);
+    }
+
+    let output = match run_transform(transform, &source, &args) {
+        Ok(Value::String(updated)) => updated,
+        Ok(result) => format!("{}\n", result),
+        Err(error) => return fail(&error, 1),
+    };
+    match std::io::stdout().write_all(output.as_bytes()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => fail(&format!("Failed to write the result: {}", error), 1),
+    }
+}
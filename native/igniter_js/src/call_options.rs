@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The options of a single call, which `IgniterJs.Native` sends along with the arguments of
+//! every NIF, from the process making the call, e.g. the timeout of
+//! `IgniterJs.Limits.with_timeout/2` or the options of `IgniterJs.ParserOptions` and
+//! `IgniterJs.CodegenOptions`.
+//!
+//! Nothing outlives the call: its options are set on the thread running it when it starts,
+//! and replaced by the ones of the next call.
+
+use crate::limits;
+#[cfg(feature = "nif")]
+use crate::limits::CALL_STACK_SIZE;
+use crate::parser_options::{set_parser_options, ParserOptions};
+use crate::parsers::javascript::code_style::{set_codegen_options, CodegenOptions};
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+
+/// The options of a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct CallOptions {
+    /// The maximum time of the call in milliseconds, or `None` for the timeout of the
+    /// limits, see [`limits::start_call`].
+    pub timeout: Option<u64>,
+    /// Whether decorators are parsed, see [`ParserOptions::decorators`].
+    pub decorators: bool,
+    /// Whether the generated code follows the quotes and semicolons of the source, see
+    /// [`CodegenOptions::preserve_code_style`].
+    pub preserve_code_style: bool,
+}
+
+impl CallOptions {
+    /// Starts a call with these options on the current thread.
+    pub fn start(&self) {
+        limits::start_call(self.timeout);
+        set_parser_options(ParserOptions {
+            decorators: self.decorators,
+        });
+        set_codegen_options(CodegenOptions {
+            preserve_code_style: self.preserve_code_style,
+        });
+    }
+}
+
+/// Runs a NIF call with its options, on a stack of [`CALL_STACK_SIZE`].
+#[cfg(feature = "nif")]
+pub fn run_call<T>(call_options: CallOptions, call: impl FnOnce() -> T) -> T {
+    call_options.start();
+    stacker::grow(CALL_STACK_SIZE, call)
+}
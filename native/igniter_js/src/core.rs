@@ -0,0 +1,856 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The transforms of the crate called by name with JSON arguments, for the hosts that are
+//! not the BEAM, e.g. the WASM build of `src/wasm.rs`.
+//!
+//! The names are the ones of the NIFs without their `_nif` suffix, and the arguments are
+//! the ones of the NIFs, by name, with the options given by the names of their atoms, e.g.
+//! `"comment_out"`. A transform returns the updated source as a JSON string, a query its
+//! result as JSON, e.g. the diagnostics of `validate_js`. A name that is not built in is
+//! looked up in the registered transforms, see [`crate::plugins`].
+//!
+//! The transforms are the edits of one source with plain arguments and a few queries of
+//! it. The NIFs over parsed documents and trees, returning source maps or positions, taking
+//! several sources or structured arguments, e.g. an import position or a query spec, and
+//! the settings of the NIFs are only called from Elixir.
+//!
+//! ```rust
+//! let args = serde_json::json!({ "import_lines": "import { Socket } from \"phoenix\";" });
+//! let result = run_transform("insert_import_to_ast", "let a = 1;", &args).unwrap();
+//! ```
+
+use crate::call_options::CallOptions;
+use crate::diagnostics::{Diagnostic, DiagnosticSeverity};
+use crate::limits;
+use crate::parsers::css::declarations::{get_declarations, has_rule};
+use crate::parsers::css::formatter as css_formatter;
+use crate::parsers::css::minify::minify_css;
+use crate::parsers::css::selectors::{
+    insert_rule_in_media, move_rule, rename_selector, RulePosition,
+};
+use crate::parsers::css::tailwind::{self, Directive, TailwindLayer};
+use crate::parsers::css::validate::validate_css;
+use crate::parsers::css::variables::{put_custom_properties, ROOT_SELECTOR};
+use crate::parsers::javascript::alpine::{
+    add_alpine_data_to_ast, ensure_alpine_import_to_ast, ensure_alpine_start_to_ast,
+    register_alpine_plugin_to_ast,
+};
+use crate::parsers::javascript::ast::{
+    extend_var_object_property_by_names_to_ast, insert_import_to_ast, remove_import_from_ast,
+    remove_var_object_properties_from_ast, replace_import_to_ast,
+};
+use crate::parsers::javascript::codemod::apply_codemod;
+use crate::parsers::javascript::engine::Engine;
+use crate::parsers::javascript::env_guard::remove_env_guard_from_ast;
+use crate::parsers::javascript::exports::{add_export_to_ast, remove_export_from_ast};
+use crate::parsers::javascript::formatter::{format_with_template_literals, TemplateLiterals};
+use crate::parsers::javascript::helpers::ModuleKind;
+use crate::parsers::javascript::hooks_index::generate_hooks_index;
+use crate::parsers::javascript::minify::{minify_js, MinifyOptions};
+use crate::parsers::javascript::object::merge_object_literal_to_ast;
+use crate::parsers::javascript::organize::organize_imports;
+use crate::parsers::javascript::phoenix::{
+    compose_on_before_el_updated_to_ast, ensure_colocated_hooks_to_ast,
+    ensure_live_socket_boilerplate_to_ast, ensure_live_socket_exposed_to_ast,
+    extend_hook_object_in_order_to_ast, extend_live_socket_option_for_target_to_ast,
+    extend_live_socket_params_to_ast, merge_topbar_config_to_ast,
+    remove_live_socket_exposure_from_ast, remove_live_socket_option_entries_with_log_from_ast,
+    wrap_live_socket_hooks_to_ast, EntryOrder, KeyPosition, LiveSocketOption, LiveSocketTarget,
+};
+use crate::parsers::javascript::relocate::move_declaration;
+use crate::parsers::javascript::removal::RemovalMode;
+use crate::parsers::javascript::rename::rename_identifier;
+use crate::parsers::javascript::service_worker::{
+    ensure_service_worker_registration_to_ast, remove_service_worker_registration_from_ast,
+};
+use crate::parsers::javascript::transpile::transpile_js;
+use crate::parsers::javascript::validate::validate_js_with_engine;
+use crate::parsers::json::jsonc;
+use crate::parsers::yaml::config as yaml;
+use crate::plugins::run_named_transform;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// The built-in transforms, sorted.
+pub const TRANSFORMS: &[&str] = &[
+    "add_alpine_data",
+    "add_export_to_ast",
+    "append_yaml_value",
+    "apply_codemod",
+    "compose_on_before_el_updated_to_ast",
+    "ensure_alpine_import",
+    "ensure_alpine_start",
+    "ensure_colocated_hooks_to_ast",
+    "ensure_live_socket_boilerplate",
+    "ensure_live_socket_exposed",
+    "ensure_service_worker_registration",
+    "extend_hook_object_to_ast",
+    "extend_live_socket_option_to_ast",
+    "extend_live_socket_params_to_ast",
+    "extend_var_object_property_by_names_to_ast",
+    "find_tailwind_layers",
+    "format_css",
+    "format_js",
+    "generate_hooks_index",
+    "get_css_declarations",
+    "get_jsonc_value",
+    "get_yaml_value",
+    "has_css_rule",
+    "insert_css_rule_in_media",
+    "insert_import_to_ast",
+    "insert_tailwind_directive",
+    "insert_tailwind_layer_directive",
+    "insert_tailwind_layer_rule",
+    "merge_object_literal",
+    "merge_topbar_config",
+    "minify_css",
+    "minify_js",
+    "move_css_rule",
+    "move_declaration_between_sources",
+    "organize_imports",
+    "put_css_custom_properties",
+    "put_tailwind_theme_variables",
+    "register_alpine_plugin",
+    "remove_env_guard",
+    "remove_export_from_ast",
+    "remove_import_from_ast",
+    "remove_jsonc_value",
+    "remove_live_socket_exposure",
+    "remove_live_socket_option_entries_from_ast",
+    "remove_objects_of_hooks_from_ast",
+    "remove_service_worker_registration",
+    "remove_tailwind_directive",
+    "remove_tailwind_theme_variables",
+    "remove_var_object_properties",
+    "rename_css_selector",
+    "rename_identifier",
+    "replace_import",
+    "set_jsonc_value",
+    "set_yaml_value",
+    "transpile_js",
+    "validate_css",
+    "validate_js",
+    "wrap_live_socket_hooks_to_ast",
+];
+
+/// The arguments of a transform, a JSON object.
+struct Args<'a>(&'a Value);
+
+impl<'a> Args<'a> {
+    fn get(&self, name: &str) -> Option<&'a Value> {
+        self.0.get(name).filter(|value| !value.is_null())
+    }
+
+    fn required(&self, name: &str) -> Result<&'a Value, String> {
+        self.get(name)
+            .ok_or_else(|| format!("The `{}` argument is required", name))
+    }
+
+    fn invalid(name: &str, expected: &str) -> String {
+        format!("The `{}` argument must be {}", name, expected)
+    }
+
+    fn str(&self, name: &str) -> Result<&'a str, String> {
+        self.required(name)?
+            .as_str()
+            .ok_or_else(|| Self::invalid(name, "a string"))
+    }
+
+    fn optional_str(&self, name: &str) -> Result<Option<&'a str>, String> {
+        match self.get(name) {
+            Some(_) => self.str(name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn strs(&self, name: &str) -> Result<Vec<&'a str>, String> {
+        self.required(name)?
+            .as_array()
+            .and_then(|values| values.iter().map(Value::as_str).collect())
+            .ok_or_else(|| Self::invalid(name, "a list of strings"))
+    }
+
+    fn optional_u64(&self, name: &str) -> Result<Option<u64>, String> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .as_u64()
+                    .ok_or_else(|| Self::invalid(name, "a non-negative integer"))
+            })
+            .transpose()
+    }
+
+    fn bool(&self, name: &str, default: bool) -> Result<bool, String> {
+        match self.get(name) {
+            Some(value) => value
+                .as_bool()
+                .ok_or_else(|| Self::invalid(name, "a boolean")),
+            None => Ok(default),
+        }
+    }
+
+    /// A JSON value given as is, e.g. the value to set in a JSONC document.
+    fn json(&self, name: &str) -> Result<String, String> {
+        self.required(name).map(Value::to_string)
+    }
+
+    /// A list of `[name, value]` pairs, or an object.
+    fn pairs(&self, name: &str) -> Result<Vec<(&'a str, &'a str)>, String> {
+        let invalid = || Self::invalid(name, "a list of [name, value] pairs");
+        match self.required(name)? {
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, value)| Some((key.as_str(), value.as_str()?)))
+                .collect::<Option<_>>()
+                .ok_or_else(invalid),
+            Value::Array(pairs) => pairs
+                .iter()
+                .map(|pair| match pair.as_array()?.as_slice() {
+                    [key, value] => Some((key.as_str()?, value.as_str()?)),
+                    _ => None,
+                })
+                .collect::<Option<_>>()
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// An option given by the name of its variant, e.g. `"comment_out"`.
+    fn option<T: Copy>(&self, name: &str, variants: &[(&str, T)], default: T) -> Result<T, String> {
+        let Some(value) = self.get(name) else {
+            return Ok(default);
+        };
+        value
+            .as_str()
+            .and_then(|value| variants.iter().find(|(variant, _)| *variant == value))
+            .map(|(_, variant)| *variant)
+            .ok_or_else(|| {
+                let names: Vec<_> = variants.iter().map(|(variant, _)| *variant).collect();
+                Self::invalid(name, &format!("one of {}", names.join(", ")))
+            })
+    }
+}
+
+fn diagnostics_json(diagnostics: &[Diagnostic]) -> Value {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let severity = match diagnostic.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+                DiagnosticSeverity::Advice => "advice",
+            };
+            json!({
+                "message": diagnostic.message,
+                "severity": severity,
+                "help": diagnostic.help,
+                "start": diagnostic.start,
+                "end": diagnostic.end,
+                "line": diagnostic.line,
+                "column": diagnostic.column,
+                "code_frame": diagnostic.code_frame,
+            })
+        })
+        .collect()
+}
+
+/// The value of a path of a document, as the `get_*_value` NIFs return it.
+fn found(value: Result<Option<String>, String>) -> Result<Value, String> {
+    value?
+        .map(Value::String)
+        .ok_or_else(|| "The path was not found".to_string())
+}
+
+/// The source, or an error when it has a syntax error, as the `validate_*` NIFs report it.
+fn validated(diagnostics: Vec<Diagnostic>) -> Result<Value, String> {
+    let json = diagnostics_json(&diagnostics);
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+    {
+        Err(json.to_string())
+    } else {
+        Ok(json)
+    }
+}
+
+/// The `mode` of a removal, `"delete"` by default.
+fn removal_mode(args: &Args) -> Result<RemovalMode, String> {
+    args.option(
+        "mode",
+        &[
+            ("delete", RemovalMode::Delete),
+            ("comment_out", RemovalMode::CommentOut),
+        ],
+        RemovalMode::default(),
+    )
+}
+
+/// The LiveSocket option of an edit, e.g. `"hooks"`, and the LiveSocket it edits: the
+/// `target` variable, or every LiveSocket when it is not given, as in the NIFs.
+fn live_socket_option<'a>(args: &Args<'a>) -> Result<(&'static str, LiveSocketTarget<'a>), String> {
+    args.required("option")?;
+    let option = args.option(
+        "option",
+        &[
+            ("hooks", LiveSocketOption::Hooks),
+            ("uploaders", LiveSocketOption::Uploaders),
+            ("dom", LiveSocketOption::Dom),
+            ("metadata", LiveSocketOption::Metadata),
+        ],
+        LiveSocketOption::Hooks,
+    )?;
+    let target = match args.optional_str("target")? {
+        Some(name) => LiveSocketTarget::Named(name),
+        None => LiveSocketTarget::All,
+    };
+    Ok((option.key(), target))
+}
+
+fn tailwind_layer(args: &Args) -> Result<TailwindLayer, String> {
+    args.option(
+        "layer",
+        &[
+            ("base", TailwindLayer::Base),
+            ("components", TailwindLayer::Components),
+            ("utilities", TailwindLayer::Utilities),
+        ],
+        TailwindLayer::Base,
+    )
+}
+
+fn tailwind_directive(args: &Args) -> Result<Directive, String> {
+    args.option(
+        "kind",
+        &[
+            ("import", Directive::Import),
+            ("source", Directive::Source),
+            ("plugin", Directive::Plugin),
+        ],
+        Directive::Import,
+    )
+}
+
+/// Runs the transform of the given name on `source`.
+///
+/// # Arguments
+/// * `name` - The name of a built-in transform, see [`TRANSFORMS`], or of a registered one.
+/// * `source` - The source to transform.
+/// * `args` - The arguments of the transform, a JSON object, or `null` when it has none.
+///   Its `timeout` in milliseconds limits the time of the call, like the timeout of
+///   [`crate::limits`], and its `decorators` and `preserve_code_style` are the parser and
+///   codegen options of the call, see [`crate::call_options::CallOptions`].
+///
+/// # Returns
+/// * `Ok(Value)` - The updated source as a JSON string, or the result of a query.
+/// * `Err(String)` - If the transform is unknown, an argument is missing or invalid, or
+///   the transform fails. The diagnostics of a failed validation are given as JSON.
+pub fn run_transform(name: &str, source: &str, args: &Value) -> Result<Value, String> {
+    let args = Args(args);
+    CallOptions {
+        timeout: args.optional_u64("timeout")?,
+        decorators: args.bool("decorators", true)?,
+        preserve_code_style: args.bool("preserve_code_style", false)?,
+    }
+    .start();
+    let result = run(name, source, args);
+    // The limits a call exceeded are reported by its error, as the NIFs do when they respond
+    limits::take_exceeded();
+    limits::take_timed_out();
+    result
+}
+
+/// Runs a JSON request of a transform, e.g.
+/// `{"transform": "format_css", "source": "a{}", "args": {}}`, as the WASM build receives
+/// it, see `src/wasm.rs`.
+///
+/// # Returns
+/// `{"status": "ok", "result": ...}` with the result of [`run_transform`], or
+/// `{"status": "error", "error": "..."}`.
+pub fn run_request(request: &[u8]) -> Value {
+    let request: Value = match serde_json::from_slice(request) {
+        Ok(request) => request,
+        Err(error) => {
+            return json!({ "status": "error", "error": format!("Invalid request: {}", error) })
+        }
+    };
+    let (Some(transform), Some(source)) =
+        (request["transform"].as_str(), request["source"].as_str())
+    else {
+        return json!({
+            "status": "error",
+            "error": "The request must have a `transform` and a `source`",
+        });
+    };
+
+    match run_transform(transform, source, &request["args"]) {
+        Ok(result) => json!({ "status": "ok", "result": result }),
+        Err(error) => json!({ "status": "error", "error": error }),
+    }
+}
+
+fn run(name: &str, source: &str, args: Args) -> Result<Value, String> {
+    let updated = |result: Result<String, String>| result.map(Value::String);
+
+    match name {
+        "insert_import_to_ast" => updated(insert_import_to_ast(source, args.str("import_lines")?)),
+        "remove_import_from_ast" => updated(remove_import_from_ast(
+            source,
+            args.str("modules")?,
+            removal_mode(&args)?,
+        )),
+        "replace_import" => updated(replace_import_to_ast(
+            source,
+            args.str("source")?,
+            args.str("import_lines")?,
+        )),
+        "add_export_to_ast" => updated(add_export_to_ast(source, args.str("export_lines")?)),
+        "remove_export_from_ast" => {
+            updated(remove_export_from_ast(source, args.str("export_lines")?))
+        }
+        "extend_hook_object_to_ast" => {
+            let names: BTreeSet<&str> = args.strs("names")?.into_iter().collect();
+            let order = args.option(
+                "order",
+                &[
+                    ("append", EntryOrder::Append),
+                    ("sorted", EntryOrder::Sorted),
+                ],
+                EntryOrder::default(),
+            )?;
+            let key_position = args.option(
+                "key_position",
+                &[
+                    ("end", KeyPosition::End),
+                    ("after_params", KeyPosition::AfterParams),
+                    ("first", KeyPosition::First),
+                ],
+                KeyPosition::default(),
+            )?;
+            updated(extend_hook_object_in_order_to_ast(
+                source,
+                names.into_iter().collect(),
+                order,
+                key_position,
+            ))
+        }
+        "extend_live_socket_option_to_ast" => {
+            let (option, target) = live_socket_option(&args)?;
+            updated(extend_live_socket_option_for_target_to_ast(
+                source,
+                target,
+                option,
+                args.strs("entries")?,
+            ))
+        }
+        "remove_live_socket_option_entries_from_ast" => {
+            let (option, target) = live_socket_option(&args)?;
+            updated(
+                remove_live_socket_option_entries_with_log_from_ast(
+                    source,
+                    target,
+                    option,
+                    args.strs("entries")?,
+                    removal_mode(&args)?,
+                    false,
+                )
+                .map(|log| log.code),
+            )
+        }
+        "remove_objects_of_hooks_from_ast" => updated(
+            remove_live_socket_option_entries_with_log_from_ast(
+                source,
+                LiveSocketTarget::Named("liveSocket"),
+                "hooks",
+                args.strs("object_names")?,
+                removal_mode(&args)?,
+                args.bool("prune_unused_imports", false)?,
+            )
+            .map(|log| log.code),
+        ),
+        "extend_live_socket_params_to_ast" => updated(extend_live_socket_params_to_ast(
+            source,
+            args.pairs("params")?,
+        )),
+        "wrap_live_socket_hooks_to_ast" => updated(wrap_live_socket_hooks_to_ast(
+            source,
+            args.str("callee")?,
+            args.strs("args")?,
+        )),
+        "compose_on_before_el_updated_to_ast" => updated(compose_on_before_el_updated_to_ast(
+            source,
+            args.str("snippet")?,
+        )),
+        "ensure_colocated_hooks_to_ast" => {
+            updated(ensure_colocated_hooks_to_ast(source, args.str("app_name")?))
+        }
+        "ensure_live_socket_boilerplate" => updated(ensure_live_socket_boilerplate_to_ast(source)),
+        "ensure_live_socket_exposed" => updated(ensure_live_socket_exposed_to_ast(source)),
+        "remove_live_socket_exposure" => updated(remove_live_socket_exposure_from_ast(
+            source,
+            removal_mode(&args)?,
+        )),
+        "merge_topbar_config" => updated(merge_topbar_config_to_ast(
+            source,
+            &args.json("json_spec")?,
+            args.bool("overwrite", false)?,
+        )),
+        "generate_hooks_index" => {
+            let file_names: Vec<String> = args
+                .strs("file_names")?
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            updated(generate_hooks_index(source, &file_names))
+        }
+        "extend_var_object_property_by_names_to_ast" => {
+            updated(extend_var_object_property_by_names_to_ast(
+                source,
+                args.str("var_name")?,
+                args.strs("object_names")?,
+            ))
+        }
+        "merge_object_literal" => updated(merge_object_literal_to_ast(
+            source,
+            args.str("var_name")?,
+            &args.json("json_spec")?,
+            args.bool("overwrite", false)?,
+        )),
+        "apply_codemod" => {
+            let result = apply_codemod(source, args.str("pattern")?, args.str("replacement")?)?;
+            Ok(json!({ "code": result.code, "replacements": result.replacements }))
+        }
+        "remove_var_object_properties" => updated(remove_var_object_properties_from_ast(
+            source,
+            args.str("var_name")?,
+            args.strs("keys")?,
+        )),
+        "remove_env_guard" => updated(remove_env_guard_from_ast(source, args.str("pattern")?)),
+        "ensure_service_worker_registration" => updated(ensure_service_worker_registration_to_ast(
+            source,
+            args.str("path")?,
+            args.optional_str("options")?,
+        )),
+        "remove_service_worker_registration" => updated(
+            remove_service_worker_registration_from_ast(source, args.optional_str("path")?),
+        ),
+        "ensure_alpine_import" => updated(ensure_alpine_import_to_ast(source)),
+        "register_alpine_plugin" => updated(register_alpine_plugin_to_ast(
+            source,
+            args.str("plugin")?,
+            args.optional_str("source")?,
+        )),
+        "add_alpine_data" => updated(add_alpine_data_to_ast(
+            source,
+            args.str("name")?,
+            args.str("value")?,
+        )),
+        "ensure_alpine_start" => updated(ensure_alpine_start_to_ast(source)),
+        "rename_identifier" => updated(rename_identifier(
+            source,
+            args.str("old_name")?,
+            args.str("new_name")?,
+        )),
+        "organize_imports" => updated(organize_imports(
+            source,
+            args.bool("separate_groups", false)?,
+        )),
+        "move_declaration_between_sources" => {
+            let moved = move_declaration(
+                source,
+                args.str("target")?,
+                args.str("name")?,
+                args.optional_str("import_from")?,
+            )?;
+            Ok(json!({ "source": moved.source, "target": moved.target }))
+        }
+        "format_js" => {
+            let module_kind = args.option(
+                "module_kind",
+                &[
+                    ("auto", ModuleKind::Auto),
+                    ("module", ModuleKind::Module),
+                    ("script", ModuleKind::Script),
+                ],
+                ModuleKind::Auto,
+            )?;
+            let template_literals = args.option(
+                "template_literals",
+                &[
+                    ("format", TemplateLiterals::Format),
+                    ("preserve", TemplateLiterals::Preserve),
+                    ("embedded", TemplateLiterals::Embedded),
+                ],
+                TemplateLiterals::default(),
+            )?;
+            updated(format_with_template_literals(
+                source,
+                module_kind,
+                template_literals,
+            ))
+        }
+        "transpile_js" => updated(transpile_js(source, args.str("target")?)),
+        "minify_js" => {
+            let defaults = MinifyOptions::default();
+            let options = MinifyOptions {
+                mangle: args.bool("mangle", defaults.mangle)?,
+                compress: args.bool("compress", defaults.compress)?,
+                level: match args.optional_u64("level")? {
+                    Some(level) => u8::try_from(level)
+                        .map_err(|_| format!("The compression level {} is too large", level))?,
+                    None => defaults.level,
+                },
+                target: args
+                    .optional_str("target")?
+                    .map_or(defaults.target, str::to_string),
+            };
+            updated(minify_js(source, &options))
+        }
+        "validate_js" => {
+            let engine = args.option(
+                "engine",
+                &[("oxc", Engine::Oxc), ("swc", Engine::Swc)],
+                Engine::Oxc,
+            )?;
+            validated(validate_js_with_engine(source, engine))
+        }
+        "format_css" => updated(css_formatter::format(source)),
+        "minify_css" => Ok(Value::String(minify_css(source))),
+        "validate_css" => validated(validate_css(source)),
+        "put_css_custom_properties" => updated(put_custom_properties(
+            source,
+            args.optional_str("selector")?.unwrap_or(ROOT_SELECTOR),
+            &args.pairs("variables")?,
+        )),
+        "rename_css_selector" => {
+            updated(rename_selector(source, args.str("from")?, args.str("to")?))
+        }
+        "insert_css_rule_in_media" => updated(insert_rule_in_media(
+            source,
+            args.str("query")?,
+            args.str("rule")?,
+        )),
+        "move_css_rule" => {
+            let position = args.option(
+                "position",
+                &[
+                    ("before", RulePosition::Before),
+                    ("after", RulePosition::After),
+                ],
+                RulePosition::After,
+            )?;
+            updated(move_rule(
+                source,
+                args.str("selector")?,
+                args.str("anchor")?,
+                position,
+            ))
+        }
+        "insert_tailwind_directive" => Ok(Value::String(tailwind::insert_directive(
+            source,
+            tailwind_directive(&args)?,
+            args.str("params")?,
+        ))),
+        "remove_tailwind_directive" => Ok(Value::String(tailwind::remove_directive(
+            source,
+            tailwind_directive(&args)?,
+            args.str("path")?,
+        ))),
+        "put_tailwind_theme_variables" => Ok(Value::String(tailwind::put_theme_variables(
+            source,
+            &args.pairs("variables")?,
+        ))),
+        "remove_tailwind_theme_variables" => Ok(Value::String(tailwind::remove_theme_variables(
+            source,
+            &args.strs("names")?,
+        ))),
+        "find_tailwind_layers" => {
+            let layers = tailwind::find_layers(source);
+            let names = |layers: Vec<TailwindLayer>| -> Vec<&str> {
+                layers.iter().map(TailwindLayer::name).collect()
+            };
+            Ok(json!({
+                "directives": names(layers.directives),
+                "blocks": names(layers.blocks),
+            }))
+        }
+        "insert_tailwind_layer_directive" => Ok(Value::String(tailwind::insert_layer_directive(
+            source,
+            tailwind_layer(&args)?,
+        ))),
+        "insert_tailwind_layer_rule" => updated(tailwind::insert_layer_rule(
+            source,
+            tailwind_layer(&args)?,
+            args.str("rule")?,
+        )),
+        "get_css_declarations" => Ok(get_declarations(source, args.str("selector")?)
+            .into_iter()
+            .map(|declaration| {
+                json!({
+                    "property": declaration.property,
+                    "value": declaration.value,
+                    "important": declaration.important,
+                    "start": declaration.start,
+                    "end": declaration.end,
+                })
+            })
+            .collect()),
+        "has_css_rule" => Ok(Value::Bool(has_rule(source, args.str("selector")?))),
+        "get_jsonc_value" => found(jsonc::get(source, &args.strs("path")?)),
+        "set_jsonc_value" => updated(jsonc::set(
+            source,
+            &args.strs("path")?,
+            &args.json("value")?,
+        )),
+        "remove_jsonc_value" => updated(jsonc::remove(source, &args.strs("path")?)),
+        "get_yaml_value" => found(yaml::get(source, &args.strs("path")?)),
+        "set_yaml_value" => updated(yaml::set(source, &args.strs("path")?, &args.json("value")?)),
+        "append_yaml_value" => updated(yaml::append(
+            source,
+            &args.strs("path")?,
+            &args.json("value")?,
+        )),
+        name => {
+            let json_args = match args.0 {
+                Value::Null => String::new(),
+                args => args.to_string(),
+            };
+            updated(run_named_transform(source, name, &json_args))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_transform() {
+        let args = json!({ "import_lines": "import { Socket } from \"phoenix\";" });
+        assert_eq!(
+            run_transform("insert_import_to_ast", "let a = 1;", &args),
+            Ok(json!("import { Socket } from \"phoenix\";\nlet a = 1;\n"))
+        );
+        assert_eq!(
+            run_transform("insert_import_to_ast", "let a = 1;", &json!({})),
+            Err("The `import_lines` argument is required".to_string())
+        );
+        let args =
+            json!({ "import_lines": "import { Socket } from \"phoenix\";", "timeout": 5000 });
+        assert!(run_transform("insert_import_to_ast", "let a = 1;", &args).is_ok());
+        assert_eq!(
+            run_transform(
+                "insert_import_to_ast",
+                "let a = 1;",
+                &json!({ "timeout": -1 })
+            ),
+            Err("The `timeout` argument must be a non-negative integer".to_string())
+        );
+
+        let code = "import topbar from \"topbar\";\nlet a = 1;\n";
+        let args = json!({ "modules": "import topbar from \"topbar\";", "mode": "comment_out" });
+        let removed = run_transform("remove_import_from_ast", code, &args).unwrap();
+        assert!(
+            removed.as_str().unwrap().contains("// removed by igniter"),
+            "{}",
+            removed
+        );
+        let args = json!({ "modules": "topbar", "mode": "hide" });
+        assert_eq!(
+            run_transform("remove_import_from_ast", code, &args),
+            Err("The `mode` argument must be one of delete, comment_out".to_string())
+        );
+
+        let args = json!({ "variables": [["--brand", "#fd4f00"]] });
+        let css = run_transform("put_css_custom_properties", ":root {}\n", &args).unwrap();
+        assert!(
+            css.as_str().unwrap().contains("--brand: #fd4f00;"),
+            "{}",
+            css
+        );
+        let args = json!({ "selector": ":root" });
+        assert_eq!(
+            run_transform("get_css_declarations", css.as_str().unwrap(), &args).unwrap()[0]
+                ["property"],
+            "--brand"
+        );
+
+        let json = "{\n  // The name\n  \"name\": \"app\"\n}\n";
+        let args = json!({ "path": ["name"] });
+        assert_eq!(
+            run_transform("get_jsonc_value", json, &args),
+            Ok(json!("\"app\""))
+        );
+        let args = json!({ "path": ["version"], "value": "1.0.0" });
+        let updated = run_transform("set_jsonc_value", json, &args).unwrap();
+        assert!(updated.as_str().unwrap().contains("\"version\": \"1.0.0\""));
+        let args = json!({ "path": ["version"] });
+        assert_eq!(
+            run_transform("get_jsonc_value", json, &args),
+            Err("The path was not found".to_string())
+        );
+
+        assert_eq!(
+            run_transform("validate_js", "let a = 1;", &Value::Null),
+            Ok(json!([]))
+        );
+        let error = run_transform("validate_js", "let a = ", &Value::Null).unwrap_err();
+        let diagnostics: Value = serde_json::from_str(&error).unwrap();
+        assert_eq!(diagnostics[0]["severity"], "error");
+
+        let js = "let liveSocket = new LiveSocket(\"/live\", Socket, {hooks: {Copy, Chart}});\n";
+        let args = json!({ "object_names": ["Chart"] });
+        let removed = run_transform("remove_objects_of_hooks_from_ast", js, &args).unwrap();
+        assert!(!removed.as_str().unwrap().contains("Chart"), "{}", removed);
+        let args = json!({ "entries": ["S3"] });
+        assert_eq!(
+            run_transform("extend_live_socket_option_to_ast", js, &args),
+            Err("The `option` argument is required".to_string())
+        );
+
+        let args = json!({ "layer": "components" });
+        let css = run_transform("insert_tailwind_layer_directive", "", &args).unwrap();
+        assert_eq!(
+            run_transform("find_tailwind_layers", css.as_str().unwrap(), &Value::Null),
+            Ok(json!({ "directives": ["components"], "blocks": [] }))
+        );
+        let args = json!({ "layer": "root" });
+        assert_eq!(
+            run_transform("insert_tailwind_layer_directive", "", &args),
+            Err("The `layer` argument must be one of base, components, utilities".to_string())
+        );
+
+        assert_eq!(
+            run_transform("minify_js", "let a = true;", &json!({ "mangle": false })),
+            Ok(json!("let a=!0;"))
+        );
+
+        assert!(run_transform("unknown", "", &Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_run_request() {
+        let request = json!({
+            "transform": "rename_identifier",
+            "source": "let a = 1;\nconsole.log(a);\n",
+            "args": { "old_name": "a", "new_name": "b" },
+        });
+        let response = run_request(request.to_string().as_bytes());
+        assert_eq!(response["status"], "ok");
+        assert_eq!(response["result"], "let b = 1;\nconsole.log(b);\n");
+
+        assert_eq!(run_request(b"{")["status"], "error");
+        assert_eq!(
+            run_request(br#"{"transform": "format_css"}"#)["error"],
+            "The request must have a `transform` and a `source`"
+        );
+    }
+
+    #[test]
+    fn test_transforms_sorted() {
+        assert!(TRANSFORMS.is_sorted());
+    }
+}
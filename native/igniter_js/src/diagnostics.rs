@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Syntax diagnostics shared by the JavaScript and CSS validation, in one shape whatever
+//! parser reported them.
+
+use biome_console::fmt::{Display, Formatter};
+use biome_console::{markup, MarkupBuf};
+use biome_diagnostics::{LogCategory, PrintDescription, Severity, Visit};
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+
+/// How serious a diagnostic is.
+///
+/// On the Elixir side these are the atoms `:error`, `:warning` and `:advice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Advice,
+}
+
+/// A problem found in a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct Diagnostic {
+    pub message: String,
+    pub severity: DiagnosticSeverity,
+    /// A hint on how to fix the problem, if the parser gives one.
+    pub help: Option<String>,
+    /// The byte offsets of the reported range.
+    pub start: usize,
+    pub end: usize,
+    /// The 1-based line and 0-based column, in characters, of `start`.
+    pub line: usize,
+    pub column: usize,
+    /// The lines around the problem with the range underlined, see [`code_frame`].
+    pub code_frame: String,
+}
+
+impl Diagnostic {
+    /// Builds a diagnostic for the byte range `start..end` of `source`.
+    pub fn new(
+        source: &str,
+        message: String,
+        severity: DiagnosticSeverity,
+        help: Option<String>,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        let start = floor_char_boundary(source, start);
+        let end = floor_char_boundary(source, end.max(start));
+        let line_start = line_start(source, start);
+
+        Self {
+            message,
+            severity,
+            help,
+            start,
+            end,
+            line: source[..start].matches('\n').count() + 1,
+            column: source[line_start..start].chars().count(),
+            code_frame: code_frame(source, start, end),
+        }
+    }
+}
+
+/// Collects the log advices of a Biome diagnostic as plain text.
+#[derive(Default)]
+struct HelpCollector {
+    help: Vec<String>,
+}
+
+impl Visit for HelpCollector {
+    fn record_log(&mut self, _category: LogCategory, text: &dyn Display) -> std::io::Result<()> {
+        let mut buffer = MarkupBuf::default();
+        Formatter::new(&mut buffer).write_markup(markup! {{text}})?;
+        self.help
+            .push(buffer.0.into_iter().map(|node| node.content).collect());
+        Ok(())
+    }
+}
+
+impl Diagnostic {
+    /// Converts a diagnostic of a Biome parser of `source`.
+    pub fn from_biome(source: &str, diagnostic: &impl biome_diagnostics::Diagnostic) -> Self {
+        let severity = match diagnostic.severity() {
+            Severity::Warning => DiagnosticSeverity::Warning,
+            Severity::Information | Severity::Hint => DiagnosticSeverity::Advice,
+            Severity::Error | Severity::Fatal => DiagnosticSeverity::Error,
+        };
+        let (start, end) = diagnostic
+            .location()
+            .span
+            .map_or((0, 0), |span| (span.start().into(), span.end().into()));
+
+        let mut collector = HelpCollector::default();
+        let help = match diagnostic.advices(&mut collector) {
+            Ok(()) if !collector.help.is_empty() => Some(collector.help.join("\n")),
+            _ => None,
+        };
+
+        Self::new(
+            source,
+            PrintDescription(diagnostic).to_string(),
+            severity,
+            help,
+            start,
+            end,
+        )
+    }
+}
+
+/// The most diagnostics listed by [`syntax_error_message`].
+const MAX_LISTED_DIAGNOSTICS: usize = 5;
+
+/// The error of a source that fails to parse, listing where and why with the code frame of
+/// each diagnostic, so the message is enough to fix the source.
+///
+/// # Example
+/// ```rust
+/// let source = "let b = ;";
+/// let severity = DiagnosticSeverity::Error;
+/// let diagnostic = Diagnostic::new(source, "Expected an expression".into(), severity, None, 8, 9);
+/// assert!(syntax_error_message(&[diagnostic]).ends_with(
+///     "\n\n1:8: Expected an expression\n> 1 | let b = ;\n    |         ^"
+/// ));
+/// ```
+pub fn syntax_error_message(diagnostics: &[Diagnostic]) -> String {
+    let mut message = "Parsing failed due to syntax errors.".to_string();
+    for diagnostic in diagnostics.iter().take(MAX_LISTED_DIAGNOSTICS) {
+        message.push_str(&format!(
+            "\n\n{}:{}: {}\n{}",
+            diagnostic.line, diagnostic.column, diagnostic.message, diagnostic.code_frame
+        ));
+        if let Some(help) = &diagnostic.help {
+            message.push_str(&format!("\nhelp: {}", help));
+        }
+    }
+    if diagnostics.len() > MAX_LISTED_DIAGNOSTICS {
+        message.push_str(&format!(
+            "\n\n{} more syntax errors",
+            diagnostics.len() - MAX_LISTED_DIAGNOSTICS
+        ));
+    }
+    message
+}
+
+fn floor_char_boundary(source: &str, mut offset: usize) -> usize {
+    offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// The byte offset of the line of `offset`, after the byte order mark on the first line,
+/// which editors do not show.
+fn line_start(source: &str, offset: usize) -> usize {
+    match source[..offset].rfind('\n') {
+        Some(index) => index + 1,
+        None if source.starts_with('\u{feff}') => '\u{feff}'.len_utf8().min(offset),
+        None => 0,
+    }
+}
+
+/// The lines of context printed before and after the line of a problem.
+const CONTEXT_LINES: usize = 2;
+
+/// Renders the lines around the byte range `start..end`, marking the first line of the
+/// range with `>` and underlining the range on it with `^`.
+///
+/// # Example
+/// ```rust
+/// let source = "let a = 1;\nlet b = ;\nlet c = 3;\n";
+/// assert_eq!(
+///     code_frame(source, 19, 20),
+///     "  1 | let a = 1;\n> 2 | let b = ;\n    |         ^\n  3 | let c = 3;"
+/// );
+/// ```
+pub fn code_frame(source: &str, start: usize, end: usize) -> String {
+    // The byte order mark is not shown, it would shift the carets of the first line
+    let bom = if source.starts_with('\u{feff}') {
+        '\u{feff}'.len_utf8()
+    } else {
+        0
+    };
+    let (source, start, end) = (
+        &source[bom..],
+        start.saturating_sub(bom),
+        end.saturating_sub(bom),
+    );
+    let lines: Vec<&str> = source.split('\n').collect();
+    let line = source[..start].matches('\n').count();
+    let first = line.saturating_sub(CONTEXT_LINES);
+    let last = (line + CONTEXT_LINES).min(lines.len() - 1);
+    let width = (last + 1).to_string().len();
+
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_text = lines[line].trim_end_matches('\r');
+    let column = source[line_start..start].chars().count();
+    let underline = source[start..end.min(line_start + line_text.len()).max(start)]
+        .chars()
+        .count()
+        .max(1);
+
+    let mut frame = vec![];
+    for (index, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let marker = if index == line { '>' } else { ' ' };
+        let text = text.trim_end_matches('\r');
+        frame.push(
+            format!("{} {:>width$} | {}", marker, index + 1, text)
+                .trim_end()
+                .to_string(),
+        );
+
+        if index == line {
+            // Tabs are kept so the carets line up with the source
+            let padding: String = line_text
+                .chars()
+                .take(column)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            frame.push(format!(
+                "  {:>width$} | {}{}",
+                "",
+                padding,
+                "^".repeat(underline)
+            ));
+        }
+    }
+
+    frame.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic() {
+        let source = "a {\n  color: é red;\n}";
+        let start = source.find("red").unwrap();
+        let diagnostic = Diagnostic::new(
+            source,
+            "Unexpected value".into(),
+            DiagnosticSeverity::Error,
+            None,
+            start,
+            start + 3,
+        );
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 11));
+        assert_eq!(
+            diagnostic.code_frame,
+            "  1 | a {\n> 2 |   color: é red;\n    |            ^^^\n  3 | }"
+        );
+
+        // A range at the end of the source is still shown
+        let diagnostic = Diagnostic::new(
+            source,
+            "Unexpected end".into(),
+            DiagnosticSeverity::Error,
+            None,
+            source.len(),
+            source.len(),
+        );
+        assert_eq!(diagnostic.line, 3);
+        assert!(diagnostic.code_frame.ends_with("> 3 | }\n    |  ^"));
+    }
+}
@@ -0,0 +1,167 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Golden tests of the transforms against the real-world files in `tests/fixtures`.
+//!
+//! Each transform has a directory named after it in [`crate::core::TRANSFORMS`], e.g.
+//! `tests/fixtures/extend_hook_object_to_ast`, holding its cases, which are run through
+//! [`run_transform`] like any other caller of the crate:
+//!
+//! | File                       | Content                                               |
+//! |----------------------------|-------------------------------------------------------|
+//! | `<case>.js`, `<case>.css`  | The input                                             |
+//! | `<case>.args.json`         | The arguments of the transform, a JSON object         |
+//! | `<case>.expected.js`       | The expected source, with the extension of the input  |
+//! | `<case>.expected.json`     | The expected result, when it is not a source          |
+//! | `<case>.expected.err`      | The expected error message, when the transform fails  |
+//!
+//! A regression case is added by dropping an input (and its `.args.json`) in the directory
+//! of a transform and running the tests with `UPDATE_FIXTURES=1`, which writes the expected
+//! files to review.
+
+use crate::core::run_transform;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory of the fixtures.
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// A case of a transform.
+#[derive(Debug)]
+pub struct Fixture {
+    pub transform: String,
+    pub input: PathBuf,
+}
+
+impl Fixture {
+    fn with_extension(&self, extension: &str) -> PathBuf {
+        let stem = self.input.file_stem().unwrap_or_default().to_string_lossy();
+        self.input.with_file_name(format!("{}.{}", stem, extension))
+    }
+
+    fn args(&self) -> Value {
+        fs::read_to_string(self.with_extension("args.json"))
+            .map(|args| serde_json::from_str(&args).expect("the fixture arguments are JSON"))
+            .unwrap_or(Value::Null)
+    }
+
+    fn expected_output(&self) -> PathBuf {
+        let extension = self.input.extension().unwrap_or_default().to_string_lossy();
+        self.with_extension(&format!("expected.{}", extension))
+    }
+
+    fn expected_error(&self) -> PathBuf {
+        self.with_extension("expected.err")
+    }
+
+    /// Runs the transform, returning the path of the expected file and the actual content.
+    pub fn run(&self) -> (PathBuf, String) {
+        let input = fs::read_to_string(&self.input).expect("the fixture input is readable");
+
+        match run_transform(&self.transform, &input, &self.args()) {
+            Ok(Value::String(output)) => (self.expected_output(), output),
+            Ok(result) => (
+                self.with_extension("expected.json"),
+                format!("{:#}\n", result),
+            ),
+            Err(error) => (self.expected_error(), format!("{}\n", error)),
+        }
+    }
+}
+
+fn is_input(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("js" | "css")
+    ) && !name.contains(".expected.")
+}
+
+/// Lists the cases of every transform, in a stable order.
+pub fn fixtures() -> Vec<Fixture> {
+    let mut fixtures = vec![];
+    let Ok(transforms) = fs::read_dir(fixtures_dir()) else {
+        return fixtures;
+    };
+
+    for transform in transforms.flatten().filter(|entry| entry.path().is_dir()) {
+        let name = transform.file_name().to_string_lossy().to_string();
+        let Ok(files) = fs::read_dir(transform.path()) else {
+            continue;
+        };
+        fixtures.extend(
+            files
+                .flatten()
+                .map(|file| file.path())
+                .filter(|path| is_input(path))
+                .map(|input| Fixture {
+                    transform: name.clone(),
+                    input,
+                }),
+        );
+    }
+
+    fixtures.sort_by(|a, b| a.input.cmp(&b.input));
+    fixtures
+}
+
+/// Runs a case of a transform by name, e.g. `run_fixture("extend_hook_object_to_ast", "app.js")`,
+/// returning the output and the expected one.
+pub fn run_fixture(transform: &str, file_name: &str) -> (String, String) {
+    let fixture = Fixture {
+        transform: transform.to_string(),
+        input: fixtures_dir().join(transform).join(file_name),
+    };
+    let (expected_path, actual) = fixture.run();
+    let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+    (actual, expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_fixtures() {
+        let update = std::env::var("UPDATE_FIXTURES").is_ok_and(|value| value == "1");
+        let fixtures = fixtures();
+        assert!(!fixtures.is_empty(), "no fixture in {:?}", fixtures_dir());
+
+        let mut failures = vec![];
+        for fixture in &fixtures {
+            let (expected_path, actual) = fixture.run();
+            if update {
+                fs::write(&expected_path, &actual).expect("the expected file is writable");
+                continue;
+            }
+
+            match fs::read_to_string(&expected_path) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => failures.push(format!(
+                    "{}:\n--- expected\n{}\n--- actual\n{}",
+                    expected_path.display(),
+                    expected,
+                    actual
+                )),
+                Err(_) => failures.push(format!(
+                    "{} is missing, run with UPDATE_FIXTURES=1 to write it:\n{}",
+                    expected_path.display(),
+                    actual
+                )),
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    }
+
+    #[test]
+    fn test_run_fixture() {
+        let (actual, expected) = run_fixture("extend_hook_object_to_ast", "phoenix_app.js");
+        assert_eq!(actual, expected);
+    }
+}
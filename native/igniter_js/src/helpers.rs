@@ -7,9 +7,24 @@
 //!
 //! This module provides utility functions for encoding consistent responses
 //! in Elixir NIFs using Rust. It leverages the Rustler library for seamless
-//! integration with the Erlang VM.
+//! integration with the Erlang VM. It also holds what the JavaScript and CSS transforms
+//! share: the comparison of a source with its formatted version, the line endings and
+//! byte order mark to emit code with, and the catching of the panics of their parsers.
 
-use rustler::{Encoder, Env, NifResult, Term};
+#[cfg(feature = "nif")]
+use crate::{atoms, limits, telemetry};
+use biome_formatter::LineEnding;
+#[cfg(feature = "nif")]
+use rustler::{Encoder, Env, NifMap, NifResult, NifUnitEnum, Term};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    static CATCHING_PANIC: Cell<bool> = const { Cell::new(false) };
+}
+
+static PANIC_HOOK: Once = Once::new();
 
 /// Encodes a response into an Erlang term.
 ///
@@ -29,7 +44,11 @@ use rustler::{Encoder, Env, NifResult, Term};
 /// # Returns
 ///
 /// Returns a `NifResult` containing the encoded tuple `(status, source, message)`
-/// as a `Term` that can be sent to the Erlang/Elixir runtime.
+/// as a `Term` that can be sent to the Erlang/Elixir runtime. When the metrics are enabled,
+/// see [`crate::telemetry`], the [`telemetry::Metrics`] of the call are appended to it. An
+/// error caused by a source exceeding a limit, see [`crate::limits`], has the message
+/// `(:too_large, LimitExceeded)` whatever the NIF returned, and an error of a call stopped
+/// by its timeout the message `:timeout`.
 ///
 /// # Example
 ///
@@ -47,6 +66,7 @@ use rustler::{Encoder, Env, NifResult, Term};
 ///
 /// This function is useful for building consistent response formats
 /// when integrating Rust code with Elixir applications.
+#[cfg(feature = "nif")]
 pub fn encode_response<T>(
     env: Env<'_>,
     status: rustler::types::atom::Atom,
@@ -56,5 +76,307 @@ pub fn encode_response<T>(
 where
     T: Encoder,
 {
-    Ok((status, source, message).encode(env))
+    let message = match (limits::take_exceeded(), limits::take_timed_out()) {
+        (_, true) if status == atoms::error() => atoms::timeout().encode(env),
+        (Some(exceeded), _) if status == atoms::error() => {
+            (atoms::too_large(), exceeded).encode(env)
+        }
+        _ => message.encode(env),
+    };
+
+    match telemetry::take() {
+        Some(metrics) => Ok((status, source, message, metrics).encode(env)),
+        None => Ok((status, source, message).encode(env)),
+    }
+}
+
+/// The source an edit returns, with whether the edit changed it.
+///
+/// An edit with nothing to do, e.g. adding a hook that is already registered, returns its
+/// input untouched, so `changed` is `false` exactly when there is nothing to write. On the
+/// Elixir side this is a map, e.g. `%{code: code, changed: false}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct Edit {
+    pub code: String,
+    pub changed: bool,
+}
+
+impl Edit {
+    pub fn new(source: &str, code: String) -> Self {
+        Self {
+            changed: code != source,
+            code,
+        }
+    }
+}
+
+/// Encodes the response of an edit NIF like [`encode_response`], with the [`Edit`] of
+/// `source` or the error of the edit.
+#[cfg(feature = "nif")]
+pub fn encode_edit<'a>(
+    env: Env<'a>,
+    source_atom: rustler::types::atom::Atom,
+    source: &str,
+    result: Result<String, String>,
+) -> NifResult<Term<'a>> {
+    match result {
+        Ok(code) => encode_response(env, atoms::ok(), source_atom, Edit::new(source, code)),
+        Err(error) => encode_response(env, atoms::error(), source_atom, error),
+    }
+}
+
+/// What the end of a source must look like to be formatted.
+///
+/// On the Elixir side these are the atoms `:ignore` and `:require`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum FinalNewline {
+    /// Whitespace before the first and after the last line is not compared.
+    #[default]
+    Ignore,
+    /// The source must end with the single newline the formatter prints.
+    Require,
+}
+
+/// How [`check_formatted`] compares a source with its formatted version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct FormatCheckOptions {
+    /// Compare `\r\n` line endings as `\n`, so files saved on Windows are formatted.
+    pub normalize_newlines: bool,
+    pub final_newline: FinalNewline,
+}
+
+impl Default for FormatCheckOptions {
+    fn default() -> Self {
+        Self {
+            normalize_newlines: true,
+            final_newline: FinalNewline::Ignore,
+        }
+    }
+}
+
+/// The first line a source differs from its formatted version on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct FormatDifference {
+    /// The 1-based line number, in the formatted version.
+    pub line: usize,
+    /// The line of the formatted version, `None` past its end.
+    pub expected: Option<String>,
+    /// The line of the source, `None` past its end.
+    pub actual: Option<String>,
+}
+
+/// The result of [`check_formatted`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct FormatCheck {
+    pub formatted: bool,
+    /// Where the source starts to differ, `None` when it is formatted.
+    pub difference: Option<FormatDifference>,
+}
+
+/// Compares a source with its formatted version.
+///
+/// Identical bytes are formatted without further work; otherwise both are normalized as
+/// the options say and compared line by line to find the first difference.
+///
+/// # Example
+/// ```rust
+/// let check = check_formatted("a;\r\nb;\r\n", "a;\nb;\n", FormatCheckOptions::default());
+/// assert!(check.formatted);
+///
+/// let check = check_formatted("a;\nb ;\n", "a;\nb;\n", FormatCheckOptions::default());
+/// assert_eq!(check.difference.unwrap().line, 2);
+/// ```
+pub fn check_formatted(source: &str, formatted: &str, options: FormatCheckOptions) -> FormatCheck {
+    if source.as_bytes() == formatted.as_bytes() {
+        return FormatCheck {
+            formatted: true,
+            difference: None,
+        };
+    }
+
+    let normalize = |code: &str| {
+        let code = if options.normalize_newlines {
+            code.replace("\r\n", "\n")
+        } else {
+            code.to_string()
+        };
+        match options.final_newline {
+            FinalNewline::Ignore => code.trim().to_string(),
+            FinalNewline::Require => code,
+        }
+    };
+    let (source, formatted) = (normalize(source), normalize(formatted));
+    if source == formatted {
+        return FormatCheck {
+            formatted: true,
+            difference: None,
+        };
+    }
+
+    // `split` rather than `lines`, so a missing or extra final newline is a difference
+    let mut expected_lines = formatted.split('\n');
+    let mut actual_lines = source.split('\n');
+    let mut line = 1;
+    loop {
+        let (expected, actual) = (expected_lines.next(), actual_lines.next());
+        if expected != actual {
+            return FormatCheck {
+                formatted: false,
+                difference: Some(FormatDifference {
+                    line,
+                    expected: expected.map(str::to_string),
+                    actual: actual.map(str::to_string),
+                }),
+            };
+        }
+        line += 1;
+    }
+}
+
+/// The line endings and byte order mark of a source, to emit generated code like it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceStyle {
+    /// Most lines end with `\r\n`.
+    pub crlf: bool,
+    /// The source starts with a UTF-8 byte order mark.
+    pub bom: bool,
+}
+
+impl SourceStyle {
+    /// Detects the dominant line ending and the byte order mark of a source.
+    pub fn detect(source: &str) -> Self {
+        let crlf = source.matches("\r\n").count();
+        let lf = source.matches('\n').count() - crlf;
+        Self {
+            crlf: crlf > lf,
+            bom: source.starts_with('\u{feff}'),
+        }
+    }
+
+    /// Rewrites code with the line endings and byte order mark of the style.
+    ///
+    /// Every line ending is rewritten, which keeps the meaning of JavaScript and CSS: line
+    /// endings in template literals are read as `\n` whatever they are.
+    ///
+    /// # Example
+    /// ```rust
+    /// let style = SourceStyle::detect("\u{feff}a;\r\nb;\r\n");
+    /// assert_eq!(style.apply("a;\nb;\nc;\n"), "\u{feff}a;\r\nb;\r\nc;\r\n");
+    /// ```
+    pub fn apply(&self, code: &str) -> String {
+        let code = code.strip_prefix('\u{feff}').unwrap_or(code);
+        let code = if self.crlf {
+            code.replace("\r\n", "\n").replace('\n', "\r\n")
+        } else {
+            code.replace("\r\n", "\n")
+        };
+
+        if self.bom {
+            format!("\u{feff}{}", code)
+        } else {
+            code
+        }
+    }
+}
+
+/// The line ending for the Biome formatters to print a source with. Biome keeps the byte
+/// order mark itself.
+pub fn line_ending(source: &str) -> LineEnding {
+    if SourceStyle::detect(source).crlf {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+/// Runs a function, returning `None` instead of unwinding when it panics, e.g. Biome's
+/// CSS parser on a few broken sources.
+///
+/// The panic is not printed: the default panic hook would write it to the stderr of the
+/// BEAM node. The panics of the other threads are still printed by the previous hook.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Option<T> {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING_PANIC.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+
+    let catching = CATCHING_PANIC.with(|cell| cell.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING_PANIC.with(|cell| cell.set(catching));
+    result.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| 1), Some(1));
+        assert_eq!(catch_panic(|| -> i32 { panic!("stuck") }), None);
+        assert!(!CATCHING_PANIC.with(Cell::get));
+    }
+
+    #[test]
+    fn test_source_style() {
+        let style = SourceStyle::detect("a;\r\nb;\r\nc;\n");
+        assert_eq!(
+            style,
+            SourceStyle {
+                crlf: true,
+                bom: false
+            }
+        );
+        assert_eq!(style.apply("a;\r\nb;\nc;\n"), "a;\r\nb;\r\nc;\r\n");
+
+        let style = SourceStyle::detect("\u{feff}a;\nb;\r\nc;\n");
+        assert_eq!(style.apply("\u{feff}a;\r\nb;\n"), "\u{feff}a;\nb;\n");
+        assert_eq!(SourceStyle::default().apply("a;\r\n"), "a;\n");
+    }
+
+    #[test]
+    fn test_check_formatted() {
+        let formatted = "a {\n  color: red;\n}\n";
+        let options = FormatCheckOptions::default();
+
+        assert!(check_formatted(formatted, formatted, options).formatted);
+        assert!(check_formatted("a {\r\n  color: red;\r\n}", formatted, options).formatted);
+
+        let strict = FormatCheckOptions {
+            normalize_newlines: false,
+            final_newline: FinalNewline::Require,
+        };
+        let check = check_formatted("a {\r\n  color: red;\r\n}\r\n", formatted, strict);
+        assert_eq!(
+            check.difference,
+            Some(FormatDifference {
+                line: 1,
+                expected: Some("a {".to_string()),
+                actual: Some("a {\r".to_string()),
+            })
+        );
+
+        let require = FormatCheckOptions {
+            final_newline: FinalNewline::Require,
+            ..options
+        };
+        let check = check_formatted("a {\n  color: red;\n}", formatted, require);
+        assert_eq!(
+            check.difference,
+            Some(FormatDifference {
+                line: 4,
+                expected: Some(String::new()),
+                actual: None,
+            })
+        );
+    }
 }
@@ -3,11 +3,41 @@
 //
 // SPDX-License-Identifier: MIT
 
+#[cfg(feature = "nif")]
 pub mod atoms;
+pub mod call_options;
+pub mod core;
+pub mod diagnostics;
+#[cfg(test)]
+pub mod fixtures;
 pub mod helpers;
+pub mod limits;
+#[cfg(feature = "nif")]
+pub mod limits_ex;
+pub mod parser_options;
+#[cfg(feature = "nif")]
 pub mod parsers {
+    pub mod asset;
     pub mod css;
+    pub mod html;
     pub mod javascript;
+    pub mod json;
+    pub mod yaml;
 }
+pub mod plugins;
+#[cfg(feature = "nif")]
+pub mod plugins_ex;
+#[cfg(test)]
+pub mod robustness;
+#[cfg(test)]
+pub mod stability;
+pub mod telemetry;
+#[cfg(feature = "nif")]
+pub mod telemetry_ex;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
+#[cfg(all(feature = "wasm", not(target_arch = "wasm32")))]
+compile_error!("The `wasm` feature builds the WASM module, for a `wasm32` target only");
 
+#[cfg(feature = "nif")]
 rustler::init!("Elixir.IgniterJs.Native");
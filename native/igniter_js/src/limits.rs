@@ -0,0 +1,446 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Limits on the size of the parsed sources, so pointing a transform at a bundled vendor
+//! file fails fast instead of stalling a scheduler or allocating a huge syntax tree.
+//!
+//! The parsers check the sources with [`check_input_size`], [`check_node_count`] and
+//! [`Depth`], which fail with a message like any parse error, and record what was exceeded
+//! for the response of the call, see [`take_exceeded`]. A limit of `0` is no limit.
+//!
+//! The parsers and the visitors of their syntax trees recurse into every nested node, so a
+//! source nested a thousand levels deep, e.g. `[[[…]]]`, overflows the stack of the dirty
+//! scheduler running the call. Unlike a panic, which rustler turns into an error, a stack
+//! overflow aborts the whole VM, so the nesting is checked before the parser gets to it.
+//!
+//! The time of a call is limited too, by the timeout of [`set_limits`] or by its own, see
+//! [`start_call`]. Its deadline starts at the first parse of the call, and is checked with
+//! [`check_deadline`] along with the limits above and while SWC reads the tokens of a
+//! source, so a call stops at the first check after its deadline, see [`take_timed_out`].
+
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// The default maximum size of a source, 10 MiB.
+pub const DEFAULT_MAX_INPUT_SIZE: usize = 10 * 1024 * 1024;
+/// The default maximum number of nodes of a syntax tree.
+pub const DEFAULT_MAX_NODE_COUNT: usize = 1_000_000;
+/// The default maximum nesting depth of a source, which every parse, transform and code
+/// generation of the crate runs at within [`CALL_STACK_SIZE`].
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+/// The size of the stack of a NIF call, on which its parses, transforms and the encoding of
+/// its result run, see `limits_ex::run_call`.
+///
+/// The stack of a scheduler thread is too small for the sources nested as deep as
+/// [`DEFAULT_MAX_DEPTH`]: a dirty CPU scheduler has 320 KiB unless the VM is started with
+/// another `+sssdcpu`, and overflowing it aborts the whole VM. Every entry point is run on
+/// sources as deep as the limit within this stack by `test_depth_fits_the_call_stack`.
+pub const CALL_STACK_SIZE: usize = 16 * 1024 * 1024;
+/// The default timeout of a call in milliseconds, none.
+pub const DEFAULT_TIMEOUT: u64 = 0;
+
+static MAX_INPUT_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_INPUT_SIZE);
+static MAX_NODE_COUNT: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_NODE_COUNT);
+static MAX_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_DEPTH);
+static TIMEOUT: AtomicU64 = AtomicU64::new(DEFAULT_TIMEOUT);
+
+thread_local! {
+    static EXCEEDED: Cell<Option<LimitExceeded>> = const { Cell::new(None) };
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static TIMED_OUT: Cell<bool> = const { Cell::new(false) };
+    static CALL_TIMEOUT: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// The limits of every NIF call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct Limits {
+    /// The maximum size of a source in bytes.
+    pub max_input_size: usize,
+    /// The maximum number of nodes of a syntax tree.
+    pub max_node_count: usize,
+    /// The maximum nesting depth of the brackets, braces and parentheses of a source.
+    pub max_depth: usize,
+    /// The maximum time of a call in milliseconds.
+    pub timeout: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_input_size: DEFAULT_MAX_INPUT_SIZE,
+            max_node_count: DEFAULT_MAX_NODE_COUNT,
+            max_depth: DEFAULT_MAX_DEPTH,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+/// Which limit a source exceeded.
+///
+/// On the Elixir side these are the atoms `:input_size`, `:node_count` and `:depth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum Limit {
+    InputSize,
+    NodeCount,
+    Depth,
+}
+
+/// A limit a source exceeded, with its size and the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub size: usize,
+    pub max: usize,
+}
+
+impl LimitExceeded {
+    pub fn message(&self) -> String {
+        match self.limit {
+            Limit::InputSize => format!(
+                "The input of {} bytes exceeds the limit of {} bytes",
+                self.size, self.max
+            ),
+            Limit::NodeCount => format!(
+                "The syntax tree of {} nodes exceeds the limit of {} nodes",
+                self.size, self.max
+            ),
+            Limit::Depth => format!(
+                "The nesting depth of {} exceeds the limit of {}",
+                self.size, self.max
+            ),
+        }
+    }
+}
+
+pub fn set_limits(limits: Limits) {
+    MAX_INPUT_SIZE.store(limits.max_input_size, Ordering::Relaxed);
+    MAX_NODE_COUNT.store(limits.max_node_count, Ordering::Relaxed);
+    MAX_DEPTH.store(limits.max_depth, Ordering::Relaxed);
+    TIMEOUT.store(limits.timeout, Ordering::Relaxed);
+}
+
+pub fn limits() -> Limits {
+    Limits {
+        max_input_size: MAX_INPUT_SIZE.load(Ordering::Relaxed),
+        max_node_count: MAX_NODE_COUNT.load(Ordering::Relaxed),
+        max_depth: MAX_DEPTH.load(Ordering::Relaxed),
+        timeout: TIMEOUT.load(Ordering::Relaxed),
+    }
+}
+
+fn check(limit: Limit, size: usize, max: usize) -> Result<(), String> {
+    if max == 0 || size <= max {
+        return Ok(());
+    }
+
+    let exceeded = LimitExceeded { limit, size, max };
+    EXCEEDED.with(|cell| cell.set(Some(exceeded)));
+    Err(exceeded.message())
+}
+
+/// Checks the size in bytes of a source before it is parsed.
+///
+/// # Example
+/// ```rust
+/// set_limits(Limits { max_input_size: 4, ..Limits::default() });
+/// assert!(check_input_size("let a = 1;".len()).is_err());
+/// ```
+pub fn check_input_size(size: usize) -> Result<(), String> {
+    check_deadline()?;
+    check(Limit::InputSize, size, limits().max_input_size)
+}
+
+/// Checks the number of nodes of a parsed syntax tree. `count` is only called when there is
+/// a limit, as counting walks the whole tree.
+pub fn check_node_count(count: impl FnOnce() -> usize) -> Result<(), String> {
+    check_deadline()?;
+    match limits().max_node_count {
+        0 => Ok(()),
+        max => check(Limit::NodeCount, count(), max),
+    }
+}
+
+/// The nesting depth of a source as it is read, checked against the limit at each level it
+/// opens. The depth of a source exceeding the limit is the level it was stopped at.
+///
+/// # Example
+/// ```rust
+/// let mut depth = Depth::new();
+/// for byte in source.bytes() {
+///     match byte {
+///         b'[' => depth.open()?,
+///         b']' => depth.close(),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Depth {
+    depth: usize,
+    max: usize,
+}
+
+impl Depth {
+    pub fn new() -> Self {
+        Self {
+            depth: 0,
+            max: limits().max_depth,
+        }
+    }
+
+    pub fn open(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        match self.max {
+            0 => Ok(()),
+            max => check(Limit::Depth, self.depth, max),
+        }
+    }
+
+    /// Closes a level, ignoring the closing brackets without an opening one.
+    pub fn close(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Reads the brackets, braces and parentheses of a source, skipping its strings and
+    /// comments, and its `//` comments too when `line_comments` is set, as they are not
+    /// comments in CSS.
+    pub fn read_brackets(&mut self, source: &str, line_comments: bool) -> Result<(), String> {
+        let bytes = source.as_bytes();
+        let mut index = 0;
+
+        while index < bytes.len() {
+            match bytes[index] {
+                b'(' | b'[' | b'{' => self.open()?,
+                b')' | b']' | b'}' => self.close(),
+                quote @ (b'"' | b'\'') => {
+                    index += 1;
+                    while index < bytes.len() && bytes[index] != quote {
+                        index += if bytes[index] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                    index = source[index + 2..]
+                        .find("*/")
+                        .map_or(bytes.len(), |end| index + 2 + end + 1);
+                }
+                b'/' if line_comments && bytes.get(index + 1) == Some(&b'/') => {
+                    index = source[index..]
+                        .find('\n')
+                        .map_or(bytes.len(), |end| index + end);
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Depth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks the nesting depth of a stylesheet or a JSON file before it is parsed, see
+/// [`Depth::read_brackets`].
+pub fn check_bracket_depth(source: &str, line_comments: bool) -> Result<(), String> {
+    Depth::new().read_brackets(source, line_comments)
+}
+
+/// Whether a parse stopped early, past the deadline of the call or at a level nested deeper
+/// than the limit, in which case its result is not the one of its source.
+pub fn check_stopped() -> Result<(), String> {
+    check_deadline()?;
+    match EXCEEDED.with(Cell::get) {
+        Some(exceeded) => Err(exceeded.message()),
+        None => Ok(()),
+    }
+}
+
+/// The limit the current call exceeded, if any, clearing it for the next call.
+pub fn take_exceeded() -> Option<LimitExceeded> {
+    EXCEEDED.with(|cell| cell.take())
+}
+
+fn check_deadline_at(now: Instant, timeout: u64) -> Result<(), String> {
+    if timeout == 0 {
+        return Ok(());
+    }
+
+    let deadline = DEADLINE.with(|cell| {
+        let deadline = cell.get().unwrap_or(now + Duration::from_millis(timeout));
+        cell.set(Some(deadline));
+        deadline
+    });
+    if now <= deadline {
+        return Ok(());
+    }
+    TIMED_OUT.with(|cell| cell.set(true));
+    Err(format!("The call exceeded its timeout of {} ms", timeout))
+}
+
+/// Starts a call on the current thread, with its own timeout in milliseconds or the one of
+/// [`set_limits`] when it is `None`.
+pub fn start_call(timeout: Option<u64>) {
+    EXCEEDED.with(|cell| cell.set(None));
+    DEADLINE.with(|cell| cell.set(None));
+    TIMED_OUT.with(|cell| cell.set(false));
+    CALL_TIMEOUT.with(|cell| cell.set(timeout));
+}
+
+/// The timeout of the current call.
+fn timeout() -> u64 {
+    CALL_TIMEOUT
+        .with(Cell::get)
+        .unwrap_or_else(|| limits().timeout)
+}
+
+/// Checks the time of the current call against its timeout, starting its deadline at the
+/// first check.
+pub fn check_deadline() -> Result<(), String> {
+    check_deadline_at(Instant::now(), timeout())
+}
+
+/// Whether the current call exceeded its timeout, ending its deadline and its own timeout
+/// for the next call.
+pub fn take_timed_out() -> bool {
+    DEADLINE.with(|cell| cell.set(None));
+    CALL_TIMEOUT.with(|cell| cell.set(None));
+    TIMED_OUT.with(|cell| cell.take())
+}
+
+/// Runs a test on a thread with the stack of a NIF call, for the sources nested as deep as
+/// the limit allows.
+#[cfg(test)]
+pub(crate) fn on_call_stack<T: Send>(test: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(CALL_STACK_SIZE)
+            .spawn_scoped(scope, test)
+            .unwrap()
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        // The limits are global, so only the checks are tested against other values
+        let max = limits().max_input_size;
+        assert_eq!(check(Limit::InputSize, max, max), Ok(()));
+        assert_eq!(check(Limit::NodeCount, 10, 0), Ok(()));
+        assert_eq!(take_exceeded(), None);
+
+        let error = check(Limit::NodeCount, 12, 10).unwrap_err();
+        assert_eq!(
+            error,
+            "The syntax tree of 12 nodes exceeds the limit of 10 nodes"
+        );
+        assert_eq!(
+            take_exceeded(),
+            Some(LimitExceeded {
+                limit: Limit::NodeCount,
+                size: 12,
+                max: 10
+            })
+        );
+        assert_eq!(take_exceeded(), None);
+
+        assert!(check_input_size(DEFAULT_MAX_INPUT_SIZE + 1).is_err());
+        assert_eq!(
+            take_exceeded().map(|exceeded| exceeded.limit),
+            Some(Limit::InputSize)
+        );
+    }
+
+    #[test]
+    fn test_depth() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert_eq!(
+            check_bracket_depth(&nested(DEFAULT_MAX_DEPTH), false),
+            Ok(())
+        );
+        assert_eq!(
+            check_bracket_depth(&nested(DEFAULT_MAX_DEPTH + 1), false),
+            Err(format!(
+                "The nesting depth of {} exceeds the limit of {}",
+                DEFAULT_MAX_DEPTH + 1,
+                DEFAULT_MAX_DEPTH
+            ))
+        );
+        assert_eq!(
+            take_exceeded().map(|exceeded| exceeded.limit),
+            Some(Limit::Depth)
+        );
+
+        // The brackets of the strings and comments are not counted
+        let open = "[".repeat(DEFAULT_MAX_DEPTH + 1);
+        for source in [
+            format!("a[content=\"{open}\"] {{}}"),
+            format!("a[content='\\'{open}'] {{}}"),
+            format!("/* {open} */"),
+        ] {
+            assert_eq!(check_bracket_depth(&source, false), Ok(()), "{source}");
+        }
+        assert!(check_bracket_depth(&format!("// {open}"), false).is_err());
+        assert_eq!(check_bracket_depth(&format!("// {open}\n1"), true), Ok(()));
+        assert_eq!(
+            take_exceeded().map(|exceeded| exceeded.limit),
+            Some(Limit::Depth)
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let now = Instant::now();
+        assert_eq!(check_deadline_at(now, 0), Ok(()));
+        assert_eq!(check_deadline_at(now, 50), Ok(()));
+        assert_eq!(
+            check_deadline_at(now + Duration::from_millis(50), 50),
+            Ok(())
+        );
+        assert_eq!(
+            check_deadline_at(now + Duration::from_millis(51), 50),
+            Err("The call exceeded its timeout of 50 ms".to_string())
+        );
+        assert!(take_timed_out());
+
+        // The deadline of the next call starts at its first check
+        assert_eq!(
+            check_deadline_at(now + Duration::from_millis(51), 50),
+            Ok(())
+        );
+        assert!(!take_timed_out());
+    }
+
+    #[test]
+    fn test_call_timeout() {
+        start_call(Some(1));
+        assert_eq!(timeout(), 1);
+        assert_eq!(check_deadline(), Ok(()));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            check_deadline(),
+            Err("The call exceeded its timeout of 1 ms".to_string())
+        );
+        assert!(take_timed_out());
+
+        // The next call is back to the timeout of the limits
+        assert_eq!(timeout(), limits().timeout);
+        start_call(None);
+        assert_eq!(timeout(), limits().timeout);
+    }
+}
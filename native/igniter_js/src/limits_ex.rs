@@ -0,0 +1,18 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::helpers::encode_response;
+use crate::limits::{set_limits, Limits};
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn set_limits_nif(env: Env, limits: Limits) -> NifResult<Term> {
+    let fn_atom = atoms::set_limits_nif();
+    set_limits(limits);
+
+    encode_response(env, atoms::ok(), fn_atom, limits)
+}
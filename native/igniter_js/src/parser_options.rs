@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Options of the JavaScript parsers of a NIF call, e.g. whether decorators are parsed, as
+//! in the Lit and Stimulus components of a project. They are sent with the arguments of the
+//! call and set on the thread running it, see [`crate::call_options`].
+//!
+//! Both the SWC and the OXC paths read them: SWC through [`es_syntax`], and OXC, which
+//! always parses decorators, through [`decorators_enabled`] to report them as errors when
+//! they are disabled.
+
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use std::cell::Cell;
+use swc_ecma_parser::{EsSyntax, Syntax};
+
+thread_local! {
+    static PARSER_OPTIONS: Cell<ParserOptions> = const {
+        Cell::new(ParserOptions { decorators: true })
+    };
+}
+
+/// The options of the JavaScript parsers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct ParserOptions {
+    /// Whether decorators, e.g. `@customElement("my-element")`, and the `accessor` fields
+    /// of the stage 3 decorators proposal are parsed.
+    pub decorators: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self { decorators: true }
+    }
+}
+
+/// Sets the parser options of the call running on the current thread.
+pub fn set_parser_options(options: ParserOptions) {
+    PARSER_OPTIONS.with(|cell| cell.set(options));
+}
+
+/// The parser options of the call running on the current thread.
+pub fn parser_options() -> ParserOptions {
+    PARSER_OPTIONS.with(Cell::get)
+}
+
+pub fn decorators_enabled() -> bool {
+    parser_options().decorators
+}
+
+/// The SWC syntax of the given options.
+pub fn es_syntax_with(options: ParserOptions) -> Syntax {
+    Syntax::Es(EsSyntax {
+        decorators: options.decorators,
+        decorators_before_export: true,
+        auto_accessors: options.decorators,
+        ..Default::default()
+    })
+}
+
+/// The SWC syntax of the current options, for every lexer and parser of a source.
+pub fn es_syntax() -> Syntax {
+    es_syntax_with(parser_options())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_es_syntax_with() {
+        let Syntax::Es(syntax) = es_syntax_with(ParserOptions { decorators: false }) else {
+            unreachable!()
+        };
+        assert!(!syntax.decorators && !syntax.auto_accessors);
+
+        let Syntax::Es(syntax) = es_syntax_with(ParserOptions::default()) else {
+            unreachable!()
+        };
+        assert!(syntax.decorators && syntax.decorators_before_export && syntax.auto_accessors);
+    }
+
+    #[test]
+    fn test_es_syntax_of_call() {
+        assert!(decorators_enabled());
+
+        set_parser_options(ParserOptions { decorators: false });
+        let syntax = es_syntax();
+        let enabled = decorators_enabled();
+        set_parser_options(ParserOptions::default());
+        assert_eq!(syntax, es_syntax_with(ParserOptions { decorators: false }));
+        assert!(!enabled);
+        assert_eq!(es_syntax(), es_syntax_with(ParserOptions::default()));
+    }
+}
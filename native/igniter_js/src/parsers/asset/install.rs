@@ -0,0 +1,258 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Package Integration
+//!
+//! Installing a JavaScript package into a Phoenix application is nearly always the same
+//! steps: import it in `app.js`, register its hooks on the LiveSocket, append a setup
+//! statement and add it to `package.json`. [`install_integration`] runs them all from one
+//! spec and reports what it changed, so installers do not each carry the orchestration.
+
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifTaggedEnum};
+use swc_common::EqIgnoreSpan;
+
+use crate::parsers::javascript::ast::insert_import_to_ast;
+use crate::parsers::javascript::helpers::parse;
+use crate::parsers::javascript::phoenix::{
+    extend_live_socket_option_with_log_to_ast, EditAction, LiveSocketTarget,
+};
+use crate::parsers::json::jsonc;
+
+/// What to integrate.
+///
+/// On the Elixir side this is a map with every key, e.g.
+/// `%{imports: ["import Sortable from \"sortablejs\";"], hooks: ["Sortable"], ...}`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct InstallSpec {
+    /// The import declarations to ensure, one per entry.
+    pub imports: Vec<String>,
+    /// The hook entries to register on `liveSocket`, like [`extend_live_socket_option_with_log_to_ast`]
+    /// takes them.
+    pub hooks: Vec<String>,
+    /// The statements to append to the end of the file unless it already has them.
+    pub statements: Vec<String>,
+    /// The packages and versions to add to the `dependencies` of `package.json`.
+    pub dependencies: Vec<(String, String)>,
+    /// The packages and versions to add to the `devDependencies` of `package.json`.
+    pub dev_dependencies: Vec<(String, String)>,
+}
+
+/// A step of an integration, and whether it changed something.
+///
+/// On the Elixir side these are tuples, e.g. `{:added_import, "import X from \"x\";"}` or
+/// `{:skipped_dependency, "x"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum InstallChange {
+    AddedImport(String),
+    /// The import was already there.
+    SkippedImport(String),
+    AddedHook(String),
+    SkippedHook(String),
+    AppendedStatement(String),
+    SkippedStatement(String),
+    /// A package was added with its version.
+    AddedDependency(String, String),
+    /// The package is already a dependency, whatever its version, which is left as it is.
+    SkippedDependency(String),
+}
+
+/// The sources after an integration and the changes made, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct InstallReport {
+    pub js: String,
+    pub package_json: Option<String>,
+    pub changes: Vec<InstallChange>,
+}
+
+/// Whether every statement of `snippet` is already at the top level of `file_content`,
+/// whatever its formatting.
+fn has_statements(file_content: &str, snippet: &str) -> Result<bool, String> {
+    let (module, _comments, _cm) = parse(file_content)?;
+    let (statements, _comments, _cm) =
+        parse(snippet).map_err(|error| format!("Invalid statement `{}`: {}", snippet, error))?;
+
+    Ok(statements.body.iter().all(|statement| {
+        module
+            .body
+            .iter()
+            .any(|item| item.eq_ignore_span(statement))
+    }))
+}
+
+fn add_dependencies(
+    package_json: &str,
+    key: &str,
+    dependencies: &[(String, String)],
+    changes: &mut Vec<InstallChange>,
+) -> Result<String, String> {
+    dependencies
+        .iter()
+        .try_fold(package_json.to_string(), |source, (name, version)| {
+            let path = [key, name.as_str()];
+            if jsonc::get(&source, &path)?.is_some() {
+                changes.push(InstallChange::SkippedDependency(name.clone()));
+                return Ok(source);
+            }
+
+            let value = serde_json::to_string(version).map_err(|error| error.to_string())?;
+            changes.push(InstallChange::AddedDependency(
+                name.clone(),
+                version.clone(),
+            ));
+            jsonc::set(&source, &path, &value)
+        })
+}
+
+/// Integrates a package into `app.js` and `package.json` in one pass.
+///
+/// The steps run in the order of the spec fields, and each one is skipped when its result
+/// is already there, so running the same spec again reports only skipped changes and leaves
+/// the sources untouched.
+///
+/// # Arguments
+/// * `js_content` - The JavaScript source, usually `app.js`.
+/// * `package_json` - The content of `package.json`, only needed for dependencies.
+/// * `spec` - What to integrate.
+///
+/// # Returns
+/// * `Ok(InstallReport)` - The updated sources and the changes made.
+/// * `Err(String)` - The error of the first failing step, e.g. when there are hooks to add
+///   but no `liveSocket`, or dependencies but no `package.json`.
+///
+/// # Example
+/// ```rust
+/// let spec = InstallSpec {
+///     imports: vec!["import Sortable from \"sortablejs\";".to_string()],
+///     hooks: vec!["Sortable".to_string()],
+///     ..Default::default()
+/// };
+/// let report = install_integration(app_js, None, &spec).unwrap();
+/// assert_eq!(report.changes[1], InstallChange::AddedHook("Sortable".to_string()));
+/// ```
+pub fn install_integration(
+    js_content: &str,
+    package_json: Option<&str>,
+    spec: &InstallSpec,
+) -> Result<InstallReport, String> {
+    let mut changes = vec![];
+    let mut js = js_content.to_string();
+
+    for import in &spec.imports {
+        let updated = insert_import_to_ast(&js, import)?;
+        changes.push(if updated == js {
+            InstallChange::SkippedImport(import.clone())
+        } else {
+            InstallChange::AddedImport(import.clone())
+        });
+        js = updated;
+    }
+
+    if !spec.hooks.is_empty() {
+        let hooks: Vec<&str> = spec.hooks.iter().map(|hook| hook.as_str()).collect();
+        let log = extend_live_socket_option_with_log_to_ast(
+            &js,
+            LiveSocketTarget::Named("liveSocket"),
+            "hooks",
+            hooks,
+        )?;
+        changes.extend(log.actions.into_iter().filter_map(|action| match action {
+            EditAction::AddedEntry(key) => Some(InstallChange::AddedHook(key)),
+            EditAction::SkippedEntry(key) => Some(InstallChange::SkippedHook(key)),
+            _ => None,
+        }));
+        js = log.code;
+    }
+
+    for statement in &spec.statements {
+        if has_statements(&js, statement)? {
+            changes.push(InstallChange::SkippedStatement(statement.clone()));
+        } else {
+            js = format!("{}\n{}\n", js.trim_end(), statement.trim());
+            changes.push(InstallChange::AppendedStatement(statement.clone()));
+        }
+    }
+
+    let package_json = match package_json {
+        Some(source) => {
+            let source =
+                add_dependencies(source, "dependencies", &spec.dependencies, &mut changes)?;
+            Some(add_dependencies(
+                &source,
+                "devDependencies",
+                &spec.dev_dependencies,
+                &mut changes,
+            )?)
+        }
+        None if spec.dependencies.is_empty() && spec.dev_dependencies.is_empty() => None,
+        None => return Err("The package.json content is required to add dependencies".into()),
+    };
+
+    Ok(InstallReport {
+        js,
+        package_json,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_integration() {
+        let app_js = r#"import { Socket } from "phoenix";
+let liveSocket = new LiveSocket("/live", Socket, { hooks: { Existing } });
+liveSocket.connect();
+"#;
+        let package_json =
+            "{\n  \"dependencies\": {\n    \"phoenix\": \"file:../deps/phoenix\"\n  }\n}\n";
+        let spec = InstallSpec {
+            imports: vec!["import Sortable from \"sortablejs\";".to_string()],
+            hooks: vec!["Sortable".to_string(), "Existing".to_string()],
+            statements: vec!["window.Sortable = Sortable;".to_string()],
+            dependencies: vec![
+                ("sortablejs".to_string(), "^1.15.0".to_string()),
+                ("phoenix".to_string(), "^1.7.0".to_string()),
+            ],
+            dev_dependencies: vec![],
+        };
+
+        let report = install_integration(app_js, Some(package_json), &spec).unwrap();
+        assert_eq!(
+            report.changes,
+            [
+                InstallChange::AddedImport("import Sortable from \"sortablejs\";".to_string()),
+                InstallChange::AddedHook("Sortable".to_string()),
+                InstallChange::SkippedHook("Existing".to_string()),
+                InstallChange::AppendedStatement("window.Sortable = Sortable;".to_string()),
+                InstallChange::AddedDependency("sortablejs".to_string(), "^1.15.0".to_string()),
+                InstallChange::SkippedDependency("phoenix".to_string()),
+            ]
+        );
+        assert!(report
+            .js
+            .ends_with("liveSocket.connect();\nwindow.Sortable = Sortable;\n"));
+        let package_json = report.package_json.unwrap();
+        assert!(package_json.contains("\"sortablejs\": \"^1.15.0\""));
+
+        // A second run changes nothing
+        let again = install_integration(&report.js, Some(&package_json), &spec).unwrap();
+        assert_eq!(again.js, report.js);
+        assert_eq!(again.package_json.as_deref(), Some(package_json.as_str()));
+        assert!(again.changes.iter().all(|change| matches!(
+            change,
+            InstallChange::SkippedImport(_)
+                | InstallChange::SkippedHook(_)
+                | InstallChange::SkippedStatement(_)
+                | InstallChange::SkippedDependency(_)
+        )));
+
+        assert!(install_integration(app_js, None, &spec).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::asset::install::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn install_integration_nif(
+    env: Env,
+    js_content: String,
+    package_json: Option<String>,
+    spec: InstallSpec,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::install_integration_nif();
+
+        match install_integration(&js_content, package_json.as_deref(), &spec) {
+            Ok(report) => encode_response(env, atoms::ok(), fn_atom, report),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
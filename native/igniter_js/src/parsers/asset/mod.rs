@@ -0,0 +1,11 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+pub mod install;
+#[cfg(feature = "nif")]
+pub mod install_ex;
+pub mod patch;
+#[cfg(feature = "nif")]
+pub mod patch_ex;
@@ -0,0 +1,307 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Asset Patch Helpers
+//!
+//! Component installers usually touch `app.js` and `app.css` together. This module lets
+//! both sides be described as a list of operations and applies them as a pair: either
+//! both files are patched successfully, or neither output is returned.
+
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifTaggedEnum};
+
+use crate::helpers::{check_formatted, FinalNewline, FormatCheckOptions, FormatDifference};
+
+use crate::parsers::css::formatter as css_formatter;
+use crate::parsers::css::validate::parse_stylesheet;
+use crate::parsers::javascript::ast::*;
+use crate::parsers::javascript::formatter as js_formatter;
+use crate::parsers::javascript::helpers::parse;
+use crate::parsers::javascript::phoenix::*;
+use crate::parsers::javascript::removal::RemovalMode;
+
+/// A single operation applied to a JavaScript source.
+///
+/// On the Elixir side these are plain tuples or atoms, e.g.
+/// `{:insert_imports, "import X from \"x\";"}`, `{:extend_hook_object, ["X"]}` or `:format`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum JsPatchOp {
+    InsertImports(String),
+    RemoveImports(String),
+    ExtendHookObject(Vec<String>),
+    RemoveObjectsOfHooks(Vec<String>),
+    ExtendVarObject(String, Vec<String>),
+    InsertAtIndex(String, usize),
+    ReplaceAtIndex(String, usize),
+    Format,
+}
+
+/// A single operation applied to a CSS source.
+///
+/// On the Elixir side these are plain tuples or atoms, e.g. `{:append, ".btn {}"}` or `:format`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum CssPatchOp {
+    Append(String),
+    Prepend(String),
+    Format,
+}
+
+/// The operations of one side of a patch.
+///
+/// On the Elixir side these are `{:js, js_ops}` and `{:css, css_ops}`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum PatchPlan {
+    Js(Vec<JsPatchOp>),
+    Css(Vec<CssPatchOp>),
+}
+
+/// The result of [`check_idempotency`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct IdempotencyCheck {
+    /// Whether the second run left the source of the first one as it was.
+    pub idempotent: bool,
+    /// The source after the first and the second run.
+    pub once: String,
+    pub twice: String,
+    /// The first line the second run changed, `expected` being the line of the first run
+    /// and `actual` the one of the second, `None` when it is idempotent.
+    pub difference: Option<FormatDifference>,
+}
+
+fn sorted_unique(names: &[String]) -> Vec<&str> {
+    let mut names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn apply_js_op(file_content: &str, op: &JsPatchOp) -> Result<String, String> {
+    match op {
+        JsPatchOp::InsertImports(imports) => insert_import_to_ast(file_content, imports),
+        JsPatchOp::RemoveImports(imports) => {
+            remove_import_from_ast(file_content, imports, RemovalMode::Delete)
+        }
+        JsPatchOp::ExtendHookObject(names) => {
+            extend_hook_object_to_ast(file_content, sorted_unique(names))
+        }
+        JsPatchOp::RemoveObjectsOfHooks(names) => {
+            remove_objects_of_hooks_from_ast(file_content, sorted_unique(names))
+        }
+        JsPatchOp::ExtendVarObject(var_name, names) => {
+            extend_var_object_property_by_names_to_ast(file_content, var_name, sorted_unique(names))
+        }
+        JsPatchOp::InsertAtIndex(code, index) => insert_ast_at_index(file_content, code, *index),
+        JsPatchOp::ReplaceAtIndex(code, index) => replace_ast_at_index(file_content, code, *index),
+        JsPatchOp::Format => js_formatter::format(file_content),
+    }
+}
+
+fn apply_css_op(file_content: &str, op: &CssPatchOp) -> Result<String, String> {
+    match op {
+        CssPatchOp::Append(css) => Ok(format!("{}\n{}\n", file_content.trim_end(), css.trim())),
+        CssPatchOp::Prepend(css) => Ok(format!("{}\n{}", css.trim(), file_content.trim_start())),
+        CssPatchOp::Format => css_formatter::format(file_content),
+    }
+}
+
+fn validate_css(file_content: &str) -> Result<(), String> {
+    if parse_stylesheet(file_content).map_or(true, |parsed| parsed.has_errors()) {
+        Err("Parsing failed due to syntax errors.".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Applies a list of operations to a JavaScript source, in order.
+///
+/// # Returns
+/// * `Ok(String)` - The JavaScript source after every operation was applied.
+/// * `Err(String)` - The error of the first failing operation, prefixed with its index.
+pub fn apply_js_patch(file_content: &str, ops: &[JsPatchOp]) -> Result<String, String> {
+    parse(file_content)?;
+
+    ops.iter()
+        .enumerate()
+        .try_fold(file_content.to_string(), |code, (index, op)| {
+            apply_js_op(&code, op).map_err(|e| format!("Operation {} ({:?}): {}", index, op, e))
+        })
+}
+
+/// Applies a list of operations to a CSS source, in order.
+///
+/// The source is validated before and after every operation, so a snippet that breaks the
+/// stylesheet is reported instead of silently written back.
+pub fn apply_css_patch(file_content: &str, ops: &[CssPatchOp]) -> Result<String, String> {
+    validate_css(file_content)?;
+
+    ops.iter()
+        .enumerate()
+        .try_fold(file_content.to_string(), |code, (index, op)| {
+            apply_css_op(&code, op)
+                .and_then(|updated| validate_css(&updated).map(|_| updated))
+                .map_err(|e| format!("Operation {} ({:?}): {}", index, op, e))
+        })
+}
+
+/// Applies a JavaScript and a CSS patch plan as a single unit.
+///
+/// Both plans are validated and applied; the updated sources are returned only if both
+/// succeed, so an install never ends up half-applied when one side fails.
+///
+/// # Arguments
+/// * `js_content` - The JavaScript source (usually `app.js`).
+/// * `js_ops` - The operations to apply to the JavaScript source.
+/// * `css_content` - The CSS source (usually `app.css`).
+/// * `css_ops` - The operations to apply to the CSS source.
+///
+/// # Returns
+/// * `Ok((String, String))` - The updated JavaScript and CSS sources.
+/// * `Err(String)` - The first error, prefixed with `js:` or `css:` to tell which side failed.
+///
+/// # Example
+/// ```rust
+/// let js_ops = vec![JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()])];
+/// let css_ops = vec![CssPatchOp::Append(".tooltip { color: red; }".to_string())];
+/// let result = apply_asset_patch(app_js, &js_ops, app_css, &css_ops);
+/// assert!(result.is_ok());
+/// ```
+pub fn apply_asset_patch(
+    js_content: &str,
+    js_ops: &[JsPatchOp],
+    css_content: &str,
+    css_ops: &[CssPatchOp],
+) -> Result<(String, String), String> {
+    let js = apply_js_patch(js_content, js_ops).map_err(|e| format!("js: {}", e))?;
+    let css = apply_css_patch(css_content, css_ops).map_err(|e| format!("css: {}", e))?;
+
+    Ok((js, css))
+}
+
+/// Applies a patch plan twice and checks the second run changes nothing, which is the
+/// contract of every installer: running it again on its own output must be a no-op.
+///
+/// The sources are compared byte for byte, line endings and final newline included.
+///
+/// # Returns
+/// * `Ok(IdempotencyCheck)` - Both outputs and where the second run diverged, if it did.
+/// * `Err(String)` - The error of the first or the second run, prefixed with `first run:`
+///   or `second run:`.
+///
+/// # Example
+/// ```rust
+/// let plan = PatchPlan::Css(vec![CssPatchOp::Append(".btn {}".to_string())]);
+/// let check = check_idempotency("body {}", &plan).unwrap();
+/// assert!(!check.idempotent);
+/// assert_eq!(check.difference.unwrap().line, 3);
+/// ```
+pub fn check_idempotency(file_content: &str, plan: &PatchPlan) -> Result<IdempotencyCheck, String> {
+    let apply = |code: &str| match plan {
+        PatchPlan::Js(ops) => apply_js_patch(code, ops),
+        PatchPlan::Css(ops) => apply_css_patch(code, ops),
+    };
+
+    let once = apply(file_content).map_err(|e| format!("first run: {}", e))?;
+    let twice = apply(&once).map_err(|e| format!("second run: {}", e))?;
+
+    let options = FormatCheckOptions {
+        normalize_newlines: false,
+        final_newline: FinalNewline::Require,
+    };
+    let check = check_formatted(&twice, &once, options);
+
+    Ok(IdempotencyCheck {
+        idempotent: check.formatted,
+        once,
+        twice,
+        difference: check.difference,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_js() -> &'static str {
+        r#"
+        import { LiveSocket } from "phoenix_live_view";
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { ...Hooks },
+          params: { _csrf_token: csrfToken },
+        });
+        "#
+    }
+
+    #[test]
+    fn test_apply_asset_patch() {
+        let js_ops = vec![
+            JsPatchOp::InsertImports("import Tooltip from \"./tooltip\";".to_string()),
+            JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()]),
+        ];
+        let css_ops = vec![CssPatchOp::Append(".tooltip { color: red; }".to_string())];
+
+        let (js, css) =
+            apply_asset_patch(app_js(), &js_ops, "body { margin: 0; }", &css_ops).unwrap();
+
+        assert!(js.contains("import Tooltip from \"./tooltip\";"));
+        assert!(js.contains("Tooltip"));
+        assert!(css.contains("body { margin: 0; }"));
+        assert!(css.contains(".tooltip { color: red; }"));
+    }
+
+    #[test]
+    fn test_apply_asset_patch_fails_when_css_side_fails() {
+        let js_ops = vec![JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()])];
+        let css_ops = vec![CssPatchOp::Append(".tooltip { color: red;".to_string())];
+
+        let result = apply_asset_patch(app_js(), &js_ops, "body { margin: 0; }", &css_ops);
+        assert!(result.unwrap_err().starts_with("css: Operation 0"));
+    }
+
+    #[test]
+    fn test_apply_asset_patch_fails_when_js_side_fails() {
+        let js = "let socket = new LiveSocket(\"/live\", Socket, {});";
+        let js_ops = vec![JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()])];
+        let css_ops = vec![CssPatchOp::Format];
+
+        let result = apply_asset_patch(js, &js_ops, "body { margin: 0; }", &css_ops);
+        assert!(result.unwrap_err().starts_with("js: Operation 0"));
+
+        let result = apply_asset_patch("let = ;", &[], "body {}", &[]);
+        assert!(result.unwrap_err().starts_with("js:"));
+    }
+
+    #[test]
+    fn test_check_idempotency() {
+        let plan = PatchPlan::Js(vec![
+            JsPatchOp::InsertImports("import Tooltip from \"./tooltip\";".to_string()),
+            JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()]),
+            JsPatchOp::Format,
+        ]);
+        let check = check_idempotency(app_js(), &plan).unwrap();
+        assert!(check.idempotent);
+        assert_eq!(check.once, check.twice);
+        assert_eq!(check.difference, None);
+
+        let plan = PatchPlan::Js(vec![JsPatchOp::InsertAtIndex("foo();".to_string(), 0)]);
+        let check = check_idempotency("bar();\n", &plan).unwrap();
+        assert!(!check.idempotent);
+        assert_eq!(
+            check.difference,
+            Some(FormatDifference {
+                line: 2,
+                expected: Some("bar();".to_string()),
+                actual: Some("foo();".to_string()),
+            })
+        );
+
+        let plan = PatchPlan::Css(vec![CssPatchOp::Append(".a {".to_string())]);
+        let error = check_idempotency("body {}", &plan).unwrap_err();
+        assert!(error.starts_with("first run: Operation 0"));
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::asset::patch::*;
+
+use rustler::{Encoder, Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn apply_asset_patch_nif(
+    env: Env,
+    js_content: String,
+    js_ops: Vec<JsPatchOp>,
+    css_content: String,
+    css_ops: Vec<CssPatchOp>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::apply_asset_patch_nif();
+        let (status, result) = match apply_asset_patch(&js_content, &js_ops, &css_content, &css_ops)
+        {
+            Ok(updated) => (atoms::ok(), updated.encode(env)),
+            Err(error_msg) => (atoms::error(), error_msg.encode(env)),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn apply_twice_equals_once_nif(
+    env: Env,
+    file_content: String,
+    plan: PatchPlan,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::apply_twice_equals_once_nif();
+
+        match check_idempotency(&file_content, &plan) {
+            Ok(check) => encode_response(env, atoms::ok(), fn_atom, check),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Read access to the rules and declarations of a stylesheet, so an installer can check
+//! what is already there, e.g. whether `--primary-color` is defined, before it patches it.
+//! Like the other text-based helpers, the queries work on the statements of the scanner
+//! and understand framework at-rules such as Tailwind's `@theme`.
+
+use crate::parsers::css::helpers::*;
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+
+/// A declaration of a rule, see [`get_declarations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct CssDeclaration {
+    /// The property name, e.g. `--primary-color`.
+    pub property: String,
+    /// The value, trimmed and without its `!important` flag.
+    pub value: String,
+    pub important: bool,
+    /// The byte offset where the declaration starts.
+    pub start: usize,
+    /// The byte offset right after the declaration, after its `;` if it has one.
+    pub end: usize,
+}
+
+/// Whether the prelude of a block selects `selector`: the whole prelude, e.g. `:root, .dark`
+/// or `@theme`, or one of the selectors of a rule, e.g. `.dark`.
+fn selects(prelude: &str, selector: &str) -> bool {
+    let prelude = normalize_selector(prelude);
+    prelude == selector
+        || (!prelude.starts_with('@')
+            && prelude
+                .split(',')
+                .any(|part| normalize_selector(part) == selector))
+}
+
+/// Collects the blocks of `selector` between `from` and `to`, also the ones nested in
+/// at-rules or other rules.
+fn find_blocks(css: &str, from: usize, to: usize, selector: &str, found: &mut Vec<Statement>) {
+    for statement in statements(css, from, to) {
+        let Some((open, close)) = statement.block else {
+            continue;
+        };
+        if selects(&statement.prelude, selector) {
+            found.push(statement.clone());
+        }
+        find_blocks(css, open + 1, close, selector, found);
+    }
+}
+
+fn rules(css: &str, selector: &str) -> Vec<Statement> {
+    let mut found = vec![];
+    find_blocks(css, 0, css.len(), &normalize_selector(selector), &mut found);
+    found
+}
+
+/// Returns the declarations of the rules of `selector`, in source order.
+///
+/// A rule matches when its selector list is `selector`, or when one of its selectors is,
+/// so `.dark` matches `:root, .dark { ... }`. Rules nested in at-rules such as `@media` or
+/// in other rules are included, and an at-rule block is matched by its prelude, e.g.
+/// `@theme`. The declarations of the rules nested in a matched rule are not included.
+///
+/// The offsets are byte offsets in `css`.
+///
+/// # Example
+/// ```rust
+/// let css = ":root {\n  --primary-color: #fd4f00 !important;\n}\n";
+/// let declarations = get_declarations(css, ":root");
+/// assert_eq!(declarations[0].property, "--primary-color");
+/// assert_eq!(declarations[0].value, "#fd4f00");
+/// assert!(declarations[0].important);
+/// ```
+pub fn get_declarations(css: &str, selector: &str) -> Vec<CssDeclaration> {
+    let bom = css.len() - css.trim_start_matches('\u{feff}').len();
+    let css = &css[bom..];
+
+    rules(css, selector)
+        .iter()
+        .flat_map(|rule| {
+            let (open, close) = rule.block.expect("blocks are filtered on their block");
+            statements(css, open + 1, close)
+        })
+        .filter_map(|declaration| {
+            let property = declaration.declaration_name()?.to_string();
+            let (_, value) = declaration.prelude.split_once(':')?;
+            let value = value.trim();
+            let (value, important) = match value.strip_suffix("!important") {
+                Some(value) => (value.trim_end(), true),
+                None => (value, false),
+            };
+
+            Some(CssDeclaration {
+                property,
+                value: value.to_string(),
+                important,
+                start: bom + declaration.start,
+                end: bom + declaration.end,
+            })
+        })
+        .collect()
+}
+
+/// Checks whether the stylesheet has a rule of `selector`, matched as in
+/// [`get_declarations`].
+///
+/// # Example
+/// ```rust
+/// assert!(has_rule("@media (min-width: 640px) {\n  .btn { padding: 0; }\n}\n", ".btn"));
+/// assert!(!has_rule(".btn:hover {}", ".btn"));
+/// ```
+pub fn has_rule(css: &str, selector: &str) -> bool {
+    let css = css.trim_start_matches('\u{feff}');
+    !rules(css, selector).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_declarations() {
+        let css = r#"@import "tailwindcss";
+
+:root,
+.dark {
+  --primary-color: #fd4f00; /* brand */
+  --font: "Inter", sans-serif !important;
+  .nested { color: red; }
+  --radius: 4px
+}
+
+@media (prefers-color-scheme: dark) {
+  .dark { --primary-color: #fff; }
+}
+
+@theme {
+  --color-brand: var(--primary-color);
+}
+"#;
+
+        let declarations = get_declarations(css, ".dark");
+        let values: Vec<(&str, &str, bool)> = declarations
+            .iter()
+            .map(|d| (d.property.as_str(), d.value.as_str(), d.important))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("--primary-color", "#fd4f00", false),
+                ("--font", "\"Inter\", sans-serif", true),
+                ("--radius", "4px", false),
+                ("--primary-color", "#fff", false),
+            ]
+        );
+        assert_eq!(
+            &css[declarations[0].start..declarations[0].end],
+            "--primary-color: #fd4f00;"
+        );
+        assert_eq!(
+            &css[declarations[2].start..declarations[2].end],
+            "--radius: 4px"
+        );
+
+        assert_eq!(get_declarations(css, ":root,\n.dark").len(), 3);
+        assert_eq!(get_declarations(css, "@theme")[0].property, "--color-brand");
+        assert!(get_declarations(css, ".missing").is_empty());
+
+        let bom = format!("\u{feff}{}", css);
+        let declarations = get_declarations(&bom, ":root");
+        assert_eq!(
+            &bom[declarations[0].start..declarations[0].end],
+            "--primary-color: #fd4f00;"
+        );
+    }
+
+    #[test]
+    fn test_has_rule() {
+        let css = "\u{feff}.btn { padding: 0; }\n@layer components {\n  .card {}\n}\n";
+        assert!(has_rule(css, ".btn"));
+        assert!(has_rule(css, ".card"));
+        assert!(has_rule(css, "@layer components"));
+        assert!(!has_rule(css, ".nav"));
+    }
+}
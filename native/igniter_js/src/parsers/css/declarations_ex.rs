@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::css::declarations::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn get_css_declarations_nif(
+    env: Env,
+    file_content: String,
+    selector: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = get_declarations(&file_content, &selector);
+        encode_response(env, atoms::ok(), atoms::get_css_declarations_nif(), result)
+    })
+}
+
+#[rustler::nif]
+pub fn has_css_rule_nif(
+    env: Env,
+    file_content: String,
+    selector: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = has_rule(&file_content, &selector);
+        encode_response(env, atoms::ok(), atoms::has_css_rule_nif(), result)
+    })
+}
@@ -0,0 +1,168 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Detection of the SCSS and Less dialects, which the Biome CSS parser does not support.
+//!
+//! Many Phoenix projects still have `.scss` assets. Instead of a syntax error on their nested
+//! rules, the formatter reports the dialect, so they can be skipped.
+
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+
+/// The dialect of a stylesheet.
+///
+/// On the Elixir side these are the atoms `:css`, `:scss` and `:less`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum CssDialect {
+    Css,
+    Scss,
+    Less,
+}
+
+impl CssDialect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CssDialect::Css => "CSS",
+            CssDialect::Scss => "SCSS",
+            CssDialect::Less => "Less",
+        }
+    }
+}
+
+/// The at-rules of SCSS, which CSS and Less do not have.
+const SCSS_AT_RULES: [&str; 13] = [
+    "@mixin",
+    "@include",
+    "@use",
+    "@forward",
+    "@extend",
+    "@function",
+    "@return",
+    "@if",
+    "@else",
+    "@each",
+    "@for",
+    "@while",
+    "@debug",
+];
+
+/// Removes the `/* */` comments, so commented out code is not detected.
+fn strip_block_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Whether a line starts with `prefix` followed by an identifier and a `:`, e.g. the
+/// variables `$brand: #000;` of SCSS and `@brand: #000;` of Less.
+fn is_variable_declaration(line: &str, prefix: char) -> bool {
+    let Some(rest) = line.strip_prefix(prefix) else {
+        return false;
+    };
+    let name_len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(rest.len());
+    name_len > 0 && rest[name_len..].starts_with(':')
+}
+
+fn is_scss_line(line: &str) -> bool {
+    let at_rule = line
+        .split(|c: char| c.is_whitespace() || c == '(' || c == '{' || c == ';')
+        .next()
+        .unwrap_or_default();
+
+    is_variable_declaration(line, '$')
+        || SCSS_AT_RULES.contains(&at_rule)
+        || line.contains("#{")
+        // Placeholder selectors, e.g. `%button {`
+        || (line.starts_with('%') && line.ends_with('{'))
+}
+
+fn is_less_line(line: &str) -> bool {
+    is_variable_declaration(line, '@')
+        // Mixin calls, e.g. `.rounded(4px);`
+        || (line.starts_with('.') && !line.contains('{') && line.ends_with(");"))
+        || line.contains(" when (")
+        || line.contains(":extend(")
+        || line.contains("~\"")
+        || line.starts_with("@import (")
+}
+
+/// Detects the dialect of a stylesheet from the syntax only SCSS or Less has, e.g. `$`
+/// variables, `@mixin`, `#{}` interpolation, `@` variables or mixin calls. Nesting is not
+/// looked at, since CSS has it too.
+///
+/// The dialect with the most lines of its syntax wins, and a stylesheet without any is CSS.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(detect_dialect("$brand: #000;\n.btn { color: $brand; }"), CssDialect::Scss);
+/// assert_eq!(detect_dialect("@brand: #000;\n.btn { .rounded(4px); }"), CssDialect::Less);
+/// assert_eq!(detect_dialect(".btn { & .icon { color: red; } }"), CssDialect::Css);
+/// ```
+pub fn detect_dialect(source: &str) -> CssDialect {
+    let source = strip_block_comments(source);
+    let (scss, less) = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//"))
+        .fold((0, 0), |(scss, less), line| {
+            (
+                scss + usize::from(is_scss_line(line)),
+                less + usize::from(is_less_line(line)),
+            )
+        });
+
+    match (scss, less) {
+        (0, 0) => CssDialect::Css,
+        (scss, less) if less > scss => CssDialect::Less,
+        _ => CssDialect::Scss,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_dialect() {
+        let scss = r#"@use "sass:math";
+$primary: #fd4f00;
+/* $commented: out; */
+.phx-modal {
+  @include shadow;
+  width: #{math.div(100, 2)}%;
+  &-content { color: $primary; }
+}
+"#;
+        assert_eq!(detect_dialect(scss), CssDialect::Scss);
+
+        let less = r#"@primary: #fd4f00;
+.phx-modal {
+  .rounded(4px);
+  &:extend(.modal all);
+}
+"#;
+        assert_eq!(detect_dialect(less), CssDialect::Less);
+
+        let css = r#"@import "tailwindcss";
+@plugin "../vendor/heroicons";
+@page :first { margin: 1in; }
+@media (min-width: 640px) { .a { color: red; } }
+:root { --brand: #000; }
+.btn { &:hover { color: var(--brand); } }
+"#;
+        assert_eq!(detect_dialect(css), CssDialect::Css);
+    }
+}
@@ -3,33 +3,68 @@
 //
 // SPDX-License-Identifier: MIT
 
+use crate::diagnostics::syntax_error_message;
+use crate::helpers::{catch_panic, check_formatted, line_ending, FormatCheck, FormatCheckOptions};
+use crate::parsers::css::dialect::{detect_dialect, CssDialect};
+use crate::parsers::css::validate::{parse_stylesheet, validate_css};
+use crate::telemetry::{measure, Phase};
 use biome_css_formatter::{context::CssFormatOptions, format_node};
-use biome_css_parser::{parse_css, CssParserOptions};
 use biome_css_syntax::CssFileSource;
 use biome_formatter::{IndentStyle, IndentWidth};
 
+/// The dialect of a stylesheet the parser fails on because it is SCSS or Less, `None` when
+/// it is CSS, whether it parses or not.
+pub fn unsupported_dialect(source_code: &str) -> Option<CssDialect> {
+    if parse_stylesheet(source_code).is_ok_and(|parsed| !parsed.has_errors()) {
+        return None;
+    }
+
+    match detect_dialect(source_code) {
+        CssDialect::Css => None,
+        dialect => Some(dialect),
+    }
+}
+
 pub fn format(source_code: &str) -> Result<String, String> {
-    let parsed = parse_css(source_code, CssParserOptions::default());
+    let parsed = parse_stylesheet(source_code)?;
 
     if parsed.has_errors() {
-        return Err("Parsing failed due to syntax errors.".into());
+        return match detect_dialect(source_code) {
+            CssDialect::Css => Err(syntax_error_message(&validate_css(source_code))),
+            dialect => Err(format!("{} is not supported, only CSS", dialect.name())),
+        };
     }
 
     let options = CssFormatOptions::new(CssFileSource::default())
         .with_indent_style(IndentStyle::Space)
-        .with_indent_width(IndentWidth::default());
-
-    let result = format_node(options, &parsed.syntax())
-        .map_err(|err| format!("Formatting failed: {}", err))?;
-
-    let formatted = result.print().map_err(|err| err.to_string())?;
-
-    Ok(formatted.into_code())
+        .with_indent_width(IndentWidth::default())
+        .with_line_ending(line_ending(source_code));
+
+    measure(Phase::Codegen, 0, || {
+        // Biome's formatter panics on some selectors, e.g. a type selector with non-ASCII
+        // letters like `Aⴀ`
+        let syntax = parsed.syntax();
+        let result = catch_panic(|| format_node(options, &syntax))
+            .ok_or_else(|| {
+                "Formatting failed: the formatter failed on this stylesheet".to_string()
+            })?
+            .map_err(|err| format!("Formatting failed: {}", err))?;
+
+        let formatted = result.print().map_err(|err| err.to_string())?;
+
+        Ok(formatted.into_code())
+    })
 }
 
 pub fn is_formatted(source_code: &str) -> Result<bool, String> {
+    Ok(check_format(source_code, FormatCheckOptions::default())?.formatted)
+}
+
+/// Checks if the stylesheet is formatted like [`is_formatted`], reporting the first line
+/// it differs on from its formatted version.
+pub fn check_format(source_code: &str, options: FormatCheckOptions) -> Result<FormatCheck, String> {
     let formatted_code = format(source_code)?;
-    Ok(formatted_code.trim() == source_code.trim())
+    Ok(check_formatted(source_code, &formatted_code, options))
 }
 
 #[cfg(test)]
@@ -78,9 +113,37 @@ h1 {
   font-size: 20px;
 }
 "#;
-        assert_eq!(is_formatted(css_unformatted).unwrap(), false);
+        assert!(!is_formatted(css_unformatted).unwrap());
 
         let formatted = format(css_formatted).unwrap();
-        assert_eq!(is_formatted(&formatted).unwrap(), true);
+        assert!(is_formatted(&formatted).unwrap());
+        assert!(is_formatted(&formatted.replace('\n', "\r\n")).unwrap());
+
+        let check = check_format(css_unformatted, FormatCheckOptions::default()).unwrap();
+        assert!(!check.formatted);
+        assert!(check.difference.is_some());
+    }
+
+    #[test]
+    fn test_format_unsupported_dialect() {
+        let scss = "$primary: #fd4f00;\n.btn {\n  &-primary { color: $primary; }\n}\n";
+        assert_eq!(format(scss).unwrap_err(), "SCSS is not supported, only CSS");
+        assert_eq!(unsupported_dialect(scss), Some(CssDialect::Scss));
+
+        assert_eq!(unsupported_dialect("a { color: red; }"), None);
+        assert_eq!(unsupported_dialect("a { color: red;"), None);
+
+        let error = format("a {\n  color: red;\n").unwrap_err();
+        assert!(error.starts_with("Parsing failed due to syntax errors.\n\n3:0: "));
+        assert!(error.contains("> 3 |"), "{}", error);
+    }
+
+    #[test]
+    fn test_format_css_the_formatter_fails_on() {
+        assert_eq!(
+            format("Aⴀ {\n  color: red;\n}\n"),
+            Err("Formatting failed: the formatter failed on this stylesheet".to_string())
+        );
+        assert!(format("@media (min-wi\n  .card { display: flex; }\n}\n").is_err());
     }
 }
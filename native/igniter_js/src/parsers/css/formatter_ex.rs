@@ -4,29 +4,76 @@
 // SPDX-License-Identifier: MIT
 
 use crate::atoms;
-use crate::helpers::encode_response;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_response, FormatCheckOptions};
 use crate::parsers::css::formatter::*;
 
 use rustler::{Env, NifResult, Term};
 
+/// Encodes an error as `{:unsupported_dialect, dialect}` when the stylesheet is SCSS or Less,
+/// so those files can be skipped, otherwise as the message.
+fn encode_error<'a>(
+    env: Env<'a>,
+    fn_atom: rustler::types::atom::Atom,
+    file_content: &str,
+    error_msg: String,
+) -> NifResult<Term<'a>> {
+    match unsupported_dialect(file_content) {
+        Some(dialect) => encode_response(
+            env,
+            atoms::error(),
+            fn_atom,
+            (atoms::unsupported_dialect(), dialect),
+        ),
+        None => encode_response(env, atoms::error(), fn_atom, error_msg),
+    }
+}
+
 #[rustler::nif]
-pub fn format_css_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::format_css_nif();
-    let (status, result) = match format(&file_content) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
-
-    encode_response(env, status, fn_atom, result)
+pub fn format_css_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::format_css_nif();
+        match format(&file_content) {
+            Ok(updated_code) => encode_response(env, atoms::ok(), fn_atom, updated_code),
+            Err(error_msg) => encode_error(env, fn_atom, &file_content, error_msg),
+        }
+    })
 }
 
 #[rustler::nif]
-pub fn is_css_formatted_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::is_css_formatted_nif();
-    let (status, result) = match is_formatted(&file_content) {
-        Ok(true) => (atoms::ok(), true),
-        _ => (atoms::error(), false),
-    };
-
-    encode_response(env, status, fn_atom, result)
+pub fn is_css_formatted_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::is_css_formatted_nif();
+        let (status, result) = match is_formatted(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn check_css_format_nif(
+    env: Env,
+    file_content: String,
+    options: FormatCheckOptions,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::check_css_format_nif();
+
+        match check_format(&file_content, options) {
+            Ok(check) => encode_response(env, atoms::ok(), fn_atom, check),
+            Err(error_msg) => encode_error(env, fn_atom, &file_content, error_msg),
+        }
+    })
 }
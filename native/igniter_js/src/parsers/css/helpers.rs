@@ -0,0 +1,350 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Helpers to work with CSS source text without a full parse.
+//!
+//! Biome does not understand framework at-rules such as Tailwind's `@plugin` or `@theme`, so
+//! edits that must keep those stylesheets intact work on the statements found by a small
+//! scanner instead. The scanner only tracks strings, comments and braces, which is enough
+//! to split a block into statements and leaves everything else byte-for-byte untouched.
+
+use crate::helpers::SourceStyle;
+
+/// Runs a text transform on a stylesheet without its byte order mark, and gives the result
+/// the line endings and byte order mark of the stylesheet, whatever the transform inserts.
+pub fn preserve_style(css: &str, transform: impl FnOnce(&str) -> String) -> String {
+    let style = SourceStyle::detect(css);
+    style.apply(&transform(css.strip_prefix('\u{feff}').unwrap_or(css)))
+}
+
+/// A statement of a CSS block: an at-rule, a rule or a declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    /// The byte offset where the statement starts.
+    pub start: usize,
+    /// The byte offset right after the statement (after its `;` or closing `}`).
+    pub end: usize,
+    /// The text before the block or the `;`, trimmed, e.g. `@import "tailwindcss"`.
+    pub prelude: String,
+    /// The byte offsets of the `{` and `}` of the statement block, if it has one.
+    pub block: Option<(usize, usize)>,
+}
+
+impl Statement {
+    /// The at-rule name without the `@`, e.g. `import`.
+    pub fn at_rule_name(&self) -> Option<&str> {
+        let rest = self.prelude.strip_prefix('@')?;
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        Some(&rest[..end])
+    }
+
+    /// The at-rule parameters, e.g. `"tailwindcss" source(none)`.
+    pub fn at_rule_params(&self) -> &str {
+        match self.at_rule_name() {
+            Some(name) => self.prelude[name.len() + 1..].trim(),
+            None => "",
+        }
+    }
+
+    /// The property name of a declaration, e.g. `--color-brand`.
+    pub fn declaration_name(&self) -> Option<&str> {
+        if self.block.is_some() || self.prelude.starts_with('@') {
+            return None;
+        }
+        self.prelude.split_once(':').map(|(name, _)| name.trim())
+    }
+}
+
+/// Skips a string or a comment starting at `index`, returning the offset right after it.
+fn skip_string_or_comment(bytes: &[u8], index: usize) -> Option<usize> {
+    match bytes[index] {
+        quote @ (b'"' | b'\'') => {
+            let mut i = index + 1;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b if b == quote => return Some(i + 1),
+                    _ => i += 1,
+                }
+            }
+            Some(bytes.len())
+        }
+        b'/' if bytes.get(index + 1) == Some(&b'*') => {
+            let close = bytes[index + 2..]
+                .windows(2)
+                .position(|window| window == b"*/")
+                .map(|position| index + 2 + position + 2);
+            Some(close.unwrap_or(bytes.len()))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the `}` matching the `{` at `open`.
+fn matching_brace(bytes: &[u8], open: usize, to: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = open;
+
+    while i < to {
+        if let Some(next) = skip_string_or_comment(bytes, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Splits the CSS between `from` and `to` into statements.
+///
+/// Use `0..css.len()` for the top-level statements, or the inside of a [`Statement::block`]
+/// for the statements of a block.
+pub fn statements(css: &str, from: usize, to: usize) -> Vec<Statement> {
+    let bytes = css.as_bytes();
+    let mut found = vec![];
+    let mut i = from;
+
+    'statements: while i < to {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            i = skip_string_or_comment(bytes, i).unwrap_or(to);
+            continue;
+        }
+
+        let start = i;
+        let mut parens = 0usize;
+        while i < to {
+            if let Some(next) = skip_string_or_comment(bytes, i) {
+                i = next;
+                continue;
+            }
+            match bytes[i] {
+                b'(' => parens += 1,
+                b')' => parens = parens.saturating_sub(1),
+                b';' if parens == 0 => {
+                    found.push(Statement {
+                        start,
+                        end: i + 1,
+                        prelude: css[start..i].trim().to_string(),
+                        block: None,
+                    });
+                    i += 1;
+                    continue 'statements;
+                }
+                b'{' => {
+                    let Some(close) = matching_brace(bytes, i, to) else {
+                        break 'statements;
+                    };
+                    found.push(Statement {
+                        start,
+                        end: close + 1,
+                        prelude: css[start..i].trim().to_string(),
+                        block: Some((i, close)),
+                    });
+                    i = close + 1;
+                    continue 'statements;
+                }
+                b'}' => break 'statements,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        // The last declaration of a block may omit its `;`
+        let prelude = css[start..to].trim();
+        if !prelude.is_empty() {
+            found.push(Statement {
+                start,
+                end: start + css[start..to].trim_end().len(),
+                prelude: prelude.to_string(),
+                block: None,
+            });
+        }
+        break;
+    }
+
+    found
+}
+
+/// Collapses the whitespace of a selector and drops its comments, so `.a  >\n.b` matches
+/// `.a > .b`. Its strings and escaped characters are kept as they are.
+pub fn normalize_selector(selector: &str) -> String {
+    let bytes = selector.as_bytes();
+    let mut normalized = String::with_capacity(selector.len());
+    let mut space = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let mut kept = None;
+        if let Some(next) = skip_string_or_comment(bytes, i) {
+            if bytes[i] != b'/' {
+                kept = Some(&selector[i..next]);
+            }
+            i = next;
+        } else {
+            let c = selector[i..].chars().next().expect("a char boundary");
+            let escaped = selector[i + 1..].chars().next().filter(|_| c == '\\');
+            let end = i + c.len_utf8() + escaped.map_or(0, char::len_utf8);
+            if c.is_whitespace() {
+                space = !normalized.is_empty();
+            } else {
+                kept = Some(&selector[i..end]);
+            }
+            i = end;
+        }
+
+        if let Some(kept) = kept {
+            if space {
+                normalized.push(' ');
+                space = false;
+            }
+            normalized.push_str(kept);
+        }
+    }
+
+    normalized
+}
+
+/// Returns the offset to insert a new line after the statement ending at `end`: the end of
+/// its line when only whitespace or a comment follows it there, otherwise `end` itself.
+pub fn line_end_after(css: &str, end: usize) -> usize {
+    let line_end = css[end..].find('\n').map_or(css.len(), |i| end + i);
+    let rest = css[end..line_end].trim();
+
+    if rest.is_empty() || (rest.starts_with("/*") && rest.ends_with("*/")) {
+        line_end
+    } else {
+        end
+    }
+}
+
+/// Returns the indentation of the line `offset` is on.
+pub fn line_indent(css: &str, offset: usize) -> &str {
+    let line_start = css[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &css[line_start..offset];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Appends declarations at the end of the block of `statement`, indented like its last
+/// declaration, or one level deeper than the statement when the block is empty.
+pub fn append_declarations(
+    css: &str,
+    statement: &Statement,
+    declarations: &[(&str, &str)],
+) -> String {
+    let Some((open, close)) = statement.block else {
+        return css.to_string();
+    };
+    if declarations.is_empty() {
+        return css.to_string();
+    }
+    let existing = statements(css, open + 1, close);
+
+    let indent = existing
+        .last()
+        .map(|declaration| line_indent(css, declaration.start).to_string())
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or_else(|| format!("{}  ", line_indent(css, statement.start)));
+
+    let (at, terminator) = match existing.last() {
+        // A last declaration without `;` needs one before anything is appended after it
+        Some(last) if !css[..last.end].ends_with(';') => (last.end, ";"),
+        Some(last) => (line_end_after(css, last.end).min(close), ""),
+        None => (open + 1, ""),
+    };
+    let lines: String = declarations
+        .iter()
+        .map(|(name, value)| format!("\n{}{}: {};", indent, name, value))
+        .collect();
+
+    // Move a closing brace that shares the line with the declarations to its own line
+    let (tail, rest) = if css[at..close].contains('\n') || !css[at..close].trim().is_empty() {
+        (String::new(), at)
+    } else {
+        (format!("\n{}", line_indent(css, statement.start)), close)
+    };
+
+    format!(
+        "{}{}{}{}{}",
+        &css[..at],
+        terminator,
+        lines,
+        tail,
+        &css[rest..]
+    )
+}
+
+/// Removes `start..end`, together with its line when nothing else is on it.
+pub fn remove_range(css: &str, start: usize, end: usize) -> String {
+    let line_start = css[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = css[end..].find('\n').map_or(css.len(), |i| end + i);
+
+    if css[line_start..start].trim().is_empty() && css[end..line_end].trim().is_empty() {
+        let remove_to = (line_end + 1).min(css.len());
+        format!("{}{}", &css[..line_start], &css[remove_to..])
+    } else {
+        format!("{}{}", &css[..start], &css[end..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statements() {
+        let css = r#"/* header; { */
+@import "tailwindcss" source(none);
+@plugin "../vendor/daisyui" {
+  themes: false;
+}
+a[href$=";"] { color: red }
+"#;
+
+        let top_level = statements(css, 0, css.len());
+        assert_eq!(top_level.len(), 3);
+        assert_eq!(top_level[0].at_rule_name(), Some("import"));
+        assert_eq!(
+            top_level[0].at_rule_params(),
+            "\"tailwindcss\" source(none)"
+        );
+        assert_eq!(top_level[1].prelude, "@plugin \"../vendor/daisyui\"");
+        assert_eq!(top_level[2].prelude, "a[href$=\";\"]");
+
+        let (open, close) = top_level[2].block.unwrap();
+        let nested = statements(css, open + 1, close);
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].declaration_name(), Some("color"));
+
+        let removed = remove_range(css, top_level[0].start, top_level[0].end);
+        assert!(removed.starts_with("/* header; { */\n@plugin"));
+    }
+
+    #[test]
+    fn test_normalize_selector() {
+        assert_eq!(normalize_selector(" .a  >\n .b "), ".a > .b");
+        assert_eq!(normalize_selector(".a /* > */ .b/**/.c"), ".a .b.c");
+        assert_eq!(
+            normalize_selector("a[title=\"a  /* b */\"]"),
+            "a[title=\"a  /* b */\"]"
+        );
+        assert_eq!(normalize_selector(".a\\  .b"), ".a\\  .b");
+    }
+}
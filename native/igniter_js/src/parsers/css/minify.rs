@@ -0,0 +1,399 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Minification and vendor prefixing of stylesheets.
+//!
+//! Both work on the source text with the scanner of [`crate::parsers::css::helpers`], like
+//! the Tailwind helpers, so they accept the framework at-rules Biome can not parse.
+
+use crate::parsers::css::helpers::{preserve_style, statements, Statement};
+use std::collections::HashMap;
+
+/// Characters no whitespace is needed after.
+const NO_SPACE_AFTER: &[char] = &['{', '}', ';', ',', ':', '>', '~', '('];
+/// Characters no whitespace is needed before.
+const NO_SPACE_BEFORE: &[char] = &['{', '}', ';', ',', '>', '~', ')', '!'];
+
+/// The end of the statement starting before `from`, the first `;`, `{` or `}` outside of a
+/// string or a comment, and whether it is a declaration, ending with `;` or `}`.
+fn statement_end(css: &str, from: usize) -> (usize, bool) {
+    let mut chars = css[from..]
+        .char_indices()
+        .map(|(index, c)| (from + index, c));
+    while let Some((index, c)) = chars.next() {
+        match c {
+            ';' | '}' => return (index, true),
+            '{' => return (index, false),
+            '"' | '\'' => {
+                while let Some((_, next)) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            '/' if css[index..].starts_with("/*") => {
+                let end = css[index + 2..]
+                    .find("*/")
+                    .map_or(css.len(), |end| index + 2 + end);
+                while chars.next().is_some_and(|(next, _)| next <= end) {}
+            }
+            _ => {}
+        }
+    }
+    (css.len(), true)
+}
+
+/// Minifies a stylesheet.
+///
+/// Comments are removed, except `/*! ... */` license comments, whitespace is collapsed and
+/// removed where it has no meaning, and the last `;` of every block is dropped. Strings are
+/// kept as is, and so is the whitespace around `+` and `-`, which `calc()` needs, and before
+/// the `:` of a selector, e.g. `.card :hover`, unlike the `:` of a declaration.
+///
+/// # Example
+/// ```rust
+/// let css = "/* Buttons */\n.btn > span,\n.link {\n  color: red;\n  margin: 0 auto;\n}\n";
+/// assert_eq!(minify_css(css), ".btn>span,.link{color:red;margin:0 auto}");
+/// ```
+pub fn minify_css(css: &str) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut pending_space = false;
+    let mut depth = 0usize;
+    // The end of the current statement, and whether it is a declaration
+    let mut statement = (0, false);
+    let mut chars = css.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        if c == '/' && css[index..].starts_with("/*") {
+            let end = css[index + 2..]
+                .find("*/")
+                .map_or(css.len(), |end| index + 2 + end + 2);
+            if css[index..].starts_with("/*!") {
+                output.push_str(&css[index..end]);
+            }
+            while chars.next_if(|(next, _)| *next < end).is_some() {}
+            continue;
+        }
+
+        if c == ':' && depth > 0 && index > statement.0 {
+            statement = statement_end(css, index);
+        }
+        let declaration_colon = c == ':' && depth > 0 && statement.1;
+
+        if pending_space
+            && !output.is_empty()
+            && !output.ends_with(NO_SPACE_AFTER)
+            && !output.ends_with("*/")
+            && !NO_SPACE_BEFORE.contains(&c)
+            && !declaration_colon
+        {
+            output.push(' ');
+        }
+        pending_space = false;
+
+        match c {
+            '"' | '\'' => {
+                output.push(c);
+                while let Some((_, next)) = chars.next() {
+                    output.push(next);
+                    if next == '\\' {
+                        if let Some((_, escaped)) = chars.next() {
+                            output.push(escaped);
+                        }
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            '{' => {
+                depth += 1;
+                output.push(c);
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if output.ends_with(';') {
+                    output.pop();
+                }
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// A declaration that needs a vendor prefix in the browsers released before a version.
+struct Prefix {
+    property: &'static str,
+    /// Only prefix declarations with this value, and prefix the value instead of the property.
+    value: Option<&'static str>,
+    prefix: &'static str,
+    engine: &'static str,
+    /// The first version supporting the unprefixed form, `None` when there is none yet.
+    unprefixed_since: Option<f64>,
+}
+
+const fn prefix(
+    property: &'static str,
+    prefix: &'static str,
+    engine: &'static str,
+    unprefixed_since: Option<f64>,
+) -> Prefix {
+    Prefix {
+        property,
+        value: None,
+        prefix,
+        engine,
+        unprefixed_since,
+    }
+}
+
+const PREFIXES: &[Prefix] = &[
+    prefix("user-select", "-webkit-", "safari", None),
+    prefix("user-select", "-webkit-", "chrome", Some(54.0)),
+    prefix("user-select", "-moz-", "firefox", Some(69.0)),
+    prefix("appearance", "-webkit-", "safari", Some(15.4)),
+    prefix("appearance", "-webkit-", "chrome", Some(84.0)),
+    prefix("appearance", "-moz-", "firefox", Some(80.0)),
+    prefix("backdrop-filter", "-webkit-", "safari", Some(18.0)),
+    prefix("background-clip", "-webkit-", "safari", Some(14.0)),
+    prefix("background-clip", "-webkit-", "chrome", Some(120.0)),
+    prefix("mask", "-webkit-", "safari", Some(15.4)),
+    prefix("mask", "-webkit-", "chrome", Some(120.0)),
+    prefix("mask-image", "-webkit-", "safari", Some(15.4)),
+    prefix("mask-image", "-webkit-", "chrome", Some(120.0)),
+    prefix("mask-size", "-webkit-", "safari", Some(15.4)),
+    prefix("mask-size", "-webkit-", "chrome", Some(120.0)),
+    prefix("mask-position", "-webkit-", "safari", Some(15.4)),
+    prefix("mask-position", "-webkit-", "chrome", Some(120.0)),
+    prefix("mask-repeat", "-webkit-", "safari", Some(15.4)),
+    prefix("mask-repeat", "-webkit-", "chrome", Some(120.0)),
+    prefix("hyphens", "-webkit-", "safari", Some(17.0)),
+    prefix("text-size-adjust", "-webkit-", "safari", None),
+    prefix("tab-size", "-moz-", "firefox", Some(91.0)),
+    prefix("box-decoration-break", "-webkit-", "safari", None),
+    prefix("box-decoration-break", "-webkit-", "chrome", Some(130.0)),
+    prefix("print-color-adjust", "-webkit-", "safari", Some(15.4)),
+    prefix("print-color-adjust", "-webkit-", "chrome", Some(136.0)),
+    Prefix {
+        property: "position",
+        value: Some("sticky"),
+        prefix: "-webkit-",
+        engine: "safari",
+        unprefixed_since: Some(13.0),
+    },
+];
+
+/// The engine whose prefixes a browser uses: Edge is Chromium and iOS Safari is Safari.
+fn engine(browser: &str) -> &str {
+    match browser {
+        "edge" => "chrome",
+        "ios_saf" | "ios" => "safari",
+        browser => browser,
+    }
+}
+
+/// The declarations to insert before `declaration`, e.g. `-webkit-user-select: none`.
+fn prefixed_declarations(declaration: &str, targets: &HashMap<String, f64>) -> Vec<String> {
+    let Some((name, value)) = declaration.split_once(':') else {
+        return vec![];
+    };
+    let (name, value) = (name.trim(), value.trim());
+    let mut prefixed: Vec<String> = vec![];
+
+    for rule in PREFIXES.iter().filter(|rule| rule.property == name) {
+        let needed = targets.iter().any(|(browser, version)| {
+            engine(browser) == rule.engine
+                && rule.unprefixed_since.is_none_or(|since| *version < since)
+        });
+        let declaration = match rule.value {
+            Some(rule_value) if value.starts_with(rule_value) => {
+                format!("{}: {}{}", name, rule.prefix, value)
+            }
+            Some(_) => continue,
+            None => format!("{}{}: {}", rule.prefix, name, value),
+        };
+
+        if needed && !prefixed.contains(&declaration) {
+            prefixed.push(declaration);
+        }
+    }
+
+    prefixed
+}
+
+fn collect_prefixes(
+    css: &str,
+    block: &[Statement],
+    targets: &HashMap<String, f64>,
+    insertions: &mut Vec<(usize, String)>,
+) {
+    let existing: Vec<String> = block
+        .iter()
+        .filter(|statement| statement.block.is_none())
+        .map(|statement| statement.prelude.replace(' ', ""))
+        .collect();
+
+    for statement in block {
+        if let Some((open, close)) = statement.block {
+            let nested = statements(css, open + 1, close);
+            collect_prefixes(css, &nested, targets, insertions);
+            continue;
+        }
+        if statement.declaration_name().is_none() {
+            continue;
+        }
+
+        let line_start = css[..statement.start].rfind('\n').map_or(0, |i| i + 1);
+        let before = &css[line_start..statement.start];
+        let separator = if before.trim().is_empty() {
+            format!("\n{}", before)
+        } else {
+            " ".to_string()
+        };
+
+        for declaration in prefixed_declarations(&statement.prelude, targets) {
+            if !existing.contains(&declaration.replace(' ', "")) {
+                insertions.push((statement.start, format!("{};{}", declaration, separator)));
+            }
+        }
+    }
+}
+
+/// Adds the vendor prefixed declarations the target browsers need.
+///
+/// # Arguments
+/// * `css` - The stylesheet.
+/// * `targets` - The oldest version to support of each browser, e.g. `{"safari": 14.0}`.
+///   Browsers are `chrome`, `edge`, `firefox`, `safari` and `ios_saf`.
+///
+/// # Returns
+/// The stylesheet with every prefixed declaration inserted right before its unprefixed
+/// declaration, unless the block already has it.
+///
+/// # Example
+/// ```rust
+/// let targets = HashMap::from([("safari".to_string(), 14.0)]);
+/// let css = ".btn {\n  user-select: none;\n}\n";
+/// assert_eq!(
+///     autoprefix_css(css, &targets),
+///     ".btn {\n  -webkit-user-select: none;\n  user-select: none;\n}\n"
+/// );
+/// ```
+pub fn autoprefix_css(css: &str, targets: &HashMap<String, f64>) -> String {
+    preserve_style(css, |css| {
+        let mut insertions = vec![];
+        collect_prefixes(
+            css,
+            &statements(css, 0, css.len()),
+            targets,
+            &mut insertions,
+        );
+
+        let mut output = css.to_string();
+        for (offset, text) in insertions.into_iter().rev() {
+            output.insert_str(offset, &text);
+        }
+        output
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_css() {
+        let css = r#"/*! License */
+@import "tailwindcss" source(none);
+@plugin "../vendor/heroicons";
+
+/* The theme */
+@theme {
+  --color-brand: #fd4f00;
+}
+
+@media screen and (min-width: 640px) {
+  .card :is(h1, h2) + p ,
+  a[title="a  ;  b"] {
+    width: calc(100% - 2rem) !important;
+    content: 'x }';
+  }
+}
+"#;
+
+        assert_eq!(
+            minify_css(css),
+            concat!(
+                "/*! License */@import \"tailwindcss\" source(none);",
+                "@plugin \"../vendor/heroicons\";@theme{--color-brand:#fd4f00}",
+                "@media screen and (min-width:640px){.card :is(h1,h2) + p,",
+                "a[title=\"a  ;  b\"]{width:calc(100% - 2rem)!important;content:'x }'}}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_minify_css_colons() {
+        let css = r#".card :hover { margin : 0 ; color:red }
+.card {
+  padding /* inner */ : 1rem;
+  content : "a :b { c" ;
+  background : url(data:image/png;base64,AAAA) ;
+  & :is(h1, h2) { font-weight : bold }
+  a :focus-visible, b:hover { outline : none }
+}
+"#;
+
+        assert_eq!(
+            minify_css(css),
+            concat!(
+                ".card :hover{margin:0;color:red}",
+                ".card{padding:1rem;content:\"a :b { c\";",
+                "background:url(data:image/png;base64,AAAA);",
+                "& :is(h1,h2){font-weight:bold}a :focus-visible,b:hover{outline:none}}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_autoprefix_css() {
+        let css = r#".btn {
+  -webkit-user-select: none;
+  user-select: none;
+  appearance: none;
+}
+@media (hover: hover) {
+  .sticky { position: sticky; top: 0 }
+}
+"#;
+
+        let targets = HashMap::from([("safari".to_string(), 12.0), ("firefox".to_string(), 100.0)]);
+        assert_eq!(
+            autoprefix_css(css, &targets),
+            r#".btn {
+  -webkit-user-select: none;
+  user-select: none;
+  -webkit-appearance: none;
+  appearance: none;
+}
+@media (hover: hover) {
+  .sticky { position: -webkit-sticky; position: sticky; top: 0 }
+}
+"#
+        );
+
+        let targets = HashMap::from([("chrome".to_string(), 130.0)]);
+        assert_eq!(autoprefix_css(css, &targets), css);
+    }
+}
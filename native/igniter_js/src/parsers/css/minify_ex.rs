@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::css::minify::*;
+
+use rustler::{Env, NifResult, Term};
+use std::collections::HashMap;
+
+#[rustler::nif]
+pub fn minify_css_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = minify_css(&file_content);
+        encode_response(env, atoms::ok(), atoms::minify_css_nif(), result)
+    })
+}
+
+#[rustler::nif]
+pub fn autoprefix_css_nif(
+    env: Env,
+    file_content: String,
+    targets: HashMap<String, f64>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = autoprefix_css(&file_content, &targets);
+        encode_response(env, atoms::ok(), atoms::autoprefix_css_nif(), result)
+    })
+}
@@ -3,5 +3,26 @@
 //
 // SPDX-License-Identifier: MIT
 
+pub mod declarations;
+#[cfg(feature = "nif")]
+pub mod declarations_ex;
+pub mod dialect;
 pub mod formatter;
+#[cfg(feature = "nif")]
 pub mod formatter_ex;
+pub mod helpers;
+pub mod minify;
+#[cfg(feature = "nif")]
+pub mod minify_ex;
+pub mod selectors;
+#[cfg(feature = "nif")]
+pub mod selectors_ex;
+pub mod tailwind;
+#[cfg(feature = "nif")]
+pub mod tailwind_ex;
+pub mod validate;
+#[cfg(feature = "nif")]
+pub mod validate_ex;
+pub mod variables;
+#[cfg(feature = "nif")]
+pub mod variables_ex;
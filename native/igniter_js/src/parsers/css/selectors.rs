@@ -0,0 +1,618 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Selector renaming, rule relocation and insertion, e.g. for the upgrade scripts of a
+//! component library renaming `.phx-modal` to `.modal`, or the dark mode overrides a theme
+//! installer adds under `@media (prefers-color-scheme: dark)`.
+//!
+//! The rules and selectors are found in the Biome CSS syntax tree, which keeps every byte of
+//! the source, and only their ranges are rewritten, so the rest of the stylesheet is left
+//! untouched. Selectors are compared by their tokens, so the comments and whitespace of a
+//! selector do not matter, unlike the whitespace in its strings, and `.sm\:flex` is the
+//! same class as `.sm\3a flex`.
+//!
+//! Inserting a rule in an at-rule works on the statements of the scanner of
+//! [`crate::parsers::css::helpers`] instead, since it also inserts the rules of Tailwind's
+//! `@layer` blocks, in stylesheets with the at-rules Biome can not parse, e.g. `@plugin`.
+
+use crate::parsers::css::helpers::*;
+use crate::parsers::css::validate::parse_stylesheet;
+use biome_css_syntax::{
+    CssClassSelector, CssIdSelector, CssQualifiedRule, CssRoot, CssSyntaxKind, CssSyntaxNode,
+};
+use biome_rowan::{AstNode, AstSeparatedList, Direction, TextRange};
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+
+/// Where [`move_rule`] puts a rule relative to the anchor rule.
+///
+/// On the Elixir side these are the atoms `:before` and `:after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum RulePosition {
+    Before,
+    After,
+}
+
+fn parse(css: &str) -> Result<CssRoot, String> {
+    parse_stylesheet(css).map(|parsed| parsed.tree())
+}
+
+fn range(range: TextRange) -> (usize, usize) {
+    (range.start().into(), range.end().into())
+}
+
+/// Resolves the escapes of a name, so `sm\:flex` and `sm\3a flex` are the same class.
+fn unescape(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 {
+            match chars.next_if(char::is_ascii_hexdigit) {
+                Some(digit) => hex.push(digit),
+                None => break,
+            }
+        }
+        if hex.is_empty() {
+            unescaped.extend(chars.next());
+            continue;
+        }
+        // A whitespace ends a hex escape and is part of it
+        chars.next_if(|c| c.is_whitespace());
+        let code = u32::from_str_radix(&hex, 16).ok().filter(|code| *code != 0);
+        unescaped.push(code.and_then(char::from_u32).unwrap_or('\u{FFFD}'));
+    }
+
+    unescaped
+}
+
+/// The tokens of a selector or a selector list, without its comments and whitespace, and
+/// with the escapes of its names resolved.
+fn selector_tokens(node: &CssSyntaxNode) -> Vec<String> {
+    node.descendants_tokens(Direction::Next)
+        .map(|token| match token.kind() {
+            // The descendant combinator
+            CssSyntaxKind::CSS_SPACE_LITERAL => " ".to_string(),
+            CssSyntaxKind::CSS_STRING_LITERAL => token.text_trimmed().to_string(),
+            _ => unescape(token.text_trimmed()),
+        })
+        .collect()
+}
+
+/// The tokens of a selector list given as text, or `None` when it is not a valid one.
+fn parse_selector(selector: &str) -> Option<Vec<String>> {
+    let root = parse(&format!("{} {{}}", selector.trim())).ok()?;
+    let rule = root
+        .syntax()
+        .descendants()
+        .find_map(CssQualifiedRule::cast)?;
+    Some(selector_tokens(rule.prelude().syntax()))
+}
+
+/// The ranges to replace to rename a selector.
+///
+/// A single class or id is renamed wherever it is used, e.g. `.modal` in `.modal:hover` and
+/// `.page .modal`, but not `.modal-content`. Any other selector is renamed where a rule is
+/// selected by exactly it, as one of the selectors of its list.
+fn rename_ranges(root: &CssRoot, from: &str) -> Vec<(usize, usize)> {
+    let Some(from) = parse_selector(from) else {
+        return vec![];
+    };
+    let descendants = root.syntax().descendants();
+
+    match from.as_slice() {
+        [prefix, name] if prefix == "." => descendants
+            .filter_map(CssClassSelector::cast)
+            .filter_map(|class| class.name().ok()?.value_token().ok())
+            .filter(|token| unescape(token.text_trimmed()) == *name)
+            .map(|token| {
+                let (start, end) = range(token.text_trimmed_range());
+                // Includes the `.`
+                (start - 1, end)
+            })
+            .collect(),
+        [prefix, name] if prefix == "#" => descendants
+            .filter_map(CssIdSelector::cast)
+            .filter_map(|id| id.name().ok()?.value_token().ok())
+            .filter(|token| unescape(token.text_trimmed()) == *name)
+            .map(|token| {
+                let (start, end) = range(token.text_trimmed_range());
+                (start - 1, end)
+            })
+            .collect(),
+        _ => descendants
+            .filter_map(CssQualifiedRule::cast)
+            .flat_map(|rule| rule.prelude().iter().flatten().collect::<Vec<_>>())
+            .filter(|selector| selector_tokens(selector.syntax()) == from)
+            .map(|selector| range(selector.syntax().text_trimmed_range()))
+            .collect(),
+    }
+}
+
+/// Renames a selector.
+///
+/// # Arguments
+/// * `css` - The stylesheet.
+/// * `from` - The selector to rename. A single class or id, e.g. `.phx-modal`, is renamed
+///   everywhere it is used, also in compound and complex selectors. Any other selector, e.g.
+///   `.modal > .title`, is renamed where it selects a rule on its own.
+/// * `to` - The new selector.
+///
+/// # Returns
+/// * `Ok(String)` - The updated stylesheet.
+/// * `Err(String)` - If the selector is not found.
+///
+/// # Example
+/// ```rust
+/// let css = ".phx-modal { color: red; }\n.phx-modal:hover, .phx-modal-content {}\n";
+/// let result = rename_selector(css, ".phx-modal", ".modal").unwrap();
+/// assert_eq!(result, ".modal { color: red; }\n.modal:hover, .phx-modal-content {}\n");
+/// ```
+pub fn rename_selector(css: &str, from: &str, to: &str) -> Result<String, String> {
+    let mut error = None;
+    let result = preserve_style(css, |css| {
+        let mut ranges = match parse(css) {
+            Ok(root) => rename_ranges(&root, from),
+            Err(parse_error) => {
+                error = Some(parse_error);
+                return css.to_string();
+            }
+        };
+        if ranges.is_empty() {
+            error = Some(format!("Selector `{}` was not found", from.trim()));
+        }
+
+        // Replaced from the end, so the offsets before them stay valid
+        ranges.sort_by_key(|(start, _)| std::cmp::Reverse(*start));
+        ranges.dedup();
+        ranges
+            .into_iter()
+            .fold(css.to_string(), |css, (start, end)| {
+                format!("{}{}{}", &css[..start], to.trim(), &css[end..])
+            })
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(result),
+    }
+}
+
+/// Finds the first rule whose selector list is `selector`.
+fn find_rule(root: &CssRoot, selector: &str) -> Option<(usize, usize)> {
+    let selector = parse_selector(selector)?;
+    root.syntax()
+        .descendants()
+        .filter_map(CssQualifiedRule::cast)
+        .find(|rule| selector_tokens(rule.prelude().syntax()) == selector)
+        .map(|rule| range(rule.syntax().text_trimmed_range()))
+}
+
+/// Moves a rule before or after another rule.
+///
+/// Rules are looked up by their whole selector list, e.g. `.btn, .button`. The moved rule is
+/// removed with its line when nothing else is on it, and put on its own line with the
+/// indentation of the anchor rule.
+///
+/// # Arguments
+/// * `css` - The stylesheet.
+/// * `selector` - The selector of the rule to move.
+/// * `anchor` - The selector of the rule to move it next to.
+/// * `position` - Whether the rule goes before or after the anchor.
+///
+/// # Returns
+/// * `Ok(String)` - The updated stylesheet.
+/// * `Err(String)` - If either rule is not found, or the anchor is the rule itself or nested
+///   in it.
+///
+/// # Example
+/// ```rust
+/// let css = ".a {}\n.b {}\n.c {}\n";
+/// assert_eq!(move_rule(css, ".c", ".a", RulePosition::Before).unwrap(), ".c {}\n.a {}\n.b {}\n");
+/// ```
+pub fn move_rule(
+    css: &str,
+    selector: &str,
+    anchor: &str,
+    position: RulePosition,
+) -> Result<String, String> {
+    let not_found = |selector: &str| format!("Selector `{}` was not found", selector.trim());
+    let mut error = None;
+
+    let result = preserve_style(css, |css| {
+        let root = match parse(css) {
+            Ok(root) => root,
+            Err(parse_error) => {
+                error = Some(parse_error);
+                return css.to_string();
+            }
+        };
+        let Some((start, end)) = find_rule(&root, selector) else {
+            error = Some(not_found(selector));
+            return css.to_string();
+        };
+        let Some((anchor_start, anchor_end)) = find_rule(&root, anchor) else {
+            error = Some(not_found(anchor));
+            return css.to_string();
+        };
+        if start <= anchor_start && anchor_end <= end {
+            error = Some(format!(
+                "The rule of `{}` can not be moved next to itself",
+                selector.trim()
+            ));
+            return css.to_string();
+        }
+
+        let rule = css[start..end].to_string();
+        let removed = remove_range(css, start, end);
+        // The parser recovers differently once the rule is removed from a broken stylesheet
+        let Some((anchor_start, anchor_end)) = parse(&removed)
+            .ok()
+            .and_then(|root| find_rule(&root, anchor))
+        else {
+            error = Some(not_found(anchor));
+            return css.to_string();
+        };
+        let indent = line_indent(&removed, anchor_start).to_string();
+
+        match position {
+            RulePosition::Before => {
+                let line_start = anchor_start - indent.len();
+                format!(
+                    "{}{}{}\n{}",
+                    &removed[..line_start],
+                    indent,
+                    rule,
+                    &removed[line_start..]
+                )
+            }
+            RulePosition::After => {
+                let at = line_end_after(&removed, anchor_end);
+                format!("{}\n{}{}{}", &removed[..at], indent, rule, &removed[at..])
+            }
+        }
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(result),
+    }
+}
+
+/// The parameters of a media query given with or without the `@media` keyword.
+fn media_params(query: &str) -> &str {
+    let query = query.trim();
+    match query.get(..6) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("@media") => query[6..].trim(),
+        _ => query,
+    }
+}
+
+/// Normalizes the parameters of a `@media` rule, so `(prefers-color-scheme:dark)` matches
+/// `(Prefers-Color-Scheme: dark)`.
+fn normalize_media_query(query: &str) -> String {
+    let collapsed = media_params(query)
+        .to_ascii_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let chars: Vec<char> = collapsed.chars().collect();
+
+    chars
+        .iter()
+        .enumerate()
+        .filter(|(index, c)| {
+            let punctuation = |c: Option<&char>| c.is_some_and(|c| "():,".contains(*c));
+            **c != ' '
+                || !(punctuation(index.checked_sub(1).and_then(|i| chars.get(i)))
+                    || punctuation(chars.get(index + 1)))
+        })
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Indents every line of a snippet but the first one.
+fn indent_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => line.to_string(),
+            _ if line.trim().is_empty() => String::new(),
+            _ => format!("{}{}", indent, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Inserts a rule inside the top-level `@media` block of a query, creating the block at the
+/// end of the stylesheet when it is missing.
+///
+/// When the block already has a rule with the same selector list, the declarations it does
+/// not have are appended to it instead, and the ones it has are kept as they are.
+///
+/// # Arguments
+/// * `css` - The stylesheet.
+/// * `query` - The media query, e.g. `(prefers-color-scheme: dark)`, with or without
+///   `@media`. It is matched ignoring case and whitespace.
+/// * `rule` - The rule to insert, e.g. `.btn { color: white; }`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated stylesheet, the same one when the rule is already there.
+/// * `Err(String)` - If `rule` is not a single rule with a block.
+///
+/// # Example
+/// ```rust
+/// let css = ".btn { color: black; }\n";
+/// let result = insert_rule_in_media(css, "(prefers-color-scheme: dark)", ".btn { color: white; }");
+/// assert_eq!(
+///     result.unwrap(),
+///     ".btn { color: black; }\n\n@media (prefers-color-scheme: dark) {\n  .btn { color: white; }\n}\n"
+/// );
+/// ```
+pub fn insert_rule_in_media(css: &str, query: &str, rule: &str) -> Result<String, String> {
+    insert_rule_in_at_rule(
+        css,
+        "media",
+        media_params(query),
+        normalize_media_query,
+        rule,
+    )
+}
+
+/// Inserts a rule inside the first top-level block of the at-rule `name` whose parameters
+/// match `params` once normalized, creating `@name params { ... }` at the end of the
+/// stylesheet when there is none, like [`insert_rule_in_media`] does for `@media`.
+pub(crate) fn insert_rule_in_at_rule(
+    css: &str,
+    name: &str,
+    params: &str,
+    normalize: fn(&str) -> String,
+    rule: &str,
+) -> Result<String, String> {
+    let rule = rule.trim();
+    let new_rule = match statements(rule, 0, rule.len()).as_slice() {
+        [statement] if statement.block.is_some() && statement.end == rule.len() => {
+            statement.clone()
+        }
+        _ => return Err(format!("`{}` is not a single CSS rule", rule)),
+    };
+    let selector = normalize_selector(&new_rule.prelude);
+    let (open, close) = new_rule.block.expect("the rule has a block");
+    let new_declarations: Vec<(&str, &str)> = statements(rule, open + 1, close)
+        .iter()
+        .filter(|declaration| declaration.declaration_name().is_some())
+        .filter_map(|declaration| {
+            let (name, value) = rule[declaration.start..declaration.end].split_once(':')?;
+            Some((name.trim(), value.trim().trim_end_matches(';').trim_end()))
+        })
+        .collect();
+    let normalized_params = normalize(params);
+
+    Ok(preserve_style(css, |css| {
+        let at_rule = statements(css, 0, css.len()).into_iter().find(|statement| {
+            statement.block.is_some()
+                && statement.at_rule_name() == Some(name)
+                && normalize(statement.at_rule_params()) == normalized_params
+        });
+        let Some(at_rule) = at_rule else {
+            let separator = match css.trim_end().len() {
+                0 => "",
+                length if css[length..].contains("\n\n") => "",
+                length if css[length..].contains('\n') => "\n",
+                _ => "\n\n",
+            };
+            return format!(
+                "{}{}@{} {} {{\n  {}\n}}\n",
+                css,
+                separator,
+                name,
+                params.trim(),
+                indent_lines(rule, "  ")
+            );
+        };
+
+        let (open, close) = at_rule.block.expect("the at-rule has a block");
+        let rules = statements(css, open + 1, close);
+        if let Some(existing) = rules.iter().find(|statement| {
+            statement.block.is_some() && normalize_selector(&statement.prelude) == selector
+        }) {
+            let (existing_open, existing_close) = existing.block.expect("rules have a block");
+            let existing_names: Vec<String> = statements(css, existing_open + 1, existing_close)
+                .iter()
+                .filter_map(|declaration| declaration.declaration_name().map(str::to_string))
+                .collect();
+            let missing: Vec<(&str, &str)> = new_declarations
+                .iter()
+                .filter(|(name, _)| !existing_names.iter().any(|existing| existing == name))
+                .copied()
+                .collect();
+            return append_declarations(css, existing, &missing);
+        }
+
+        let indent = rules
+            .last()
+            .map(|last| line_indent(css, last.start).to_string())
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or_else(|| format!("{}  ", line_indent(css, at_rule.start)));
+        let at = match rules.last() {
+            Some(last) => line_end_after(css, last.end).min(close),
+            None => open + 1,
+        };
+        // Move a closing brace that shares the line with the rules to its own line
+        let (tail, rest) = if css[at..close].contains('\n') {
+            (String::new(), &css[at..])
+        } else {
+            let tail = format!("\n{}", line_indent(css, at_rule.start));
+            (tail, css[at..].trim_start_matches([' ', '\t']))
+        };
+
+        format!(
+            "{}\n{}{}{}{}",
+            &css[..at],
+            indent,
+            indent_lines(rule, &indent),
+            tail,
+            rest
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_selector() {
+        let css = r#"/* .phx-modal */
+.phx-modal { color: red; }
+.page > .phx-modal:hover, .phx-modal-content { color: blue; }
+@media (min-width: 640px) {
+  .phx-modal.open, #phx-modal { display: block; }
+}
+"#;
+
+        let result = rename_selector(css, ".phx-modal", ".modal").unwrap();
+        assert_eq!(
+            result,
+            r#"/* .phx-modal */
+.modal { color: red; }
+.page > .modal:hover, .phx-modal-content { color: blue; }
+@media (min-width: 640px) {
+  .modal.open, #phx-modal { display: block; }
+}
+"#
+        );
+
+        let result = rename_selector(css, ".page  >  .phx-modal:hover", ".modal:hover").unwrap();
+        assert!(result.contains(".modal:hover, .phx-modal-content {"));
+
+        let result = rename_selector(css, "#phx-modal", "#modal").unwrap();
+        assert!(result.contains(".phx-modal.open, #modal {"));
+
+        assert!(rename_selector(css, ".missing", ".modal").is_err());
+        assert!(rename_selector(css, ".page", ".body").is_ok());
+    }
+
+    #[test]
+    fn test_rename_selector_strings_comments_and_escapes() {
+        let css = r#".sm\:flex { display: flex; }
+.md\3a flex, .sm\:flex:hover { display: block; }
+a[title="a  b"] /* link */ > .icon { color: red; }
+a[title="a b"] > .icon { color: blue; }
+.card/* inner */.open, #item\.1 { margin: 0; }
+"#;
+
+        // The same class, escaped differently
+        let result = rename_selector(css, ".sm\\3a flex", ".sm\\:grid").unwrap();
+        assert!(result.starts_with(".sm\\:grid { display: flex; }\n.md\\3a flex, .sm\\:grid:hover"));
+
+        // The whitespace of a string is kept, the comments and other whitespace are not
+        let result = rename_selector(css, "a[title=\"a  b\"]>.icon", ".link").unwrap();
+        assert!(result.contains(".link { color: red; }\na[title=\"a b\"] > .icon"));
+        let result = rename_selector(css, "a[title=\"a b\"] > .icon", ".text").unwrap();
+        assert!(result.contains("/* link */ > .icon { color: red; }\n.text { color: blue; }"));
+        assert!(rename_selector(css, "a[title=\"a   b\"] > .icon", ".link").is_err());
+
+        let result = rename_selector(css, ".card.open", ".panel").unwrap();
+        assert!(result.contains(".panel, #item\\.1 {"));
+        // A comment between two compound selectors is no descendant combinator
+        assert!(rename_selector(css, ".card .open", ".panel").is_err());
+        let result = rename_selector(css, "#item\\2e 1", "#entry").unwrap();
+        assert!(result.contains(".card/* inner */.open, #entry {"));
+
+        let result = move_rule(
+            css,
+            ".card.open, #item\\.1",
+            ".sm\\:flex",
+            RulePosition::Before,
+        );
+        assert!(result
+            .unwrap()
+            .starts_with(".card/* inner */.open, #item\\.1 { margin: 0; }\n.sm"));
+        assert!(rename_selector(css, "a[title=", ".link").is_err());
+    }
+
+    #[test]
+    fn test_insert_rule_in_media() {
+        let css = r#".btn { color: black; }
+
+@media (prefers-color-scheme: dark) {
+  .card { background: black; }
+}
+"#;
+
+        let result = insert_rule_in_media(
+            css,
+            "@media (Prefers-Color-Scheme:dark)",
+            ".btn {\n  color: white;\n}",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            r#".btn { color: black; }
+
+@media (prefers-color-scheme: dark) {
+  .card { background: black; }
+  .btn {
+    color: white;
+  }
+}
+"#
+        );
+
+        // The declarations missing from an existing rule are appended to it
+        let again = insert_rule_in_media(
+            &result,
+            "(prefers-color-scheme: dark)",
+            ".btn { color: gray; border: none; }",
+        )
+        .unwrap();
+        assert!(again.contains("  .btn {\n    color: white;\n    border: none;\n  }\n}"));
+        let same =
+            insert_rule_in_media(&again, "(prefers-color-scheme: dark)", ".btn { border: 0 }")
+                .unwrap();
+        assert_eq!(same, again);
+
+        // The block is created when missing, and a one-line block is split
+        let result = insert_rule_in_media(".a {}", "print", ".b { display: none; }").unwrap();
+        assert_eq!(
+            result,
+            ".a {}\n\n@media print {\n  .b { display: none; }\n}\n"
+        );
+        let result = insert_rule_in_media("@media print { .a {} }\r\n", "print", ".b {}").unwrap();
+        assert_eq!(result, "@media print { .a {}\r\n  .b {}\r\n}\r\n");
+
+        assert!(insert_rule_in_media(css, "print", "color: red;").is_err());
+        assert!(insert_rule_in_media(css, "print", ".a {} .b {}").is_err());
+    }
+
+    #[test]
+    fn test_move_rule() {
+        let css = "@media print {\r\n  .a { color: red; }\r\n}\r\n.b {}\r\n.c {\r\n  color: blue;\r\n}\r\n";
+
+        let result = move_rule(css, ".c", ".a", RulePosition::After).unwrap();
+        assert_eq!(
+            result,
+            "@media print {\r\n  .a { color: red; }\r\n  .c {\r\n  color: blue;\r\n}\r\n}\r\n.b {}\r\n"
+        );
+
+        let result = move_rule(css, ".b", "@media print", RulePosition::Before);
+        assert!(result.is_err());
+        let result = move_rule(css, ".b", ".c", RulePosition::After).unwrap();
+        assert_eq!(
+            result,
+            "@media print {\r\n  .a { color: red; }\r\n}\r\n.c {\r\n  color: blue;\r\n}\r\n.b {}\r\n"
+        );
+
+        assert!(move_rule(css, ".missing", ".a", RulePosition::Before).is_err());
+        assert!(move_rule(css, ".a", ".a", RulePosition::Before).is_err());
+    }
+}
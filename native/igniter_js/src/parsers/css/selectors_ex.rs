@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::css::selectors::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn rename_css_selector_nif(
+    env: Env,
+    file_content: String,
+    from: String,
+    to: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = rename_selector(&file_content, &from, &to);
+
+        encode_edit(env, atoms::rename_css_selector_nif(), &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn move_css_rule_nif(
+    env: Env,
+    file_content: String,
+    selector: String,
+    anchor: String,
+    position: RulePosition,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = move_rule(&file_content, &selector, &anchor, position);
+
+        encode_edit(env, atoms::move_css_rule_nif(), &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn insert_css_rule_in_media_nif(
+    env: Env,
+    file_content: String,
+    query: String,
+    rule: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_rule_in_media(&file_content, &query, &rule);
+
+        encode_edit(
+            env,
+            atoms::insert_css_rule_in_media_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
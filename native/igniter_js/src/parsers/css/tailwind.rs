@@ -0,0 +1,591 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Tailwind v4 Directives
+//!
+//! Tailwind v4 moves its configuration into the stylesheet: `@import "tailwindcss"`,
+//! `@source`, `@plugin` and `@theme { ... }`. The functions in this module insert and remove
+//! those directives idempotently, keeping the conventional order (imports, then sources,
+//! then plugins, then the theme) and leaving comments and unrelated rules untouched.
+//!
+//! The `@tailwind base;` directives of Tailwind v3 and the `@layer components { ... }`
+//! blocks component libraries add their rules to are handled the same way.
+
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+
+use crate::parsers::css::helpers::*;
+use crate::parsers::css::selectors::insert_rule_in_at_rule;
+
+/// A single-line Tailwind directive.
+///
+/// On the Elixir side these are the atoms `:import`, `:source` and `:plugin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum Directive {
+    Import,
+    Source,
+    Plugin,
+}
+
+impl Directive {
+    const ORDER: [Directive; 3] = [Directive::Import, Directive::Source, Directive::Plugin];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Directive::Import => "import",
+            Directive::Source => "source",
+            Directive::Plugin => "plugin",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Directive> {
+        Self::ORDER.into_iter().find(|kind| kind.name() == name)
+    }
+
+    fn rank(&self) -> usize {
+        Self::ORDER
+            .iter()
+            .position(|kind| kind == self)
+            .unwrap_or(0)
+    }
+}
+
+/// Returns the path a directive points to, without quotes or `url()`, e.g. `tailwindcss` for
+/// `"tailwindcss" source(none)`.
+fn directive_path(params: &str) -> &str {
+    let params = params.trim();
+
+    if let Some(rest) = params.strip_prefix("url(") {
+        let inner = rest.split(')').next().unwrap_or("");
+        return inner.trim().trim_matches(|c| c == '"' || c == '\'');
+    }
+
+    match params.chars().next() {
+        Some(quote @ ('"' | '\'')) => params[1..].split(quote).next().unwrap_or(""),
+        _ => params.split_whitespace().next().unwrap_or(""),
+    }
+}
+
+/// Quotes the path of the parameters unless they already start with a string or `url()`.
+fn normalize_params(params: &str) -> String {
+    let params = params.trim();
+    if params.starts_with('"') || params.starts_with('\'') || params.starts_with("url(") {
+        params.to_string()
+    } else {
+        format!("\"{}\"", params)
+    }
+}
+
+fn directive_of(statement: &Statement) -> Option<Directive> {
+    statement.at_rule_name().and_then(Directive::from_name)
+}
+
+/// A layer of Tailwind, named by `@tailwind` directives and `@layer` blocks.
+///
+/// On the Elixir side these are the atoms `:base`, `:components` and `:utilities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum TailwindLayer {
+    Base,
+    Components,
+    Utilities,
+}
+
+impl TailwindLayer {
+    const ORDER: [TailwindLayer; 3] = [
+        TailwindLayer::Base,
+        TailwindLayer::Components,
+        TailwindLayer::Utilities,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TailwindLayer::Base => "base",
+            TailwindLayer::Components => "components",
+            TailwindLayer::Utilities => "utilities",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<TailwindLayer> {
+        Self::ORDER.into_iter().find(|layer| layer.name() == name)
+    }
+}
+
+/// The layer of a `@tailwind` directive.
+fn tailwind_layer_of(statement: &Statement) -> Option<TailwindLayer> {
+    (statement.at_rule_name() == Some("tailwind") && statement.block.is_none())
+        .then(|| TailwindLayer::from_name(statement.at_rule_params()))
+        .flatten()
+}
+
+/// The layer of a `@layer` block.
+fn layer_block_of(statement: &Statement) -> Option<TailwindLayer> {
+    (statement.at_rule_name() == Some("layer") && statement.block.is_some())
+        .then(|| TailwindLayer::from_name(statement.at_rule_params()))
+        .flatten()
+}
+
+/// Inserts `text` as new lines after the last top-level statement accepted by `is_anchor`,
+/// or before the first statement that is not a `@charset` when there is none.
+pub(crate) fn insert_after_anchor(
+    css: &str,
+    text: &str,
+    is_anchor: impl Fn(&Statement) -> bool,
+    separator: &str,
+) -> String {
+    let top_level = statements(css, 0, css.len());
+
+    if let Some(anchor) = top_level
+        .iter()
+        .rev()
+        .find(|statement| is_anchor(statement))
+    {
+        let at = line_end_after(css, anchor.end);
+        return format!("{}{}{}{}", &css[..at], separator, text, &css[at..]);
+    }
+
+    match top_level
+        .iter()
+        .find(|statement| statement.at_rule_name() != Some("charset"))
+    {
+        Some(first) => {
+            let spacing = if directive_of(first).is_some() || tailwind_layer_of(first).is_some() {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            format!(
+                "{}{}{}{}",
+                &css[..first.start],
+                text,
+                spacing,
+                &css[first.start..]
+            )
+        }
+        None if css.trim().is_empty() => format!("{}\n", text),
+        None => format!("{}\n{}\n", css.trim_end(), text),
+    }
+}
+
+/// Inserts a Tailwind directive, unless one with the same kind and path already exists.
+///
+/// # Arguments
+/// * `css` - The stylesheet, usually `app.css`.
+/// * `kind` - The directive to insert.
+/// * `params` - The directive parameters, e.g. `../vendor/heroicons` or
+///   `"tailwindcss" source(none)`. A bare path is quoted.
+///
+/// # Returns
+/// The updated stylesheet. The directive is placed after the last directive of the same
+/// kind, or after the directives that conventionally precede it.
+///
+/// # Example
+/// ```rust
+/// let css = "@import \"tailwindcss\";\n";
+/// let result = insert_directive(css, Directive::Plugin, "../vendor/heroicons");
+/// assert_eq!(result, "@import \"tailwindcss\";\n@plugin \"../vendor/heroicons\";\n");
+/// ```
+pub fn insert_directive(css: &str, kind: Directive, params: &str) -> String {
+    preserve_style(css, |css| {
+        let params = normalize_params(params);
+        let path = directive_path(&params);
+
+        let exists = statements(css, 0, css.len()).iter().any(|statement| {
+            directive_of(statement) == Some(kind)
+                && directive_path(statement.at_rule_params()) == path
+        });
+        if exists {
+            return css.to_string();
+        }
+
+        let line = format!("@{} {};", kind.name(), params);
+        insert_after_anchor(
+            css,
+            &line,
+            |statement| {
+                statement.at_rule_name() == Some("charset")
+                    || directive_of(statement).is_some_and(|found| found.rank() <= kind.rank())
+            },
+            "\n",
+        )
+    })
+}
+
+/// Removes every Tailwind directive of the given kind pointing to `path`.
+///
+/// # Example
+/// ```rust
+/// let css = "@import \"tailwindcss\";\n@plugin \"../vendor/heroicons\";\n";
+/// let result = remove_directive(css, Directive::Plugin, "../vendor/heroicons");
+/// assert_eq!(result, "@import \"tailwindcss\";\n");
+/// ```
+pub fn remove_directive(css: &str, kind: Directive, path: &str) -> String {
+    preserve_style(css, |css| {
+        let path = directive_path(&normalize_params(path)).to_string();
+
+        statements(css, 0, css.len())
+            .iter()
+            .rev()
+            .filter(|statement| {
+                directive_of(statement) == Some(kind)
+                    && directive_path(statement.at_rule_params()) == path
+            })
+            .fold(css.to_string(), |css, statement| {
+                remove_range(&css, statement.start, statement.end)
+            })
+    })
+}
+
+/// Finds the `@theme` block to edit: the first plain `@theme`, or the first one with options
+/// (e.g. `@theme inline`) when there is no plain one.
+fn find_theme(css: &str) -> Option<Statement> {
+    let themes: Vec<Statement> = statements(css, 0, css.len())
+        .into_iter()
+        .filter(|statement| statement.at_rule_name() == Some("theme") && statement.block.is_some())
+        .collect();
+
+    themes
+        .iter()
+        .find(|theme| theme.at_rule_params().is_empty())
+        .or(themes.first())
+        .cloned()
+}
+
+/// Adds theme variables to the `@theme` block, creating the block after the directives when
+/// the stylesheet has none. Variables that are already defined keep their value.
+///
+/// # Arguments
+/// * `css` - The stylesheet, usually `app.css`.
+/// * `variables` - The variable names (with their `--` prefix) and values.
+///
+/// # Example
+/// ```rust
+/// let css = "@import \"tailwindcss\";\n";
+/// let result = put_theme_variables(css, &[("--color-brand", "#fd4f00")]);
+/// assert!(result.contains("@theme {\n  --color-brand: #fd4f00;\n}"));
+/// ```
+pub fn put_theme_variables(css: &str, variables: &[(&str, &str)]) -> String {
+    preserve_style(css, |css| {
+        let Some(theme) = find_theme(css) else {
+            if variables.is_empty() {
+                return css.to_string();
+            }
+            let body: String = variables
+                .iter()
+                .map(|(name, value)| format!("  {}: {};\n", name, value))
+                .collect();
+            let block = format!("@theme {{\n{}}}", body);
+            return insert_after_anchor(css, &block, |s| directive_of(s).is_some(), "\n\n");
+        };
+
+        let (open, close) = theme
+            .block
+            .expect("theme blocks are filtered on their block");
+        let declarations = statements(css, open + 1, close);
+
+        let missing: Vec<(&str, &str)> = variables
+            .iter()
+            .filter(|(name, _)| {
+                !declarations
+                    .iter()
+                    .any(|declaration| declaration.declaration_name() == Some(*name))
+            })
+            .copied()
+            .collect();
+
+        append_declarations(css, &theme, &missing)
+    })
+}
+
+/// Removes theme variables from the `@theme` block, and the block itself once it is empty.
+///
+/// # Example
+/// ```rust
+/// let css = "@theme {\n  --color-brand: #fd4f00;\n}\n";
+/// assert_eq!(remove_theme_variables(css, &["--color-brand"]), "");
+/// ```
+pub fn remove_theme_variables(css: &str, names: &[&str]) -> String {
+    preserve_style(css, |css| {
+        let Some(theme) = find_theme(css) else {
+            return css.to_string();
+        };
+        let (open, close) = theme
+            .block
+            .expect("theme blocks are filtered on their block");
+
+        let updated = statements(css, open + 1, close)
+            .iter()
+            .rev()
+            .filter(|declaration| {
+                declaration
+                    .declaration_name()
+                    .is_some_and(|name| names.contains(&name))
+            })
+            .fold(css.to_string(), |css, declaration| {
+                remove_range(&css, declaration.start, declaration.end)
+            });
+
+        match find_theme(&updated) {
+            Some(theme) => {
+                let (open, close) = theme
+                    .block
+                    .expect("theme blocks are filtered on their block");
+                if updated[open + 1..close].trim().is_empty() {
+                    remove_range(&updated, theme.start, theme.end)
+                } else {
+                    updated
+                }
+            }
+            None => updated,
+        }
+    })
+}
+
+/// The layers a stylesheet has `@tailwind` directives and `@layer` blocks for, in source
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct TailwindLayers {
+    pub directives: Vec<TailwindLayer>,
+    pub blocks: Vec<TailwindLayer>,
+}
+
+/// Finds the top-level `@tailwind` directives and `@layer` blocks of a stylesheet.
+///
+/// # Example
+/// ```rust
+/// let css = "@tailwind base;\n@layer components {\n  .btn { padding: 0; }\n}\n";
+/// let layers = find_layers(css);
+/// assert_eq!(layers.directives, [TailwindLayer::Base]);
+/// assert_eq!(layers.blocks, [TailwindLayer::Components]);
+/// ```
+pub fn find_layers(css: &str) -> TailwindLayers {
+    let top_level = statements(css, 0, css.len());
+    TailwindLayers {
+        directives: top_level.iter().filter_map(tailwind_layer_of).collect(),
+        blocks: top_level.iter().filter_map(layer_block_of).collect(),
+    }
+}
+
+/// Inserts the `@tailwind` directive of a layer unless the stylesheet already has it.
+///
+/// # Returns
+/// The updated stylesheet. The directive is placed after the `@tailwind` directives of the
+/// layers before it (`base`, then `components`, then `utilities`) and after the imports,
+/// which must come first.
+///
+/// # Example
+/// ```rust
+/// let css = "@tailwind base;\n@tailwind utilities;\n";
+/// let result = insert_layer_directive(css, TailwindLayer::Components);
+/// assert_eq!(result, "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n");
+/// ```
+pub fn insert_layer_directive(css: &str, layer: TailwindLayer) -> String {
+    preserve_style(css, |css| {
+        if find_layers(css).directives.contains(&layer) {
+            return css.to_string();
+        }
+
+        let rank = |layer: TailwindLayer| TailwindLayer::ORDER.iter().position(|l| *l == layer);
+        let line = format!("@tailwind {};", layer.name());
+        insert_after_anchor(
+            css,
+            &line,
+            |statement| {
+                matches!(statement.at_rule_name(), Some("charset" | "import"))
+                    || tailwind_layer_of(statement).is_some_and(|found| rank(found) < rank(layer))
+            },
+            "\n",
+        )
+    })
+}
+
+/// Inserts a rule inside the `@layer` block of a layer, creating the block at the end of
+/// the stylesheet when it is missing.
+///
+/// When the block already has a rule with the same selector list, the declarations it does
+/// not have are appended to it instead, so inserting the same rule again changes nothing.
+///
+/// # Returns
+/// * `Ok(String)` - The updated stylesheet.
+/// * `Err(String)` - If `rule` is not a single rule with a block.
+///
+/// # Example
+/// ```rust
+/// let css = "@tailwind components;\n";
+/// let result = insert_layer_rule(css, TailwindLayer::Components, ".btn { padding: 0; }");
+/// assert_eq!(
+///     result.unwrap(),
+///     "@tailwind components;\n\n@layer components {\n  .btn { padding: 0; }\n}\n"
+/// );
+/// ```
+pub fn insert_layer_rule(css: &str, layer: TailwindLayer, rule: &str) -> Result<String, String> {
+    insert_rule_in_at_rule(
+        css,
+        "layer",
+        layer.name(),
+        |params| params.trim().to_string(),
+        rule,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_css() -> &'static str {
+        r#"/* See the Tailwind configuration guide for advanced usage */
+@import "tailwindcss" source(none);
+@source "../css";
+@source "../js";
+
+/* A Tailwind plugin that makes "hero-#{ICON}" classes available. */
+@plugin "../vendor/heroicons";
+
+@theme {
+  --color-brand: #fd4f00;
+}
+
+body { color: red; }
+"#
+    }
+
+    #[test]
+    fn test_insert_directive() {
+        let css = app_css();
+
+        let result = insert_directive(css, Directive::Plugin, "../vendor/daisyui");
+        assert!(result.contains(
+            "@plugin \"../vendor/heroicons\";\n@plugin \"../vendor/daisyui\";\n\n@theme"
+        ));
+        assert_eq!(
+            insert_directive(&result, Directive::Plugin, "../vendor/daisyui"),
+            result
+        );
+
+        let result = insert_directive(css, Directive::Source, "'../../deps/backpex/**/*.*ex'");
+        assert!(result.contains("@source \"../js\";\n@source '../../deps/backpex/**/*.*ex';\n"));
+
+        let result = insert_directive(css, Directive::Import, "tailwindcss");
+        assert_eq!(result, css);
+
+        let result = insert_directive("body { color: red; }\n", Directive::Import, "tailwindcss");
+        assert_eq!(result, "@import \"tailwindcss\";\n\nbody { color: red; }\n");
+
+        let result = insert_directive("", Directive::Plugin, "../vendor/heroicons");
+        assert_eq!(result, "@plugin \"../vendor/heroicons\";\n");
+    }
+
+    #[test]
+    fn test_insert_directive_keeps_source_style() {
+        let css = "\u{feff}@import \"tailwindcss\";\r\n\r\nbody { color: red; }\r\n";
+        let result = insert_directive(css, Directive::Plugin, "../vendor/heroicons");
+        assert_eq!(
+            result,
+            "\u{feff}@import \"tailwindcss\";\r\n@plugin \"../vendor/heroicons\";\r\n\r\nbody { color: red; }\r\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_directive() {
+        let css = app_css();
+
+        let result = remove_directive(css, Directive::Source, "../js");
+        assert!(result.contains("@source \"../css\";\n\n/* A Tailwind plugin"));
+        assert_eq!(
+            remove_directive(&result, Directive::Source, "../js"),
+            result
+        );
+
+        let result = remove_directive(css, Directive::Import, "\"tailwindcss\"");
+        assert!(result
+            .starts_with("/* See the Tailwind configuration guide for advanced usage */\n@source"));
+    }
+
+    #[test]
+    fn test_theme_variables() {
+        let css = app_css();
+
+        let result = put_theme_variables(
+            css,
+            &[("--color-brand", "red"), ("--font-display", "Satoshi")],
+        );
+        assert!(
+            result.contains("@theme {\n  --color-brand: #fd4f00;\n  --font-display: Satoshi;\n}")
+        );
+        assert_eq!(
+            put_theme_variables(&result, &[("--font-display", "Satoshi")]),
+            result
+        );
+
+        let result = remove_theme_variables(&result, &["--font-display"]);
+        assert_eq!(result, css);
+
+        let result = remove_theme_variables(css, &["--color-brand"]);
+        assert!(result.contains("@plugin \"../vendor/heroicons\";\n\n\nbody"));
+
+        let result = put_theme_variables(&result, &[("--color-brand", "#fd4f00")]);
+        assert!(result.contains(
+            "@plugin \"../vendor/heroicons\";\n\n@theme {\n  --color-brand: #fd4f00;\n}\n"
+        ));
+
+        let inline = "@theme inline { --font-sans: var(--x) }";
+        let result = put_theme_variables(inline, &[("--font-mono", "monospace")]);
+        assert_eq!(
+            result,
+            "@theme inline { --font-sans: var(--x);\n  --font-mono: monospace;\n}"
+        );
+    }
+
+    #[test]
+    fn test_tailwind_layers() {
+        let css = "@import \"./fonts.css\";\n@tailwind utilities;\n\nbody { margin: 0; }\n";
+
+        let result = insert_layer_directive(css, TailwindLayer::Base);
+        let result = insert_layer_directive(&result, TailwindLayer::Components);
+        assert_eq!(
+            result,
+            "@import \"./fonts.css\";\n@tailwind base;\n@tailwind components;\n@tailwind utilities;\n\nbody { margin: 0; }\n"
+        );
+        assert_eq!(insert_layer_directive(&result, TailwindLayer::Base), result);
+
+        let result = insert_layer_rule(
+            &result,
+            TailwindLayer::Components,
+            ".btn {\n  padding: 0;\n}",
+        )
+        .unwrap();
+        assert!(result.ends_with(
+            "body { margin: 0; }\n\n@layer components {\n  .btn {\n    padding: 0;\n  }\n}\n"
+        ));
+
+        // Rules are appended into the existing block, and merged by selector
+        let result =
+            insert_layer_rule(&result, TailwindLayer::Components, ".card { margin: 0; }").unwrap();
+        let result = insert_layer_rule(
+            &result,
+            TailwindLayer::Components,
+            ".btn { padding: 0; color: red; }",
+        )
+        .unwrap();
+        assert!(result.ends_with(
+            "@layer components {\n  .btn {\n    padding: 0;\n    color: red;\n  }\n  .card { margin: 0; }\n}\n"
+        ));
+        assert_eq!(
+            insert_layer_rule(&result, TailwindLayer::Components, ".card { margin: 0; }").unwrap(),
+            result
+        );
+
+        let layers = find_layers(&result);
+        assert_eq!(layers.directives, TailwindLayer::ORDER);
+        assert_eq!(layers.blocks, [TailwindLayer::Components]);
+
+        assert_eq!(
+            insert_layer_directive("", TailwindLayer::Base),
+            "@tailwind base;\n"
+        );
+    }
+}
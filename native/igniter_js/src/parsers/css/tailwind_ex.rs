@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_edit, encode_response, Edit};
+use crate::parsers::css::tailwind::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn insert_tailwind_directive_nif(
+    env: Env,
+    file_content: String,
+    kind: Directive,
+    params: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_directive(&file_content, kind, &params);
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::insert_tailwind_directive_nif(),
+            Edit::new(&file_content, result),
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn remove_tailwind_directive_nif(
+    env: Env,
+    file_content: String,
+    kind: Directive,
+    path: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = remove_directive(&file_content, kind, &path);
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::remove_tailwind_directive_nif(),
+            Edit::new(&file_content, result),
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn put_tailwind_theme_variables_nif(
+    env: Env,
+    file_content: String,
+    variables: Vec<(String, String)>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let variables: Vec<(&str, &str)> = variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let result = put_theme_variables(&file_content, &variables);
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::put_tailwind_theme_variables_nif(),
+            Edit::new(&file_content, result),
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn remove_tailwind_theme_variables_nif(
+    env: Env,
+    file_content: String,
+    names: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        let result = remove_theme_variables(&file_content, &names);
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::remove_tailwind_theme_variables_nif(),
+            Edit::new(&file_content, result),
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn find_tailwind_layers_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = find_layers(&file_content);
+        encode_response(env, atoms::ok(), atoms::find_tailwind_layers_nif(), result)
+    })
+}
+
+#[rustler::nif]
+pub fn insert_tailwind_layer_directive_nif(
+    env: Env,
+    file_content: String,
+    layer: TailwindLayer,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_layer_directive(&file_content, layer);
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::insert_tailwind_layer_directive_nif(),
+            Edit::new(&file_content, result),
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn insert_tailwind_layer_rule_nif(
+    env: Env,
+    file_content: String,
+    layer: TailwindLayer,
+    rule: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_layer_rule(&file_content, layer, &rule);
+
+        encode_edit(
+            env,
+            atoms::insert_tailwind_layer_rule_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Parse-only validation of stylesheets, so a broken file is reported before it is patched.
+
+use crate::diagnostics::{Diagnostic, DiagnosticSeverity};
+use crate::helpers::catch_panic;
+use crate::limits::{check_bracket_depth, check_input_size, check_node_count};
+use crate::telemetry::{measure, Phase};
+use biome_css_parser::{parse_css, CssParse, CssParserOptions};
+
+/// Parses a stylesheet with Biome under the limits of [`crate::limits`], failing instead of
+/// panicking on the few sources its parser gets stuck on, e.g. `@media (min-wi` followed by
+/// a rule.
+pub fn parse_stylesheet(source_code: &str) -> Result<CssParse, String> {
+    check_input_size(source_code.len())?;
+    check_bracket_depth(source_code, false)?;
+
+    let parsed = measure(Phase::Parse, source_code.len(), || {
+        catch_panic(|| parse_css(source_code, CssParserOptions::default()))
+            .ok_or_else(|| "The CSS parser failed on this stylesheet".to_string())
+    })?;
+    check_node_count(|| parsed.syntax().descendants().count())?;
+    Ok(parsed)
+}
+
+/// Parses a stylesheet and returns every syntax error found.
+///
+/// Framework at-rules Biome does not know, such as Tailwind's `@plugin` or `@theme`, are
+/// reported like any other unknown syntax, see [`crate::parsers::css::helpers`].
+///
+/// # Example
+/// ```rust
+/// let diagnostics = validate_css("a {\n  color: red;\n");
+/// assert_eq!(diagnostics.len(), 1);
+/// assert!(validate_css("a { color: red; }").is_empty());
+/// ```
+pub fn validate_css(source_code: &str) -> Vec<Diagnostic> {
+    let parsed = match parse_stylesheet(source_code) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            return vec![Diagnostic::new(
+                source_code,
+                message,
+                DiagnosticSeverity::Error,
+                None,
+                0,
+                0,
+            )]
+        }
+    };
+
+    parsed
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| Diagnostic::from_biome(source_code, diagnostic))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_css() {
+        let source = "body {\n  color: red;\n}\n\nh1 {\n  font-size: 20px;\n";
+        let diagnostics = validate_css(source);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.line, 7);
+        assert!(diagnostic.code_frame.contains("  6 |   font-size: 20px;"));
+
+        assert!(validate_css("body { color: red; }").is_empty());
+    }
+
+    #[test]
+    fn test_parse_stylesheet_stuck() {
+        // A media query cut in the middle of a feature, which Biome's parser panics on
+        let source = "@media (min-wi\n  .card { display: flex; }\n}\n";
+        assert!(parse_stylesheet(source).is_err());
+
+        let diagnostics = validate_css(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+}
@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! CSS custom properties, e.g. the brand colors a theme installer sets in the `:root` block
+//! of `app.css`. Like the Tailwind helpers, the edits work on the source text, so the other
+//! declarations, comments and formatting are kept as they are.
+
+use crate::parsers::css::helpers::*;
+use crate::parsers::css::tailwind::insert_after_anchor;
+
+/// The selector of the custom properties when none is given.
+pub const ROOT_SELECTOR: &str = ":root";
+
+/// Finds the first top-level rule of the selector.
+fn find_rule(css: &str, selector: &str) -> Option<Statement> {
+    let selector = normalize_selector(selector);
+    statements(css, 0, css.len()).into_iter().find(|statement| {
+        statement.block.is_some()
+            && !statement.prelude.starts_with('@')
+            && normalize_selector(&statement.prelude) == selector
+    })
+}
+
+/// Replaces the value of a declaration, keeping its `!important` flag.
+fn replace_value(css: &str, declaration: &Statement, value: &str) -> String {
+    let text = &css[declaration.start..declaration.end];
+    let Some(colon) = text.find(':') else {
+        return css.to_string();
+    };
+    let value_start = declaration.start + colon + 1;
+    let value_end = declaration.start + text.strip_suffix(';').unwrap_or(text).trim_end().len();
+
+    let current = css[value_start..value_end].trim();
+    let important = current.strip_suffix("!important").is_some() && !value.contains("!important");
+    let value = if important {
+        format!("{} !important", value)
+    } else {
+        value.to_string()
+    };
+    if current == value {
+        return css.to_string();
+    }
+
+    format!("{} {}{}", &css[..value_start], value, &css[value_end..])
+}
+
+/// Sets custom properties in the block of a selector, `:root` by default: existing
+/// declarations get the new value, the others are appended to the block, and the block is
+/// created after the top-level directives, e.g. `@import`, when the stylesheet has none.
+///
+/// # Arguments
+/// * `css` - The stylesheet, usually `app.css`.
+/// * `selector` - The selector of the block, e.g. `:root` or `[data-theme="dark"]`.
+/// * `variables` - The property names (with their `--` prefix) and values.
+///
+/// # Returns
+/// * `Ok(String)` - The updated stylesheet, the same one when every value is already set.
+/// * `Err(String)` - If a name is not a custom property.
+///
+/// # Example
+/// ```rust
+/// let css = ":root {\n  --brand: #000;\n  --radius: 4px;\n}\n";
+/// let result = put_custom_properties(css, ROOT_SELECTOR, &[("--brand", "#fd4f00")]).unwrap();
+/// assert_eq!(result, ":root {\n  --brand: #fd4f00;\n  --radius: 4px;\n}\n");
+/// ```
+pub fn put_custom_properties(
+    css: &str,
+    selector: &str,
+    variables: &[(&str, &str)],
+) -> Result<String, String> {
+    if let Some((name, _)) = variables.iter().find(|(name, _)| {
+        !name.starts_with("--") || name.len() == 2 || name.contains(char::is_whitespace)
+    }) {
+        return Err(format!("`{}` is not a custom property", name));
+    }
+
+    Ok(preserve_style(css, |css| {
+        let Some(rule) = find_rule(css, selector) else {
+            if variables.is_empty() {
+                return css.to_string();
+            }
+            let body: String = variables
+                .iter()
+                .map(|(name, value)| format!("  {}: {};\n", name, value))
+                .collect();
+            let block = format!("{} {{\n{}}}", selector.trim(), body);
+            let is_directive = |statement: &Statement| {
+                statement.at_rule_name().is_some() && statement.block.is_none()
+            };
+            return insert_after_anchor(css, &block, is_directive, "\n\n");
+        };
+
+        let (open, close) = rule.block.expect("rules are filtered on their block");
+        let declarations = statements(css, open + 1, close);
+
+        // The values are replaced from the end, so the offsets before them stay valid
+        let mut replaced: Vec<(&Statement, &str)> = declarations
+            .iter()
+            .filter_map(|declaration| {
+                let name = declaration.declaration_name()?;
+                let (_, value) = variables.iter().rev().find(|(n, _)| *n == name)?;
+                Some((declaration, *value))
+            })
+            .collect();
+        replaced.sort_by_key(|(declaration, _)| std::cmp::Reverse(declaration.start));
+        let updated = replaced
+            .into_iter()
+            .fold(css.to_string(), |css, (declaration, value)| {
+                replace_value(&css, declaration, value)
+            });
+
+        let mut missing: Vec<(&str, &str)> = vec![];
+        for (name, value) in variables {
+            let defined = declarations
+                .iter()
+                .any(|declaration| declaration.declaration_name() == Some(*name));
+            match missing.iter_mut().find(|(n, _)| n == name) {
+                Some(entry) => entry.1 = value,
+                None if !defined => missing.push((name, value)),
+                None => {}
+            }
+        }
+
+        let rule = find_rule(&updated, selector).expect("the rule is still there");
+        append_declarations(&updated, &rule, &missing)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_custom_properties() {
+        let css = r#"@import "tailwindcss";
+
+/* Brand */
+:root {
+  --brand: #000; /* primary */
+  --accent: red !important;
+  --radius: 4px
+}
+
+.btn { color: var(--brand); }
+"#;
+
+        let result = put_custom_properties(
+            css,
+            ROOT_SELECTOR,
+            &[
+                ("--brand", "#fd4f00"),
+                ("--accent", "blue"),
+                ("--spacing", "0.25rem"),
+                ("--radius", "4px"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            r#"@import "tailwindcss";
+
+/* Brand */
+:root {
+  --brand: #fd4f00; /* primary */
+  --accent: blue !important;
+  --radius: 4px;
+  --spacing: 0.25rem;
+}
+
+.btn { color: var(--brand); }
+"#
+        );
+
+        let again =
+            put_custom_properties(&result, ROOT_SELECTOR, &[("--spacing", "0.25rem")]).unwrap();
+        assert_eq!(again, result);
+    }
+
+    #[test]
+    fn test_put_custom_properties_creates_the_block() {
+        let css = "@import \"tailwindcss\";\r\n\r\n.btn {}\r\n";
+        let result =
+            put_custom_properties(css, "[data-theme=\"dark\"]", &[("--brand", "#fff")]).unwrap();
+        assert_eq!(
+            result,
+            "@import \"tailwindcss\";\r\n\r\n[data-theme=\"dark\"] {\r\n  --brand: #fff;\r\n}\r\n\r\n.btn {}\r\n"
+        );
+
+        assert!(put_custom_properties(css, ROOT_SELECTOR, &[("color", "red")]).is_err());
+    }
+}
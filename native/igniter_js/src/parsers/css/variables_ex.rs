@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::css::variables::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn put_css_custom_properties_nif(
+    env: Env,
+    file_content: String,
+    selector: String,
+    variables: Vec<(String, String)>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let variables: Vec<(&str, &str)> = variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let result = put_custom_properties(&file_content, &selector, &variables);
+
+        encode_edit(
+            env,
+            atoms::put_css_custom_properties_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
@@ -0,0 +1,273 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # HTML/HEEx Script Helpers
+//!
+//! Layouts such as `root.html.heex` sometimes carry inline `<script>` blocks that installers
+//! need to patch. This module locates those blocks in an HTML string, runs the JavaScript
+//! transforms on their contents and splices the result back, leaving the surrounding markup
+//! byte-for-byte untouched.
+
+use crate::parsers::asset::patch::{apply_js_patch, JsPatchOp};
+
+/// An inline `<script>` block found in an HTML document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptBlock {
+    /// The position of the block among all the script blocks of the document.
+    pub index: usize,
+    /// The byte offset where the script content starts (right after the opening tag).
+    pub start: usize,
+    /// The byte offset where the script content ends (right before the closing tag).
+    pub end: usize,
+    /// The raw attributes of the opening tag, e.g. `type="module" defer`.
+    pub attributes: String,
+    /// The script content.
+    pub content: String,
+}
+
+fn find_ignore_case(haystack: &str, needle: &str, from: usize) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+        .map(|position| position + from)
+}
+
+/// Finds the `>` closing an opening tag, ignoring the ones inside quoted attribute values
+/// and HEEx expressions such as `:type={ColocatedHook}`.
+fn find_tag_end(html: &str, from: usize) -> Option<usize> {
+    let mut quote = None;
+    let mut braces = 0usize;
+
+    for (offset, ch) in html[from..].char_indices() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '{') => braces += 1,
+            (None, '}') => braces = braces.saturating_sub(1),
+            (None, '>') if braces == 0 => return Some(from + offset),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Tells whether the script block holds JavaScript, based on its attributes.
+///
+/// Scripts loaded from a `src` and data blocks such as `type="application/json"` are skipped.
+fn is_javascript(attributes: &str) -> bool {
+    let attributes = attributes.to_ascii_lowercase();
+    let has_src = attributes
+        .split_whitespace()
+        .any(|attribute| attribute.starts_with("src=") || attribute == "src");
+
+    let script_type = attributes.split_whitespace().find_map(|attribute| {
+        attribute
+            .strip_prefix("type=")
+            .map(|value| value.trim_matches(|c| c == '"' || c == '\''))
+    });
+
+    !has_src
+        && match script_type {
+            None => true,
+            Some(script_type) => matches!(
+                script_type,
+                "module" | "text/javascript" | "application/javascript"
+            ),
+        }
+}
+
+/// Locates the inline JavaScript `<script>` blocks of an HTML or HEEx document.
+///
+/// # Arguments
+/// * `html` - The HTML (or HEEx) document as a string.
+///
+/// # Returns
+/// The inline script blocks in document order. Blocks with a `src` attribute or a
+/// non-JavaScript `type` are not returned.
+pub fn find_script_blocks(html: &str) -> Vec<ScriptBlock> {
+    let mut blocks = vec![];
+    let mut cursor = 0;
+
+    while let Some(tag_start) = find_ignore_case(html, "<script", cursor) {
+        let name_end = tag_start + "<script".len();
+        // `<scripts>` or `<script-foo>` are other elements
+        if !matches!(
+            html.as_bytes().get(name_end),
+            Some(b' ' | b'\t' | b'\n' | b'\r' | b'>' | b'/')
+        ) {
+            cursor = name_end;
+            continue;
+        }
+
+        let Some(tag_end) = find_tag_end(html, name_end) else {
+            break;
+        };
+        let Some(close_start) = find_ignore_case(html, "</script", tag_end) else {
+            break;
+        };
+
+        let attributes = html[name_end..tag_end].trim().to_string();
+        if is_javascript(&attributes) {
+            blocks.push(ScriptBlock {
+                index: blocks.len(),
+                start: tag_end + 1,
+                end: close_start,
+                attributes,
+                content: html[tag_end + 1..close_start].to_string(),
+            });
+        }
+
+        cursor = close_start + "</script".len();
+    }
+
+    blocks
+}
+
+/// Re-indents generated code with the indentation the original script content used, and
+/// keeps its leading and trailing whitespace, so the splice blends into the markup.
+fn fit_into_block(original: &str, generated: &str) -> String {
+    let indent: String = original
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).collect())
+        .unwrap_or_default();
+
+    let leading = &original[..original.len() - original.trim_start().len()];
+    let trailing = &original[original.trim_end().len()..];
+
+    let body = generated
+        .trim()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let leading = if leading.contains('\n') {
+        leading
+            .rsplit_once('\n')
+            .map(|(before, _)| before)
+            .unwrap_or("")
+            .to_string()
+            + "\n"
+    } else {
+        String::new()
+    };
+
+    format!("{}{}{}", leading, body, trailing)
+}
+
+/// Applies JavaScript operations to inline script blocks and splices the results back.
+///
+/// # Arguments
+/// * `html` - The HTML (or HEEx) document as a string.
+/// * `index` - The index of the block to patch (see [`find_script_blocks`]), or `None` to
+///   patch every inline JavaScript block.
+/// * `ops` - The operations to apply to the script content.
+///
+/// # Returns
+/// * `Ok(String)` - The document with the patched script blocks; everything outside the
+///   patched blocks is left untouched.
+/// * `Err(String)` - If the block does not exist, or an operation fails on a block (e.g.,
+///   the block contains HEEx interpolation that is not valid JavaScript).
+pub fn patch_script_blocks(
+    html: &str,
+    index: Option<usize>,
+    ops: &[JsPatchOp],
+) -> Result<String, String> {
+    let blocks: Vec<ScriptBlock> = find_script_blocks(html)
+        .into_iter()
+        .filter(|block| index.is_none_or(|index| block.index == index))
+        .collect();
+
+    if let (Some(index), true) = (index, blocks.is_empty()) {
+        return Err(format!("Script block {} does not exist", index));
+    }
+
+    let mut result = html.to_string();
+    // Splice from the end, so the offsets of the remaining blocks stay valid
+    for block in blocks.iter().rev() {
+        let patched = apply_js_patch(&block.content, ops)
+            .map_err(|e| format!("Script block {}: {}", block.index, e))?;
+        result.replace_range(
+            block.start..block.end,
+            &fit_into_block(&block.content, &patched),
+        );
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root_html() -> &'static str {
+        r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <script defer phx-track-static type="text/javascript" src={~p"/assets/app.js"}>
+    </script>
+    <script type="application/json" id="data">{"a": 1}</script>
+    <SCRIPT>
+      window.dataLayer = window.dataLayer || [];
+      function gtag(){dataLayer.push(arguments);}
+    </SCRIPT>
+  </head>
+  <body>
+    <script :type={Phoenix.LiveView.ColocatedHook} name=".Foo">
+      export default {
+        mounted() {}
+      }
+    </script>
+  </body>
+</html>
+"#
+    }
+
+    #[test]
+    fn test_find_script_blocks() {
+        let html = root_html();
+        let blocks = find_script_blocks(html);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].index, 0);
+        assert!(blocks[0].content.contains("window.dataLayer"));
+        assert_eq!(&html[blocks[0].start..blocks[0].end], blocks[0].content);
+        assert_eq!(
+            blocks[1].attributes,
+            ":type={Phoenix.LiveView.ColocatedHook} name=\".Foo\""
+        );
+    }
+
+    #[test]
+    fn test_patch_script_blocks() {
+        let html = root_html();
+        let ops = vec![JsPatchOp::InsertAtIndex(
+            "gtag(\"config\", \"G-XXXX\");".to_string(),
+            2,
+        )];
+
+        let result = patch_script_blocks(html, Some(0), &ops).unwrap();
+        assert!(result.contains("      gtag(\"config\", \"G-XXXX\");\n    </SCRIPT>"));
+
+        let blocks = find_script_blocks(html);
+        assert_eq!(result[..blocks[0].start], html[..blocks[0].start]);
+        assert!(result.ends_with(&html[blocks[0].end..]));
+
+        assert!(patch_script_blocks(html, Some(5), &ops).is_err());
+
+        let html = "<script>let a = <%= @value %>;</script>";
+        assert!(patch_script_blocks(html, None, &ops).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::asset::patch::JsPatchOp;
+use crate::parsers::html::script::*;
+
+use rustler::{Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct ScriptBlockResult {
+    pub index: usize,
+    pub start: usize,
+    pub end: usize,
+    pub attributes: String,
+    pub content: String,
+}
+
+#[rustler::nif]
+pub fn find_html_script_blocks_nif(
+    env: Env,
+    html: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let blocks: Vec<ScriptBlockResult> = find_script_blocks(&html)
+            .into_iter()
+            .map(|block| ScriptBlockResult {
+                index: block.index,
+                start: block.start,
+                end: block.end,
+                attributes: block.attributes,
+                content: block.content,
+            })
+            .collect();
+
+        encode_response(
+            env,
+            atoms::ok(),
+            atoms::find_html_script_blocks_nif(),
+            blocks,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn patch_html_script_blocks_nif(
+    env: Env,
+    html: String,
+    index: Option<usize>,
+    ops: Vec<JsPatchOp>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match patch_script_blocks(&html, index, &ops) {
+            Ok(updated_html) => (atoms::ok(), updated_html),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, atoms::patch_html_script_blocks_nif(), result)
+    })
+}
@@ -0,0 +1,476 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Alpine.js Helpers
+//!
+//! Helpers to wire Alpine.js into a Phoenix `app.js`, the way the Phoenix hook helpers do for
+//! the LiveSocket: the `alpinejs` import, plugin registrations, `Alpine.data` components and
+//! `Alpine.start()`. Every helper is idempotent and returns the content untouched when there
+//! is nothing to add.
+//!
+//! The setup statements are kept in the order Alpine requires:
+//!
+//! ```javascript
+//! import Alpine from "alpinejs";
+//! import focus from "@alpinejs/focus";
+//! window.Alpine = Alpine;
+//! Alpine.plugin(focus);
+//! Alpine.data("dropdown", () => ({ open: false }));
+//! Alpine.start();
+//! let liveSocket = new LiveSocket("/live", Socket, {});
+//! ```
+
+use crate::parsers::javascript::helpers::*;
+
+use super::ast::insert_import_to_ast;
+use swc_common::EqIgnoreSpan;
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitMutWith;
+
+const ALPINE_SOURCE: &str = "alpinejs";
+
+const ALPINE_IMPORT: &str = "import Alpine from \"alpinejs\";";
+
+/// The top-level Alpine setup statements, in the order they must run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SetupStep {
+    /// `window.Alpine = Alpine`
+    Window,
+    /// `Alpine.plugin(x)`
+    Plugin,
+    /// `Alpine.data("name", fn)`
+    Data,
+    /// `Alpine.start()`
+    Start,
+}
+
+/// Returns the local name of the default import of a module, e.g. `Alpine` for
+/// `import Alpine from "alpinejs"`.
+fn default_import_local(module: &Module, source: &str) -> Option<String> {
+    module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if import.src.value == *source => {
+            import.specifiers.iter().find_map(|spec| match spec {
+                ImportSpecifier::Default(default) => Some(default.local.sym.to_string()),
+                _ => None,
+            })
+        }
+        _ => None,
+    })
+}
+
+/// Returns the call of a top-level `<alpine>.<method>(...)` statement.
+fn alpine_call<'a>(item: &'a ModuleItem, alpine: &str) -> Option<(&'a str, &'a CallExpr)> {
+    let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = item else {
+        return None;
+    };
+    let Expr::Call(call) = &**expr else {
+        return None;
+    };
+    let Callee::Expr(callee) = &call.callee else {
+        return None;
+    };
+    let Expr::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(method),
+        ..
+    }) = &**callee
+    else {
+        return None;
+    };
+
+    match &**obj {
+        Expr::Ident(obj) if obj.sym == alpine => Some((&*method.sym, call)),
+        _ => None,
+    }
+}
+
+/// Tells which setup step a top-level statement is, if any.
+fn setup_step(item: &ModuleItem, alpine: &str) -> Option<SetupStep> {
+    if let Some((method, _)) = alpine_call(item, alpine) {
+        return match method {
+            "plugin" => Some(SetupStep::Plugin),
+            "data" => Some(SetupStep::Data),
+            "start" => Some(SetupStep::Start),
+            _ => None,
+        };
+    }
+
+    let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = item else {
+        return None;
+    };
+    let Expr::Assign(AssignExpr {
+        op: AssignOp::Assign,
+        left: AssignTarget::Simple(SimpleAssignTarget::Member(member)),
+        right,
+        ..
+    }) = &**expr
+    else {
+        return None;
+    };
+
+    match (&*member.obj, &member.prop, &**right) {
+        (Expr::Ident(obj), MemberProp::Ident(prop), Expr::Ident(value))
+            if obj.sym == "window" && prop.sym == "Alpine" && value.sym == alpine =>
+        {
+            Some(SetupStep::Window)
+        }
+        _ => None,
+    }
+}
+
+/// The index of the first top-level statement declaring a variable with `new LiveSocket(...)`.
+fn live_socket_index(module: &Module) -> Option<usize> {
+    module.body.iter().position(|item| {
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = item else {
+            return false;
+        };
+        var_decl.decls.iter().any(|decl| {
+            matches!(
+                decl.init.as_deref(),
+                Some(Expr::New(NewExpr { callee, .. }))
+                    if matches!(&**callee, Expr::Ident(callee) if callee.sym == "LiveSocket")
+            )
+        })
+    })
+}
+
+/// Where a statement of the given step goes: after the last setup statement of the same or
+/// an earlier step, otherwise before the first one of a later step, otherwise right after
+/// the imports.
+fn setup_index(module: &Module, alpine: &str, step: SetupStep) -> usize {
+    let steps: Vec<Option<SetupStep>> = module
+        .body
+        .iter()
+        .map(|item| setup_step(item, alpine))
+        .collect();
+
+    if let Some(index) = steps.iter().rposition(|s| s.is_some_and(|s| s <= step)) {
+        return index + 1;
+    }
+    if let Some(index) = steps.iter().position(|s| s.is_some_and(|s| s > step)) {
+        return index;
+    }
+
+    module
+        .body
+        .iter()
+        .rposition(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+        .map_or(0, |index| index + 1)
+}
+
+/// Parses a single statement without spans, so it can be inserted into any module.
+fn parse_stmt(code: &str) -> Result<ModuleItem, String> {
+    let (module, _comments, _cm) = parse(code)?;
+    match <[ModuleItem; 1]>::try_from(module.body) {
+        Ok([mut item]) => {
+            item.visit_mut_with(&mut DropSpan);
+            Ok(item)
+        }
+        _ => Err(format!("Failed to parse statement: {}", code)),
+    }
+}
+
+/// Adds the `alpinejs` import when it is missing, then applies `edit` to the module with the
+/// local name of Alpine. The code is only generated again when `edit` changes the module.
+fn edit_alpine_setup<F>(file_content: &str, edit: F) -> Result<String, String>
+where
+    F: FnOnce(&mut Module, &str) -> Result<bool, String>,
+{
+    let code = ensure_alpine_import_to_ast(file_content)?;
+    let (mut module, comments, cm) = parse(&code)?;
+    let alpine = default_import_local(&module, ALPINE_SOURCE)
+        .ok_or_else(|| "The alpinejs import could not be added.".to_string())?;
+
+    if edit(&mut module, &alpine)? {
+        Ok(code_gen_from_ast_module(&mut module, comments, cm))
+    } else {
+        Ok(code)
+    }
+}
+
+/// Ensures Alpine is imported with `import Alpine from "alpinejs"`.
+///
+/// An existing default import of `alpinejs` is kept whatever its local name is, and the
+/// other helpers of this module use that name.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails.
+pub fn ensure_alpine_import_to_ast(file_content: &str) -> Result<String, String> {
+    let (module, _comments, _cm) = parse(file_content)?;
+    match default_import_local(&module, ALPINE_SOURCE) {
+        Some(_) => Ok(file_content.to_string()),
+        None => insert_import_to_ast(file_content, ALPINE_IMPORT),
+    }
+}
+
+/// Registers an Alpine plugin with `Alpine.plugin(plugin)`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `plugin`: The plugin expression, e.g. `focus`.
+/// - `source`: The module to import the plugin from as a default import, e.g.
+///   `@alpinejs/focus`. An existing default import of it is reused under its local name,
+///   in which case `plugin` is only used when the import is missing.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails,
+/// `plugin` is not an expression, or not an identifier while `source` is given.
+///
+/// # Behavior
+/// - The `alpinejs` import is added when missing.
+/// - The registration goes after the other plugins, before the `Alpine.data` components and
+///   `Alpine.start()`.
+/// - A plugin that is already registered is not registered again.
+///
+/// # Example
+/// ```rust
+/// let result = register_alpine_plugin_to_ast("", "focus", Some("@alpinejs/focus")).unwrap();
+/// assert!(result.contains("import focus from \"@alpinejs/focus\";"));
+/// assert!(result.contains("Alpine.plugin(focus);"));
+/// ```
+pub fn register_alpine_plugin_to_ast(
+    file_content: &str,
+    plugin: &str,
+    source: Option<&str>,
+) -> Result<String, String> {
+    let mut plugin_expr = parse_expr(plugin)?;
+
+    let code = match source {
+        Some(source) => {
+            if !matches!(&*plugin_expr, Expr::Ident(ident) if ident.sym == plugin.trim()) {
+                return Err(format!("`{}` is not a valid identifier", plugin));
+            }
+
+            let (module, _comments, _cm) = parse(file_content)?;
+            match default_import_local(&module, source) {
+                Some(local) => {
+                    plugin_expr = parse_expr(&local)?;
+                    file_content.to_string()
+                }
+                None => insert_import_to_ast(
+                    file_content,
+                    &format!("import {} from \"{}\";", plugin.trim(), source),
+                )?,
+            }
+        }
+        None => file_content.to_string(),
+    };
+
+    edit_alpine_setup(&code, |module, alpine| {
+        let registered = module.body.iter().any(|item| {
+            matches!(
+                alpine_call(item, alpine),
+                Some(("plugin", call)) if call
+                    .args
+                    .first()
+                    .is_some_and(|arg| arg.expr.eq_ignore_span(&plugin_expr))
+            )
+        });
+        if registered {
+            return Ok(false);
+        }
+
+        let mut item = parse_stmt(&format!("{}.plugin(x);", alpine))?;
+        if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &mut item {
+            if let Expr::Call(call) = &mut **expr {
+                call.args[0].expr = plugin_expr;
+            }
+        }
+
+        let index = setup_index(module, alpine, SetupStep::Plugin);
+        module.body.insert(index, item);
+        Ok(true)
+    })
+}
+
+/// Adds an Alpine component with `Alpine.data("name", value)`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `name`: The name of the component, as used by `x-data`.
+/// - `value`: The expression of the component, e.g. `() => ({ open: false })`.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails
+/// or `value` is not an expression.
+///
+/// # Behavior
+/// - The `alpinejs` import is added when missing.
+/// - The component goes after the other components and the plugins, before
+///   `Alpine.start()`.
+/// - A component that is already defined under `name` is kept as is.
+pub fn add_alpine_data_to_ast(
+    file_content: &str,
+    name: &str,
+    value: &str,
+) -> Result<String, String> {
+    let value = parse_expr(value)?;
+
+    edit_alpine_setup(file_content, |module, alpine| {
+        let defined = module.body.iter().any(|item| {
+            matches!(
+                alpine_call(item, alpine),
+                Some(("data", call)) if matches!(
+                    call.args.first().map(|arg| &*arg.expr),
+                    Some(Expr::Lit(Lit::Str(str))) if str.value == *name
+                )
+            )
+        });
+        if defined {
+            return Ok(false);
+        }
+
+        let mut item = parse_stmt(&format!("{}.data(\"\", x);", alpine))?;
+        if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &mut item {
+            if let Expr::Call(call) = &mut **expr {
+                if let Expr::Lit(Lit::Str(str)) = &mut *call.args[0].expr {
+                    str.value = name.into();
+                    str.raw = None;
+                }
+                call.args[1].expr = value;
+            }
+        }
+
+        let index = setup_index(module, alpine, SetupStep::Data);
+        module.body.insert(index, item);
+        Ok(true)
+    })
+}
+
+/// Ensures Alpine is exposed with `window.Alpine = Alpine` and started with `Alpine.start()`.
+///
+/// `window.Alpine` lets the LiveSocket `dom` callbacks reach Alpine, and `Alpine.start()` must
+/// run after the plugins and components are registered and before the LiveSocket is set up.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails.
+///
+/// # Behavior
+/// - The `alpinejs` import is added when missing.
+/// - A missing `window.Alpine = Alpine` goes before the plugins.
+/// - A missing `Alpine.start()` goes after the plugins and components.
+/// - An existing `Alpine.start()` is moved there when it runs before a plugin or component,
+///   or after the `new LiveSocket(...)` declaration; otherwise it stays where it is.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});
+/// Alpine.start();"#;
+/// let result = ensure_alpine_start_to_ast(code).unwrap();
+/// assert!(result.find("Alpine.start()") < result.find("new LiveSocket"));
+/// ```
+pub fn ensure_alpine_start_to_ast(file_content: &str) -> Result<String, String> {
+    edit_alpine_setup(file_content, |module, alpine| {
+        let mut changed = false;
+
+        if !module
+            .body
+            .iter()
+            .any(|item| setup_step(item, alpine) == Some(SetupStep::Window))
+        {
+            let item = parse_stmt(&format!("window.Alpine = {};", alpine))?;
+            let index = setup_index(module, alpine, SetupStep::Window);
+            module.body.insert(index, item);
+            changed = true;
+        }
+
+        let start = module
+            .body
+            .iter()
+            .position(|item| setup_step(item, alpine) == Some(SetupStep::Start));
+        let last_setup = module.body.iter().rposition(
+            |item| matches!(setup_step(item, alpine), Some(step) if step < SetupStep::Start),
+        );
+
+        let item = match start {
+            Some(start)
+                if last_setup.is_none_or(|last| start > last)
+                    && live_socket_index(module).is_none_or(|live| start < live) =>
+            {
+                return Ok(changed);
+            }
+            Some(start) => module.body.remove(start),
+            None => parse_stmt(&format!("{}.start();", alpine))?,
+        };
+
+        let index = setup_index(module, alpine, SetupStep::Start);
+        module.body.insert(index, item);
+        Ok(true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alpine_setup() {
+        let code = r#"import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+"#;
+
+        let result = register_alpine_plugin_to_ast(code, "focus", Some("@alpinejs/focus")).unwrap();
+        let result =
+            add_alpine_data_to_ast(&result, "dropdown", "() => ({ open: false })").unwrap();
+        let result = register_alpine_plugin_to_ast(&result, "persist", None).unwrap();
+        let once = ensure_alpine_start_to_ast(&result).unwrap();
+
+        assert_eq!(
+            once,
+            r#"import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+import focus from "@alpinejs/focus";
+import Alpine from "alpinejs";
+window.Alpine = Alpine;
+Alpine.plugin(focus);
+Alpine.plugin(persist);
+Alpine.data("dropdown", ()=>({
+        open: false
+    }));
+Alpine.start();
+let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+"#
+        );
+
+        // Running every helper again is a no-op
+        let twice = register_alpine_plugin_to_ast(&once, "focus", Some("@alpinejs/focus")).unwrap();
+        let twice = add_alpine_data_to_ast(&twice, "dropdown", "() => ({})").unwrap();
+        let twice = ensure_alpine_start_to_ast(&twice).unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_ensure_alpine_start_ordering() {
+        // An existing import is reused under its own name, and a late start is moved up
+        let code = r#"import AlpineJS from "alpinejs";
+import collapse from "@alpinejs/collapse";
+AlpineJS.plugin(collapse);
+let liveSocket = new LiveSocket("/live", Socket, {});
+// Start Alpine
+AlpineJS.start();
+"#;
+
+        let result = register_alpine_plugin_to_ast(code, "other", Some("@alpinejs/collapse"));
+        assert_eq!(result.unwrap(), code);
+
+        let result = ensure_alpine_start_to_ast(code).unwrap();
+        assert!(!result.contains("import Alpine "));
+        assert!(result.contains(
+            "window.Alpine = AlpineJS;\nAlpineJS.plugin(collapse);\n// Start Alpine\nAlpineJS.start();\nlet liveSocket"
+        ));
+
+        // A start running before the plugins is moved after them
+        let code = "import Alpine from \"alpinejs\";\nwindow.Alpine = Alpine;\nAlpine.start();\nAlpine.plugin(focus);\n";
+        let result = ensure_alpine_start_to_ast(code).unwrap();
+        assert!(result.ends_with("Alpine.plugin(focus);\nAlpine.start();\n"));
+
+        assert!(register_alpine_plugin_to_ast(code, "window.focus", Some("x")).is_err());
+        assert!(add_alpine_data_to_ast(code, "broken", "() =>").is_err());
+        assert!(ensure_alpine_start_to_ast("import {").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::alpine::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn ensure_alpine_import_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::ensure_alpine_import_nif();
+        let result = ensure_alpine_import_to_ast(&file_content);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn register_alpine_plugin_nif(
+    env: Env,
+    file_content: String,
+    plugin: String,
+    source: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::register_alpine_plugin_nif();
+        let result = register_alpine_plugin_to_ast(&file_content, &plugin, source.as_deref());
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn add_alpine_data_nif(
+    env: Env,
+    file_content: String,
+    name: String,
+    value: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::add_alpine_data_nif();
+        let result = add_alpine_data_to_ast(&file_content, &name, &value);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn ensure_alpine_start_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::ensure_alpine_start_nif();
+        let result = ensure_alpine_start_to_ast(&file_content);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
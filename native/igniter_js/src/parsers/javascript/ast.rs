@@ -13,7 +13,11 @@
 //! The module leverages a Rust-based parser and integrates seamlessly with Elixir through NIFs.
 
 use crate::parsers::javascript::helpers::*;
-use swc_common::{SyntaxContext, DUMMY_SP};
+use crate::parsers::javascript::removal::{emit_removal, RemovalMode};
+use crate::parsers::javascript::unused::prune_imports_unused_after;
+#[cfg(feature = "nif")]
+use rustler::NifTaggedEnum;
+use swc_common::{sync::Lrc, SourceMap, SourceMapper, Span, Spanned, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
 use swc_ecma_visit::{VisitMut, VisitMutWith};
 
@@ -58,12 +62,32 @@ impl FindCondition {
 // ####################### (▰˘◡˘▰) Work with AST import (▰˘◡˘▰) ######################
 // ###################################################################################
 
+/// Where [`insert_import_at_position_to_ast`] places the new imports.
+///
+/// On the Elixir side these are `:top`, `:after_last_import` and `{:before, "phoenix"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum ImportPosition {
+    /// Before everything but the directives, e.g. `"use strict"`.
+    Top,
+    /// After the last import, or at the top if there is none.
+    #[default]
+    AfterLastImport,
+    /// Before the first import of the given source, or after the last import if the source
+    /// is not imported.
+    Before(String),
+}
+
 #[derive(Debug)]
 struct ASTVisitImport<'a> {
     code: &'a str,
     duplicate_imports: Vec<String>,
     none_duplicate_imports: Vec<String>,
     operation: Operation,
+    position: ImportPosition,
+    inserted: usize,
+    /// The spans of the deleted imports.
+    removed: Vec<Span>,
 }
 
 impl Default for ASTVisitImport<'_> {
@@ -73,10 +97,53 @@ impl Default for ASTVisitImport<'_> {
             duplicate_imports: Vec::new(),
             none_duplicate_imports: Vec::new(),
             operation: Operation::Edit,
+            position: ImportPosition::default(),
+            inserted: 0,
+            removed: Vec::new(),
         }
     }
 }
 
+impl ASTVisitImport<'_> {
+    /// The index of `body` the next new import goes to, so a batch of imports keeps its
+    /// order.
+    fn insert_index(&self, body: &[ModuleItem]) -> usize {
+        match &self.position {
+            ImportPosition::Top => position_index(body, &self.position) + self.inserted,
+            position => position_index(body, position),
+        }
+    }
+}
+
+/// The index of `body` code inserted at `position` goes to.
+pub fn position_index(body: &[ModuleItem], position: &ImportPosition) -> usize {
+    let is_import =
+        |item: &ModuleItem| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_)));
+    let after_last_import = || {
+        body.iter()
+            .rposition(is_import)
+            .map_or(0, |index| index + 1)
+    };
+
+    match position {
+        ImportPosition::Top => body
+            .iter()
+            .take_while(|item| {
+                matches!(item, ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))
+                    if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+            })
+            .count(),
+        ImportPosition::AfterLastImport => after_last_import(),
+        ImportPosition::Before(source) => body
+            .iter()
+            .position(|item| {
+                matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                    if import.src.value == **source)
+            })
+            .unwrap_or_else(after_last_import),
+    }
+}
+
 impl VisitMut for ASTVisitImport<'_> {
     fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
         // We are using it to delete imports
@@ -97,7 +164,7 @@ impl VisitMut for ASTVisitImport<'_> {
             }
 
             for &index in indices_to_remove.iter().rev() {
-                items.remove(index);
+                self.removed.push(items.remove(index).span());
             }
         }
 
@@ -106,18 +173,12 @@ impl VisitMut for ASTVisitImport<'_> {
 
     fn visit_mut_module(&mut self, module: &mut Module) {
         // We are using it to add imports and know it is duplicated or not
-        let (imports, _comments, _cm) = parse(self.code).expect("Failed to parse imports");
+        let (mut imports, _comments, _cm) = parse(self.code).expect("Failed to parse imports");
+        imports.visit_mut_with(&mut DropSpan);
 
         for import in imports.body {
             if !is_duplicate_import(&import, &module.body) {
                 if matches!(self.operation, Operation::Add | Operation::Read) {
-                    let mut last_import_index = None;
-                    for (i, item) in module.body.iter().enumerate() {
-                        if matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))) {
-                            last_import_index = Some(i);
-                        }
-                    }
-
                     for imp in import.as_module_decl().iter() {
                         if let ModuleDecl::Import(import_decl) = imp {
                             let src_value = import_decl.src.value.to_string();
@@ -127,11 +188,9 @@ impl VisitMut for ASTVisitImport<'_> {
                         }
                     }
 
-                    if let Some(index) = last_import_index {
-                        module.body.insert(index + 1, import);
-                    } else {
-                        module.body.insert(0, import);
-                    }
+                    let index = self.insert_index(&module.body);
+                    module.body.insert(index, import);
+                    self.inserted += 1;
                 }
             } else if matches!(self.operation, Operation::Read) {
                 if let ModuleItem::ModuleDecl(ModuleDecl::Import(new_import_decl)) = import {
@@ -147,8 +206,8 @@ impl VisitMut for ASTVisitImport<'_> {
 
 /// Checks if a specific module is imported in the JavaScript source code.
 ///
-/// This function parses the given JavaScript source code into an AST
-/// and determines if the specified `module_name` is imported.
+/// Only the imports of the source are parsed, with [`parse_imports`], and the function
+/// determines if the specified `module_name` is imported.
 ///
 /// # Arguments
 /// - `file_content`: The JavaScript source code as a string slice.
@@ -158,19 +217,19 @@ impl VisitMut for ASTVisitImport<'_> {
 /// A `Result` containing `true` if the module is imported, `false` otherwise,
 /// or an error message if parsing fails.
 pub fn is_module_imported_from_ast(file_content: &str, module_name: &str) -> Result<bool, bool> {
-    let mut import_visitor = ASTVisitImport {
-        code: module_name,
-        operation: Operation::Read,
-        ..Default::default()
-    };
-
-    let _output = code_gen_from_ast_vist(file_content, &mut import_visitor);
+    let (imports, _comments, _cm) = parse(module_name).map_err(|_| false)?;
+    let imports: Vec<ModuleItem> = imports
+        .body
+        .into_iter()
+        .filter(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+        .collect();
+    let existing = parse_imports(file_content).map_err(|_| false)?;
 
-    if import_visitor.none_duplicate_imports.is_empty()
-        && import_visitor.duplicate_imports.is_empty()
+    if !imports.is_empty()
+        && imports
+            .iter()
+            .all(|import| is_duplicate_import(import, &existing))
     {
-        Err(false)
-    } else if import_visitor.none_duplicate_imports.is_empty() {
         Ok(true)
     } else {
         Err(false)
@@ -195,9 +254,32 @@ pub fn is_module_imported_from_ast(file_content: &str, module_name: &str) -> Res
 /// - Ensures duplicate imports are skipped.
 /// - Inserts new import statements after existing ones or at the top if none exist.
 pub fn insert_import_to_ast(file_content: &str, import_lines: &str) -> Result<String, String> {
+    insert_import_at_position_to_ast(file_content, import_lines, ImportPosition::AfterLastImport)
+}
+
+/// Inserts new import statements like [`insert_import_to_ast`], at the given position.
+///
+/// An import is skipped when its source is already imported with all of its specifiers,
+/// whichever import declaration of the source has them. A side-effect import like
+/// `import "./polyfill"` is skipped when the source is imported in any form.
+///
+/// # Example
+/// ```rust
+/// let code = "import { Socket } from \"phoenix\";\nimport topbar from \"topbar\";";
+/// let position = ImportPosition::Before("topbar".into());
+/// let result = insert_import_at_position_to_ast(code, "import \"./polyfill\";", position);
+/// assert!(result.unwrap().contains("import \"./polyfill\";\nimport topbar"));
+/// ```
+pub fn insert_import_at_position_to_ast(
+    file_content: &str,
+    import_lines: &str,
+    position: ImportPosition,
+) -> Result<String, String> {
+    parse(import_lines).map_err(|_| "Failed to parse imports".to_string())?;
     let mut import_visitor = ASTVisitImport {
         code: import_lines,
         operation: Operation::Add,
+        position,
         ..Default::default()
     };
 
@@ -221,19 +303,136 @@ pub fn insert_import_to_ast(file_content: &str, import_lines: &str) -> Result<St
 /// # Behavior
 /// - Retains all other import statements and code structure.
 /// - Removes only the specified modules from the import declarations.
-pub fn remove_import_from_ast(file_content: &str, modules: &str) -> Result<String, String> {
+/// - With [`RemovalMode::CommentOut`], the imports are commented out instead, see
+///   [`crate::parsers::javascript::removal`].
+pub fn remove_import_from_ast(
+    file_content: &str,
+    modules: &str,
+    mode: RemovalMode,
+) -> Result<String, String> {
+    parse(modules).map_err(|_| "Failed to parse imports".to_string())?;
     let mut import_visitor = ASTVisitImport {
         code: modules,
         operation: Operation::Delete,
         ..Default::default()
     };
 
-    code_gen_from_ast_vist(file_content, &mut import_visitor)
+    let (mut module, comments, cm) =
+        parse(file_content).map_err(|_| "Failed to parse JavaScript content".to_string())?;
+    let original = module.clone();
+    module.visit_mut_with(&mut import_visitor);
+    Ok(emit_removal(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+        &import_visitor.removed,
+        mode,
+    ))
+}
+
+/// Replaces the import declarations of a source with new ones, e.g. to switch
+/// `import data from "./data.json" assert { type: "json" }` to the `with` keyword, or
+/// `import styles from "./app.css"` to another stylesheet.
+///
+/// The new declarations take the place of the first import of `source`, and the other
+/// imports of `source` are removed. Import attributes are kept as written, the new ones
+/// are written with `with` unless the file uses `assert`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `source`: The imported source to replace, e.g. `./data.json`.
+/// - `import_lines`: The new import declarations.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if a source does
+/// not parse, `import_lines` has anything but import declarations, or `source` is not
+/// imported.
+///
+/// # Example
+/// ```rust
+/// let code = "import data from \"./data.json\" assert { type: \"json\" };";
+/// let new_import = "import data from \"./data.json\" with { type: \"json\" };";
+/// let result = replace_import_to_ast(code, "./data.json", new_import).unwrap();
+/// assert!(result.contains("with {"));
+/// ```
+pub fn replace_import_to_ast(
+    file_content: &str,
+    source: &str,
+    import_lines: &str,
+) -> Result<String, String> {
+    let (mut imports, _comments, _cm) = parse(import_lines)?;
+    if imports.body.is_empty()
+        || !imports
+            .body
+            .iter()
+            .all(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+    {
+        return Err(format!(
+            "Only import declarations can replace the import of `{}`",
+            source
+        ));
+    }
+    imports.visit_mut_with(&mut DropSpan);
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let indices: Vec<usize> = module
+        .body
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                if import.src.value == *source)
+        })
+        .map(|(index, _)| index)
+        .collect();
+    let Some(&first) = indices.first() else {
+        return Err(format!("The module `{}` is not imported", source));
+    };
+
+    for index in indices.into_iter().rev() {
+        module.body.remove(index);
+    }
+    module.body.splice(first..first, imports.body);
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
 }
 
 // ###################################################################################
 // ##################### (▰˘◡˘▰) Work with AST Statistics (▰˘◡˘▰) ####################
 // ###################################################################################
+/// The source location of a node reported by [`statistics_from_ast`].
+///
+/// `start` and `end` are byte offsets into the source, `line` and `column` are 1-based and
+/// 0-based respectively, like editors show them, with the column counted in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn from_span(cm: &Lrc<SourceMap>, span: Span) -> Self {
+        let loc = cm.lookup_char_pos(span.lo);
+        let (start, end) = SpanOffsets::new(cm).range(span);
+        Self {
+            start,
+            end,
+            line: loc.line,
+            column: loc.col.0,
+        }
+    }
+}
+
 pub struct ASTStatistics {
     pub functions: usize,
     pub classes: usize,
@@ -241,7 +440,18 @@ pub struct ASTStatistics {
     pub imports: usize,
     pub trys: usize,
     pub throws: usize,
+    pub exports: usize,
+    pub arrow_functions: usize,
+    pub async_functions: usize,
+    pub top_level_awaits: usize,
+    pub console_calls: usize,
+    pub event_listeners: usize,
     pub operation: Operation,
+    /// The locations of the counted nodes per category, only filled when requested.
+    pub locations: Option<Vec<(&'static str, SourceLocation)>>,
+    spans: Vec<(&'static str, Span)>,
+    collect_spans: bool,
+    function_depth: usize,
 }
 
 impl Default for ASTStatistics {
@@ -253,7 +463,42 @@ impl Default for ASTStatistics {
             imports: 0,
             trys: 0,
             throws: 0,
+            exports: 0,
+            arrow_functions: 0,
+            async_functions: 0,
+            top_level_awaits: 0,
+            console_calls: 0,
+            event_listeners: 0,
             operation: Operation::Read,
+            locations: None,
+            spans: vec![],
+            collect_spans: false,
+            function_depth: 0,
+        }
+    }
+}
+
+impl ASTStatistics {
+    fn record(&mut self, category: &'static str, span: Span) {
+        let counter = match category {
+            "functions" => &mut self.functions,
+            "classes" => &mut self.classes,
+            "debuggers" => &mut self.debuggers,
+            "imports" => &mut self.imports,
+            "trys" => &mut self.trys,
+            "throws" => &mut self.throws,
+            "exports" => &mut self.exports,
+            "arrow_functions" => &mut self.arrow_functions,
+            "async_functions" => &mut self.async_functions,
+            "top_level_awaits" => &mut self.top_level_awaits,
+            "console_calls" => &mut self.console_calls,
+            "event_listeners" => &mut self.event_listeners,
+            _ => return,
+        };
+        *counter += 1;
+
+        if self.collect_spans {
+            self.spans.push((category, span));
         }
     }
 }
@@ -261,42 +506,100 @@ impl Default for ASTStatistics {
 impl VisitMut for ASTStatistics {
     fn visit_mut_function(&mut self, node: &mut Function) {
         if matches!(self.operation, Operation::Read) {
-            self.functions += 1;
+            self.record("functions", node.span);
+            if node.is_async {
+                self.record("async_functions", node.span);
+            }
+        }
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_mut_arrow_expr(&mut self, node: &mut ArrowExpr) {
+        if matches!(self.operation, Operation::Read) {
+            self.record("arrow_functions", node.span);
+            if node.is_async {
+                self.record("async_functions", node.span);
+            }
         }
-        node.visit_mut_children_with(self)
+        self.function_depth += 1;
+        node.visit_mut_children_with(self);
+        self.function_depth -= 1;
     }
 
     fn visit_mut_class(&mut self, node: &mut Class) {
         if matches!(self.operation, Operation::Read) {
-            self.classes += 1;
+            self.record("classes", node.span);
         }
         node.visit_mut_children_with(self)
     }
 
     fn visit_mut_debugger_stmt(&mut self, node: &mut DebuggerStmt) {
         if matches!(self.operation, Operation::Read) {
-            self.debuggers += 1;
+            self.record("debuggers", node.span);
         }
         node.visit_mut_children_with(self)
     }
 
     fn visit_mut_import_decl(&mut self, node: &mut ImportDecl) {
         if matches!(self.operation, Operation::Read) {
-            self.imports += 1;
+            self.record("imports", node.span);
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_module_decl(&mut self, node: &mut ModuleDecl) {
+        if matches!(self.operation, Operation::Read) {
+            let span = match node {
+                ModuleDecl::ExportDecl(decl) => Some(decl.span),
+                ModuleDecl::ExportNamed(named) => Some(named.span),
+                ModuleDecl::ExportDefaultDecl(decl) => Some(decl.span),
+                ModuleDecl::ExportDefaultExpr(expr) => Some(expr.span),
+                ModuleDecl::ExportAll(all) => Some(all.span),
+                _ => None,
+            };
+            if let Some(span) = span {
+                self.record("exports", span);
+            }
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_await_expr(&mut self, node: &mut AwaitExpr) {
+        if matches!(self.operation, Operation::Read) && self.function_depth == 0 {
+            self.record("top_level_awaits", node.span);
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_call_expr(&mut self, node: &mut CallExpr) {
+        if matches!(self.operation, Operation::Read) {
+            if let Callee::Expr(callee) = &node.callee {
+                if let Expr::Member(member) = &**callee {
+                    if matches!(&*member.obj, Expr::Ident(ident) if ident.sym == "console") {
+                        self.record("console_calls", node.span);
+                    }
+                    if matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == "addEventListener")
+                    {
+                        self.record("event_listeners", node.span);
+                    }
+                }
+            }
         }
         node.visit_mut_children_with(self)
     }
 
     fn visit_mut_try_stmt(&mut self, node: &mut TryStmt) {
         if matches!(self.operation, Operation::Read) {
-            self.trys += 1;
+            self.record("trys", node.span);
         }
         node.visit_mut_children_with(self)
     }
 
     fn visit_mut_throw_stmt(&mut self, node: &mut ThrowStmt) {
         if matches!(self.operation, Operation::Read) {
-            self.throws += 1;
+            self.record("throws", node.span);
         }
         node.visit_mut_children_with(self)
     }
@@ -327,12 +630,47 @@ pub fn statistics_from_ast(file_content: &str) -> Result<ASTStatistics, String>
     Ok(import_visitor)
 }
 
+/// Like [`statistics_from_ast`], but also returns the location of every counted node, grouped
+/// by category, so callers can point at the exact `console.log` or `debugger` in a report.
+///
+/// # Arguments
+/// - `file_content`: A string slice containing the JavaScript source code.
+///
+/// # Returns
+/// The statistics with `locations` filled, or an error message if parsing fails.
+///
+/// # Example
+/// ```rust
+/// let result = statistics_with_locations_from_ast("debugger;").unwrap();
+/// assert_eq!(result.locations.unwrap()[0].0, "debuggers");
+/// ```
+pub fn statistics_with_locations_from_ast(file_content: &str) -> Result<ASTStatistics, String> {
+    let (mut module, _comments, cm) = parse(file_content)?;
+    let mut visitor = ASTStatistics {
+        operation: Operation::Read,
+        collect_spans: true,
+        ..Default::default()
+    };
+
+    module.visit_mut_with(&mut visitor);
+
+    let locations = visitor
+        .spans
+        .iter()
+        .map(|(category, span)| (*category, SourceLocation::from_span(&cm, *span)))
+        .collect();
+    visitor.locations = Some(locations);
+
+    Ok(visitor)
+}
+
 // ###################################################################################
 // ################### (▰˘◡˘▰) Work with AST Var and Object (▰˘◡˘▰) ##################
 // ###################################################################################
 struct ObjectExtender {
     target_var_name: String,
     new_properties: Vec<Prop>,
+    keys_to_remove: Vec<String>,
     operation: Operation,
     find: FindCondition,
 }
@@ -342,6 +680,7 @@ impl Default for ObjectExtender {
         Self {
             target_var_name: "".to_string(),
             new_properties: Vec::new(),
+            keys_to_remove: Vec::new(),
             operation: Operation::Edit,
             find: FindCondition::NotFound("".to_string()),
         }
@@ -350,6 +689,27 @@ impl Default for ObjectExtender {
 
 impl VisitMut for ObjectExtender {
     fn visit_mut_var_decl(&mut self, var_decl: &mut VarDecl) {
+        if matches!(self.operation, Operation::Delete) {
+            for decl in &mut var_decl.decls {
+                if decl
+                    .name
+                    .as_ident()
+                    .is_none_or(|ident| ident.sym != self.target_var_name)
+                {
+                    continue;
+                }
+                if let Some(init) = &mut decl.init {
+                    self.find = FindCondition::FoundError("".to_string());
+                    if let Expr::Object(obj_expr) = init.as_mut() {
+                        self.find = FindCondition::Found;
+                        obj_expr.props.retain(|prop| {
+                            !object_entry_key(prop)
+                                .is_some_and(|key| self.keys_to_remove.contains(&key))
+                        });
+                    }
+                }
+            }
+        }
         if matches!(self.operation, Operation::Edit) {
             for decl in &mut var_decl.decls {
                 if let Some(ident) = decl.name.as_ident() {
@@ -463,6 +823,47 @@ pub fn extend_var_object_property_by_names_to_ast<'a>(
     }
 }
 
+/// Removes properties from the object a variable is declared with, the inverse of
+/// [`extend_var_object_property_by_names_to_ast`].
+///
+/// Properties are matched by their key: `Name` matches a shorthand or `Name: value` entry and
+/// `...Name` a spread entry. Keys that are not in the object are ignored.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `var_name` - The name of the variable (object) to remove properties from.
+/// * `keys` - The keys of the properties to remove.
+///
+/// # Returns
+/// * `Ok(String)` - The updated JavaScript source code.
+/// * `Err(String)` - If the variable is not found or is not initialized with an object.
+///
+/// # Example
+/// ```rust
+/// let js_code = "let Components = { ...Base, Modal, Chart: LazyChart };";
+/// let result = remove_var_object_properties_from_ast(js_code, "Components", ["...Base", "Chart"]);
+/// assert!(result.unwrap().contains("let Components = {\n    Modal\n};"));
+/// ```
+pub fn remove_var_object_properties_from_ast<'a>(
+    file_content: &str,
+    var_name: &str,
+    keys: impl IntoIterator<Item = &'a str>,
+) -> Result<String, String> {
+    let mut object_extender = ObjectExtender {
+        target_var_name: var_name.to_string(),
+        keys_to_remove: keys.into_iter().map(str::to_string).collect(),
+        operation: Operation::Delete,
+        ..Default::default()
+    };
+
+    let result = code_gen_from_ast_vist(file_content, &mut object_extender);
+    if object_extender.find == FindCondition::Found {
+        result
+    } else {
+        Err(object_extender.find.message().to_string())
+    }
+}
+
 /// Checks if a given variable is declared in the JavaScript AST.
 ///
 /// This function parses the provided JavaScript `file_content` and searches for
@@ -508,6 +909,386 @@ pub fn contains_variable_from_ast(file_content: &str, variable_name: &str) -> Re
     Err(false)
 }
 
+/// Options restricting which declarations [`find_variable_from_ast`] matches.
+#[derive(Debug, Clone, Default)]
+pub struct VariableLookup {
+    /// Only match declarations made directly in the module body (including `export const`).
+    pub top_level_only: bool,
+    /// Only match these kinds: `const`, `let`, `var`, `function` or `class`. Empty means any.
+    pub kinds: Vec<String>,
+}
+
+/// A declaration found by [`find_variable_from_ast`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableMatch {
+    /// `const`, `let`, `var`, `function` or `class`.
+    pub kind: &'static str,
+    /// Whether the declaration is made directly in the module body.
+    pub top_level: bool,
+    /// The type of the initializer expression (`object`, `array`, `call`, `new`,
+    /// `arrow_function`, `function`, `class`, `identifier`, `member`, `literal`, `template`
+    /// or `other`), or `None` when the variable has no initializer.
+    pub init: Option<&'static str>,
+    /// The span of the initializer, or of the declaration for functions and classes.
+    pub init_span: Option<Span>,
+}
+
+fn expression_type(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Object(_) => "object",
+        Expr::Array(_) => "array",
+        Expr::Call(_) => "call",
+        Expr::New(_) => "new",
+        Expr::Arrow(_) => "arrow_function",
+        Expr::Fn(_) => "function",
+        Expr::Class(_) => "class",
+        Expr::Ident(_) => "identifier",
+        Expr::Member(_) => "member",
+        Expr::Lit(_) => "literal",
+        Expr::Tpl(_) => "template",
+        Expr::Paren(paren) => expression_type(&paren.expr),
+        _ => "other",
+    }
+}
+
+struct VariableFinder<'a> {
+    name: &'a str,
+    depth: usize,
+    found: Vec<VariableMatch>,
+}
+
+impl VariableFinder<'_> {
+    fn nested<N: VisitMutWith<Self>>(&mut self, node: &mut N) {
+        self.depth += 1;
+        node.visit_mut_children_with(self);
+        self.depth -= 1;
+    }
+}
+
+impl VisitMut for VariableFinder<'_> {
+    fn visit_mut_var_decl(&mut self, node: &mut VarDecl) {
+        let kind = match node.kind {
+            VarDeclKind::Const => "const",
+            VarDeclKind::Let => "let",
+            VarDeclKind::Var => "var",
+        };
+
+        for decl in &node.decls {
+            if matches!(&decl.name, Pat::Ident(BindingIdent { id, .. }) if id.sym == self.name) {
+                self.found.push(VariableMatch {
+                    kind,
+                    top_level: self.depth == 0,
+                    init: decl.init.as_deref().map(expression_type),
+                    init_span: decl.init.as_deref().map(Spanned::span),
+                });
+            }
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_fn_decl(&mut self, node: &mut FnDecl) {
+        if node.ident.sym == self.name {
+            self.found.push(VariableMatch {
+                kind: "function",
+                top_level: self.depth == 0,
+                init: Some("function"),
+                init_span: Some(node.function.span),
+            });
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_class_decl(&mut self, node: &mut ClassDecl) {
+        if node.ident.sym == self.name {
+            self.found.push(VariableMatch {
+                kind: "class",
+                top_level: self.depth == 0,
+                init: Some("class"),
+                init_span: Some(node.class.span),
+            });
+        }
+        node.visit_mut_children_with(self)
+    }
+
+    fn visit_mut_function(&mut self, node: &mut Function) {
+        self.nested(node)
+    }
+
+    fn visit_mut_arrow_expr(&mut self, node: &mut ArrowExpr) {
+        self.nested(node)
+    }
+
+    fn visit_mut_block_stmt(&mut self, node: &mut BlockStmt) {
+        self.nested(node)
+    }
+
+    fn visit_mut_for_stmt(&mut self, node: &mut ForStmt) {
+        self.nested(node)
+    }
+
+    fn visit_mut_for_in_stmt(&mut self, node: &mut ForInStmt) {
+        self.nested(node)
+    }
+
+    fn visit_mut_for_of_stmt(&mut self, node: &mut ForOfStmt) {
+        self.nested(node)
+    }
+
+    fn visit_mut_class(&mut self, node: &mut Class) {
+        self.nested(node)
+    }
+}
+
+/// Finds a declaration by name, with scope and kind restrictions.
+///
+/// Unlike [`contains_variable_from_ast`], which only looks at top-level `let` declarations,
+/// this looks at every `const`/`let`/`var`, function and class declaration in the module and
+/// reports where it was found and what it is initialized with, so callers can branch on
+/// whether `hooks` is an object literal or something else.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `variable_name` - The name of the declaration to search for.
+/// * `lookup` - The scope and kind restrictions.
+///
+/// # Returns
+/// * `Ok(Some(VariableMatch))` - The first matching declaration, top-level ones first.
+/// * `Ok(None)` - If no declaration matches.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let lookup = VariableLookup { top_level_only: true, kinds: vec!["const".to_string()] };
+/// let result = find_variable_from_ast("const Hooks = {};", "Hooks", &lookup).unwrap();
+/// assert_eq!(result.unwrap().init, Some("object"));
+/// ```
+pub fn find_variable_from_ast(
+    file_content: &str,
+    variable_name: &str,
+    lookup: &VariableLookup,
+) -> Result<Option<VariableMatch>, String> {
+    let (mut module, _comments, _cm) = parse(file_content)?;
+    Ok(find_variable_in_module(&mut module, variable_name, lookup))
+}
+
+/// Same as [`find_variable_from_ast`], for an already parsed module.
+pub fn find_variable_in_module(
+    module: &mut Module,
+    variable_name: &str,
+    lookup: &VariableLookup,
+) -> Option<VariableMatch> {
+    let mut finder = VariableFinder {
+        name: variable_name,
+        depth: 0,
+        found: vec![],
+    };
+    module.visit_mut_with(&mut finder);
+
+    let mut candidates: Vec<VariableMatch> = finder
+        .found
+        .into_iter()
+        .filter(|found| !lookup.top_level_only || found.top_level)
+        .filter(|found| lookup.kinds.is_empty() || lookup.kinds.iter().any(|k| k == found.kind))
+        .collect();
+    candidates.sort_by_key(|found| !found.top_level);
+
+    candidates.into_iter().next()
+}
+
+/// The initializer of a variable, or of one of its object keys, found by
+/// [`get_variable_initializer_from_ast`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableInitializer {
+    /// The type of the expression, like [`VariableMatch::init`].
+    pub kind: &'static str,
+    /// The source text of the expression.
+    pub source: String,
+    /// Whether the variable is declared directly in the module body.
+    pub top_level: bool,
+    /// The byte offsets of the expression.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds the initializer at a span, to walk into it after its declaration was found.
+fn initializer_at_span(module: &Module, span: Span) -> Option<&Expr> {
+    let mut exprs = vec![];
+    module
+        .body
+        .iter()
+        .for_each(|item| collect_initializers(item, &mut exprs));
+    exprs.into_iter().find(|expr| expr.span() == span)
+}
+
+/// Collects the initializers of the declarations of a module item, in the blocks, functions
+/// and callbacks it contains.
+fn collect_initializers<'a>(item: &'a ModuleItem, exprs: &mut Vec<&'a Expr>) {
+    struct Collector<'a, 'b> {
+        exprs: &'b mut Vec<&'a Expr>,
+    }
+
+    impl<'a> Collector<'a, '_> {
+        fn var_decl(&mut self, var_decl: &'a VarDecl) {
+            self.exprs.extend(
+                var_decl
+                    .decls
+                    .iter()
+                    .filter_map(|decl| decl.init.as_deref()),
+            );
+        }
+
+        fn stmts(&mut self, stmts: &'a [Stmt]) {
+            stmts.iter().for_each(|stmt| self.stmt(stmt));
+        }
+
+        fn stmt(&mut self, stmt: &'a Stmt) {
+            match stmt {
+                Stmt::Decl(Decl::Var(var_decl)) => self.var_decl(var_decl),
+                Stmt::Decl(Decl::Fn(fn_decl)) => self.function(&fn_decl.function),
+                Stmt::Block(block) => self.stmts(&block.stmts),
+                Stmt::If(if_stmt) => {
+                    self.stmt(&if_stmt.cons);
+                    if let Some(alt) = &if_stmt.alt {
+                        self.stmt(alt);
+                    }
+                }
+                Stmt::Expr(ExprStmt { expr, .. }) => self.expr(expr),
+                _ => {}
+            }
+        }
+
+        fn function(&mut self, function: &'a Function) {
+            if let Some(body) = &function.body {
+                self.stmts(&body.stmts);
+            }
+        }
+
+        fn expr(&mut self, expr: &'a Expr) {
+            if let Expr::Call(call) = expr {
+                for arg in &call.args {
+                    match &*arg.expr {
+                        Expr::Arrow(ArrowExpr { body, .. }) => {
+                            if let BlockStmtOrExpr::BlockStmt(block) = &**body {
+                                self.stmts(&block.stmts);
+                            }
+                        }
+                        Expr::Fn(FnExpr { function, .. }) => self.function(function),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    let mut collector = Collector { exprs };
+    match item {
+        ModuleItem::Stmt(stmt) => collector.stmt(stmt),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Var(var_decl),
+            ..
+        })) => collector.var_decl(var_decl),
+        _ => {}
+    }
+}
+
+/// The value of `key` in an object literal, or in the last argument of a call or `new`
+/// expression, e.g. the options of `new LiveSocket("/live", Socket, {...})`, as its span and
+/// type, along with the expression when there is one to walk into.
+fn object_key_value<'a>(
+    expr: &'a Expr,
+    key: &str,
+) -> Option<(Span, &'static str, Option<&'a Expr>)> {
+    let last_arg = |args: Option<&'a Vec<ExprOrSpread>>| args?.last().map(|arg| &*arg.expr);
+    let object = match expr.unwrap_parens() {
+        Expr::Object(object) => object,
+        Expr::New(new_expr) => match last_arg(new_expr.args.as_ref())?.unwrap_parens() {
+            Expr::Object(object) => object,
+            _ => return None,
+        },
+        Expr::Call(call) => match last_arg(Some(&call.args))?.unwrap_parens() {
+            Expr::Object(object) => object,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    // The last definition of a key wins
+    let prop = object
+        .props
+        .iter()
+        .rev()
+        .find(|prop| prop_key_name(prop).as_deref() == Some(key))?;
+    let PropOrSpread::Prop(prop) = prop else {
+        return None;
+    };
+    Some(match &**prop {
+        Prop::KeyValue(KeyValueProp { value, .. }) => {
+            (value.span(), expression_type(value), Some(&**value))
+        }
+        Prop::Shorthand(ident) => (ident.span, "identifier", None),
+        prop => (prop.span(), "function", None),
+    })
+}
+
+/// Returns the source text and type of the initializer of a variable, e.g. to read the
+/// current value of an option before deciding how to patch it.
+///
+/// The declaration is found like [`find_variable_from_ast`] does, top-level ones first. The
+/// name may be followed by object keys, e.g. `socketOptions.longPollFallbackMs`, which are
+/// read from the object literal the variable is initialized with, or from the last argument
+/// of the call or `new` expression it is initialized with, so
+/// `liveSocket.longPollFallbackMs` reads the option of `new LiveSocket(...)`.
+///
+/// # Returns
+/// * `Ok(Some(VariableInitializer))` - The initializer.
+/// * `Ok(None)` - If no declaration matches, it has no initializer, or a key is missing.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = "const opts = { longPollFallbackMs: 2500 };";
+/// let lookup = VariableLookup::default();
+/// let found = get_variable_initializer_from_ast(code, "opts.longPollFallbackMs", &lookup);
+/// assert_eq!(found.unwrap().unwrap().source, "2500");
+/// ```
+pub fn get_variable_initializer_from_ast(
+    file_content: &str,
+    path: &str,
+    lookup: &VariableLookup,
+) -> Result<Option<VariableInitializer>, String> {
+    let mut keys = path.split('.');
+    let variable_name = keys.next().unwrap_or_default();
+    let (mut module, _comments, cm) = parse(file_content)?;
+
+    let Some(found) = find_variable_in_module(&mut module, variable_name, lookup) else {
+        return Ok(None);
+    };
+    let (Some(mut span), Some(mut kind)) = (found.init_span, found.init) else {
+        return Ok(None);
+    };
+
+    let mut expr = initializer_at_span(&module, span);
+    for key in keys {
+        let Some((value_span, value_kind, value)) =
+            expr.and_then(|expr| object_key_value(expr, key))
+        else {
+            return Ok(None);
+        };
+        (span, kind, expr) = (value_span, value_kind, value);
+    }
+
+    let location = SourceLocation::from_span(&cm, span);
+    Ok(Some(VariableInitializer {
+        kind,
+        source: cm
+            .span_to_snippet(span)
+            .map_err(|_| "Invalid span".to_string())?,
+        top_level: found.top_level,
+        start: location.start,
+        end: location.end,
+    }))
+}
+
 /// Inserts a new JavaScript AST at a specified index in the existing AST.
 ///
 /// This function takes an existing JavaScript source code (`file_content`) and inserts
@@ -548,7 +1329,9 @@ pub fn insert_ast_at_index(
     index: usize,
 ) -> Result<String, String> {
     let (mut module, comments, cm) = parse(file_content)?;
-    let (insert_module, _, _) = parse(insert_code)?;
+    let original = module.clone();
+    let (mut insert_module, _, _) = parse(insert_code)?;
+    insert_module.visit_mut_with(&mut DropSpan);
 
     if index > module.body.len() {
         return Err("Index out of bounds".to_string());
@@ -556,7 +1339,13 @@ pub fn insert_ast_at_index(
 
     module.body.splice(index..index, insert_module.body);
 
-    Ok(code_gen_from_ast_module(&mut module, comments, cm))
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
 }
 
 /// Replaces the AST node at a specified index with a new JavaScript AST.
@@ -593,8 +1382,31 @@ pub fn replace_ast_at_index(
     replace_code: &str,
     index: usize,
 ) -> Result<String, String> {
+    replace_ast_at_index_pruning_imports(file_content, replace_code, index, false)
+        .map(|(code, _)| code)
+}
+
+/// Same as [`replace_ast_at_index`], along with the local names of the imports removed
+/// because the replaced node was the last to use them, when `prune_unused_imports` is set.
+///
+/// # Example
+/// ```rust
+/// let file_content = "import Chart from \"chart\"; let hooks = { Chart };";
+/// let (code, pruned) =
+///     replace_ast_at_index_pruning_imports(file_content, "let hooks = {};", 1, true).unwrap();
+/// assert_eq!(code, "let hooks = {};\n");
+/// assert_eq!(pruned, vec!["Chart"]);
+/// ```
+pub fn replace_ast_at_index_pruning_imports(
+    file_content: &str,
+    replace_code: &str,
+    index: usize,
+    prune_unused_imports: bool,
+) -> Result<(String, Vec<String>), String> {
     let (mut module, comments, cm) = parse(file_content)?;
-    let (replace_module, _, _) = parse(replace_code)?;
+    let original = module.clone();
+    let (mut replace_module, _, _) = parse(replace_code)?;
+    replace_module.visit_mut_with(&mut DropSpan);
 
     if index >= module.body.len() {
         return Err("Index out of bounds".to_string());
@@ -605,8 +1417,17 @@ pub fn replace_ast_at_index(
     }
 
     module.body.splice(index..=index, replace_module.body);
+    let pruned = if prune_unused_imports {
+        prune_imports_unused_after(&original, &mut module)
+            .into_iter()
+            .map(|(local, _)| local)
+            .collect()
+    } else {
+        vec![]
+    };
 
-    Ok(code_gen_from_ast_module(&mut module, comments, cm))
+    let code = code_gen_edit(file_content, &original, &mut module, comments, cm);
+    Ok((code, pruned))
 }
 
 #[cfg(test)]
@@ -615,6 +1436,54 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_import_attributes_round_trip() {
+        let code = r#"import data from "./data.json" assert { type: "json" };
+import styles from "./app.module.css";
+import "./app.css";
+export { default as config } from "./config.json" assert { type: "json" };
+let a = 1;
+"#;
+
+        // The `assert` keyword is kept by the transforms
+        let result = insert_import_to_ast(code, "import topbar from \"topbar\";").unwrap();
+        assert!(result.contains("import data from \"./data.json\" assert {"));
+        assert!(result.contains("from \"./config.json\" assert {"));
+        assert!(result.contains("import styles from \"./app.module.css\";"));
+        assert!(!result.contains(" with {"));
+
+        let result = remove_import_from_ast(code, "import \"./app.css\";", RemovalMode::Delete);
+        let result = result.unwrap();
+        assert!(!result.contains("\"./app.css\""));
+        assert!(result.contains("import data from \"./data.json\" assert {"));
+
+        let result = replace_import_to_ast(
+            code,
+            "./app.module.css",
+            "import theme from \"./theme.module.css\";",
+        )
+        .unwrap();
+        assert!(result.contains("import data from \"./data.json\" assert {"));
+        assert!(
+            result.contains("\nimport theme from \"./theme.module.css\";\nimport \"./app.css\";")
+        );
+
+        let code = "import data from \"./data.json\" with { type: \"json\" };\nlet a = 1;\n";
+        let result = insert_import_to_ast(code, "import topbar from \"topbar\";").unwrap();
+        assert!(result.contains("import data from \"./data.json\" with {"));
+
+        // The replaced import takes the keyword of the file
+        let result = replace_import_to_ast(
+            code,
+            "./data.json",
+            "import data from \"./data.json\" assert { type: \"json\" };",
+        )
+        .unwrap();
+        assert!(result.contains("import data from \"./data.json\" with {"));
+        assert!(replace_import_to_ast(code, "./missing.json", "import \"./x.css\";").is_err());
+        assert!(replace_import_to_ast(code, "./data.json", "let a = 1;").is_err());
+    }
+
     #[test]
     fn test_is_module_imported_from_ast() {
         let code = r#"
@@ -651,6 +1520,15 @@ mod tests {
         let result = is_module_imported_from_ast(code, import);
 
         assert!(result.is_err(), "Expected Ok(true), but got {:?}", result);
+
+        // Only the import section is parsed when nothing else imports
+        let code = "import { Socket } from \"phoenix\";\nlet = ;\n";
+        let import = "import { Socket } from \"phoenix\";";
+        assert_eq!(is_module_imported_from_ast(code, import), Ok(true));
+
+        // An import after other statements is still found
+        let code = "let a = 1;\nimport { Socket } from \"phoenix\";\n";
+        assert_eq!(is_module_imported_from_ast(code, import), Ok(true));
     }
     #[test]
     fn test_insert_import_to_ast() {
@@ -691,6 +1569,83 @@ mod tests {
         println!("{}", result)
     }
 
+    #[test]
+    fn test_insert_import_to_ast_keeps_source_style() {
+        let code =
+            "\u{feff}import { Socket } from \"phoenix\";\r\n\r\n// Connect\r\nlet a = 1;\r\n";
+        let result =
+            insert_import_to_ast(code, "import topbar from \"../vendor/topbar\";").unwrap();
+        assert_eq!(
+            result,
+            "\u{feff}import { Socket } from \"phoenix\";\r\nimport topbar from \"../vendor/topbar\";\r\n// Connect\r\nlet a = 1;\r\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_import_to_ast_of_other_source() {
+        // The inserted import is emitted on its own, not as a slice of the source
+        let code = "'\u{fffc}ꬠ🌀a𝕀0a( (\u{9d7}𪜀A®¡🌀ኊ";
+        let result = insert_import_to_ast(code, "import { Socket } from \"phoenix\";").unwrap();
+        assert!(result.starts_with("import { Socket } from \"phoenix\";\n'"));
+
+        assert_eq!(
+            insert_import_to_ast(code, "import {"),
+            Err("Failed to parse imports".to_string())
+        );
+        assert_eq!(
+            remove_import_from_ast(code, "import {", RemovalMode::Delete),
+            Err("Failed to parse imports".to_string())
+        );
+    }
+
+    #[test]
+    fn test_insert_import_at_position_to_ast() {
+        let code = r#""use strict";
+import { Socket } from "phoenix";
+import * as Hooks from "./hooks";
+import topbar from "topbar";
+let a = 1;
+"#;
+
+        let imports = "import \"./polyfill\";\nimport \"./other\";";
+        let result = insert_import_at_position_to_ast(code, imports, ImportPosition::Top).unwrap();
+        assert!(result.starts_with(
+            "\"use strict\";\nimport \"./polyfill\";\nimport \"./other\";\nimport { Socket }"
+        ));
+
+        let position = ImportPosition::Before("./hooks".into());
+        let result = insert_import_at_position_to_ast(code, imports, position).unwrap();
+        assert!(result.contains("import \"./other\";\nimport * as Hooks from \"./hooks\";"));
+
+        // An unknown source falls back to after the last import
+        let position = ImportPosition::Before("unknown".into());
+        let result = insert_import_at_position_to_ast(code, imports, position).unwrap();
+        assert!(result.contains("import topbar from \"topbar\";\nimport \"./polyfill\";"));
+    }
+
+    #[test]
+    fn test_insert_import_to_ast_skips_duplicates() {
+        let code = r#"import Phoenix from "phoenix";
+import * as PhoenixAll from "phoenix";
+import { Socket as S } from "phoenix";
+"#;
+
+        // Specifiers are looked up in every import of the source
+        let duplicates = r#"import "phoenix";
+import * as PhoenixAll from "phoenix";
+import Phoenix, { Socket as S } from "phoenix";
+"#;
+        let result = insert_import_to_ast(code, duplicates).unwrap();
+        assert_eq!(result, code);
+
+        let result = insert_import_to_ast(code, "import * as Other from \"phoenix\";").unwrap();
+        assert!(result.contains("import * as Other from \"phoenix\";"));
+
+        // The same local name imported under another name is not a duplicate
+        let result = insert_import_to_ast(code, "import { S } from \"phoenix\";").unwrap();
+        assert!(result.contains("import { S } from \"phoenix\";"));
+    }
+
     #[test]
     fn test_remove_import_from_ast() {
         let code = r#"
@@ -710,7 +1665,8 @@ mod tests {
                 import { NoneRepeated } from "orepeat";
                 import { NoneRepeated1 } from "orepeat1";
             "#;
-        let result = remove_import_from_ast(code, import).expect("Failed to generate code");
+        let result = remove_import_from_ast(code, import, RemovalMode::Delete)
+            .expect("Failed to generate code");
 
         assert!(result.contains("import \"phoenix_html\";"));
         assert!(!result.contains("import { Socket, SocketV1 } from \"phoenix\";"));
@@ -722,10 +1678,64 @@ mod tests {
         let Hooks = {};
         "#;
 
-        let result = remove_import_from_ast(code, "import bar from \"another-module\";")
-            .expect("Failed to generate code");
+        let result = remove_import_from_ast(
+            code,
+            "import bar from \"another-module\";",
+            RemovalMode::Delete,
+        )
+        .expect("Failed to generate code");
 
         println!("{}", result);
+
+        let result = remove_import_from_ast(
+            code,
+            "import bar from \"another-module\";",
+            RemovalMode::CommentOut,
+        )
+        .unwrap();
+        assert!(
+            result.contains("        // removed by igniter: import bar from \"another-module\";\n")
+        );
+        assert!(result.contains("let Hooks = {};"));
+    }
+
+    #[test]
+    fn test_get_variable_initializer_from_ast() {
+        let code = r#"const socketOptions = {
+  longPollFallbackMs: 2500,
+  hooks: { Chart, Map: MapHook },
+};
+let liveSocket = new LiveSocket("/live", Socket, { params: { _csrf_token: csrfToken } });
+function setup() {
+  const Hooks = [];
+}
+"#;
+        let lookup = VariableLookup::default();
+        let get = |path| get_variable_initializer_from_ast(code, path, &lookup).unwrap();
+
+        let found = get("socketOptions.longPollFallbackMs").unwrap();
+        assert_eq!((found.kind, found.source.as_str()), ("literal", "2500"));
+        assert_eq!(&code[found.start..found.end], "2500");
+        assert!(found.top_level);
+
+        let found = get("socketOptions.hooks").unwrap();
+        assert_eq!(found.kind, "object");
+        assert_eq!(found.source, "{ Chart, Map: MapHook }");
+        assert_eq!(get("socketOptions.hooks.Map").unwrap().source, "MapHook");
+        assert_eq!(get("socketOptions.hooks.Chart").unwrap().kind, "identifier");
+
+        let found = get("liveSocket.params._csrf_token").unwrap();
+        assert_eq!(
+            (found.kind, found.source.as_str()),
+            ("identifier", "csrfToken")
+        );
+        assert_eq!(get("liveSocket").unwrap().kind, "new");
+
+        let found = get("Hooks").unwrap();
+        assert_eq!((found.kind, found.top_level), ("array", false));
+
+        assert_eq!(get("socketOptions.missing"), None);
+        assert_eq!(get("missing"), None);
     }
 
     #[test]
@@ -754,6 +1764,70 @@ mod tests {
         assert_eq!(parsed.throws, 0);
     }
 
+    #[test]
+    fn test_statistics_with_locations_from_ast() {
+        let code = r#"export const ready = await loadConfig();
+window.addEventListener("phx:page-loading-start", async (_info) => {
+  console.log("loading");
+  await sleep(1);
+});
+export default function boot() {}
+"#;
+
+        let parsed = statistics_with_locations_from_ast(code).unwrap();
+        assert_eq!(parsed.exports, 2);
+        assert_eq!(parsed.arrow_functions, 1);
+        assert_eq!(parsed.async_functions, 1);
+        assert_eq!(parsed.top_level_awaits, 1);
+        assert_eq!(parsed.console_calls, 1);
+        assert_eq!(parsed.event_listeners, 1);
+        assert_eq!(parsed.functions, 1);
+
+        let locations = parsed.locations.unwrap();
+        let (_, console) = locations
+            .iter()
+            .find(|(category, _)| *category == "console_calls")
+            .unwrap();
+        assert_eq!(
+            &code[console.start..console.end],
+            "console.log(\"loading\")"
+        );
+        assert_eq!((console.line, console.column), (3, 2));
+
+        assert!(statistics_from_ast(code).unwrap().locations.is_none());
+        assert!(statistics_with_locations_from_ast("let = ;").is_err());
+    }
+
+    #[test]
+    fn test_remove_var_object_properties_from_ast() {
+        let code = r#"import Modal from "./modal.js";
+const Components = {
+    ...Base,
+    Modal,
+    Chart: LazyChart,
+    "data-table": DataTable
+};
+export default Components;
+"#;
+
+        let result = remove_var_object_properties_from_ast(
+            code,
+            "Components",
+            ["...Base", "Chart", "data-table", "Missing"],
+        )
+        .unwrap();
+        assert!(result.contains("const Components = {\n    Modal\n};"));
+
+        // Removing them again changes nothing
+        let again =
+            remove_var_object_properties_from_ast(&result, "Components", ["Chart"]).unwrap();
+        assert_eq!(again, result);
+
+        assert!(remove_var_object_properties_from_ast(code, "Missing", ["Modal"]).is_err());
+        let code = "const Components = () => {};";
+        assert!(remove_var_object_properties_from_ast(code, "Components", ["Modal"]).is_err());
+    }
+
     #[test]
     fn test_extend_var_object_property_by_names_to_ast() {
         let code = r#"
@@ -839,8 +1913,59 @@ mod tests {
         println!("{:#?}", result.unwrap())
     }
 
-    #[cfg(test)]
-    mod tests {
+    #[test]
+    fn test_find_variable_from_ast() {
+        let code = r#"
+            export const Hooks = { Copy };
+            let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+            function setup() {
+              var hooks = buildHooks();
+              for (const item of []) {}
+            }
+            class Tooltip {}
+            "#;
+
+        let any = VariableLookup::default();
+        let found = find_variable_from_ast(code, "Hooks", &any)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (found.kind, found.top_level, found.init),
+            ("const", true, Some("object"))
+        );
+
+        let found = find_variable_from_ast(code, "liveSocket", &any)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.init, Some("new"));
+
+        let found = find_variable_from_ast(code, "hooks", &any)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (found.kind, found.top_level, found.init),
+            ("var", false, Some("call"))
+        );
+
+        let top_level = VariableLookup {
+            top_level_only: true,
+            kinds: vec![],
+        };
+        assert_eq!(find_variable_from_ast(code, "hooks", &top_level), Ok(None));
+        assert_eq!(find_variable_from_ast(code, "item", &top_level), Ok(None));
+
+        let classes = VariableLookup {
+            top_level_only: false,
+            kinds: vec!["class".to_string()],
+        };
+        assert!(find_variable_from_ast(code, "Tooltip", &classes)
+            .unwrap()
+            .is_some());
+        assert_eq!(find_variable_from_ast(code, "Hooks", &classes), Ok(None));
+        assert!(find_variable_from_ast("let = ;", "Hooks", &any).is_err());
+    }
+
+    mod index_tests {
         use super::*;
 
         #[test]
@@ -956,6 +2081,49 @@ mod tests {
             println!("{}", updated_ast);
         }
 
+        #[test]
+        fn test_replace_ast_at_index_pruning_imports() {
+            let file_content = r#"import "phoenix_html";
+import { Socket } from "phoenix";
+import Chart, { ChartTooltip } from "../vendor/chart";
+import Unused from "unused";
+let hooks = { Chart, ChartTooltip };
+let socket = new Socket("/socket");
+"#;
+
+            let (code, pruned) = replace_ast_at_index_pruning_imports(
+                file_content,
+                "let hooks = { ChartTooltip };",
+                4,
+                true,
+            )
+            .unwrap();
+            assert_eq!(pruned, vec!["Chart"]);
+            assert!(code.contains(r#"import { ChartTooltip } from "../vendor/chart";"#));
+            assert!(code.contains(r#"import Unused from "unused";"#));
+            assert!(code.contains(r#"import "phoenix_html";"#));
+
+            // A local binding of the same name is not a use of the import
+            let replace_code =
+                "function draw(Chart, ChartTooltip) { return [Chart, ChartTooltip]; }";
+            let (code, pruned) =
+                replace_ast_at_index_pruning_imports(file_content, replace_code, 4, true).unwrap();
+            assert_eq!(pruned, vec!["Chart", "ChartTooltip"]);
+            assert!(!code.contains("../vendor/chart"));
+
+            // The replacement uses it
+            let replace_code = "const draw = () => new Chart(); let hooks = {};";
+            let (_, pruned) =
+                replace_ast_at_index_pruning_imports(file_content, replace_code, 4, true).unwrap();
+            assert_eq!(pruned, vec!["ChartTooltip"]);
+
+            let (code, pruned) =
+                replace_ast_at_index_pruning_imports(file_content, "let hooks = {};", 4, false)
+                    .unwrap();
+            assert!(pruned.is_empty());
+            assert!(code.contains("../vendor/chart"));
+        }
+
         #[test]
         fn test_replace_ast_at_index_of_bounds() {
             let file_content = "function a() {}";
@@ -0,0 +1,356 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Structural comparison of two sources at the granularity of their top-level statements,
+//! e.g. to tell which parts of a generated file a user customized before overwriting it.
+//!
+//! Statements are paired by what they declare: imports and re-exports by their source,
+//! declarations by their names, the default export as `default` and expression statements
+//! by their callee or assignment target, e.g. `liveSocket.connect`. Formatting, comments
+//! and quotes are ignored.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::*;
+use crate::parsers::javascript::matcher::callee_path;
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+use std::collections::HashMap;
+use swc_common::{sync::Lrc, EqIgnoreSpan, SourceMap, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+/// How a statement differs between the sources.
+///
+/// On the Elixir side these are the atoms `:added`, `:removed` and `:changed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// What a top-level statement is.
+///
+/// On the Elixir side these are the atoms `:import`, `:export`, `:variable`, `:function`,
+/// `:class`, `:expression` and `:other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum StatementCategory {
+    /// `import ... from "source"`.
+    Import,
+    /// `export ... from "source"`, `export { ... }` and `export default ...`.
+    Export,
+    /// `let`, `const` and `var` declarations, exported or not.
+    Variable,
+    /// Function declarations, exported or not.
+    Function,
+    /// Class declarations, exported or not.
+    Class,
+    /// Expression statements, e.g. `liveSocket.connect()`.
+    Expression,
+    /// Anything else, e.g. an `if` block.
+    Other,
+}
+
+/// A top-level statement found by [`ast_diff`] that was added, removed or changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementChange {
+    pub kind: ChangeKind,
+    pub category: StatementCategory,
+    /// What the statement declares, e.g. the source of an import or the names of a
+    /// declaration, when it is known.
+    pub name: Option<String>,
+    /// The location in the old source, unless the statement was added.
+    pub old_location: Option<SourceLocation>,
+    /// The location in the new source, unless the statement was removed.
+    pub new_location: Option<SourceLocation>,
+}
+
+/// Clears the raw text of literals, so `'a'` and `"a"` compare equal.
+pub(crate) struct DropRaw;
+
+impl VisitMut for DropRaw {
+    fn visit_mut_str(&mut self, str: &mut Str) {
+        str.raw = None;
+    }
+
+    fn visit_mut_number(&mut self, number: &mut Number) {
+        number.raw = None;
+    }
+
+    fn visit_mut_big_int(&mut self, big_int: &mut BigInt) {
+        big_int.raw = None;
+    }
+}
+
+fn decl_identity(decl: &Decl) -> (StatementCategory, Option<String>) {
+    match decl {
+        Decl::Var(var) => {
+            let names: Vec<String> = var
+                .decls
+                .iter()
+                .filter_map(|declarator| declarator.name.as_ident())
+                .map(|ident| ident.sym.to_string())
+                .collect();
+            let name = (!names.is_empty()).then(|| names.join(", "));
+            (StatementCategory::Variable, name)
+        }
+        Decl::Fn(function) => (
+            StatementCategory::Function,
+            Some(function.ident.sym.to_string()),
+        ),
+        Decl::Class(class) => (StatementCategory::Class, Some(class.ident.sym.to_string())),
+        _ => (StatementCategory::Other, None),
+    }
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string(),
+    }
+}
+
+/// The category of a statement and what it declares, which pairs it with its counterpart.
+pub(crate) fn identity(item: &ModuleItem) -> (StatementCategory, Option<String>) {
+    match item {
+        ModuleItem::ModuleDecl(decl) => match decl {
+            ModuleDecl::Import(import) => (
+                StatementCategory::Import,
+                Some(import.src.value.to_string()),
+            ),
+            ModuleDecl::ExportDecl(export) => decl_identity(&export.decl),
+            ModuleDecl::ExportNamed(export) => {
+                let name = match &export.src {
+                    Some(src) => src.value.to_string(),
+                    None => export
+                        .specifiers
+                        .iter()
+                        .map(|specifier| match specifier {
+                            ExportSpecifier::Named(named) => {
+                                export_name(named.exported.as_ref().unwrap_or(&named.orig))
+                            }
+                            ExportSpecifier::Namespace(namespace) => export_name(&namespace.name),
+                            ExportSpecifier::Default(default) => default.exported.sym.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                (StatementCategory::Export, Some(name))
+            }
+            ModuleDecl::ExportAll(export) => (
+                StatementCategory::Export,
+                Some(export.src.value.to_string()),
+            ),
+            ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+                (StatementCategory::Export, Some("default".into()))
+            }
+            _ => (StatementCategory::Other, None),
+        },
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl_identity(decl),
+        ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) => {
+            let name = match &**expr {
+                Expr::Call(CallExpr {
+                    callee: Callee::Expr(callee),
+                    ..
+                }) => callee_path(callee),
+                Expr::Assign(AssignExpr {
+                    left: AssignTarget::Simple(SimpleAssignTarget::Member(member)),
+                    ..
+                }) => callee_path(&Expr::Member(member.clone())),
+                Expr::Assign(AssignExpr {
+                    left: AssignTarget::Simple(SimpleAssignTarget::Ident(ident)),
+                    ..
+                }) => Some(ident.sym.to_string()),
+                _ => None,
+            };
+            (StatementCategory::Expression, name)
+        }
+        ModuleItem::Stmt(_) => (StatementCategory::Other, None),
+    }
+}
+
+struct Statement {
+    item: ModuleItem,
+    category: StatementCategory,
+    name: Option<String>,
+    location: SourceLocation,
+}
+
+fn statements(source: &str) -> Result<Vec<Statement>, String> {
+    let (module, _comments, cm): (Module, _, Lrc<SourceMap>) = parse(source)?;
+
+    Ok(module
+        .body
+        .into_iter()
+        .map(|mut item| {
+            let location = SourceLocation::from_span(&cm, item.span());
+            let (category, name) = identity(&item);
+            item.visit_mut_with(&mut DropRaw);
+            Statement {
+                item,
+                category,
+                name,
+                location,
+            }
+        })
+        .collect())
+}
+
+/// Compares two sources statement by statement, ignoring formatting and comments.
+///
+/// Statements with a name are paired with the statement of the same category and name, in
+/// order when there are several, and reported as changed when their code differs.
+/// Statements without a name, e.g. an `if` block, are paired with an identical statement,
+/// so a change to them is reported as a removal and an addition.
+///
+/// # Returns
+/// * `Ok(Vec<StatementChange>)` - The removed and changed statements in the order of the
+///   old source, then the added statements in the order of the new source. It is empty
+///   when the sources are the same program.
+/// * `Err(String)` - If either source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let old = "import { Socket } from \"phoenix\";\nlet csrfToken = 1;";
+/// let new = "import { Socket } from 'phoenix';\nlet csrfToken = 2;\nimport topbar from \"topbar\";";
+/// let changes = ast_diff(old, new).unwrap();
+/// assert_eq!(changes[0].kind, ChangeKind::Changed);
+/// assert_eq!(changes[0].name.as_deref(), Some("csrfToken"));
+/// assert_eq!(changes[1].kind, ChangeKind::Added);
+/// assert_eq!(changes[1].category, StatementCategory::Import);
+/// ```
+pub fn ast_diff(old_source: &str, new_source: &str) -> Result<Vec<StatementChange>, String> {
+    let old = statements(old_source)?;
+    let new = statements(new_source)?;
+    let mut paired = vec![false; new.len()];
+    let mut changes = vec![];
+
+    // The unpaired statements of the new source with a name, by category and name
+    let mut named: HashMap<(StatementCategory, &str), Vec<usize>> = HashMap::new();
+    for (index, statement) in new.iter().enumerate().rev() {
+        if let Some(name) = &statement.name {
+            named
+                .entry((statement.category, name))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    for statement in &old {
+        let counterpart = match &statement.name {
+            Some(name) => named
+                .get_mut(&(statement.category, name.as_str()))
+                .and_then(Vec::pop),
+            None => new.iter().enumerate().position(|(index, other)| {
+                !paired[index] && other.name.is_none() && other.item.eq_ignore_span(&statement.item)
+            }),
+        };
+
+        let change = |kind, new_location| StatementChange {
+            kind,
+            category: statement.category,
+            name: statement.name.clone(),
+            old_location: Some(statement.location.clone()),
+            new_location,
+        };
+        match counterpart {
+            Some(index) => {
+                paired[index] = true;
+                if !new[index].item.eq_ignore_span(&statement.item) {
+                    changes.push(change(
+                        ChangeKind::Changed,
+                        Some(new[index].location.clone()),
+                    ));
+                }
+            }
+            None => changes.push(change(ChangeKind::Removed, None)),
+        }
+    }
+
+    changes.extend(
+        new.iter()
+            .zip(paired)
+            .filter(|(_, paired)| !paired)
+            .map(|(statement, _)| StatementChange {
+                kind: ChangeKind::Added,
+                category: statement.category,
+                name: statement.name.clone(),
+                old_location: None,
+                new_location: Some(statement.location.clone()),
+            }),
+    );
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(changes: &[StatementChange]) -> Vec<(ChangeKind, StatementCategory, Option<&str>)> {
+        changes
+            .iter()
+            .map(|change| (change.kind, change.category, change.name.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ast_diff() {
+        let old = r#"import { Socket } from "phoenix";
+import topbar from "../vendor/topbar";
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+function setup() { return 1; }
+if (debug) { console.log("debug"); }
+liveSocket.connect();
+"#;
+        let new = r#"import {Socket} from 'phoenix'
+// The token of the session
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content")
+import Alpine from "alpinejs";
+if (debug) { console.log("debugging"); }
+liveSocket.connect();
+function setup() { return 2; }
+"#;
+
+        let changes = ast_diff(old, new).unwrap();
+        use ChangeKind::*;
+        use StatementCategory::*;
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (Removed, Import, Some("../vendor/topbar")),
+                (Changed, Function, Some("setup")),
+                (Removed, Other, None),
+                (Added, Import, Some("alpinejs")),
+                (Added, Other, None),
+            ]
+        );
+
+        let setup = &changes[1];
+        assert_eq!(setup.old_location.as_ref().map(|l| l.line), Some(4));
+        assert_eq!(setup.new_location.as_ref().map(|l| l.line), Some(7));
+
+        assert!(ast_diff(old, old).unwrap().is_empty());
+        assert!(ast_diff(old, "let = ;").is_err());
+    }
+
+    #[test]
+    fn test_ast_diff_repeated_statements() {
+        let old = "Hooks.Copy = copy;\nHooks.Copy = other;\n";
+        let new = "Hooks.Copy = copy;\n";
+
+        let changes = ast_diff(old, new).unwrap();
+        assert_eq!(
+            summary(&changes),
+            vec![(
+                ChangeKind::Removed,
+                StatementCategory::Expression,
+                Some("Hooks.Copy")
+            )]
+        );
+        assert_eq!(changes[0].old_location.as_ref().map(|l| l.line), Some(2));
+    }
+}
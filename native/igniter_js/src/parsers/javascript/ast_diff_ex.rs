@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_diff::*;
+use crate::parsers::javascript::ast_ex::SourceLocationResult;
+
+use rustler::{Encoder, Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct StatementChangeResult {
+    pub kind: ChangeKind,
+    pub category: StatementCategory,
+    pub name: Option<String>,
+    pub old_location: Option<SourceLocationResult>,
+    pub new_location: Option<SourceLocationResult>,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn ast_diff_nif(
+    env: Env,
+    old_source: String,
+    new_source: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::ast_diff_nif();
+
+        match ast_diff(&old_source, &new_source) {
+            Ok(changes) => {
+                let result: Vec<StatementChangeResult> = changes
+                    .into_iter()
+                    .map(|change| StatementChangeResult {
+                        kind: change.kind,
+                        category: change.category,
+                        name: change.name,
+                        old_location: change.old_location.map(Into::into),
+                        new_location: change.new_location.map(Into::into),
+                    })
+                    .collect();
+                encode_response(env, atoms::ok(), fn_atom, result.encode(env))
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
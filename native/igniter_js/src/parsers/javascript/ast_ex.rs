@@ -3,28 +3,40 @@
 //
 // SPDX-License-Identifier: MIT
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::atoms;
-use crate::helpers::encode_response;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_edit, encode_response, Edit};
 use crate::parsers::javascript::ast::*;
-use crate::parsers::javascript::ast_json::convert_ast_to_estree;
+use crate::parsers::javascript::ast_json::{
+    convert_ast_to_estree_statements, convert_ast_to_estree_with_options, EstreeOptions,
+};
+use crate::parsers::javascript::estree_codegen::estree_to_source;
+use crate::parsers::javascript::object::{
+    extend_object_property_by_names_to_ast, merge_object_literal_to_ast, ObjectTarget,
+};
 use crate::parsers::javascript::phoenix::*;
-use rustler::{Env, NifResult, NifStruct, NifTaggedEnum, Term};
+use crate::parsers::javascript::removal::RemovalMode;
+use crate::parsers::javascript::sdk::find_sdk_initializations_from_ast;
+use rustler::{Atom, Encoder, Env, NifMap, NifResult, NifStruct, NifTaggedEnum, Term};
 
 #[rustler::nif]
 pub fn is_module_imported_from_ast_nif(
     env: Env,
     file_content: String,
     module_name: String,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let fn_atom = atoms::is_module_imported_from_ast_nif();
-    let (status, result) = match is_module_imported_from_ast(&file_content, &module_name) {
-        Ok(true) => (atoms::ok(), true),
-        _ => (atoms::error(), false),
-    };
+    run_call(call_options, || {
+        let fn_atom = atoms::is_module_imported_from_ast_nif();
+        let (status, result) = match is_module_imported_from_ast(&file_content, &module_name) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
 
-    encode_response(env, status, fn_atom, result)
+        encode_response(env, status, fn_atom, result)
+    })
 }
 
 #[rustler::nif]
@@ -32,35 +44,210 @@ pub fn insert_import_to_ast_nif(
     env: Env,
     file_content: String,
     import_lines: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_import_to_ast(&file_content, &import_lines);
+
+        encode_edit(
+            env,
+            atoms::insert_import_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn insert_import_at_position_to_ast_nif(
+    env: Env,
+    file_content: String,
+    import_lines: String,
+    position: ImportPosition,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = insert_import_at_position_to_ast(&file_content, &import_lines, position);
+
+        encode_edit(
+            env,
+            atoms::insert_import_at_position_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+fn remove_import_from_ast_nif(
+    env: Env,
+    file_content: String,
+    modules: String,
+    mode: RemovalMode,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = remove_import_from_ast(&file_content, &modules, mode);
+
+        encode_edit(
+            env,
+            atoms::remove_import_from_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn replace_import_nif(
+    env: Env,
+    file_content: String,
+    source: String,
+    import_lines: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::replace_import_nif();
+
+        let result = replace_import_to_ast(&file_content, &source, &import_lines);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn find_live_socket_node_from_ast_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::find_live_socket_node_from_ast();
+
+        let (status, result) = match find_live_socket_node_from_ast(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn has_live_socket_connect_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::has_live_socket_connect_nif();
+
+        let (status, result) = match has_live_socket_connect_from_ast(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn has_topbar_config_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::has_topbar_config_nif();
+
+        let (status, result) = match has_topbar_config_from_ast(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn merge_topbar_config_nif(
+    env: Env,
+    file_content: String,
+    json_spec: String,
+    overwrite: bool,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::merge_topbar_config_nif();
+
+        let result = merge_topbar_config_to_ast(&file_content, &json_spec, overwrite);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn ensure_live_socket_boilerplate_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let (status, result) = match insert_import_to_ast(&file_content, &import_lines) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+    run_call(call_options, || {
+        let fn_atom = atoms::ensure_live_socket_boilerplate_nif();
+
+        let result = ensure_live_socket_boilerplate_to_ast(&file_content);
 
-    encode_response(env, status, atoms::insert_import_to_ast_nif(), result)
+        encode_edit(env, fn_atom, &file_content, result)
+    })
 }
 
 #[rustler::nif]
-fn remove_import_from_ast_nif(env: Env, file_content: String, modules: String) -> NifResult<Term> {
-    let (status, result) = match remove_import_from_ast(&file_content, &modules) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+pub fn has_live_socket_exposure_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::has_live_socket_exposure_nif();
+
+        let (status, result) = match has_live_socket_exposure_from_ast(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
 
-    encode_response(env, status, atoms::remove_import_from_ast_nif(), result)
+        encode_response(env, status, fn_atom, result)
+    })
 }
 
 #[rustler::nif]
-pub fn find_live_socket_node_from_ast_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::find_live_socket_node_from_ast();
+pub fn ensure_live_socket_exposed_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::ensure_live_socket_exposed_nif();
 
-    let (status, result) = match find_live_socket_node_from_ast(&file_content) {
-        Ok(true) => (atoms::ok(), true),
-        _ => (atoms::error(), false),
-    };
+        let result = ensure_live_socket_exposed_to_ast(&file_content);
 
-    encode_response(env, status, fn_atom, result)
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn remove_live_socket_exposure_nif(
+    env: Env,
+    file_content: String,
+    mode: RemovalMode,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_live_socket_exposure_nif();
+
+        let result = remove_live_socket_exposure_from_ast(&file_content, mode);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
 }
 
 #[rustler::nif]
@@ -68,15 +255,101 @@ pub fn contains_variable_from_ast_nif(
     env: Env,
     file_content: String,
     variable_name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::contains_variable_from_ast_nif();
+
+        let (status, result) = match contains_variable_from_ast(&file_content, &variable_name) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[derive(Debug, NifMap)]
+pub struct VariableMatchResult {
+    pub kind: Atom,
+    pub top_level: bool,
+    pub init: Option<Atom>,
+}
+
+#[rustler::nif]
+pub fn find_variable_from_ast_nif(
+    env: Env,
+    file_content: String,
+    variable_name: String,
+    top_level_only: bool,
+    kinds: Vec<String>,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let fn_atom = atoms::contains_variable_from_ast_nif();
+    run_call(call_options, || {
+        let fn_atom = atoms::find_variable_from_ast_nif();
+        let lookup = VariableLookup {
+            top_level_only,
+            kinds,
+        };
+
+        match find_variable_from_ast(&file_content, &variable_name, &lookup) {
+            Ok(Some(found)) => {
+                let result = VariableMatchResult {
+                    kind: Atom::from_str(env, found.kind)?,
+                    top_level: found.top_level,
+                    init: found
+                        .init
+                        .map(|init| Atom::from_str(env, init))
+                        .transpose()?,
+                };
+                encode_response(env, atoms::ok(), fn_atom, result)
+            }
+            Ok(None) => encode_response(env, atoms::error(), fn_atom, false),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[derive(Debug, NifMap)]
+pub struct VariableInitializerResult {
+    pub kind: Atom,
+    pub source: String,
+    pub top_level: bool,
+    pub start: usize,
+    pub end: usize,
+}
 
-    let (status, result) = match contains_variable_from_ast(&file_content, &variable_name) {
-        Ok(true) => (atoms::ok(), true),
-        _ => (atoms::error(), false),
-    };
+#[rustler::nif]
+pub fn get_variable_initializer_nif(
+    env: Env,
+    file_content: String,
+    path: String,
+    top_level_only: bool,
+    kinds: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::get_variable_initializer_nif();
+        let lookup = VariableLookup {
+            top_level_only,
+            kinds,
+        };
 
-    encode_response(env, status, fn_atom, result)
+        match get_variable_initializer_from_ast(&file_content, &path, &lookup) {
+            Ok(Some(found)) => {
+                let result = VariableInitializerResult {
+                    kind: Atom::from_str(env, found.kind)?,
+                    source: found.source,
+                    top_level: found.top_level,
+                    start: found.start,
+                    end: found.end,
+                };
+                encode_response(env, atoms::ok(), fn_atom, result)
+            }
+            Ok(None) => encode_response(env, atoms::error(), fn_atom, false),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
 }
 
 #[rustler::nif]
@@ -84,16 +357,184 @@ pub fn extend_hook_object_to_ast_nif(
     env: Env,
     file_content: String,
     names: Vec<String>,
+    order: EntryOrder,
+    key_position: KeyPosition,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let unique_names: HashSet<String> = names.into_iter().collect();
-    let mut vec_of_strs: Vec<&str> = unique_names.iter().map(|s| s.as_str()).collect();
-    vec_of_strs.sort();
-    let (status, result) = match extend_hook_object_to_ast(&file_content, vec_of_strs) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+    run_call(call_options, || {
+        let unique_names: BTreeSet<String> = names.into_iter().collect();
+        let vec_of_strs: Vec<&str> = unique_names.iter().map(|s| s.as_str()).collect();
+        let fn_atom = atoms::extend_hook_object_to_ast_nif();
+        match extend_hook_object_in_order_to_ast(&file_content, vec_of_strs, order, key_position) {
+            Ok(updated_code) => encode_response(
+                env,
+                atoms::ok(),
+                fn_atom,
+                Edit::new(&file_content, updated_code),
+            ),
+            Err(error_msg) => encode_live_socket_error(
+                env,
+                fn_atom,
+                &file_content,
+                LiveSocketTarget::Named("liveSocket"),
+                error_msg,
+            ),
+        }
+    })
+}
 
-    encode_response(env, status, atoms::extend_hook_object_to_ast_nif(), result)
+/// The LiveSocket a variable name targets, or all of them for `nil`.
+fn live_socket_target(target: &Option<String>) -> LiveSocketTarget<'_> {
+    match target {
+        Some(name) => LiveSocketTarget::Named(name),
+        None => LiveSocketTarget::All,
+    }
+}
+
+/// Encodes an error as `{:unsupported_options_shape, callee}` when the LiveSocket options are
+/// built by a call, e.g. `buildOpts()`, otherwise as the message.
+fn encode_live_socket_error<'a>(
+    env: Env<'a>,
+    fn_atom: Atom,
+    file_content: &str,
+    target: LiveSocketTarget,
+    error_msg: String,
+) -> NifResult<Term<'a>> {
+    match unsupported_live_socket_options(file_content, target) {
+        Some(callee) => encode_response(
+            env,
+            atoms::error(),
+            fn_atom,
+            (atoms::unsupported_options_shape(), callee),
+        ),
+        None => encode_response(env, atoms::error(), fn_atom, error_msg),
+    }
+}
+
+#[rustler::nif]
+pub fn extend_live_socket_option_to_ast_nif(
+    env: Env,
+    file_content: String,
+    option: LiveSocketOption,
+    entries: Vec<String>,
+    target: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::extend_live_socket_option_to_ast_nif();
+        let entries: Vec<&str> = entries.iter().map(|s| s.as_str()).collect();
+        let target = live_socket_target(&target);
+        match extend_live_socket_option_for_target_to_ast(
+            &file_content,
+            target,
+            option.key(),
+            entries,
+        ) {
+            Ok(updated_code) => encode_response(
+                env,
+                atoms::ok(),
+                fn_atom,
+                Edit::new(&file_content, updated_code),
+            ),
+            Err(error_msg) => {
+                encode_live_socket_error(env, fn_atom, &file_content, target, error_msg)
+            }
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn remove_live_socket_option_entries_from_ast_nif(
+    env: Env,
+    file_content: String,
+    option: LiveSocketOption,
+    entries: Vec<String>,
+    target: Option<String>,
+    mode: RemovalMode,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_live_socket_option_entries_from_ast_nif();
+        let entries: Vec<&str> = entries.iter().map(|s| s.as_str()).collect();
+        let target = live_socket_target(&target);
+        match remove_live_socket_option_entries_with_log_from_ast(
+            &file_content,
+            target,
+            option.key(),
+            entries,
+            mode,
+            false,
+        ) {
+            Ok(log) => encode_response(
+                env,
+                atoms::ok(),
+                fn_atom,
+                Edit::new(&file_content, log.code),
+            ),
+            Err(error_msg) => {
+                encode_live_socket_error(env, fn_atom, &file_content, target, error_msg)
+            }
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn extend_live_socket_option_with_log_nif(
+    env: Env,
+    file_content: String,
+    option: LiveSocketOption,
+    entries: Vec<String>,
+    target: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::extend_live_socket_option_with_log_nif();
+        let entries: Vec<&str> = entries.iter().map(|s| s.as_str()).collect();
+        let target = live_socket_target(&target);
+
+        match extend_live_socket_option_with_log_to_ast(
+            &file_content,
+            target,
+            option.key(),
+            entries,
+        ) {
+            Ok(log) => encode_response(env, atoms::ok(), fn_atom, log),
+            Err(error_msg) => {
+                encode_live_socket_error(env, fn_atom, &file_content, target, error_msg)
+            }
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn remove_live_socket_option_entries_with_log_nif(
+    env: Env,
+    file_content: String,
+    option: LiveSocketOption,
+    entries: Vec<String>,
+    target: Option<String>,
+    mode: RemovalMode,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_live_socket_option_entries_with_log_nif();
+        let entries: Vec<&str> = entries.iter().map(|s| s.as_str()).collect();
+        let target = live_socket_target(&target);
+
+        match remove_live_socket_option_entries_with_log_from_ast(
+            &file_content,
+            target,
+            option.key(),
+            entries,
+            mode,
+            false,
+        ) {
+            Ok(log) => encode_response(env, atoms::ok(), fn_atom, log),
+            Err(error_msg) => {
+                encode_live_socket_error(env, fn_atom, &file_content, target, error_msg)
+            }
+        }
+    })
 }
 
 #[rustler::nif]
@@ -101,15 +542,97 @@ fn remove_objects_of_hooks_from_ast_nif(
     env: Env,
     file_content: String,
     object_names: Vec<String>,
+    mode: RemovalMode,
+    prune_unused_imports: bool,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let fn_atom = atoms::remove_objects_of_hooks_from_ast_nif();
-    let vec_of_strs: Vec<&str> = object_names.iter().map(|s| s.as_str()).collect();
-    let (status, result) = match remove_objects_of_hooks_from_ast(&file_content, vec_of_strs) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_objects_of_hooks_from_ast_nif();
+        let vec_of_strs: Vec<&str> = object_names.iter().map(|s| s.as_str()).collect();
+        match remove_live_socket_option_entries_with_log_from_ast(
+            &file_content,
+            LiveSocketTarget::Named("liveSocket"),
+            "hooks",
+            vec_of_strs,
+            mode,
+            prune_unused_imports,
+        ) {
+            Ok(log) => {
+                let pruned_imports = log
+                    .actions
+                    .into_iter()
+                    .filter_map(|action| match action {
+                        EditAction::PrunedImport(local) => Some(local),
+                        _ => None,
+                    })
+                    .collect();
+                encode_pruned_edit(
+                    env,
+                    fn_atom,
+                    &file_content,
+                    Ok((log.code, pruned_imports)),
+                    prune_unused_imports,
+                )
+            }
+            Err(error_msg) => encode_live_socket_error(
+                env,
+                fn_atom,
+                &file_content,
+                LiveSocketTarget::Named("liveSocket"),
+                error_msg,
+            ),
+        }
+    })
+}
+
+/// An [`Edit`] along with the local names of the imports it left unused and removed.
+#[derive(Debug, NifMap)]
+pub struct PrunedEdit {
+    pub code: String,
+    pub changed: bool,
+    pub pruned_imports: Vec<String>,
+}
 
-    encode_response(env, status, fn_atom, result)
+/// Encodes the response of an edit NIF taking the `prune_unused_imports` option: the
+/// [`Edit`] without it, the [`PrunedEdit`] with it.
+fn encode_pruned_edit<'a>(
+    env: Env<'a>,
+    fn_atom: Atom,
+    source: &str,
+    result: Result<(String, Vec<String>), String>,
+    prune_unused_imports: bool,
+) -> NifResult<Term<'a>> {
+    match result {
+        Ok((code, pruned_imports)) if prune_unused_imports => {
+            let Edit { code, changed } = Edit::new(source, code);
+            let edit = PrunedEdit {
+                code,
+                changed,
+                pruned_imports,
+            };
+            encode_response(env, atoms::ok(), fn_atom, edit)
+        }
+        result => encode_edit(env, fn_atom, source, result.map(|(code, _)| code)),
+    }
+}
+
+#[derive(Debug, NifMap)]
+pub struct SourceLocationResult {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<SourceLocation> for SourceLocationResult {
+    fn from(location: SourceLocation) -> Self {
+        Self {
+            start: location.start,
+            end: location.end,
+            line: location.line,
+            column: location.column,
+        }
+    }
 }
 
 #[derive(Debug, NifStruct)]
@@ -121,6 +644,13 @@ pub struct ASTStatisticsResult {
     pub imports: usize,
     pub trys: usize,
     pub throws: usize,
+    pub exports: usize,
+    pub arrow_functions: usize,
+    pub async_functions: usize,
+    pub top_level_awaits: usize,
+    pub console_calls: usize,
+    pub event_listeners: usize,
+    pub locations: Option<HashMap<Atom, Vec<SourceLocationResult>>>,
 }
 
 #[derive(Debug, NifTaggedEnum)]
@@ -130,25 +660,66 @@ pub enum ASTStatisticsResultType {
 }
 
 #[rustler::nif]
-fn statistics_from_ast_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::statistics_from_ast_nif();
+fn statistics_from_ast_nif(
+    env: Env,
+    file_content: String,
+    with_locations: bool,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::statistics_from_ast_nif();
 
-    let (status, result) = match statistics_from_ast(&file_content) {
-        Ok(updated_code) => (
-            atoms::ok(),
-            ASTStatisticsResultType::Statistics(ASTStatisticsResult {
-                imports: updated_code.imports,
-                classes: updated_code.classes,
-                debuggers: updated_code.debuggers,
-                functions: updated_code.functions,
-                throws: updated_code.throws,
-                trys: updated_code.trys,
-            }),
-        ),
-        Err(error_msg) => (atoms::error(), ASTStatisticsResultType::Error(error_msg)),
-    };
+        let statistics = if with_locations {
+            statistics_with_locations_from_ast(&file_content)
+        } else {
+            statistics_from_ast(&file_content)
+        };
 
-    encode_response(env, status, fn_atom, result)
+        let (status, result) = match statistics {
+            Ok(updated_code) => {
+                let locations = match updated_code.locations {
+                    Some(locations) => {
+                        let mut grouped: HashMap<Atom, Vec<SourceLocationResult>> = HashMap::new();
+                        for (category, location) in locations {
+                            grouped
+                                .entry(Atom::from_str(env, category)?)
+                                .or_default()
+                                .push(SourceLocationResult {
+                                    start: location.start,
+                                    end: location.end,
+                                    line: location.line,
+                                    column: location.column,
+                                });
+                        }
+                        Some(grouped)
+                    }
+                    None => None,
+                };
+
+                (
+                    atoms::ok(),
+                    ASTStatisticsResultType::Statistics(ASTStatisticsResult {
+                        imports: updated_code.imports,
+                        classes: updated_code.classes,
+                        debuggers: updated_code.debuggers,
+                        functions: updated_code.functions,
+                        throws: updated_code.throws,
+                        trys: updated_code.trys,
+                        exports: updated_code.exports,
+                        arrow_functions: updated_code.arrow_functions,
+                        async_functions: updated_code.async_functions,
+                        top_level_awaits: updated_code.top_level_awaits,
+                        console_calls: updated_code.console_calls,
+                        event_listeners: updated_code.event_listeners,
+                        locations,
+                    }),
+                )
+            }
+            Err(error_msg) => (atoms::error(), ASTStatisticsResultType::Error(error_msg)),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
 }
 
 #[rustler::nif]
@@ -157,33 +728,93 @@ pub fn extend_var_object_property_by_names_to_ast_nif(
     file_content: String,
     var_name: String,
     object_names: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let unique_names: BTreeSet<String> = object_names.into_iter().collect();
+        let vec_of_strs: Vec<&str> = unique_names.iter().map(|s| s.as_str()).collect();
+
+        let result =
+            extend_var_object_property_by_names_to_ast(&file_content, &var_name, vec_of_strs);
+
+        encode_edit(
+            env,
+            atoms::extend_var_object_property_by_names_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn remove_var_object_properties_nif(
+    env: Env,
+    file_content: String,
+    var_name: String,
+    keys: Vec<String>,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let unique_names: HashSet<String> = object_names.into_iter().collect();
-    let mut vec_of_strs: Vec<&str> = unique_names.iter().map(|s| s.as_str()).collect();
-    vec_of_strs.sort();
+    run_call(call_options, || {
+        let keys = keys.iter().map(String::as_str);
+        let result = remove_var_object_properties_from_ast(&file_content, &var_name, keys);
+
+        encode_edit(
+            env,
+            atoms::remove_var_object_properties_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
 
-    let (status, result) =
-        match extend_var_object_property_by_names_to_ast(&file_content, &var_name, vec_of_strs) {
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn convert_ast_to_estree_nif(
+    env: Env,
+    file_content: String,
+    options: EstreeOptions,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match convert_ast_to_estree_with_options(&file_content, &options) {
             Ok(updated_code) => (atoms::ok(), updated_code),
             Err(error_msg) => (atoms::error(), error_msg),
         };
 
-    encode_response(
-        env,
-        status,
-        atoms::extend_var_object_property_by_names_to_ast_nif(),
-        result,
-    )
+        encode_response(env, status, atoms::convert_ast_to_estree_nif(), result)
+    })
 }
 
-#[rustler::nif]
-pub fn convert_ast_to_estree_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let (status, result) = match convert_ast_to_estree(&file_content) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn convert_ast_to_estree_statements_nif(
+    env: Env,
+    file_content: String,
+    options: EstreeOptions,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::convert_ast_to_estree_statements_nif();
 
-    encode_response(env, status, atoms::convert_ast_to_estree_nif(), result)
+        match convert_ast_to_estree_statements(&file_content, &options) {
+            Ok(fragments) => encode_response(env, atoms::ok(), fn_atom, fragments),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn estree_to_source_nif(
+    env: Env,
+    estree_json: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match estree_to_source(&estree_json) {
+            Ok(updated_code) => (atoms::ok(), updated_code),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, atoms::estree_to_source_nif(), result)
+    })
 }
 
 #[rustler::nif]
@@ -192,13 +823,13 @@ pub fn insert_ast_at_index_nif(
     file_content: String,
     insert_code: String,
     index: usize,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let (status, result) = match insert_ast_at_index(&file_content, &insert_code, index) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+    run_call(call_options, || {
+        let result = insert_ast_at_index(&file_content, &insert_code, index);
 
-    encode_response(env, status, atoms::insert_ast_at_index_nif(), result)
+        encode_edit(env, atoms::insert_ast_at_index_nif(), &file_content, result)
+    })
 }
 
 #[rustler::nif]
@@ -207,11 +838,194 @@ pub fn replace_ast_at_index_nif(
     file_content: String,
     replace_code: String,
     index: usize,
+    prune_unused_imports: bool,
+    call_options: CallOptions,
 ) -> NifResult<Term> {
-    let (status, result) = match replace_ast_at_index(&file_content, &replace_code, index) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
+    run_call(call_options, || {
+        let result = replace_ast_at_index_pruning_imports(
+            &file_content,
+            &replace_code,
+            index,
+            prune_unused_imports,
+        );
+
+        encode_pruned_edit(
+            env,
+            atoms::replace_ast_at_index_nif(),
+            &file_content,
+            result,
+            prune_unused_imports,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn find_sdk_initializations_from_ast_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match find_sdk_initializations_from_ast(&file_content) {
+            Ok(found) => (atoms::ok(), found),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(
+            env,
+            status,
+            atoms::find_sdk_initializations_from_ast_nif(),
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn ensure_colocated_hooks_to_ast_nif(
+    env: Env,
+    file_content: String,
+    app_name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = ensure_colocated_hooks_to_ast(&file_content, &app_name);
+
+        encode_edit(
+            env,
+            atoms::ensure_colocated_hooks_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn list_hooks_from_ast_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match list_hooks_from_ast(&file_content) {
+            Ok(names) => (atoms::ok(), names.encode(env)),
+            Err(error_msg) => (atoms::error(), error_msg.encode(env)),
+        };
+
+        encode_response(env, status, atoms::list_hooks_from_ast_nif(), result)
+    })
+}
+
+#[rustler::nif]
+pub fn list_live_sockets_from_ast_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let (status, result) = match list_live_sockets_from_ast(&file_content) {
+            Ok(names) => (atoms::ok(), names.encode(env)),
+            Err(error_msg) => (atoms::error(), error_msg.encode(env)),
+        };
+
+        encode_response(env, status, atoms::list_live_sockets_from_ast_nif(), result)
+    })
+}
+
+#[rustler::nif]
+pub fn extend_live_socket_params_to_ast_nif(
+    env: Env,
+    file_content: String,
+    params: Vec<(String, String)>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let params = params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        let result = extend_live_socket_params_to_ast(&file_content, params);
+
+        encode_edit(
+            env,
+            atoms::extend_live_socket_params_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn wrap_live_socket_hooks_to_ast_nif(
+    env: Env,
+    file_content: String,
+    callee: String,
+    callee_args: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let args = callee_args.iter().map(String::as_str).collect();
+        let result = wrap_live_socket_hooks_to_ast(&file_content, &callee, args);
+
+        encode_edit(
+            env,
+            atoms::wrap_live_socket_hooks_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn compose_on_before_el_updated_to_ast_nif(
+    env: Env,
+    file_content: String,
+    snippet: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = compose_on_before_el_updated_to_ast(&file_content, &snippet);
+
+        encode_edit(
+            env,
+            atoms::compose_on_before_el_updated_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
+
+#[rustler::nif]
+pub fn merge_object_literal_nif(
+    env: Env,
+    file_content: String,
+    var_name: String,
+    json_spec: String,
+    overwrite: bool,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::merge_object_literal_nif();
+        let result = merge_object_literal_to_ast(&file_content, &var_name, &json_spec, overwrite);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn extend_object_property_by_names_to_ast_nif(
+    env: Env,
+    file_content: String,
+    target: ObjectTarget,
+    object_names: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::extend_object_property_by_names_to_ast_nif();
+        let unique_names: BTreeSet<String> = object_names.into_iter().collect();
+        let vec_of_strs: Vec<&str> = unique_names.iter().map(|s| s.as_str()).collect();
+
+        let result = extend_object_property_by_names_to_ast(&file_content, &target, vec_of_strs);
 
-    encode_response(env, status, atoms::replace_ast_at_index_nif(), result)
+        encode_edit(env, fn_atom, &file_content, result)
+    })
 }
@@ -3,59 +3,225 @@
 //
 // SPDX-License-Identifier: MIT
 
+use crate::limits::{check_input_size, check_node_count};
+use crate::parser_options::decorators_enabled;
+use crate::parsers::javascript::deadline::check_depth;
+use crate::parsers::javascript::helpers::{detect_module_kind, ModuleKind};
+use crate::telemetry::{measure, Phase};
 use oxc_allocator::Allocator;
-use oxc_ast_visit::utf8_to_utf16::Utf8ToUtf16;
-use oxc_diagnostics::Severity;
-use oxc_parser::{ParseOptions, Parser};
+use oxc_ast::ast::{BindingPattern, Decorator, Expression, Program, Statement};
+use oxc_ast_visit::{utf8_to_utf16::Utf8ToUtf16, walk, Visit};
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use oxc_estree::{CompactTSSerializer, ESTree, PrettyTSSerializer};
+use oxc_parser::{ParseOptions, Parser, ParserReturn};
 use oxc_span::SourceType;
-use serde_json::json;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use serde_json::{json, Value};
 
-/// Converts JavaScript AST to the ESTree format.
-///
-/// This function takes JavaScript source code, parses it into an Abstract Syntax Tree (AST),
-/// and converts it into the ESTree-compatible JSON format. It also captures any parsing errors
-/// and comments within the source.
-///
-/// # Arguments
-/// * `source_text` - The JavaScript source code as a string.
-///
-/// # Returns
-/// * `Ok(String)` - A pretty-printed JSON representation of the AST in ESTree format.
-/// * `Err(String)` - If parsing or JSON serialization fails.
-///
-/// # Errors
-/// * Returns `"Failed to serialize JSON"` if the ESTree AST cannot be converted to JSON.
-/// * If there are syntax errors in `source_text`, they will be included in the `"errors"` field.
-///
-/// # Output Structure
-/// The returned JSON contains:
-/// * `"program"` - The parsed AST in ESTree format.
-/// * `"comments"` - Extracted comments from the source code.
-/// * `"errors"` - A list of syntax errors with details.
+/// How node positions are reported in the ESTree output.
 ///
-/// # Example
-/// ```rust
-/// let js_code = "function test() { console.log('Hello, world!'); } // Comment";
-/// let result = convert_ast_to_estree(js_code);
+/// On the Elixir side these are the atoms `:utf16`, `:utf8` and `:none`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum SpanFormat {
+    /// UTF-16 code unit offsets, as JavaScript tooling expects.
+    #[default]
+    Utf16,
+    /// UTF-8 byte offsets, to slice the source as an Elixir binary.
+    Utf8,
+    /// No `start`, `end` or `range` fields at all.
+    None,
+}
+
+/// Options for the ESTree conversion.
 ///
-/// assert!(result.is_ok());
-/// let json_output = result.unwrap();
-/// assert!(json_output.contains("\"type\": \"Program\""));
-/// assert!(json_output.contains("\"type\": \"FunctionDeclaration\""));
-/// assert!(json_output.contains("\"comments\""));
-/// ```
-pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
-    let source_type = SourceType::from_path("example.js").expect("Invalid file extension");
-    let allocator = Allocator::default();
-    let parser_return = Parser::new(&allocator, source_text, source_type)
+/// On the Elixir side this is a map, e.g.
+/// `%{compact: true, spans: :utf8, node_types: ["ImportDeclaration"]}`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct EstreeOptions {
+    /// Emit compact JSON instead of pretty-printed JSON, which is much smaller for large files.
+    pub compact: bool,
+    /// How node positions are reported.
+    pub spans: SpanFormat,
+    /// Only keep the top-level statements of these ESTree types, e.g. `ImportDeclaration`.
+    /// Empty keeps every statement.
+    pub node_types: Vec<String>,
+}
+
+/// The ESTree type of a top-level statement, without serializing it.
+pub(crate) fn statement_type(statement: &Statement) -> &'static str {
+    match statement {
+        Statement::BlockStatement(_) => "BlockStatement",
+        Statement::BreakStatement(_) => "BreakStatement",
+        Statement::ContinueStatement(_) => "ContinueStatement",
+        Statement::DebuggerStatement(_) => "DebuggerStatement",
+        Statement::DoWhileStatement(_) => "DoWhileStatement",
+        Statement::EmptyStatement(_) => "EmptyStatement",
+        Statement::ExpressionStatement(_) => "ExpressionStatement",
+        Statement::ForInStatement(_) => "ForInStatement",
+        Statement::ForOfStatement(_) => "ForOfStatement",
+        Statement::ForStatement(_) => "ForStatement",
+        Statement::IfStatement(_) => "IfStatement",
+        Statement::LabeledStatement(_) => "LabeledStatement",
+        Statement::ReturnStatement(_) => "ReturnStatement",
+        Statement::SwitchStatement(_) => "SwitchStatement",
+        Statement::ThrowStatement(_) => "ThrowStatement",
+        Statement::TryStatement(_) => "TryStatement",
+        Statement::WhileStatement(_) => "WhileStatement",
+        Statement::WithStatement(_) => "WithStatement",
+        Statement::VariableDeclaration(_) => "VariableDeclaration",
+        Statement::FunctionDeclaration(_) => "FunctionDeclaration",
+        Statement::ClassDeclaration(_) => "ClassDeclaration",
+        Statement::TSTypeAliasDeclaration(_) => "TSTypeAliasDeclaration",
+        Statement::TSInterfaceDeclaration(_) => "TSInterfaceDeclaration",
+        Statement::TSEnumDeclaration(_) => "TSEnumDeclaration",
+        Statement::TSModuleDeclaration(_) => "TSModuleDeclaration",
+        Statement::TSImportEqualsDeclaration(_) => "TSImportEqualsDeclaration",
+        Statement::ImportDeclaration(_) => "ImportDeclaration",
+        Statement::ExportAllDeclaration(_) => "ExportAllDeclaration",
+        Statement::ExportDefaultDeclaration(_) => "ExportDefaultDeclaration",
+        Statement::ExportNamedDeclaration(_) => "ExportNamedDeclaration",
+        Statement::TSExportAssignment(_) => "TSExportAssignment",
+        Statement::TSNamespaceExportDeclaration(_) => "TSNamespaceExportDeclaration",
+    }
+}
+
+/// Applies the node type filter and the span format to a parsed program.
+fn prepare_program(program: &mut Program, source_text: &str, options: &EstreeOptions) {
+    if !options.node_types.is_empty() {
+        program.body.retain(|statement| {
+            options
+                .node_types
+                .iter()
+                .any(|node_type| node_type == statement_type(statement))
+        });
+    }
+
+    if options.spans == SpanFormat::Utf16 {
+        Utf8ToUtf16::new(source_text).convert_program(program);
+    }
+}
+
+/// Removes the position fields of every node.
+fn strip_spans(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("start");
+            map.remove("end");
+            map.remove("range");
+            map.values_mut().for_each(strip_spans);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_spans),
+        _ => {}
+    }
+}
+
+/// Removes the position fields from a serialized node when spans are disabled.
+fn finish_json(json: String, options: &EstreeOptions) -> Result<String, String> {
+    if options.spans != SpanFormat::None {
+        return Ok(json);
+    }
+
+    let mut value: Value =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to serialize JSON: {:?}", e))?;
+    strip_spans(&mut value);
+
+    if options.compact {
+        serde_json::to_string(&value)
+    } else {
+        serde_json::to_string_pretty(&value)
+    }
+    .map_err(|e| format!("Failed to serialize JSON: {:?}", e))
+}
+
+/// Collects the decorators of a program, which OXC parses whatever the options.
+#[derive(Default)]
+struct DecoratorFinder {
+    errors: Vec<OxcDiagnostic>,
+}
+
+impl<'a> Visit<'a> for DecoratorFinder {
+    fn visit_decorator(&mut self, decorator: &Decorator<'a>) {
+        self.errors.push(
+            OxcDiagnostic::error("Decorators are not enabled")
+                .with_help("Enable them with the `decorators` parser option")
+                .with_label(decorator.span),
+        );
+    }
+}
+
+/// Parses a program with OXC under the limits of [`crate::limits`], the entry point of every
+/// OXC parse of the crate.
+pub(crate) fn parse_program<'a>(
+    allocator: &'a Allocator,
+    source_text: &'a str,
+) -> Result<ParserReturn<'a>, String> {
+    check_input_size(source_text.len())?;
+    check_depth(source_text)?;
+
+    let parser_return = measure(Phase::Parse, source_text.len(), || {
+        parse_program_with_decorators(allocator, source_text, decorators_enabled())
+    });
+    check_node_count(|| {
+        let mut counter = NodeCounter::default();
+        counter.visit_program(&parser_return.program);
+        counter.count
+    })?;
+    Ok(parser_return)
+}
+
+/// Counts the statements, expressions and binding patterns of a program, like the SWC
+/// parse does.
+#[derive(Default)]
+struct NodeCounter {
+    count: usize,
+}
+
+impl<'a> Visit<'a> for NodeCounter {
+    fn visit_statement(&mut self, statement: &Statement<'a>) {
+        self.count += 1;
+        walk::walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression<'a>) {
+        self.count += 1;
+        walk::walk_expression(self, expression);
+    }
+
+    fn visit_binding_pattern(&mut self, pattern: &BindingPattern<'a>) {
+        self.count += 1;
+        walk::walk_binding_pattern(self, pattern);
+    }
+}
+
+fn parse_program_with_decorators<'a>(
+    allocator: &'a Allocator,
+    source_text: &'a str,
+    decorators: bool,
+) -> ParserReturn<'a> {
+    let source_type = match detect_module_kind(source_text) {
+        ModuleKind::Script => SourceType::cjs(),
+        _ => SourceType::from_path("example.js").expect("Invalid file extension"),
+    };
+    let mut parser_return = Parser::new(allocator, source_text, source_type)
         .with_options(ParseOptions {
             parse_regular_expression: true,
             ..ParseOptions::default()
         })
         .parse();
 
-    let errors = parser_return
-        .errors
+    if !decorators {
+        let mut finder = DecoratorFinder::default();
+        finder.visit_program(&parser_return.program);
+        parser_return.errors.extend(finder.errors);
+    }
+    parser_return
+}
+
+fn errors_to_json(errors: Vec<OxcDiagnostic>) -> Vec<Value> {
+    errors
         .into_iter()
         .map(|e| {
             let severity = match e.severity {
@@ -96,11 +262,75 @@ pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
                 "url": url
             })
         })
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
+
+fn node_to_json<T: ESTree>(node: &T, options: &EstreeOptions) -> String {
+    if options.compact {
+        let mut serializer = CompactTSSerializer::new(true);
+        node.serialize(&mut serializer);
+        serializer.into_string()
+    } else {
+        let mut serializer = PrettyTSSerializer::new(true);
+        node.serialize(&mut serializer);
+        serializer.into_string()
+    }
+}
+
+/// Converts JavaScript AST to the ESTree format.
+///
+/// This function takes JavaScript source code, parses it into an Abstract Syntax Tree (AST),
+/// and converts it into the ESTree-compatible JSON format. It also captures any parsing errors
+/// and comments within the source.
+///
+/// # Arguments
+/// * `source_text` - The JavaScript source code as a string.
+///
+/// # Returns
+/// * `Ok(String)` - A pretty-printed JSON representation of the AST in ESTree format.
+/// * `Err(String)` - If parsing or JSON serialization fails.
+///
+/// # Errors
+/// * Returns `"Failed to serialize JSON"` if the ESTree AST cannot be converted to JSON.
+/// * If there are syntax errors in `source_text`, they will be included in the `"errors"` field.
+///
+/// # Output Structure
+/// The returned JSON contains:
+/// * `"program"` - The parsed AST in ESTree format.
+/// * `"comments"` - Extracted comments from the source code.
+/// * `"errors"` - A list of syntax errors with details.
+///
+/// # Example
+/// ```rust
+/// let js_code = "function test() { console.log('Hello, world!'); } // Comment";
+/// let result = convert_ast_to_estree(js_code);
+///
+/// assert!(result.is_ok());
+/// let json_output = result.unwrap();
+/// assert!(json_output.contains("\"type\": \"Program\""));
+/// assert!(json_output.contains("\"type\": \"FunctionDeclaration\""));
+/// assert!(json_output.contains("\"comments\""));
+/// ```
+pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
+    convert_ast_to_estree_with_options(source_text, &EstreeOptions::default())
+}
+
+/// Same as [`convert_ast_to_estree`], with [`EstreeOptions`].
+///
+/// With `compact: true` the program JSON is embedded as produced by the serializer instead of
+/// being parsed and pretty-printed again, so the conversion does not hold a second copy of
+/// the tree in memory.
+pub fn convert_ast_to_estree_with_options(
+    source_text: &str,
+    options: &EstreeOptions,
+) -> Result<String, String> {
+    let allocator = Allocator::default();
+    let parser_return = parse_program(&allocator, source_text)?;
+    let errors = errors_to_json(parser_return.errors);
 
     let mut program = parser_return.program;
     let span_converter = Utf8ToUtf16::new(source_text);
-    span_converter.convert_program(&mut program);
+    prepare_program(&mut program, source_text, options);
 
     let comments_json: Vec<_> = program
         .comments
@@ -108,8 +338,10 @@ pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
         .map(|comment| {
             let value = comment.content_span().source_text(source_text).to_string();
             let mut span = comment.span;
-            if let Some(mut converter) = span_converter.converter() {
-                converter.convert_span(&mut span);
+            if options.spans == SpanFormat::Utf16 {
+                if let Some(mut converter) = span_converter.converter() {
+                    converter.convert_span(&mut span);
+                }
             }
             json!({
                 "type": if comment.is_line() { "Line" } else { "Block" },
@@ -119,6 +351,21 @@ pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
             })
         })
         .collect();
+
+    if options.compact {
+        let to_json = |value: &Vec<Value>| {
+            serde_json::to_string(value).map_err(|e| format!("Failed to serialize JSON: {:?}", e))
+        };
+
+        let full_json = format!(
+            "{{\"program\":{},\"comments\":{},\"errors\":{}}}",
+            program.to_estree_ts_json(true),
+            to_json(&comments_json)?,
+            to_json(&errors)?
+        );
+        return finish_json(full_json, options);
+    }
+
     let estree_json = program.to_pretty_estree_ts_json(true);
 
     let full_json = json!({
@@ -127,8 +374,53 @@ pub fn convert_ast_to_estree(source_text: &str) -> Result<String, String> {
         "errors": errors
     });
 
-    serde_json::to_string_pretty(&full_json)
-        .map_err(|e| format!("Failed to serialize JSON: {:?}", e))
+    let full_json = serde_json::to_string_pretty(&full_json)
+        .map_err(|e| format!("Failed to serialize JSON: {:?}", e))?;
+    finish_json(full_json, options)
+}
+
+/// Converts JavaScript to ESTree, one JSON document per top-level statement.
+///
+/// Large bundles produce huge ESTree documents; returning the statements separately lets the
+/// caller decode and process them one at a time instead of holding the whole tree at once.
+///
+/// # Arguments
+/// * `source_text` - The JavaScript source code as a string.
+/// * `options` - The conversion options.
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - The ESTree JSON of every top-level statement, in order.
+/// * `Err(String)` - The syntax errors of the source, if any.
+///
+/// # Example
+/// ```rust
+/// let fragments = convert_ast_to_estree_statements("let a = 1; f(a);", &EstreeOptions::default());
+/// assert_eq!(fragments.unwrap().len(), 2);
+/// ```
+pub fn convert_ast_to_estree_statements(
+    source_text: &str,
+    options: &EstreeOptions,
+) -> Result<Vec<String>, String> {
+    let allocator = Allocator::default();
+    let parser_return = parse_program(&allocator, source_text)?;
+
+    if !parser_return.errors.is_empty() {
+        let messages: Vec<String> = parser_return
+            .errors
+            .iter()
+            .map(|error| error.message.to_string())
+            .collect();
+        return Err(messages.join("\n"));
+    }
+
+    let mut program = parser_return.program;
+    prepare_program(&mut program, source_text, options);
+
+    program
+        .body
+        .iter()
+        .map(|statement| finish_json(node_to_json(statement, options), options))
+        .collect()
 }
 
 #[cfg(test)]
@@ -154,4 +446,106 @@ mod tests {
         println!("{}", json_output);
         assert!(is_valid_json(&json_output));
     }
+
+    #[test]
+    fn test_parse_program_with_decorators() {
+        let js_code = r#"
+            @customElement("copy-button")
+            export class CopyButton extends LitElement {
+              @property({ type: String }) accessor text = "";
+            }
+            "#;
+
+        let allocator = Allocator::default();
+        let parser_return = parse_program_with_decorators(&allocator, js_code, true);
+        assert!(parser_return.errors.is_empty());
+
+        let allocator = Allocator::default();
+        let parser_return = parse_program_with_decorators(&allocator, js_code, false);
+        assert_eq!(parser_return.errors.len(), 2);
+        assert_eq!(
+            parser_return.errors[0].message,
+            "Decorators are not enabled"
+        );
+    }
+
+    #[test]
+    fn test_convert_ast_to_estree_compact() {
+        let js_code = "import { a } from \"a\"; // comment\nconst b = a;";
+
+        let pretty: Value = serde_json::from_str(&convert_ast_to_estree(js_code).unwrap()).unwrap();
+        let options = EstreeOptions {
+            compact: true,
+            ..Default::default()
+        };
+        let compact_json = convert_ast_to_estree_with_options(js_code, &options).unwrap();
+        let compact: Value = serde_json::from_str(&compact_json).unwrap();
+
+        assert!(!compact_json.contains('\n'));
+        assert_eq!(pretty, compact);
+    }
+
+    #[test]
+    fn test_convert_ast_to_estree_statements() {
+        let js_code = "import { a } from \"a\";\nconst b = a;\nconsole.log(b);";
+        let options = EstreeOptions {
+            compact: true,
+            ..Default::default()
+        };
+
+        let fragments = convert_ast_to_estree_statements(js_code, &options).unwrap();
+        let types: Vec<Value> = fragments
+            .iter()
+            .map(|fragment| serde_json::from_str::<Value>(fragment).unwrap()["type"].clone())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "ImportDeclaration",
+                "VariableDeclaration",
+                "ExpressionStatement"
+            ]
+        );
+
+        assert!(convert_ast_to_estree_statements("let = ;", &options).is_err());
+    }
+
+    #[test]
+    fn test_convert_ast_to_estree_span_formats_and_filters() {
+        let js_code = "const s = \"é\";\nimport { a } from \"a\";\nf(a);";
+
+        let parse = |options: &EstreeOptions| -> Value {
+            serde_json::from_str(&convert_ast_to_estree_with_options(js_code, options).unwrap())
+                .unwrap()
+        };
+
+        let utf16 = parse(&EstreeOptions::default());
+        assert_eq!(utf16["program"]["body"][1]["start"], 15);
+
+        let utf8 = parse(&EstreeOptions {
+            spans: SpanFormat::Utf8,
+            ..Default::default()
+        });
+        assert_eq!(utf8["program"]["body"][1]["start"], 16);
+
+        let filtered = parse(&EstreeOptions {
+            compact: true,
+            spans: SpanFormat::None,
+            node_types: vec!["ImportDeclaration".to_string()],
+        });
+        let body = filtered["program"]["body"].as_array().unwrap();
+        assert_eq!(body.len(), 1);
+        assert_eq!(body[0]["type"], "ImportDeclaration");
+        assert!(body[0].get("start").is_none());
+        assert!(body[0]["source"].get("end").is_none());
+
+        let options = EstreeOptions {
+            spans: SpanFormat::None,
+            node_types: vec!["ExpressionStatement".to_string()],
+            ..Default::default()
+        };
+        let fragments = convert_ast_to_estree_statements(js_code, &options).unwrap();
+        assert_eq!(fragments.len(), 1);
+        assert!(!fragments[0].contains("\"start\""));
+    }
 }
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The names declared at the top level of a module, so an installer can check for a
+//! collision before inserting a declaration, e.g. a second `const csrfToken`.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::parse;
+use crate::parsers::javascript::unused::pat_idents;
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+use swc_common::{sync::Lrc, SourceMap};
+use swc_ecma_ast::*;
+
+/// How a top-level name is declared.
+///
+/// On the Elixir side these are the atoms `:const`, `:let`, `:var`, `:function` and
+/// `:class`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum BindingKind {
+    Const,
+    Let,
+    Var,
+    Function,
+    Class,
+}
+
+/// A name declared at the top level of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopLevelBinding {
+    pub name: String,
+    pub kind: BindingKind,
+    /// Whether the declaration is exported, e.g. `export const name = ...`.
+    pub exported: bool,
+    /// The location of the name in the declaration.
+    pub location: SourceLocation,
+}
+
+fn decl_bindings(
+    decl: &Decl,
+    exported: bool,
+    cm: &Lrc<SourceMap>,
+    bindings: &mut Vec<TopLevelBinding>,
+) {
+    let mut push = |ident: &Ident, kind| {
+        bindings.push(TopLevelBinding {
+            name: ident.sym.to_string(),
+            kind,
+            exported,
+            location: SourceLocation::from_span(cm, ident.span),
+        })
+    };
+
+    match decl {
+        Decl::Fn(function) => push(&function.ident, BindingKind::Function),
+        Decl::Class(class) => push(&class.ident, BindingKind::Class),
+        Decl::Var(var) => {
+            let kind = match var.kind {
+                VarDeclKind::Const => BindingKind::Const,
+                VarDeclKind::Let => BindingKind::Let,
+                VarDeclKind::Var => BindingKind::Var,
+            };
+            let mut idents = vec![];
+            for declarator in &var.decls {
+                pat_idents(&declarator.name, &mut idents);
+            }
+            idents.into_iter().for_each(|ident| push(ident, kind));
+        }
+        _ => {}
+    }
+}
+
+/// Lists the names declared at the top level of a module, in source order.
+///
+/// Every name of a destructuring declaration is listed, e.g. `a` and `c` for
+/// `const { a, b: c } = x`, and so are the named functions and classes of
+/// `export default`. Imports are not declarations and are not listed.
+///
+/// # Returns
+/// * `Ok(Vec<TopLevelBinding>)` - The bindings.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = "let csrfToken = token(); export function connect() {}";
+/// let bindings = list_top_level_bindings(code).unwrap();
+/// assert_eq!(bindings[1].name, "connect");
+/// assert_eq!(bindings[1].kind, BindingKind::Function);
+/// ```
+pub fn list_top_level_bindings(file_content: &str) -> Result<Vec<TopLevelBinding>, String> {
+    let (module, _comments, cm) = parse(file_content)?;
+    let mut bindings = vec![];
+
+    for item in &module.body {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl_bindings(decl, false, &cm, &mut bindings),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                decl_bindings(&export.decl, true, &cm, &mut bindings)
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+                let (ident, kind) = match &export.decl {
+                    DefaultDecl::Fn(FnExpr {
+                        ident: Some(ident), ..
+                    }) => (ident, BindingKind::Function),
+                    DefaultDecl::Class(ClassExpr {
+                        ident: Some(ident), ..
+                    }) => (ident, BindingKind::Class),
+                    _ => continue,
+                };
+                bindings.push(TopLevelBinding {
+                    name: ident.sym.to_string(),
+                    kind,
+                    exported: true,
+                    location: SourceLocation::from_span(&cm, ident.span),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(bindings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_top_level_bindings() {
+        let code = r#"import { Socket } from "phoenix";
+let csrfToken = document.querySelector("meta").getAttribute("content");
+const { hooks, colors: [primary, ...others] } = config;
+export var liveSocket = new LiveSocket("/live", Socket);
+function connect() { const nested = 1; }
+export default class App {}
+"#;
+        let bindings = list_top_level_bindings(code).unwrap();
+        let names: Vec<(&str, BindingKind, bool)> = bindings
+            .iter()
+            .map(|binding| (binding.name.as_str(), binding.kind, binding.exported))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("csrfToken", BindingKind::Let, false),
+                ("hooks", BindingKind::Const, false),
+                ("primary", BindingKind::Const, false),
+                ("others", BindingKind::Const, false),
+                ("liveSocket", BindingKind::Var, true),
+                ("connect", BindingKind::Function, false),
+                ("App", BindingKind::Class, true),
+            ]
+        );
+
+        let location = &bindings[0].location;
+        assert_eq!((location.line, location.column), (2, 4));
+        assert_eq!(&code[location.start..location.end], "csrfToken");
+
+        assert!(list_top_level_bindings("let = ;").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_ex::SourceLocationResult;
+use crate::parsers::javascript::bindings::*;
+
+use rustler::{Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct TopLevelBindingResult {
+    pub name: String,
+    pub kind: BindingKind,
+    pub exported: bool,
+    pub location: SourceLocationResult,
+}
+
+#[rustler::nif]
+pub fn list_top_level_bindings_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::list_top_level_bindings_nif();
+
+        match list_top_level_bindings(&file_content) {
+            Ok(bindings) => {
+                let result: Vec<TopLevelBindingResult> = bindings
+                    .into_iter()
+                    .map(|binding| TopLevelBindingResult {
+                        name: binding.name,
+                        kind: binding.kind,
+                        exported: binding.exported,
+                        location: binding.location.into(),
+                    })
+                    .collect();
+                encode_response(env, atoms::ok(), fn_atom, result)
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
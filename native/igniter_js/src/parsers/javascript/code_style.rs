@@ -0,0 +1,380 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The quotes and semicolons of a source, so the code SWC generates from it can follow
+//! them instead of its own style.
+//!
+//! SWC keeps the strings of a source as written, but quotes the strings a transform adds
+//! with `"` and ends every statement with `;`. With [`CodegenOptions::preserve_code_style`]
+//! set, an edit of a file written with `'` and without semicolons adds its imports and
+//! hooks the same way, instead of a `;` on every line of the file.
+//!
+//! The options are those of the call running on the current thread, sent with its arguments,
+//! see [`crate::call_options`].
+
+use crate::parsers::javascript::helpers::{parse, SpanOffsets};
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use swc_common::{BytePos, SourceMap, Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+thread_local! {
+    static CODEGEN_OPTIONS: Cell<CodegenOptions> = const {
+        Cell::new(CodegenOptions {
+            preserve_code_style: false,
+        })
+    };
+}
+
+/// The options of the code generated from a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct CodegenOptions {
+    /// Whether the generated code follows the quotes and semicolons of the source, see
+    /// [`CodeStyle`].
+    pub preserve_code_style: bool,
+}
+
+/// Sets the codegen options of the call running on the current thread.
+pub fn set_codegen_options(options: CodegenOptions) {
+    CODEGEN_OPTIONS.with(|cell| cell.set(options));
+}
+
+/// The codegen options of the call running on the current thread.
+pub fn codegen_options() -> CodegenOptions {
+    CODEGEN_OPTIONS.with(Cell::get)
+}
+
+/// The dominant quotes and semicolons of a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeStyle {
+    /// Most strings are quoted with `'`.
+    pub single_quotes: bool,
+    /// Most statements end with `;`.
+    pub semicolons: bool,
+}
+
+impl Default for CodeStyle {
+    /// The style SWC emits.
+    fn default() -> Self {
+        Self {
+            single_quotes: false,
+            semicolons: true,
+        }
+    }
+}
+
+/// Counts the quotes of the strings and the semicolons of the statements of a source.
+struct StyleCounter<'a> {
+    source: &'a str,
+    offsets: SpanOffsets,
+    single_quotes: usize,
+    double_quotes: usize,
+    semicolons: usize,
+    no_semicolons: usize,
+}
+
+impl StyleCounter<'_> {
+    fn count_statement_end(&mut self, span: Span) {
+        let (_, end) = self.offsets.range(span);
+        match self.source.get(..end) {
+            Some(text) if text.ends_with(';') => self.semicolons += 1,
+            Some(_) => self.no_semicolons += 1,
+            None => {}
+        }
+    }
+}
+
+/// Whether a statement ends with a `;` when it is written with one.
+fn may_end_with_semicolon(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::Expr(_)
+            | Stmt::Decl(Decl::Var(_))
+            | Stmt::Return(_)
+            | Stmt::Throw(_)
+            | Stmt::Break(_)
+            | Stmt::Continue(_)
+            | Stmt::Debugger(_)
+    )
+}
+
+fn module_decl_may_end_with_semicolon(decl: &ModuleDecl) -> bool {
+    match decl {
+        ModuleDecl::Import(_)
+        | ModuleDecl::ExportNamed(_)
+        | ModuleDecl::ExportAll(_)
+        | ModuleDecl::ExportDefaultExpr(_) => true,
+        ModuleDecl::ExportDecl(export) => matches!(export.decl, Decl::Var(_)),
+        _ => false,
+    }
+}
+
+impl Visit for StyleCounter<'_> {
+    fn visit_str(&mut self, str: &Str) {
+        match str.raw.as_deref().and_then(|raw| raw.chars().next()) {
+            Some('\'') => self.single_quotes += 1,
+            Some('"') => self.double_quotes += 1,
+            _ => {}
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if may_end_with_semicolon(stmt) {
+            self.count_statement_end(stmt.span());
+        }
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+        if module_decl_may_end_with_semicolon(decl) {
+            self.count_statement_end(decl.span());
+        }
+        decl.visit_children_with(self);
+    }
+}
+
+/// Quotes the strings a transform added with `'`, unless they need escapes.
+struct SingleQuoter<'a> {
+    /// The source without its byte order mark, as kept in the source map.
+    source: &'a str,
+    start_pos: BytePos,
+}
+
+impl SingleQuoter<'_> {
+    /// Whether a string is written as is in the source. The strings parsed from a snippet,
+    /// e.g. an inserted import, have spans of their own source map.
+    fn is_from_source(&self, str: &Str) -> bool {
+        let (Some(raw), false) = (&str.raw, str.span.is_dummy()) else {
+            return false;
+        };
+        let start = str.span.lo.0.wrapping_sub(self.start_pos.0) as usize;
+        let end = str.span.hi.0.wrapping_sub(self.start_pos.0) as usize;
+        self.source.get(start..end) == Some(raw.as_str())
+    }
+}
+
+impl VisitMut for SingleQuoter<'_> {
+    fn visit_mut_str(&mut self, str: &mut Str) {
+        let plain = !str.value.chars().any(|c| {
+            c == '\'' || c == '\\' || c.is_control() || c == '\u{2028}' || c == '\u{2029}'
+        });
+        if plain && !self.is_from_source(str) {
+            str.raw = Some(format!("'{}'", str.value).into());
+        }
+    }
+
+    // The strings of JSX attributes can not be escaped, they keep their quotes
+    fn visit_mut_jsx_attr_value(&mut self, _value: &mut JSXAttrValue) {}
+}
+
+/// Whether a line starting with `text` would continue the statement before it when that
+/// statement does not end with `;`.
+fn continues_statement(text: &str) -> bool {
+    text.starts_with(['(', '[', '`', '+', '-', '/', '*', ',', '.', '<', '=', '?'])
+}
+
+/// Collects the `;` ending the statements of a generated source.
+struct SemicolonFinder<'a> {
+    code: &'a str,
+    offsets: SpanOffsets,
+    found: BTreeSet<usize>,
+}
+
+impl SemicolonFinder<'_> {
+    fn add(&mut self, span: Span) {
+        let (_, end) = self.offsets.range(span);
+        if end > 0 && self.code[..end].ends_with(';') {
+            self.found.insert(end - 1);
+        }
+    }
+}
+
+impl Visit for SemicolonFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if may_end_with_semicolon(stmt) {
+            self.add(stmt.span());
+        }
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+        if module_decl_may_end_with_semicolon(decl) {
+            self.add(decl.span());
+        }
+        decl.visit_children_with(self);
+    }
+}
+
+impl CodeStyle {
+    /// The style to emit a module parsed into `cm` with: the style of its source when
+    /// [`CodegenOptions::preserve_code_style`] is set, otherwise the style SWC emits.
+    pub fn of(cm: &SourceMap) -> Self {
+        match cm.files().first() {
+            Some(file) if codegen_options().preserve_code_style => Self::detect(&file.src),
+            _ => Self::default(),
+        }
+    }
+
+    /// Detects the style of a source, the style SWC emits when it can not be parsed.
+    ///
+    /// # Example
+    /// ```rust
+    /// let style = CodeStyle::detect("import { Socket } from 'phoenix'\nlet a = 1\n");
+    /// assert!(style.single_quotes && !style.semicolons);
+    /// ```
+    pub fn detect(source: &str) -> Self {
+        let Ok((module, _comments, cm)) = parse(source) else {
+            return Self::default();
+        };
+
+        let mut counter = StyleCounter {
+            source,
+            offsets: SpanOffsets::new(&cm),
+            single_quotes: 0,
+            double_quotes: 0,
+            semicolons: 0,
+            no_semicolons: 0,
+        };
+        module.visit_with(&mut counter);
+
+        Self {
+            single_quotes: counter.single_quotes > counter.double_quotes,
+            semicolons: counter.semicolons >= counter.no_semicolons,
+        }
+    }
+
+    /// Quotes the strings added to a module parsed into `cm` like the source, before it is
+    /// emitted.
+    pub fn prepare(&self, module: &mut Module, cm: &SourceMap) {
+        let Some(file) = cm.files().first().cloned() else {
+            return;
+        };
+        if self.single_quotes {
+            module.visit_mut_with(&mut SingleQuoter {
+                source: &file.src,
+                start_pos: file.start_pos,
+            });
+        }
+    }
+
+    /// Removes the `;` ending the statements of emitted code when the source does without.
+    /// A `;` is kept where the next line would continue the statement, e.g. a line starting
+    /// with `(` or `[`, or when something other than a comment follows it on its line.
+    ///
+    /// # Example
+    /// ```rust
+    /// let style = CodeStyle { single_quotes: false, semicolons: false };
+    /// assert_eq!(style.apply("let a = 1;\n[a].map(f);\n"), "let a = 1;\n[a].map(f)\n");
+    /// ```
+    pub fn apply(&self, code: &str) -> String {
+        if self.semicolons {
+            return code.to_string();
+        }
+        let Ok((module, _comments, cm)) = parse(code) else {
+            return code.to_string();
+        };
+
+        let mut finder = SemicolonFinder {
+            code,
+            offsets: SpanOffsets::new(&cm),
+            found: BTreeSet::new(),
+        };
+        module.visit_with(&mut finder);
+
+        let mut result = code.to_string();
+        for semicolon in finder.found.into_iter().rev() {
+            let rest = &code[semicolon + 1..];
+            let line_end = rest.find('\n').unwrap_or(rest.len());
+            let line = rest[..line_end].trim();
+            let removable = if line.is_empty() || line.starts_with("//") {
+                !continues_statement(rest[line_end..].trim_start())
+            } else {
+                line.starts_with('}')
+            };
+            if removable {
+                result.remove(semicolon);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::javascript::helpers::code_gen_from_ast_module;
+
+    #[test]
+    fn test_code_style() {
+        let code = r#"import { Socket } from 'phoenix'
+import topbar from "../vendor/topbar"
+import Hooks from './hooks'
+
+let liveSocket = new LiveSocket('/live', Socket, { hooks: Hooks })
+;[1, 2].forEach((n) => console.log(n))
+"#;
+        let (mut module, comments, cm) = parse(code).unwrap();
+        let style = CodeStyle::detect(code);
+        assert_eq!(
+            style,
+            CodeStyle {
+                single_quotes: true,
+                semicolons: false
+            }
+        );
+
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = &mut module.body[0] else {
+            unreachable!()
+        };
+        *import.src = Str::from("phoenix_live_view");
+        let (snippet, _comments, _cm) = parse("import { Presence } from \"phoenix\";").unwrap();
+        module.body.insert(1, snippet.body[0].clone());
+        style.prepare(&mut module, &cm);
+        // The style is applied to the code emitted without the option
+        let emitted = code_gen_from_ast_module(&mut module, comments, cm);
+        assert_eq!(
+            style.apply(&emitted),
+            r#"import { Socket } from 'phoenix_live_view'
+import { Presence } from 'phoenix'
+import topbar from "../vendor/topbar"
+import Hooks from './hooks'
+let liveSocket = new LiveSocket('/live', Socket, {
+    hooks: Hooks
+});
+[
+    1,
+    2
+].forEach((n)=>console.log(n))
+"#
+        );
+
+        assert_eq!(CodeStyle::detect("let a = \"b\";"), CodeStyle::default());
+        assert_eq!(CodeStyle::detect("let a = "), CodeStyle::default());
+    }
+
+    #[test]
+    fn test_code_style_of_call() {
+        let (_module, _comments, cm) = parse("import { Socket } from 'phoenix'\n").unwrap();
+        assert_eq!(CodeStyle::of(&cm), CodeStyle::default());
+
+        set_codegen_options(CodegenOptions {
+            preserve_code_style: true,
+        });
+        let style = CodeStyle::of(&cm);
+        set_codegen_options(CodegenOptions::default());
+        assert_eq!(
+            style,
+            CodeStyle {
+                single_quotes: true,
+                semicolons: false
+            }
+        );
+        assert_eq!(CodeStyle::of(&cm), CodeStyle::default());
+    }
+}
@@ -0,0 +1,412 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Codemods
+//!
+//! Rewrites every expression of a module matching a pattern written as JavaScript, so an
+//! upgrade can be expressed without a new transform, e.g.
+//!
+//! ```rust
+//! let result = apply_codemod(code, "topbar.config($OPTS)", "topbar.setup($OPTS)").unwrap();
+//! ```
+//!
+//! A pattern is an expression with placeholders:
+//!
+//! * `$NAME` matches any expression, e.g. `$OPTS` in `topbar.config($OPTS)`.
+//! * `$$$NAME` matches any number of arguments or array elements, e.g. the trailing
+//!   arguments of `new LiveSocket("/live", Socket, $$$REST)`.
+//!
+//! Placeholder names are upper case, so identifiers like `$el` are matched as they are. A
+//! placeholder used twice matches the same expression twice. The rest of the pattern is
+//! compared ignoring spans, parentheses and formatting. The replacement is an expression
+//! too, with the placeholders replaced by what they matched.
+
+use crate::parsers::javascript::helpers::*;
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use std::collections::HashMap;
+use swc_common::EqIgnoreSpan;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+/// The result of [`apply_codemod`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct CodemodResult {
+    /// The code after the replacements.
+    pub code: String,
+    /// Whether the code differs from the source, i.e. whether there is anything to write.
+    pub changed: bool,
+    /// How many expressions were replaced.
+    pub replacements: usize,
+}
+
+/// What a placeholder matched.
+#[derive(Debug, Clone)]
+enum Capture {
+    Expr(Box<Expr>),
+    List(Vec<ExprOrSpread>),
+}
+
+type Captures = HashMap<String, Capture>;
+
+/// The name of a placeholder, `NAME` for `$NAME`, if the identifier is one.
+fn placeholder(sym: &str) -> Option<&str> {
+    let name = sym.strip_prefix('$')?;
+    let valid = !name.is_empty()
+        && name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some(name)
+}
+
+fn expr_placeholder(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Ident(ident) if !ident.sym.starts_with("$$$") => placeholder(&ident.sym),
+        _ => None,
+    }
+}
+
+/// The name of a list placeholder, `NAME` for `$$$NAME`, if the argument is one.
+fn list_placeholder(arg: &ExprOrSpread) -> Option<&str> {
+    match (&arg.spread, &*arg.expr) {
+        (None, Expr::Ident(ident)) => placeholder(ident.sym.strip_prefix("$$")?),
+        _ => None,
+    }
+}
+
+fn unparen(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unparen(&paren.expr),
+        _ => expr,
+    }
+}
+
+/// Binds a placeholder, or checks it matches what it is already bound to.
+fn bind(captures: &mut Captures, name: &str, capture: Capture) -> bool {
+    match (captures.get(name), &capture) {
+        (None, _) => {
+            captures.insert(name.to_string(), capture);
+            true
+        }
+        (Some(Capture::Expr(bound)), Capture::Expr(expr)) => bound.eq_ignore_span(expr),
+        (Some(Capture::List(bound)), Capture::List(list)) => bound.eq_ignore_span(list),
+        _ => false,
+    }
+}
+
+fn match_expr(pattern: &Expr, target: &Expr, captures: &mut Captures) -> bool {
+    let (pattern, target) = (unparen(pattern), unparen(target));
+
+    if let Some(name) = expr_placeholder(pattern) {
+        return bind(captures, name, Capture::Expr(Box::new(target.clone())));
+    }
+
+    match (pattern, target) {
+        (Expr::Call(pattern), Expr::Call(target)) => {
+            let callee = match (&pattern.callee, &target.callee) {
+                (Callee::Expr(pattern), Callee::Expr(target)) => {
+                    match_expr(pattern, target, captures)
+                }
+                (pattern, target) => pattern.eq_ignore_span(target),
+            };
+            callee && match_list(&pattern.args, &target.args, captures)
+        }
+        (Expr::New(pattern), Expr::New(target)) => {
+            let no_args = vec![];
+            match_expr(&pattern.callee, &target.callee, captures)
+                && match_list(
+                    pattern.args.as_ref().unwrap_or(&no_args),
+                    target.args.as_ref().unwrap_or(&no_args),
+                    captures,
+                )
+        }
+        (Expr::Member(pattern), Expr::Member(target)) => {
+            let prop = match (&pattern.prop, &target.prop) {
+                (MemberProp::Computed(pattern), MemberProp::Computed(target)) => {
+                    match_expr(&pattern.expr, &target.expr, captures)
+                }
+                (pattern, target) => pattern.eq_ignore_span(target),
+            };
+            prop && match_expr(&pattern.obj, &target.obj, captures)
+        }
+        (Expr::Array(pattern), Expr::Array(target)) => {
+            let holes = |elems: &[Option<ExprOrSpread>]| elems.iter().any(Option::is_none);
+            if holes(&pattern.elems) || holes(&target.elems) {
+                return pattern.eq_ignore_span(target);
+            }
+            let elems = |elems: &[Option<ExprOrSpread>]| elems.iter().flatten().cloned().collect();
+            let (pattern_elems, target_elems): (Vec<_>, Vec<_>) =
+                (elems(&pattern.elems), elems(&target.elems));
+            match_list(&pattern_elems, &target_elems, captures)
+        }
+        (Expr::Object(pattern), Expr::Object(target)) => {
+            pattern.props.len() == target.props.len()
+                && pattern
+                    .props
+                    .iter()
+                    .zip(&target.props)
+                    .all(|(pattern, target)| match_prop(pattern, target, captures))
+        }
+        (Expr::Unary(pattern), Expr::Unary(target)) => {
+            pattern.op == target.op && match_expr(&pattern.arg, &target.arg, captures)
+        }
+        (Expr::Bin(pattern), Expr::Bin(target)) => {
+            pattern.op == target.op
+                && match_expr(&pattern.left, &target.left, captures)
+                && match_expr(&pattern.right, &target.right, captures)
+        }
+        (Expr::Cond(pattern), Expr::Cond(target)) => {
+            match_expr(&pattern.test, &target.test, captures)
+                && match_expr(&pattern.cons, &target.cons, captures)
+                && match_expr(&pattern.alt, &target.alt, captures)
+        }
+        (Expr::Await(pattern), Expr::Await(target)) => {
+            match_expr(&pattern.arg, &target.arg, captures)
+        }
+        (pattern, target) => pattern.eq_ignore_span(target),
+    }
+}
+
+fn match_prop(pattern: &PropOrSpread, target: &PropOrSpread, captures: &mut Captures) -> bool {
+    match (pattern, target) {
+        (PropOrSpread::Spread(pattern), PropOrSpread::Spread(target)) => {
+            match_expr(&pattern.expr, &target.expr, captures)
+        }
+        (PropOrSpread::Prop(pattern), PropOrSpread::Prop(target)) => {
+            match (&**pattern, &**target) {
+                (Prop::KeyValue(pattern), Prop::KeyValue(target)) => {
+                    pattern.key.eq_ignore_span(&target.key)
+                        && match_expr(&pattern.value, &target.value, captures)
+                }
+                (pattern, target) => pattern.eq_ignore_span(target),
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Matches arguments or array elements, where a `$$$NAME` takes the elements the
+/// elements after it leave.
+fn match_list(pattern: &[ExprOrSpread], target: &[ExprOrSpread], captures: &mut Captures) -> bool {
+    let Some((first, rest)) = pattern.split_first() else {
+        return target.is_empty();
+    };
+
+    if let Some(name) = list_placeholder(first) {
+        let Some(taken) = target.len().checked_sub(rest.len()) else {
+            return false;
+        };
+        return bind(captures, name, Capture::List(target[..taken].to_vec()))
+            && match_list(rest, &target[taken..], captures);
+    }
+
+    match target.split_first() {
+        Some((target_first, target_rest)) => {
+            first.spread.is_some() == target_first.spread.is_some()
+                && match_expr(&first.expr, &target_first.expr, captures)
+                && match_list(rest, target_rest, captures)
+        }
+        None => false,
+    }
+}
+
+/// Replaces the placeholders of a replacement with what they matched.
+struct Substitute<'a> {
+    captures: &'a Captures,
+    error: Option<String>,
+}
+
+impl Substitute<'_> {
+    fn expand(&mut self, list: &mut Vec<ExprOrSpread>) {
+        let mut expanded = Vec::with_capacity(list.len());
+        for mut arg in list.drain(..) {
+            match list_placeholder(&arg).map(|name| (name, self.captures.get(name))) {
+                Some((_, Some(Capture::List(captured)))) => expanded.extend(captured.clone()),
+                Some((name, _)) => {
+                    self.error = Some(format!("`$$${}` is not in the pattern", name));
+                }
+                None => {
+                    arg.visit_mut_with(self);
+                    expanded.push(arg);
+                }
+            }
+        }
+        *list = expanded;
+    }
+}
+
+impl VisitMut for Substitute<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Some(name) = expr_placeholder(expr) {
+            match self.captures.get(name) {
+                Some(Capture::Expr(captured)) => *expr = *captured.clone(),
+                _ => self.error = Some(format!("`${}` is not in the pattern", name)),
+            }
+            return;
+        }
+
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr_or_spreads(&mut self, args: &mut Vec<ExprOrSpread>) {
+        self.expand(args);
+    }
+
+    fn visit_mut_array_lit(&mut self, array: &mut ArrayLit) {
+        if array.elems.iter().any(Option::is_none) {
+            array.visit_mut_children_with(self);
+            return;
+        }
+
+        let mut elems = array.elems.drain(..).flatten().collect();
+        self.expand(&mut elems);
+        array.elems = elems.into_iter().map(Some).collect();
+    }
+}
+
+struct CodemodVisitor<'a> {
+    pattern: &'a Expr,
+    replacement: &'a Expr,
+    replacements: usize,
+    error: Option<String>,
+}
+
+impl VisitMut for CodemodVisitor<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        let mut captures = Captures::new();
+        if !match_expr(self.pattern, expr, &mut captures) {
+            expr.visit_mut_children_with(self);
+            return;
+        }
+
+        let mut replacement = self.replacement.clone();
+        let mut substitute = Substitute {
+            captures: &captures,
+            error: None,
+        };
+        replacement.visit_mut_with(&mut substitute);
+        if let Some(error) = substitute.error {
+            self.error.get_or_insert(error);
+            return;
+        }
+
+        // The replacement is not visited again, so a pattern matching its replacement
+        // is applied once
+        *expr = replacement;
+        self.replacements += 1;
+    }
+}
+
+/// Replaces every expression matching a pattern.
+///
+/// Expressions are matched from the outside in, and the matched ones are not looked into, so
+/// `a($X)` replaces `a(a(1))` once, with `$X` being `a(1)`.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `pattern` - The expression to find, with placeholders, e.g. `topbar.config($OPTS)`.
+/// * `replacement` - The expression to put instead, e.g. `topbar.setup($OPTS)`.
+///
+/// # Returns
+/// * `Ok(CodemodResult)` - The updated code and how many expressions were replaced, the
+///   same code when none was.
+/// * `Err(String)` - If a source can not be parsed, the pattern is a lone placeholder or the
+///   replacement uses a placeholder the pattern does not have.
+///
+/// # Example
+/// ```rust
+/// let code = "topbar.config({ barColors: { 0: \"#29d\" } });";
+/// let result = apply_codemod(code, "topbar.config($OPTS)", "topbar.setup($OPTS)").unwrap();
+/// assert!(result.code.starts_with("topbar.setup({"));
+/// assert_eq!(result.replacements, 1);
+/// ```
+pub fn apply_codemod(
+    file_content: &str,
+    pattern: &str,
+    replacement: &str,
+) -> Result<CodemodResult, String> {
+    let pattern = parse_expr(pattern)?;
+    let replacement = parse_expr(replacement)?;
+    if expr_placeholder(unparen(&pattern)).is_some() {
+        return Err("The pattern matches every expression".into());
+    }
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut visitor = CodemodVisitor {
+        pattern: &pattern,
+        replacement: &replacement,
+        replacements: 0,
+        error: None,
+    };
+    module.visit_mut_with(&mut visitor);
+
+    if let Some(error) = visitor.error {
+        return Err(error);
+    }
+
+    let code = code_gen_edit(file_content, &original, &mut module, comments, cm);
+    Ok(CodemodResult {
+        changed: code != file_content,
+        code,
+        replacements: visitor.replacements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_codemod() {
+        let code = r##"topbar.config({ barColors: { 0: "#29d" } });
+window.addEventListener("phx:page-loading-start", _info => topbar.show(300));
+let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks, longPollFallbackMs: 2500 });
+"##;
+
+        let result = apply_codemod(code, "topbar.config($OPTS)", "topbar.setup($OPTS)").unwrap();
+        assert_eq!(result.replacements, 1);
+        assert!(result
+            .code
+            .starts_with("topbar.setup({\n    barColors: {\n        0: \"#29d\"\n    }\n});"));
+
+        let result =
+            apply_codemod(code, "topbar.show($DELAY)", "topbar.show($DELAY, true)").unwrap();
+        assert!(result.code.contains("(_info)=>topbar.show(300, true)"));
+
+        let result = apply_codemod(
+            code,
+            "new LiveSocket($PATH, Socket, $$$REST)",
+            "new LiveSocket($PATH, Socket, $$$REST, { debug: true })",
+        )
+        .unwrap();
+        assert!(result
+            .code
+            .contains("longPollFallbackMs: 2500\n}, {\n    debug: true\n});"));
+
+        let result = apply_codemod(code, "topbar.hide()", "topbar.close()").unwrap();
+        assert_eq!(result.replacements, 0);
+    }
+
+    #[test]
+    fn test_apply_codemod_placeholders() {
+        let code = "a(a(1)); b(x, x); b(x, y); $el.show();";
+
+        let result = apply_codemod(code, "a($X)", "c($X)").unwrap();
+        assert!(result.code.starts_with("c(a(1));"));
+
+        let result = apply_codemod(code, "b($X, $X)", "d($X)").unwrap();
+        assert!(result.code.contains("d(x);\nb(x, y);"));
+
+        let result = apply_codemod(code, "$el.show()", "$el.hide()").unwrap();
+        assert!(result.code.contains("$el.hide();"));
+
+        assert!(apply_codemod(code, "a($X)", "c($Y)").is_err());
+        assert!(apply_codemod(code, "$X", "c($X)").is_err());
+        assert!(apply_codemod(code, "a(", "c()").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::codemod::*;
+
+use rustler::{Encoder, Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn apply_codemod_nif(
+    env: Env,
+    file_content: String,
+    pattern: String,
+    replacement: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::apply_codemod_nif();
+
+        match apply_codemod(&file_content, &pattern, &replacement) {
+            Ok(result) => encode_response(env, atoms::ok(), fn_atom, result),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg.encode(env)),
+        }
+    })
+}
@@ -0,0 +1,386 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Reports the ECMAScript features a file uses and the ES version it requires, so an asset
+//! pipeline can warn when the code is newer than the esbuild target of the project.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::{es_version, es_version_name, parse};
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use swc_common::{sync::Lrc, SourceMap, Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// An ECMAScript feature added after ES5.
+///
+/// On the Elixir side these are atoms, e.g. `:optional_chaining`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum EsFeature {
+    ArrowFunctions,
+    Classes,
+    BlockScoping,
+    TemplateLiterals,
+    Destructuring,
+    ExponentOperator,
+    AsyncFunctions,
+    ObjectRestSpread,
+    AsyncIteration,
+    OptionalCatchBinding,
+    OptionalChaining,
+    NullishCoalescing,
+    DynamicImport,
+    BigInt,
+    LogicalAssignment,
+    ClassFields,
+    PrivateMethods,
+    ClassStaticBlock,
+    TopLevelAwait,
+}
+
+impl EsFeature {
+    /// The ES version the feature was added in.
+    pub fn es_version(&self) -> EsVersion {
+        match self {
+            EsFeature::ArrowFunctions
+            | EsFeature::Classes
+            | EsFeature::BlockScoping
+            | EsFeature::TemplateLiterals
+            | EsFeature::Destructuring => EsVersion::Es2015,
+            EsFeature::ExponentOperator => EsVersion::Es2016,
+            EsFeature::AsyncFunctions => EsVersion::Es2017,
+            EsFeature::ObjectRestSpread | EsFeature::AsyncIteration => EsVersion::Es2018,
+            EsFeature::OptionalCatchBinding => EsVersion::Es2019,
+            EsFeature::OptionalChaining
+            | EsFeature::NullishCoalescing
+            | EsFeature::DynamicImport
+            | EsFeature::BigInt => EsVersion::Es2020,
+            EsFeature::LogicalAssignment => EsVersion::Es2021,
+            EsFeature::ClassFields
+            | EsFeature::PrivateMethods
+            | EsFeature::ClassStaticBlock
+            | EsFeature::TopLevelAwait => EsVersion::Es2022,
+        }
+    }
+}
+
+/// The first use of a feature in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct FeatureUsage {
+    pub feature: EsFeature,
+    /// The ES version the feature requires, e.g. `es2020`.
+    pub target: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct SyntaxCompatibility {
+    /// The features used, in the order of their first use.
+    pub features: Vec<FeatureUsage>,
+    /// The lowest ES version supporting every feature, `es5` when none is used.
+    pub min_target: String,
+    /// Whether the file needs a newer ES version than the target checked against.
+    pub exceeds_target: bool,
+}
+
+struct FeatureFinder {
+    cm: Lrc<SourceMap>,
+    found: Vec<(EsFeature, Span)>,
+    /// The number of functions around the visited node, to tell a top-level `await`.
+    function_depth: usize,
+}
+
+impl FeatureFinder {
+    fn found(&mut self, feature: EsFeature, span: Span) {
+        if !self.found.iter().any(|(found, _)| *found == feature) {
+            self.found.push((feature, span));
+        }
+    }
+
+    fn in_function<N: VisitWith<Self>>(&mut self, node: &N) {
+        self.function_depth += 1;
+        node.visit_children_with(self);
+        self.function_depth -= 1;
+    }
+}
+
+impl Visit for FeatureFinder {
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.found(EsFeature::ArrowFunctions, arrow.span);
+        if arrow.is_async {
+            self.found(EsFeature::AsyncFunctions, arrow.span);
+        }
+        self.in_function(arrow);
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        match (function.is_async, function.is_generator) {
+            (true, true) => self.found(EsFeature::AsyncIteration, function.span),
+            (true, false) => self.found(EsFeature::AsyncFunctions, function.span),
+            _ => {}
+        }
+        self.in_function(function);
+    }
+
+    fn visit_getter_prop(&mut self, getter: &GetterProp) {
+        self.in_function(getter);
+    }
+
+    fn visit_setter_prop(&mut self, setter: &SetterProp) {
+        self.in_function(setter);
+    }
+
+    fn visit_class(&mut self, class: &Class) {
+        self.found(EsFeature::Classes, class.span);
+        class.visit_children_with(self);
+    }
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        self.found(EsFeature::ClassFields, prop.span);
+        self.in_function(prop);
+    }
+
+    fn visit_private_prop(&mut self, prop: &PrivateProp) {
+        self.found(EsFeature::ClassFields, prop.span);
+        self.in_function(prop);
+    }
+
+    fn visit_private_method(&mut self, method: &PrivateMethod) {
+        self.found(EsFeature::PrivateMethods, method.span);
+        method.visit_children_with(self);
+    }
+
+    fn visit_static_block(&mut self, block: &StaticBlock) {
+        self.found(EsFeature::ClassStaticBlock, block.span);
+        self.in_function(block);
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl) {
+        if var_decl.kind != VarDeclKind::Var {
+            self.found(EsFeature::BlockScoping, var_decl.span);
+        }
+        var_decl.visit_children_with(self);
+    }
+
+    fn visit_tpl(&mut self, tpl: &Tpl) {
+        self.found(EsFeature::TemplateLiterals, tpl.span);
+        tpl.visit_children_with(self);
+    }
+
+    fn visit_array_pat(&mut self, pat: &ArrayPat) {
+        self.found(EsFeature::Destructuring, pat.span);
+        pat.visit_children_with(self);
+    }
+
+    fn visit_object_pat(&mut self, pat: &ObjectPat) {
+        self.found(EsFeature::Destructuring, pat.span);
+        if let Some(rest) = pat.props.iter().find_map(|prop| match prop {
+            ObjectPatProp::Rest(rest) => Some(rest),
+            _ => None,
+        }) {
+            self.found(EsFeature::ObjectRestSpread, rest.span);
+        }
+        pat.visit_children_with(self);
+    }
+
+    fn visit_object_lit(&mut self, object: &ObjectLit) {
+        if let Some(spread) = object.props.iter().find_map(|prop| match prop {
+            PropOrSpread::Spread(spread) => Some(spread),
+            _ => None,
+        }) {
+            self.found(EsFeature::ObjectRestSpread, spread.span());
+        }
+        object.visit_children_with(self);
+    }
+
+    fn visit_bin_expr(&mut self, bin: &BinExpr) {
+        match bin.op {
+            BinaryOp::Exp => self.found(EsFeature::ExponentOperator, bin.span),
+            BinaryOp::NullishCoalescing => self.found(EsFeature::NullishCoalescing, bin.span),
+            _ => {}
+        }
+        bin.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        match assign.op {
+            AssignOp::ExpAssign => self.found(EsFeature::ExponentOperator, assign.span),
+            AssignOp::AndAssign | AssignOp::OrAssign | AssignOp::NullishAssign => {
+                self.found(EsFeature::LogicalAssignment, assign.span)
+            }
+            _ => {}
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_catch_clause(&mut self, catch: &CatchClause) {
+        if catch.param.is_none() {
+            self.found(EsFeature::OptionalCatchBinding, catch.span);
+        }
+        catch.visit_children_with(self);
+    }
+
+    fn visit_opt_chain_expr(&mut self, chain: &OptChainExpr) {
+        self.found(EsFeature::OptionalChaining, chain.span);
+        chain.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if matches!(call.callee, Callee::Import(_)) {
+            self.found(EsFeature::DynamicImport, call.span);
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_big_int(&mut self, big_int: &BigInt) {
+        self.found(EsFeature::BigInt, big_int.span);
+    }
+
+    fn visit_await_expr(&mut self, await_expr: &AwaitExpr) {
+        if self.function_depth == 0 {
+            self.found(EsFeature::TopLevelAwait, await_expr.span);
+        }
+        await_expr.visit_children_with(self);
+    }
+
+    fn visit_for_of_stmt(&mut self, for_of: &ForOfStmt) {
+        if for_of.is_await {
+            self.found(EsFeature::AsyncIteration, for_of.span);
+            if self.function_depth == 0 {
+                self.found(EsFeature::TopLevelAwait, for_of.span);
+            }
+        }
+        for_of.visit_children_with(self);
+    }
+}
+
+/// Reports the ECMAScript features a file uses, with the location of their first use, and
+/// the lowest ES version supporting all of them.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code.
+/// * `target` - The ES version to check against, e.g. the esbuild `--target` of the
+///   project (`es2017`), or `None` to only report the features.
+///
+/// # Returns
+/// * `Ok(SyntaxCompatibility)` - The features, the minimum target, and whether it is newer
+///   than `target`.
+/// * `Err(String)` - If the file can not be parsed or the target is unknown.
+///
+/// # Example
+/// ```rust
+/// let result = check_syntax_compatibility("const port = config?.port;", Some("es2017"));
+/// let result = result.unwrap();
+/// assert_eq!(result.min_target, "es2020");
+/// assert!(result.exceeds_target);
+/// ```
+pub fn check_syntax_compatibility(
+    file_content: &str,
+    target: Option<&str>,
+) -> Result<SyntaxCompatibility, String> {
+    let target = target.map(es_version).transpose()?;
+    let (module, _comments, cm) = parse(file_content)?;
+
+    let mut finder = FeatureFinder {
+        cm,
+        found: vec![],
+        function_depth: 0,
+    };
+    module.visit_with(&mut finder);
+
+    let min_target = finder
+        .found
+        .iter()
+        .map(|(feature, _)| feature.es_version())
+        .max()
+        .unwrap_or(EsVersion::Es5);
+    let features = finder
+        .found
+        .iter()
+        .map(|(feature, span)| {
+            let location = SourceLocation::from_span(&finder.cm, *span);
+            FeatureUsage {
+                feature: *feature,
+                target: es_version_name(feature.es_version()).to_string(),
+                line: location.line,
+                column: location.column,
+            }
+        })
+        .collect();
+
+    Ok(SyntaxCompatibility {
+        features,
+        min_target: es_version_name(min_target).to_string(),
+        exceeds_target: target.is_some_and(|target| min_target > target),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(result: &SyntaxCompatibility) -> Vec<EsFeature> {
+        result.features.iter().map(|usage| usage.feature).collect()
+    }
+
+    #[test]
+    fn test_check_syntax_compatibility() {
+        let code = r#"import { Socket } from "phoenix";
+const { LiveSocket } = await import("phoenix_live_view");
+class Hook {
+  mounted = false;
+  async load() {
+    return this.el?.dataset ?? {};
+  }
+}
+"#;
+
+        let result = check_syntax_compatibility(code, Some("es2017")).unwrap();
+        assert_eq!(
+            features(&result),
+            [
+                EsFeature::BlockScoping,
+                EsFeature::Destructuring,
+                EsFeature::TopLevelAwait,
+                EsFeature::DynamicImport,
+                EsFeature::Classes,
+                EsFeature::ClassFields,
+                EsFeature::AsyncFunctions,
+                EsFeature::NullishCoalescing,
+                EsFeature::OptionalChaining,
+            ]
+        );
+        assert_eq!(result.min_target, "es2022");
+        assert!(result.exceeds_target);
+        let await_usage = &result.features[2];
+        assert_eq!((await_usage.line, await_usage.column), (2, 23));
+        assert_eq!(await_usage.target, "es2022");
+
+        let result = check_syntax_compatibility("var a = 1;", Some("es5")).unwrap();
+        assert!(result.features.is_empty());
+        assert_eq!(result.min_target, "es5");
+        assert!(!result.exceeds_target);
+
+        let code = "async function run() { await load(); }\nlet a = 2 ** 8;";
+        let result = check_syntax_compatibility(code, None).unwrap();
+        assert_eq!(
+            features(&result),
+            [
+                EsFeature::AsyncFunctions,
+                EsFeature::BlockScoping,
+                EsFeature::ExponentOperator
+            ]
+        );
+        assert_eq!(result.min_target, "es2017");
+        assert!(!result.exceeds_target);
+
+        assert!(check_syntax_compatibility("let a = ;", None).is_err());
+        assert!(check_syntax_compatibility("let a = 1;", Some("es1999")).is_err());
+    }
+}
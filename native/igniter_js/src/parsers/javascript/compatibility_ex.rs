@@ -0,0 +1,28 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::compatibility::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn check_syntax_compatibility_nif(
+    env: Env,
+    file_content: String,
+    target: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::check_syntax_compatibility_nif();
+
+        match check_syntax_compatibility(&file_content, target.as_deref()) {
+            Ok(compatibility) => encode_response(env, atoms::ok(), fn_atom, compatibility),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
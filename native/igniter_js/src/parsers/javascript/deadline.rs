@@ -0,0 +1,403 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The tokens of the SWC lexer, cut short at the deadline of the call or at a level nested
+//! deeper than the limit, so the parse of a pathological source stops instead of running
+//! past its timeout or overflowing the stack, see [`crate::limits`].
+//!
+//! The levels are the brackets, braces, parentheses and template substitutions, and the
+//! labels and prefix operators in a row, e.g. `a: b: c: x` or `!!!x`, which nest without a
+//! bracket. Other chains, such as a long `+` concatenation, nest as deep as their length in
+//! the syntax tree, but with a few small stack frames a level.
+//!
+//! Past the deadline or the depth limit the parser gets the end of the file, and fails or
+//! returns what it parsed so far. The caller checks [`check_stopped`] after the parse to
+//! report the exceeded limit instead of that result.
+
+use crate::limits::{check_deadline, check_stopped, Depth};
+use crate::parser_options::es_syntax;
+use swc_common::BytePos;
+use swc_common::{sync::Lrc, FileName, SourceMap};
+use swc_ecma_ast::EsVersion;
+use swc_ecma_lexer::common::input::Tokens as BaseTokens;
+use swc_ecma_lexer::common::syntax::SyntaxFlags;
+use swc_ecma_lexer::lexer::TokenFlags;
+use swc_ecma_lexer::TokenContexts;
+use swc_ecma_parser::error::Error;
+use swc_ecma_parser::input::Tokens;
+use swc_ecma_parser::lexer::Lexer;
+use swc_ecma_parser::unstable::{Token, TokenAndSpan, TokenValue};
+use swc_ecma_parser::{Context, Parser, StringInput};
+
+/// The number of tokens read between two checks of the deadline.
+const CHECK_INTERVAL: usize = 1024;
+
+#[derive(Clone)]
+pub struct DeadlineTokens<I> {
+    inner: I,
+    count: usize,
+    depth: Depth,
+    /// The number of labels and of prefix operators in a row, each a level of `depth`.
+    labels: usize,
+    prefixes: usize,
+    previous: [Option<Token>; 2],
+    stopped: bool,
+}
+
+impl<I> DeadlineTokens<I> {
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            count: 0,
+            depth: Depth::new(),
+            labels: 0,
+            prefixes: 0,
+            previous: [None, None],
+            stopped: false,
+        }
+    }
+}
+
+impl<I: Tokens> Iterator for DeadlineTokens<I> {
+    type Item = TokenAndSpan;
+
+    fn next(&mut self) -> Option<TokenAndSpan> {
+        self.count += 1;
+        if self.count.is_multiple_of(CHECK_INTERVAL) && check_deadline().is_err() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return None;
+        }
+
+        let token = self.inner.next()?;
+        let [before_previous, previous] = self.previous;
+        self.previous = [previous, Some(token.token)];
+
+        let is_label = token.token == Token::Colon
+            && previous == Some(Token::Ident)
+            // A key of an object literal follows a `{` too, the first label of a block does
+            // not count
+            && matches!(
+                before_previous,
+                None | Some(Token::Semi | Token::RBrace | Token::Colon)
+            );
+        let is_prefix = matches!(
+            token.token,
+            Token::Bang
+                | Token::Tilde
+                | Token::Plus
+                | Token::Minus
+                | Token::PlusPlus
+                | Token::MinusMinus
+                | Token::DotDotDot
+                | Token::TypeOf
+                | Token::Void
+                | Token::Delete
+                | Token::Await
+                | Token::New
+                | Token::Yield
+        );
+        // The identifier of a label is only known as such at its `:`
+        if !is_label && token.token != Token::Ident {
+            self.close_run(Run::Labels);
+        }
+        if !is_prefix {
+            self.close_run(Run::Prefixes);
+        }
+        if is_label {
+            self.labels += 1;
+            self.open();
+        }
+        if is_prefix {
+            self.prefixes += 1;
+            self.open();
+        }
+
+        match token.token {
+            Token::LParen | Token::LBracket | Token::LBrace | Token::TemplateHead => self.open(),
+            Token::RParen | Token::RBracket | Token::RBrace => self.depth.close(),
+            _ => {}
+        }
+        if self.stopped {
+            return None;
+        }
+        Some(token)
+    }
+}
+
+enum Run {
+    Labels,
+    Prefixes,
+}
+
+impl<I> DeadlineTokens<I> {
+    fn open(&mut self) {
+        if self.depth.open().is_err() {
+            self.stopped = true;
+        }
+    }
+
+    fn close_run(&mut self, run: Run) {
+        let levels = match run {
+            Run::Labels => std::mem::take(&mut self.labels),
+            Run::Prefixes => std::mem::take(&mut self.prefixes),
+        };
+        for _ in 0..levels {
+            self.depth.close();
+        }
+    }
+}
+
+/// Checks the nesting depth of a JavaScript source before it is parsed by a parser without
+/// these tokens, i.e. OXC or Biome.
+///
+/// The source is read by the SWC parser, as its lexer alone can not tell a regular
+/// expression from a division, nor go on with a template after the `}` of a substitution.
+pub fn check_depth(source: &str) -> Result<(), String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Anon.into(), source.to_string());
+    let lexer = Lexer::new(
+        es_syntax(),
+        Default::default(),
+        StringInput::from(&*fm),
+        None,
+    );
+
+    let _ = Parser::new_from(DeadlineTokens::new(lexer)).parse_program();
+    check_stopped()
+}
+
+impl<I: Tokens> BaseTokens<TokenAndSpan> for DeadlineTokens<I> {
+    type Checkpoint = I::Checkpoint;
+
+    fn set_ctx(&mut self, ctx: Context) {
+        self.inner.set_ctx(ctx)
+    }
+
+    fn ctx(&self) -> Context {
+        self.inner.ctx()
+    }
+
+    fn ctx_mut(&mut self) -> &mut Context {
+        self.inner.ctx_mut()
+    }
+
+    fn syntax(&self) -> SyntaxFlags {
+        self.inner.syntax()
+    }
+
+    fn target(&self) -> EsVersion {
+        self.inner.target()
+    }
+
+    fn checkpoint_save(&self) -> Self::Checkpoint {
+        self.inner.checkpoint_save()
+    }
+
+    fn checkpoint_load(&mut self, checkpoint: Self::Checkpoint) {
+        self.inner.checkpoint_load(checkpoint)
+    }
+
+    fn start_pos(&self) -> BytePos {
+        self.inner.start_pos()
+    }
+
+    fn set_expr_allowed(&mut self, allow: bool) {
+        self.inner.set_expr_allowed(allow)
+    }
+
+    fn set_next_regexp(&mut self, start: Option<BytePos>) {
+        self.inner.set_next_regexp(start)
+    }
+
+    fn token_context(&self) -> &TokenContexts {
+        self.inner.token_context()
+    }
+
+    fn token_context_mut(&mut self) -> &mut TokenContexts {
+        self.inner.token_context_mut()
+    }
+
+    fn set_token_context(&mut self, contexts: TokenContexts) {
+        self.inner.set_token_context(contexts)
+    }
+
+    fn add_error(&mut self, error: Error) {
+        self.inner.add_error(error)
+    }
+
+    fn add_module_mode_error(&mut self, error: Error) {
+        self.inner.add_module_mode_error(error)
+    }
+
+    fn end_pos(&self) -> BytePos {
+        self.inner.end_pos()
+    }
+
+    fn take_errors(&mut self) -> Vec<Error> {
+        self.inner.take_errors()
+    }
+
+    fn take_script_module_errors(&mut self) -> Vec<Error> {
+        self.inner.take_script_module_errors()
+    }
+
+    fn update_token_flags(&mut self, f: impl FnOnce(&mut TokenFlags)) {
+        self.inner.update_token_flags(f)
+    }
+
+    fn token_flags(&self) -> TokenFlags {
+        self.inner.token_flags()
+    }
+}
+
+impl<I: Tokens> Tokens for DeadlineTokens<I> {
+    fn clone_token_value(&self) -> Option<TokenValue> {
+        self.inner.clone_token_value()
+    }
+
+    fn take_token_value(&mut self) -> Option<TokenValue> {
+        self.inner.take_token_value()
+    }
+
+    fn get_token_value(&self) -> Option<&TokenValue> {
+        self.inner.get_token_value()
+    }
+
+    fn set_token_value(&mut self, token_value: Option<TokenValue>) {
+        self.inner.set_token_value(token_value)
+    }
+
+    fn scan_jsx_token(&mut self, allow_multiline_jsx_text: bool) -> TokenAndSpan {
+        self.inner.scan_jsx_token(allow_multiline_jsx_text)
+    }
+
+    fn scan_jsx_open_el_terminal_token(&mut self) -> TokenAndSpan {
+        self.inner.scan_jsx_open_el_terminal_token()
+    }
+
+    fn rescan_jsx_open_el_terminal_token(&mut self, reset: BytePos) -> TokenAndSpan {
+        self.inner.rescan_jsx_open_el_terminal_token(reset)
+    }
+
+    fn rescan_jsx_token(&mut self, allow_multiline_jsx_text: bool, reset: BytePos) -> TokenAndSpan {
+        self.inner.rescan_jsx_token(allow_multiline_jsx_text, reset)
+    }
+
+    fn scan_jsx_identifier(&mut self, start: BytePos) -> TokenAndSpan {
+        self.inner.scan_jsx_identifier(start)
+    }
+
+    fn scan_jsx_attribute_value(&mut self) -> TokenAndSpan {
+        self.inner.scan_jsx_attribute_value()
+    }
+
+    fn rescan_template_token(
+        &mut self,
+        start: BytePos,
+        start_with_back_tick: bool,
+    ) -> TokenAndSpan {
+        // The `}` of a substitution closed a level, which the text after it opens again up
+        // to the next substitution
+        let token = self
+            .inner
+            .rescan_template_token(start, start_with_back_tick);
+        if matches!(token.token, Token::TemplateHead | Token::TemplateMiddle) {
+            self.open();
+        }
+        token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::{
+        limits, on_call_stack, start_call, take_exceeded, take_timed_out, Limit, LimitExceeded,
+    };
+    use crate::parser_options::es_syntax;
+    use std::time::Duration;
+    use swc_common::{sync::Lrc, FileName, SourceMap};
+    use swc_ecma_parser::{lexer::Lexer, StringInput};
+
+    #[test]
+    fn test_deadline_tokens() {
+        let source = "a;".repeat(2048);
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(FileName::Anon.into(), source);
+        let lexer = || {
+            Lexer::new(
+                es_syntax(),
+                Default::default(),
+                StringInput::from(&*fm),
+                None,
+            )
+        };
+
+        start_call(Some(0));
+        assert_eq!(DeadlineTokens::new(lexer()).count(), 4096);
+
+        // Past the deadline, the tokens end at the next check
+        start_call(Some(1));
+        assert_eq!(check_deadline(), Ok(()));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(DeadlineTokens::new(lexer()).count(), CHECK_INTERVAL - 1);
+        assert!(take_timed_out());
+    }
+
+    #[test]
+    fn test_check_depth() {
+        on_call_stack(check_depth_at_the_limit);
+    }
+
+    fn check_depth_at_the_limit() {
+        let max = limits().max_depth;
+        let nested = |open: &str, close: &str, depth| {
+            format!("{}1{};", open.repeat(depth), close.repeat(depth))
+        };
+        for (open, close) in [
+            ("[", "]"),
+            ("f(", ")"),
+            ("{a: ", "}"),
+            ("`${", "}`"),
+            ("a: ", ""),
+            ("!", ""),
+        ] {
+            assert_eq!(check_depth(&nested(open, close, max)), Ok(()), "{open}");
+            assert!(
+                check_depth(&nested(open, close, max + 1)).is_err(),
+                "{open}"
+            );
+            assert_eq!(
+                take_exceeded(),
+                Some(LimitExceeded {
+                    limit: Limit::Depth,
+                    size: max + 1,
+                    max
+                })
+            );
+        }
+
+        // A template goes on after the `}` of a substitution, and a regular expression or a
+        // string is not nested
+        let open = "[".repeat(max + 1);
+        let source = format!("x = `${{a}}${{b}}{open}`; y = /'/; z = '{open}';");
+        assert_eq!(check_depth(&source), Ok(()));
+        let source = format!("x = `${{a}}${{b}}` / 2; y = {};", nested("[", "]", max));
+        assert_eq!(check_depth(&source), Ok(()));
+
+        // The labels and prefix operators are only levels in a row
+        let terms = vec!["a"; 2 * max];
+        for source in [
+            format!("x = {};", terms.join(" - ")),
+            format!("x = {};", terms.join(" + -")),
+            "if (a) { b: c; }\n".repeat(2 * max),
+            format!("x = {{{}}};", terms.join(": 1, ")),
+        ] {
+            assert_eq!(check_depth(&source), Ok(()), "{source}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Parsed JavaScript Documents
+//!
+//! Every string-based function of this crate parses its input from scratch. A `Document`
+//! keeps the source together with its parsed module, so a caller can parse once, run many
+//! queries against the same AST, apply a batch of mutations, and serialize at the end.
+//!
+//! The parsed module is cached until the next mutation; queries after a mutation parse the
+//! updated source once and share the result again.
+
+use std::sync::Mutex;
+
+use swc_ecma_ast::*;
+
+use crate::parsers::asset::patch::{apply_js_patch, JsPatchOp};
+use crate::parsers::javascript::ast::{find_variable_in_module, VariableLookup, VariableMatch};
+use crate::parsers::javascript::helpers::parse;
+use crate::parsers::javascript::phoenix::list_hooks_in_module;
+
+#[derive(Debug)]
+struct DocumentState {
+    code: String,
+    module: Option<Module>,
+}
+
+/// A JavaScript source and its cached parsed module.
+#[derive(Debug)]
+pub struct Document {
+    state: Mutex<DocumentState>,
+}
+
+impl Document {
+    /// Parses the source, failing early when it is not valid JavaScript.
+    pub fn parse(code: &str) -> Result<Document, String> {
+        let (module, _comments, _cm) = parse(code)?;
+
+        Ok(Document {
+            state: Mutex::new(DocumentState {
+                code: code.to_string(),
+                module: Some(module),
+            }),
+        })
+    }
+
+    /// Runs `query` against the cached module, parsing the current source if a mutation
+    /// invalidated it.
+    fn with_module<T>(&self, query: impl FnOnce(&mut Module) -> T) -> Result<T, String> {
+        let mut state = self.state.lock().map_err(|_| "Document lock poisoned")?;
+
+        if state.module.is_none() {
+            let (module, _comments, _cm) = parse(&state.code)?;
+            state.module = Some(module);
+        }
+
+        Ok(query(
+            state.module.as_mut().expect("module was just parsed"),
+        ))
+    }
+
+    /// The sources of the import declarations, in order.
+    pub fn imports(&self) -> Result<Vec<String>, String> {
+        self.with_module(|module| {
+            module
+                .body
+                .iter()
+                .filter_map(|item| match item {
+                    ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                        Some(import.src.value.to_string())
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+
+    /// The hooks registered in the LiveSocket, see `list_hooks_from_ast`.
+    pub fn hooks(&self) -> Result<Vec<String>, String> {
+        self.with_module(list_hooks_in_module)?
+    }
+
+    /// Finds a declaration, see `find_variable_from_ast`.
+    pub fn find_variable(
+        &self,
+        variable_name: &str,
+        lookup: &VariableLookup,
+    ) -> Result<Option<VariableMatch>, String> {
+        self.with_module(|module| find_variable_in_module(module, variable_name, lookup))
+    }
+
+    /// Applies a batch of operations. Either every operation succeeds and the document is
+    /// updated, or the document is left as it was.
+    pub fn apply(&self, ops: &[JsPatchOp]) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|_| "Document lock poisoned")?;
+        let updated = apply_js_patch(&state.code, ops)?;
+
+        if updated != state.code {
+            state.code = updated;
+            state.module = None;
+        }
+        Ok(())
+    }
+
+    /// The current source of the document.
+    pub fn code(&self) -> Result<String, String> {
+        let state = self.state.lock().map_err(|_| "Document lock poisoned")?;
+        Ok(state.code.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let code = r#"
+            import { Socket } from "phoenix";
+            import { LiveSocket } from "phoenix_live_view";
+            let Hooks = { Copy };
+            let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+            "#;
+
+        let document = Document::parse(code).unwrap();
+        assert_eq!(
+            document.imports().unwrap(),
+            ["phoenix", "phoenix_live_view"]
+        );
+        assert_eq!(document.hooks().unwrap(), ["Copy"]);
+
+        let lookup = VariableLookup::default();
+        let found = document.find_variable("Hooks", &lookup).unwrap().unwrap();
+        assert_eq!(found.init, Some("object"));
+
+        document
+            .apply(&[
+                JsPatchOp::InsertImports("import Tooltip from \"./tooltip\";".to_string()),
+                JsPatchOp::ExtendHookObject(vec!["Tooltip".to_string()]),
+            ])
+            .unwrap();
+        assert_eq!(document.hooks().unwrap(), ["...Hooks", "Tooltip"]);
+        assert_eq!(document.imports().unwrap().len(), 3);
+
+        let before = document.code().unwrap();
+        let failing = [
+            JsPatchOp::InsertImports("import Menu from \"./menu\";".to_string()),
+            JsPatchOp::InsertAtIndex("let = ;".to_string(), 0),
+        ];
+        assert!(document.apply(&failing).is_err());
+        assert_eq!(document.code().unwrap(), before);
+
+        assert!(Document::parse("let = ;").is_err());
+    }
+}
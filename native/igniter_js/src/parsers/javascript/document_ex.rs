@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::asset::patch::JsPatchOp;
+use crate::parsers::javascript::ast::VariableLookup;
+use crate::parsers::javascript::ast_ex::VariableMatchResult;
+use crate::parsers::javascript::document::Document;
+
+use rustler::{Atom, Encoder, Env, NifResult, NifTaggedEnum, ResourceArc, Term};
+
+#[rustler::resource_impl]
+impl rustler::Resource for Document {}
+
+/// A query run against a parsed document.
+///
+/// On the Elixir side these are `:imports`, `:hooks` and `{:find_variable, name}`.
+#[derive(Debug, NifTaggedEnum)]
+pub enum DocumentQuery {
+    Imports,
+    Hooks,
+    FindVariable(String),
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn parse_document_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::parse_document_nif();
+
+        match Document::parse(&file_content) {
+            Ok(document) => encode_response(env, atoms::ok(), fn_atom, ResourceArc::new(document)),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn query_document_nif<'a>(
+    env: Env<'a>,
+    document: ResourceArc<Document>,
+    query: DocumentQuery,
+    call_options: CallOptions,
+) -> NifResult<Term<'a>> {
+    run_call(call_options, || {
+        let fn_atom = atoms::query_document_nif();
+
+        let result = match query {
+            DocumentQuery::Imports => document.imports().map(|imports| imports.encode(env)),
+            DocumentQuery::Hooks => document.hooks().map(|hooks| hooks.encode(env)),
+            DocumentQuery::FindVariable(name) => {
+                match document.find_variable(&name, &VariableLookup::default()) {
+                    Ok(Some(found)) => Ok(VariableMatchResult {
+                        kind: Atom::from_str(env, found.kind)?,
+                        top_level: found.top_level,
+                        init: found
+                            .init
+                            .map(|init| Atom::from_str(env, init))
+                            .transpose()?,
+                    }
+                    .encode(env)),
+                    Ok(None) => Ok(rustler::types::atom::nil().encode(env)),
+                    Err(error_msg) => Err(error_msg),
+                }
+            }
+        };
+
+        match result {
+            Ok(term) => encode_response(env, atoms::ok(), fn_atom, term),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn apply_document_nif(
+    env: Env,
+    document: ResourceArc<Document>,
+    ops: Vec<JsPatchOp>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::apply_document_nif();
+
+        match document.apply(&ops) {
+            Ok(()) => encode_response(env, atoms::ok(), fn_atom, document),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn document_to_source_nif(
+    env: Env,
+    document: ResourceArc<Document>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::document_to_source_nif();
+
+        let (status, result) = match document.code() {
+            Ok(code) => (atoms::ok(), code),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
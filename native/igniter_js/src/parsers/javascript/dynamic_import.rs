@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Detection and insertion of dynamic `import("module")` calls, for the hooks and vendor
+//! modules an app lazy-loads instead of importing them up front.
+
+use crate::parsers::javascript::helpers::*;
+use crate::parsers::javascript::imports::{ImportCollector, ImportStatement};
+#[cfg(feature = "nif")]
+use rustler::{NifTaggedEnum, NifUnitEnum};
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitWith;
+
+/// The form of the lazy-loading snippet inserted by [`insert_dynamic_import_to_ast`].
+///
+/// On the Elixir side these are the atoms `:await` and `:then`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum DynamicImportStyle {
+    /// `const X = await import("module");`, the namespace of the module. It needs top-level
+    /// await, so an ES module.
+    #[default]
+    Await,
+    /// `const X = import("module").then((module) => module.default);`, a promise of the
+    /// default export, e.g. a hook loaded on demand.
+    Then,
+}
+
+/// The top-level statement a new statement is inserted next to.
+///
+/// On the Elixir side these are `:after_last_import`, `{:before, "liveSocket"}`,
+/// `{:after, "Hooks"}` and `:end`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum StatementAnchor {
+    /// After the last import, or at the top if there is none.
+    #[default]
+    AfterLastImport,
+    /// Before the declaration of the given name.
+    Before(String),
+    /// After the declaration of the given name.
+    After(String),
+    /// At the end of the module.
+    End,
+}
+
+/// Checks whether a module is loaded with a dynamic `import("module")` anywhere in the
+/// source, e.g. inside a hook.
+///
+/// # Returns
+/// * `Ok(bool)` - Whether a dynamic import of the module was found. Static imports do not
+///   count.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = "const Chart = { mounted() { import(\"chart.js\").then(() => {}); } };";
+/// assert_eq!(is_dynamically_imported(code, "chart.js"), Ok(true));
+/// assert_eq!(is_dynamically_imported(code, "topbar"), Ok(false));
+/// ```
+pub fn is_dynamically_imported(file_content: &str, module_name: &str) -> Result<bool, String> {
+    let (module, _comments, _cm) = parse(file_content)?;
+    Ok(is_dynamically_imported_in(&module, module_name))
+}
+
+fn is_dynamically_imported_in(module: &Module, module_name: &str) -> bool {
+    let mut collector = ImportCollector::default();
+    module.visit_with(&mut collector);
+    collector.imports.iter().any(|(source, statement, _)| {
+        *statement == ImportStatement::Dynamic && source == module_name
+    })
+}
+
+/// The names a top-level statement declares.
+fn declared_names(item: &ModuleItem) -> Vec<String> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+        _ => return vec![],
+    };
+
+    match decl {
+        Decl::Var(var) => var
+            .decls
+            .iter()
+            .filter_map(|declarator| declarator.name.as_ident())
+            .map(|ident| ident.sym.to_string())
+            .collect(),
+        Decl::Fn(function) => vec![function.ident.sym.to_string()],
+        Decl::Class(class) => vec![class.ident.sym.to_string()],
+        _ => vec![],
+    }
+}
+
+fn declaration_index(body: &[ModuleItem], name: &str) -> Result<usize, String> {
+    body.iter()
+        .position(|item| declared_names(item).iter().any(|declared| declared == name))
+        .ok_or_else(|| format!("No top-level declaration of `{}` was found", name))
+}
+
+/// The index of `body` a new statement goes to.
+fn anchor_index(body: &[ModuleItem], anchor: &StatementAnchor) -> Result<usize, String> {
+    match anchor {
+        StatementAnchor::AfterLastImport => Ok(body
+            .iter()
+            .rposition(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+            .map_or(0, |index| index + 1)),
+        StatementAnchor::Before(name) => declaration_index(body, name),
+        StatementAnchor::After(name) => declaration_index(body, name).map(|index| index + 1),
+        StatementAnchor::End => Ok(body.len()),
+    }
+}
+
+fn dynamic_import_snippet(
+    binding: &str,
+    module_name: &str,
+    style: DynamicImportStyle,
+) -> Result<ModuleItem, String> {
+    let source = serde_json::to_string(module_name).map_err(|error| error.to_string())?;
+    let code = match style {
+        DynamicImportStyle::Await => format!("const {} = await import({});", binding, source),
+        DynamicImportStyle::Then => format!(
+            "const {} = import({}).then((module) => module.default);",
+            binding, source
+        ),
+    };
+
+    parse(&code)
+        .ok()
+        .and_then(|(module, _, _)| module.body.into_iter().next())
+        .filter(|item| declared_names(item) == [binding])
+        .ok_or_else(|| format!("`{}` is not a valid variable name", binding))
+}
+
+/// Inserts a lazy-loading snippet for a module, e.g. `const Chart = await import("chart.js")`,
+/// next to an anchor statement.
+///
+/// Nothing is inserted when the module is already imported dynamically.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `binding` - The name of the variable holding the module.
+/// * `module_name` - The module to import, e.g. `"../vendor/chart"`.
+/// * `style` - The form of the snippet.
+/// * `anchor` - Where the snippet goes.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If the source can not be parsed, the binding is not a valid name or
+///   is already declared, or the anchor declaration does not exist.
+///
+/// # Example
+/// ```rust
+/// let code = "import { Socket } from \"phoenix\";\nlet Hooks = {};";
+/// let anchor = StatementAnchor::Before("Hooks".into());
+/// let result =
+///     insert_dynamic_import_to_ast(code, "Chart", "chart.js", DynamicImportStyle::Await, anchor);
+/// assert!(result.unwrap().contains("const Chart = await import(\"chart.js\");\nlet Hooks"));
+/// ```
+pub fn insert_dynamic_import_to_ast(
+    file_content: &str,
+    binding: &str,
+    module_name: &str,
+    style: DynamicImportStyle,
+    anchor: StatementAnchor,
+) -> Result<String, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let snippet = dynamic_import_snippet(binding, module_name, style)?;
+
+    if is_dynamically_imported_in(&module, module_name) {
+        return Ok(code_gen_edit(
+            file_content,
+            &original,
+            &mut module,
+            comments,
+            cm,
+        ));
+    }
+    if module
+        .body
+        .iter()
+        .any(|item| declared_names(item).iter().any(|name| name == binding))
+    {
+        return Err(format!("`{}` is already declared", binding));
+    }
+
+    let index = anchor_index(&module.body, &anchor)?;
+    module.body.insert(index, snippet);
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP: &str = r#"import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+let Hooks = {};
+let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+"#;
+
+    #[test]
+    fn test_is_dynamically_imported() {
+        let code = r#"import topbar from "topbar";
+Hooks.Chart = { async mounted() { const { Chart } = await import("chart.js"); } };
+const Editor = import(`../vendor/editor`);
+"#;
+        assert_eq!(is_dynamically_imported(code, "chart.js"), Ok(true));
+        assert_eq!(is_dynamically_imported(code, "../vendor/editor"), Ok(true));
+        assert_eq!(is_dynamically_imported(code, "topbar"), Ok(false));
+        assert!(is_dynamically_imported("let = ;", "topbar").is_err());
+    }
+
+    #[test]
+    fn test_insert_dynamic_import_to_ast() {
+        let result = insert_dynamic_import_to_ast(
+            APP,
+            "Chart",
+            "chart.js",
+            DynamicImportStyle::Await,
+            StatementAnchor::default(),
+        )
+        .unwrap();
+        assert!(result.contains(
+            "from \"phoenix_live_view\";\nconst Chart = await import(\"chart.js\");\nlet Hooks"
+        ));
+
+        let result = insert_dynamic_import_to_ast(
+            APP,
+            "Editor",
+            "../vendor/editor",
+            DynamicImportStyle::Then,
+            StatementAnchor::After("Hooks".into()),
+        )
+        .unwrap();
+        assert!(result.contains(
+            "let Hooks = {};\nconst Editor = import(\"../vendor/editor\").then((module)=>module.default);"
+        ));
+
+        // Already imported, so nothing changes
+        let again = insert_dynamic_import_to_ast(
+            &result,
+            "Other",
+            "../vendor/editor",
+            DynamicImportStyle::Await,
+            StatementAnchor::End,
+        )
+        .unwrap();
+        assert_eq!(again, result);
+
+        for (binding, anchor) in [
+            ("Hooks", StatementAnchor::End),
+            ("not-valid", StatementAnchor::End),
+            ("Chart", StatementAnchor::Before("missing".into())),
+        ] {
+            assert!(insert_dynamic_import_to_ast(
+                APP,
+                binding,
+                "chart.js",
+                DynamicImportStyle::Await,
+                anchor
+            )
+            .is_err());
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_edit, encode_response};
+use crate::parsers::javascript::dynamic_import::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn is_dynamically_imported_nif(
+    env: Env,
+    file_content: String,
+    module_name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::is_dynamically_imported_nif();
+
+        match is_dynamically_imported(&file_content, &module_name) {
+            Ok(imported) => encode_response(env, atoms::ok(), fn_atom, imported),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn insert_dynamic_import_to_ast_nif(
+    env: Env,
+    file_content: String,
+    binding: String,
+    module_name: String,
+    style: DynamicImportStyle,
+    anchor: StatementAnchor,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result =
+            insert_dynamic_import_to_ast(&file_content, &binding, &module_name, style, anchor);
+
+        encode_edit(
+            env,
+            atoms::insert_dynamic_import_to_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
@@ -0,0 +1,431 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! One interface over the two JavaScript parsers of the crate, SWC and OXC.
+//!
+//! SWC backs the transforms, as it can emit code from its syntax tree, and OXC backs the
+//! ESTree conversion and the validation, as it recovers from more syntax errors. Their
+//! details differ: SWC positions are offsets in a source map starting at `1`, SWC keeps the
+//! comments aside by position while OXC keeps them in the program, OXC puts the directives
+//! such as `"use strict"` outside of the body, and their errors have different shapes.
+//!
+//! Each parser implements [`JsEngine`], which reports them the same way: top-level
+//! statements by ESTree type, comments without their delimiters, UTF-8 byte offsets and
+//! [`Diagnostic`]s. A NIF taking an [`Engine`] can then use either one, and the choice no
+//! longer shows in what the Elixir side receives.
+
+use crate::diagnostics::{Diagnostic, DiagnosticSeverity};
+use crate::parsers::javascript::ast_json::{parse_program, statement_type};
+use crate::parsers::javascript::estree_codegen::estree_to_source;
+use crate::parsers::javascript::helpers::{
+    code_gen_from_ast_module, parse, parse_with_diagnostics, ModuleKind, SpanOffsets,
+};
+use oxc_allocator::Allocator;
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use oxc_span::GetSpan;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use swc_common::comments;
+use swc_common::Spanned;
+use swc_ecma_ast::*;
+
+/// The parser behind a NIF.
+///
+/// On the Elixir side these are the atoms `:swc` and `:oxc`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum Engine {
+    #[default]
+    Swc,
+    Oxc,
+}
+
+impl Engine {
+    pub fn backend(self) -> &'static dyn JsEngine {
+        match self {
+            Engine::Swc => &Swc,
+            Engine::Oxc => &Oxc,
+        }
+    }
+}
+
+/// On the Elixir side these are the atoms `:line` and `:block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum CommentKind {
+    Line,
+    Block,
+}
+
+/// A comment, with its text without the `//` or `/* */` delimiters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct EngineComment {
+    pub kind: CommentKind,
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A top-level statement, with its ESTree type, e.g. `ImportDeclaration`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct EngineStatement {
+    pub kind: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// What an engine reports of a source, see [`parse_with_engine`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct ParsedSource {
+    pub engine: Engine,
+    pub statements: Vec<EngineStatement>,
+    pub comments: Vec<EngineComment>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Receives what an engine visits of a source, in source order.
+pub trait EngineVisitor {
+    fn visit_statement(&mut self, _statement: EngineStatement) {}
+
+    fn visit_comment(&mut self, _comment: EngineComment) {}
+}
+
+impl EngineVisitor for () {}
+
+/// A JavaScript parser, reporting what it parses in the same shape as the other ones.
+pub trait JsEngine {
+    /// Parses the source, then visits its top-level statements and its comments. A source
+    /// with a syntax error is not visited, even when the parser recovered from it.
+    ///
+    /// Returns the syntax errors and warnings of the source.
+    fn visit(&self, source: &str, visitor: &mut dyn EngineVisitor) -> Vec<Diagnostic>;
+
+    /// Parses the source and prints it back from its syntax tree.
+    fn codegen(&self, source: &str) -> Result<String, String>;
+}
+
+/// The SWC parser and code generator, see [`crate::parsers::javascript::helpers`].
+pub struct Swc;
+
+/// The OXC parser, see [`crate::parsers::javascript::ast_json`]. OXC has no code generator
+/// in this crate, so the code is printed from the ESTree of the program, without comments.
+pub struct Oxc;
+
+fn swc_statement_type(item: &ModuleItem) -> &'static str {
+    match item {
+        ModuleItem::ModuleDecl(decl) => match decl {
+            ModuleDecl::Import(_) => "ImportDeclaration",
+            ModuleDecl::ExportDecl(_) => "ExportNamedDeclaration",
+            ModuleDecl::ExportNamed(named) => match named.specifiers.as_slice() {
+                [ExportSpecifier::Namespace(_)] => "ExportAllDeclaration",
+                _ => "ExportNamedDeclaration",
+            },
+            ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+                "ExportDefaultDeclaration"
+            }
+            ModuleDecl::ExportAll(_) => "ExportAllDeclaration",
+            ModuleDecl::TsImportEquals(_) => "TSImportEqualsDeclaration",
+            ModuleDecl::TsExportAssignment(_) => "TSExportAssignment",
+            ModuleDecl::TsNamespaceExport(_) => "TSNamespaceExportDeclaration",
+        },
+        ModuleItem::Stmt(stmt) => match stmt {
+            Stmt::Block(_) => "BlockStatement",
+            Stmt::Empty(_) => "EmptyStatement",
+            Stmt::Debugger(_) => "DebuggerStatement",
+            Stmt::With(_) => "WithStatement",
+            Stmt::Return(_) => "ReturnStatement",
+            Stmt::Labeled(_) => "LabeledStatement",
+            Stmt::Break(_) => "BreakStatement",
+            Stmt::Continue(_) => "ContinueStatement",
+            Stmt::If(_) => "IfStatement",
+            Stmt::Switch(_) => "SwitchStatement",
+            Stmt::Throw(_) => "ThrowStatement",
+            Stmt::Try(_) => "TryStatement",
+            Stmt::While(_) => "WhileStatement",
+            Stmt::DoWhile(_) => "DoWhileStatement",
+            Stmt::For(_) => "ForStatement",
+            Stmt::ForIn(_) => "ForInStatement",
+            Stmt::ForOf(_) => "ForOfStatement",
+            Stmt::Decl(decl) => match decl {
+                Decl::Class(_) => "ClassDeclaration",
+                Decl::Fn(_) => "FunctionDeclaration",
+                Decl::Var(_) | Decl::Using(_) => "VariableDeclaration",
+                Decl::TsInterface(_) => "TSInterfaceDeclaration",
+                Decl::TsTypeAlias(_) => "TSTypeAliasDeclaration",
+                Decl::TsEnum(_) => "TSEnumDeclaration",
+                Decl::TsModule(_) => "TSModuleDeclaration",
+            },
+            Stmt::Expr(_) => "ExpressionStatement",
+        },
+    }
+}
+
+fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+}
+
+impl JsEngine for Swc {
+    fn visit(&self, source: &str, visitor: &mut dyn EngineVisitor) -> Vec<Diagnostic> {
+        let ((module, comments, cm), diagnostics) =
+            match parse_with_diagnostics(source, ModuleKind::Auto) {
+                Ok(parsed) => parsed,
+                Err(diagnostics) => return diagnostics,
+            };
+        if has_errors(&diagnostics) {
+            return diagnostics;
+        }
+
+        let spans = SpanOffsets::new(&cm);
+
+        for item in &module.body {
+            let (start, end) = spans.range(item.span());
+            visitor.visit_statement(EngineStatement {
+                kind: swc_statement_type(item).to_string(),
+                start,
+                end,
+            });
+        }
+
+        let (leading, trailing) = comments.borrow_all();
+        let mut all: Vec<comments::Comment> = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .cloned()
+            .collect();
+        all.sort_by_key(|comment| comment.span.lo);
+        all.dedup_by_key(|comment| comment.span.lo);
+        for comment in all {
+            let (start, end) = spans.range(comment.span);
+            visitor.visit_comment(EngineComment {
+                kind: match comment.kind {
+                    comments::CommentKind::Line => CommentKind::Line,
+                    comments::CommentKind::Block => CommentKind::Block,
+                },
+                text: comment.text.to_string(),
+                start,
+                end,
+            });
+        }
+
+        diagnostics
+    }
+
+    fn codegen(&self, source: &str) -> Result<String, String> {
+        let (mut module, comments, cm) = parse(source)?;
+        Ok(code_gen_from_ast_module(&mut module, comments, cm))
+    }
+}
+
+fn oxc_diagnostic(source: &str, error: &OxcDiagnostic) -> Diagnostic {
+    let severity = match error.severity {
+        Severity::Error => DiagnosticSeverity::Error,
+        Severity::Warning => DiagnosticSeverity::Warning,
+        Severity::Advice => DiagnosticSeverity::Advice,
+    };
+    let (start, end) = error
+        .labels
+        .as_ref()
+        .and_then(|labels| {
+            labels
+                .iter()
+                .find(|label| label.primary())
+                .or(labels.first())
+        })
+        .map_or((0, 0), |label| {
+            (label.offset(), label.offset() + label.len())
+        });
+
+    Diagnostic::new(
+        source,
+        error.message.to_string(),
+        severity,
+        error.help.as_ref().map(|help| help.to_string()),
+        start,
+        end,
+    )
+}
+
+impl JsEngine for Oxc {
+    fn visit(&self, source: &str, visitor: &mut dyn EngineVisitor) -> Vec<Diagnostic> {
+        let allocator = Allocator::default();
+        let parser_return = match parse_program(&allocator, source) {
+            Ok(parser_return) => parser_return,
+            Err(message) => {
+                return vec![Diagnostic::new(
+                    source,
+                    message,
+                    DiagnosticSeverity::Error,
+                    None,
+                    0,
+                    0,
+                )]
+            }
+        };
+        let diagnostics: Vec<Diagnostic> = parser_return
+            .errors
+            .iter()
+            .map(|error| oxc_diagnostic(source, error))
+            .collect();
+        if has_errors(&diagnostics) {
+            return diagnostics;
+        }
+
+        let program = parser_return.program;
+        for directive in &program.directives {
+            visitor.visit_statement(EngineStatement {
+                kind: "ExpressionStatement".to_string(),
+                start: directive.span.start as usize,
+                end: directive.span.end as usize,
+            });
+        }
+        for statement in &program.body {
+            let span = statement.span();
+            visitor.visit_statement(EngineStatement {
+                kind: statement_type(statement).to_string(),
+                start: span.start as usize,
+                end: span.end as usize,
+            });
+        }
+        for comment in &program.comments {
+            visitor.visit_comment(EngineComment {
+                kind: if comment.is_line() {
+                    CommentKind::Line
+                } else {
+                    CommentKind::Block
+                },
+                text: comment.content_span().source_text(source).to_string(),
+                start: comment.span.start as usize,
+                end: comment.span.end as usize,
+            });
+        }
+
+        diagnostics
+    }
+
+    fn codegen(&self, source: &str) -> Result<String, String> {
+        let allocator = Allocator::default();
+        let parser_return = parse_program(&allocator, source)?;
+        if let Some(error) = parser_return
+            .errors
+            .iter()
+            .find(|error| error.severity == Severity::Error)
+        {
+            let diagnostic = oxc_diagnostic(source, error);
+            return Err(format!(
+                "Failed to parse module: {} at {}:{}",
+                diagnostic.message, diagnostic.line, diagnostic.column
+            ));
+        }
+
+        estree_to_source(&parser_return.program.to_estree_ts_json(true))
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    statements: Vec<EngineStatement>,
+    comments: Vec<EngineComment>,
+}
+
+impl EngineVisitor for Collector {
+    fn visit_statement(&mut self, statement: EngineStatement) {
+        self.statements.push(statement);
+    }
+
+    fn visit_comment(&mut self, comment: EngineComment) {
+        self.comments.push(comment);
+    }
+}
+
+/// Parses the source with the given engine, collecting its top-level statements, comments
+/// and diagnostics.
+///
+/// # Example
+/// ```rust
+/// let parsed = parse_with_engine("// Socket\nimport { Socket } from \"phoenix\";", Engine::Oxc);
+/// assert_eq!(parsed.statements[0].kind, "ImportDeclaration");
+/// assert_eq!(parsed.comments[0].text, " Socket");
+/// ```
+pub fn parse_with_engine(source: &str, engine: Engine) -> ParsedSource {
+    let mut collector = Collector::default();
+    let diagnostics = engine.backend().visit(source, &mut collector);
+
+    ParsedSource {
+        engine,
+        statements: collector.statements,
+        comments: collector.comments,
+        diagnostics,
+    }
+}
+
+/// Parses the source with the given engine and prints it back, see [`JsEngine::codegen`].
+pub fn codegen_with_engine(source: &str, engine: Engine) -> Result<String, String> {
+    engine.backend().codegen(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_engine() {
+        let source = r#""use strict";
+// Phoenix
+import { Socket } from "phoenix";
+export * as hooks from "./hooks";
+/* The socket */ let socket = new Socket("/socket", {});
+"#;
+        let swc = parse_with_engine(source, Engine::Swc);
+        let oxc = parse_with_engine(source, Engine::Oxc);
+
+        assert!(swc.diagnostics.is_empty());
+        assert_eq!(swc.statements, oxc.statements);
+        assert_eq!(swc.comments, oxc.comments);
+        assert_eq!(
+            swc.statements
+                .iter()
+                .map(|statement| statement.kind.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "ExpressionStatement",
+                "ImportDeclaration",
+                "ExportAllDeclaration",
+                "VariableDeclaration"
+            ]
+        );
+        let comment = &swc.comments[1];
+        assert_eq!(
+            (comment.kind, comment.text.as_str()),
+            (CommentKind::Block, " The socket ")
+        );
+        assert_eq!(&source[comment.start..comment.end], "/* The socket */");
+
+        for engine in [Engine::Swc, Engine::Oxc] {
+            let parsed = parse_with_engine("let a = ;", engine);
+            assert!(parsed.statements.is_empty());
+            assert_eq!(parsed.diagnostics[0].severity, DiagnosticSeverity::Error);
+            assert!(codegen_with_engine("let a = ;", engine)
+                .unwrap_err()
+                .starts_with("Failed to parse module"));
+        }
+    }
+
+    #[test]
+    fn test_codegen_with_engine() {
+        let source = "import { Socket } from \"phoenix\";\n// Connect\nlet socket = new Socket(\"/socket\", {});\n";
+
+        assert_eq!(codegen_with_engine(source, Engine::Swc).unwrap(), source);
+        assert_eq!(
+            codegen_with_engine(source, Engine::Oxc).unwrap(),
+            "import { Socket } from \"phoenix\";\nlet socket = new Socket(\"/socket\", {});\n"
+        );
+    }
+}
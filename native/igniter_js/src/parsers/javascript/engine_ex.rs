@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::diagnostics::DiagnosticSeverity;
+use crate::helpers::{encode_edit, encode_response};
+use crate::parsers::javascript::engine::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn parse_with_engine_nif(
+    env: Env,
+    file_content: String,
+    engine: Engine,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::parse_with_engine_nif();
+        let parsed = parse_with_engine(&file_content, engine);
+        let status = if parsed
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        {
+            atoms::error()
+        } else {
+            atoms::ok()
+        };
+
+        encode_response(env, status, fn_atom, parsed)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn codegen_with_engine_nif(
+    env: Env,
+    file_content: String,
+    engine: Engine,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::codegen_with_engine_nif();
+        let result = codegen_with_engine(&file_content, engine);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
@@ -0,0 +1,425 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Environment guards, i.e. the blocks keeping debug-only code out of production builds:
+//!
+//! ```javascript
+//! if (process.env.NODE_ENV === "development") {
+//!     liveSocket.enableDebug();
+//! }
+//! ```
+//!
+//! The statements are matched by their code, ignoring the formatting, so a pattern like
+//! `liveSocket.enableDebug()` finds them wherever they are.
+
+use crate::parsers::javascript::ast::FindCondition;
+use crate::parsers::javascript::helpers::*;
+#[cfg(feature = "nif")]
+use rustler::NifTaggedEnum;
+use swc_common::{EqIgnoreSpan, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+/// The condition of a guard.
+///
+/// On the Elixir side these are `{:node_env, "development"}`, `{:import_meta_env, "DEV"}`
+/// and `{:condition, "code"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum EnvGuard {
+    /// `process.env.NODE_ENV === "<value>"`.
+    NodeEnv(String),
+    /// `import.meta.env.<flag>`, e.g. the `DEV` and `PROD` flags of Vite.
+    ImportMetaEnv(String),
+    /// Any expression.
+    Condition(String),
+}
+
+impl EnvGuard {
+    fn code(&self) -> Result<String, String> {
+        match self {
+            EnvGuard::NodeEnv(value) => Ok(format!(
+                "process.env.NODE_ENV === {}",
+                serde_json::to_string(value).map_err(|error| error.to_string())?
+            )),
+            EnvGuard::ImportMetaEnv(flag) => Ok(format!("import.meta.env.{}", flag)),
+            EnvGuard::Condition(code) => Ok(code.clone()),
+        }
+    }
+
+    fn expr(&self) -> Result<Box<Expr>, String> {
+        let mut expr = parse_expr(&self.code()?)?;
+        expr.visit_mut_with(&mut DropSpan);
+        Ok(expr)
+    }
+}
+
+/// Statements and module items, so the top level and the blocks are searched alike.
+trait StmtLike: From<Stmt> {
+    fn stmt(&self) -> Option<&Stmt>;
+}
+
+impl StmtLike for Stmt {
+    fn stmt(&self) -> Option<&Stmt> {
+        Some(self)
+    }
+}
+
+impl StmtLike for ModuleItem {
+    fn stmt(&self) -> Option<&Stmt> {
+        self.as_stmt()
+    }
+}
+
+/// Parses the statements a guard wraps or is matched by.
+fn parse_pattern(pattern: &str) -> Result<Vec<Stmt>, String> {
+    let (module, _comments, _cm) = parse(pattern)?;
+    let stmts = module
+        .body
+        .into_iter()
+        .map(|item| match item {
+            ModuleItem::Stmt(stmt) => Ok(stmt),
+            ModuleItem::ModuleDecl(_) => Err(format!(
+                "Imports and exports can not be guarded: {}",
+                pattern
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if stmts.is_empty() {
+        return Err("The statement pattern is empty".to_string());
+    }
+    Ok(stmts)
+}
+
+/// The index of the first run of items equal to the pattern.
+fn find_run<T: StmtLike>(items: &[T], pattern: &[Stmt]) -> Option<usize> {
+    if items.len() < pattern.len() {
+        return None;
+    }
+    (0..=items.len() - pattern.len()).find(|start| {
+        items[*start..]
+            .iter()
+            .zip(pattern)
+            .all(|(item, stmt)| item.stmt().is_some_and(|item| item.eq_ignore_span(stmt)))
+    })
+}
+
+/// Whether an expression reads the environment, through `process.env` or `import.meta.env`.
+#[derive(Default)]
+struct EnvReader {
+    found: bool,
+}
+
+impl Visit for EnvReader {
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == "env") {
+            self.found |= match &*member.obj {
+                Expr::Ident(obj) => obj.sym == "process",
+                Expr::MetaProp(meta) => meta.kind == MetaPropKind::ImportMeta,
+                _ => false,
+            };
+        }
+        member.visit_children_with(self);
+    }
+}
+
+/// The statements of an `if` without `else` whose test reads the environment.
+fn env_guard_body(stmt: &Stmt) -> Option<&Vec<Stmt>> {
+    let Stmt::If(IfStmt {
+        test,
+        cons,
+        alt: None,
+        ..
+    }) = stmt
+    else {
+        return None;
+    };
+    let Stmt::Block(BlockStmt { stmts, .. }) = &**cons else {
+        return None;
+    };
+
+    let mut reader = EnvReader::default();
+    test.visit_with(&mut reader);
+    reader.found.then_some(stmts)
+}
+
+/// Finds whether the pattern is already inside a block guarded by the condition.
+struct GuardFinder<'a> {
+    test: &'a Expr,
+    pattern: &'a [Stmt],
+    found: bool,
+}
+
+impl Visit for GuardFinder<'_> {
+    fn visit_if_stmt(&mut self, stmt: &IfStmt) {
+        if let (true, Stmt::Block(block)) =
+            (stmt.test.as_ref().eq_ignore_span(self.test), &*stmt.cons)
+        {
+            self.found |= find_run(&block.stmts, self.pattern).is_some();
+        }
+        stmt.visit_children_with(self);
+    }
+}
+
+/// Wraps the first run of statements equal to the pattern in a guard.
+struct GuardWrapper<'a> {
+    test: &'a Expr,
+    pattern: &'a [Stmt],
+    wrapped: bool,
+}
+
+impl GuardWrapper<'_> {
+    fn wrap<T: StmtLike>(&mut self, items: &mut Vec<T>) {
+        if self.wrapped {
+            return;
+        }
+        let Some(start) = find_run(items, self.pattern) else {
+            return;
+        };
+
+        let stmts = items
+            .drain(start..start + self.pattern.len())
+            .filter_map(|item| item.stmt().cloned())
+            .collect();
+        let guard = Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(self.test.clone()),
+            cons: Box::new(Stmt::Block(BlockStmt {
+                span: DUMMY_SP,
+                stmts,
+                ..Default::default()
+            })),
+            alt: None,
+        });
+        items.insert(start, guard.into());
+        self.wrapped = true;
+    }
+}
+
+impl VisitMut for GuardWrapper<'_> {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        self.wrap(items);
+        items.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        self.wrap(stmts);
+        stmts.visit_mut_children_with(self);
+    }
+}
+
+/// Replaces the guards holding the pattern with their statements.
+struct GuardRemover<'a> {
+    pattern: &'a [Stmt],
+    removed: bool,
+}
+
+impl GuardRemover<'_> {
+    fn unwrap<T: StmtLike>(&mut self, items: &mut Vec<T>) {
+        let mut index = 0;
+        while index < items.len() {
+            let body = items[index]
+                .stmt()
+                .and_then(env_guard_body)
+                .filter(|body| find_run(body, self.pattern).is_some())
+                .cloned();
+            match body {
+                Some(body) => {
+                    let count = body.len();
+                    items.splice(index..=index, body.into_iter().map(T::from));
+                    index += count;
+                    self.removed = true;
+                }
+                None => index += 1,
+            }
+        }
+    }
+}
+
+impl VisitMut for GuardRemover<'_> {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        self.unwrap(items);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        self.unwrap(stmts);
+    }
+}
+
+/// Wraps statements in an environment guard, e.g. to keep debugging code out of
+/// production.
+///
+/// The first run of statements equal to the pattern, at the top level or in a block, is
+/// moved into a `if (<condition>) { ... }` block in its place. The content is returned
+/// untouched when the statements are already guarded by the same condition.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `pattern` - The code of the statements to wrap, e.g. `liveSocket.enableDebug();`.
+/// * `guard` - The condition of the guard.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If the source, the pattern or the condition can not be parsed, or the
+///   statements are not found.
+///
+/// # Example
+/// ```rust
+/// let code = "liveSocket.connect();\nliveSocket.enableDebug();";
+/// let guard = EnvGuard::NodeEnv("development".to_string());
+/// let result = wrap_in_env_guard_to_ast(code, "liveSocket.enableDebug()", &guard).unwrap();
+/// assert!(result.contains("if (process.env.NODE_ENV === \"development\") {"));
+/// ```
+pub fn wrap_in_env_guard_to_ast(
+    file_content: &str,
+    pattern: &str,
+    guard: &EnvGuard,
+) -> Result<String, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let pattern = parse_pattern(pattern)?;
+    let test = guard.expr()?;
+
+    let mut finder = GuardFinder {
+        test: &test,
+        pattern: &pattern,
+        found: false,
+    };
+    module.visit_with(&mut finder);
+    if finder.found {
+        return Ok(file_content.to_string());
+    }
+
+    let mut wrapper = GuardWrapper {
+        test: &test,
+        pattern: &pattern,
+        wrapped: false,
+    };
+    module.visit_mut_with(&mut wrapper);
+    if !wrapper.wrapped {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    }
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// Removes the environment guards holding statements equal to the pattern, keeping the
+/// statements in their place, the counterpart of [`wrap_in_env_guard_to_ast`].
+///
+/// A guard is an `if` without `else` whose condition reads `process.env` or
+/// `import.meta.env`, whatever the comparison is.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code, untouched when no guard holds the statements.
+/// * `Err(String)` - If the source or the pattern can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = "if (import.meta.env.DEV) {\n  liveSocket.enableDebug();\n}";
+/// let result = remove_env_guard_from_ast(code, "liveSocket.enableDebug()").unwrap();
+/// assert_eq!(result.trim(), "liveSocket.enableDebug();");
+/// ```
+pub fn remove_env_guard_from_ast(file_content: &str, pattern: &str) -> Result<String, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let pattern = parse_pattern(pattern)?;
+
+    let mut remover = GuardRemover {
+        pattern: &pattern,
+        removed: false,
+    };
+    module.visit_mut_with(&mut remover);
+    if !remover.removed {
+        return Ok(file_content.to_string());
+    }
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP: &str = r#"let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+liveSocket.enableDebug();
+liveSocket.enableLatencySim(1000);
+"#;
+
+    #[test]
+    fn test_wrap_in_env_guard() {
+        let guard = EnvGuard::NodeEnv("development".to_string());
+        let pattern = "liveSocket.enableDebug()\nliveSocket.enableLatencySim( 1000 )";
+        let result = wrap_in_env_guard_to_ast(APP, pattern, &guard).unwrap();
+        assert!(result.contains(
+            "liveSocket.connect();\nif (process.env.NODE_ENV === \"development\") {\n    \
+             liveSocket.enableDebug();\n    liveSocket.enableLatencySim(1000);\n}"
+        ));
+        assert_eq!(
+            wrap_in_env_guard_to_ast(&result, pattern, &guard).unwrap(),
+            result
+        );
+
+        // Another condition wraps the guarded statements again
+        let dev = EnvGuard::ImportMetaEnv("DEV".to_string());
+        let nested = wrap_in_env_guard_to_ast(&result, "liveSocket.enableDebug()", &dev).unwrap();
+        assert!(nested.contains("if (import.meta.env.DEV) {\n        liveSocket.enableDebug();"));
+
+        let condition = EnvGuard::Condition("window.debug".to_string());
+        let result = wrap_in_env_guard_to_ast(APP, "liveSocket.connect();", &condition).unwrap();
+        assert!(result.contains("if (window.debug) {\n    liveSocket.connect();\n}"));
+
+        assert!(wrap_in_env_guard_to_ast(APP, "liveSocket.disconnect()", &guard).is_err());
+        assert!(wrap_in_env_guard_to_ast(APP, "import a from \"a\"", &guard).is_err());
+        assert!(wrap_in_env_guard_to_ast(APP, "", &guard).is_err());
+        let invalid = EnvGuard::Condition("a ===".to_string());
+        assert!(wrap_in_env_guard_to_ast(APP, "liveSocket.connect()", &invalid).is_err());
+    }
+
+    #[test]
+    fn test_remove_env_guard() {
+        let code = r#"if (process.env.NODE_ENV !== "production") {
+  liveSocket.enableDebug();
+  liveSocket.enableLatencySim(1000);
+}
+if (window.debug) {
+  liveSocket.enableDebug();
+}
+function setup() {
+  if (import.meta.env.DEV) { liveSocket.enableDebug(); }
+}
+"#;
+        let result = remove_env_guard_from_ast(code, "liveSocket.enableDebug();").unwrap();
+        assert!(result.starts_with(
+            "liveSocket.enableDebug();\nliveSocket.enableLatencySim(1000);\nif (window.debug) {"
+        ));
+        assert!(result.contains("function setup() {\n    liveSocket.enableDebug();\n}"));
+        assert_eq!(
+            remove_env_guard_from_ast(&result, "liveSocket.enableDebug();").unwrap(),
+            result
+        );
+
+        assert_eq!(
+            remove_env_guard_from_ast(code, "liveSocket.connect()").unwrap(),
+            code
+        );
+        assert!(remove_env_guard_from_ast(code, "liveSocket.(").is_err());
+    }
+}
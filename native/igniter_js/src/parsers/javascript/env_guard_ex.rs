@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::env_guard::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn wrap_in_env_guard_nif(
+    env: Env,
+    file_content: String,
+    pattern: String,
+    guard: EnvGuard,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::wrap_in_env_guard_nif();
+        let result = wrap_in_env_guard_to_ast(&file_content, &pattern, &guard);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn remove_env_guard_nif(
+    env: Env,
+    file_content: String,
+    pattern: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_env_guard_nif();
+        let result = remove_env_guard_from_ast(&file_content, &pattern);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
@@ -0,0 +1,958 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # ESTree Code Generation
+//!
+//! The inverse of [`crate::parsers::javascript::ast_json`]: this module prints JavaScript
+//! source from an ESTree JSON document, so structural edits can be done on the JSON on the
+//! Elixir side and turned back into code.
+//!
+//! The printer supports the JavaScript (not TypeScript) subset of ESTree, inserts the
+//! parentheses required by operator precedence and finally runs the result through SWC,
+//! which validates it and gives it the same layout as the other code generating functions.
+//! Comments are not part of the ESTree nodes and are not printed.
+
+use crate::parsers::javascript::helpers::{code_gen_from_ast_module, parse};
+use serde_json::Value;
+
+const SEQUENCE: u8 = 1;
+const ASSIGNMENT: u8 = 2;
+const CONDITIONAL: u8 = 3;
+const UNARY: u8 = 16;
+const POSTFIX: u8 = 17;
+const CALL: u8 = 19;
+const PRIMARY: u8 = 20;
+
+type PrintResult = Result<String, String>;
+
+fn node_type(node: &Value) -> &str {
+    node["type"].as_str().unwrap_or_default()
+}
+
+fn field<'a>(node: &'a Value, key: &str) -> Result<&'a Value, String> {
+    match node.get(key) {
+        Some(value) if !value.is_null() => Ok(value),
+        _ => Err(format!(
+            "Missing `{}` in ESTree node of type `{}`",
+            key,
+            node_type(node)
+        )),
+    }
+}
+
+fn optional<'a>(node: &'a Value, key: &str) -> Option<&'a Value> {
+    node.get(key).filter(|value| !value.is_null())
+}
+
+fn flag(node: &Value, key: &str) -> bool {
+    node[key].as_bool().unwrap_or(false)
+}
+
+fn items<'a>(node: &'a Value, key: &str) -> &'a [Value] {
+    node[key].as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn unsupported(node: &Value) -> String {
+    format!("Unsupported ESTree node type: `{}`", node_type(node))
+}
+
+/// Whether `code` starts with the keyword `keyword`, and not just an identifier like it.
+fn starts_with_keyword(code: &str, keyword: &str) -> bool {
+    code.strip_prefix(keyword).is_some_and(|rest| {
+        !rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    })
+}
+
+fn binary_precedence(operator: &str) -> Option<u8> {
+    let precedence = match operator {
+        "??" => 4,
+        "||" => 4,
+        "&&" => 5,
+        "|" => 6,
+        "^" => 7,
+        "&" => 8,
+        "==" | "!=" | "===" | "!==" => 9,
+        "<" | ">" | "<=" | ">=" | "instanceof" | "in" => 10,
+        "<<" | ">>" | ">>>" => 11,
+        "+" | "-" => 12,
+        "*" | "/" | "%" => 13,
+        "**" => 14,
+        _ => return None,
+    };
+    Some(precedence)
+}
+
+fn precedence(node: &Value) -> u8 {
+    match node_type(node) {
+        "SequenceExpression" => SEQUENCE,
+        "AssignmentExpression" | "ArrowFunctionExpression" | "YieldExpression" => ASSIGNMENT,
+        "ConditionalExpression" => CONDITIONAL,
+        "BinaryExpression" | "LogicalExpression" => node["operator"]
+            .as_str()
+            .and_then(binary_precedence)
+            .unwrap_or(PRIMARY),
+        "UnaryExpression" | "AwaitExpression" => UNARY,
+        "UpdateExpression" if flag(node, "prefix") => UNARY,
+        "UpdateExpression" => POSTFIX,
+        "NewExpression"
+        | "CallExpression"
+        | "MemberExpression"
+        | "ChainExpression"
+        | "TaggedTemplateExpression"
+        | "ImportExpression" => CALL,
+        _ => PRIMARY,
+    }
+}
+
+/// Prints an expression, wrapped in parentheses when it binds looser than `min_precedence`.
+fn expr(node: &Value, min_precedence: u8) -> PrintResult {
+    let code = expr_code(node)?;
+
+    if precedence(node) < min_precedence {
+        Ok(format!("({})", code))
+    } else {
+        Ok(code)
+    }
+}
+
+fn literal(node: &Value) -> PrintResult {
+    if let Some(raw) = node["raw"].as_str() {
+        return Ok(raw.to_string());
+    }
+    if let Some(regex) = optional(node, "regex") {
+        return Ok(format!(
+            "/{}/{}",
+            regex["pattern"].as_str().unwrap_or_default(),
+            regex["flags"].as_str().unwrap_or_default()
+        ));
+    }
+    if let Some(bigint) = node["bigint"].as_str() {
+        return Ok(format!("{}n", bigint));
+    }
+
+    match &node["value"] {
+        Value::String(value) => serde_json::to_string(value).map_err(|e| e.to_string()),
+        value => Ok(value.to_string()),
+    }
+}
+
+fn args(nodes: &[Value]) -> PrintResult {
+    let args = nodes
+        .iter()
+        .map(|node| expr(node, ASSIGNMENT))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(args.join(", "))
+}
+
+fn property_key(node: &Value) -> PrintResult {
+    let key = field(node, "key")?;
+
+    if flag(node, "computed") {
+        Ok(format!("[{}]", expr(key, ASSIGNMENT)?))
+    } else {
+        expr_code(key)
+    }
+}
+
+/// Prints the `name(params) { body }` part of methods, getters and setters.
+fn method(key: String, kind: &str, is_static: bool, function: &Value) -> PrintResult {
+    let mut prefix = String::new();
+    if is_static {
+        prefix.push_str("static ");
+    }
+    if flag(function, "async") {
+        prefix.push_str("async ");
+    }
+    if flag(function, "generator") {
+        prefix.push('*');
+    }
+    if kind == "get" || kind == "set" {
+        prefix.push_str(kind);
+        prefix.push(' ');
+    }
+
+    Ok(format!(
+        "{}{}({}) {}",
+        prefix,
+        key,
+        params(function)?,
+        block(field(function, "body")?)?
+    ))
+}
+
+fn object_member(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "SpreadElement" | "RestElement" => Ok(format!(
+            "...{}",
+            expr(field(node, "argument")?, ASSIGNMENT)?
+        )),
+        "Property" => {
+            let key = property_key(node)?;
+            let value = field(node, "value")?;
+            let kind = node["kind"].as_str().unwrap_or("init");
+
+            if kind != "init" || flag(node, "method") {
+                method(key, kind, false, value)
+            } else if flag(node, "shorthand") && node_type(value) == "AssignmentPattern" {
+                pattern(value)
+            } else if flag(node, "shorthand") {
+                Ok(key)
+            } else {
+                Ok(format!("{}: {}", key, expr(value, ASSIGNMENT)?))
+            }
+        }
+        _ => Err(unsupported(node)),
+    }
+}
+
+fn template(node: &Value) -> PrintResult {
+    let quasis = items(node, "quasis");
+    let expressions = items(node, "expressions");
+    let mut code = String::from("`");
+
+    for (index, quasi) in quasis.iter().enumerate() {
+        code.push_str(quasi["value"]["raw"].as_str().unwrap_or_default());
+        if let Some(expression) = expressions.get(index) {
+            code.push_str(&format!("${{{}}}", expr(expression, SEQUENCE)?));
+        }
+    }
+
+    code.push('`');
+    Ok(code)
+}
+
+fn function(node: &Value) -> PrintResult {
+    let mut code = String::new();
+    if flag(node, "async") {
+        code.push_str("async ");
+    }
+    code.push_str("function");
+    if flag(node, "generator") {
+        code.push('*');
+    }
+    if let Some(id) = optional(node, "id") {
+        code.push_str(&format!(" {}", expr_code(id)?));
+    }
+
+    Ok(format!(
+        "{}({}) {}",
+        code,
+        params(node)?,
+        block(field(node, "body")?)?
+    ))
+}
+
+fn params(function: &Value) -> PrintResult {
+    let params = items(function, "params")
+        .iter()
+        .map(pattern)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(params.join(", "))
+}
+
+fn class(node: &Value) -> PrintResult {
+    let mut code = String::from("class");
+    if let Some(id) = optional(node, "id") {
+        code.push_str(&format!(" {}", expr_code(id)?));
+    }
+    if let Some(super_class) = optional(node, "superClass") {
+        code.push_str(&format!(" extends {}", expr(super_class, CALL)?));
+    }
+
+    let mut members = vec![];
+    for member in items(field(node, "body")?, "body") {
+        let is_static = flag(member, "static");
+        let member = match node_type(member) {
+            "MethodDefinition" => {
+                let kind = member["kind"].as_str().unwrap_or("method");
+                method(
+                    property_key(member)?,
+                    kind,
+                    is_static,
+                    field(member, "value")?,
+                )?
+            }
+            "PropertyDefinition" => {
+                let prefix = if is_static { "static " } else { "" };
+                match optional(member, "value") {
+                    Some(value) => format!(
+                        "{}{} = {};",
+                        prefix,
+                        property_key(member)?,
+                        expr(value, ASSIGNMENT)?
+                    ),
+                    None => format!("{}{};", prefix, property_key(member)?),
+                }
+            }
+            "StaticBlock" => format!("static {}", statements_block(items(member, "body"))?),
+            _ => return Err(unsupported(member)),
+        };
+        members.push(member);
+    }
+
+    Ok(format!("{} {{\n{}\n}}", code, members.join("\n")))
+}
+
+fn pattern(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "ObjectPattern" => {
+            let properties = items(node, "properties")
+                .iter()
+                .map(|property| match node_type(property) {
+                    "Property" if flag(property, "shorthand") => pattern(field(property, "value")?),
+                    "Property" => Ok(format!(
+                        "{}: {}",
+                        property_key(property)?,
+                        pattern(field(property, "value")?)?
+                    )),
+                    "RestElement" => Ok(format!("...{}", pattern(field(property, "argument")?)?)),
+                    _ => Err(unsupported(property)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("{{ {} }}", properties.join(", ")))
+        }
+        "ArrayPattern" => {
+            let elements = items(node, "elements")
+                .iter()
+                .map(|element| match element {
+                    Value::Null => Ok(String::new()),
+                    element => pattern(element),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("[{}]", elements.join(", ")))
+        }
+        "AssignmentPattern" => Ok(format!(
+            "{} = {}",
+            pattern(field(node, "left")?)?,
+            expr(field(node, "right")?, ASSIGNMENT)?
+        )),
+        "RestElement" => Ok(format!("...{}", pattern(field(node, "argument")?)?)),
+        _ => expr(node, CALL),
+    }
+}
+
+fn expr_code(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "Identifier" => Ok(field(node, "name")?
+            .as_str()
+            .unwrap_or_default()
+            .to_string()),
+        "PrivateIdentifier" => Ok(format!(
+            "#{}",
+            field(node, "name")?.as_str().unwrap_or_default()
+        )),
+        "Literal" => literal(node),
+        "ThisExpression" => Ok("this".to_string()),
+        "Super" => Ok("super".to_string()),
+        "TemplateLiteral" => template(node),
+        "TaggedTemplateExpression" => Ok(format!(
+            "{}{}",
+            expr(field(node, "tag")?, CALL)?,
+            template(field(node, "quasi")?)?
+        )),
+        "ArrayExpression" => {
+            let elements = items(node, "elements")
+                .iter()
+                .map(|element| match element {
+                    Value::Null => Ok(String::new()),
+                    element if node_type(element) == "SpreadElement" => object_member(element),
+                    element => expr(element, ASSIGNMENT),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("[{}]", elements.join(", ")))
+        }
+        "ObjectExpression" => {
+            let properties = items(node, "properties")
+                .iter()
+                .map(object_member)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("{{ {} }}", properties.join(", ")))
+        }
+        "SpreadElement" => object_member(node),
+        "FunctionExpression" => function(node),
+        "ClassExpression" => class(node),
+        "ArrowFunctionExpression" => {
+            let prefix = if flag(node, "async") { "async " } else { "" };
+            let body = field(node, "body")?;
+            let body = if node_type(body) == "BlockStatement" {
+                block(body)?
+            } else if node_type(body) == "ObjectExpression" {
+                format!("({})", expr(body, ASSIGNMENT)?)
+            } else {
+                expr(body, ASSIGNMENT)?
+            };
+            Ok(format!("{}({}) => {}", prefix, params(node)?, body))
+        }
+        "MemberExpression" => {
+            let object = expr(field(node, "object")?, CALL)?;
+            let property = field(node, "property")?;
+            let optional = if flag(node, "optional") { "?." } else { "" };
+
+            if flag(node, "computed") {
+                Ok(format!(
+                    "{}{}[{}]",
+                    object,
+                    optional,
+                    expr(property, SEQUENCE)?
+                ))
+            } else if optional.is_empty() {
+                Ok(format!("{}.{}", object, expr_code(property)?))
+            } else {
+                Ok(format!("{}?.{}", object, expr_code(property)?))
+            }
+        }
+        "CallExpression" => {
+            let optional = if flag(node, "optional") { "?." } else { "" };
+            Ok(format!(
+                "{}{}({})",
+                expr(field(node, "callee")?, CALL)?,
+                optional,
+                args(items(node, "arguments"))?
+            ))
+        }
+        "ChainExpression" => expr_code(field(node, "expression")?),
+        "ParenthesizedExpression" => {
+            Ok(format!("({})", expr(field(node, "expression")?, SEQUENCE)?))
+        }
+        "NewExpression" => {
+            let callee = field(node, "callee")?;
+            let callee = match node_type(callee) {
+                "CallExpression" | "ChainExpression" => format!("({})", expr_code(callee)?),
+                _ => expr(callee, CALL)?,
+            };
+            Ok(format!(
+                "new {}({})",
+                callee,
+                args(items(node, "arguments"))?
+            ))
+        }
+        "ImportExpression" => {
+            let mut arguments = vec![field(node, "source")?.clone()];
+            if let Some(options) = optional(node, "options") {
+                arguments.push(options.clone());
+            }
+            Ok(format!("import({})", args(&arguments)?))
+        }
+        "MetaProperty" => Ok(format!(
+            "{}.{}",
+            expr_code(field(node, "meta")?)?,
+            expr_code(field(node, "property")?)?
+        )),
+        "UnaryExpression" => {
+            let operator = field(node, "operator")?.as_str().unwrap_or_default();
+            let argument = expr(field(node, "argument")?, UNARY)?;
+            if operator.chars().all(char::is_alphabetic) {
+                Ok(format!("{} {}", operator, argument))
+            } else {
+                // Keeps `- -x` from turning into `--x`
+                let separator = if argument.starts_with(operator) {
+                    " "
+                } else {
+                    ""
+                };
+                Ok(format!("{}{}{}", operator, separator, argument))
+            }
+        }
+        "UpdateExpression" => {
+            let operator = field(node, "operator")?.as_str().unwrap_or_default();
+            let argument = expr(field(node, "argument")?, POSTFIX)?;
+            if flag(node, "prefix") {
+                Ok(format!("{}{}", operator, argument))
+            } else {
+                Ok(format!("{}{}", argument, operator))
+            }
+        }
+        "AwaitExpression" => Ok(format!("await {}", expr(field(node, "argument")?, UNARY)?)),
+        "YieldExpression" => {
+            let keyword = if flag(node, "delegate") {
+                "yield*"
+            } else {
+                "yield"
+            };
+            match optional(node, "argument") {
+                Some(argument) => Ok(format!("{} {}", keyword, expr(argument, ASSIGNMENT)?)),
+                None => Ok(keyword.to_string()),
+            }
+        }
+        "BinaryExpression" | "LogicalExpression" => {
+            let operator = field(node, "operator")?.as_str().unwrap_or_default();
+            let precedence = binary_precedence(operator)
+                .ok_or_else(|| format!("Unsupported binary operator: `{}`", operator))?;
+            let (left_precedence, right_precedence) = if operator == "**" {
+                (precedence + 1, precedence)
+            } else {
+                (precedence, precedence + 1)
+            };
+
+            // `??` can not be mixed with `||` or `&&` without parentheses
+            let operand = |operand: &Value, min_precedence: u8| -> PrintResult {
+                let mixes_nullish = node_type(operand) == "LogicalExpression"
+                    && (operator == "??") != (operand["operator"] == "??");
+                if mixes_nullish {
+                    Ok(format!("({})", expr_code(operand)?))
+                } else {
+                    expr(operand, min_precedence)
+                }
+            };
+
+            Ok(format!(
+                "{} {} {}",
+                operand(field(node, "left")?, left_precedence)?,
+                operator,
+                operand(field(node, "right")?, right_precedence)?
+            ))
+        }
+        "AssignmentExpression" => Ok(format!(
+            "{} {} {}",
+            pattern(field(node, "left")?)?,
+            field(node, "operator")?.as_str().unwrap_or("="),
+            expr(field(node, "right")?, ASSIGNMENT)?
+        )),
+        "ConditionalExpression" => Ok(format!(
+            "{} ? {} : {}",
+            expr(field(node, "test")?, CONDITIONAL + 1)?,
+            expr(field(node, "consequent")?, ASSIGNMENT)?,
+            expr(field(node, "alternate")?, ASSIGNMENT)?
+        )),
+        "SequenceExpression" => {
+            let expressions = items(node, "expressions")
+                .iter()
+                .map(|expression| expr(expression, ASSIGNMENT))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(expressions.join(", "))
+        }
+        _ => Err(unsupported(node)),
+    }
+}
+
+fn statements_block(nodes: &[Value]) -> PrintResult {
+    let statements = nodes.iter().map(stmt).collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("{{\n{}\n}}", statements.join("\n")))
+}
+
+fn block(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "BlockStatement" => statements_block(items(node, "body")),
+        _ => Err(unsupported(node)),
+    }
+}
+
+fn variable_declaration(node: &Value) -> PrintResult {
+    let declarations = items(node, "declarations")
+        .iter()
+        .map(|declarator| {
+            let id = pattern(field(declarator, "id")?)?;
+            match optional(declarator, "init") {
+                Some(init) => Ok(format!("{} = {}", id, expr(init, ASSIGNMENT)?)),
+                None => Ok(id),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(format!(
+        "{} {}",
+        node["kind"].as_str().unwrap_or("let"),
+        declarations.join(", ")
+    ))
+}
+
+/// Prints the left side of `for (... in ...)` and `for (... of ...)`, or the init of `for`.
+fn for_head(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "VariableDeclaration" => variable_declaration(node),
+        _ => pattern(node),
+    }
+}
+
+fn module_name(node: &Value) -> PrintResult {
+    expr_code(node)
+}
+
+fn import_attributes(node: &Value) -> PrintResult {
+    let attributes = items(node, "attributes")
+        .iter()
+        .map(|attribute| {
+            Ok(format!(
+                "{}: {}",
+                expr_code(field(attribute, "key")?)?,
+                literal(field(attribute, "value")?)?
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if attributes.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!(" with {{ {} }}", attributes.join(", ")))
+    }
+}
+
+fn import_declaration(node: &Value) -> PrintResult {
+    let source = literal(field(node, "source")?)?;
+    let mut default_and_namespace = vec![];
+    let mut named = vec![];
+
+    for specifier in items(node, "specifiers") {
+        let local = expr_code(field(specifier, "local")?)?;
+        match node_type(specifier) {
+            "ImportDefaultSpecifier" => default_and_namespace.push(local),
+            "ImportNamespaceSpecifier" => default_and_namespace.push(format!("* as {}", local)),
+            "ImportSpecifier" => {
+                let imported = module_name(field(specifier, "imported")?)?;
+                if imported == local {
+                    named.push(local);
+                } else {
+                    named.push(format!("{} as {}", imported, local));
+                }
+            }
+            _ => return Err(unsupported(specifier)),
+        }
+    }
+
+    if !named.is_empty() {
+        default_and_namespace.push(format!("{{ {} }}", named.join(", ")));
+    }
+
+    if default_and_namespace.is_empty() {
+        Ok(format!("import {}{};", source, import_attributes(node)?))
+    } else {
+        Ok(format!(
+            "import {} from {}{};",
+            default_and_namespace.join(", "),
+            source,
+            import_attributes(node)?
+        ))
+    }
+}
+
+fn export_named_declaration(node: &Value) -> PrintResult {
+    if let Some(declaration) = optional(node, "declaration") {
+        return Ok(format!("export {}", stmt(declaration)?));
+    }
+
+    let specifiers = items(node, "specifiers")
+        .iter()
+        .map(|specifier| {
+            let local = module_name(field(specifier, "local")?)?;
+            let exported = module_name(field(specifier, "exported")?)?;
+            if local == exported {
+                Ok(local)
+            } else {
+                Ok(format!("{} as {}", local, exported))
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    match optional(node, "source") {
+        Some(source) => Ok(format!(
+            "export {{ {} }} from {}{};",
+            specifiers.join(", "),
+            literal(source)?,
+            import_attributes(node)?
+        )),
+        None => Ok(format!("export {{ {} }};", specifiers.join(", "))),
+    }
+}
+
+fn stmt(node: &Value) -> PrintResult {
+    match node_type(node) {
+        "EmptyStatement" => Ok(";".to_string()),
+        "BlockStatement" => block(node),
+        "ExpressionStatement" => {
+            let code = expr(field(node, "expression")?, SEQUENCE)?;
+            let needs_parens = code.starts_with('{')
+                || starts_with_keyword(&code, "function")
+                || starts_with_keyword(&code, "class")
+                || code.starts_with("async function")
+                || code.starts_with("let [");
+            if needs_parens {
+                Ok(format!("({});", code))
+            } else {
+                Ok(format!("{};", code))
+            }
+        }
+        "VariableDeclaration" => Ok(format!("{};", variable_declaration(node)?)),
+        "FunctionDeclaration" => function(node),
+        "ClassDeclaration" => class(node),
+        "ReturnStatement" => match optional(node, "argument") {
+            Some(argument) => Ok(format!("return {};", expr(argument, SEQUENCE)?)),
+            None => Ok("return;".to_string()),
+        },
+        "ThrowStatement" => Ok(format!(
+            "throw {};",
+            expr(field(node, "argument")?, SEQUENCE)?
+        )),
+        "BreakStatement" | "ContinueStatement" => {
+            let keyword = if node_type(node) == "BreakStatement" {
+                "break"
+            } else {
+                "continue"
+            };
+            match optional(node, "label") {
+                Some(label) => Ok(format!("{} {};", keyword, expr_code(label)?)),
+                None => Ok(format!("{};", keyword)),
+            }
+        }
+        "DebuggerStatement" => Ok("debugger;".to_string()),
+        "LabeledStatement" => Ok(format!(
+            "{}: {}",
+            expr_code(field(node, "label")?)?,
+            stmt(field(node, "body")?)?
+        )),
+        "IfStatement" => {
+            let mut code = format!(
+                "if ({}) {}",
+                expr(field(node, "test")?, SEQUENCE)?,
+                stmt(field(node, "consequent")?)?
+            );
+            if let Some(alternate) = optional(node, "alternate") {
+                code.push_str(&format!(" else {}", stmt(alternate)?));
+            }
+            Ok(code)
+        }
+        "WhileStatement" => Ok(format!(
+            "while ({}) {}",
+            expr(field(node, "test")?, SEQUENCE)?,
+            stmt(field(node, "body")?)?
+        )),
+        "DoWhileStatement" => Ok(format!(
+            "do {} while ({});",
+            stmt(field(node, "body")?)?,
+            expr(field(node, "test")?, SEQUENCE)?
+        )),
+        "ForStatement" => {
+            let part = |key: &str| -> PrintResult {
+                match optional(node, key) {
+                    Some(part) if key == "init" => for_head(part),
+                    Some(part) => expr(part, SEQUENCE),
+                    None => Ok(String::new()),
+                }
+            };
+            Ok(format!(
+                "for ({}; {}; {}) {}",
+                part("init")?,
+                part("test")?,
+                part("update")?,
+                stmt(field(node, "body")?)?
+            ))
+        }
+        "ForInStatement" | "ForOfStatement" => {
+            let keyword = if node_type(node) == "ForInStatement" {
+                "in"
+            } else {
+                "of"
+            };
+            let await_keyword = if flag(node, "await") { " await" } else { "" };
+            Ok(format!(
+                "for{} ({} {} {}) {}",
+                await_keyword,
+                for_head(field(node, "left")?)?,
+                keyword,
+                expr(field(node, "right")?, ASSIGNMENT)?,
+                stmt(field(node, "body")?)?
+            ))
+        }
+        "SwitchStatement" => {
+            let cases = items(node, "cases")
+                .iter()
+                .map(|case| {
+                    let head = match optional(case, "test") {
+                        Some(test) => format!("case {}:", expr(test, SEQUENCE)?),
+                        None => "default:".to_string(),
+                    };
+                    let body = items(case, "consequent")
+                        .iter()
+                        .map(stmt)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(format!("{}\n{}", head, body.join("\n")))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(format!(
+                "switch ({}) {{\n{}\n}}",
+                expr(field(node, "discriminant")?, SEQUENCE)?,
+                cases.join("\n")
+            ))
+        }
+        "TryStatement" => {
+            let mut code = format!("try {}", block(field(node, "block")?)?);
+            if let Some(handler) = optional(node, "handler") {
+                match optional(handler, "param") {
+                    Some(param) => code.push_str(&format!(" catch ({}) ", pattern(param)?)),
+                    None => code.push_str(" catch "),
+                }
+                code.push_str(&block(field(handler, "body")?)?);
+            }
+            if let Some(finalizer) = optional(node, "finalizer") {
+                code.push_str(&format!(" finally {}", block(finalizer)?));
+            }
+            Ok(code)
+        }
+        "ImportDeclaration" => import_declaration(node),
+        "ExportNamedDeclaration" => export_named_declaration(node),
+        "ExportDefaultDeclaration" => {
+            let declaration = field(node, "declaration")?;
+            match node_type(declaration) {
+                "FunctionDeclaration" | "ClassDeclaration" => {
+                    Ok(format!("export default {}", stmt(declaration)?))
+                }
+                _ => Ok(format!(
+                    "export default {};",
+                    expr(declaration, ASSIGNMENT)?
+                )),
+            }
+        }
+        "ExportAllDeclaration" => {
+            let exported = match optional(node, "exported") {
+                Some(exported) => format!(" as {}", module_name(exported)?),
+                None => String::new(),
+            };
+            Ok(format!(
+                "export *{} from {}{};",
+                exported,
+                literal(field(node, "source")?)?,
+                import_attributes(node)?
+            ))
+        }
+        // Lets a bare expression node be printed as a statement
+        _ => stmt(&serde_json::json!({ "type": "ExpressionStatement", "expression": node })),
+    }
+}
+
+/// Finds the statements to print in the different shapes an ESTree document can have.
+fn statements_of(document: &Value) -> Vec<&Value> {
+    match document {
+        Value::Array(nodes) => nodes.iter().collect(),
+        Value::Object(map) if map.contains_key("program") => statements_of(&document["program"]),
+        _ if node_type(document) == "Program" => items(document, "body").iter().collect(),
+        _ => vec![document],
+    }
+}
+
+/// Generates JavaScript source code from an ESTree JSON document.
+///
+/// The document can be the full output of
+/// [`crate::parsers::javascript::ast_json::convert_ast_to_estree`], a `Program` node, a
+/// single statement or expression node, or a list of statements, such as the fragments of
+/// [`crate::parsers::javascript::ast_json::convert_ast_to_estree_statements`]. Positions are
+/// ignored, so nodes can be built or moved around freely.
+///
+/// # Arguments
+/// * `estree_json` - The ESTree document as JSON.
+///
+/// # Returns
+/// * `Ok(String)` - The generated JavaScript code.
+/// * `Err(String)` - If the JSON is invalid, contains an unsupported node, or does not
+///   describe valid JavaScript.
+///
+/// # Example
+/// ```rust
+/// let estree = r#"{"type": "ExpressionStatement", "expression": {
+///   "type": "CallExpression",
+///   "callee": {"type": "Identifier", "name": "init"},
+///   "arguments": [{"type": "Literal", "value": 1, "raw": "1"}]
+/// }}"#;
+/// assert_eq!(estree_to_source(estree).unwrap(), "init(1);\n");
+/// ```
+pub fn estree_to_source(estree_json: &str) -> Result<String, String> {
+    let document: Value =
+        serde_json::from_str(estree_json).map_err(|e| format!("Invalid ESTree JSON: {}", e))?;
+
+    let code = statements_of(&document)
+        .into_iter()
+        .map(stmt)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n");
+
+    let (mut module, comments, cm) = parse(&code)
+        .map_err(|_| "The ESTree document does not describe valid JavaScript".to_string())?;
+    Ok(code_gen_from_ast_module(&mut module, comments, cm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::javascript::ast_json::{
+        convert_ast_to_estree, convert_ast_to_estree_statements, EstreeOptions,
+    };
+
+    fn normalize(code: &str) -> String {
+        let (mut module, comments, cm) = parse(code).unwrap();
+        code_gen_from_ast_module(&mut module, comments, cm)
+    }
+
+    #[test]
+    fn test_estree_to_source_round_trip() {
+        let code = r#"
+import Default, * as all from "all";
+import { a, b as c } from "module";
+import "./side-effect.js";
+export { a, c as d };
+export * as ns from "ns";
+export const value = (1 + 2) * 3 ** 2 ** -1;
+export default class Hook extends Base {
+  static count = 0;
+  #secret;
+  constructor(...args) { super(...args); }
+  get name() { return `hook-${this.id}`; }
+  async *items() { yield* this.list; }
+}
+let { x, y: [first, , third = 1], ...rest } = obj ?? {};
+const f = async (a = 1) => ({ a, [a]: a?.b?.[0] ?? (a || b) });
+for (const key in obj) if (!key) continue; else break;
+for await (const item of items) console.log(item);
+for (let i = 0; i < 10; i++) {}
+while (true) { do x--; while (x > 0); }
+switch (x) { case 1: f(); break; default: g(); }
+try { risky(); } catch ({ message }) { throw new Error(message); } finally { done(); }
+label: for (;;) { continue label; }
+(function () {})();
+new (factory())();
+x = a ? b : c, y = typeof z === "undefined", z = /ab+c/gi;
+"#;
+        let estree = convert_ast_to_estree(code).unwrap();
+        assert_eq!(estree_to_source(&estree).unwrap(), normalize(code));
+    }
+
+    #[test]
+    fn test_estree_to_source_from_fragments() {
+        let code = "import { LiveSocket } from \"phoenix_live_view\";\nlet hooks = {};\n";
+        let fragments = convert_ast_to_estree_statements(code, &EstreeOptions::default()).unwrap();
+        let mut statements: Vec<Value> = fragments
+            .iter()
+            .map(|fragment| serde_json::from_str(fragment).unwrap())
+            .collect();
+        statements.reverse();
+
+        let result = estree_to_source(&serde_json::to_string(&statements).unwrap()).unwrap();
+        assert_eq!(
+            result,
+            "let hooks = {};\nimport { LiveSocket } from \"phoenix_live_view\";\n"
+        );
+    }
+
+    #[test]
+    fn test_estree_to_source_adds_required_parentheses() {
+        let estree = r#"{"type": "ExpressionStatement", "expression": {
+          "type": "BinaryExpression", "operator": "*",
+          "left": {"type": "BinaryExpression", "operator": "+",
+            "left": {"type": "Identifier", "name": "a"},
+            "right": {"type": "Identifier", "name": "b"}},
+          "right": {"type": "ArrowFunctionExpression", "params": [],
+            "body": {"type": "ObjectExpression", "properties": []}}
+        }}"#;
+
+        assert_eq!(estree_to_source(estree).unwrap(), "(a + b) * (()=>({}));\n");
+    }
+
+    #[test]
+    fn test_estree_to_source_errors() {
+        assert!(estree_to_source("{").is_err());
+        assert!(estree_to_source(r#"{"type": "TSEnumDeclaration"}"#).is_err());
+        assert!(estree_to_source(r#"{"type": "ExpressionStatement"}"#).is_err());
+    }
+}
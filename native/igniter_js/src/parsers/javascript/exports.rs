@@ -0,0 +1,362 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Adding and removing exports, e.g. appending `export { default as Chart } from "./chart"`
+//! to the `hooks/index.js` aggregator an installer generated a hook file for.
+
+use crate::parsers::javascript::helpers::*;
+use std::collections::HashMap;
+use swc_common::EqIgnoreSpan;
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitMutWith;
+
+/// The name of the default export.
+const DEFAULT: &str = "default";
+
+/// What an exported name refers to: the source it is re-exported from, if any, and the
+/// exported binding, `"*"` for a namespace.
+type ExportBinding = (Option<String>, String);
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string(),
+    }
+}
+
+/// The exported name and the binding of a specifier of `export { ... }`.
+fn specifier_binding(
+    specifier: &ExportSpecifier,
+    source: &Option<String>,
+) -> (String, ExportBinding) {
+    match specifier {
+        ExportSpecifier::Named(named) => {
+            let orig = export_name(&named.orig);
+            let exported = named.exported.as_ref().map_or(orig.clone(), export_name);
+            (exported, (source.clone(), orig))
+        }
+        ExportSpecifier::Namespace(namespace) => {
+            (export_name(&namespace.name), (source.clone(), "*".into()))
+        }
+        ExportSpecifier::Default(default) => (
+            default.exported.sym.to_string(),
+            (source.clone(), DEFAULT.into()),
+        ),
+    }
+}
+
+fn named_export_source(export: &NamedExport) -> Option<String> {
+    export.src.as_ref().map(|src| src.value.to_string())
+}
+
+/// The names a module exports with what they refer to. Declarations and default exports
+/// refer to themselves.
+fn exported_bindings(module: &Module) -> HashMap<String, ExportBinding> {
+    let mut bindings = HashMap::new();
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+
+        match decl {
+            ModuleDecl::ExportNamed(export) => {
+                let source = named_export_source(export);
+                for specifier in &export.specifiers {
+                    let (exported, binding) = specifier_binding(specifier, &source);
+                    bindings.insert(exported, binding);
+                }
+            }
+            ModuleDecl::ExportDecl(export) => {
+                let names = match &export.decl {
+                    Decl::Fn(function) => vec![function.ident.sym.to_string()],
+                    Decl::Class(class) => vec![class.ident.sym.to_string()],
+                    Decl::Var(var) => var
+                        .decls
+                        .iter()
+                        .filter_map(|declarator| declarator.name.as_ident())
+                        .map(|ident| ident.sym.to_string())
+                        .collect(),
+                    _ => vec![],
+                };
+                for name in names {
+                    bindings.insert(name.clone(), (None, name));
+                }
+            }
+            ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+                bindings.insert(DEFAULT.into(), (None, DEFAULT.into()));
+            }
+            _ => {}
+        }
+    }
+
+    bindings
+}
+
+fn is_default_export(item: &ModuleItem) -> bool {
+    matches!(
+        item,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_))
+    )
+}
+
+fn is_export_all(item: &ModuleItem, source: &Str) -> bool {
+    matches!(item, ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export))
+        if export.src.value == source.value)
+}
+
+/// Parses the exports to add or remove, which may only be `export { ... }`,
+/// `export { ... } from "..."`, `export * from "..."` and `export default ...`.
+fn parse_exports(export_lines: &str) -> Result<Vec<ModuleDecl>, String> {
+    let (mut exports, _comments, _cm) = parse(export_lines)?;
+    exports.visit_mut_with(&mut DropSpan);
+
+    exports
+        .body
+        .into_iter()
+        .map(|item| match item {
+            ModuleItem::ModuleDecl(
+                decl @ (ModuleDecl::ExportNamed(_)
+                | ModuleDecl::ExportAll(_)
+                | ModuleDecl::ExportDefaultExpr(_)
+                | ModuleDecl::ExportDefaultDecl(_)),
+            ) => Ok(decl),
+            _ => Err(format!(
+                "Only `export {{ ... }}`, `export * from` and `export default` are supported: {}",
+                export_lines.trim()
+            )),
+        })
+        .collect()
+}
+
+/// Adds exports to the end of a module, skipping the ones it already has.
+///
+/// A specifier of `export { ... }` is skipped when its name is already exported with the
+/// same binding, so the rest of the specifiers are still added, `export * from "..."` when
+/// the source is already re-exported this way and `export default ...` when the module has
+/// the same default export.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `export_lines` - The exports to add, e.g. `export { default as Chart } from "./chart";`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If a source can not be parsed, `export_lines` has anything but exports,
+///   or a name is already exported with another binding, including a different default
+///   export, since a module can not export a name twice.
+///
+/// # Example
+/// ```rust
+/// let code = "export { default as Copy } from \"./copy\";";
+/// let result = add_export_to_ast(code, "export { default as Chart } from \"./chart\";");
+/// assert!(result.unwrap().ends_with("export { default as Chart } from \"./chart\";\n"));
+/// ```
+pub fn add_export_to_ast(file_content: &str, export_lines: &str) -> Result<String, String> {
+    let exports = parse_exports(export_lines)?;
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut bindings = exported_bindings(&module);
+
+    for export in exports {
+        match export {
+            ModuleDecl::ExportNamed(mut export) => {
+                let source = named_export_source(&export);
+                let mut specifiers = vec![];
+
+                for specifier in export.specifiers {
+                    let (exported, binding) = specifier_binding(&specifier, &source);
+                    match bindings.get(&exported) {
+                        Some(existing) if *existing == binding => {}
+                        Some(_) => return Err(format!("`{}` is already exported", exported)),
+                        None => {
+                            bindings.insert(exported, binding);
+                            specifiers.push(specifier);
+                        }
+                    }
+                }
+
+                if !specifiers.is_empty() {
+                    export.specifiers = specifiers;
+                    module
+                        .body
+                        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)));
+                }
+            }
+            ModuleDecl::ExportAll(export) => {
+                if !module
+                    .body
+                    .iter()
+                    .any(|item| is_export_all(item, &export.src))
+                {
+                    module
+                        .body
+                        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)));
+                }
+            }
+            default => {
+                let default = ModuleItem::ModuleDecl(default);
+                match module.body.iter().find(|item| is_default_export(item)) {
+                    Some(existing) if existing.eq_ignore_span(&default) => {}
+                    Some(_) => return Err("The module already has a default export".into()),
+                    None if bindings.contains_key(DEFAULT) => {
+                        return Err(format!("`{}` is already exported", DEFAULT))
+                    }
+                    None => {
+                        bindings.insert(DEFAULT.into(), (None, DEFAULT.into()));
+                        module.body.push(default);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// Removes exports from a module, the counterpart of [`add_export_to_ast`].
+///
+/// A specifier of `export { ... }` removes the specifiers exporting its name, from the same
+/// source, dropping the declarations left without specifiers, `export * from "..."` removes
+/// the re-exports of the source and `export default ...` removes the default export, whatever
+/// it is. Exported declarations, e.g. `export const hooks = {}`, are kept.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `export_lines` - The exports to remove, e.g. `export { Chart } from "./chart";`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If a source can not be parsed or `export_lines` has anything but exports.
+pub fn remove_export_from_ast(file_content: &str, export_lines: &str) -> Result<String, String> {
+    let exports = parse_exports(export_lines)?;
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+
+    for export in exports {
+        match export {
+            ModuleDecl::ExportNamed(export) => {
+                let source = named_export_source(&export);
+                let removed: Vec<String> = export
+                    .specifiers
+                    .iter()
+                    .map(|specifier| specifier_binding(specifier, &source).0)
+                    .collect();
+
+                module.body.retain_mut(|item| {
+                    let ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(existing)) = item else {
+                        return true;
+                    };
+                    let existing_source = named_export_source(existing);
+                    if existing_source != source || existing.specifiers.is_empty() {
+                        return true;
+                    }
+
+                    existing.specifiers.retain(|specifier| {
+                        !removed.contains(&specifier_binding(specifier, &existing_source).0)
+                    });
+                    !existing.specifiers.is_empty()
+                });
+            }
+            ModuleDecl::ExportAll(export) => {
+                module.body.retain(|item| !is_export_all(item, &export.src))
+            }
+            _ => module.body.retain(|item| !is_default_export(item)),
+        }
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_export_to_ast() {
+        let code = r#"import Copy from "./copy";
+export { Copy };
+export * from "./charts";
+"#;
+
+        let result = add_export_to_ast(
+            code,
+            r#"export { Copy, Copy as Clipboard };
+export * from "./charts";
+export { default as Sortable } from "./sortable";
+export default { Copy };"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            r#"import Copy from "./copy";
+export { Copy };
+export * from "./charts";
+export { Copy as Clipboard };
+export { default as Sortable } from "./sortable";
+export default {
+    Copy
+};
+"#
+        );
+
+        // Adding the same exports again changes nothing
+        let again = add_export_to_ast(
+            &result,
+            "export { default as Sortable } from \"./sortable\";\nexport default { Copy };",
+        )
+        .unwrap();
+        assert_eq!(again, result);
+    }
+
+    #[test]
+    fn test_add_export_to_ast_errors() {
+        let code = "const a = 1;\nexport { a as b };\nexport default a;";
+
+        assert!(add_export_to_ast(code, "export { c as b };").is_err());
+        assert!(add_export_to_ast(code, "export { b } from \"./b\";").is_err());
+        assert!(add_export_to_ast(code, "export default 1;").is_err());
+        assert!(add_export_to_ast(code, "const c = 1;").is_err());
+        assert!(add_export_to_ast(code, "export const c = 1;").is_err());
+        assert!(add_export_to_ast(code, "export { a as c };").is_ok());
+    }
+
+    #[test]
+    fn test_remove_export_from_ast() {
+        let code = r#"import Copy from "./copy";
+export const hooks = {};
+export { Copy, Copy as Clipboard };
+export { default as Sortable } from "./sortable";
+export * from "./charts";
+export default hooks;
+"#;
+
+        let result = remove_export_from_ast(
+            code,
+            r#"export { Clipboard };
+export { Sortable } from "./sortable";
+export * from "./charts";
+export { hooks };
+export default 1;"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "import Copy from \"./copy\";\nexport const hooks = {};\nexport { Copy };\n"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::exports::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn add_export_to_ast_nif(
+    env: Env,
+    file_content: String,
+    export_lines: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = add_export_to_ast(&file_content, &export_lines);
+
+        encode_edit(env, atoms::add_export_to_ast_nif(), &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn remove_export_from_ast_nif(
+    env: Env,
+    file_content: String,
+    export_lines: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = remove_export_from_ast(&file_content, &export_lines);
+
+        encode_edit(
+            env,
+            atoms::remove_export_from_ast_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
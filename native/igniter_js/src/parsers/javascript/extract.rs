@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Extraction of the original source of a top-level declaration, so an installer moving
+//! code between files, e.g. splitting the hooks out of an `app.js`, keeps it as written
+//! instead of re-printing it.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::{attached_comments_start, parse, SpanOffsets};
+use crate::parsers::javascript::unused::pat_idents;
+use swc_common::{BytePos, Span, Spanned};
+use swc_ecma_ast::*;
+
+/// The source of a declaration, see [`extract_code_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The declaration as written, with its leading comments.
+    pub code: String,
+    /// The location of the code in the source.
+    pub location: SourceLocation,
+}
+
+fn declares(decl: &Decl, name: &str) -> bool {
+    match decl {
+        Decl::Fn(function) => function.ident.sym == name,
+        Decl::Class(class) => class.ident.sym == name,
+        Decl::Var(var) => var.decls.iter().any(|declarator| {
+            let mut idents = vec![];
+            pat_idents(&declarator.name, &mut idents);
+            idents.iter().any(|ident| ident.sym == name)
+        }),
+        _ => false,
+    }
+}
+
+/// Whether a module item is the top-level declaration of `name`, exported or not.
+pub fn is_declaration_of(item: &ModuleItem, name: &str) -> bool {
+    match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => declares(decl, name),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => declares(&export.decl, name),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => match &export.decl {
+            DefaultDecl::Fn(FnExpr {
+                ident: Some(ident), ..
+            })
+            | DefaultDecl::Class(ClassExpr {
+                ident: Some(ident), ..
+            }) => ident.sym == name,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the original source of the top-level declaration of `name`, a function, a
+/// class or a variable, with its `export` keyword and the comments on the lines right
+/// above it.
+///
+/// A variable declared along with others, e.g. `a` of `const a = 1, b = 2;`, returns the
+/// whole declaration.
+///
+/// # Returns
+/// * `Ok(CodeBlock)` - The code and its location, from its first comment to its end.
+/// * `Err(String)` - If the source can not be parsed or `name` is not declared at the top
+///   level.
+///
+/// # Example
+/// ```rust
+/// let code = "let a = 1;\n\n// Copies the text\nfunction copy() {}\n";
+/// let block = extract_code_block(code, "copy").unwrap();
+/// assert_eq!(block.code, "// Copies the text\nfunction copy() {}");
+/// ```
+pub fn extract_code_block(file_content: &str, name: &str) -> Result<CodeBlock, String> {
+    let (module, comments, cm) = parse(file_content)?;
+    let offsets = SpanOffsets::new(&cm);
+
+    let item = module
+        .body
+        .iter()
+        .find(|item| is_declaration_of(item, name))
+        .ok_or_else(|| format!("`{}` is not declared at the top level", name))?;
+
+    let span = item.span();
+    let start = attached_comments_start(file_content, &comments, offsets, span.lo);
+    let (_, end) = offsets.range(span);
+    let lo = BytePos(span.lo.0 - (offsets.offset(span.lo) - start) as u32);
+
+    Ok(CodeBlock {
+        code: file_content[start..end].to_string(),
+        location: SourceLocation::from_span(&cm, Span::new(lo, span.hi)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_code_block() {
+        let code = r#"// Phoenix app
+import { Socket } from "phoenix";
+
+let csrfToken = token(); // the token
+/**
+ * Copies the text of the element.
+ */
+// Used by the copy buttons
+export const CopyHook = {
+  mounted() {
+    this.el.addEventListener("click", () => copy(this.el.innerText));
+  },
+};
+
+function copy(text) { navigator.clipboard.writeText(text); }
+const { colors, sizes: [small] } = theme, other = 1;
+export default class App {}
+"#;
+
+        let block = extract_code_block(code, "CopyHook").unwrap();
+        assert_eq!(
+            block.code,
+            r#"/**
+ * Copies the text of the element.
+ */
+// Used by the copy buttons
+export const CopyHook = {
+  mounted() {
+    this.el.addEventListener("click", () => copy(this.el.innerText));
+  },
+};"#
+        );
+        assert_eq!(&code[block.location.start..block.location.end], block.code);
+        assert_eq!((block.location.line, block.location.column), (5, 0));
+
+        let block = extract_code_block(code, "copy").unwrap();
+        assert_eq!(
+            block.code,
+            "function copy(text) { navigator.clipboard.writeText(text); }"
+        );
+
+        let block = extract_code_block(code, "small").unwrap();
+        assert!(block.code.starts_with("const { colors") && block.code.ends_with("other = 1;"));
+
+        let block = extract_code_block(code, "csrfToken").unwrap();
+        assert_eq!(block.code, "let csrfToken = token();");
+
+        assert_eq!(
+            extract_code_block(code, "App").unwrap().code,
+            "export default class App {}"
+        );
+        assert!(extract_code_block(code, "text").is_err());
+        assert!(extract_code_block(code, "Socket").is_err());
+    }
+}
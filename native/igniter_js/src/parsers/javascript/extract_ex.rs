@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_ex::SourceLocationResult;
+use crate::parsers::javascript::extract::extract_code_block;
+
+use rustler::{Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct CodeBlockResult {
+    pub code: String,
+    pub location: SourceLocationResult,
+}
+
+#[rustler::nif]
+pub fn extract_code_block_nif(
+    env: Env,
+    file_content: String,
+    name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::extract_code_block_nif();
+
+        match extract_code_block(&file_content, &name) {
+            Ok(block) => {
+                let result = CodeBlockResult {
+                    code: block.code,
+                    location: block.location.into(),
+                };
+                encode_response(env, atoms::ok(), fn_atom, result)
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -3,11 +3,42 @@
 //
 // SPDX-License-Identifier: MIT
 
-use biome_formatter::{IndentStyle, IndentWidth};
+use crate::diagnostics::{syntax_error_message, Diagnostic};
+use crate::helpers::{check_formatted, line_ending, FormatCheck, FormatCheckOptions};
+use crate::limits::check_input_size;
+use crate::parsers::css::formatter as css_formatter;
+use crate::parsers::javascript::deadline::check_depth;
+use crate::parsers::javascript::helpers::{source_map_from_offsets, GeneratedCode, ModuleKind};
+use crate::telemetry::{measure, Phase};
+use biome_formatter::{IndentStyle, IndentWidth, Printed};
 use biome_js_formatter::context::JsFormatOptions;
 use biome_js_formatter::format_node;
 use biome_js_parser::{parse, JsParserOptions};
-use biome_js_syntax::{JsFileSource, ModuleKind};
+use biome_js_syntax::{
+    AnyJsTemplateElement, JsFileSource, JsSyntaxNode, JsTemplateExpression,
+    ModuleKind as BiomeModuleKind,
+};
+use biome_rowan::{AstNode, AstNodeList, TextRange, TextSize};
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+use std::ops::Range;
+
+/// How the contents of template literals are formatted.
+///
+/// On the Elixir side these are the atoms `:format`, `:preserve` and `:embedded`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum TemplateLiterals {
+    /// Like Biome, which keeps the text and formats the `${...}` expressions.
+    #[default]
+    Format,
+    /// Keeps the template literals exactly as they are, expressions included.
+    Preserve,
+    /// Formats the CSS of the tagged templates of CSS-in-JS libraries, e.g. `` css`...` ``
+    /// and `` styled.div`...` ``, and preserves the other template literals. Biome has no
+    /// formatter for the other embedded languages, like GraphQL.
+    Embedded,
+}
 
 /// Formats JavaScript source code using a standardized formatting style.
 ///
@@ -23,7 +54,8 @@ use biome_js_syntax::{JsFileSource, ModuleKind};
 /// * `Err(String)` - If parsing or formatting fails.
 ///
 /// # Errors
-/// * Returns `"Parsing failed due to syntax errors."` if the input code contains syntax errors.
+/// * Returns `"Parsing failed due to syntax errors."` if the input code contains syntax errors,
+///   followed by the line, column, message and code frame of the errors.
 /// * Returns `"Formatting failed: <error message>"` if the formatting process encounters an issue.
 ///
 /// # Example
@@ -37,34 +69,341 @@ use biome_js_syntax::{JsFileSource, ModuleKind};
 /// assert!(formatted_code.contains("console.log('Hello, world!');"));
 /// ```
 pub fn format(source_code: &str) -> Result<String, String> {
-    let parsed = parse(
+    format_with_module_kind(source_code, ModuleKind::Auto)
+}
+
+/// Formats JavaScript source code like [`format`], as the given kind of module, so
+/// CommonJS files like `postcss.config.js` are not held to the rules of ES modules.
+///
+/// # Example
+/// ```rust
+/// let result = format_with_module_kind("return module.exports", ModuleKind::Script);
+/// assert_eq!(result, Ok("return module.exports;\n".to_string()));
+/// ```
+pub fn format_with_module_kind(
+    source_code: &str,
+    module_kind: ModuleKind,
+) -> Result<String, String> {
+    format_with_template_literals(source_code, module_kind, TemplateLiterals::Format)
+}
+
+/// Formats JavaScript source code like [`format_with_module_kind`], with the contents of the
+/// template literals formatted as given.
+///
+/// # Example
+/// ```rust
+/// let source = "const button = css`color:red;padding:0`";
+/// let embedded = TemplateLiterals::Embedded;
+/// let result = format_with_template_literals(source, ModuleKind::Auto, embedded).unwrap();
+/// assert_eq!(result, "const button = css`\n  color: red;\n  padding: 0;\n`;\n");
+/// ```
+pub fn format_with_template_literals(
+    source_code: &str,
+    module_kind: ModuleKind,
+    template_literals: TemplateLiterals,
+) -> Result<String, String> {
+    let code = format_printed(source_code, module_kind)?.into_code();
+    let replacements = template_replacements(source_code, &code, template_literals)?;
+    Ok(apply_replacements(&code, &replacements))
+}
+
+/// Formats JavaScript source code like [`format_with_module_kind`], also returning the
+/// source map from the formatted code to the source, which is named `source_name` in the map.
+///
+/// # Example
+/// ```rust
+/// let result =
+///     format_with_source_map("let a=1", "app.js", ModuleKind::Auto, TemplateLiterals::Format)
+///         .unwrap();
+/// assert_eq!(result.code, "let a = 1;\n");
+/// assert!(result.source_map.contains("\"sources\":[\"app.js\"]"));
+/// ```
+pub fn format_with_source_map(
+    source_code: &str,
+    source_name: &str,
+    module_kind: ModuleKind,
+    template_literals: TemplateLiterals,
+) -> Result<GeneratedCode, String> {
+    let printed = format_printed(source_code, module_kind)?;
+    let markers: Vec<(usize, usize)> = printed
+        .sourcemap()
+        .iter()
+        .map(|marker| (marker.source.into(), marker.dest.into()))
+        .collect();
+    let code = printed.into_code();
+
+    let replacements = template_replacements(source_code, &code, template_literals)?;
+    let markers = markers.into_iter().filter_map(|(source, dest)| {
+        replaced_offset(&replacements, dest).map(|dest| (source, dest))
+    });
+    let code = apply_replacements(&code, &replacements);
+    let source_map = source_map_from_offsets(source_code, source_name, &code, markers)?;
+
+    Ok(GeneratedCode { code, source_map })
+}
+
+/// Parses the source code as the given kind of module, along with the options to format it.
+fn parse_for_format(
+    source_code: &str,
+    module_kind: ModuleKind,
+) -> Result<(JsSyntaxNode, JsFormatOptions), String> {
+    let module_kind = match module_kind.resolve(source_code) {
+        ModuleKind::Script => BiomeModuleKind::Script,
+        _ => BiomeModuleKind::Module,
+    };
+    let file_source = JsFileSource::default().with_module_kind(module_kind);
+    check_input_size(source_code.len())?;
+    check_depth(source_code)?;
+    let parsed = measure(Phase::Parse, source_code.len(), || {
+        parse(source_code, file_source, JsParserOptions::default())
+    });
+
+    if parsed.has_errors() {
+        let diagnostics: Vec<Diagnostic> = parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| Diagnostic::from_biome(source_code, diagnostic))
+            .collect();
+        return Err(syntax_error_message(&diagnostics));
+    }
+
+    let options = JsFormatOptions::new(file_source)
+        .with_indent_style(IndentStyle::Space)
+        .with_indent_width(IndentWidth::default())
+        .with_line_ending(line_ending(source_code));
+
+    Ok((parsed.syntax(), options))
+}
+
+/// The outermost template literals of a module, in source order.
+fn template_expressions(root: &JsSyntaxNode) -> Vec<JsTemplateExpression> {
+    root.descendants()
+        .filter_map(JsTemplateExpression::cast)
+        .filter(|template| {
+            !template
+                .syntax()
+                .ancestors()
+                .skip(1)
+                .any(|ancestor| JsTemplateExpression::can_cast(ancestor.kind()))
+        })
+        .collect()
+}
+
+/// The range of a template literal from its opening backtick to its closing one, i.e.
+/// without its tag.
+fn literal_range(template: &JsTemplateExpression) -> Option<Range<usize>> {
+    let start = template.l_tick_token().ok()?.text_trimmed_range().start();
+    let end = template.r_tick_token().ok()?.text_trimmed_range().end();
+    Some(usize::from(start)..usize::from(end))
+}
+
+/// Whether a tag is one of a CSS-in-JS library, e.g. `css`, `styled.div` or
+/// `styled(Button)`.
+fn is_css_tag(tag: &str) -> bool {
+    matches!(
+        tag,
+        "css" | "keyframes" | "createGlobalStyle" | "injectGlobal"
+    ) || tag.starts_with("styled.")
+        || tag.starts_with("styled(")
+}
+
+/// Formats the CSS of a template literal without `${...}` expressions, whether it is a
+/// stylesheet or the declarations of a single rule, indented under `indent`.
+fn format_css_template(css: &str, indent: &str) -> Option<String> {
+    let lines: Vec<String> = match css_formatter::format(css) {
+        Ok(formatted) => formatted.lines().map(str::to_string).collect(),
+        Err(_) => {
+            let formatted = css_formatter::format(&format!("a {{\n{}\n}}\n", css)).ok()?;
+            let lines: Vec<&str> = formatted.lines().collect();
+            lines[1..lines.len().saturating_sub(1)]
+                .iter()
+                .map(|line| line.strip_prefix("  ").unwrap_or(line).to_string())
+                .collect()
+        }
+    };
+
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return Some("``".to_string());
+    }
+    let body: Vec<String> = lines
+        .iter()
+        .map(|line| match line.is_empty() {
+            true => String::new(),
+            false => format!("{}  {}", indent, line),
+        })
+        .collect();
+    Some(format!("`\n{}\n{}`", body.join("\n"), indent))
+}
+
+/// The text a template literal of the formatted code is replaced with, `None` to keep it.
+fn template_replacement(
+    source_code: &str,
+    source_template: &JsTemplateExpression,
+    code: &str,
+    range: &Range<usize>,
+    template_literals: TemplateLiterals,
+) -> Option<String> {
+    let source_text = &source_code[literal_range(source_template)?];
+    let embedded = source_template
+        .tag()
+        .filter(|tag| is_css_tag(&tag.syntax().text_trimmed().to_string()))
+        .and_then(|_| {
+            source_template
+                .elements()
+                .iter()
+                .map(|element| match element {
+                    AnyJsTemplateElement::JsTemplateChunkElement(chunk) => chunk
+                        .template_chunk_token()
+                        .ok()
+                        .map(|token| token.text_trimmed().to_string()),
+                    AnyJsTemplateElement::JsTemplateElement(_) => None,
+                })
+                .collect::<Option<String>>()
+        });
+
+    match (template_literals, embedded) {
+        (TemplateLiterals::Format, _) => None,
+        (TemplateLiterals::Embedded, Some(css)) => {
+            let line_start = code[..range.start].rfind('\n').map_or(0, |index| index + 1);
+            let line = &code[line_start..range.start];
+            let indent = &line[..line.len() - line.trim_start().len()];
+            format_css_template(&css, indent).or_else(|| Some(source_text.to_string()))
+        }
+        _ => Some(source_text.to_string()),
+    }
+}
+
+/// The replacements of the template literals of the formatted code, by their source or
+/// their formatted CSS, in order.
+fn template_replacements(
+    source_code: &str,
+    code: &str,
+    template_literals: TemplateLiterals,
+) -> Result<Vec<(Range<usize>, String)>, String> {
+    if template_literals == TemplateLiterals::Format {
+        return Ok(vec![]);
+    }
+
+    let source_root = parse(
         source_code,
-        JsFileSource::default().with_module_kind(ModuleKind::Module),
+        JsFileSource::default(),
         JsParserOptions::default(),
     );
+    let code_root = parse(code, JsFileSource::default(), JsParserOptions::default());
+    let source_templates = template_expressions(&source_root.syntax());
+    let code_templates = template_expressions(&code_root.syntax());
+    if source_templates.len() != code_templates.len() {
+        return Err("Formatting failed: the template literals could not be matched".into());
+    }
 
-    if parsed.has_errors() {
-        return Err("Parsing failed due to syntax errors.".into());
+    Ok(source_templates
+        .iter()
+        .zip(&code_templates)
+        .filter_map(|(source_template, code_template)| {
+            let range = literal_range(code_template)?;
+            let text = template_replacement(
+                source_code,
+                source_template,
+                code,
+                &range,
+                template_literals,
+            )?;
+            (code[range.clone()] != text).then_some((range, text))
+        })
+        .collect())
+}
+
+fn apply_replacements(code: &str, replacements: &[(Range<usize>, String)]) -> String {
+    let mut result = String::with_capacity(code.len());
+    let mut previous_end = 0;
+    for (range, text) in replacements {
+        result.push_str(&code[previous_end..range.start]);
+        result.push_str(text);
+        previous_end = range.end;
     }
+    result.push_str(&code[previous_end..]);
+    result
+}
 
-    let options =
-        JsFormatOptions::new(JsFileSource::default().with_module_kind(ModuleKind::Module))
-            .with_indent_style(IndentStyle::Space)
-            .with_indent_width(IndentWidth::default());
+/// The offset in the code with the replacements of an offset in the formatted code, `None`
+/// when it is inside a replaced template literal.
+fn replaced_offset(replacements: &[(Range<usize>, String)], offset: usize) -> Option<usize> {
+    let mut shifted = offset;
+    for (range, text) in replacements {
+        if offset <= range.start {
+            break;
+        }
+        if offset < range.end {
+            return None;
+        }
+        shifted = shifted + text.len() - range.len();
+    }
+    Some(shifted)
+}
 
-    let result = format_node(options, &parsed.syntax())
-        .map_err(|err| format!("Formatting failed: {}", err))?;
+fn format_printed(source_code: &str, module_kind: ModuleKind) -> Result<Printed, String> {
+    let (root, options) = parse_for_format(source_code, module_kind)?;
+
+    measure(Phase::Codegen, 0, || {
+        let result =
+            format_node(options, &root).map_err(|err| format!("Formatting failed: {}", err))?;
+
+        result.print().map_err(|err| err.to_string())
+    })
+}
+
+/// Formats only the statements between the byte offsets `start` and `end`, e.g. the region
+/// a patch changed, and leaves the rest of the file as it is.
+///
+/// The range is widened to the nodes it touches, so the formatted region may start before
+/// `start` and end after `end`.
+///
+/// # Returns
+/// * `Ok(String)` - The whole source code, with the range formatted.
+/// * `Err(String)` - If the range is not within the source code, or parsing or formatting
+///   fails.
+///
+/// # Example
+/// ```rust
+/// let js_code = "let  a=1\nlet b=2\n";
+/// assert_eq!(format_range(js_code, 9, 16), Ok("let  a=1\nlet b = 2;\n".to_string()));
+/// ```
+pub fn format_range(source_code: &str, start: usize, end: usize) -> Result<String, String> {
+    if start > end
+        || end > source_code.len()
+        || !source_code.is_char_boundary(start)
+        || !source_code.is_char_boundary(end)
+    {
+        return Err(format!(
+            "The range {}..{} is not within the source code",
+            start, end
+        ));
+    }
 
-    let formatted = result.print().map_err(|err| err.to_string())?;
+    let (root, options) = parse_for_format(source_code, ModuleKind::Auto)?;
+    let range = TextRange::new(
+        TextSize::try_from(start).map_err(|err| err.to_string())?,
+        TextSize::try_from(end).map_err(|err| err.to_string())?,
+    );
+    let printed = biome_js_formatter::format_range(options, &root, range)
+        .map_err(|err| format!("Formatting failed: {}", err))?;
 
-    Ok(formatted.into_code())
+    let Some(formatted_range) = printed.range() else {
+        return Ok(source_code.to_string());
+    };
+    Ok(format!(
+        "{}{}{}",
+        &source_code[..usize::from(formatted_range.start())],
+        printed.as_code(),
+        &source_code[usize::from(formatted_range.end())..]
+    ))
 }
 
 /// Checks if the given JavaScript source code is already formatted.
 ///
 /// This function formats the provided `source_code` and compares it with the original.
-/// If the formatted version matches the input (ignoring leading and trailing spaces),
-/// it returns `true`; otherwise, it returns `false`.
+/// If the formatted version matches the input (ignoring leading and trailing spaces and
+/// `\r\n` line endings), it returns `true`; otherwise, it returns `false`.
 ///
 /// # Arguments
 /// * `source_code` - A string containing JavaScript source code.
@@ -88,13 +427,27 @@ pub fn format(source_code: &str) -> Result<String, String> {
 /// assert_eq!(result, Ok(false));
 /// ```
 pub fn is_formatted(source_code: &str) -> Result<bool, String> {
+    Ok(check_format(source_code, FormatCheckOptions::default())?.formatted)
+}
+
+/// Checks if the given JavaScript source code is formatted like [`is_formatted`], with the
+/// given line ending and final newline policy, reporting the first line it differs on.
+///
+/// # Example
+/// ```rust
+/// let check = check_format("let a = 1;\nlet b=2;\n", FormatCheckOptions::default()).unwrap();
+/// assert!(!check.formatted);
+/// assert_eq!(check.difference.unwrap().line, 2);
+/// ```
+pub fn check_format(source_code: &str, options: FormatCheckOptions) -> Result<FormatCheck, String> {
     let formatted_code = format(source_code)?;
-    Ok(formatted_code.trim() == source_code.trim())
+    Ok(check_formatted(source_code, &formatted_code, options))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::FormatDifference;
 
     fn app_js() -> &'static str {
         r##"
@@ -161,6 +514,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_check_format() {
+        let source = "let a = 1;\r\nlet b=2;\r\n";
+        let check = check_format(source, FormatCheckOptions::default()).unwrap();
+        assert_eq!(
+            check.difference,
+            Some(FormatDifference {
+                line: 2,
+                expected: Some("let b = 2;".to_string()),
+                actual: Some("let b=2;".to_string()),
+            })
+        );
+
+        // A stray `\r\n` in a file with `\n` line endings
+        let source = "let a = 1;\nlet b = 2;\r\nlet c = 3;\n";
+        assert!(is_formatted(source).unwrap());
+        let options = FormatCheckOptions {
+            normalize_newlines: false,
+            ..FormatCheckOptions::default()
+        };
+        assert!(!check_format(source, options).unwrap().formatted);
+    }
+
+    #[test]
+    fn test_format_keeps_source_style() {
+        let source = "\u{feff}let a=1\r\nlet b=2\r\n";
+        assert_eq!(
+            format(source),
+            Ok("\u{feff}let a = 1;\r\nlet b = 2;\r\n".to_string())
+        );
+        assert!(is_formatted("\u{feff}let a = 1;\r\nlet b = 2;\r\n").unwrap());
+    }
+
+    #[test]
+    fn test_format_commonjs() {
+        let config = "const legacy = 010;\nmodule.exports = {plugins: {tailwindcss: {}}}";
+        let expected = "const legacy = 010;\nmodule.exports = { plugins: { tailwindcss: {} } };\n";
+
+        assert_eq!(format(config), Ok(expected.to_string()));
+        assert_eq!(
+            format_with_module_kind(config, ModuleKind::Script),
+            Ok(expected.to_string())
+        );
+        assert!(format_with_module_kind(config, ModuleKind::Module).is_err());
+    }
+
+    #[test]
+    fn test_format_syntax_error() {
+        let error = format("let a = 1;\nlet b = ;\n").unwrap_err();
+        assert!(error.starts_with("Parsing failed due to syntax errors.\n\n2:8: "));
+        assert!(
+            error.contains("> 2 | let b = ;\n    |         ^"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_format_js_with_source_map() {
+        let source = "let a=1\nconst label =   'é';";
+        let result =
+            format_with_source_map(source, "app.js", ModuleKind::Auto, TemplateLiterals::Format)
+                .unwrap();
+        assert_eq!(result.code, format(source).unwrap());
+
+        let source_map: serde_json::Value = serde_json::from_str(&result.source_map).unwrap();
+        assert_eq!(source_map["version"], 3);
+        assert_eq!(source_map["sources"], serde_json::json!(["app.js"]));
+        assert_eq!(source_map["sourcesContent"], serde_json::json!([source]));
+        // `'é'` moves from column 16 to 14 when the second line is formatted
+        let decoded = swc_sourcemap::decode_slice(result.source_map.as_bytes()).unwrap();
+        let swc_sourcemap::DecodedMap::Regular(decoded) = decoded else {
+            panic!("Expected a regular source map");
+        };
+        let token = decoded.lookup_token(1, 14).unwrap();
+        assert_eq!((token.get_src_line(), token.get_src_col()), (1, 16));
+    }
+
+    #[test]
+    fn test_format_template_literals() {
+        let source = r#"const query = gql`
+  query { user(id: ${ id }) { name } }
+`;
+const Button = styled.button`color:red;&:hover{color:blue}`;
+const theme = css`
+    .dark{background:black}
+`;
+const label = `Hello ${ name }`;
+"#;
+
+        let formatted = format(source).unwrap();
+        assert!(formatted.contains("user(id: ${id})"));
+        assert!(formatted.contains("const label = `Hello ${name}`;"));
+
+        let preserved =
+            format_with_template_literals(source, ModuleKind::Auto, TemplateLiterals::Preserve)
+                .unwrap();
+        assert!(preserved.contains("user(id: ${ id })"));
+        assert!(preserved.contains("const label = `Hello ${ name }`;"));
+        assert!(preserved.contains("styled.button`color:red;&:hover{color:blue}`;"));
+
+        let embedded =
+            format_with_template_literals(source, ModuleKind::Auto, TemplateLiterals::Embedded)
+                .unwrap();
+        assert!(embedded.contains("user(id: ${ id })"));
+        assert!(embedded.contains(
+            "const Button = styled.button`\n  color: red;\n  &:hover {\n    color: blue;\n  }\n`;"
+        ));
+        assert!(embedded.contains("const theme = css`\n  .dark {\n    background: black;\n  }\n`;"));
+
+        // The source map skips the replaced template literals
+        let result = format_with_source_map(
+            source,
+            "app.js",
+            ModuleKind::Auto,
+            TemplateLiterals::Embedded,
+        )
+        .unwrap();
+        assert_eq!(result.code, embedded);
+    }
+
     #[test]
     fn test_is_formatted_js() {
         assert!(is_formatted(app_js()).is_ok());
@@ -168,9 +642,29 @@ mod tests {
         let js_code_formatted = r#"function test() {
           console.log("hello world");
         }"#;
-        assert_eq!(is_formatted(js_code_unformatted).unwrap(), false);
+        assert!(!is_formatted(js_code_unformatted).unwrap());
 
         let formatted = format(js_code_formatted).unwrap();
-        assert_eq!(is_formatted(&formatted).unwrap(), true);
+        assert!(is_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_format_range() {
+        let code = "import  {Socket}  from \"phoenix\"\nlet   a=1\nlet b={c:1}\n";
+        let start = code.find("let b").unwrap();
+
+        let result = format_range(code, start, code.len()).unwrap();
+        assert_eq!(
+            result,
+            "import  {Socket}  from \"phoenix\"\nlet   a=1\nlet b = { c: 1 };\n"
+        );
+
+        assert_eq!(
+            format_range("let  a=1\nlet b=2\n", 9, 16),
+            Ok("let  a=1\nlet b = 2;\n".to_string())
+        );
+        assert!(format_range(code, 5, 2).is_err());
+        assert!(format_range(code, 0, code.len() + 1).is_err());
+        assert!(format_range("let a = ", 0, 3).is_err());
     }
 }
@@ -4,29 +4,100 @@
 // SPDX-License-Identifier: MIT
 
 use crate::atoms;
-use crate::helpers::encode_response;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_response, FormatCheckOptions};
 use crate::parsers::javascript::formatter::*;
+use crate::parsers::javascript::helpers::ModuleKind;
 
 use rustler::{Env, NifResult, Term};
 
 #[rustler::nif]
-pub fn format_js_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::format_js_nif();
-    let (status, result) = match format(&file_content) {
-        Ok(updated_code) => (atoms::ok(), updated_code),
-        Err(error_msg) => (atoms::error(), error_msg),
-    };
-
-    encode_response(env, status, fn_atom, result)
+pub fn format_js_nif(
+    env: Env,
+    file_content: String,
+    module_kind: ModuleKind,
+    template_literals: TemplateLiterals,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::format_js_nif();
+        let result = format_with_template_literals(&file_content, module_kind, template_literals);
+        let (status, result) = match result {
+            Ok(updated_code) => (atoms::ok(), updated_code),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn format_js_with_source_map_nif(
+    env: Env,
+    file_content: String,
+    source_name: String,
+    module_kind: ModuleKind,
+    template_literals: TemplateLiterals,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::format_js_with_source_map_nif();
+
+        match format_with_source_map(&file_content, &source_name, module_kind, template_literals) {
+            Ok(generated) => encode_response(env, atoms::ok(), fn_atom, generated),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn format_js_range_nif(
+    env: Env,
+    file_content: String,
+    start: usize,
+    end: usize,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::format_js_range_nif();
+
+        match format_range(&file_content, start, end) {
+            Ok(updated_code) => encode_response(env, atoms::ok(), fn_atom, updated_code),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
 }
 
 #[rustler::nif]
-pub fn is_js_formatted_nif(env: Env, file_content: String) -> NifResult<Term> {
-    let fn_atom = atoms::is_js_formatted_nif();
-    let (status, result) = match is_formatted(&file_content) {
-        Ok(true) => (atoms::ok(), true),
-        _ => (atoms::error(), false),
-    };
-
-    encode_response(env, status, fn_atom, result)
+pub fn is_js_formatted_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::is_js_formatted_nif();
+        let (status, result) = match is_formatted(&file_content) {
+            Ok(true) => (atoms::ok(), true),
+            _ => (atoms::error(), false),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif]
+pub fn check_js_format_nif(
+    env: Env,
+    file_content: String,
+    options: FormatCheckOptions,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::check_js_format_nif();
+
+        match check_format(&file_content, options) {
+            Ok(check) => encode_response(env, atoms::ok(), fn_atom, check),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
 }
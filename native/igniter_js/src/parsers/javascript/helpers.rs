@@ -3,54 +3,572 @@
 //
 // SPDX-License-Identifier: MIT
 
-use swc_ecma_ast::{ImportSpecifier, Module, ModuleDecl, ModuleItem};
+use swc_ecma_ast::{
+    BindingIdent, Decl, EsVersion, ExportDecl, Expr, ExprStmt, GetterProp, Ident, IdentName,
+    ImportDecl, ImportNamedSpecifier, ImportSpecifier, KeyValueProp, Lit, MethodProp, Module,
+    ModuleDecl, ModuleExportName, ModuleItem, ObjectLit, ParenExpr, Pat, Program, Prop, PropName,
+    PropOrSpread, SetterProp, Stmt, Str,
+};
 use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
-use swc_ecma_visit::{VisitMut, VisitMutWith};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
 
+use std::sync::{Arc, Mutex};
 use swc_common::{
-    comments::SingleThreadedComments,
-    errors::{ColorConfig, Handler},
+    comments::{Comments, SingleThreadedComments},
+    errors::{DiagnosticBuilder, Emitter as DiagnosticEmitter, Handler, Level},
+    source_map::SourceMapGenConfig,
     sync::Lrc,
-    FileName, SourceMap,
+    BytePos, EqIgnoreSpan, FileName, SourceMap, SourceMapper, Span, DUMMY_SP,
 };
+use swc_sourcemap::SourceMapBuilder;
+
+use crate::diagnostics::{Diagnostic, DiagnosticSeverity};
+use crate::helpers::SourceStyle;
+use crate::limits::{check_input_size, check_node_count, check_stopped};
+use crate::parser_options::es_syntax;
+use crate::parsers::javascript::code_style::CodeStyle;
+use crate::parsers::javascript::deadline::DeadlineTokens;
+use crate::telemetry::{measure, Phase};
+
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use swc_ecma_parser::{lexer::Lexer, unstable::Token, Parser, StringInput};
+
+/// The name of the parsed source in its source map.
+const SOURCE_FILE_NAME: &str = "virtual_file.js";
+/// The name of a parsed source starting with a byte order mark.
+const BOM_SOURCE_FILE_NAME: &str = "virtual_file.bom.js";
+
+/// The line endings and byte order mark of the source parsed into `cm`, so the generated
+/// code is emitted like it.
+pub fn source_style(cm: &SourceMap) -> SourceStyle {
+    cm.files()
+        .first()
+        .map_or(SourceStyle::default(), |file| SourceStyle {
+            crlf: SourceStyle::detect(&file.src).crlf,
+            bom: matches!(&*file.name, FileName::Custom(name) if name == BOM_SOURCE_FILE_NAME),
+        })
+}
+
+/// Converts the spans of a parsed source to byte offsets into the source as it was given.
+///
+/// SWC counts positions from the start of the file in its source map and drops the byte
+/// order mark of the source, so a span can not index the source directly. Every offset
+/// returned to Elixir or used to slice a source goes through this.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpanOffsets {
+    start_pos: BytePos,
+    bom: usize,
+}
+
+impl SpanOffsets {
+    /// The offsets of the source parsed into `cm`.
+    pub fn new(cm: &SourceMap) -> Self {
+        let bom = source_style(cm).bom;
+        cm.files()
+            .first()
+            .map_or(Self::default(), |file| Self::for_file(file.start_pos, bom))
+    }
+
+    fn for_file(start_pos: BytePos, bom: bool) -> Self {
+        Self {
+            start_pos,
+            bom: if bom { '\u{feff}'.len_utf8() } else { 0 },
+        }
+    }
+
+    /// The offsets into the text of the source file in the source map, which has no byte
+    /// order mark.
+    pub fn without_bom(self) -> Self {
+        Self { bom: 0, ..self }
+    }
 
-use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+    /// The byte offset of the text, after the byte order mark.
+    pub fn text_start(&self) -> usize {
+        self.bom
+    }
+
+    /// The byte offset of a position.
+    pub fn offset(&self, pos: BytePos) -> usize {
+        pos.0.saturating_sub(self.start_pos.0) as usize + self.bom
+    }
+
+    /// The byte range of a span.
+    pub fn range(&self, span: Span) -> (usize, usize) {
+        (self.offset(span.lo), self.offset(span.hi))
+    }
+}
+
+/// Whether a source is parsed as an ES module or as a CommonJS script.
+///
+/// On the Elixir side these are the atoms `:auto`, `:module` and `:script`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum ModuleKind {
+    /// Detected from the source with [`detect_module_kind`].
+    Auto,
+    Module,
+    /// A CommonJS script, e.g. `postcss.config.js`, which is not in strict mode and may
+    /// `return` at the top level.
+    Script,
+}
+
+impl ModuleKind {
+    /// The kind of a file by its extension: `.mjs` is a module, `.cjs` a script and
+    /// anything else is detected.
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".mjs") {
+            ModuleKind::Module
+        } else if path.ends_with(".cjs") {
+            ModuleKind::Script
+        } else {
+            ModuleKind::Auto
+        }
+    }
+
+    /// Resolves [`ModuleKind::Auto`] for a source.
+    pub fn resolve(self, file_content: &str) -> Self {
+        match self {
+            ModuleKind::Auto => detect_module_kind(file_content),
+            kind => kind,
+        }
+    }
+}
 
+/// Detects whether a source is a CommonJS script: it uses `require(...)`, `module.exports`
+/// or `exports.name`, and has no `import` or `export` declaration. Anything else, including
+/// code that can not be parsed, is a module.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(detect_module_kind("module.exports = {}"), ModuleKind::Script);
+/// assert_eq!(detect_module_kind("export default {}"), ModuleKind::Module);
+/// ```
+pub fn detect_module_kind(file_content: &str) -> ModuleKind {
+    if !["require(", "module.exports", "exports."]
+        .iter()
+        .any(|marker| file_content.contains(marker))
+    {
+        return ModuleKind::Module;
+    }
+
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(
+        FileName::Custom(SOURCE_FILE_NAME.into()).into(),
+        file_content.to_string(),
+    );
+    let lexer = Lexer::new(
+        es_syntax(),
+        Default::default(),
+        StringInput::from(&*fm),
+        None,
+    );
+
+    match Parser::new_from(DeadlineTokens::new(lexer)).parse_program() {
+        Ok(Program::Script(_)) => ModuleKind::Script,
+        _ => ModuleKind::Module,
+    }
+}
+
+/// Parses a source, detecting whether it is an ES module or a CommonJS script.
+///
+/// Scripts are returned as a [`Module`] without module declarations, so every transform
+/// works on both and the code is emitted as it was written.
 pub fn parse(
     file_content: &str,
 ) -> Result<(Module, SingleThreadedComments, Lrc<SourceMap>), String> {
+    parse_with_module_kind(file_content, ModuleKind::Auto)
+}
+
+/// Parses a source like [`parse`], as the given kind of module.
+///
+/// A source that can not be parsed is an error naming the problem, e.g.
+/// `"Failed to parse module: Expression expected at 2:8"`.
+pub fn parse_with_module_kind(
+    file_content: &str,
+    module_kind: ModuleKind,
+) -> Result<ParsedModule, String> {
+    match parse_with_diagnostics(file_content, module_kind) {
+        Ok((parsed, _diagnostics)) => Ok(parsed),
+        Err(diagnostics) => Err(match diagnostics.last() {
+            Some(diagnostic) => format!(
+                "Failed to parse module: {} at {}:{}",
+                diagnostic.message, diagnostic.line, diagnostic.column
+            ),
+            None => "Failed to parse module".to_string(),
+        }),
+    }
+}
+
+/// Collects the diagnostics of a [`Handler`] instead of writing them to stderr, which for
+/// a NIF is the stderr of the BEAM node.
+struct BufferedEmitter {
+    source: String,
+    offsets: SpanOffsets,
+    diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl DiagnosticEmitter for BufferedEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        let severity = match db.level {
+            Level::Warning => DiagnosticSeverity::Warning,
+            Level::Note | Level::Help => DiagnosticSeverity::Advice,
+            _ => DiagnosticSeverity::Error,
+        };
+        let (start, end) = db
+            .span
+            .primary_span()
+            .filter(|span| !span.is_dummy())
+            .map_or((0, 0), |span| self.offsets.range(span));
+        let help = db
+            .children
+            .iter()
+            .map(|child| child.message())
+            .find(|message| !message.is_empty());
+
+        self.diagnostics.lock().unwrap().push(Diagnostic::new(
+            &self.source,
+            db.message(),
+            severity,
+            help,
+            start,
+            end,
+        ));
+    }
+}
+
+/// A module with its comments and the source map of its spans.
+pub type ParsedModule = (Module, SingleThreadedComments, Lrc<SourceMap>);
+
+/// Parses a source like [`parse_with_module_kind`], returning the syntax errors SWC
+/// recovered from along with the module.
+///
+/// # Returns
+/// * `Ok((ParsedModule, Vec<Diagnostic>))` - The module and the recoverable errors, e.g. a
+///   duplicate parameter name in strict mode.
+/// * `Err(Vec<Diagnostic>)` - The recoverable errors followed by the error that stopped
+///   the parsing.
+pub fn parse_with_diagnostics(
+    file_content: &str,
+    module_kind: ModuleKind,
+) -> Result<(ParsedModule, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let limit_error = |message: String| {
+        vec![Diagnostic::new(
+            file_content,
+            message,
+            DiagnosticSeverity::Error,
+            None,
+            0,
+            0,
+        )]
+    };
+    check_input_size(file_content.len()).map_err(limit_error)?;
+
+    let parsed = measure(Phase::Parse, file_content.len(), || {
+        parse_source(file_content, module_kind)
+    });
+    // A parse past the deadline or the depth limit stopped early, its result is not the one
+    // of the source
+    check_stopped().map_err(limit_error)?;
+    let parsed = parsed?;
+    check_node_count(|| {
+        let mut counter = NodeCounter::default();
+        parsed.0 .0.visit_with(&mut counter);
+        counter.count
+    })
+    .map_err(limit_error)?;
+    Ok(parsed)
+}
+
+/// Counts the statements, expressions and patterns of a module, the nodes checked against
+/// the limit of [`check_node_count`].
+#[derive(Default)]
+struct NodeCounter {
+    count: usize,
+}
+
+impl Visit for NodeCounter {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.count += 1;
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+        self.count += 1;
+        decl.visit_children_with(self);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.count += 1;
+        expr.visit_children_with(self);
+    }
+
+    fn visit_pat(&mut self, pat: &Pat) {
+        self.count += 1;
+        pat.visit_children_with(self);
+    }
+}
+
+fn parse_source(
+    file_content: &str,
+    module_kind: ModuleKind,
+) -> Result<(ParsedModule, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let module_kind = module_kind.resolve(file_content);
     let cm: Lrc<SourceMap> = Default::default();
-    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
 
+    // SWC drops the byte order mark, the file name keeps track of it for the code generation
+    let file_name = if file_content.starts_with('\u{feff}') {
+        BOM_SOURCE_FILE_NAME
+    } else {
+        SOURCE_FILE_NAME
+    };
     let fm = cm.new_source_file(
-        FileName::Custom("virtual_file.js".into()).into(),
+        FileName::Custom(file_name.into()).into(),
         file_content.to_string(),
     );
 
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let handler = Handler::with_emitter(
+        true,
+        false,
+        Box::new(BufferedEmitter {
+            source: file_content.to_string(),
+            offsets: SpanOffsets::for_file(fm.start_pos, file_content.starts_with('\u{feff}')),
+            diagnostics: diagnostics.clone(),
+        }),
+    );
+
     let comments = SingleThreadedComments::default();
 
     let lexer = Lexer::new(
-        Syntax::Es(Default::default()),
+        es_syntax(),
         Default::default(),
         StringInput::from(&*fm),
         Some(&comments),
     );
 
-    let mut parser = Parser::new_from(lexer);
+    let mut parser = Parser::new_from(DeadlineTokens::new(lexer));
+
+    let parsed = match module_kind {
+        ModuleKind::Script => parser.parse_commonjs().map(|script| Module {
+            span: script.span,
+            body: script.body.into_iter().map(ModuleItem::Stmt).collect(),
+            shebang: script.shebang,
+        }),
+        _ => parser.parse_module(),
+    };
 
     for e in parser.take_errors() {
         e.into_diagnostic(&handler).emit();
     }
 
-    // let module = parser.parse_module().expect("Failed to parse module");
-    let module = match parser.parse_module() {
-        Ok(m) => m,
-        Err(_e) => {
-            return Err("Failed to parse module".to_string());
-        }
+    let parsed = parsed.map_err(|e| e.into_diagnostic(&handler).emit());
+    let diagnostics = std::mem::take(&mut *diagnostics.lock().unwrap());
+
+    match parsed {
+        Ok(module) => Ok(((module, comments, cm), diagnostics)),
+        Err(()) => Err(diagnostics),
+    }
+}
+
+/// Parses an ES version name, e.g. `es5`, `es2020` or `esnext`.
+pub fn es_version(target: &str) -> Result<EsVersion, String> {
+    let version = match target.to_lowercase().as_str() {
+        "es3" => EsVersion::Es3,
+        "es5" => EsVersion::Es5,
+        "es2015" | "es6" => EsVersion::Es2015,
+        "es2016" => EsVersion::Es2016,
+        "es2017" => EsVersion::Es2017,
+        "es2018" => EsVersion::Es2018,
+        "es2019" => EsVersion::Es2019,
+        "es2020" => EsVersion::Es2020,
+        "es2021" => EsVersion::Es2021,
+        "es2022" => EsVersion::Es2022,
+        "es2023" => EsVersion::Es2023,
+        "es2024" => EsVersion::Es2024,
+        "esnext" => EsVersion::EsNext,
+        _ => return Err(format!("Unknown ES target: {}", target)),
     };
+    Ok(version)
+}
 
-    Ok((module, comments, cm))
+/// The name of an ES version, e.g. `es2020`, the inverse of [`es_version`].
+pub fn es_version_name(version: EsVersion) -> &'static str {
+    match version {
+        EsVersion::Es3 => "es3",
+        EsVersion::Es5 => "es5",
+        EsVersion::Es2015 => "es2015",
+        EsVersion::Es2016 => "es2016",
+        EsVersion::Es2017 => "es2017",
+        EsVersion::Es2018 => "es2018",
+        EsVersion::Es2019 => "es2019",
+        EsVersion::Es2020 => "es2020",
+        EsVersion::Es2021 => "es2021",
+        EsVersion::Es2022 => "es2022",
+        EsVersion::Es2023 => "es2023",
+        EsVersion::Es2024 => "es2024",
+        EsVersion::EsNext => "esnext",
+    }
+}
+
+/// Resets every span of a node to `DUMMY_SP`.
+///
+/// Nodes parsed from a snippet carry positions of their own source map; they must not be
+/// mixed with the positions of the file they are inserted into, otherwise the emitter
+/// attaches the file's comments to them.
+pub struct DropSpan;
+
+impl VisitMut for DropSpan {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+}
+
+/// Parses a single JavaScript expression, e.g. `document.documentElement.lang`.
+///
+/// The snippet is parsed as a parenthesized expression statement, so object literals and
+/// functions are accepted as is, while anything that is not exactly one expression is
+/// rejected. The returned expression has no spans, so it can be inserted into any module.
+pub fn parse_expr(code: &str) -> Result<Box<Expr>, String> {
+    let error = || format!("Failed to parse expression: {}", code);
+    let (module, _comments, _cm) = parse(&format!("(\n{}\n);", code)).map_err(|_| error())?;
+
+    match <[ModuleItem; 1]>::try_from(module.body) {
+        Ok([ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))]) => match *expr {
+            Expr::Paren(ParenExpr { mut expr, .. }) => {
+                expr.visit_mut_with(&mut DropSpan);
+                Ok(expr)
+            }
+            _ => Err(error()),
+        },
+        _ => Err(error()),
+    }
+}
+
+/// Finds the object literal a top-level variable is initialized with, e.g. the `{...}` of
+/// `let hooks = {...}`.
+pub fn find_var_object_literal<'a>(
+    module: &'a mut Module,
+    var_name: &str,
+) -> Option<&'a mut ObjectLit> {
+    module.body.iter_mut().find_map(|item| {
+        let var_decl = match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => var_decl,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var_decl),
+                ..
+            })) => var_decl,
+            _ => return None,
+        };
+
+        var_decl
+            .decls
+            .iter_mut()
+            .find_map(|decl| match (&decl.name, &mut decl.init) {
+                (Pat::Ident(BindingIdent { id, .. }), Some(init)) if id.sym == var_name => {
+                    match &mut **init {
+                        Expr::Object(obj_expr) => Some(obj_expr),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+    })
+}
+
+/// Returns the key of a shorthand, key-value, method, getter or setter property.
+pub fn prop_key_name(prop: &PropOrSpread) -> Option<String> {
+    let key = match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::Shorthand(ident) => return Some(ident.sym.to_string()),
+            Prop::KeyValue(KeyValueProp { key, .. }) => key,
+            Prop::Method(MethodProp { key, .. }) => key,
+            Prop::Getter(GetterProp { key, .. }) => key,
+            Prop::Setter(SetterProp { key, .. }) => key,
+            Prop::Assign(_) => return None,
+        },
+        PropOrSpread::Spread(_) => return None,
+    };
+
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        PropName::Num(n) => Some(n.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the key an entry of an object is registered under, e.g. a hook: the identifier for
+/// shorthand props, the identifier, string or number key of key-value props, also computed
+/// from a literal like `["phx-drag"]`, and `...Name` for spreads.
+pub fn object_entry_key(prop: &PropOrSpread) -> Option<String> {
+    match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::Shorthand(ident) => Some(ident.sym.to_string()),
+            Prop::KeyValue(KeyValueProp { key, .. }) => match key {
+                PropName::Ident(ident) => Some(ident.sym.to_string()),
+                PropName::Str(s) => Some(s.value.to_string()),
+                PropName::Num(n) => Some(n.value.to_string()),
+                PropName::Computed(computed) => match &*computed.expr {
+                    Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+                    Expr::Lit(Lit::Num(n)) => Some(n.value.to_string()),
+                    _ => None,
+                },
+                PropName::BigInt(_) => None,
+            },
+            _ => None,
+        },
+        PropOrSpread::Spread(spread) => match &*spread.expr {
+            Expr::Ident(ident) => Some(format!("...{}", ident.sym)),
+            _ => None,
+        },
+    }
+}
+
+/// Builds a `key: value` property, quoting the key when it is not a valid identifier.
+pub fn new_key_value_prop(key: &str, value: Box<Expr>) -> PropOrSpread {
+    let key = if Ident::verify_symbol(key).is_ok() {
+        PropName::Ident(IdentName::new(key.into(), DUMMY_SP))
+    } else {
+        PropName::Str(Str {
+            span: DUMMY_SP,
+            value: key.into(),
+            raw: None,
+        })
+    };
+
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp { key, value })))
+}
+
+/// Whether the import attributes of the parsed source are written with the legacy `assert`
+/// keyword, e.g. `import data from "./data.json" assert { type: "json" }`. The module is
+/// then emitted with `assert` too, instead of the `with` SWC writes by default.
+fn uses_import_assertions(module: &Module, cm: &SourceMap) -> bool {
+    module.body.iter().any(|item| {
+        let (src, with) = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => (&import.src, &import.with),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(swc_ecma_ast::NamedExport {
+                src: Some(src),
+                with,
+                ..
+            })) => (src, with),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => (&export.src, &export.with),
+            _ => return false,
+        };
+        match with {
+            Some(with) if !src.span.is_dummy() && !with.span.is_dummy() => cm
+                .span_to_snippet(Span::new(src.span.hi, with.span.lo))
+                .is_ok_and(|keyword| keyword.trim() == "assert"),
+            _ => false,
+        }
+    })
+}
+
+/// The configuration the code of a parsed module is emitted with.
+fn emit_config(module: &Module, cm: &SourceMap) -> Config {
+    Config::default()
+        .with_minify(false)
+        .with_emit_assert_for_import_attributes(uses_import_assertions(module, cm))
 }
 
 pub fn code_gen_from_ast_vist<T>(file_content: &str, mut visitor: T) -> Result<String, String>
@@ -61,22 +579,31 @@ where
         Ok(result) => result,
         Err(_) => return Err("Failed to parse JavaScript content".to_string()),
     };
+    let original = module.clone();
 
     module.visit_mut_with(&mut visitor);
-    let mut buf = vec![];
+    if module.eq_ignore_span(&original) {
+        return Ok(file_content.to_string());
+    }
+    let code_style = CodeStyle::of(&cm);
+    code_style.prepare(&mut module, &cm);
+    measure(Phase::Codegen, 0, || {
+        let mut buf = vec![];
 
-    let mut emitter = Emitter {
-        cfg: Config::default().with_minify(false),
-        cm: cm.clone(),
-        comments: Some(&comments),
-        wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
-    };
+        let mut emitter = Emitter {
+            cfg: emit_config(&module, &cm),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
 
-    if emitter.emit_module(&module).is_err() {
-        return Err("Failed to emit module".to_string());
-    }
+        if emitter.emit_module(&module).is_err() {
+            return Err("Failed to emit module".to_string());
+        }
 
-    String::from_utf8(buf).map_err(|_| "Invalid UTF-8".to_string())
+        let code = String::from_utf8(buf).map_err(|_| "Invalid UTF-8".to_string())?;
+        Ok(source_style(&cm).apply(&code_style.apply(&code)))
+    })
 }
 
 pub fn code_gen_from_ast_module(
@@ -84,56 +611,546 @@ pub fn code_gen_from_ast_module(
     comments: SingleThreadedComments,
     cm: Lrc<SourceMap>,
 ) -> String {
+    let code_style = CodeStyle::of(&cm);
+    code_style.prepare(module, &cm);
+    measure(Phase::Codegen, 0, || {
+        let mut buf = vec![];
+
+        let mut emitter = Emitter {
+            cfg: emit_config(module, &cm),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+
+        emitter.emit_module(module).expect("Failed to emit module");
+        let code = String::from_utf8(buf).expect("Invalid UTF-8");
+        source_style(&cm).apply(&code_style.apply(&code))
+    })
+}
+
+/// Emits a single import declaration without its spans, e.g. an import built from the
+/// specifiers of another one.
+pub fn emit_import(import: ImportDecl) -> String {
+    let mut module = Module {
+        span: DUMMY_SP,
+        body: vec![ModuleItem::ModuleDecl(ModuleDecl::Import(import))],
+        shebang: None,
+    };
+    module.visit_mut_with(&mut DropSpan);
+    let code = code_gen_from_ast_module(
+        &mut module,
+        SingleThreadedComments::default(),
+        Lrc::new(SourceMap::default()),
+    );
+    code.trim_end().to_string()
+}
+
+/// Emits a module edited by a transform like [`code_gen_from_ast_module`], or returns
+/// `source` untouched when the module is still its `original` program, as parsed before
+/// the edit, so a transform with nothing to do leaves the source as it was written and
+/// reports no change, see [`crate::helpers::Edit`].
+///
+/// Comments are not compared, as no transform edits them.
+///
+/// # Example
+/// ```rust
+/// let (mut module, comments, cm) = parse("let a=1")?;
+/// let original = module.clone();
+/// assert_eq!(code_gen_edit("let a=1", &original, &mut module, comments, cm), "let a=1");
+/// ```
+pub fn code_gen_edit(
+    source: &str,
+    original: &Module,
+    module: &mut Module,
+    comments: SingleThreadedComments,
+    cm: Lrc<SourceMap>,
+) -> String {
+    if module.eq_ignore_span(original) {
+        return source.to_string();
+    }
+    code_gen_from_ast_module(module, comments, cm)
+}
+
+/// Generated code together with its source map, a JSON string in the source map v3 format.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct GeneratedCode {
+    pub code: String,
+    pub source_map: String,
+}
+
+/// Names the parsed input `source_name` in the source map and embeds its content.
+struct SourceMapNaming<'a> {
+    source_name: &'a str,
+}
+
+impl SourceMapGenConfig for SourceMapNaming<'_> {
+    fn file_name_to_source(&self, _file_name: &FileName) -> String {
+        self.source_name.to_string()
+    }
+
+    fn inline_sources_content(&self, _file_name: &FileName) -> bool {
+        true
+    }
+}
+
+/// Emits a module like [`code_gen_from_ast_module`], along with the source map from the
+/// generated code to the parsed source, which is named `source_name` in the map.
+pub fn code_gen_with_source_map(
+    module: &Module,
+    comments: Option<&SingleThreadedComments>,
+    cm: Lrc<SourceMap>,
+    cfg: Config,
+    source_name: &str,
+) -> Result<GeneratedCode, String> {
+    measure(Phase::Codegen, 0, || {
+        emit_with_source_map(module, comments, cm, cfg, source_name)
+    })
+}
+
+fn emit_with_source_map(
+    module: &Module,
+    comments: Option<&SingleThreadedComments>,
+    cm: Lrc<SourceMap>,
+    cfg: Config,
+    source_name: &str,
+) -> Result<GeneratedCode, String> {
     let mut buf = vec![];
+    let mut mappings = vec![];
 
     let mut emitter = Emitter {
-        cfg: Config::default().with_minify(false),
+        cfg,
         cm: cm.clone(),
-        comments: Some(&comments),
-        wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        comments: comments.map(|comments| comments as _),
+        wr: JsWriter::new(cm.clone(), "\n", &mut buf, Some(&mut mappings)),
     };
+    emitter
+        .emit_module(module)
+        .map_err(|e| format!("Failed to emit module: {:?}", e))?;
 
-    emitter.emit_module(module).expect("Failed to emit module");
-    String::from_utf8(buf).expect("Invalid UTF-8")
+    let code = String::from_utf8(buf).map_err(|_| "Invalid UTF-8".to_string())?;
+    // A byte order mark would shift the mapped columns of the first line
+    let style = SourceStyle {
+        bom: false,
+        ..source_style(&cm)
+    };
+    let code = style.apply(&code);
+    let source_map = cm.build_source_map(&mappings, None, SourceMapNaming { source_name });
+
+    Ok(GeneratedCode {
+        code,
+        source_map: source_map_to_json(&source_map)?,
+    })
 }
 
-pub fn is_duplicate_import(new_import: &ModuleItem, body: &[ModuleItem]) -> bool {
-    if let ModuleItem::ModuleDecl(ModuleDecl::Import(new_import_decl)) = new_import {
-        for item in body {
-            if let ModuleItem::ModuleDecl(ModuleDecl::Import(existing_import_decl)) = item {
-                if new_import_decl.src.value == existing_import_decl.src.value {
-                    for new_spec in &new_import_decl.specifiers {
-                        if !existing_import_decl
-                            .specifiers
-                            .iter()
-                            .any(|existing_spec| specifier_equals(new_spec, existing_spec))
-                        {
-                            return false;
-                        }
+/// The line and UTF-16 column of a byte offset, both starting at 0 as in source maps.
+fn line_col(text: &str, line_starts: &[usize], offset: usize) -> (u32, u32) {
+    let line = line_starts.partition_point(|start| *start <= offset) - 1;
+    let column = text[line_starts[line]..offset].encode_utf16().count();
+    (line as u32, column as u32)
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect()
+}
+
+/// Builds a source map from pairs of byte offsets `(in source, in generated code)`, for
+/// code that is not emitted by SWC, e.g. the output of the Biome formatter.
+pub fn source_map_from_offsets(
+    source: &str,
+    source_name: &str,
+    code: &str,
+    offsets: impl IntoIterator<Item = (usize, usize)>,
+) -> Result<String, String> {
+    let source_lines = line_starts(source);
+    let code_lines = line_starts(code);
+
+    let mut builder = SourceMapBuilder::new(None);
+    let source_id = builder.add_source(source_name.to_string().into());
+    builder.set_source_contents(source_id, Some(source.to_string().into()));
+
+    for (source_offset, code_offset) in offsets {
+        if source_offset > source.len() || code_offset > code.len() {
+            continue;
+        }
+        let (src_line, src_col) = line_col(source, &source_lines, source_offset);
+        let (dst_line, dst_col) = line_col(code, &code_lines, code_offset);
+        builder.add_raw(
+            dst_line,
+            dst_col,
+            src_line,
+            src_col,
+            Some(source_id),
+            None,
+            false,
+        );
+    }
+
+    source_map_to_json(&builder.into_sourcemap())
+}
+
+fn source_map_to_json(source_map: &swc_sourcemap::SourceMap) -> Result<String, String> {
+    let mut json = vec![];
+    source_map
+        .to_writer(&mut json)
+        .map_err(|e| format!("Failed to write source map: {}", e))?;
+    String::from_utf8(json).map_err(|_| "Invalid UTF-8".to_string())
+}
+
+/// The byte offset where the leading import declarations of a source end, found with the
+/// lexer alone. Comments and blank lines between the imports are part of the section.
+fn leading_imports_end(file_content: &str) -> usize {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(
+        FileName::Custom(SOURCE_FILE_NAME.into()).into(),
+        file_content.to_string(),
+    );
+    let offsets = SpanOffsets::for_file(fm.start_pos, file_content.starts_with('\u{feff}'));
+    let mut tokens = Lexer::new(
+        es_syntax(),
+        Default::default(),
+        StringInput::from(&*fm),
+        None,
+    )
+    .map(|token| (token.token, token.span))
+    .peekable();
+    let mut end = offsets.text_start();
+
+    loop {
+        // `import(...)` and `import.meta` are expressions, not declarations
+        match tokens.next() {
+            Some((Token::Import, _))
+                if !matches!(tokens.peek(), Some((Token::LParen | Token::Dot, _))) => {}
+            _ => return end,
+        }
+
+        // The source is the first string outside of the braces of the specifiers
+        let mut depth = 0usize;
+        let mut hi = loop {
+            match tokens.next() {
+                Some((Token::LBrace, _)) => depth += 1,
+                Some((Token::RBrace, _)) => depth = depth.saturating_sub(1),
+                Some((Token::Str, span)) if depth == 0 => break span.hi,
+                Some((Token::Error, _)) | None => return end,
+                _ => {}
+            }
+        };
+
+        if matches!(tokens.peek(), Some((Token::With | Token::Assert, _))) {
+            tokens.next();
+            let mut depth = 0usize;
+            loop {
+                match tokens.next() {
+                    Some((Token::LBrace, _)) => depth += 1,
+                    Some((Token::RBrace, span)) if depth == 1 => {
+                        hi = span.hi;
+                        break;
                     }
-                    return true;
+                    Some((Token::RBrace, _)) => depth = depth.saturating_sub(1),
+                    Some((Token::Error, _)) | None => return end,
+                    _ => {}
                 }
             }
         }
+        if let Some((Token::Semi, span)) = tokens.peek() {
+            hi = span.hi;
+            tokens.next();
+        }
+
+        end = offsets.offset(hi);
+    }
+}
+
+/// The import declarations at the top level of a source, for the queries that only read
+/// them.
+///
+/// When the rest of the source has no `import` keyword, only the leading import section is
+/// parsed, which for a large file is a fraction of a full parse. Errors after that section
+/// are then not reported.
+///
+/// # Example
+/// ```rust
+/// let imports = parse_imports("import { Socket } from \"phoenix\";\nlet a = 1;").unwrap();
+/// assert_eq!(imports.len(), 1);
+/// ```
+pub fn parse_imports(file_content: &str) -> Result<Vec<ModuleItem>, String> {
+    let end = leading_imports_end(file_content);
+    let source = if file_content[end..].contains("import") {
+        file_content
+    } else {
+        &file_content[..end]
+    };
+    let (module, _comments, _cm) = parse(source)?;
+
+    Ok(module
+        .body
+        .into_iter()
+        .filter(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+        .collect())
+}
+
+/// Whether an import adds nothing to `body`: its source is already imported and each of its
+/// specifiers is in one of the import declarations of the source. A side-effect import like
+/// `import "./polyfill"` is a duplicate of any import of its source.
+pub fn is_duplicate_import(new_import: &ModuleItem, body: &[ModuleItem]) -> bool {
+    let ModuleItem::ModuleDecl(ModuleDecl::Import(new_import_decl)) = new_import else {
+        return false;
+    };
+
+    let existing_imports: Vec<&ImportDecl> = body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(existing_import_decl))
+                if existing_import_decl.src.value == new_import_decl.src.value =>
+            {
+                Some(existing_import_decl)
+            }
+            _ => None,
+        })
+        .collect();
+
+    !existing_imports.is_empty()
+        && new_import_decl.specifiers.iter().all(|new_spec| {
+            existing_imports.iter().any(|existing_import_decl| {
+                existing_import_decl
+                    .specifiers
+                    .iter()
+                    .any(|existing_spec| specifier_equals(new_spec, existing_spec))
+            })
+        })
+}
+
+fn imported_name(named: &ImportNamedSpecifier) -> &str {
+    match &named.imported {
+        Some(ModuleExportName::Ident(ident)) => &ident.sym,
+        Some(ModuleExportName::Str(str)) => &str.value,
+        None => &named.local.sym,
+    }
+}
+
+/// What an import specifier binds from its module.
+#[derive(Debug, PartialEq, Eq)]
+enum ImportedBinding<'a> {
+    Default,
+    Namespace,
+    Named(&'a str),
+}
+
+/// The binding and the local name of a specifier. `import { default as A }` binds the
+/// default export like `import A`, while `import { A }` binds the export named `A`.
+fn imported_binding(spec: &ImportSpecifier) -> (ImportedBinding<'_>, &str) {
+    match spec {
+        ImportSpecifier::Named(named) => match imported_name(named) {
+            "default" => (ImportedBinding::Default, &named.local.sym),
+            name => (ImportedBinding::Named(name), &named.local.sym),
+        },
+        ImportSpecifier::Default(default) => (ImportedBinding::Default, &default.local.sym),
+        ImportSpecifier::Namespace(namespace) => (ImportedBinding::Namespace, &namespace.local.sym),
     }
-    false
 }
 
 fn specifier_equals(new_spec: &ImportSpecifier, existing_spec: &ImportSpecifier) -> bool {
-    match (new_spec, existing_spec) {
-        (ImportSpecifier::Named(new_named), ImportSpecifier::Named(existing_named)) => {
-            new_named.local.sym == existing_named.local.sym
-        }
-        (ImportSpecifier::Default(new_named), ImportSpecifier::Default(existing_named)) => {
-            new_named.local.sym == existing_named.local.sym
-        }
-        (ImportSpecifier::Namespace(new_named), ImportSpecifier::Namespace(existing_named)) => {
-            new_named.local.sym == existing_named.local.sym
+    imported_binding(new_spec) == imported_binding(existing_spec)
+}
+
+/// Whether only whitespace precedes `offset` on its line.
+pub fn begins_line(text: &str, offset: usize) -> bool {
+    text[..offset]
+        .rsplit('\n')
+        .next()
+        .is_some_and(|before| before.trim().is_empty())
+}
+
+/// The start of the line of `offset` when only whitespace precedes it, else `offset`.
+pub fn line_start(text: &str, offset: usize) -> usize {
+    if begins_line(text, offset) {
+        text[..offset].rfind('\n').map_or(0, |i| i + 1)
+    } else {
+        offset
+    }
+}
+
+/// The byte offset where the comments attached to the node starting at `pos` start, or the
+/// offset of the node when it has none.
+///
+/// The attached comments are those on the lines right above the node, so a header of the
+/// file separated from it by a blank line, or a comment ending the line of the previous
+/// statement, is not part of it.
+pub fn attached_comments_start(
+    file_content: &str,
+    comments: &SingleThreadedComments,
+    offsets: SpanOffsets,
+    pos: BytePos,
+) -> usize {
+    let mut start = offsets.offset(pos);
+    for comment in comments.get_leading(pos).unwrap_or_default().iter().rev() {
+        let (comment_lo, comment_hi) = offsets.range(comment.span);
+        if !begins_line(file_content, comment_lo)
+            || file_content[comment_hi..start].matches('\n').count() > 1
+        {
+            break;
         }
-        _ => false,
+        start = comment_lo;
     }
+    start
 }
 
 pub fn replace_four_spaces_with_tab(input: &str) -> String {
     input.replace("    ", "\t")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_common::Spanned;
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let Err(error) = parse("let a = 1;\nlet b = ;") else {
+            panic!("parsed invalid code");
+        };
+        assert!(error.starts_with("Failed to parse module: "));
+        assert!(error.ends_with(" at 2:8"), "{}", error);
+
+        let Err(diagnostics) = parse_with_diagnostics("let a = 1;\nlet b = ;", ModuleKind::Auto)
+        else {
+            panic!("parsed invalid code");
+        };
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert!(diagnostics[0].code_frame.contains("> 2 | let b = ;"));
+
+        let (_, diagnostics) = parse_with_diagnostics("let a = 1;", ModuleKind::Auto).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let code = "\u{feff}import \"phoenix_html\"\n// The socket\nimport { Socket } from \"phoenix\";\nimport data from \"./data.json\" with { type: \"json\" };\nconst hooks = {};\n";
+        assert_eq!(
+            &code[..leading_imports_end(code)],
+            "\u{feff}import \"phoenix_html\"\n// The socket\nimport { Socket } from \"phoenix\";\nimport data from \"./data.json\" with { type: \"json\" };"
+        );
+        assert_eq!(parse_imports(code).unwrap().len(), 3);
+
+        // Dynamic imports are not declarations, and the whole source is parsed then
+        let code = "import(\"./app\");\nimport { Socket } from \"phoenix\";\n";
+        assert_eq!(leading_imports_end(code), 0);
+        assert_eq!(parse_imports(code).unwrap().len(), 1);
+        assert!(parse_imports("import { from \"phoenix\";").is_err());
+    }
+
+    #[test]
+    fn test_span_offsets() {
+        // A byte order mark and multibyte characters before the spans
+        let code = "\u{feff}let title = \"🚀 Réunion\";\nlet x = 1;";
+        let (module, _comments, cm) = parse(code).unwrap();
+        let offsets = SpanOffsets::new(&cm);
+        let (start, end) = offsets.range(module.body[1].span());
+        assert_eq!(offsets.text_start(), 3);
+        assert_eq!(&code[start..end], "let x = 1;");
+
+        let code = code.replace("1;", ";");
+        let Err(diagnostics) = parse_with_diagnostics(&code, ModuleKind::Auto) else {
+            panic!("parsed invalid code");
+        };
+        assert_eq!(&code[diagnostics[0].start..diagnostics[0].end], ";");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 8));
+        assert!(diagnostics[0]
+            .code_frame
+            .starts_with("  1 | let title = \"🚀 Réunion\";\n> 2 | let x = ;\n    |         ^"));
+    }
+
+    #[test]
+    fn test_is_duplicate_import_with_mixed_specifiers() {
+        let (module, _comments, _cm) = parse(
+            r#"import Chart, * as charts from "chart.js";
+import { Tooltip as Tip } from "chart.js";
+"#,
+        )
+        .unwrap();
+        let is_duplicate = |code: &str| {
+            let (import, _comments, _cm) = parse(code).unwrap();
+            is_duplicate_import(&import.body[0], &module.body)
+        };
+
+        assert!(is_duplicate("import * as charts from \"chart.js\";"));
+        assert!(is_duplicate(
+            "import { default as Chart } from \"chart.js\";"
+        ));
+        assert!(is_duplicate(
+            "import Chart, { Tooltip as Tip } from \"chart.js\";"
+        ));
+        assert!(is_duplicate(
+            "import { \"Tooltip\" as Tip } from \"chart.js\";"
+        ));
+
+        // A named export is not the default one, and the namespace only binds its name
+        assert!(!is_duplicate("import { Chart } from \"chart.js\";"));
+        assert!(!is_duplicate(
+            "import { default as Tip } from \"chart.js\";"
+        ));
+        assert!(!is_duplicate("import * as Chart from \"chart.js\";"));
+        assert!(!is_duplicate("import charts from \"chart.js\";"));
+        assert!(!is_duplicate("import Chart from \"chart\";"));
+    }
+
+    #[test]
+    fn test_detect_module_kind() {
+        assert_eq!(
+            detect_module_kind("const path = require(\"path\");\nmodule.exports = {};"),
+            ModuleKind::Script
+        );
+        assert_eq!(
+            detect_module_kind("import x from \"x\";\nmodule.exports = x;"),
+            ModuleKind::Module
+        );
+        assert_eq!(detect_module_kind("let a = 1;"), ModuleKind::Module);
+        assert_eq!(
+            ModuleKind::from_path("postcss.config.cjs"),
+            ModuleKind::Script
+        );
+        assert_eq!(ModuleKind::from_path("app.js"), ModuleKind::Auto);
+
+        // Top-level `return` is only valid in a CommonJS script
+        let code = "if (!process.env.CI) return;\nmodule.exports = {};";
+        let (mut module, comments, cm) = parse(code).unwrap();
+        assert_eq!(
+            code_gen_from_ast_module(&mut module, comments, cm),
+            "if (!process.env.CI) return;\nmodule.exports = {};\n"
+        );
+    }
+
+    #[test]
+    fn test_code_gen_edit() {
+        let code = "// hooks\nlet hooks = {Copy}";
+        assert_eq!(code_gen_from_ast_vist(code, DropSpan).unwrap(), code);
+
+        let (mut module, comments, cm) = parse(code).unwrap();
+        let original = module.clone();
+        let unchanged = code_gen_edit(code, &original, &mut module, comments.clone(), cm.clone());
+        assert_eq!(unchanged, code);
+
+        let (other, _, _) = parse("let other = 1;").unwrap();
+        module.body.extend(other.body);
+        let updated = code_gen_edit(code, &original, &mut module, comments, cm);
+        assert!(updated.ends_with("let other = 1;\n"), "{}", updated);
+    }
+
+    #[test]
+    fn test_parse_expr() {
+        assert!(matches!(
+            *parse_expr("document.documentElement.lang").unwrap(),
+            Expr::Member(_)
+        ));
+        assert!(matches!(
+            *parse_expr("{ mounted() { this.el.focus(); } }").unwrap(),
+            Expr::Object(_)
+        ));
+        assert!(parse_expr("a); (b").is_err());
+        assert!(parse_expr("let a = 1").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Generation of the `hooks/index.js` aggregator of a multi-file hook layout, which imports
+//! every hook file of the directory and exports them as one object:
+//!
+//! ```javascript
+//! import CopyToClipboard from "./copy_to_clipboard";
+//! import Chart from "./chart";
+//! const hooks = {
+//!     CopyToClipboard,
+//!     Chart
+//! };
+//! export default hooks;
+//! ```
+//!
+//! The `app.js` then spreads it into the LiveSocket hooks, see
+//! [`extend_hook_object_to_ast`](super::ast::extend_hook_object_to_ast).
+
+use crate::parsers::javascript::helpers::*;
+use swc_common::{SyntaxContext, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitMutWith;
+
+/// The variable the aggregator declares the hooks object with.
+const HOOKS_VAR: &str = "hooks";
+
+/// The extensions stripped from the file names in the import sources.
+const EXTENSIONS: [&str; 6] = ["js", "mjs", "cjs", "jsx", "ts", "tsx"];
+
+/// The hook name and the import source of a hook file, e.g. `CopyToClipboard` and
+/// `./copy_to_clipboard` for `copy_to_clipboard.js`. `None` for the aggregator itself.
+fn hook_of_file(file_name: &str) -> Result<Option<(String, String)>, String> {
+    let path = file_name.trim_start_matches("./");
+    let path = match path.rsplit_once('.') {
+        Some((stem, extension)) if EXTENSIONS.contains(&extension) => stem,
+        _ => path,
+    };
+    let base = path.rsplit('/').next().unwrap_or(path);
+    if base == "index" {
+        return Ok(None);
+    }
+
+    let mut name: String = base
+        .split(|c: char| !c.is_alphanumeric() && c != '$')
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    if Ident::verify_symbol(&name).is_err() {
+        return Err(format!("No hook name can be derived from `{}`", file_name));
+    }
+
+    Ok(Some((name, format!("./{}", path))))
+}
+
+/// The local name of the default import of a source.
+fn default_import_local(module: &Module, source: &str) -> Option<String> {
+    module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if import.src.value == *source => import
+            .specifiers
+            .iter()
+            .find_map(|specifier| match specifier {
+                ImportSpecifier::Default(default) => Some(default.local.sym.to_string()),
+                _ => None,
+            }),
+        _ => None,
+    })
+}
+
+fn is_import_local(module: &Module, name: &str) -> bool {
+    module.body.iter().any(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+            import.specifiers.iter().any(|specifier| match specifier {
+                ImportSpecifier::Named(named) => named.local.sym == *name,
+                ImportSpecifier::Default(default) => default.local.sym == *name,
+                ImportSpecifier::Namespace(namespace) => namespace.local.sym == *name,
+            })
+        }
+        _ => false,
+    })
+}
+
+fn parse_items(code: &str) -> Result<Vec<ModuleItem>, String> {
+    let (mut module, _comments, _cm) = parse(code)?;
+    module.visit_mut_with(&mut DropSpan);
+    Ok(module.body)
+}
+
+fn has_default_export(module: &Module) -> bool {
+    module.body.iter().any(|item| {
+        matches!(
+            item,
+            ModuleItem::ModuleDecl(
+                ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_)
+            )
+        )
+    })
+}
+
+/// Finds the exported hooks object: the `hooks` variable or the `export default {...}`.
+fn hooks_object(module: &mut Module) -> Option<&mut ObjectLit> {
+    let has_hooks_var = find_var_object_literal(module, HOOKS_VAR).is_some();
+    if has_hooks_var {
+        return find_var_object_literal(module, HOOKS_VAR);
+    }
+
+    module.body.iter_mut().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => match &mut *export.expr {
+            Expr::Object(object) => Some(object),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Generates or updates a `hooks/index.js` aggregator for the given hook files.
+///
+/// Every file gets a default import, unless its source is already imported by default, and an
+/// entry named after the file in the hooks object, unless an entry of this name exists, so
+/// entries added by hand are kept. The hooks object is the `hooks` variable or an object
+/// exported by default, and is created with its `export default hooks` when there is none.
+///
+/// # Arguments
+/// * `file_content` - The current aggregator, empty to generate it.
+/// * `file_names` - The hook files of the directory relative to it, e.g. `copy_to_clipboard.js`
+///   or `charts/line_chart.js`. The aggregator itself, `index.js`, is skipped.
+///
+/// # Returns
+/// * `Ok(String)` - The generated or updated aggregator.
+/// * `Err(String)` - If the aggregator can not be parsed, a file name has no usable name,
+///   the hook name is already imported from another source, or the aggregator has a default
+///   export which is not the hooks object.
+///
+/// # Example
+/// ```rust
+/// let result = generate_hooks_index("", &["copy_to_clipboard.js".into()]).unwrap();
+/// assert!(result.contains("import CopyToClipboard from \"./copy_to_clipboard\";"));
+/// assert!(result.contains("export default hooks;"));
+/// ```
+pub fn generate_hooks_index(file_content: &str, file_names: &[String]) -> Result<String, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+
+    let mut entries = vec![];
+    for file_name in file_names {
+        let Some((name, source)) = hook_of_file(file_name)? else {
+            continue;
+        };
+
+        let local = match default_import_local(&module, &source) {
+            Some(local) => local,
+            None if is_import_local(&module, &name) => {
+                return Err(format!("`{}` is already imported", name));
+            }
+            None => {
+                let is_import = |item: &ModuleItem| {
+                    matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_)))
+                };
+                let index = module
+                    .body
+                    .iter()
+                    .rposition(is_import)
+                    .map_or(0, |index| index + 1);
+                let import = parse_items(&format!("import {} from \"{}\";", name, source))?;
+                module.body.splice(index..index, import);
+                name.clone()
+            }
+        };
+        entries.push((name, local));
+    }
+
+    if hooks_object(&mut module).is_none() {
+        if has_default_export(&module) {
+            return Err("The default export is not the hooks object".into());
+        }
+        let declaration = parse_items(&format!(
+            "const {} = {{}};\nexport default {};",
+            HOOKS_VAR, HOOKS_VAR
+        ))?;
+        module.body.extend(declaration);
+    }
+
+    let object = hooks_object(&mut module).expect("The hooks object was just created");
+    for (name, local) in entries {
+        if object
+            .props
+            .iter()
+            .any(|prop| prop_key_name(prop).as_ref() == Some(&name))
+        {
+            continue;
+        }
+
+        let prop = if name == local {
+            PropOrSpread::Prop(Box::new(Prop::Shorthand(Ident::new(
+                name.into(),
+                DUMMY_SP,
+                SyntaxContext::empty(),
+            ))))
+        } else {
+            new_key_value_prop(
+                &name,
+                Box::new(Expr::Ident(Ident::new(
+                    local.into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                ))),
+            )
+        };
+        object.props.push(prop);
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_hooks_index() {
+        let files = [
+            "copy-to-clipboard.js",
+            "index.js",
+            "charts/line_chart.ts",
+            "2fa.js",
+        ]
+        .map(String::from);
+
+        let result = generate_hooks_index("", &files).unwrap();
+        assert_eq!(
+            result,
+            r#"import CopyToClipboard from "./copy-to-clipboard";
+import LineChart from "./charts/line_chart";
+import _2fa from "./2fa";
+const hooks = {
+    CopyToClipboard,
+    LineChart,
+    _2fa
+};
+export default hooks;
+"#
+        );
+
+        // Running it again changes nothing
+        assert_eq!(generate_hooks_index(&result, &files).unwrap(), result);
+    }
+
+    #[test]
+    fn test_generate_hooks_index_keeps_user_entries() {
+        let code = r#"import Copy from "./copy";
+import { Tooltip } from "tippy-hooks";
+export default {
+    Copy: { mounted() {} },
+    Tooltip
+};
+"#;
+
+        let result = generate_hooks_index(code, &["copy.js".into(), "chart.js".into()]).unwrap();
+        assert_eq!(
+            result,
+            r#"import Copy from "./copy";
+import { Tooltip } from "tippy-hooks";
+import Chart from "./chart";
+export default {
+    Copy: {
+        mounted () {}
+    },
+    Tooltip,
+    Chart
+};
+"#
+        );
+
+        assert!(generate_hooks_index(code, &["tooltip.js".into()]).is_err());
+        assert!(generate_hooks_index("export default 1;", &["chart.js".into()]).is_err());
+        assert!(generate_hooks_index("", &["-.js".into()]).is_err());
+    }
+}
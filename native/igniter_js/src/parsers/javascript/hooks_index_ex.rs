@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::hooks_index::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn generate_hooks_index_nif(
+    env: Env,
+    file_content: String,
+    file_names: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let result = generate_hooks_index(&file_content, &file_names);
+
+        encode_edit(
+            env,
+            atoms::generate_hooks_index_nif(),
+            &file_content,
+            result,
+        )
+    })
+}
@@ -0,0 +1,359 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Extraction of the dependency graph of an entrypoint, e.g. which vendor files an `app.js`
+//! pulls in, so installers know them before modifying them.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::parse;
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+use std::collections::{HashMap, HashSet, VecDeque};
+use swc_common::Span;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Where an import source points to.
+///
+/// On the Elixir side these are the atoms `:package` and `:file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum ImportKind {
+    /// A bare specifier resolved from the packages, e.g. `"phoenix"`.
+    Package,
+    /// A relative or absolute path, e.g. `"../vendor/topbar"`.
+    File,
+}
+
+/// The syntax that imports a source.
+///
+/// On the Elixir side these are the atoms `:import`, `:export`, `:dynamic` and `:require`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum ImportStatement {
+    /// `import ... from "source"` and `import "source"`.
+    Import,
+    /// `export ... from "source"`.
+    Export,
+    /// `import("source")`.
+    Dynamic,
+    /// `require("source")`.
+    Require,
+}
+
+/// An imported source found by [`analyze_imports`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSource {
+    pub source: String,
+    pub kind: ImportKind,
+    pub statement: ImportStatement,
+    /// The path of the file containing the import.
+    pub importer: String,
+    /// The path of the imported file when it is one of the given files.
+    pub resolved: Option<String>,
+    /// The location of the source string in the importing file.
+    pub location: SourceLocation,
+}
+
+/// The extensions tried, in order, to resolve a file import without one.
+const EXTENSIONS: &[&str] = &["", ".js", ".mjs", ".cjs", ".ts", "/index.js", "/index.ts"];
+
+#[derive(Default)]
+pub(crate) struct ImportCollector {
+    pub(crate) imports: Vec<(String, ImportStatement, Span)>,
+}
+
+impl ImportCollector {
+    fn push(&mut self, source: &Str, statement: ImportStatement) {
+        self.imports
+            .push((source.value.to_string(), statement, source.span));
+    }
+}
+
+impl Visit for ImportCollector {
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        self.push(&import.src, ImportStatement::Import);
+    }
+
+    fn visit_named_export(&mut self, export: &NamedExport) {
+        if let Some(src) = &export.src {
+            self.push(src, ImportStatement::Export);
+        }
+    }
+
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        self.push(&export.src, ImportStatement::Export);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let statement = match &call.callee {
+            Callee::Import(_) => Some(ImportStatement::Dynamic),
+            Callee::Expr(callee) if matches!(&**callee, Expr::Ident(ident) if ident.sym == "require") => {
+                Some(ImportStatement::Require)
+            }
+            _ => None,
+        };
+
+        if let (Some(statement), [arg]) = (statement, call.args.as_slice()) {
+            match &*arg.expr {
+                Expr::Lit(Lit::Str(source)) => self.push(source, statement),
+                Expr::Tpl(tpl) if tpl.exprs.is_empty() && tpl.quasis.len() == 1 => {
+                    let quasi = &tpl.quasis[0];
+                    let source = quasi.cooked.as_ref().map_or(&quasi.raw, |cooked| cooked);
+                    self.imports
+                        .push((source.to_string(), statement, quasi.span));
+                }
+                _ => {}
+            }
+        }
+
+        call.visit_children_with(self);
+    }
+}
+
+fn import_kind(source: &str) -> ImportKind {
+    if source.starts_with("./")
+        || source.starts_with("../")
+        || source.starts_with('/')
+        || source == "."
+        || source == ".."
+    {
+        ImportKind::File
+    } else {
+        ImportKind::Package
+    }
+}
+
+/// Joins an import source to the directory of the importing file, resolving `.` and `..`.
+fn join_path(importer: &str, source: &str) -> String {
+    let mut parts: Vec<&str> = if source.starts_with('/') {
+        vec![]
+    } else {
+        let mut parts: Vec<&str> = importer.split('/').collect();
+        parts.pop();
+        parts
+    };
+
+    for part in source.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if matches!(parts.last(), None | Some(&"..")) {
+                    parts.push("..");
+                } else {
+                    parts.pop();
+                }
+            }
+            part => parts.push(part),
+        }
+    }
+
+    let joined = parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    if source.starts_with('/') {
+        format!("/{}", joined)
+    } else {
+        joined
+    }
+}
+
+/// The known file a file import points to, trying the usual extensions.
+fn resolve(importer: &str, source: &str, exists: impl Fn(&str) -> bool) -> Option<String> {
+    let path = join_path(importer, source);
+    EXTENSIONS
+        .iter()
+        .map(|extension| format!("{}{}", path, extension))
+        .find(|candidate| exists(candidate))
+}
+
+/// Lists every source imported by an entrypoint and by the files it imports.
+///
+/// # Arguments
+/// * `file_content` - The source of the entrypoint.
+/// * `path` - The path of the entrypoint, e.g. `"assets/js/app.js"`.
+/// * `files` - The other files by path, in the same form as `path`, to follow the file
+///   imports into. Imports of files that are not given are reported without `resolved`.
+///
+/// # Returns
+/// * `Ok(Vec<ImportedSource>)` - The imports of the entrypoint first, then the imports of
+///   each resolved file in the order they are found. Static imports, re-exports, dynamic
+///   `import()` and `require()` calls with a string source are reported.
+/// * `Err(String)` - If the entrypoint or a resolved file can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let files = HashMap::from([(
+///     "assets/vendor/topbar.js".to_string(),
+///     "export default {};".to_string(),
+/// )]);
+/// let code = r#"import topbar from "../vendor/topbar"; import "phoenix_html";"#;
+/// let imports = analyze_imports(code, "assets/js/app.js", &files).unwrap();
+/// assert_eq!(imports[0].resolved.as_deref(), Some("assets/vendor/topbar.js"));
+/// assert_eq!(imports[1].kind, ImportKind::Package);
+/// ```
+pub fn analyze_imports(
+    file_content: &str,
+    path: &str,
+    files: &HashMap<String, String>,
+) -> Result<Vec<ImportedSource>, String> {
+    let mut imports = vec![];
+    let mut visited = HashSet::from([path.to_string()]);
+    let mut queue = VecDeque::from([(path.to_string(), file_content)]);
+
+    while let Some((importer, content)) = queue.pop_front() {
+        let (module, _comments, cm) =
+            parse(content).map_err(|error| format!("{}: {}", importer, error))?;
+        let mut collector = ImportCollector::default();
+        module.visit_with(&mut collector);
+
+        for (source, statement, span) in collector.imports {
+            let kind = import_kind(&source);
+            let resolved = match kind {
+                ImportKind::File => resolve(&importer, &source, |candidate| {
+                    candidate == path || files.contains_key(candidate)
+                }),
+                ImportKind::Package => None,
+            };
+
+            if let Some(resolved) = &resolved {
+                if visited.insert(resolved.clone()) {
+                    queue.push_back((resolved.clone(), files[resolved].as_str()));
+                }
+            }
+
+            imports.push(ImportedSource {
+                source,
+                kind,
+                statement,
+                importer: importer.clone(),
+                resolved,
+                location: SourceLocation::from_span(&cm, span),
+            });
+        }
+    }
+
+    Ok(imports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_imports() {
+        let app = r#"import "phoenix_html";
+import { Socket } from "phoenix";
+import topbar from "../vendor/topbar";
+import { hooks } from "./hooks";
+export * from "./utils.js";
+const chart = () => import(`./chart`);
+const legacy = require("legacy-lib");
+"#;
+        let files = HashMap::from([
+            (
+                "assets/vendor/topbar.js".to_string(),
+                "export default {};".to_string(),
+            ),
+            (
+                "assets/js/hooks/index.js".to_string(),
+                "import Copy from \"./copy\";\nimport \"../app\";\nexport const hooks = { Copy };"
+                    .to_string(),
+            ),
+        ]);
+
+        let imports = analyze_imports(app, "assets/js/app.js", &files).unwrap();
+        let summary: Vec<_> = imports
+            .iter()
+            .map(|import| {
+                (
+                    import.source.as_str(),
+                    import.kind,
+                    import.statement,
+                    import.importer.as_str(),
+                    import.resolved.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (
+                    "phoenix_html",
+                    ImportKind::Package,
+                    ImportStatement::Import,
+                    "assets/js/app.js",
+                    None
+                ),
+                (
+                    "phoenix",
+                    ImportKind::Package,
+                    ImportStatement::Import,
+                    "assets/js/app.js",
+                    None
+                ),
+                (
+                    "../vendor/topbar",
+                    ImportKind::File,
+                    ImportStatement::Import,
+                    "assets/js/app.js",
+                    Some("assets/vendor/topbar.js")
+                ),
+                (
+                    "./hooks",
+                    ImportKind::File,
+                    ImportStatement::Import,
+                    "assets/js/app.js",
+                    Some("assets/js/hooks/index.js")
+                ),
+                (
+                    "./utils.js",
+                    ImportKind::File,
+                    ImportStatement::Export,
+                    "assets/js/app.js",
+                    None
+                ),
+                (
+                    "./chart",
+                    ImportKind::File,
+                    ImportStatement::Dynamic,
+                    "assets/js/app.js",
+                    None
+                ),
+                (
+                    "legacy-lib",
+                    ImportKind::Package,
+                    ImportStatement::Require,
+                    "assets/js/app.js",
+                    None
+                ),
+                (
+                    "./copy",
+                    ImportKind::File,
+                    ImportStatement::Import,
+                    "assets/js/hooks/index.js",
+                    None
+                ),
+                (
+                    "../app",
+                    ImportKind::File,
+                    ImportStatement::Import,
+                    "assets/js/hooks/index.js",
+                    Some("assets/js/app.js")
+                ),
+            ]
+        );
+
+        // The location is the one of the source string, quotes included
+        let location = &imports[1].location;
+        assert_eq!(&app[location.start..location.end], "\"phoenix\"");
+        assert_eq!((location.line, location.column), (2, 23));
+
+        assert!(analyze_imports("import {", "app.js", &files).is_err());
+    }
+}
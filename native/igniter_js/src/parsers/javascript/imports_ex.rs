@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_ex::SourceLocationResult;
+use crate::parsers::javascript::imports::*;
+
+use rustler::{Encoder, Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct ImportedSourceResult {
+    pub source: String,
+    pub kind: ImportKind,
+    pub statement: ImportStatement,
+    pub importer: String,
+    pub resolved: Option<String>,
+    pub location: SourceLocationResult,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn analyze_imports_nif(
+    env: Env,
+    file_content: String,
+    path: String,
+    files: HashMap<String, String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::analyze_imports_nif();
+
+        match analyze_imports(&file_content, &path, &files) {
+            Ok(imports) => {
+                let result: Vec<ImportedSourceResult> = imports
+                    .into_iter()
+                    .map(|import| ImportedSourceResult {
+                        source: import.source,
+                        kind: import.kind,
+                        statement: import.statement,
+                        importer: import.importer,
+                        resolved: import.resolved,
+                        location: import.location.into(),
+                    })
+                    .collect();
+                encode_response(env, atoms::ok(), fn_atom, result.encode(env))
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -0,0 +1,485 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Matchers
+//!
+//! A small declarative query and patch API: a [`Matcher`] selects expressions of a module,
+//! a key path walks into the object literals they hold and a [`PatchOperation`] reads,
+//! inserts, replaces or deletes what it reached. For example the hooks of the LiveSocket
+//! options are
+//!
+//! ```rust
+//! let spec = QuerySpec {
+//!     find: Matcher::Var("liveSocket".into()),
+//!     path: vec!["hooks".into()],
+//!     operation: PatchOperation::Query,
+//! };
+//! ```
+//!
+//! Errors are the messages of [`FindCondition`], like the other transforms.
+
+use crate::parsers::javascript::ast::FindCondition;
+use crate::parsers::javascript::helpers::*;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifTaggedEnum};
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, SourceMap, SyntaxContext, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+/// The expressions a query starts from.
+///
+/// On the Elixir side these are `{:var, "liveSocket"}` and `{:callee, "Alpine.plugin"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum Matcher {
+    /// The initializer of a top-level variable, e.g. the `new LiveSocket(...)` of
+    /// `let liveSocket = new LiveSocket(...)`.
+    Var(String),
+    /// The calls, and `new` expressions, of a callee anywhere in the module, written as a
+    /// dotted path, e.g. `topbar.config` or `LiveSocket`.
+    Callee(String),
+}
+
+/// What is done with the expressions a query reached.
+///
+/// On the Elixir side these are `:query`, `{:insert, "key", "code"}`, `{:replace, "code"}`
+/// and `:delete`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum PatchOperation {
+    /// Only returns the matches.
+    Query,
+    /// Adds a `key: code` property to the object literal, unless it has the key.
+    Insert(String, String),
+    /// Replaces the expression with `code`.
+    Replace(String),
+    /// Removes the property the key path ends at, or without a key path the variable
+    /// declaration or the call statement.
+    Delete,
+}
+
+/// A query: where to start, the keys to walk into and the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct QuerySpec {
+    pub find: Matcher,
+    pub path: Vec<String>,
+    pub operation: PatchOperation,
+}
+
+/// The result of [`query_and_patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct QueryResult {
+    /// The code after the operation.
+    pub code: String,
+    /// Whether the code differs from the source, i.e. whether there is anything to write.
+    pub changed: bool,
+    /// The code of every expression the query reached, before the operation.
+    pub matches: Vec<String>,
+}
+
+/// The dotted path of a callee made of identifiers, e.g. `topbar.config`.
+pub fn callee_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(member) => match &member.prop {
+            MemberProp::Ident(prop) => Some(format!("{}.{}", callee_path(&member.obj)?, prop.sym)),
+            _ => None,
+        },
+        Expr::Paren(paren) => callee_path(&paren.expr),
+        _ => None,
+    }
+}
+
+impl Matcher {
+    /// Whether a call or `new` expression matches a [`Matcher::Callee`].
+    fn matches_callee(&self, expr: &Expr) -> bool {
+        let Matcher::Callee(path) = self else {
+            return false;
+        };
+
+        let callee = match expr {
+            Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                ..
+            }) => callee,
+            Expr::New(new_expr) => &new_expr.callee,
+            _ => return false,
+        };
+        callee_path(callee).as_deref() == Some(path.as_str())
+    }
+}
+
+/// The object literal an expression holds: itself, or the last object literal argument of a
+/// call or `new` expression, e.g. the options of `new LiveSocket("/live", Socket, {...})`.
+pub fn held_object(expr: &mut Expr) -> Option<&mut ObjectLit> {
+    let args = match expr {
+        Expr::Object(object) => return Some(object),
+        Expr::Paren(paren) => return held_object(&mut paren.expr),
+        Expr::Call(call) => &mut call.args,
+        Expr::New(new_expr) => new_expr.args.as_mut()?,
+        _ => return None,
+    };
+
+    args.iter_mut()
+        .rev()
+        .find_map(|arg| match (&arg.spread, &mut *arg.expr) {
+            (None, Expr::Object(object)) => Some(object),
+            _ => None,
+        })
+}
+
+/// Walks the key path from an expression, into the values of the `key: value` properties.
+pub fn resolve_key_path<'a>(
+    expr: &'a mut Expr,
+    path: &[String],
+) -> Result<&'a mut Expr, FindCondition> {
+    let Some((key, rest)) = path.split_first() else {
+        return Ok(expr);
+    };
+
+    let object = held_object(expr).ok_or(FindCondition::FoundError(format!(
+        "No object literal to look up `{}` in",
+        key
+    )))?;
+    let prop = object
+        .props
+        .iter_mut()
+        .find(|prop| prop_key_name(prop).as_deref() == Some(key.as_str()))
+        .ok_or(FindCondition::NotFound(format!(
+            "No `{}` key was found",
+            key
+        )))?;
+
+    match prop {
+        PropOrSpread::Prop(prop) => match &mut **prop {
+            Prop::KeyValue(key_value) => resolve_key_path(&mut key_value.value, rest),
+            _ => Err(FindCondition::FoundError(format!(
+                "The `{}` key has no value to walk into",
+                key
+            ))),
+        },
+        PropOrSpread::Spread(_) => unreachable!("spreads have no key"),
+    }
+}
+
+/// The code of an expression, on its own.
+fn expr_code(expr: &Expr, cm: &Lrc<SourceMap>) -> String {
+    const PREFIX: &str = "const _ = ";
+    let mut module = Module {
+        span: DUMMY_SP,
+        body: vec![ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            kind: VarDeclKind::Const,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(BindingIdent::from(Ident::new(
+                    "_".into(),
+                    DUMMY_SP,
+                    SyntaxContext::empty(),
+                ))),
+                init: Some(Box::new(expr.clone())),
+                definite: false,
+            }],
+            ..Default::default()
+        }))))],
+        shebang: None,
+    };
+
+    let code = code_gen_from_ast_module(&mut module, SingleThreadedComments::default(), cm.clone());
+    code.trim_end()
+        .trim_start_matches(PREFIX)
+        .trim_end_matches(';')
+        .to_string()
+}
+
+struct QueryVisitor<'a> {
+    spec: &'a QuerySpec,
+    cm: Lrc<SourceMap>,
+    matches: Vec<String>,
+    error: Option<FindCondition>,
+}
+
+impl QueryVisitor<'_> {
+    /// Applies the operation to a matched expression. Returns `true` when the expression
+    /// itself is to be removed, which only a [`PatchOperation::Delete`] without a key path does.
+    fn apply(&mut self, expr: &mut Expr) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        match self.apply_at_path(expr) {
+            Ok(remove) => remove,
+            Err(error) => {
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+
+    fn apply_at_path(&mut self, expr: &mut Expr) -> Result<bool, FindCondition> {
+        let path = &self.spec.path;
+
+        if let (PatchOperation::Delete, Some((key, parent_path))) =
+            (&self.spec.operation, path.split_last())
+        {
+            let parent = resolve_key_path(expr, parent_path)?;
+            let object = held_object(parent).ok_or(FindCondition::FoundError(format!(
+                "No object literal to delete `{}` from",
+                key
+            )))?;
+            let index = object
+                .props
+                .iter()
+                .position(|prop| prop_key_name(prop).as_deref() == Some(key.as_str()))
+                .ok_or(FindCondition::NotFound(format!(
+                    "No `{}` key was found",
+                    key
+                )))?;
+
+            if let PropOrSpread::Prop(prop) = object.props.remove(index) {
+                if let Prop::KeyValue(key_value) = *prop {
+                    self.matches.push(expr_code(&key_value.value, &self.cm));
+                }
+            }
+            return Ok(false);
+        }
+
+        let target = resolve_key_path(expr, path)?;
+        self.matches.push(expr_code(target, &self.cm));
+
+        match &self.spec.operation {
+            PatchOperation::Query => {}
+            PatchOperation::Insert(key, code) => {
+                let value = parse_expr(code).map_err(FindCondition::FoundError)?;
+                let object = held_object(target).ok_or(FindCondition::FoundError(format!(
+                    "No object literal to insert `{}` into",
+                    key
+                )))?;
+                if !object
+                    .props
+                    .iter()
+                    .any(|prop| prop_key_name(prop).as_deref() == Some(key.as_str()))
+                {
+                    object.props.push(new_key_value_prop(key, value));
+                }
+            }
+            PatchOperation::Replace(code) => {
+                *target = *parse_expr(code).map_err(FindCondition::FoundError)?;
+            }
+            PatchOperation::Delete => return Ok(true),
+        }
+
+        Ok(false)
+    }
+}
+
+impl VisitMut for QueryVisitor<'_> {
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        if let Matcher::Var(name) = &self.spec.find {
+            let mut index = 0;
+            while index < module.body.len() {
+                let var_decl = match &mut module.body[index] {
+                    ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => var_decl,
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                        decl: Decl::Var(var_decl),
+                        ..
+                    })) => var_decl,
+                    _ => {
+                        index += 1;
+                        continue;
+                    }
+                };
+
+                var_decl.decls.retain_mut(|declarator| {
+                    let is_match =
+                        matches!(&declarator.name, Pat::Ident(binding) if binding.id.sym == *name);
+                    match &mut declarator.init {
+                        Some(init) if is_match => !self.apply(init),
+                        _ => true,
+                    }
+                });
+
+                if var_decl.decls.is_empty() {
+                    module.body.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+            return;
+        }
+
+        module.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+
+        // A deleted call is removed with its statement
+        items.retain(|item| {
+            !matches!(item, ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) if expr.is_invalid())
+        });
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        stmts
+            .retain(|stmt| !matches!(stmt, Stmt::Expr(ExprStmt { expr, .. }) if expr.is_invalid()));
+    }
+
+    fn visit_mut_expr_stmt(&mut self, stmt: &mut ExprStmt) {
+        if self.spec.find.matches_callee(&stmt.expr) {
+            stmt.expr.visit_mut_children_with(self);
+            if self.apply(&mut stmt.expr) {
+                // Marks the statement for the removal in the enclosing list
+                *stmt.expr = Expr::Invalid(Invalid { span: DUMMY_SP });
+            }
+            return;
+        }
+
+        stmt.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        if self.spec.find.matches_callee(expr) && self.apply(expr) {
+            self.error = Some(FindCondition::FoundError(
+                "Only a call on its own statement can be deleted".into(),
+            ));
+        }
+    }
+}
+
+/// Runs a query on a module and applies its operation to every expression it reached.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `spec` - The matcher, the key path and the operation.
+///
+/// # Returns
+/// * `Ok(QueryResult)` - The updated code, the same code for [`PatchOperation::Query`], and
+///   the code of what the query reached.
+/// * `Err(String)` - If the source or a code snippet can not be parsed, nothing matched, a key
+///   of the path is missing or a value is not an object literal.
+///
+/// # Example
+/// ```rust
+/// let code = "topbar.config({ barColors: { 0: \"#29d\" } });";
+/// let spec = QuerySpec {
+///     find: Matcher::Callee("topbar.config".into()),
+///     path: vec!["barColors".into()],
+///     operation: PatchOperation::Insert("1".into(), "\"#fff\"".into()),
+/// };
+/// let result = query_and_patch(code, &spec).unwrap();
+/// assert!(result.code.contains("1: \"#fff\""));
+/// ```
+pub fn query_and_patch(file_content: &str, spec: &QuerySpec) -> Result<QueryResult, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut visitor = QueryVisitor {
+        spec,
+        cm: cm.clone(),
+        matches: vec![],
+        error: None,
+    };
+    module.visit_mut_with(&mut visitor);
+
+    if let Some(error) = visitor.error {
+        return Err(error.message().to_string());
+    }
+    if visitor.matches.is_empty() {
+        return Err(FindCondition::NotFound("".into()).message().to_string());
+    }
+
+    let code = code_gen_edit(file_content, &original, &mut module, comments, cm);
+    Ok(QueryResult {
+        changed: code != file_content,
+        code,
+        matches: visitor.matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(find: Matcher, path: &[&str], operation: PatchOperation) -> QuerySpec {
+        QuerySpec {
+            find,
+            path: path.iter().map(|key| key.to_string()).collect(),
+            operation,
+        }
+    }
+
+    const CODE: &str = r#"let liveSocket = new LiveSocket("/live", Socket, {
+    hooks: { Copy: CopyHook },
+    params: { _csrf_token: csrfToken }
+});
+Alpine.plugin(focus);
+Alpine.plugin(collapse);
+"#;
+
+    #[test]
+    fn test_query() {
+        let var = Matcher::Var("liveSocket".into());
+        let result =
+            query_and_patch(CODE, &spec(var.clone(), &["hooks"], PatchOperation::Query)).unwrap();
+        assert_eq!(result.matches, ["{\n    Copy: CopyHook\n}"]);
+
+        let callee = Matcher::Callee("Alpine.plugin".into());
+        let result = query_and_patch(CODE, &spec(callee, &[], PatchOperation::Query)).unwrap();
+        assert_eq!(
+            result.matches,
+            ["Alpine.plugin(focus)", "Alpine.plugin(collapse)"]
+        );
+
+        assert!(
+            query_and_patch(CODE, &spec(var.clone(), &["other"], PatchOperation::Query)).is_err()
+        );
+        assert!(query_and_patch(
+            CODE,
+            &spec(var, &["hooks", "Copy", "x"], PatchOperation::Query)
+        )
+        .is_err());
+        let missing = Matcher::Var("socket".into());
+        assert!(query_and_patch(CODE, &spec(missing, &[], PatchOperation::Query)).is_err());
+    }
+
+    #[test]
+    fn test_patch() {
+        let var = Matcher::Var("liveSocket".into());
+
+        let insert = PatchOperation::Insert("Chart".into(), "ChartHook".into());
+        let result = query_and_patch(CODE, &spec(var.clone(), &["hooks"], insert.clone())).unwrap();
+        assert!(result
+            .code
+            .contains("Copy: CopyHook,\n        Chart: ChartHook\n"));
+        // Inserting an existing key changes nothing
+        let again = query_and_patch(&result.code, &spec(var.clone(), &["hooks"], insert)).unwrap();
+        assert_eq!(again.code, result.code);
+
+        let replace = PatchOperation::Replace("{ _csrf_token: token }".into());
+        let result = query_and_patch(CODE, &spec(var.clone(), &["params"], replace)).unwrap();
+        assert!(result.code.contains("_csrf_token: token"));
+        assert_eq!(result.matches, ["{\n    _csrf_token: csrfToken\n}"]);
+
+        let result = query_and_patch(
+            CODE,
+            &spec(var.clone(), &["params"], PatchOperation::Delete),
+        )
+        .unwrap();
+        assert!(!result.code.contains("params"));
+
+        let result = query_and_patch(CODE, &spec(var, &[], PatchOperation::Delete)).unwrap();
+        assert!(result.code.starts_with("Alpine.plugin(focus);"));
+
+        let callee = Matcher::Callee("Alpine.plugin".into());
+        let result = query_and_patch(CODE, &spec(callee, &[], PatchOperation::Delete)).unwrap();
+        assert!(!result.code.contains("Alpine"));
+
+        let callee = Matcher::Callee("LiveSocket".into());
+        assert!(query_and_patch(CODE, &spec(callee, &[], PatchOperation::Delete)).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::matcher::*;
+
+use rustler::{Encoder, Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn query_and_patch_nif(
+    env: Env,
+    file_content: String,
+    spec: QuerySpec,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::query_and_patch_nif();
+
+        match query_and_patch(&file_content, &spec) {
+            Ok(result) => encode_response(env, atoms::ok(), fn_atom, result),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg.encode(env)),
+        }
+    })
+}
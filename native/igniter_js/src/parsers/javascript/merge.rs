@@ -0,0 +1,360 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Three-way merge of a generated file, e.g. the `app.js` of a Phoenix version, with the
+//! file a user edited since it was generated.
+//!
+//! The merge works on top-level statements, paired like [`ast_diff`] pairs them: a statement
+//! the generator changed is updated unless the user changed it too, a statement the user
+//! changed is kept, and a statement both changed differently is a conflict, written between
+//! markers:
+//!
+//! ```text
+//! <<<<<<< user
+//! let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+//! =======
+//! let liveSocket = new LiveSocket("/live", Socket, { longPollFallbackMs: 2500 });
+//! >>>>>>> generated
+//! ```
+//!
+//! The statements are written with their original text, comments included, in the order of
+//! the user file; new statements of the generator follow the statement they follow in it.
+//!
+//! [`ast_diff`]: crate::parsers::javascript::ast_diff::ast_diff
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::ast_diff::{identity, DropRaw, StatementCategory};
+use crate::parsers::javascript::helpers::*;
+use std::collections::HashMap;
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, SourceMap, Spanned, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitMutWith;
+
+/// A statement both the generator and the user changed, left between conflict markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub category: StatementCategory,
+    /// What the statement declares, when it is known.
+    pub name: Option<String>,
+}
+
+/// The result of [`merge_generated`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// The merged source, with conflict markers when there are conflicts.
+    pub code: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// What pairs a statement across the three versions: its category, what it declares and
+/// its occurrence, or its code when it declares nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum StatementKey {
+    Named(StatementCategory, String, usize),
+    Unnamed(String, usize),
+}
+
+struct Statement<'a> {
+    key: StatementKey,
+    category: StatementCategory,
+    name: Option<String>,
+    /// The code without formatting, comments and quotes, to compare the versions.
+    code: String,
+    /// The text from the end of the previous statement, with the comments before it.
+    text: &'a str,
+}
+
+/// Generates the code of a statement on its own, ignoring its formatting.
+fn normalized_code(item: &ModuleItem) -> String {
+    let mut item = item.clone();
+    item.visit_mut_with(&mut DropSpan);
+    item.visit_mut_with(&mut DropRaw);
+    let mut module = Module {
+        span: DUMMY_SP,
+        body: vec![item],
+        shebang: None,
+    };
+    code_gen_from_ast_module(
+        &mut module,
+        SingleThreadedComments::default(),
+        Lrc::new(SourceMap::default()),
+    )
+}
+
+/// The statements of a version, and the text after the last one.
+fn statements<'a>(source: &'a str, version: &str) -> Result<(Vec<Statement<'a>>, &'a str), String> {
+    let (module, _comments, cm) =
+        parse(source).map_err(|error| format!("Failed to parse the {}: {}", version, error))?;
+    let mut occurrences: HashMap<StatementKey, usize> = HashMap::new();
+    let mut statements = vec![];
+    let mut previous_end = 0;
+
+    for item in &module.body {
+        let location = SourceLocation::from_span(&cm, item.span());
+        let (category, name) = identity(item);
+        let code = normalized_code(item);
+
+        let base_key = match &name {
+            Some(name) => StatementKey::Named(category, name.clone(), 0),
+            None => StatementKey::Unnamed(code.clone(), 0),
+        };
+        let occurrence = occurrences.entry(base_key.clone()).or_default();
+        let key = match base_key {
+            StatementKey::Named(category, name, _) => {
+                StatementKey::Named(category, name, *occurrence)
+            }
+            StatementKey::Unnamed(code, _) => StatementKey::Unnamed(code, *occurrence),
+        };
+        *occurrence += 1;
+
+        statements.push(Statement {
+            key,
+            category,
+            name,
+            code,
+            text: &source[previous_end..location.end],
+        });
+        previous_end = location.end;
+    }
+
+    Ok((statements, &source[previous_end..]))
+}
+
+/// Which version of a statement the merged file has.
+enum Choice {
+    User,
+    Generated,
+    Conflict,
+    Removed,
+}
+
+fn choose(base: Option<&str>, generated: Option<&str>, user: Option<&str>) -> Choice {
+    // The user's version wins when the generator did not change the statement
+    if generated == user || base == generated {
+        if user.is_some() {
+            Choice::User
+        } else {
+            Choice::Removed
+        }
+    } else if base == user {
+        if generated.is_some() {
+            Choice::Generated
+        } else {
+            Choice::Removed
+        }
+    } else {
+        Choice::Conflict
+    }
+}
+
+/// Appends a statement text, on a line of its own.
+fn push_text(code: &mut String, text: &str) {
+    if !code.is_empty() && !code.ends_with('\n') && !text.starts_with(['\n', '\r']) {
+        code.push('\n');
+    }
+    code.push_str(text);
+}
+
+/// Merges the changes between two generated versions of a file into the user's version.
+///
+/// # Arguments
+/// * `base` - The generated file the user started from.
+/// * `generated` - The new generated file.
+/// * `user` - The current file, with the user's edits.
+///
+/// # Returns
+/// * `Ok(MergeResult)` - The merged code, with its conflicts. The user file is returned
+///   unchanged when the generator changed nothing.
+/// * `Err(String)` - If a version can not be parsed, naming which one.
+///
+/// # Example
+/// ```rust
+/// let base = "import { Socket } from \"phoenix\";\nlet delay = 200;\n";
+/// let generated = "import { Socket } from \"phoenix\";\nimport topbar from \"topbar\";\nlet delay = 200;\n";
+/// let user = "import { Socket } from \"phoenix\";\nlet delay = 500;\n";
+/// let merged = merge_generated(base, generated, user).unwrap();
+/// assert_eq!(merged.code, "import { Socket } from \"phoenix\";\nimport topbar from \"topbar\";\nlet delay = 500;\n");
+/// ```
+pub fn merge_generated(base: &str, generated: &str, user: &str) -> Result<MergeResult, String> {
+    let (base, _) = statements(base, "base file")?;
+    let (generated, _) = statements(generated, "generated file")?;
+    let (user, user_trailing) = statements(user, "user file")?;
+
+    let codes = |statements: &[Statement]| -> HashMap<StatementKey, String> {
+        statements
+            .iter()
+            .map(|statement| (statement.key.clone(), statement.code.clone()))
+            .collect()
+    };
+    let base_codes = codes(&base);
+    let generated_index: HashMap<&StatementKey, usize> = generated
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| (&statement.key, index))
+        .collect();
+    let user_index: HashMap<&StatementKey, usize> = user
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| (&statement.key, index))
+        .collect();
+
+    let choice = |key: &StatementKey| {
+        choose(
+            base_codes.get(key).map(String::as_str),
+            generated_index
+                .get(key)
+                .map(|&index| generated[index].code.as_str()),
+            user_index.get(key).map(|&index| user[index].code.as_str()),
+        )
+    };
+
+    // The statements in the order of the user file, then the new ones of the generator
+    // after the statement they follow in the generated file
+    let mut order: Vec<&StatementKey> = user
+        .iter()
+        .map(|statement| &statement.key)
+        .filter(|key| !matches!(choice(key), Choice::Removed))
+        .collect();
+    let mut anchor: Option<usize> = None;
+    for statement in &generated {
+        if let Some(index) = order.iter().position(|key| **key == statement.key) {
+            anchor = Some(index);
+        } else if !matches!(choice(&statement.key), Choice::Removed) {
+            let index = anchor.map_or(0, |index| index + 1);
+            order.insert(index, &statement.key);
+            anchor = Some(index);
+        }
+    }
+
+    let mut code = String::new();
+    let mut conflicts = vec![];
+    for key in order {
+        let user = user_index.get(key).map(|&index| &user[index]);
+        let generated = generated_index.get(key).map(|&index| &generated[index]);
+
+        match (choice(key), user, generated) {
+            (Choice::User, Some(statement), _) | (Choice::Generated, _, Some(statement)) => {
+                push_text(&mut code, statement.text)
+            }
+            (Choice::Conflict, user, generated) => {
+                let Some(statement) = user.or(generated) else {
+                    continue;
+                };
+                let text = |statement: Option<&Statement>| {
+                    statement.map_or(String::new(), |statement| {
+                        format!("{}\n", statement.text.trim_start_matches(['\n', '\r']))
+                    })
+                };
+                push_text(
+                    &mut code,
+                    &format!(
+                        "\n<<<<<<< user\n{}=======\n{}>>>>>>> generated",
+                        text(user),
+                        text(generated)
+                    ),
+                );
+                conflicts.push(MergeConflict {
+                    category: statement.category,
+                    name: statement.name.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+    push_text(&mut code, user_trailing);
+    if code.starts_with("\n<<<<<<<") {
+        code.remove(0);
+    }
+
+    Ok(MergeResult { code, conflicts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r##"// Phoenix app.js
+import "phoenix_html";
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+import topbar from "../vendor/topbar";
+
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+let liveSocket = new LiveSocket("/live", Socket, {
+  params: { _csrf_token: csrfToken }
+});
+
+topbar.config({ barColors: { 0: "#29d" } });
+liveSocket.connect();
+"##;
+
+    #[test]
+    fn test_merge_generated() {
+        // The generator adds a hook import and changes the options, the user changed the
+        // topbar color and added a statement
+        let generated = BASE
+            .replace(
+                "import topbar",
+                "import { hooks as colocatedHooks } from \"phoenix-colocated/app\";\nimport topbar",
+            )
+            .replace("params:", "longPollFallbackMs: 2500,\n  params:");
+        let user = BASE.replace("#29d", "#f00").replace(
+            "liveSocket.connect();\n",
+            "liveSocket.connect();\n\n// Debug in the console\nwindow.liveSocket = liveSocket;\n",
+        );
+
+        let merged = merge_generated(BASE, &generated, &user).unwrap();
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(
+            merged.code,
+            generated.replace("#29d", "#f00").replace(
+                "liveSocket.connect();\n",
+                "liveSocket.connect();\n\n// Debug in the console\nwindow.liveSocket = liveSocket;\n",
+            )
+        );
+
+        // A byte order mark and multibyte characters keep the statements aligned
+        let bom = |code: &str| format!("\u{feff}{}", code.replace("// Phoenix", "// 🚀 Phœnix"));
+        let merged = merge_generated(&bom(BASE), &bom(&generated), &bom(&user)).unwrap();
+        assert_eq!(
+            merged.code,
+            bom(&generated.replace("#29d", "#f00").replace(
+                "liveSocket.connect();\n",
+                "liveSocket.connect();\n\n// Debug in the console\nwindow.liveSocket = liveSocket;\n",
+            ))
+        );
+
+        // Nothing to merge
+        assert_eq!(merge_generated(BASE, BASE, &user).unwrap().code, user);
+        assert_eq!(
+            merge_generated(BASE, &generated, BASE).unwrap().code,
+            generated
+        );
+    }
+
+    #[test]
+    fn test_merge_generated_conflicts_and_removals() {
+        let generated = BASE
+            .replace("#29d", "#00f")
+            .replace("import topbar from \"../vendor/topbar\";\n", "");
+        let user = BASE.replace("#29d", "#f00");
+
+        let merged = merge_generated(BASE, &generated, &user).unwrap();
+        assert_eq!(
+            merged.conflicts,
+            vec![MergeConflict {
+                category: StatementCategory::Expression,
+                name: Some("topbar.config".into()),
+            }]
+        );
+        assert!(!merged.code.contains("import topbar"));
+        assert!(merged.code.contains(
+            "\n<<<<<<< user\ntopbar.config({ barColors: { 0: \"#f00\" } });\n=======\ntopbar.config({ barColors: { 0: \"#00f\" } });\n>>>>>>> generated\nliveSocket.connect();\n"
+        ));
+
+        let error = merge_generated(BASE, BASE, "let = ;").unwrap_err();
+        assert!(error.starts_with("Failed to parse the user file"));
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_diff::StatementCategory;
+use crate::parsers::javascript::merge::*;
+
+use rustler::{Encoder, Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct MergeConflictResult {
+    pub category: StatementCategory,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, NifMap)]
+pub struct MergeResultMap {
+    pub code: String,
+    pub conflicts: Vec<MergeConflictResult>,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn merge_generated_nif(
+    env: Env,
+    base: String,
+    generated: String,
+    user: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::merge_generated_nif();
+
+        match merge_generated(&base, &generated, &user) {
+            Ok(merged) => {
+                let result = MergeResultMap {
+                    code: merged.code,
+                    conflicts: merged
+                        .conflicts
+                        .into_iter()
+                        .map(|conflict| MergeConflictResult {
+                            category: conflict.category,
+                            name: conflict.name,
+                        })
+                        .collect(),
+                };
+                encode_response(env, atoms::ok(), fn_atom, result.encode(env))
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
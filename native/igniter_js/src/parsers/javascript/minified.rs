@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Tells whether a file is probably a minified bundle, e.g. `priv/static/assets/app.js`
+//! selected instead of `assets/js/app.js`, so it is not patched by mistake.
+//!
+//! The text of the file is measured first: its line lengths, how much whitespace it has and
+//! the entropy of its characters. The shape of its syntax tree is then checked, i.e. whether
+//! its bindings are mangled to one or two letters and whether its body is a single bundler
+//! wrapper. A file is reported as minified when at least two of these signals are found.
+
+use crate::parsers::javascript::helpers::parse;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// A line longer than this is only found in generated code.
+const LONG_LINE_LENGTH: usize = 500;
+/// The average length of the non-empty lines above which a file is made of long lines.
+const LONG_AVERAGE_LINE_LENGTH: f64 = 200.0;
+/// The share of whitespace below which a file is compacted.
+const LOW_WHITESPACE_RATIO: f64 = 0.08;
+/// The entropy, in bits per character, above which the characters of a file are packed.
+const HIGH_ENTROPY: f64 = 5.2;
+/// The share of one or two letter bindings above which the names are mangled.
+const SHORT_IDENTIFIER_RATIO: f64 = 0.6;
+/// The sizes below which the ratios say nothing about a file.
+const MIN_SIZE: usize = 512;
+const MIN_BINDINGS: usize = 20;
+
+/// A sign that a file is minified.
+///
+/// On the Elixir side these are atoms, e.g. `:long_lines`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum MinifiedSignal {
+    LongLines,
+    LowWhitespace,
+    HighEntropy,
+    ShortIdentifiers,
+    BundleWrapper,
+    SourceMapComment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct MinifiedReport {
+    pub minified: bool,
+    /// The signs found, in the order of [`MinifiedSignal`].
+    pub signals: Vec<MinifiedSignal>,
+    pub max_line_length: usize,
+    pub average_line_length: f64,
+    pub whitespace_ratio: f64,
+    /// The Shannon entropy of the characters, in bits per character.
+    pub entropy: f64,
+    /// The share of bindings named with one or two characters, `0.0` when the file does not
+    /// parse.
+    pub short_identifier_ratio: f64,
+    /// The URL of the `//# sourceMappingURL=` comment, which leads to the source file.
+    pub source_map_url: Option<String>,
+}
+
+#[derive(Default)]
+struct BindingCounter {
+    total: usize,
+    short: usize,
+}
+
+impl Visit for BindingCounter {
+    fn visit_binding_ident(&mut self, ident: &BindingIdent) {
+        self.total += 1;
+        if ident.id.sym.chars().count() <= 2 {
+            self.short += 1;
+        }
+    }
+}
+
+fn entropy(file_content: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    file_content
+        .bytes()
+        .for_each(|byte| counts[byte as usize] += 1);
+    let total = file_content.len() as f64;
+
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+fn source_map_url(file_content: &str) -> Option<String> {
+    file_content.lines().rev().find_map(|line| {
+        let line = line.trim();
+        line.strip_prefix("//# sourceMappingURL=")
+            .or_else(|| line.strip_prefix("//@ sourceMappingURL="))
+            .map(|url| url.trim().to_string())
+    })
+}
+
+/// Whether the body is one expression statement wrapping the code, like the
+/// `(() => { ... })();` of esbuild or the `!function(e){ ... }(...)` of webpack.
+fn is_bundle_wrapper(module: &Module) -> bool {
+    let statements: Vec<&ModuleItem> = module
+        .body
+        .iter()
+        .filter(|item| !matches!(item, ModuleItem::Stmt(Stmt::Empty(_))))
+        .collect();
+    let [ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))] = statements.as_slice() else {
+        return false;
+    };
+
+    let mut expr = &**expr;
+    loop {
+        expr = match expr {
+            Expr::Paren(paren) => &paren.expr,
+            Expr::Unary(unary) => &unary.arg,
+            Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                ..
+            }) => {
+                return matches!(
+                    callee.unwrap_parens(),
+                    Expr::Fn(_) | Expr::Arrow(_) | Expr::Member(_)
+                )
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Measures the file and tells whether it is probably minified.
+///
+/// # Example
+/// ```rust
+/// let report = is_probably_minified("import { Socket } from \"phoenix\";\n");
+/// assert!(!report.minified);
+/// ```
+pub fn is_probably_minified(file_content: &str) -> MinifiedReport {
+    let lengths: Vec<usize> = file_content
+        .lines()
+        .map(|line| line.trim_end().chars().count())
+        .filter(|length| *length > 0)
+        .collect();
+    let max_line_length = lengths.iter().copied().max().unwrap_or(0);
+    let average_line_length = match lengths.len() {
+        0 => 0.0,
+        count => lengths.iter().sum::<usize>() as f64 / count as f64,
+    };
+    let whitespace = file_content.chars().filter(|c| c.is_whitespace()).count();
+    let whitespace_ratio = match file_content.chars().count() {
+        0 => 0.0,
+        count => whitespace as f64 / count as f64,
+    };
+    let entropy = entropy(file_content);
+    let source_map_url = source_map_url(file_content);
+
+    let mut bindings = BindingCounter::default();
+    let mut bundle_wrapper = false;
+    if let Ok((module, _comments, _cm)) = parse(file_content) {
+        module.visit_with(&mut bindings);
+        bundle_wrapper = is_bundle_wrapper(&module);
+    }
+    let short_identifier_ratio = match bindings.total {
+        0 => 0.0,
+        total => bindings.short as f64 / total as f64,
+    };
+
+    let large = file_content.len() >= MIN_SIZE;
+    let signals: Vec<MinifiedSignal> = [
+        (
+            MinifiedSignal::LongLines,
+            max_line_length > LONG_LINE_LENGTH || average_line_length > LONG_AVERAGE_LINE_LENGTH,
+        ),
+        (
+            MinifiedSignal::LowWhitespace,
+            large && whitespace_ratio < LOW_WHITESPACE_RATIO,
+        ),
+        (MinifiedSignal::HighEntropy, large && entropy > HIGH_ENTROPY),
+        (
+            MinifiedSignal::ShortIdentifiers,
+            bindings.total >= MIN_BINDINGS && short_identifier_ratio > SHORT_IDENTIFIER_RATIO,
+        ),
+        (MinifiedSignal::BundleWrapper, large && bundle_wrapper),
+        (MinifiedSignal::SourceMapComment, source_map_url.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(signal, found)| found.then_some(signal))
+    .collect();
+
+    MinifiedReport {
+        minified: signals.len() >= 2,
+        signals,
+        max_line_length,
+        average_line_length,
+        whitespace_ratio,
+        entropy,
+        short_identifier_ratio,
+        source_map_url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_probably_minified() {
+        let source = r#"import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+let liveSocket = new LiveSocket("/live", Socket, {
+  longPollFallbackMs: 2500,
+  params: { _csrf_token: csrfToken },
+});
+
+liveSocket.connect();
+window.liveSocket = liveSocket;
+"#;
+        let report = is_probably_minified(source);
+        assert!(!report.minified);
+        assert!(report.signals.is_empty());
+        assert_eq!(report.source_map_url, None);
+
+        let body = (0..30)
+            .map(|i| format!("var a{i}=function(e,t){{return e+t*{i}}};"))
+            .collect::<String>();
+        let bundle = format!("(()=>{{{body}}})();\n//# sourceMappingURL=app.js.map\n");
+        let report = is_probably_minified(&bundle);
+        assert!(report.minified);
+        assert_eq!(
+            report.signals,
+            [
+                MinifiedSignal::LongLines,
+                MinifiedSignal::LowWhitespace,
+                MinifiedSignal::ShortIdentifiers,
+                MinifiedSignal::BundleWrapper,
+                MinifiedSignal::SourceMapComment,
+            ]
+        );
+        assert_eq!(report.source_map_url.as_deref(), Some("app.js.map"));
+    }
+}
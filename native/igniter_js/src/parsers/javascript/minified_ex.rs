@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::minified::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn is_probably_minified_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::is_probably_minified_nif();
+
+        encode_response(
+            env,
+            atoms::ok(),
+            fn_atom,
+            is_probably_minified(&file_content),
+        )
+    })
+}
@@ -0,0 +1,721 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # JavaScript Minification
+//!
+//! Minifies JavaScript with the SWC code generator in minify mode, which drops comments
+//! and whitespace, optionally after two AST passes:
+//!
+//! - **Mangling** renames the bindings declared inside functions, blocks and classes to
+//!   short names. Top-level bindings are kept, since they may be used by other scripts, and
+//!   nothing is renamed when the code calls `eval`.
+//! - **Compression** applies the size reductions that never change the behavior of the
+//!   code, up to the compression level of [`MinifyOptions::level`]: from removing
+//!   `debugger` and empty statements, to folding the operations on literals and the
+//!   conditions they decide, to removing the statements that can not be reached.
+//!
+//! The compression is written on the SWC AST the other transforms use rather than with
+//! `swc_ecma_minifier`, whose optimization crates are not among the dependencies of the
+//! crate, so it only applies reductions that hold whatever the code around them does.
+
+use crate::parsers::javascript::helpers::{
+    code_gen_with_source_map, es_version, parse, GeneratedCode,
+};
+use crate::parsers::javascript::scope::{Renamer, ScopeCollector};
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use std::collections::{HashMap, HashSet};
+use swc_atoms::Atom;
+use swc_common::{sync::Lrc, SourceMap, Span};
+use swc_ecma_ast::*;
+use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+/// Options for the minification.
+///
+/// On the Elixir side this is a map, e.g.
+/// `%{mangle: true, compress: true, level: 3, target: "es2015"}`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct MinifyOptions {
+    /// Rename local bindings to short names.
+    pub mangle: bool,
+    /// Apply the size reductions that keep the behavior of the code.
+    pub compress: bool,
+    /// The compression level, from `1` to [`MAX_LEVEL`]:
+    ///
+    /// - `1` removes `debugger` and empty statements and shortens booleans to `!0` and `!1`.
+    /// - `2` also folds the operations on literals, e.g. `60 * 1000` or `"a" + "b"`, the
+    ///   conditions with a literal test, e.g. `if (true)`, and writes `undefined` as
+    ///   `void 0`.
+    /// - `3` also removes the statements after a `return`, `throw`, `break` or `continue`.
+    pub level: u8,
+    /// The ES version the output is emitted for, e.g. `es5`, `es2020` or `esnext`.
+    pub target: String,
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        Self {
+            mangle: true,
+            compress: true,
+            level: MAX_LEVEL,
+            target: "esnext".to_string(),
+        }
+    }
+}
+
+/// The highest compression level.
+pub const MAX_LEVEL: u8 = 3;
+
+/// Short words the name generator must skip.
+const RESERVED_WORDS: &[&str] = &["do", "if", "in", "for", "let", "new", "try", "var"];
+
+/// Assigns a short name to every binding that is not declared at the top level.
+///
+/// A new name never matches a name of the original code, so no reference can be
+/// captured, nor a name given to a binding of an enclosing scope, so no binding that is
+/// visible in a scope is shadowed. Sibling scopes reuse the same names.
+fn new_names(collector: &ScopeCollector) -> HashMap<(usize, Atom), Atom> {
+    let mut new_names = HashMap::new();
+    let mut scope_names: Vec<HashSet<Atom>> = vec![HashSet::new(); collector.scopes.len()];
+
+    for (index, scope) in collector.scopes.iter().enumerate().skip(1) {
+        let mut taken = HashSet::new();
+        let mut ancestor = scope.parent;
+        while let Some(parent) = ancestor {
+            taken.extend(scope_names[parent].iter().cloned());
+            ancestor = collector.scopes[parent].parent;
+        }
+
+        let mut counter = 0;
+        for binding in &scope.bindings {
+            let name = loop {
+                let name = Atom::from(short_name(counter));
+                counter += 1;
+                if !taken.contains(&name)
+                    && !collector.names.contains(&name)
+                    && !RESERVED_WORDS.contains(&name.as_ref())
+                {
+                    break name;
+                }
+            };
+            scope_names[index].insert(name.clone());
+            new_names.insert((index, binding.clone()), name);
+        }
+    }
+
+    new_names
+}
+
+/// `a`, `b`, ..., `Z`, `aa`, `ba`, ...
+fn short_name(mut index: usize) -> String {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut name = String::new();
+    loop {
+        name.push(CHARS[index % CHARS.len()] as char);
+        index /= CHARS.len();
+        if index == 0 {
+            return name;
+        }
+        index -= 1;
+    }
+}
+
+/// The size reductions applied when `compress` is enabled, up to the compression level.
+struct Compressor {
+    level: u8,
+    /// Whether every `undefined` of the code is the global one, so it can be `void 0`.
+    global_undefined: bool,
+}
+
+impl Compressor {
+    fn new(module: &Module, level: u8) -> Self {
+        let mut collector = ScopeCollector::new();
+        module.visit_with(&mut collector);
+        let mut with_finder = WithFinder(false);
+        module.visit_with(&mut with_finder);
+
+        let undefined = Atom::from("undefined");
+        let global_undefined = !collector.has_eval
+            && !with_finder.0
+            && !collector
+                .scopes
+                .iter()
+                .any(|scope| scope.bindings.contains(&undefined));
+        Self {
+            level,
+            global_undefined,
+        }
+    }
+
+    fn is_removable(stmt: &Stmt) -> bool {
+        matches!(stmt, Stmt::Empty(_) | Stmt::Debugger(_))
+    }
+
+    /// Removes the statements after one that never completes normally, except the
+    /// declarations, which are hoisted or shadow a binding for the code before them.
+    fn remove_unreachable(stmts: &mut Vec<Stmt>) {
+        let Some(end) = stmts.iter().position(|stmt| {
+            matches!(
+                stmt,
+                Stmt::Return(_) | Stmt::Throw(_) | Stmt::Break(_) | Stmt::Continue(_)
+            )
+        }) else {
+            return;
+        };
+        let mut index = 0;
+        stmts.retain(|stmt| {
+            index += 1;
+            index <= end + 1 || matches!(stmt, Stmt::Decl(_)) || declares_hoisted(stmt)
+        });
+    }
+
+    /// Folds an expression whose value is known from its literals.
+    fn fold(&self, expr: &mut Expr) {
+        let folded = match expr {
+            Expr::Bin(bin) => fold_bin(bin),
+            Expr::Cond(cond) => truthiness(&cond.test).map(|test| {
+                if test {
+                    *cond.cons.clone()
+                } else {
+                    *cond.alt.clone()
+                }
+            }),
+            Expr::Ident(ident) if self.global_undefined && &*ident.sym == "undefined" => {
+                Some(void_zero(ident.span))
+            }
+            _ => None,
+        };
+        // A method taken out of a condition is called without its object as `this`
+        if let Some(folded) = folded.filter(|folded| !is_member(folded)) {
+            *expr = folded;
+        }
+    }
+
+    /// Replaces an `if` with a literal test by the branch it takes, when the other one
+    /// declares nothing, and a `while (true)` by the shorter `for (;;)`.
+    fn fold_stmt(stmt: &mut Stmt) {
+        match stmt {
+            Stmt::If(if_stmt) => {
+                let Some(test) = truthiness(&if_stmt.test) else {
+                    return;
+                };
+                let (taken, dropped) = if test {
+                    (Some(&*if_stmt.cons), if_stmt.alt.as_deref())
+                } else {
+                    (if_stmt.alt.as_deref(), Some(&*if_stmt.cons))
+                };
+                // A nested `if` could take the `else` of an enclosing one
+                if dropped.is_some_and(declares_hoisted)
+                    || matches!(taken, Some(Stmt::Decl(_) | Stmt::If(_)))
+                {
+                    return;
+                }
+                *stmt = taken
+                    .cloned()
+                    .unwrap_or(Stmt::Empty(EmptyStmt { span: if_stmt.span }));
+            }
+            Stmt::While(while_stmt) if truthiness(&while_stmt.test) == Some(true) => {
+                *stmt = Stmt::For(ForStmt {
+                    span: while_stmt.span,
+                    init: None,
+                    test: None,
+                    update: None,
+                    body: while_stmt.body.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VisitMut for Compressor {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        items.retain(|item| !matches!(item, ModuleItem::Stmt(stmt) if Self::is_removable(stmt)));
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        stmts.retain(|stmt| !Self::is_removable(stmt));
+        if self.level >= 3 {
+            Self::remove_unreachable(stmts);
+        }
+    }
+
+    fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
+        stmt.visit_mut_children_with(self);
+        if self.level >= 2 {
+            Self::fold_stmt(stmt);
+        }
+        if let Stmt::Debugger(debugger) = stmt {
+            *stmt = Stmt::Empty(EmptyStmt {
+                span: debugger.span,
+            });
+        }
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // `delete undefined` is `false` in a script, but `delete void 0` is `true`
+        if let Expr::Unary(UnaryExpr {
+            op: UnaryOp::Delete,
+            arg,
+            ..
+        }) = expr
+        {
+            if arg.is_ident() {
+                return;
+            }
+        }
+
+        expr.visit_mut_children_with(self);
+        if self.level >= 2 {
+            self.fold(expr);
+        }
+        if let Expr::Lit(Lit::Bool(Bool { span, value })) = expr {
+            *expr = Expr::Unary(UnaryExpr {
+                span: *span,
+                op: UnaryOp::Bang,
+                arg: Box::new(number(*span, if *value { 0.0 } else { 1.0 })),
+            });
+        }
+    }
+}
+
+/// Finds a `with` statement, within which `undefined` may be a property of its object.
+struct WithFinder(bool);
+
+impl Visit for WithFinder {
+    fn visit_with_stmt(&mut self, _: &WithStmt) {
+        self.0 = true;
+    }
+}
+
+/// Finds the `var` and function declarations of a statement, which are hoisted out of it.
+struct HoistedFinder(bool);
+
+impl Visit for HoistedFinder {
+    fn visit_var_decl(&mut self, decl: &VarDecl) {
+        self.0 |= decl.kind == VarDeclKind::Var;
+        decl.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, _: &FnDecl) {
+        self.0 = true;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+fn declares_hoisted(stmt: &Stmt) -> bool {
+    let mut finder = HoistedFinder(false);
+    stmt.visit_with(&mut finder);
+    finder.0
+}
+
+fn number(span: Span, value: f64) -> Expr {
+    Expr::Lit(Lit::Num(Number {
+        span,
+        value,
+        raw: None,
+    }))
+}
+
+fn void_zero(span: Span) -> Expr {
+    Expr::Unary(UnaryExpr {
+        span,
+        op: UnaryOp::Void,
+        arg: Box::new(number(span, 0.0)),
+    })
+}
+
+fn is_member(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Member(_) | Expr::SuperProp(_) | Expr::OptChain(_)
+    )
+}
+
+/// The literal value of an expression, with the booleans already shortened to `!0` and
+/// `!1` and `undefined` to `void 0`.
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Null,
+    Undefined,
+}
+
+fn literal(expr: &Expr) -> Option<Literal> {
+    match expr {
+        Expr::Paren(paren) => literal(&paren.expr),
+        Expr::Lit(Lit::Bool(Bool { value, .. })) => Some(Literal::Bool(*value)),
+        Expr::Lit(Lit::Num(Number { value, .. })) => Some(Literal::Num(*value)),
+        Expr::Lit(Lit::Str(Str { value, .. })) => Some(Literal::Str(value.to_string())),
+        Expr::Lit(Lit::Null(_)) => Some(Literal::Null),
+        Expr::Unary(UnaryExpr { op, arg, .. }) => match (op, &**arg) {
+            (UnaryOp::Bang, Expr::Lit(Lit::Num(Number { value, .. })))
+                if *value == 0.0 || *value == 1.0 =>
+            {
+                Some(Literal::Bool(*value == 0.0))
+            }
+            (UnaryOp::Void, Expr::Lit(Lit::Num(_))) => Some(Literal::Undefined),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether a literal expression is truthy, or `None` when its value is not known.
+fn truthiness(expr: &Expr) -> Option<bool> {
+    Some(match literal(expr)? {
+        Literal::Bool(value) => value,
+        Literal::Num(value) => value != 0.0 && !value.is_nan(),
+        Literal::Str(value) => !value.is_empty(),
+        Literal::Null | Literal::Undefined => false,
+    })
+}
+
+/// A number JavaScript prints the same way as Rust: an integer below 2^53.
+fn exact_integer(value: f64) -> Option<i64> {
+    (value.fract() == 0.0
+        && value.abs() < 9_007_199_254_740_992.0
+        && !(value == 0.0 && value.is_sign_negative()))
+    .then_some(value as i64)
+}
+
+fn fold_bin(bin: &BinExpr) -> Option<Expr> {
+    let span = bin.span;
+    let left = literal(&bin.left);
+    let right = literal(&bin.right);
+
+    match bin.op {
+        // The left side of a logical operator decides whether the right one is evaluated
+        BinaryOp::LogicalAnd => {
+            let left = truthiness(&bin.left)?;
+            return Some(if left {
+                *bin.right.clone()
+            } else {
+                *bin.left.clone()
+            });
+        }
+        BinaryOp::LogicalOr => {
+            let left = truthiness(&bin.left)?;
+            return Some(if left {
+                *bin.left.clone()
+            } else {
+                *bin.right.clone()
+            });
+        }
+        BinaryOp::NullishCoalescing => {
+            let left = left?;
+            let nullish = matches!(left, Literal::Null | Literal::Undefined);
+            return Some(if nullish {
+                *bin.right.clone()
+            } else {
+                *bin.left.clone()
+            });
+        }
+        _ => {}
+    }
+
+    let (left, right) = (left?, right?);
+    let bool = |value: bool| Some(Expr::Lit(Lit::Bool(Bool { span, value })));
+    match (bin.op, left, right) {
+        (BinaryOp::Add, Literal::Str(left), Literal::Str(right)) => {
+            Some(Expr::Lit(Lit::Str(Str {
+                span,
+                value: format!("{}{}", left, right).into(),
+                raw: None,
+            })))
+        }
+        (BinaryOp::Add, Literal::Str(left), Literal::Num(right)) => {
+            let right = exact_integer(right)?;
+            Some(Expr::Lit(Lit::Str(Str {
+                span,
+                value: format!("{}{}", left, right).into(),
+                raw: None,
+            })))
+        }
+        (BinaryOp::Add, Literal::Num(left), Literal::Str(right)) => {
+            let left = exact_integer(left)?;
+            Some(Expr::Lit(Lit::Str(Str {
+                span,
+                value: format!("{}{}", left, right).into(),
+                raw: None,
+            })))
+        }
+        (op, Literal::Num(left), Literal::Num(right)) => match op {
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                let value = match op {
+                    BinaryOp::Add => left + right,
+                    BinaryOp::Sub => left - right,
+                    BinaryOp::Mul => left * right,
+                    BinaryOp::Div => left / right,
+                    _ => left % right,
+                };
+                // A negative number would be a unary minus, and a fraction may be longer
+                exact_integer(value)
+                    .filter(|value| *value >= 0)
+                    .map(|_| number(span, value))
+            }
+            BinaryOp::EqEqEq | BinaryOp::EqEq => bool(left == right),
+            BinaryOp::NotEqEq | BinaryOp::NotEq => bool(left != right),
+            BinaryOp::Lt => bool(left < right),
+            BinaryOp::LtEq => bool(left <= right),
+            BinaryOp::Gt => bool(left > right),
+            BinaryOp::GtEq => bool(left >= right),
+            _ => None,
+        },
+        (BinaryOp::EqEqEq, left, right) => bool(left == right),
+        (BinaryOp::NotEqEq, left, right) => bool(left != right),
+        _ => None,
+    }
+}
+
+fn mangle(module: &mut Module) {
+    let mut collector = ScopeCollector::new();
+    module.visit_with(&mut collector);
+    if collector.has_eval {
+        return;
+    }
+
+    let new_names = new_names(&collector);
+    let renames = collector
+        .occurrences
+        .iter()
+        .filter_map(|(lo, sym, scope)| {
+            let declared_in = collector.resolve(sym, *scope)?;
+            let name = new_names.get(&(declared_in, sym.clone()))?;
+            Some(((*lo, sym.clone()), name.clone()))
+        })
+        .collect();
+
+    module.visit_mut_with(&mut Renamer { renames });
+}
+
+/// Minifies JavaScript code.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `options` - Whether to mangle and compress, and the ES version to emit for.
+///
+/// # Returns
+/// * `Ok(String)` - The minified code.
+/// * `Err(String)` - If the source can not be parsed or the target is unknown.
+///
+/// # Example
+/// ```rust
+/// let js_code = "function add(first, second) {\n  return first + second;\n}";
+/// let minified = minify_js(js_code, &MinifyOptions::default()).unwrap();
+/// assert_eq!(minified, "function add(a,b){return a+b;}");
+/// ```
+pub fn minify_js(file_content: &str, options: &MinifyOptions) -> Result<String, String> {
+    let (module, cm, cfg) = minify_module(file_content, options)?;
+
+    let mut buf = vec![];
+    let mut emitter = Emitter {
+        cfg,
+        cm: cm.clone(),
+        comments: None,
+        wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+    };
+
+    emitter
+        .emit_module(&module)
+        .map_err(|e| format!("Failed to emit module: {:?}", e))?;
+    String::from_utf8(buf).map_err(|_| "Invalid UTF-8".to_string())
+}
+
+/// Like [`minify_js`], also returning the source map from the minified code to the source,
+/// which is named `source_name` in the map.
+pub fn minify_js_with_source_map(
+    file_content: &str,
+    options: &MinifyOptions,
+    source_name: &str,
+) -> Result<GeneratedCode, String> {
+    let (module, cm, cfg) = minify_module(file_content, options)?;
+    code_gen_with_source_map(&module, None, cm, cfg, source_name)
+}
+
+fn minify_module(
+    file_content: &str,
+    options: &MinifyOptions,
+) -> Result<(Module, Lrc<SourceMap>, Config), String> {
+    let target = es_version(&options.target)?;
+    if options.compress && !(1..=MAX_LEVEL).contains(&options.level) {
+        return Err(format!(
+            "The compression level must be from 1 to {}, got {}",
+            MAX_LEVEL, options.level
+        ));
+    }
+    let (mut module, _comments, cm) = parse(file_content)?;
+
+    if options.mangle {
+        mangle(&mut module);
+    }
+    if options.compress {
+        let mut compressor = Compressor::new(&module, options.level);
+        module.visit_mut_with(&mut compressor);
+    }
+
+    let cfg = Config::default()
+        .with_minify(true)
+        .with_omit_last_semi(true)
+        .with_target(target);
+    Ok((module, cm, cfg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_js() {
+        let code = r#"
+// Adds the two numbers
+export function add(first, second) {
+  const total = first + second;
+  debugger;
+  return total;
+}
+"#;
+        assert_eq!(
+            minify_js(code, &MinifyOptions::default()).unwrap(),
+            "export function add(a,b){const c=a+b;return c;}"
+        );
+
+        let options = MinifyOptions {
+            mangle: false,
+            compress: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            minify_js(code, &options).unwrap(),
+            "export function add(first,second){const total=first+second;debugger;return total;}"
+        );
+
+        let result = minify_js_with_source_map(code, &MinifyOptions::default(), "add.js").unwrap();
+        assert_eq!(
+            result.code,
+            "export function add(a,b){const c=a+b;return c;}"
+        );
+        let source_map: serde_json::Value = serde_json::from_str(&result.source_map).unwrap();
+        assert_eq!(source_map["sources"], serde_json::json!(["add.js"]));
+        assert!(source_map["mappings"].as_str().unwrap().starts_with("AAEA"));
+    }
+
+    #[test]
+    fn test_minify_js_keeps_references_intact() {
+        let code = r#"
+const a = 1;
+function outer(value, { label, size = a }) {
+  let b = value;
+  const inner = () => ({ b, value, label, size, a, window });
+  {
+    let b = 2;
+    label: for (const item of [b]) { if (item) break label; }
+  }
+  return inner();
+}
+"#;
+        let options = MinifyOptions {
+            compress: false,
+            ..Default::default()
+        };
+        let minified = minify_js(code, &options).unwrap();
+
+        assert!(minified.starts_with(
+            "const a=1;function outer(c,{label:d,size:e=a}){let f=c;const g=()=>({b:f,value:c,label:d,size:e,a,window});"
+        ));
+        assert!(minified.contains("label:for(const"));
+
+        let code = "function risky(code) { return eval(code); }";
+        assert_eq!(
+            minify_js(code, &options).unwrap(),
+            "function risky(code){return eval(code);}"
+        );
+
+        assert!(minify_js(
+            code,
+            &MinifyOptions {
+                target: "es1999".to_string(),
+                ..Default::default()
+            }
+        )
+        .is_err());
+        assert!(minify_js("let = ;", &MinifyOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_minify_js_compression_levels() {
+        let minify = |code: &str, level: u8| {
+            let options = MinifyOptions {
+                mangle: false,
+                level,
+                ..Default::default()
+            };
+            minify_js(code, &options).unwrap()
+        };
+
+        let code = r#"
+export const timeout = 60 * 1000, label = "a" + "b" + 1, same = 1 === 1, half = 1 / 2;
+export function check(value) {
+  if (true) { run(value); } else { skip(); }
+  while (true) { if (value) break; }
+  return value === undefined ? null ?? "none" : value;
+  cleanup();
+  var later = 1;
+  function hoisted() {}
+}
+"#;
+        assert_eq!(
+            minify(code, 1),
+            "export const timeout=60*1e3,label=\"a\"+\"b\"+1,same=1===1,half=1/2;export function check(value){if(!0){run(value);}else{skip();}while(!0){if(value)break;}return value===undefined?null??\"none\":value;cleanup();var later=1;function hoisted(){}}"
+        );
+        assert_eq!(
+            minify(code, 2),
+            "export const timeout=6e4,label=\"ab1\",same=!0,half=1/2;export function check(value){{run(value);}for(;;){if(value)break;}return value===void 0?\"none\":value;cleanup();var later=1;function hoisted(){}}"
+        );
+        assert_eq!(
+            minify(code, 3),
+            "export const timeout=6e4,label=\"ab1\",same=!0,half=1/2;export function check(value){{run(value);}for(;;){if(value)break;}return value===void 0?\"none\":value;var later=1;function hoisted(){}}"
+        );
+
+        // A method keeps its object as `this`, a hoisted `var` its branch, and a local or
+        // `with` binding of `undefined` its name
+        assert_eq!(
+            minify("let m = (true ? obj.method : other)();", 3),
+            "let m=(!0?obj.method:other)();"
+        );
+        assert_eq!(
+            minify("if (false) { var flag = 1; } else { run(); }", 3),
+            "if(!1){var flag=1;}else{run();}"
+        );
+        assert_eq!(
+            minify("function f(undefined) { return undefined; }", 3),
+            "function f(undefined){return undefined;}"
+        );
+        assert_eq!(
+            minify("with (o) { undefined; } x = delete undefined;", 3),
+            "with(o){undefined;}x=delete undefined;"
+        );
+
+        assert!(minify_js(
+            "let a;",
+            &MinifyOptions {
+                level: MAX_LEVEL + 1,
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+}
@@ -3,10 +3,104 @@
 //
 // SPDX-License-Identifier: MIT
 
+pub mod alpine;
+#[cfg(feature = "nif")]
+pub mod alpine_ex;
 pub mod ast;
+pub mod ast_diff;
+#[cfg(feature = "nif")]
+pub mod ast_diff_ex;
+#[cfg(feature = "nif")]
 pub mod ast_ex;
 pub mod ast_json;
+pub mod bindings;
+#[cfg(feature = "nif")]
+pub mod bindings_ex;
+pub mod code_style;
+#[cfg(feature = "nif")]
+pub mod codemod;
+#[cfg(feature = "nif")]
+pub mod codemod_ex;
+pub mod compatibility;
+#[cfg(feature = "nif")]
+pub mod compatibility_ex;
+pub mod deadline;
+pub mod document;
+#[cfg(feature = "nif")]
+pub mod document_ex;
+pub mod dynamic_import;
+#[cfg(feature = "nif")]
+pub mod dynamic_import_ex;
+pub mod engine;
+#[cfg(feature = "nif")]
+pub mod engine_ex;
+pub mod env_guard;
+#[cfg(feature = "nif")]
+pub mod env_guard_ex;
+pub mod estree_codegen;
+pub mod exports;
+#[cfg(feature = "nif")]
+pub mod exports_ex;
+pub mod extract;
+#[cfg(feature = "nif")]
+pub mod extract_ex;
 pub mod formatter;
+#[cfg(feature = "nif")]
 pub mod formatter_ex;
 pub mod helpers;
+pub mod hooks_index;
+#[cfg(feature = "nif")]
+pub mod hooks_index_ex;
+pub mod imports;
+#[cfg(feature = "nif")]
+pub mod imports_ex;
+pub mod matcher;
+#[cfg(feature = "nif")]
+pub mod matcher_ex;
+pub mod merge;
+#[cfg(feature = "nif")]
+pub mod merge_ex;
+pub mod minified;
+#[cfg(feature = "nif")]
+pub mod minified_ex;
+pub mod minify;
+#[cfg(feature = "nif")]
+pub mod minify_ex;
+pub mod object;
+pub mod organize;
+#[cfg(feature = "nif")]
+pub mod organize_ex;
 pub mod phoenix;
+pub mod positions;
+#[cfg(feature = "nif")]
+pub mod positions_ex;
+pub mod region;
+#[cfg(feature = "nif")]
+pub mod region_ex;
+pub mod relocate;
+#[cfg(feature = "nif")]
+pub mod relocate_ex;
+pub mod removal;
+pub mod rename;
+#[cfg(feature = "nif")]
+pub mod rename_ex;
+pub mod role;
+#[cfg(feature = "nif")]
+pub mod role_ex;
+pub mod scope;
+pub mod sdk;
+pub mod service_worker;
+#[cfg(feature = "nif")]
+pub mod service_worker_ex;
+pub mod syntax_tree;
+#[cfg(feature = "nif")]
+pub mod syntax_tree_ex;
+pub mod transpile;
+#[cfg(feature = "nif")]
+pub mod transpile_ex;
+pub mod unused;
+#[cfg(feature = "nif")]
+pub mod unused_ex;
+pub mod validate;
+#[cfg(feature = "nif")]
+pub mod validate_ex;
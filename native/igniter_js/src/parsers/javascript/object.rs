@@ -0,0 +1,385 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Object Literal Helpers
+//!
+//! Generic helpers to patch object literals, such as `topbar.config(...)` arguments, chart
+//! options or any config object a variable is declared with, from a JSON description.
+
+use crate::parsers::javascript::ast::extend_var_object_property_by_names_to_ast;
+use crate::parsers::javascript::helpers::*;
+use crate::parsers::javascript::phoenix::{check_hook_entries, push_hook_entries};
+#[cfg(feature = "nif")]
+use rustler::NifTaggedEnum;
+use serde_json::Value;
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::*;
+
+/// The object literal an operation applies to.
+///
+/// On the Elixir side these are `{:var, "Components"}`, `:export_default` and `:module_exports`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum ObjectTarget {
+    /// The object a top-level variable is declared with: `const Components = {...}`.
+    Var(String),
+    /// The default export of the module: `export default {...}`, the object passed to a
+    /// wrapper such as `export default defineConfig({...})`, or the object of the variable
+    /// exported by `export default Hooks`.
+    ExportDefault,
+    /// The CommonJS export: `module.exports = {...}`, as found in tailwind/postcss configs.
+    ModuleExports,
+}
+
+/// Returns the object literal an expression is, or wraps as the first argument of a call.
+fn object_of_expr(expr: &mut Expr) -> Option<&mut ObjectLit> {
+    match expr {
+        Expr::Object(obj_expr) => Some(obj_expr),
+        Expr::Paren(paren) => object_of_expr(&mut paren.expr),
+        Expr::Call(call) => match call.args.first_mut() {
+            Some(ExprOrSpread { spread: None, expr }) => match &mut **expr {
+                Expr::Object(obj_expr) => Some(obj_expr),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_module_exports(target: &AssignTarget) -> bool {
+    let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = target else {
+        return false;
+    };
+
+    matches!(&*member.obj, Expr::Ident(obj) if obj.sym == "module")
+        && matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == "exports")
+}
+
+/// Finds the object literal selected by `target` in the module body.
+pub fn find_object_literal<'a>(
+    module: &'a mut Module,
+    target: &ObjectTarget,
+) -> Option<&'a mut ObjectLit> {
+    match target {
+        ObjectTarget::Var(var_name) => find_var_object_literal(module, var_name),
+        ObjectTarget::ExportDefault => {
+            let exported_var = module.body.iter().find_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+                    match &*export.expr {
+                        Expr::Ident(ident) => Some(ident.sym.to_string()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+            if let Some(var_name) = exported_var {
+                return find_var_object_literal(module, &var_name);
+            }
+
+            module.body.iter_mut().find_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+                    object_of_expr(&mut export.expr)
+                }
+                _ => None,
+            })
+        }
+        ObjectTarget::ModuleExports => module.body.iter_mut().find_map(|item| match item {
+            ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) => match &mut **expr {
+                Expr::Assign(assign)
+                    if assign.op == AssignOp::Assign && is_module_exports(&assign.left) =>
+                {
+                    object_of_expr(&mut assign.right)
+                }
+                _ => None,
+            },
+            _ => None,
+        }),
+    }
+}
+
+/// Extends the object literal selected by `target` with shorthand (or `...spread`) entries.
+///
+/// `ObjectTarget::Var` keeps the behavior of [`extend_var_object_property_by_names_to_ast`];
+/// the other targets append each name that is not already present.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `target` - The object literal to extend.
+/// * `object_names` - The entries to add, e.g. `["Tooltip", "...Hooks"]`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated JavaScript source code.
+/// * `Err(String)` - If the source can not be parsed or the target object is not found.
+///
+/// # Example
+/// ```rust
+/// let code = "module.exports = { plugins: [] };";
+/// let result = extend_object_property_by_names_to_ast(code, &ObjectTarget::ModuleExports, ["theme"]);
+/// assert!(result.unwrap().contains("theme"));
+/// ```
+pub fn extend_object_property_by_names_to_ast<'a>(
+    file_content: &str,
+    target: &ObjectTarget,
+    object_names: impl IntoIterator<Item = &'a str> + Clone,
+) -> Result<String, String> {
+    if let ObjectTarget::Var(var_name) = target {
+        return extend_var_object_property_by_names_to_ast(file_content, var_name, object_names);
+    }
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let obj_expr = find_object_literal(&mut module, target)
+        .ok_or_else(|| format!("No object literal found for {:?}", target))?;
+
+    let names: Vec<&str> = object_names.into_iter().collect();
+    check_hook_entries(&names)?;
+    push_hook_entries(&mut obj_expr.props, &names);
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// Converts a JSON value into the equivalent JavaScript expression.
+pub fn json_to_expr(value: &Value) -> Box<Expr> {
+    let expr = match value {
+        Value::Null => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+        Value::Bool(b) => Expr::Lit(Lit::Bool(Bool {
+            span: DUMMY_SP,
+            value: *b,
+        })),
+        Value::Number(n) => Expr::Lit(Lit::Num(Number {
+            span: DUMMY_SP,
+            value: n.as_f64().unwrap_or_default(),
+            raw: Some(n.to_string().into()),
+        })),
+        Value::String(s) => Expr::Lit(Lit::Str(Str {
+            span: DUMMY_SP,
+            value: s.as_str().into(),
+            raw: None,
+        })),
+        Value::Array(items) => Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: items
+                .iter()
+                .map(|item| {
+                    Some(ExprOrSpread {
+                        spread: None,
+                        expr: json_to_expr(item),
+                    })
+                })
+                .collect(),
+        }),
+        Value::Object(map) => Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: map
+                .iter()
+                .map(|(key, value)| new_key_value_prop(key, json_to_expr(value)))
+                .collect(),
+        }),
+    };
+
+    Box::new(expr)
+}
+
+/// Deep-merges a JSON object into an object literal.
+///
+/// Missing keys are added; when both sides hold an object the merge recurses; any other
+/// existing value is kept unless `overwrite` is set.
+pub fn merge_json_into_object(
+    obj_expr: &mut ObjectLit,
+    spec: &serde_json::Map<String, Value>,
+    overwrite: bool,
+) {
+    for (key, value) in spec {
+        let existing = obj_expr
+            .props
+            .iter_mut()
+            .find(|prop| prop_key_name(prop).as_deref() == Some(key.as_str()));
+
+        match existing {
+            None => obj_expr
+                .props
+                .push(new_key_value_prop(key, json_to_expr(value))),
+            Some(prop) => {
+                if let (Value::Object(nested), Some(nested_obj)) = (value, object_prop_value(prop))
+                {
+                    merge_json_into_object(nested_obj, nested, overwrite);
+                } else if overwrite {
+                    match prop {
+                        // Keeps the key as it is written, e.g. `0` rather than `"0"`
+                        PropOrSpread::Prop(prop) if prop.is_key_value() => {
+                            if let Prop::KeyValue(key_value) = &mut **prop {
+                                key_value.value = json_to_expr(value);
+                            }
+                        }
+                        _ => *prop = new_key_value_prop(key, json_to_expr(value)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn object_prop_value(prop: &mut PropOrSpread) -> Option<&mut ObjectLit> {
+    match prop {
+        PropOrSpread::Prop(prop) => match &mut **prop {
+            Prop::KeyValue(KeyValueProp { value, .. }) => match &mut **value {
+                Expr::Object(obj_expr) => Some(obj_expr),
+                _ => None,
+            },
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    }
+}
+
+/// Deep-merges a JSON structure into the object literal a top-level variable is declared
+/// with.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `var_name` - The variable the object literal is assigned to.
+/// * `json_spec` - A JSON object describing the keys to merge, e.g.
+///   `{"barColors": {"0": "#29d"}, "shadowColor": "rgba(0, 0, 0, .3)"}`.
+/// * `overwrite` - Replace existing values instead of keeping them.
+///
+/// # Returns
+/// * `Ok(String)` - The updated JavaScript source code.
+/// * `Err(String)` - If the source can not be parsed, the spec is not a JSON object, or the
+///   variable is not declared with an object literal.
+///
+/// # Example
+/// ```rust
+/// let code = "const config = { a: 1 };";
+/// let result = merge_object_literal_to_ast(code, "config", r#"{"b": {"c": true}}"#, false);
+/// assert!(result.unwrap().contains("c: true"));
+/// ```
+pub fn merge_object_literal_to_ast(
+    file_content: &str,
+    var_name: &str,
+    json_spec: &str,
+    overwrite: bool,
+) -> Result<String, String> {
+    let spec: Value =
+        serde_json::from_str(json_spec).map_err(|e| format!("Invalid JSON spec: {}", e))?;
+    let Value::Object(spec) = spec else {
+        return Err("The JSON spec must be an object".to_string());
+    };
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let obj_expr = find_var_object_literal(&mut module, var_name).ok_or_else(|| {
+        format!(
+            "Variable '{}' is not declared with an object literal",
+            var_name
+        )
+    })?;
+
+    merge_json_into_object(obj_expr, &spec, overwrite);
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_object_literal_to_ast() {
+        let code = r#"
+            const chartOptions = {
+              responsive: true,
+              plugins: { legend: { display: false } },
+              colors,
+            };
+            "#;
+        let spec = r##"{
+          "responsive": false,
+          "plugins": { "legend": { "position": "top" }, "tooltip": { "enabled": true } },
+          "colors": ["#fff"],
+          "aspect-ratio": 1.5
+        }"##;
+
+        let result = merge_object_literal_to_ast(code, "chartOptions", spec, false).unwrap();
+        assert!(result.contains("responsive: true"));
+        assert!(result.contains("display: false,\n            position: \"top\""));
+        assert!(result.contains("tooltip: {\n            enabled: true"));
+        assert!(result.contains("    colors,"));
+        assert!(result.contains("\"aspect-ratio\": 1.5"));
+
+        let again = merge_object_literal_to_ast(&result, "chartOptions", spec, false).unwrap();
+        assert_eq!(again, result);
+
+        let result = merge_object_literal_to_ast(code, "chartOptions", spec, true).unwrap();
+        assert!(result.contains("responsive: false"));
+        assert!(result.contains("colors: [\n        \"#fff\"\n    ]"));
+        assert!(result.contains("display: false"));
+    }
+
+    #[test]
+    fn test_extend_object_property_by_names_to_ast() {
+        let code = r#"
+            const plugin = require("tailwindcss/plugin");
+            module.exports = { content: ["./js/**/*.js"], plugin };
+            "#;
+
+        let target = ObjectTarget::ModuleExports;
+        let result =
+            extend_object_property_by_names_to_ast(code, &target, ["plugin", "theme"]).unwrap();
+        assert!(result.contains(
+            "plugin,
+    theme
+}"
+        ));
+
+        let code = "export default defineConfig({ plugins: [] });";
+        let target = ObjectTarget::ExportDefault;
+        let result = extend_object_property_by_names_to_ast(code, &target, ["...shared"]).unwrap();
+        assert!(result.contains("...shared"));
+
+        let code = "export default { mounted() {} };";
+        assert!(extend_object_property_by_names_to_ast(code, &target, ["Copy"]).is_ok());
+
+        let code = "const Hooks = { Copy };\nexport default Hooks;";
+        let result = extend_object_property_by_names_to_ast(code, &target, ["Menu"]).unwrap();
+        assert!(result.contains("const Hooks = {\n    Copy,\n    Menu\n};"));
+
+        let target = ObjectTarget::Var("Components".to_string());
+        let code = "const Components = { Copy };";
+        let result = extend_object_property_by_names_to_ast(code, &target, ["Copy", "Menu"]);
+        assert!(result.unwrap().contains("Menu"));
+
+        let code = "export const Components = { Copy };";
+        let result = extend_object_property_by_names_to_ast(code, &target, ["Menu"]);
+        assert!(result
+            .unwrap()
+            .contains("export const Components = {\n    Copy,\n    Menu\n};"));
+
+        let code = "module.exports = plugin;";
+        let target = ObjectTarget::ModuleExports;
+        assert!(extend_object_property_by_names_to_ast(code, &target, ["theme"]).is_err());
+    }
+
+    #[test]
+    fn test_merge_object_literal_to_ast_errors() {
+        let code = "const config = buildConfig();";
+        assert!(merge_object_literal_to_ast(code, "config", "{}", false).is_err());
+        assert!(merge_object_literal_to_ast(code, "config", "[1]", false).is_err());
+        assert!(merge_object_literal_to_ast(code, "config", "{", false).is_err());
+        assert!(merge_object_literal_to_ast("let = ;", "config", "{}", false).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Sorting of the import statements of a module, in the order of Biome's organize imports,
+//! so the imports added by different installers end up in one canonical order and the
+//! diffs of a project stay small.
+//!
+//! Biome's analyzer is not a dependency of this crate, so the ordering is implemented
+//! here, on the text of the imports, and everything but the imports is left as written.
+
+use crate::parsers::javascript::helpers::{
+    attached_comments_start, begins_line, emit_import, line_start, parse, SpanOffsets,
+};
+use std::cmp::Ordering;
+use swc_common::comments::Comments;
+use swc_ecma_ast::*;
+
+/// The groups imports are sorted in, in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportGroup {
+    /// A source with a protocol, e.g. `"node:path"` or `"https://esm.sh/preact"`.
+    Protocol,
+    /// A bare specifier resolved from the packages, e.g. `"phoenix"` or `"@scope/lib"`.
+    Package,
+    /// A path alias of a bundler, e.g. `"@/hooks"`, `"~/hooks"` or `"#hooks"`.
+    Alias,
+    /// An absolute path, e.g. `"/assets/app.js"`.
+    Absolute,
+    /// A relative path, e.g. `"../vendor/topbar"` or `"./hooks"`.
+    Relative,
+}
+
+fn import_group(source: &str) -> ImportGroup {
+    let has_protocol = source.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    if source.starts_with('.') {
+        ImportGroup::Relative
+    } else if source.starts_with('/') {
+        ImportGroup::Absolute
+    } else if has_protocol {
+        ImportGroup::Protocol
+    } else if source.starts_with("@/") || source.starts_with('~') || source.starts_with('#') {
+        ImportGroup::Alias
+    } else {
+        ImportGroup::Package
+    }
+}
+
+/// Compares two names ignoring their case first, so `"Socket"` sorts between `"phoenix"`
+/// and `"topbar"` as a reader expects.
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string(),
+    }
+}
+
+fn specifier_name(specifier: &ImportSpecifier) -> Option<String> {
+    match specifier {
+        ImportSpecifier::Named(named) => Some(
+            named
+                .imported
+                .as_ref()
+                .map_or_else(|| named.local.sym.to_string(), export_name),
+        ),
+        _ => None,
+    }
+}
+
+/// The import with its named specifiers sorted, or `None` when they already are.
+fn sorted_specifiers(import: &ImportDecl) -> Option<ImportDecl> {
+    let order =
+        |a: &ImportSpecifier, b: &ImportSpecifier| match (specifier_name(a), specifier_name(b)) {
+            (Some(a), Some(b)) => compare_names(&a, &b),
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+    if import
+        .specifiers
+        .is_sorted_by(|a, b| order(a, b) != Ordering::Greater)
+    {
+        return None;
+    }
+
+    let mut sorted = import.clone();
+    sorted.specifiers.sort_by(order);
+    Some(sorted)
+}
+
+/// An import of a chunk, with the text it is moved with.
+struct ImportEntry {
+    group: ImportGroup,
+    source: String,
+    /// The comments on the lines right above the import and the import itself, up to the
+    /// end of its line when only a comment follows it.
+    text: String,
+}
+
+fn line_end(text: &str, offset: usize) -> usize {
+    let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let rest = text[offset..end].trim();
+    if rest.is_empty() || rest.starts_with("//") {
+        end
+    } else {
+        offset
+    }
+}
+
+/// Sorts the chunks of imports of `file_content`.
+///
+/// A chunk is a run of import statements that import bindings. A side-effect import such
+/// as `import "phoenix_html"` runs code as it is loaded, so it is never moved and ends a
+/// chunk, as any other statement does. Within a chunk the imports are sorted by group,
+/// from the packages with a protocol to the relative paths, then by source, and the named
+/// specifiers of each import are sorted by name. Imports of the same source keep their
+/// order.
+///
+/// The comments on the lines right above an import move with it, and sorting a sorted
+/// module returns it as it was.
+///
+/// # Arguments
+/// * `file_content` - The source code.
+/// * `separate_groups` - Whether the groups of a chunk are separated by a blank line.
+///
+/// # Returns
+/// * `Ok(String)` - The source with its imports sorted.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = "import topbar from \"../vendor/topbar\";\nimport { Socket } from \"phoenix\";\n";
+/// assert_eq!(
+///     organize_imports(code, false).unwrap(),
+///     "import { Socket } from \"phoenix\";\nimport topbar from \"../vendor/topbar\";\n"
+/// );
+/// ```
+pub fn organize_imports(file_content: &str, separate_groups: bool) -> Result<String, String> {
+    let (module, comments, cm) = parse(file_content)?;
+    let offsets = SpanOffsets::new(&cm);
+
+    let mut chunks: Vec<Vec<&ImportDecl>> = vec![vec![]];
+    for item in &module.body {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.specifiers.is_empty() => {
+                chunks.last_mut().unwrap().push(import)
+            }
+            _ if chunks.last().unwrap().is_empty() => {}
+            _ => chunks.push(vec![]),
+        }
+    }
+
+    let mut replacements = vec![];
+    for chunk in chunks.iter().filter(|chunk| !chunk.is_empty()) {
+        let mut entries = vec![];
+        let mut chunk_start = 0;
+        let mut chunk_end = 0;
+
+        for (index, import) in chunk.iter().enumerate() {
+            let (lo, hi) = offsets.range(import.span);
+            // A comment after the previous import on its line is moved with that import.
+            let leading: Vec<_> = comments
+                .get_leading(import.span.lo)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|comment| begins_line(file_content, offsets.offset(comment.span.lo)))
+                .collect();
+            // The comments above the first import of a chunk are kept in place unless they
+            // are attached to it, so a header of the file does not move.
+            let attached = if index == 0 {
+                attached_comments_start(file_content, &comments, offsets, import.span.lo)
+            } else {
+                leading
+                    .first()
+                    .map_or(lo, |comment| offsets.range(comment.span).0)
+            };
+            let start = line_start(file_content, attached);
+            let end = line_end(file_content, hi);
+
+            let text = match sorted_specifiers(import) {
+                Some(sorted) => format!(
+                    "{}{}{}",
+                    &file_content[start..lo],
+                    emit_import(sorted),
+                    &file_content[hi..end]
+                ),
+                None => file_content[start..end].to_string(),
+            };
+
+            if index == 0 {
+                chunk_start = start;
+            }
+            chunk_end = end;
+            entries.push(ImportEntry {
+                group: import_group(&import.src.value),
+                source: import.src.value.to_string(),
+                text,
+            });
+        }
+
+        entries.sort_by(|a, b| {
+            a.group
+                .cmp(&b.group)
+                .then_with(|| compare_names(&a.source, &b.source))
+        });
+
+        let mut organized = String::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if index > 0 {
+                let new_group = entries[index - 1].group != entry.group;
+                organized.push_str(if separate_groups && new_group {
+                    "\n\n"
+                } else {
+                    "\n"
+                });
+            }
+            organized.push_str(entry.text.trim_end());
+        }
+
+        if organized != file_content[chunk_start..chunk_end] {
+            replacements.push((chunk_start, chunk_end, organized));
+        }
+    }
+
+    let mut result = file_content.to_string();
+    for (start, end, organized) in replacements.into_iter().rev() {
+        result.replace_range(start..end, &organized);
+    }
+
+    parse(&result).map_err(|error| format!("Failed to organize the imports: {}", error))?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_organize_imports() {
+        let code = r#"// Phoenix app
+import "phoenix_html"
+// Establish Phoenix Socket and LiveView configuration.
+import topbar from "../vendor/topbar"
+import {LiveSocket} from "phoenix_live_view"
+import Hooks from "./hooks" // the hooks of the app
+import {Socket, Presence as P, Channel} from "phoenix"
+import path from "node:path"
+
+let csrfToken = 1
+import b from "b"
+import a from "a"
+"#;
+        let organized = organize_imports(code, false).unwrap();
+        assert_eq!(
+            organized,
+            r#"// Phoenix app
+import "phoenix_html"
+import path from "node:path"
+import { Channel, Presence as P, Socket } from "phoenix";
+import {LiveSocket} from "phoenix_live_view"
+// Establish Phoenix Socket and LiveView configuration.
+import topbar from "../vendor/topbar"
+import Hooks from "./hooks" // the hooks of the app
+
+let csrfToken = 1
+import a from "a"
+import b from "b"
+"#
+        );
+        assert_eq!(organize_imports(&organized, false).unwrap(), organized);
+
+        let separated = organize_imports(&organized, true).unwrap();
+        assert!(separated.contains(
+            "import {LiveSocket} from \"phoenix_live_view\"\n\n// Establish Phoenix Socket"
+        ));
+        assert_eq!(organize_imports(&separated, true).unwrap(), separated);
+
+        assert!(organize_imports("import {", false).is_err());
+    }
+
+    #[test]
+    fn test_import_group() {
+        assert_eq!(import_group("node:path"), ImportGroup::Protocol);
+        assert_eq!(import_group("https://esm.sh/preact"), ImportGroup::Protocol);
+        assert_eq!(import_group("@scope/lib"), ImportGroup::Package);
+        assert_eq!(import_group("@/hooks"), ImportGroup::Alias);
+        assert_eq!(import_group("/assets/app.js"), ImportGroup::Absolute);
+        assert_eq!(import_group("../vendor/topbar"), ImportGroup::Relative);
+    }
+}
@@ -13,29 +13,220 @@
 
 use crate::parsers::javascript::helpers::*;
 
-use super::ast::{FindCondition, Operation};
-use swc_common::{SyntaxContext, DUMMY_SP};
+use super::ast::{insert_import_to_ast, FindCondition, Operation};
+use super::matcher::callee_path;
+use super::object::{json_to_expr, merge_json_into_object};
+use super::removal::{emit_removal, RemovalMode};
+use super::unused::prune_imports_unused_after;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifTaggedEnum, NifUnitEnum};
+use swc_common::{EqIgnoreSpan, Span, Spanned, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
-use swc_ecma_visit::{VisitMut, VisitMutWith};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
 
-pub struct HookExtender<'a> {
-    target_var_name: &'a str,
+/// The object valued options of the LiveSocket that entries can be added to and removed from.
+///
+/// On the Elixir side these are the atoms `:hooks`, `:uploaders`, `:dom` and `:metadata`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum LiveSocketOption {
+    Hooks,
+    Uploaders,
+    Dom,
+    Metadata,
+}
+
+impl LiveSocketOption {
+    /// The key of the option in the LiveSocket options object.
+    pub fn key(&self) -> &'static str {
+        match self {
+            LiveSocketOption::Hooks => "hooks",
+            LiveSocketOption::Uploaders => "uploaders",
+            LiveSocketOption::Dom => "dom",
+            LiveSocketOption::Metadata => "metadata",
+        }
+    }
+}
+
+/// An action taken by an edit of a LiveSocket option, so installers can tell precisely what
+/// they modified.
+///
+/// On the Elixir side these are tuples, e.g. `{:created_key, "hooks"}` or
+/// `{:added_entry, "MyHook"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTaggedEnum))]
+pub enum EditAction {
+    /// The option was missing and was created.
+    CreatedKey(String),
+    /// The option referenced a variable, e.g. `hooks: hooks`, and was turned into an object
+    /// spreading it, e.g. `hooks: { ...hooks }`.
+    SpreadReference(String),
+    /// An entry was added, by its key, e.g. `MyHook` or `...Hooks`.
+    AddedEntry(String),
+    /// An entry was not added as its key is already registered.
+    SkippedEntry(String),
+    /// An entry was removed, by its key.
+    RemovedEntry(String),
+    /// An import the removed entries were the last to use was removed, by its local name.
+    PrunedImport(String),
+}
+
+/// The code produced by an edit along with the actions it took, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct EditLog {
+    pub code: String,
+    /// Whether the code differs from the source, i.e. whether there is anything to write.
+    pub changed: bool,
+    pub actions: Vec<EditAction>,
+}
+
+/// Where new entries go in an object valued option.
+///
+/// On the Elixir side these are the atoms `:append` and `:sorted`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum EntryOrder {
+    /// After the existing entries.
+    #[default]
+    Append,
+    /// Spreads after the existing spreads, which stay first, and the other entries
+    /// alphabetically among the existing ones, so repeated installs give the same object.
+    Sorted,
+}
+
+/// Where a missing option goes in the LiveSocket options object when it is created.
+///
+/// On the Elixir side these are the atoms `:end`, `:after_params` and `:first`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum KeyPosition {
+    /// After the existing options.
+    #[default]
+    End,
+    /// Right after `params`, like the Phoenix generator lays them out, or after the existing
+    /// options when there is no `params`.
+    AfterParams,
+    First,
+}
+
+impl KeyPosition {
+    /// The index of `props` a new option goes to.
+    fn index(&self, props: &[PropOrSpread]) -> usize {
+        match self {
+            KeyPosition::End => props.len(),
+            KeyPosition::AfterParams => props
+                .iter()
+                .rposition(|prop| prop_key_name(prop).as_deref() == Some("params"))
+                .map_or(props.len(), |index| index + 1),
+            KeyPosition::First => 0,
+        }
+    }
+}
+
+/// The LiveSocket instances an edit applies to, for files creating more than one, e.g.
+/// `liveSocket` and `adminSocket`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveSocketTarget<'a> {
+    /// The `new LiveSocket(...)` bound to the given variable name.
+    Named(&'a str),
+    /// Every `new LiveSocket(...)` bound to a variable.
+    All,
+}
+
+impl LiveSocketTarget<'_> {
+    fn matches(&self, var_name: &str) -> bool {
+        match self {
+            LiveSocketTarget::Named(name) => *name == var_name,
+            LiveSocketTarget::All => true,
+        }
+    }
+}
+
+/// Extends an object valued option of the LiveSocket, such as `hooks` or `uploaders`.
+pub struct LiveSocketOptionExtender<'a> {
+    target: LiveSocketTarget<'a>,
+    option_key: &'a str,
     new_objects: Vec<&'a str>,
     operation: Operation,
     find: FindCondition,
+    actions: Vec<EditAction>,
+    /// The variables the options are passed as, e.g. `socketOptions`.
+    options_refs: Vec<String>,
+    order: EntryOrder,
+    key_position: KeyPosition,
+    /// The spans of the removed entries.
+    removed: Vec<Span>,
 }
 
-impl<'a> HookExtender<'a> {
+impl<'a> LiveSocketOptionExtender<'a> {
+    /// An extender of the `hooks` option.
     pub fn new(target_var_name: &'a str, new_objects: Vec<&'a str>) -> Self {
+        Self::for_option(target_var_name, "hooks", new_objects)
+    }
+
+    /// An extender of the given option, e.g. `uploaders`.
+    pub fn for_option(
+        target_var_name: &'a str,
+        option_key: &'a str,
+        new_objects: Vec<&'a str>,
+    ) -> Self {
+        Self::for_target(
+            LiveSocketTarget::Named(target_var_name),
+            option_key,
+            new_objects,
+        )
+    }
+
+    /// An extender of the given option of the targeted LiveSocket instances.
+    pub fn for_target(
+        target: LiveSocketTarget<'a>,
+        option_key: &'a str,
+        new_objects: Vec<&'a str>,
+    ) -> Self {
         Self {
-            target_var_name,
+            target,
+            option_key,
             new_objects,
             find: FindCondition::NotFound("".to_string()),
             operation: Operation::Edit,
+            actions: vec![],
+            options_refs: vec![],
+            order: EntryOrder::Append,
+            key_position: KeyPosition::End,
+            removed: vec![],
+        }
+    }
+
+    /// Sets where the new entries go.
+    pub fn with_order(mut self, order: EntryOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets where the option goes when it is created.
+    pub fn with_key_position(mut self, key_position: KeyPosition) -> Self {
+        self.key_position = key_position;
+        self
+    }
+
+    /// Extends the object literals of the options passed as a variable, e.g.
+    /// `new LiveSocket("/live", Socket, socketOptions)`.
+    fn extend_options_refs(&mut self, module: &mut Module) {
+        for var_name in self.options_refs.clone() {
+            if let Some(obj_expr) = find_scoped_object_literal(module, &var_name) {
+                self.find = FindCondition::Found;
+                self.extend_or_create_hooks(obj_expr);
+            }
         }
     }
 
     fn extend_or_create_hooks(&mut self, obj_expr: &mut ObjectLit) {
+        // Nothing to add (e.g., we are only looking for the LiveSocket), so keep the shape as is
+        if self.new_objects.is_empty() {
+            return;
+        }
+
         // Find the hooks property
         let hooks_prop_index = obj_expr.props.iter().position(|prop| {
             if let PropOrSpread::Prop(prop) = prop {
@@ -44,7 +235,7 @@ impl<'a> HookExtender<'a> {
                     ..
                 }) = &**prop
                 {
-                    return ident.sym == *"hooks";
+                    return ident.sym == *self.option_key;
                 }
             }
             false
@@ -58,36 +249,11 @@ impl<'a> HookExtender<'a> {
                         // Case 1: hooks is an inline object literal
                         Expr::Object(hooks_obj) => {
                             // Extend existing inline object
-                            for new_object in &self.new_objects {
-                                let already_exists =
-                                    hooks_obj.props.iter().any(|prop| match prop {
-                                        PropOrSpread::Prop(prop) => {
-                                            if let Prop::Shorthand(ident) = &**prop {
-                                                ident.sym == *new_object
-                                            } else {
-                                                false
-                                            }
-                                        }
-                                        PropOrSpread::Spread(spread) => {
-                                            if let Expr::Ident(ident) = &*spread.expr {
-                                                let spread_sym = format!("...{}", ident.sym);
-                                                spread_sym == *new_object
-                                            } else {
-                                                false
-                                            }
-                                        }
-                                    });
-
-                                if !already_exists {
-                                    hooks_obj.props.push(PropOrSpread::Prop(Box::new(
-                                        Prop::Shorthand(Ident::new(
-                                            (*new_object).into(),
-                                            DUMMY_SP,
-                                            SyntaxContext::empty(),
-                                        )),
-                                    )));
-                                }
-                            }
+                            self.actions.extend(push_hook_entries_in_order(
+                                &mut hooks_obj.props,
+                                &self.new_objects,
+                                self.order,
+                            ));
                         }
                         // Case 2: hooks is an identifier reference (e.g., hooks: hooks)
                         Expr::Ident(ident) => {
@@ -98,15 +264,13 @@ impl<'a> HookExtender<'a> {
                             })];
 
                             // Add the new objects
-                            for new_object in &self.new_objects {
-                                new_props.push(PropOrSpread::Prop(Box::new(Prop::Shorthand(
-                                    Ident::new(
-                                        (*new_object).into(),
-                                        DUMMY_SP,
-                                        SyntaxContext::empty(),
-                                    ),
-                                ))));
-                            }
+                            self.actions
+                                .push(EditAction::SpreadReference(ident.sym.to_string()));
+                            self.actions.extend(push_hook_entries_in_order(
+                                &mut new_props,
+                                &self.new_objects,
+                                self.order,
+                            ));
 
                             // Replace the value with the new object
                             **value = Expr::Object(ObjectLit {
@@ -122,79 +286,412 @@ impl<'a> HookExtender<'a> {
             }
         } else {
             // Create hooks if it doesn't exist
+            let mut props = vec![];
+            self.actions
+                .push(EditAction::CreatedKey(self.option_key.to_string()));
+            self.actions.extend(push_hook_entries_in_order(
+                &mut props,
+                &self.new_objects,
+                self.order,
+            ));
             let new_hooks = ObjectLit {
                 span: DUMMY_SP,
-                props: self
-                    .new_objects
-                    .iter()
-                    .map(|name| {
-                        PropOrSpread::Prop(Box::new(Prop::Shorthand(Ident::new(
-                            (*name).into(),
-                            DUMMY_SP,
-                            SyntaxContext::empty(),
-                        ))))
-                    })
-                    .collect(),
+                props,
             };
 
-            obj_expr
-                .props
-                .push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            let index = self.key_position.index(&obj_expr.props);
+            obj_expr.props.insert(
+                index,
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
                     key: PropName::Ident(
-                        Ident::new("hooks".into(), DUMMY_SP, SyntaxContext::empty()).into(),
+                        Ident::new(self.option_key.into(), DUMMY_SP, SyntaxContext::empty()).into(),
                     ),
                     value: Box::new(Expr::Object(new_hooks)),
-                }))));
+                }))),
+            );
         }
     }
 
+    /// Removes the given objects from the `hooks` value of the LiveSocket options.
+    ///
+    /// When `hooks` is an identifier reference (e.g., `hooks: hooks`), nothing is removed
+    /// here and the referenced identifier name is returned, so the caller can follow it to
+    /// its declaration.
     fn remove_objects_from_hooks(
         &mut self,
         obj_expr: &mut ObjectLit,
         objects_to_remove: Vec<&str>,
-    ) {
-        if let Some(hooks_property) = obj_expr.props.iter_mut().find_map(|prop| {
+    ) -> Option<String> {
+        let hooks_value = obj_expr.props.iter_mut().find_map(|prop| {
             if let PropOrSpread::Prop(prop) = prop {
                 if let Prop::KeyValue(KeyValueProp {
                     key: PropName::Ident(ident),
                     value,
                 }) = &mut **prop
                 {
-                    if ident.sym == *"hooks" {
-                        if let Expr::Object(obj_expr) = &mut **value {
-                            return Some(obj_expr);
-                        }
+                    if ident.sym == *self.option_key {
+                        return Some(value);
                     }
                 }
             }
             None
-        }) {
-            hooks_property.props.retain(|prop| match prop {
-                PropOrSpread::Prop(prop) => {
-                    if let Prop::Shorthand(ident) = &**prop {
-                        !objects_to_remove.contains(&&*ident.sym)
-                    } else {
-                        true
-                    }
-                }
-                PropOrSpread::Spread(spread) => {
-                    if let Expr::Ident(ident) = &*spread.expr {
-                        !objects_to_remove.contains(&format!("...{}", ident.sym).as_str())
-                    } else {
-                        true
-                    }
+        })?;
+
+        match &mut **hooks_value {
+            Expr::Object(hooks_obj) => {
+                self.actions.extend(retain_hook_props(
+                    hooks_obj,
+                    &objects_to_remove,
+                    &mut self.removed,
+                ));
+                None
+            }
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a hook entry into its key and value snippet: `Sortable: window.Sortable` is the
+/// key `Sortable` with the value `window.Sortable`, while bare names and spreads have none.
+/// A quoted key may contain colons, e.g. `"phx:scroll-top": { mounted() {} }`, and the value
+/// can be any expression, such as an inline hook definition.
+fn split_hook_entry(entry: &str) -> (&str, Option<&str>) {
+    let entry = entry.trim();
+    if entry.starts_with("...") {
+        return (entry, None);
+    }
+
+    if let Some(quote) = entry.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        if let Some(close) = entry[1..].find(quote).map(|index| index + 1) {
+            if let Some(value) = entry[close + 1..].trim_start().strip_prefix(':') {
+                return (&entry[1..close], Some(value.trim()));
+            }
+        }
+    }
+
+    match entry.split_once(':') {
+        Some((key, value)) => (key.trim(), Some(value.trim())),
+        None => (entry, None),
+    }
+}
+
+fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Checks that the value of every `Name: value` hook entry is a single expression.
+pub fn check_hook_entries(names: &[&str]) -> Result<(), String> {
+    for name in names {
+        if let (_, Some(value)) = split_hook_entry(name) {
+            parse_expr(value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds a hook entry from its name; names starting with `...` become spread elements,
+/// and `Name: value` entries key-value props, e.g. `Chart: Charts.LineChart`.
+fn new_hook_entry(name: &str) -> Option<PropOrSpread> {
+    let ident = |sym: &str| Ident::new(sym.into(), DUMMY_SP, SyntaxContext::empty());
+
+    if let Some(spread_name) = name.trim().strip_prefix("...") {
+        return Some(PropOrSpread::Spread(SpreadElement {
+            dot3_token: DUMMY_SP,
+            expr: Box::new(Expr::Ident(ident(spread_name))),
+        }));
+    }
+
+    let prop = match split_hook_entry(name) {
+        (key, Some(value)) => {
+            let key = if is_identifier_name(key) {
+                PropName::Ident(ident(key).into())
+            } else {
+                PropName::Str(Str {
+                    span: DUMMY_SP,
+                    value: key.into(),
+                    raw: None,
+                })
+            };
+            Prop::KeyValue(KeyValueProp {
+                key,
+                value: parse_expr(value).ok()?,
+            })
+        }
+        (name, None) => Prop::Shorthand(ident(name)),
+    };
+    Some(PropOrSpread::Prop(Box::new(prop)))
+}
+
+/// Appends the given hook entries, skipping every name that is already registered, so
+/// running the same install more than once does not duplicate shorthand, key-value or
+/// spread entries. Entries whose value is not an expression are skipped, see
+/// [`check_hook_entries`].
+///
+/// Returns the entries added and skipped, by key.
+pub fn push_hook_entries(props: &mut Vec<PropOrSpread>, names: &[&str]) -> Vec<EditAction> {
+    push_hook_entries_in_order(props, names, EntryOrder::Append)
+}
+
+/// The index a new entry goes at in [`EntryOrder::Sorted`]: a spread after the last spread,
+/// and any other entry before the first entry whose key sorts after its own.
+fn sorted_entry_index(props: &[PropOrSpread], entry: &PropOrSpread) -> usize {
+    let spreads_end = props
+        .iter()
+        .rposition(|prop| matches!(prop, PropOrSpread::Spread(_)))
+        .map_or(0, |index| index + 1);
+    let Some(key) = object_entry_key(entry).filter(|_| matches!(entry, PropOrSpread::Prop(_)))
+    else {
+        return spreads_end;
+    };
+
+    let sort_key = |key: &str| (key.to_lowercase(), key.to_string());
+    props[spreads_end..]
+        .iter()
+        .position(|prop| {
+            matches!(prop, PropOrSpread::Prop(_))
+                && object_entry_key(prop).is_some_and(|other| sort_key(&other) > sort_key(&key))
+        })
+        .map_or(props.len(), |index| spreads_end + index)
+}
+
+/// Same as [`push_hook_entries`], putting the entries where `order` says.
+pub fn push_hook_entries_in_order(
+    props: &mut Vec<PropOrSpread>,
+    names: &[&str],
+    order: EntryOrder,
+) -> Vec<EditAction> {
+    let mut actions = vec![];
+    for name in names {
+        let (key, _) = split_hook_entry(name);
+        if props
+            .iter()
+            .any(|prop| object_entry_key(prop).as_deref() == Some(key))
+        {
+            actions.push(EditAction::SkippedEntry(key.to_string()));
+            continue;
+        }
+        if let Some(entry) = new_hook_entry(name) {
+            match order {
+                EntryOrder::Append => props.push(entry),
+                EntryOrder::Sorted => props.insert(sorted_entry_index(props, &entry), entry),
+            }
+            actions.push(EditAction::AddedEntry(key.to_string()));
+        }
+    }
+    actions
+}
+
+/// Removes the given entries, matched by their key like [`object_entry_key`] reads it, and
+/// returns the removed ones, by key. The spans of the removed entries are pushed to `spans`.
+fn retain_hook_props(
+    hooks_obj: &mut ObjectLit,
+    objects_to_remove: &[&str],
+    spans: &mut Vec<Span>,
+) -> Vec<EditAction> {
+    let mut removed = vec![];
+    hooks_obj.props.retain(|prop| match object_entry_key(prop) {
+        Some(key) if objects_to_remove.contains(&key.as_str()) => {
+            removed.push(EditAction::RemovedEntry(key));
+            spans.push(prop.span());
+            false
+        }
+        _ => true,
+    });
+    removed
+}
+
+/// The variable declarations a LiveSocket and its options may be declared in: those of the
+/// module body and of the callbacks passed to top-level calls, such as
+/// `document.addEventListener("DOMContentLoaded", () => { ... })`.
+fn live_socket_var_decls(module: &mut Module) -> Vec<&mut VarDecl> {
+    let mut var_decls = vec![];
+    for item in &mut module.body {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl)))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var_decl),
+                ..
+            })) => var_decls.push(&mut **var_decl),
+            ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) => {
+                let Expr::Call(call) = &mut **expr else {
+                    continue;
+                };
+                for arg in &mut call.args {
+                    let body = match &mut *arg.expr {
+                        Expr::Arrow(ArrowExpr { body, .. }) => match &mut **body {
+                            BlockStmtOrExpr::BlockStmt(block) => block,
+                            BlockStmtOrExpr::Expr(_) => continue,
+                        },
+                        Expr::Fn(FnExpr { function, .. }) => match &mut function.body {
+                            Some(block) => block,
+                            None => continue,
+                        },
+                        _ => continue,
+                    };
+                    var_decls.extend(body.stmts.iter_mut().filter_map(|stmt| match stmt {
+                        Stmt::Decl(Decl::Var(var_decl)) => Some(&mut **var_decl),
+                        _ => None,
+                    }));
                 }
-            });
+            }
+            _ => {}
         }
     }
+    var_decls
+}
+
+/// Same as [`find_var_object_literal`], also looking into the callbacks of top-level calls,
+/// see [`live_socket_var_decls`].
+fn find_scoped_object_literal<'a>(
+    module: &'a mut Module,
+    var_name: &str,
+) -> Option<&'a mut ObjectLit> {
+    live_socket_var_decls(module)
+        .into_iter()
+        .flat_map(|var_decl| var_decl.decls.iter_mut())
+        .find_map(|decl| match (&decl.name, &mut decl.init) {
+            (Pat::Ident(BindingIdent { id, .. }), Some(init)) if id.sym == var_name => {
+                match &mut **init {
+                    Expr::Object(obj_expr) => Some(obj_expr),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+}
+
+/// Finds the last argument of a `new LiveSocket(...)` bound to `var_name`, at the top level or
+/// in the callback of a top-level call.
+fn find_live_socket_options_arg<'a>(
+    module: &'a mut Module,
+    var_name: &str,
+) -> Option<&'a mut Expr> {
+    live_socket_var_decls(module)
+        .into_iter()
+        .find_map(|var_decl| {
+            var_decl.decls.iter_mut().find_map(|decl| {
+                let (Pat::Ident(BindingIdent { id, .. }), Some(init)) =
+                    (&decl.name, &mut decl.init)
+                else {
+                    return None;
+                };
+                if id.sym != var_name {
+                    return None;
+                }
+
+                let Expr::New(new_expr) = &mut **init else {
+                    return None;
+                };
+                if !is_new_live_socket(new_expr) {
+                    return None;
+                }
+
+                new_expr.args.as_mut()?.last_mut().map(|arg| &mut *arg.expr)
+            })
+        })
 }
 
-impl VisitMut for HookExtender<'_> {
+/// Finds the options object literal of the `new LiveSocket(...)` bound to `var_name`,
+/// following the options passed as a variable to its declaration.
+fn find_live_socket_options<'a>(
+    module: &'a mut Module,
+    var_name: &str,
+) -> Option<&'a mut ObjectLit> {
+    let options_ref = match find_live_socket_options_arg(module, var_name)? {
+        Expr::Object(_) => None,
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        _ => return None,
+    };
+
+    match options_ref {
+        Some(options_ref) => find_scoped_object_literal(module, &options_ref),
+        None => match find_live_socket_options_arg(module, var_name)? {
+            Expr::Object(obj_expr) => Some(obj_expr),
+            _ => None,
+        },
+    }
+}
+
+/// The error of the LiveSocket options built by a call, e.g. `buildOpts()`, which can not be
+/// edited statically.
+fn unsupported_options_message(call: &CallExpr) -> String {
+    let callee = match &call.callee {
+        Callee::Expr(callee) => callee_path(callee),
+        _ => None,
+    };
+    format!(
+        "The LiveSocket options are built by calling `{}`, only an object literal or a variable \
+         holding one is supported",
+        callee.unwrap_or_else(|| "a function".to_string())
+    )
+}
+
+/// Returns the callee of the call building the options of the targeted LiveSocket, e.g.
+/// `buildOpts` for `new LiveSocket("/live", Socket, buildOpts())`, which the option edits
+/// report as an unsupported options shape.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, buildOpts());"#;
+/// let target = LiveSocketTarget::Named("liveSocket");
+/// assert_eq!(unsupported_live_socket_options(code, target), Some("buildOpts".to_string()));
+/// ```
+pub fn unsupported_live_socket_options(
+    file_content: &str,
+    target: LiveSocketTarget,
+) -> Option<String> {
+    let (mut module, _comments, _cm) = parse(file_content).ok()?;
+    live_socket_var_decls(&mut module)
+        .into_iter()
+        .find_map(|var_decl| {
+            var_decl.decls.iter().find_map(|decl| {
+                let (Pat::Ident(BindingIdent { id, .. }), Some(init)) = (&decl.name, &decl.init)
+                else {
+                    return None;
+                };
+                let Expr::New(new_expr) = &**init else {
+                    return None;
+                };
+                if !target.matches(&id.sym) || !is_new_live_socket(new_expr) {
+                    return None;
+                }
+
+                match &*new_expr.args.as_ref()?.last()?.expr {
+                    Expr::Call(CallExpr {
+                        callee: Callee::Expr(callee),
+                        ..
+                    }) => Some(callee_path(callee).unwrap_or_else(|| "a function".to_string())),
+                    _ => None,
+                }
+            })
+        })
+}
+
+/// Returns the value of the `hooks` key of the LiveSocket options, if any.
+fn hooks_value(obj_expr: &ObjectLit) -> Option<&Expr> {
+    obj_expr.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(ident),
+                value,
+            }) if ident.sym == *"hooks" => Some(&**value),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+impl VisitMut for LiveSocketOptionExtender<'_> {
     fn visit_mut_var_decl(&mut self, var_decl: &mut VarDecl) {
         if matches!(self.operation, Operation::Edit) {
             for decl in &mut var_decl.decls {
                 if let Some(ident) = decl.name.as_ident() {
-                    if ident.sym == self.target_var_name {
+                    if self.target.matches(&ident.sym) {
                         if let Some(init) = &mut decl.init {
                             if let Expr::New(new_expr) = init.as_mut() {
                                 if let Expr::Ident(callee_ident) = &*new_expr.callee {
@@ -204,9 +701,20 @@ impl VisitMut for HookExtender<'_> {
                                         if let Some(args) = &mut new_expr.args {
                                             if let Some(ExprOrSpread { expr, .. }) = args.last_mut()
                                             {
-                                                if let Expr::Object(obj_expr) = &mut **expr {
-                                                    self.find = FindCondition::Found;
-                                                    self.extend_or_create_hooks(obj_expr);
+                                                match &mut **expr {
+                                                    Expr::Object(obj_expr) => {
+                                                        self.find = FindCondition::Found;
+                                                        self.extend_or_create_hooks(obj_expr);
+                                                    }
+                                                    Expr::Ident(ident) => self
+                                                        .options_refs
+                                                        .push(ident.sym.to_string()),
+                                                    Expr::Call(call) => {
+                                                        self.find = FindCondition::FoundError(
+                                                            unsupported_options_message(call),
+                                                        )
+                                                    }
+                                                    _ => {}
                                                 }
                                             }
                                         }
@@ -242,25 +750,205 @@ impl VisitMut for HookExtender<'_> {
 /// - Checks for the presence of `liveSocket` in the AST.
 /// - Finds or initializes the `hooks` object in the AST.
 /// - Adds new properties to the `hooks` object without duplicating existing ones.
-///
-/// Warning: If you use the spread operator (e.g., ..Hooks) multiple times, the code does
-/// not deduplicate it, and it will include each occurrence as is.
+/// - Names starting with `...` (e.g., `...Hooks`) are added as spread entries, keyed by their
+///   identifier, so an existing `...Hooks` is never added twice.
+/// - Names of the form `Name: value` (e.g., `Sortable: window.Sortable`) are added as
+///   key-value entries with the parsed value expression, keyed by `Name`.
 pub fn extend_hook_object_to_ast(
     file_content: &str,
     new_objects: Vec<&str>,
 ) -> Result<String, String> {
-    let mut hook_extender = HookExtender::new("liveSocket", new_objects);
+    extend_hook_object_in_order_to_ast(
+        file_content,
+        new_objects,
+        EntryOrder::Append,
+        KeyPosition::End,
+    )
+}
+
+/// Adds entries to an object valued option of the LiveSocket, like
+/// [`extend_hook_object_to_ast`] does for `hooks`, e.g. `uploaders`, `dom` or `metadata`.
+///
+/// Entries are names (`S3`), spreads (`...Uploaders`) or `Name: value` pairs
+/// (`onBeforeElUpdated: (from, to) => {}`). The option is created when it is missing, and
+/// entries whose key is already registered are skipped, so running it twice is a no-op.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+/// let result = extend_live_socket_option_to_ast(code, "uploaders", vec!["S3"]).unwrap();
+/// assert!(result.contains("uploaders: {"));
+/// ```
+pub fn extend_live_socket_option_to_ast(
+    file_content: &str,
+    option_key: &str,
+    new_objects: Vec<&str>,
+) -> Result<String, String> {
+    extend_live_socket_option_for_target_to_ast(
+        file_content,
+        LiveSocketTarget::Named("liveSocket"),
+        option_key,
+        new_objects,
+    )
+}
+
+/// Same as [`extend_live_socket_option_to_ast`], for the targeted LiveSocket instances
+/// instead of `liveSocket`, see [`list_live_sockets_from_ast`].
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});
+/// let adminSocket = new LiveSocket("/admin/live", Socket, {});"#;
+/// let target = LiveSocketTarget::Named("adminSocket");
+/// let result =
+///     extend_live_socket_option_for_target_to_ast(code, target, "hooks", vec!["Admin"]).unwrap();
+/// assert!(result.contains("hooks: {\n        Admin\n    }"));
+/// ```
+pub fn extend_live_socket_option_for_target_to_ast(
+    file_content: &str,
+    target: LiveSocketTarget,
+    option_key: &str,
+    new_objects: Vec<&str>,
+) -> Result<String, String> {
+    extend_live_socket_option_with_log_to_ast(file_content, target, option_key, new_objects)
+        .map(|log| log.code)
+}
+
+/// Same as [`extend_live_socket_option_for_target_to_ast`], along with the actions taken,
+/// e.g. `[CreatedKey("hooks"), AddedEntry("MyHook")]`.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: hooks });"#;
+/// let target = LiveSocketTarget::Named("liveSocket");
+/// let log = extend_live_socket_option_with_log_to_ast(code, target, "hooks", vec!["MyHook"]);
+/// assert_eq!(
+///     log.unwrap().actions,
+///     [
+///         EditAction::SpreadReference("hooks".into()),
+///         EditAction::AddedEntry("MyHook".into())
+///     ]
+/// );
+/// ```
+pub fn extend_live_socket_option_with_log_to_ast(
+    file_content: &str,
+    target: LiveSocketTarget,
+    option_key: &str,
+    new_objects: Vec<&str>,
+) -> Result<EditLog, String> {
+    extend_live_socket_option_in_order(
+        file_content,
+        target,
+        option_key,
+        new_objects,
+        EntryOrder::Append,
+        KeyPosition::End,
+    )
+}
+
+/// Same as [`extend_hook_object_to_ast`], putting the new hooks where `order` says, e.g.
+/// alphabetically among the existing ones with [`EntryOrder::Sorted`], and a missing `hooks`
+/// where `key_position` says, e.g. after `params` with [`KeyPosition::AfterParams`].
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: { ...Hooks, Alpha, Zeta } });"#;
+/// let result =
+///     extend_hook_object_in_order_to_ast(code, vec!["Beta"], EntryOrder::Sorted, KeyPosition::End);
+/// assert!(result.unwrap().contains("...Hooks,\n        Alpha,\n        Beta,\n        Zeta"));
+/// ```
+pub fn extend_hook_object_in_order_to_ast(
+    file_content: &str,
+    new_objects: Vec<&str>,
+    order: EntryOrder,
+    key_position: KeyPosition,
+) -> Result<String, String> {
+    extend_live_socket_option_in_order(
+        file_content,
+        LiveSocketTarget::Named("liveSocket"),
+        "hooks",
+        new_objects,
+        order,
+        key_position,
+    )
+    .map(|log| log.code)
+}
+
+fn extend_live_socket_option_in_order(
+    file_content: &str,
+    target: LiveSocketTarget,
+    option_key: &str,
+    new_objects: Vec<&str>,
+    order: EntryOrder,
+    key_position: KeyPosition,
+) -> Result<EditLog, String> {
+    check_hook_entries(&new_objects)?;
+    let mut hook_extender = LiveSocketOptionExtender::for_target(target, option_key, new_objects)
+        .with_order(order)
+        .with_key_position(key_position);
+
+    let (mut module, comments, cm) =
+        parse(file_content).map_err(|_| "Failed to parse JavaScript content".to_string())?;
+    let original = module.clone();
+    module.visit_mut_with(&mut hook_extender);
+    hook_extender.extend_options_refs(&mut module);
 
-    let result = code_gen_from_ast_vist(file_content, &mut hook_extender);
+    let code = code_gen_edit(file_content, &original, &mut module, comments, cm);
     if hook_extender.find == FindCondition::Found {
-        result
+        Ok(EditLog {
+            changed: code != file_content,
+            code,
+            actions: hook_extender.actions,
+        })
     } else {
         Err(hook_extender.find.message().to_string())
     }
 }
 
+fn is_new_live_socket(new_expr: &NewExpr) -> bool {
+    callee_path(&new_expr.callee).as_deref() == Some("LiveSocket")
+}
+
+/// Finds the names of the variables bound to a `new LiveSocket(...)`, in source order.
+#[derive(Debug, Default)]
+struct LiveSocketFinder {
+    names: Vec<String>,
+}
+
+impl Visit for LiveSocketFinder {
+    fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+        if let (Pat::Ident(BindingIdent { id, .. }), Some(init)) = (&decl.name, &decl.init) {
+            if let Expr::New(new_expr) = &**init {
+                if is_new_live_socket(new_expr) {
+                    self.names.push(id.sym.to_string());
+                }
+            }
+        }
+        decl.visit_children_with(self);
+    }
+}
+
+/// Lists the variables a `new LiveSocket(...)` is bound to, e.g. `liveSocket` and
+/// `adminSocket` in apps creating one socket per live session.
+///
+/// # Returns
+/// A `Result` containing the variable names in source order, or an error message if
+/// parsing fails.
+pub fn list_live_sockets_from_ast(file_content: &str) -> Result<Vec<String>, String> {
+    let (module, _comments, _cm) = parse(file_content)?;
+    Ok(list_live_sockets_in_module(&module))
+}
+
+/// Lists the variables a `new LiveSocket(...)` is bound to in a parsed module, see
+/// [`list_live_sockets_from_ast`].
+pub fn list_live_sockets_in_module(module: &Module) -> Vec<String> {
+    let mut finder = LiveSocketFinder::default();
+    module.visit_with(&mut finder);
+    finder.names
+}
+
 pub fn find_live_socket_node_from_ast(file_content: &str) -> Result<bool, bool> {
-    let mut hook_extender = HookExtender::new("liveSocket", vec![]);
+    let mut hook_extender = LiveSocketOptionExtender::new("liveSocket", vec![]);
     let _result = code_gen_from_ast_vist(file_content, &mut hook_extender);
     if hook_extender.find == FindCondition::Found {
         Ok(true)
@@ -291,25 +979,84 @@ pub fn remove_objects_of_hooks_from_ast(
     file_content: &str,
     objects_to_remove: Vec<&str>,
 ) -> Result<String, String> {
-    let mut hook_extender = HookExtender::new("liveSocket", vec![]);
+    remove_live_socket_option_entries_from_ast(file_content, "hooks", objects_to_remove)
+}
+
+/// Removes entries from an object valued option of the LiveSocket, like
+/// [`remove_objects_of_hooks_from_ast`] does for `hooks`, e.g. `uploaders`.
+pub fn remove_live_socket_option_entries_from_ast(
+    file_content: &str,
+    option_key: &str,
+    objects_to_remove: Vec<&str>,
+) -> Result<String, String> {
+    remove_live_socket_option_entries_for_target_from_ast(
+        file_content,
+        LiveSocketTarget::Named("liveSocket"),
+        option_key,
+        objects_to_remove,
+    )
+}
+
+/// Same as [`remove_live_socket_option_entries_from_ast`], for the targeted LiveSocket
+/// instances instead of `liveSocket`.
+pub fn remove_live_socket_option_entries_for_target_from_ast(
+    file_content: &str,
+    target: LiveSocketTarget,
+    option_key: &str,
+    objects_to_remove: Vec<&str>,
+) -> Result<String, String> {
+    remove_live_socket_option_entries_with_log_from_ast(
+        file_content,
+        target,
+        option_key,
+        objects_to_remove,
+        RemovalMode::Delete,
+        false,
+    )
+    .map(|log| log.code)
+}
 
-    let (mut module, comments, cm) = parse(file_content).expect("Failed to parse imports");
+/// Same as [`remove_live_socket_option_entries_for_target_from_ast`], along with the entries
+/// removed, e.g. `[RemovedEntry("MyHook")]`. With [`RemovalMode::CommentOut`], the entries are
+/// commented out instead of deleted. With `prune_unused_imports`, the imports the entries
+/// were the last to use are removed too, e.g. `[PrunedImport("MyHook")]`.
+pub fn remove_live_socket_option_entries_with_log_from_ast(
+    file_content: &str,
+    target: LiveSocketTarget,
+    option_key: &str,
+    objects_to_remove: Vec<&str>,
+    mode: RemovalMode,
+    prune_unused_imports: bool,
+) -> Result<EditLog, String> {
+    let mut hook_extender = LiveSocketOptionExtender::for_target(target, option_key, vec![]);
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
 
     module.visit_mut_with(&mut hook_extender);
 
-    for item in &mut module.body {
-        if let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = item {
-            for decl in &mut var_decl.decls {
-                if let Some(init) = &mut decl.init {
-                    if let Expr::New(new_expr) = init.as_mut() {
-                        if let Some(args) = &mut new_expr.args {
-                            if let Some(ExprOrSpread { expr, .. }) = args.last_mut() {
-                                if let Expr::Object(obj_expr) = &mut **expr {
-                                    hook_extender.remove_objects_from_hooks(
-                                        obj_expr,
-                                        objects_to_remove.clone(),
-                                    );
-                                }
+    let mut referenced_hooks = vec![];
+    for var_decl in live_socket_var_decls(&mut module) {
+        for decl in &mut var_decl.decls {
+            if !decl
+                .name
+                .as_ident()
+                .is_some_and(|ident| target.matches(&ident.sym))
+            {
+                continue;
+            }
+            if let Some(init) = &mut decl.init {
+                if let Expr::New(new_expr) = init.as_mut() {
+                    if !is_new_live_socket(new_expr) {
+                        continue;
+                    }
+                    if let Some(args) = &mut new_expr.args {
+                        if let Some(ExprOrSpread { expr, .. }) = args.last_mut() {
+                            if let Expr::Object(obj_expr) = &mut **expr {
+                                referenced_hooks.extend(hook_extender.remove_objects_from_hooks(
+                                    obj_expr,
+                                    objects_to_remove.clone(),
+                                ));
                             }
                         }
                     }
@@ -318,12 +1065,1107 @@ pub fn remove_objects_of_hooks_from_ast(
         }
     }
 
-    let result = code_gen_from_ast_module(&mut module, comments, cm);
-    if hook_extender.find == FindCondition::Found {
-        Ok(result)
-    } else {
-        Err(hook_extender.find.message().to_string())
-    }
+    // The options are passed as a variable, e.g. `new LiveSocket("/live", Socket, opts)`
+    for var_name in hook_extender.options_refs.clone() {
+        if let Some(obj_expr) = find_scoped_object_literal(&mut module, &var_name) {
+            hook_extender.find = FindCondition::Found;
+            referenced_hooks.extend(
+                hook_extender.remove_objects_from_hooks(obj_expr, objects_to_remove.clone()),
+            );
+        }
+    }
+
+    // Case 2: hooks is an identifier reference (e.g., hooks: hooks), so follow it to its
+    // declaration and remove the objects from the referenced object literal.
+    for var_name in referenced_hooks {
+        if let Some(hooks_obj) = find_scoped_object_literal(&mut module, &var_name) {
+            hook_extender.actions.extend(retain_hook_props(
+                hooks_obj,
+                &objects_to_remove,
+                &mut hook_extender.removed,
+            ));
+        }
+    }
+
+    if prune_unused_imports {
+        for (local, span) in prune_imports_unused_after(&original, &mut module) {
+            hook_extender.actions.push(EditAction::PrunedImport(local));
+            hook_extender.removed.push(span);
+        }
+    }
+
+    let code = emit_removal(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+        &hook_extender.removed,
+        mode,
+    );
+    if hook_extender.find == FindCondition::Found {
+        Ok(EditLog {
+            changed: code != file_content,
+            code,
+            actions: hook_extender.actions,
+        })
+    } else {
+        Err(hook_extender.find.message().to_string())
+    }
+}
+
+/// Ensures the Phoenix 1.8 colocated hooks of an application are wired into the LiveSocket.
+///
+/// Colocated hooks are compiled into the `phoenix-colocated/<app_name>` module and must be
+/// imported and spread into the `hooks` object:
+///
+/// ```javascript
+/// import { hooks as colocatedHooks } from "phoenix-colocated/my_app";
+/// let liveSocket = new LiveSocket("/live", Socket, { hooks: { ...colocatedHooks } });
+/// ```
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `app_name`: The OTP application name, e.g. `my_app`.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code as a `String` on success,
+/// or an error message if parsing fails or the `liveSocket` variable is not found.
+///
+/// # Behavior
+/// - Reuses an existing `import { hooks as X } from "phoenix-colocated/<app_name>"`, whatever
+///   its local name is, and adds the import only when it is missing.
+/// - Adds `...colocatedHooks` to the `hooks` object, creating the object when needed.
+/// - When `hooks` references a variable (e.g., `hooks: hooks`) whose object literal already
+///   spreads the colocated hooks, the file is left untouched.
+pub fn ensure_colocated_hooks_to_ast(file_content: &str, app_name: &str) -> Result<String, String> {
+    let source = format!("phoenix-colocated/{}", app_name);
+    let (mut module, _comments, _cm) = parse(file_content)?;
+
+    let existing_local = module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
+            if import_decl.src.value == *source.as_str() =>
+        {
+            import_decl.specifiers.iter().find_map(|spec| match spec {
+                ImportSpecifier::Named(named) => {
+                    let imported = match &named.imported {
+                        Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                        Some(ModuleExportName::Str(s)) => s.value.to_string(),
+                        None => named.local.sym.to_string(),
+                    };
+                    (imported == "hooks").then(|| named.local.sym.to_string())
+                }
+                _ => None,
+            })
+        }
+        _ => None,
+    });
+
+    let (code, local) = match existing_local {
+        Some(local) => (file_content.to_string(), local),
+        None => {
+            let import_line = format!("import {{ hooks as colocatedHooks }} from \"{}\";", source);
+            (
+                insert_import_to_ast(file_content, &import_line)?,
+                "colocatedHooks".to_string(),
+            )
+        }
+    };
+
+    let spread = format!("...{}", local);
+
+    // Case 2: hooks is an identifier reference whose object already spreads the colocated hooks
+    let referenced_hooks =
+        find_live_socket_options(&mut module, "liveSocket").and_then(|options| {
+            match hooks_value(options) {
+                Some(Expr::Ident(ident)) => Some(ident.sym.to_string()),
+                _ => None,
+            }
+        });
+    if let Some(var_name) = referenced_hooks {
+        if let Some(hooks_obj) = find_scoped_object_literal(&mut module, &var_name) {
+            if hooks_obj
+                .props
+                .iter()
+                .any(|prop| object_entry_key(prop).as_deref() == Some(spread.as_str()))
+            {
+                return Ok(code);
+            }
+        }
+    }
+
+    extend_hook_object_to_ast(&code, vec![spread.as_str()])
+}
+
+/// Lists the names of all hooks registered in the LiveSocket `hooks` object.
+///
+/// Shorthand props and key-value props are reported by their key, and spreads as
+/// `...Name`. When `hooks` references a variable (e.g., `hooks: hooks`), the entries of the
+/// referenced object literal are listed, followed by the hooks assigned to it later on
+/// (e.g., `hooks.map = mapHook`).
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+///
+/// # Returns
+/// A `Result` containing the hook names in source order, an empty list if the LiveSocket
+/// has no hooks, or an error message if parsing fails or the `liveSocket` is not found.
+pub fn list_hooks_from_ast(file_content: &str) -> Result<Vec<String>, String> {
+    let (mut module, _comments, _cm) = parse(file_content)?;
+    list_hooks_in_module(&mut module)
+}
+
+/// Same as [`list_hooks_from_ast`], for an already parsed module.
+pub fn list_hooks_in_module(module: &mut Module) -> Result<Vec<String>, String> {
+    let hooks = match find_live_socket_options(module, "liveSocket") {
+        Some(options) => hooks_value(options).cloned(),
+        None => {
+            return Err(FindCondition::NotFound("".to_string())
+                .message()
+                .to_string())
+        }
+    };
+
+    match hooks {
+        Some(Expr::Object(hooks_obj)) => Ok(hooks_obj
+            .props
+            .iter()
+            .filter_map(object_entry_key)
+            .collect()),
+        Some(Expr::Ident(ident)) => {
+            let mut names: Vec<String> = find_scoped_object_literal(module, &ident.sym)
+                .map(|hooks_obj| {
+                    hooks_obj
+                        .props
+                        .iter()
+                        .filter_map(object_entry_key)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            for item in &module.body {
+                if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = item {
+                    if let Expr::Assign(AssignExpr {
+                        op: AssignOp::Assign,
+                        left: AssignTarget::Simple(SimpleAssignTarget::Member(member)),
+                        ..
+                    }) = &**expr
+                    {
+                        if let (Expr::Ident(obj), MemberProp::Ident(prop)) =
+                            (&*member.obj, &member.prop)
+                        {
+                            let name = prop.sym.to_string();
+                            if obj.sym == ident.sym && !names.contains(&name) {
+                                names.push(name);
+                            }
+                        }
+                    }
+                }
+            }
+
+            Ok(names)
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// Returns the object literal an options value resolves to: an inline object, or the object
+/// returned by an arrow function (`() => ({...})` or `() => { return {...}; }`).
+fn object_of_value(value: &mut Expr) -> Option<&mut ObjectLit> {
+    match value {
+        Expr::Object(obj_expr) => Some(obj_expr),
+        Expr::Paren(paren) => object_of_value(&mut paren.expr),
+        Expr::Arrow(arrow) => match &mut *arrow.body {
+            BlockStmtOrExpr::Expr(expr) => object_of_value(expr),
+            BlockStmtOrExpr::BlockStmt(block) => {
+                block.stmts.iter_mut().rev().find_map(|stmt| match stmt {
+                    Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => object_of_value(arg),
+                    _ => None,
+                })
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Adds the given key-value pairs to an object literal, skipping keys that already exist.
+fn merge_key_values(obj_expr: &mut ObjectLit, entries: &[(&str, Box<Expr>)]) {
+    for (key, value) in entries {
+        if !obj_expr
+            .props
+            .iter()
+            .any(|prop| prop_key_name(prop).as_deref() == Some(*key))
+        {
+            obj_expr.props.push(new_key_value_prop(key, value.clone()));
+        }
+    }
+}
+
+/// Merges keys into the `params` option of the LiveSocket.
+///
+/// Each entry is a key and the JavaScript expression of its value, e.g.
+/// `("locale", "document.documentElement.lang")`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `params`: The key and value expression pairs to add.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code as a `String` on success,
+/// or an error message if parsing fails, a value is not a valid expression, the `liveSocket`
+/// variable is not found or `params` has a shape that can not be patched.
+///
+/// # Behavior
+/// - `params: {...}` is extended in place.
+/// - `params: csrfParams` is followed to the object literal `csrfParams` is declared with.
+/// - `params: () => ({...})` and `params: () => { return {...}; }` extend the returned object.
+/// - A missing `params` option is created.
+/// - Keys that already exist are left untouched, so running it twice is a no-op.
+pub fn extend_live_socket_params_to_ast(
+    file_content: &str,
+    params: Vec<(&str, &str)>,
+) -> Result<String, String> {
+    let entries = params
+        .into_iter()
+        .map(|(key, value)| parse_expr(value).map(|expr| (key, expr)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+
+    let Some(options) = find_live_socket_options(&mut module, "liveSocket") else {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    };
+
+    let params_value = options.props.iter_mut().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &mut **prop {
+            Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(ident),
+                value,
+            }) if ident.sym == *"params" => Some(value),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let referenced_params = match params_value {
+        None => {
+            let mut params_obj = ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            };
+            merge_key_values(&mut params_obj, &entries);
+            options.props.push(new_key_value_prop(
+                "params",
+                Box::new(Expr::Object(params_obj)),
+            ));
+            None
+        }
+        Some(value) => match &mut **value {
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            value => match object_of_value(value) {
+                Some(params_obj) => {
+                    merge_key_values(params_obj, &entries);
+                    None
+                }
+                None => {
+                    return Err(FindCondition::FoundError("".to_string())
+                        .message()
+                        .to_string())
+                }
+            },
+        },
+    };
+
+    if let Some(var_name) = referenced_params {
+        match find_scoped_object_literal(&mut module, &var_name) {
+            Some(params_obj) => merge_key_values(params_obj, &entries),
+            None => {
+                return Err(format!(
+                    "The params variable `{}` is not declared as an object literal.",
+                    var_name
+                ))
+            }
+        }
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// Whether `value` is a call to `callee`, or wraps one in its first argument, e.g.
+/// `withLiveReact(composeHooks(Hooks))` for `composeHooks`.
+fn is_wrapped_in(value: &Expr, callee: &str) -> bool {
+    match value {
+        Expr::Paren(paren) => is_wrapped_in(&paren.expr, callee),
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(call_callee),
+            args,
+            ..
+        }) => {
+            callee_path(call_callee).as_deref() == Some(callee)
+                || args
+                    .first()
+                    .is_some_and(|arg| arg.spread.is_none() && is_wrapped_in(&arg.expr, callee))
+        }
+        _ => false,
+    }
+}
+
+/// Wraps the value of the `hooks` option of the LiveSocket in a call, as some libraries
+/// require, e.g. `hooks: withLiveReact(Hooks)` or `hooks: composeHooks(Hooks, Colocated)`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `callee`: The function called, e.g. `"withLiveReact"` or `"LiveReact.wrap"`.
+/// - `args`: The JavaScript expressions passed after the hooks, e.g. `["Colocated"]`.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code as a `String` on success,
+/// or an error message if parsing fails, the callee or an argument is not a valid
+/// expression, or the `liveSocket` variable or its `hooks` option is not found.
+///
+/// # Behavior
+/// - `hooks: Hooks` and the shorthand `hooks` become `hooks: callee(Hooks, ...args)`.
+/// - Hooks already passed to `callee`, even inside another wrapping call such as
+///   `hooks: other(callee(Hooks))`, are left untouched, so running it twice is a no-op.
+pub fn wrap_live_socket_hooks_to_ast(
+    file_content: &str,
+    callee: &str,
+    args: Vec<&str>,
+) -> Result<String, String> {
+    let callee_expr = parse_expr(callee)?;
+    let Some(callee) = callee_path(&callee_expr) else {
+        return Err(format!(
+            "The callee `{}` is not a function name, e.g. `withLiveReact`",
+            callee
+        ));
+    };
+    let args = args
+        .into_iter()
+        .map(parse_expr)
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+
+    let Some(options) = find_live_socket_options(&mut module, "liveSocket") else {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    };
+
+    let hooks_prop = options.props.iter_mut().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(ident),
+                ..
+            }) if ident.sym == *"hooks" => Some(prop),
+            Prop::Shorthand(ident) if ident.sym == *"hooks" => Some(prop),
+            _ => None,
+        },
+        _ => None,
+    });
+    let Some(hooks_prop) = hooks_prop else {
+        return Err("The LiveSocket has no `hooks` option to wrap".to_string());
+    };
+
+    let value = match &mut **hooks_prop {
+        Prop::KeyValue(KeyValueProp { value, .. }) => value,
+        Prop::Shorthand(ident) => {
+            let value = Box::new(Expr::Ident(ident.clone()));
+            **hooks_prop = Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(IdentName::new("hooks".into(), DUMMY_SP)),
+                value,
+            });
+            let Prop::KeyValue(KeyValueProp { value, .. }) = &mut **hooks_prop else {
+                unreachable!()
+            };
+            value
+        }
+        _ => unreachable!(),
+    };
+
+    if !is_wrapped_in(value, &callee) {
+        let hooks = std::mem::replace(&mut **value, Expr::Invalid(Invalid { span: DUMMY_SP }));
+        **value = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            ctxt: SyntaxContext::empty(),
+            callee: Callee::Expr(callee_expr),
+            args: std::iter::once(Box::new(hooks))
+                .chain(args)
+                .map(|expr| ExprOrSpread { spread: None, expr })
+                .collect(),
+            type_args: None,
+        });
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// The parameters the LiveSocket passes to `dom.onBeforeElUpdated`.
+const ON_BEFORE_EL_UPDATED_PARAMS: &str = "from, to";
+
+/// Parses the statements of a `dom.onBeforeElUpdated` snippet, without spans.
+fn parse_callback_body(snippet: &str) -> Result<Vec<Stmt>, String> {
+    let error = || format!("Failed to parse statements: {}", snippet);
+    let code = format!(
+        "function onBeforeElUpdated({}) {{\n{}\n}}",
+        ON_BEFORE_EL_UPDATED_PARAMS, snippet
+    );
+    let (mut module, _comments, _cm) = parse(&code).map_err(|_| error())?;
+
+    match module.body.pop() {
+        Some(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(mut fn_decl)))) if module.body.is_empty() => {
+            fn_decl.visit_mut_with(&mut DropSpan);
+            Ok(fn_decl
+                .function
+                .body
+                .map(|body| body.stmts)
+                .unwrap_or_default())
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Builds `onBeforeElUpdated(from, to) { ... }` running `stmts`, then `existing` if any.
+fn new_on_before_el_updated(stmts: Vec<Stmt>, existing: Option<Box<Expr>>) -> PropOrSpread {
+    let mut code = format!(
+        "{{ onBeforeElUpdated({}) {{}} }}",
+        ON_BEFORE_EL_UPDATED_PARAMS
+    );
+    if existing.is_some() {
+        code = format!(
+            "{{ onBeforeElUpdated({0}) {{ return existing({0}); }} }}",
+            ON_BEFORE_EL_UPDATED_PARAMS
+        );
+    }
+
+    let Ok(expr) = parse_expr(&code) else {
+        unreachable!("the onBeforeElUpdated template is valid JavaScript")
+    };
+    let Expr::Object(ObjectLit { mut props, .. }) = *expr else {
+        unreachable!("the onBeforeElUpdated template is an object")
+    };
+    let mut prop = props.remove(0);
+
+    if let PropOrSpread::Prop(prop) = &mut prop {
+        if let Prop::Method(MethodProp { function, .. }) = &mut **prop {
+            if let Some(body) = &mut function.body {
+                if let (Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })), Some(existing)) =
+                    (body.stmts.first_mut(), existing)
+                {
+                    if let Expr::Call(CallExpr {
+                        callee: Callee::Expr(callee),
+                        ..
+                    }) = &mut **arg
+                    {
+                        *callee = match *existing {
+                            Expr::Ident(_) | Expr::Member(_) => existing,
+                            _ => Box::new(Expr::Paren(ParenExpr {
+                                span: DUMMY_SP,
+                                expr: existing,
+                            })),
+                        };
+                    }
+                }
+                body.stmts.splice(0..0, stmts);
+            }
+        }
+    }
+    prop
+}
+
+/// Returns the parameters and body of a function valued prop, e.g.
+/// `onBeforeElUpdated(from, to) {...}` or `onBeforeElUpdated: (from, to) => {...}`.
+fn callback_params_and_body(prop: &mut Prop) -> Option<(Vec<&Pat>, &mut BlockStmt)> {
+    match prop {
+        Prop::Method(MethodProp { function, .. }) => Some((
+            function.params.iter().map(|param| &param.pat).collect(),
+            function.body.as_mut()?,
+        )),
+        Prop::KeyValue(KeyValueProp { value, .. }) => match &mut **value {
+            Expr::Fn(FnExpr { function, .. }) => Some((
+                function.params.iter().map(|param| &param.pat).collect(),
+                function.body.as_mut()?,
+            )),
+            Expr::Arrow(ArrowExpr { params, body, .. }) => match &mut **body {
+                BlockStmtOrExpr::BlockStmt(block) => Some((params.iter().collect(), block)),
+                BlockStmtOrExpr::Expr(_) => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds `stmts` to the `onBeforeElUpdated` callback of a `dom` object, keeping what the
+/// existing callback does.
+fn compose_on_before_el_updated(dom: &mut ObjectLit, stmts: Vec<Stmt>) {
+    let index = dom
+        .props
+        .iter()
+        .position(|prop| prop_key_name(prop).as_deref() == Some("onBeforeElUpdated"));
+
+    let Some(index) = index else {
+        dom.props.push(new_on_before_el_updated(stmts, None));
+        return;
+    };
+    let PropOrSpread::Prop(prop) = &mut dom.props[index] else {
+        return;
+    };
+
+    if let Some((params, body)) = callback_params_and_body(prop) {
+        if stmts.iter().all(|stmt| {
+            body.stmts
+                .iter()
+                .any(|existing| existing.eq_ignore_span(stmt))
+        }) {
+            return;
+        }
+
+        // A callback with the same parameter names runs the snippet first, so an early
+        // `return` in it does not skip the snippet
+        let names: Vec<_> = params
+            .iter()
+            .map(|param| param.as_ident().map(|ident| ident.sym.to_string()))
+            .collect();
+        let expected: Vec<_> = ON_BEFORE_EL_UPDATED_PARAMS
+            .split(", ")
+            .map(|name| Some(name.to_string()))
+            .collect();
+        if names.len() <= expected.len() && names == expected[..names.len()] {
+            body.stmts.splice(0..0, stmts);
+            return;
+        }
+    }
+
+    // Any other callback is called from a new one
+    let existing = match &**prop {
+        Prop::Method(MethodProp { function, .. }) => Box::new(Expr::Fn(FnExpr {
+            ident: None,
+            function: function.clone(),
+        })),
+        Prop::KeyValue(KeyValueProp { value, .. }) => value.clone(),
+        Prop::Shorthand(ident) => Box::new(Expr::Ident(ident.clone())),
+        _ => return,
+    };
+    dom.props[index] = new_on_before_el_updated(stmts, Some(existing));
+}
+
+/// Composes a snippet into the `dom.onBeforeElUpdated` callback of the LiveSocket, as
+/// libraries like Alpine.js and Shoelace require.
+///
+/// The snippet is the body of the callback and can use its `from` and `to` parameters, e.g.
+/// `if (from._x_dataStack) { window.Alpine.clone(from, to); }`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `snippet`: The statements to run in the callback.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code as a `String` on success,
+/// or an error message if parsing fails, the snippet is not a list of statements, the
+/// `liveSocket` variable is not found or `dom` has a shape that can not be patched.
+///
+/// # Behavior
+/// - A missing `dom` option or `onBeforeElUpdated` callback is created.
+/// - `dom: domOptions` is followed to the object literal `domOptions` is declared with.
+/// - The snippet is prepended to an existing callback taking `(from, to)`.
+/// - Any other existing callback, like a function reference or one naming its parameters
+///   differently, is kept and called from a new callback running the snippet first.
+/// - A callback already running every statement of the snippet is left untouched, so
+///   running it twice is a no-op.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+///   dom: { onBeforeElUpdated(from, to) { morph(from, to); } },
+/// });"#;
+/// let snippet = "if (from._x_dataStack) { window.Alpine.clone(from, to); }";
+/// let result = compose_on_before_el_updated_to_ast(code, snippet).unwrap();
+/// assert!(result.contains("window.Alpine.clone(from, to);"));
+/// assert!(result.contains("morph(from, to);"));
+/// ```
+pub fn compose_on_before_el_updated_to_ast(
+    file_content: &str,
+    snippet: &str,
+) -> Result<String, String> {
+    let stmts = parse_callback_body(snippet)?;
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+
+    let Some(options) = find_live_socket_options(&mut module, "liveSocket") else {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    };
+
+    let dom_value = options.props.iter_mut().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &mut **prop {
+            Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(ident),
+                value,
+            }) if ident.sym == *"dom" => Some(value),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let referenced_dom = match dom_value {
+        None => {
+            let mut dom = ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            };
+            compose_on_before_el_updated(&mut dom, stmts.clone());
+            options
+                .props
+                .push(new_key_value_prop("dom", Box::new(Expr::Object(dom))));
+            None
+        }
+        Some(value) => match &mut **value {
+            Expr::Object(dom) => {
+                compose_on_before_el_updated(dom, stmts.clone());
+                None
+            }
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => {
+                return Err(FindCondition::FoundError("".to_string())
+                    .message()
+                    .to_string())
+            }
+        },
+    };
+
+    if let Some(var_name) = referenced_dom {
+        match find_scoped_object_literal(&mut module, &var_name) {
+            Some(dom) => compose_on_before_el_updated(dom, stmts),
+            None => {
+                return Err(format!(
+                    "The dom variable `{}` is not declared as an object literal.",
+                    var_name
+                ))
+            }
+        }
+    }
+
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// The standard tail of a Phoenix `app.js` showing the progress bar on live navigation.
+const TOPBAR_BOILERPLATE: &str = r##"// Show progress bar on live navigation and form submits
+topbar.config({barColors: {0: "#29d"}, shadowColor: "rgba(0, 0, 0, .3)"})
+window.addEventListener("phx:page-loading-start", _info => topbar.show(300))
+window.addEventListener("phx:page-loading-stop", _info => topbar.hide())
+"##;
+
+/// The standard tail of a Phoenix `app.js` connecting the LiveSocket.
+const CONNECT_BOILERPLATE: &str = r#"// connect if there are any LiveViews on the page
+liveSocket.connect()
+"#;
+
+/// The standard tail of a Phoenix `app.js` exposing the LiveSocket for debugging.
+const WINDOW_BOILERPLATE: &str = r#"// expose liveSocket on window for web console debug logs and latency simulation:
+// >> liveSocket.enableDebug()
+// >> liveSocket.enableLatencySim(1000)  // enabled for duration of browser session
+// >> liveSocket.disableLatencySim()
+window.liveSocket = liveSocket
+"#;
+
+const TOPBAR_IMPORT: &str = "import topbar from \"../vendor/topbar\";";
+
+/// Finds the pieces of the Phoenix `app.js` boilerplate, wherever they are in the module.
+#[derive(Debug, Default)]
+struct BoilerplateFinder {
+    live_socket_connect: bool,
+    topbar_config: bool,
+    window_live_socket: bool,
+    topbar_import: bool,
+}
+
+impl Visit for BoilerplateFinder {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(method),
+                ..
+            }) = &**callee
+            {
+                if let Expr::Ident(obj) = &**obj {
+                    match (&*obj.sym, &*method.sym) {
+                        ("liveSocket", "connect") => self.live_socket_connect = true,
+                        ("topbar", "config") => self.topbar_config = true,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if is_live_socket_exposure(assign) {
+            self.window_live_socket = true;
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        if import
+            .specifiers
+            .iter()
+            .any(|spec| matches!(spec, ImportSpecifier::Default(default) if default.local.sym == "topbar"))
+        {
+            self.topbar_import = true;
+        }
+    }
+}
+
+/// Checks whether an assignment is the debugging exposure `window.liveSocket = ...`, or
+/// `window["liveSocket"] = ...`, whatever is assigned.
+fn is_live_socket_exposure(assign: &AssignExpr) -> bool {
+    let AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr { obj, prop, .. })) =
+        &assign.left
+    else {
+        return false;
+    };
+    let is_live_socket = match prop {
+        MemberProp::Ident(prop) => prop.sym == "liveSocket",
+        MemberProp::Computed(ComputedPropName { expr, .. }) => {
+            matches!(&**expr, Expr::Lit(Lit::Str(prop)) if prop.value == "liveSocket")
+        }
+        _ => false,
+    };
+    is_live_socket && matches!(&**obj, Expr::Ident(obj) if obj.sym == "window")
+}
+
+fn find_boilerplate(file_content: &str) -> Result<BoilerplateFinder, String> {
+    let (module, _comments, _cm) = parse(file_content)?;
+    let mut finder = BoilerplateFinder::default();
+    module.visit_with(&mut finder);
+    Ok(finder)
+}
+
+/// Checks whether the LiveSocket is connected with `liveSocket.connect()`.
+pub fn has_live_socket_connect_from_ast(file_content: &str) -> Result<bool, String> {
+    Ok(find_boilerplate(file_content)?.live_socket_connect)
+}
+
+/// Checks whether the progress bar is configured with `topbar.config(...)`.
+pub fn has_topbar_config_from_ast(file_content: &str) -> Result<bool, String> {
+    Ok(find_boilerplate(file_content)?.topbar_config)
+}
+
+/// Restores the standard tail of a Phoenix `app.js` when parts of it are missing.
+///
+/// The missing blocks among the topbar configuration, `liveSocket.connect()` and
+/// `window.liveSocket = liveSocket` are appended to the end of the file, in that order, with
+/// their usual comments. The `topbar` import from `../vendor/topbar` is added along with the
+/// topbar block when there is none. The content is returned untouched when nothing is
+/// missing, so running it twice is a no-op.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails
+/// or the `liveSocket` variable is not found.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+/// let result = ensure_live_socket_boilerplate_to_ast(code).unwrap();
+/// assert!(result.contains("liveSocket.connect()"));
+/// ```
+pub fn ensure_live_socket_boilerplate_to_ast(file_content: &str) -> Result<String, String> {
+    let found = find_boilerplate(file_content)?;
+    if find_live_socket_node_from_ast(file_content).is_err() {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    }
+
+    let mut tail = vec![];
+    if !found.topbar_config {
+        tail.push(TOPBAR_BOILERPLATE);
+    }
+    if !found.live_socket_connect {
+        tail.push(CONNECT_BOILERPLATE);
+    }
+    if !found.window_live_socket {
+        tail.push(WINDOW_BOILERPLATE);
+    }
+    if tail.is_empty() {
+        return Ok(file_content.to_string());
+    }
+
+    let mut code = file_content.trim_end().to_string();
+    for block in tail {
+        code.push_str("\n\n");
+        code.push_str(block.trim_end());
+    }
+    code.push('\n');
+
+    if !found.topbar_config && !found.topbar_import {
+        code = insert_import_to_ast(&code, TOPBAR_IMPORT)?;
+    }
+    Ok(code)
+}
+
+/// Merges the keys of a JSON object into the object the first `topbar.config(...)` call
+/// takes, see [`merge_json_into_object`].
+struct TopbarConfigMerger<'a> {
+    spec: &'a serde_json::Map<String, serde_json::Value>,
+    overwrite: bool,
+    found: Option<Result<(), String>>,
+}
+
+impl VisitMut for TopbarConfigMerger<'_> {
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        if self.found.is_some() {
+            return;
+        }
+        let Callee::Expr(callee) = &call.callee else {
+            return call.visit_mut_children_with(self);
+        };
+        if callee_path(callee).as_deref() != Some("topbar.config") {
+            return call.visit_mut_children_with(self);
+        }
+
+        self.found = Some(match call.args.first_mut() {
+            None => {
+                call.args.push(ExprOrSpread {
+                    spread: None,
+                    expr: json_to_expr(&serde_json::Value::Object(self.spec.clone())),
+                });
+                Ok(())
+            }
+            Some(ExprOrSpread { spread: None, expr }) => match &mut **expr {
+                Expr::Object(obj_expr) => {
+                    merge_json_into_object(obj_expr, self.spec, self.overwrite);
+                    Ok(())
+                }
+                _ => Err("The topbar configuration is not an object literal".to_string()),
+            },
+            Some(_) => Err("The topbar configuration is not an object literal".to_string()),
+        });
+    }
+}
+
+/// Updates the progress bar configuration, the object of `topbar.config({...})`.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `json_spec`: A JSON object of the keys to set, e.g.
+///   `{"barColors": {"0": "#fd4f00"}, "shadowColor": "rgba(0, 0, 0, .3)"}`.
+/// - `overwrite`: Replace the values already set instead of keeping them.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if the spec is
+/// not a JSON object, the configuration is not an object literal, or there is no
+/// configuration call and no `liveSocket` to add it after.
+///
+/// # Behavior
+/// - The spec is deep-merged into the object of the first `topbar.config(...)` call, so
+///   `barColors` entries that are not in the spec are kept.
+/// - Without a `topbar.config(...)` call, one is added after the statement creating the
+///   LiveSocket, along with the `topbar` import from `../vendor/topbar` when there is none.
+///
+/// # Example
+/// ```rust
+/// let code = r##"topbar.config({barColors: {0: "#29d"}, shadowColor: "rgba(0, 0, 0, .3)"})"##;
+/// let result = merge_topbar_config_to_ast(code, r##"{"barColors": {"0": "#fd4f00"}}"##, true);
+/// assert!(result.unwrap().contains("0: \"#fd4f00\""));
+/// ```
+pub fn merge_topbar_config_to_ast(
+    file_content: &str,
+    json_spec: &str,
+    overwrite: bool,
+) -> Result<String, String> {
+    let spec: serde_json::Value =
+        serde_json::from_str(json_spec).map_err(|e| format!("Invalid JSON spec: {}", e))?;
+    let serde_json::Value::Object(spec) = spec else {
+        return Err("The JSON spec must be an object".to_string());
+    };
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut merger = TopbarConfigMerger {
+        spec: &spec,
+        overwrite,
+        found: None,
+    };
+    module.visit_mut_with(&mut merger);
+    if let Some(found) = merger.found {
+        found?;
+        return Ok(code_gen_edit(
+            file_content,
+            &original,
+            &mut module,
+            comments,
+            cm,
+        ));
+    }
+
+    let live_socket_index = module
+        .body
+        .iter()
+        .position(|item| {
+            let mut finder = LiveSocketFinder::default();
+            item.visit_with(&mut finder);
+            !finder.names.is_empty()
+        })
+        .ok_or_else(|| {
+            FindCondition::NotFound("".to_string())
+                .message()
+                .to_string()
+        })?;
+
+    let mut config = parse_expr("topbar.config({})")?;
+    if let Expr::Call(call) = &mut *config {
+        call.args[0].expr = json_to_expr(&serde_json::Value::Object(spec));
+    }
+    module.body.insert(
+        live_socket_index + 1,
+        ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: config,
+        })),
+    );
+
+    let code = code_gen_edit(file_content, &original, &mut module, comments, cm);
+    if find_boilerplate(&code)?.topbar_import {
+        Ok(code)
+    } else {
+        insert_import_to_ast(&code, TOPBAR_IMPORT)
+    }
+}
+
+/// Checks whether the LiveSocket is exposed for debugging with `window.liveSocket = ...`.
+pub fn has_live_socket_exposure_from_ast(file_content: &str) -> Result<bool, String> {
+    Ok(find_boilerplate(file_content)?.window_live_socket)
+}
+
+/// Exposes the LiveSocket on `window` for web console debugging.
+///
+/// The `window.liveSocket = <name>` block of the Phoenix boilerplate is appended to the end
+/// of the file, `<name>` being the first variable a `new LiveSocket(...)` is bound to. The
+/// content is returned untouched when `window.liveSocket` is already assigned, whatever the
+/// assigned variable is.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails
+/// or no LiveSocket is found.
+///
+/// # Example
+/// ```rust
+/// let code = r#"let socket = new LiveSocket("/live", Socket, {});"#;
+/// let result = ensure_live_socket_exposed_to_ast(code).unwrap();
+/// assert!(result.ends_with("window.liveSocket = socket\n"));
+/// ```
+pub fn ensure_live_socket_exposed_to_ast(file_content: &str) -> Result<String, String> {
+    if find_boilerplate(file_content)?.window_live_socket {
+        return Ok(file_content.to_string());
+    }
+    let Some(name) = list_live_sockets_from_ast(file_content)?.into_iter().next() else {
+        return Err(FindCondition::NotFound("".to_string())
+            .message()
+            .to_string());
+    };
+
+    let block = WINDOW_BOILERPLATE.replace("= liveSocket", &format!("= {}", name));
+    Ok(format!(
+        "{}\n\n{}\n",
+        file_content.trim_end(),
+        block.trim_end()
+    ))
+}
+
+/// Removes the statements assigning `window.liveSocket`, along with their comments.
+#[derive(Default)]
+struct LiveSocketExposureRemover {
+    /// The spans of the removed statements.
+    removed: Vec<Span>,
+}
+
+impl LiveSocketExposureRemover {
+    fn keep(&mut self, stmt: &Stmt) -> bool {
+        let exposure = matches!(stmt, Stmt::Expr(ExprStmt { expr, .. })
+            if matches!(&**expr, Expr::Assign(assign) if is_live_socket_exposure(assign)));
+        if exposure {
+            self.removed.push(stmt.span());
+        }
+        !exposure
+    }
+}
+
+impl VisitMut for LiveSocketExposureRemover {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        items.retain(|item| match item {
+            ModuleItem::Stmt(stmt) => self.keep(stmt),
+            _ => true,
+        });
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        stmts.retain(|stmt| self.keep(stmt));
+    }
+}
+
+/// Removes the debugging exposure of the LiveSocket, i.e. every `window.liveSocket = ...`
+/// statement whatever the assigned variable is, so it can be toggled per environment. With
+/// [`RemovalMode::CommentOut`], the statements are commented out instead.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if parsing fails.
+///
+/// # Example
+/// ```rust
+/// let code = "liveSocket.connect();\nwindow.liveSocket = liveSocket;";
+/// let result = remove_live_socket_exposure_from_ast(code, RemovalMode::Delete).unwrap();
+/// assert!(!result.contains("window.liveSocket"));
+/// ```
+pub fn remove_live_socket_exposure_from_ast(
+    file_content: &str,
+    mode: RemovalMode,
+) -> Result<String, String> {
+    if !find_boilerplate(file_content)?.window_live_socket {
+        return Ok(file_content.to_string());
+    }
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut remover = LiveSocketExposureRemover::default();
+    module.visit_mut_with(&mut remover);
+    Ok(emit_removal(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+        &remover.removed,
+        mode,
+    ))
 }
 
 #[cfg(test)]
@@ -505,9 +2347,18 @@ mod tests {
     fn test_extend_hooks_with_const_let_var_declarations() {
         // Test with different variable declaration types
         let test_cases = vec![
-            ("const", r#"const liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#),
-            ("let", r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#),
-            ("var", r#"var liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#),
+            (
+                "const",
+                r#"const liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#,
+            ),
+            (
+                "let",
+                r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#,
+            ),
+            (
+                "var",
+                r#"var liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#,
+            ),
         ];
 
         for (decl_type, code) in test_cases {
@@ -616,4 +2467,843 @@ mod tests {
 
         assert!(result.is_err())
     }
+
+    #[test]
+    fn test_remove_objects_of_hooks_with_identifier_reference() {
+        let code = r#"
+        let hooks = { ...colocatedHooks, KeepScrollPosition, CopyMixInstallationHook };
+        hooks.map = mapHook;
+
+        const liveSocket = new LiveSocket("/live", Socket, {
+          params: { _csrf_token: csrfToken },
+          hooks: hooks,
+        });
+        "#;
+
+        let result =
+            remove_objects_of_hooks_from_ast(code, vec!["KeepScrollPosition", "...colocatedHooks"])
+                .unwrap();
+
+        assert!(result.contains("CopyMixInstallationHook"));
+        assert!(!result.contains("KeepScrollPosition"));
+        assert!(!result.contains("...colocatedHooks"));
+        assert!(result.contains("hooks: hooks"));
+        assert!(result.contains("hooks.map = mapHook"));
+    }
+
+    #[test]
+    fn test_remove_objects_of_hooks_by_key() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: {
+            ...Hooks,
+            Copy,
+            Drag: DragHook,
+            "phx-drop": DropHook,
+            ["phx-sort"]: SortHook,
+            [dynamicName]: DynamicHook,
+            42: AnswerHook,
+          },
+        });
+        "#;
+
+        let log = remove_live_socket_option_entries_with_log_from_ast(
+            code,
+            LiveSocketTarget::Named("liveSocket"),
+            "hooks",
+            vec!["Drag", "phx-drop", "phx-sort", "42", "dynamicName"],
+            RemovalMode::Delete,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            log.actions,
+            ["Drag", "phx-drop", "phx-sort", "42"].map(|key| EditAction::RemovedEntry(key.into()))
+        );
+        for removed in ["DragHook", "DropHook", "SortHook", "AnswerHook"] {
+            assert!(!log.code.contains(removed));
+        }
+        assert!(log
+            .code
+            .contains("...Hooks,\n        Copy,\n        [dynamicName]: DynamicHook\n"));
+    }
+
+    #[test]
+    fn test_remove_objects_of_hooks_with_unparsable_content() {
+        let result = remove_objects_of_hooks_from_ast("let = ;", vec!["Hook"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_extend_hook_object_deduplicates_spreads() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { ...Hooks, MyHook },
+        });
+        "#;
+
+        let names = vec!["...Hooks", "...Hooks", "...Colocated", "MyHook"];
+        let once = extend_hook_object_to_ast(code, names.clone()).unwrap();
+        assert_eq!(once.matches("...Hooks").count(), 1);
+        assert_eq!(once.matches("...Colocated").count(), 1);
+        assert_eq!(once.matches("MyHook").count(), 1);
+
+        let twice = extend_hook_object_to_ast(&once, names.clone()).unwrap();
+        assert_eq!(once, twice);
+
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: hooks,
+        });
+        "#;
+
+        let once = extend_hook_object_to_ast(code, vec!["...hooks", "...Colocated"]).unwrap();
+        assert_eq!(once.matches("...hooks").count(), 1);
+        let twice = extend_hook_object_to_ast(&once, vec!["...hooks", "...Colocated"]).unwrap();
+        assert_eq!(once, twice);
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+        let once = extend_hook_object_to_ast(code, vec!["...Hooks", "MyHook"]).unwrap();
+        let twice = extend_hook_object_to_ast(&once, vec!["...Hooks", "MyHook"]).unwrap();
+        assert_eq!(once, twice);
+        assert_eq!(twice.matches("hooks:").count(), 1);
+    }
+
+    #[test]
+    fn test_merge_topbar_config_to_ast() {
+        let code = r##"import topbar from "../vendor/topbar";
+let liveSocket = new LiveSocket("/live", Socket, {});
+topbar.config({barColors: {0: "#29d"}, shadowColor: "rgba(0, 0, 0, .3)"});
+"##;
+        let spec = r##"{"barColors": {"0": "#fd4f00", "1": "#fff"}, "barThickness": 4}"##;
+
+        let result = merge_topbar_config_to_ast(code, spec, true).unwrap();
+        assert!(result.contains("0: \"#fd4f00\",\n        \"1\": \"#fff\""));
+        assert!(result.contains("shadowColor: \"rgba(0, 0, 0, .3)\""));
+        assert!(result.contains("barThickness: 4"));
+        let again = merge_topbar_config_to_ast(&result, spec, true).unwrap();
+        assert_eq!(again, result);
+
+        let result = merge_topbar_config_to_ast(code, spec, false).unwrap();
+        assert!(result.contains("0: \"#29d\""));
+
+        // The call is created after the LiveSocket, with its import
+        let code = r#"import { Socket } from "phoenix";
+let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+"#;
+        let result = merge_topbar_config_to_ast(code, spec, true).unwrap();
+        assert!(result.contains("import topbar from \"../vendor/topbar\";"));
+        let config = result.find("topbar.config({").unwrap();
+        assert!(result.find("new LiveSocket").unwrap() < config);
+        assert!(config < result.find("liveSocket.connect()").unwrap());
+
+        assert!(merge_topbar_config_to_ast("let a = 1;", spec, true).is_err());
+        assert!(merge_topbar_config_to_ast("topbar.config(options);", spec, true).is_err());
+        assert!(merge_topbar_config_to_ast(code, "[]", true).is_err());
+    }
+
+    #[test]
+    fn test_ensure_live_socket_boilerplate_to_ast() {
+        let code = r#"import topbar from "../vendor/topbar";
+let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+"#;
+        assert_eq!(has_live_socket_connect_from_ast(code), Ok(true));
+        assert_eq!(has_topbar_config_from_ast(code), Ok(false));
+
+        let once = ensure_live_socket_boilerplate_to_ast(code).unwrap();
+        assert!(once.starts_with(code));
+        assert!(once.contains("topbar.config({barColors: {0: \"#29d\"}"));
+        assert!(once.ends_with("window.liveSocket = liveSocket\n"));
+        assert_eq!(once.matches("liveSocket.connect()").count(), 1);
+        assert_eq!(has_topbar_config_from_ast(&once), Ok(true));
+        assert_eq!(ensure_live_socket_boilerplate_to_ast(&once).unwrap(), once);
+
+        // The topbar import is added along with its configuration
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+        let result = ensure_live_socket_boilerplate_to_ast(code).unwrap();
+        assert!(result.starts_with("import topbar from \"../vendor/topbar\";"));
+        assert!(result.contains("// connect if there are any LiveViews on the page"));
+        assert_eq!(has_live_socket_connect_from_ast(&result), Ok(true));
+
+        assert!(ensure_live_socket_boilerplate_to_ast("let socket = 1;").is_err());
+        assert!(has_topbar_config_from_ast("topbar.config(").is_err());
+    }
+
+    #[test]
+    fn test_ensure_and_remove_live_socket_exposure() {
+        let code = r#"let adminSocket = new LiveSocket("/live", Socket, {});
+adminSocket.connect();
+"#;
+        assert_eq!(has_live_socket_exposure_from_ast(code), Ok(false));
+
+        let exposed = ensure_live_socket_exposed_to_ast(code).unwrap();
+        assert!(exposed.starts_with(code));
+        assert!(exposed.ends_with("window.liveSocket = adminSocket\n"));
+        assert_eq!(has_live_socket_exposure_from_ast(&exposed), Ok(true));
+        assert_eq!(
+            ensure_live_socket_exposed_to_ast(&exposed).unwrap(),
+            exposed
+        );
+
+        // Any assigned variable counts as exposed
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});
+window["liveSocket"] = socket;
+"#;
+        assert_eq!(ensure_live_socket_exposed_to_ast(code).unwrap(), code);
+
+        let removed = remove_live_socket_exposure_from_ast(&exposed, RemovalMode::Delete).unwrap();
+        assert!(!removed.contains("window.liveSocket"));
+        assert!(!removed.contains("liveSocket.enableDebug()"));
+        assert!(removed.contains("adminSocket.connect()"));
+        assert_eq!(has_live_socket_exposure_from_ast(&removed), Ok(false));
+        assert_eq!(
+            remove_live_socket_exposure_from_ast(&removed, RemovalMode::Delete).unwrap(),
+            removed
+        );
+
+        let code = "if (dev) {\n  window.liveSocket = liveSocket;\n}\nwindow.other = 1;\n";
+        let removed = remove_live_socket_exposure_from_ast(code, RemovalMode::Delete).unwrap();
+        assert!(!removed.contains("liveSocket"));
+        assert!(removed.contains("window.other = 1"));
+
+        assert!(ensure_live_socket_exposed_to_ast("let socket = 1;").is_err());
+    }
+
+    #[test]
+    fn test_extend_and_remove_live_socket_option_entries() {
+        let code = r#"
+        let Uploaders = { S3 };
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { Copy },
+          uploaders: Uploaders,
+        });
+        "#;
+
+        let once =
+            extend_live_socket_option_to_ast(code, "uploaders", vec!["S3", "UpChunk"]).unwrap();
+        assert!(once
+            .contains("uploaders: {\n        ...Uploaders,\n        S3,\n        UpChunk\n    }"));
+        let twice =
+            extend_live_socket_option_to_ast(&once, "uploaders", vec!["S3", "UpChunk"]).unwrap();
+        assert_eq!(once, twice);
+
+        let dom = vec!["onBeforeElUpdated: (from, to) => { if (from._x_dataStack) { window.Alpine.clone(from, to); } }"];
+        let result = extend_live_socket_option_to_ast(&once, "dom", dom.clone()).unwrap();
+        assert!(result.contains("dom: {\n        onBeforeElUpdated: (from, to)=>{"));
+        assert_eq!(
+            extend_live_socket_option_to_ast(&result, "dom", dom).unwrap(),
+            result
+        );
+
+        let result = extend_live_socket_option_to_ast(code, "metadata", vec![]).unwrap();
+        assert!(!result.contains("metadata"));
+
+        let result =
+            remove_live_socket_option_entries_from_ast(code, "uploaders", vec!["S3"]).unwrap();
+        assert!(result.contains("let Uploaders = {};"));
+        assert!(result.contains("hooks: {\n        Copy\n    }"));
+
+        let code = r#"let socket = new LiveSocket("/live", Socket, {});"#;
+        assert!(extend_live_socket_option_to_ast(code, "uploaders", vec!["S3"]).is_err());
+    }
+
+    #[test]
+    fn test_extend_hook_object_with_member_expression_values() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { ...Hooks, Chart: Charts.BarChart },
+        });
+        "#;
+
+        let names = vec![
+            "Sortable: window.Sortable",
+            "Chart: Charts.LineChart",
+            "'date-picker': Pickers.date",
+        ];
+        let once = extend_hook_object_to_ast(code, names.clone()).unwrap();
+        assert!(once.contains("Sortable: window.Sortable"));
+        assert!(once.contains("\"date-picker\": Pickers.date"));
+        // An existing hook is kept as is
+        assert!(once.contains("Chart: Charts.BarChart"));
+        assert!(!once.contains("Charts.LineChart"));
+
+        let twice = extend_hook_object_to_ast(&once, names).unwrap();
+        assert_eq!(once, twice);
+
+        assert!(extend_hook_object_to_ast(code, vec!["Broken: window."]).is_err());
+    }
+
+    #[test]
+    fn test_extend_hook_object_with_inline_values() {
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+    hooks: { Copy }
+});
+"#;
+
+        let names = vec![
+            "ScrollTop: { mounted() { this.el.addEventListener(\"click\", () => window.scrollTo(0, 0)); } }",
+            "\"phx:focus\": { mounted: () => {} }",
+            "Lazy: () => import(\"./lazy\")",
+        ];
+        let once = extend_hook_object_to_ast(code, names.clone()).unwrap();
+        assert!(once.contains("ScrollTop: {\n            mounted () {\n                this.el.addEventListener(\"click\", ()=>window.scrollTo(0, 0));"));
+        assert!(once.contains("\"phx:focus\": {\n            mounted: ()=>{}\n        }"));
+        assert!(once.contains("Lazy: ()=>import(\"./lazy\")"));
+
+        let twice = extend_hook_object_to_ast(&once, names).unwrap();
+        assert_eq!(once, twice);
+
+        assert!(extend_hook_object_to_ast(code, vec!["Broken: { mounted() {"]).is_err());
+    }
+
+    #[test]
+    fn test_ensure_colocated_hooks_to_ast() {
+        let code = r#"
+        import { Socket } from "phoenix";
+        import { LiveSocket } from "phoenix_live_view";
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { CopyMixInstallationHook },
+        });
+        "#;
+
+        let once = ensure_colocated_hooks_to_ast(code, "my_app").unwrap();
+        assert!(
+            once.contains("import { hooks as colocatedHooks } from \"phoenix-colocated/my_app\";")
+        );
+        assert!(once.contains("...colocatedHooks"));
+
+        let twice = ensure_colocated_hooks_to_ast(&once, "my_app").unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_ensure_colocated_hooks_with_existing_import_and_identifier_reference() {
+        let code = r#"
+        import { hooks as colocated } from "phoenix-colocated/my_app";
+        let hooks = { ...colocated, KeepScrollPosition };
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: hooks,
+        });
+        "#;
+
+        let result = ensure_colocated_hooks_to_ast(code, "my_app").unwrap();
+        assert_eq!(result, code);
+        assert!(!result.contains("colocatedHooks"));
+
+        let code = r#"
+        let hooks = { KeepScrollPosition };
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: hooks,
+        });
+        "#;
+
+        let result = ensure_colocated_hooks_to_ast(code, "my_app").unwrap();
+        assert!(result.contains("phoenix-colocated/my_app"));
+        assert!(result.contains("...hooks"));
+        assert!(result.contains("...colocatedHooks"));
+
+        let code = r#"let socket = new LiveSocket("/live", Socket, {});"#;
+        assert!(ensure_colocated_hooks_to_ast(code, "my_app").is_err());
+    }
+
+    #[test]
+    fn test_list_hooks_from_ast() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          hooks: { ...Hooks, CopyMixInstallationHook, "phx-drag": DragHook, Chart: Charts.Line },
+        });
+        "#;
+
+        let result = list_hooks_from_ast(code).unwrap();
+        assert_eq!(
+            result,
+            vec!["...Hooks", "CopyMixInstallationHook", "phx-drag", "Chart"]
+        );
+
+        let code = r#"
+        let hooks = { ...colocatedHooks, KeepScrollPosition };
+        hooks.map = mapHook;
+        hooks.KeepScrollPosition = KeepScrollPosition;
+        let liveSocket = new LiveSocket("/live", Socket, { hooks: hooks });
+        "#;
+
+        let result = list_hooks_from_ast(code).unwrap();
+        assert_eq!(
+            result,
+            vec!["...colocatedHooks", "KeepScrollPosition", "map"]
+        );
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+        assert!(list_hooks_from_ast(code).unwrap().is_empty());
+
+        let code = r#"let socket = new LiveSocket("/live", Socket, {});"#;
+        assert!(list_hooks_from_ast(code).is_err());
+    }
+
+    #[test]
+    fn test_wrap_live_socket_hooks_to_ast() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          params: { _csrf_token: csrfToken },
+          hooks: Hooks,
+        });
+        "#;
+
+        let once = wrap_live_socket_hooks_to_ast(code, "composeHooks", vec!["Colocated"]).unwrap();
+        assert!(once.contains("hooks: composeHooks(Hooks, Colocated)"));
+        assert_eq!(
+            once,
+            wrap_live_socket_hooks_to_ast(&once, "composeHooks", vec!["Colocated"]).unwrap()
+        );
+
+        let twice = wrap_live_socket_hooks_to_ast(&once, "withLiveReact", vec![]).unwrap();
+        assert!(twice.contains("hooks: withLiveReact(composeHooks(Hooks, Colocated))"));
+        assert_eq!(
+            twice,
+            wrap_live_socket_hooks_to_ast(&twice, "composeHooks", vec!["Colocated"]).unwrap()
+        );
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { hooks });"#;
+        let result = wrap_live_socket_hooks_to_ast(code, "LiveReact.wrap", vec![]).unwrap();
+        assert!(result.contains("hooks: LiveReact.wrap(hooks)"));
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { params: {} });"#;
+        assert!(wrap_live_socket_hooks_to_ast(code, "withLiveReact", vec![]).is_err());
+        assert!(wrap_live_socket_hooks_to_ast(code, "() => 1", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_extend_live_socket_params_to_ast() {
+        let params = vec![
+            ("locale", "document.documentElement.lang"),
+            ("_csrf_token", "otherToken"),
+            (
+                "time-zone",
+                "Intl.DateTimeFormat().resolvedOptions().timeZone",
+            ),
+        ];
+
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          params: { _csrf_token: csrfToken },
+        });
+        "#;
+
+        let once = extend_live_socket_params_to_ast(code, params.clone()).unwrap();
+        assert!(once.contains("_csrf_token: csrfToken"));
+        assert!(!once.contains("otherToken"));
+        assert!(once.contains("locale: document.documentElement.lang"));
+        assert!(once.contains("\"time-zone\": Intl.DateTimeFormat()"));
+        assert_eq!(
+            once,
+            extend_live_socket_params_to_ast(&once, params.clone()).unwrap()
+        );
+
+        let code = r#"
+        let csrfParams = { _csrf_token: csrfToken };
+        let liveSocket = new LiveSocket("/live", Socket, { params: csrfParams });
+        "#;
+        let result = extend_live_socket_params_to_ast(code, params.clone()).unwrap();
+        assert!(result.contains("params: csrfParams"));
+        assert!(result.contains("locale: document.documentElement.lang"));
+
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          params: (liveViewName) => ({ _csrf_token: csrfToken, view: liveViewName }),
+        });
+        "#;
+        let result = extend_live_socket_params_to_ast(code, params.clone()).unwrap();
+        assert!(result.contains("view: liveViewName"));
+        assert!(result.contains("locale: document.documentElement.lang"));
+
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, {
+          params: () => { const token = csrfToken; return { _csrf_token: token }; },
+        });
+        "#;
+        let result = extend_live_socket_params_to_ast(code, params.clone()).unwrap();
+        assert!(result.contains("locale: document.documentElement.lang"));
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#;
+        let result = extend_live_socket_params_to_ast(code, params.clone()).unwrap();
+        assert!(result.contains("params: {"));
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { params: getParams() });"#;
+        assert!(extend_live_socket_params_to_ast(code, params.clone()).is_err());
+        assert!(extend_live_socket_params_to_ast(code, vec![("a", "let")]).is_err());
+    }
+
+    #[test]
+    fn test_compose_on_before_el_updated_to_ast() {
+        let snippet = "if (from._x_dataStack) { window.Alpine.clone(from, to); }";
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { hooks: {} });"#;
+        let once = compose_on_before_el_updated_to_ast(code, snippet).unwrap();
+        assert!(once.contains(
+            "dom: {\n        onBeforeElUpdated (from, to) {\n            if (from._x_dataStack) {"
+        ));
+        assert_eq!(
+            compose_on_before_el_updated_to_ast(&once, snippet).unwrap(),
+            once
+        );
+
+        // A callback with the same parameters runs the snippet first
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+          dom: { onBeforeElUpdated: (from, to) => { if (from.id === "skip") return; morph(from, to); } },
+        });"#;
+        let result = compose_on_before_el_updated_to_ast(code, snippet).unwrap();
+        let clone = result.find("window.Alpine.clone").unwrap();
+        assert!(clone < result.find("\"skip\"").unwrap());
+        assert!(result.contains("morph(from, to);"));
+
+        // Other callbacks are called from a new one
+        let code = r#"
+        const dom = { onBeforeElUpdated(a, b) { morph(a, b); } };
+        let liveSocket = new LiveSocket("/live", Socket, { dom: dom });
+        "#;
+        let once = compose_on_before_el_updated_to_ast(code, snippet).unwrap();
+        assert!(once
+            .contains("return (function(a, b) {\n            morph(a, b);\n        })(from, to);"));
+        assert!(once.contains("dom: dom"));
+        assert_eq!(
+            compose_on_before_el_updated_to_ast(&once, snippet).unwrap(),
+            once
+        );
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { dom: { onBeforeElUpdated: Shoelace.morph } });"#;
+        let result = compose_on_before_el_updated_to_ast(code, snippet).unwrap();
+        assert!(result.contains("return Shoelace.morph(from, to);"));
+
+        assert!(compose_on_before_el_updated_to_ast(code, "if (").is_err());
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, { dom: getDom() });"#;
+        assert!(compose_on_before_el_updated_to_ast(code, snippet).is_err());
+        let code = r#"let socket = new LiveSocket("/live", Socket, {});"#;
+        assert!(compose_on_before_el_updated_to_ast(code, snippet).is_err());
+    }
+
+    #[test]
+    fn test_live_socket_targets() {
+        let code = r#"
+        let liveSocket = new LiveSocket("/live", Socket, { hooks: { Copy } });
+        function admin() {
+          return 1;
+        }
+        const adminSocket = new LiveSocket("/admin/live", Socket, { hooks: { Copy } });
+        let other = new Other("/live", Socket, { hooks: { Copy } });
+        "#;
+
+        assert_eq!(
+            list_live_sockets_from_ast(code).unwrap(),
+            vec!["liveSocket", "adminSocket"]
+        );
+        assert!(list_live_sockets_from_ast("let socket = ;").is_err());
+
+        let admin = LiveSocketTarget::Named("adminSocket");
+        let result =
+            extend_live_socket_option_for_target_to_ast(code, admin, "hooks", vec!["Admin"])
+                .unwrap();
+        assert_eq!(result.matches("Admin\n").count(), 1);
+        assert!(result.contains("hooks: {\n        Copy,\n        Admin\n    }"));
+
+        let all = LiveSocketTarget::All;
+        let result =
+            extend_live_socket_option_for_target_to_ast(code, all, "hooks", vec!["Admin"]).unwrap();
+        assert_eq!(result.matches("Admin\n").count(), 2);
+
+        let result = remove_live_socket_option_entries_for_target_from_ast(
+            code,
+            admin,
+            "hooks",
+            vec!["Copy"],
+        )
+        .unwrap();
+        assert_eq!(result.matches("Copy").count(), 2);
+
+        let result =
+            remove_live_socket_option_entries_for_target_from_ast(code, all, "hooks", vec!["Copy"])
+                .unwrap();
+        // Objects passed to other constructors are left alone
+        assert_eq!(result.matches("Copy").count(), 1);
+
+        let missing = LiveSocketTarget::Named("other");
+        assert!(
+            extend_live_socket_option_for_target_to_ast(code, missing, "hooks", vec![]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_live_socket_option_edit_log() {
+        let target = LiveSocketTarget::Named("liveSocket");
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+        let log =
+            extend_live_socket_option_with_log_to_ast(code, target, "hooks", vec!["A", "...B"])
+                .unwrap();
+        assert_eq!(
+            log.actions,
+            [
+                EditAction::CreatedKey("hooks".into()),
+                EditAction::AddedEntry("A".into()),
+                EditAction::AddedEntry("...B".into())
+            ]
+        );
+
+        let again =
+            extend_live_socket_option_with_log_to_ast(&log.code, target, "hooks", vec!["A: a"])
+                .unwrap();
+        assert_eq!(again.actions, [EditAction::SkippedEntry("A".into())]);
+
+        let code = r#"
+        let hooks = { A, ...B, C: c };
+        let liveSocket = new LiveSocket("/live", Socket, { hooks: hooks });
+        "#;
+        let log = remove_live_socket_option_entries_with_log_from_ast(
+            code,
+            target,
+            "hooks",
+            vec!["A", "...B", "C", "D"],
+            RemovalMode::Delete,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            log.actions,
+            [
+                EditAction::RemovedEntry("A".into()),
+                EditAction::RemovedEntry("...B".into()),
+                EditAction::RemovedEntry("C".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_live_socket_options_passed_as_variable_or_call() {
+        let code = r#"const socketOptions = {
+    params: { _csrf_token: csrfToken },
+    hooks: { Existing }
+};
+let liveSocket = new LiveSocket("/live", Socket, socketOptions);
+"#;
+
+        let result = extend_hook_object_to_ast(code, vec!["MyHook"]).unwrap();
+        assert!(result.contains("hooks: {\n        Existing,\n        MyHook\n    }"));
+        assert!(result.contains("new LiveSocket(\"/live\", Socket, socketOptions)"));
+        assert_eq!(
+            list_hooks_from_ast(&result).unwrap(),
+            ["Existing", "MyHook"]
+        );
+
+        let result = remove_objects_of_hooks_from_ast(&result, vec!["Existing"]).unwrap();
+        assert_eq!(list_hooks_from_ast(&result).unwrap(), ["MyHook"]);
+
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, opts.build());"#;
+        let target = LiveSocketTarget::Named("liveSocket");
+        assert_eq!(
+            unsupported_live_socket_options(code, target),
+            Some("opts.build".to_string())
+        );
+        let error = extend_hook_object_to_ast(code, vec!["MyHook"]).unwrap_err();
+        assert!(error.contains("`opts.build`"));
+        assert!(remove_objects_of_hooks_from_ast(code, vec!["MyHook"]).is_err());
+        assert_eq!(unsupported_live_socket_options(&result, target), None);
+    }
+
+    #[test]
+    fn test_extend_hook_object_in_sorted_order() {
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+    hooks: { ...Hooks, Chart, Modal: ModalHook, Tooltip }
+});"#;
+
+        let result = extend_hook_object_in_order_to_ast(
+            code,
+            vec!["...colocatedHooks", "Dropdown", "Zoom", "alert"],
+            EntryOrder::Sorted,
+            KeyPosition::End,
+        )
+        .unwrap();
+        assert_eq!(
+            list_hooks_from_ast(&result).unwrap(),
+            [
+                "...Hooks",
+                "...colocatedHooks",
+                "alert",
+                "Chart",
+                "Dropdown",
+                "Modal",
+                "Tooltip",
+                "Zoom"
+            ]
+        );
+
+        let again = extend_hook_object_in_order_to_ast(
+            &result,
+            vec!["Dropdown", "Zoom"],
+            EntryOrder::Sorted,
+            KeyPosition::End,
+        )
+        .unwrap();
+        assert_eq!(again, result);
+
+        let result = extend_hook_object_in_order_to_ast(
+            code,
+            vec!["Alpha"],
+            EntryOrder::Append,
+            KeyPosition::End,
+        );
+        assert_eq!(
+            list_hooks_from_ast(&result.unwrap()).unwrap(),
+            ["...Hooks", "Chart", "Modal", "Tooltip", "Alpha"]
+        );
+    }
+
+    #[test]
+    fn test_extend_hook_object_key_position() {
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+    longPollFallbackMs: 2500,
+    params: { _csrf_token: csrfToken },
+    dom: {}
+});"#;
+        let keys = |position| {
+            let result = extend_hook_object_in_order_to_ast(
+                code,
+                vec!["Chart"],
+                EntryOrder::Append,
+                position,
+            )
+            .unwrap();
+            let (mut module, _comments, _cm) = parse(&result).unwrap();
+            find_live_socket_options(&mut module, "liveSocket")
+                .unwrap()
+                .props
+                .iter()
+                .filter_map(prop_key_name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            keys(KeyPosition::End),
+            ["longPollFallbackMs", "params", "dom", "hooks"]
+        );
+        assert_eq!(
+            keys(KeyPosition::AfterParams),
+            ["longPollFallbackMs", "params", "hooks", "dom"]
+        );
+        assert_eq!(
+            keys(KeyPosition::First),
+            ["hooks", "longPollFallbackMs", "params", "dom"]
+        );
+    }
+
+    #[test]
+    fn test_live_socket_in_dom_content_loaded() {
+        let code = r#"document.addEventListener("DOMContentLoaded", () => {
+  const socketOptions = { hooks: { Existing } };
+  const liveSocket = new LiveSocket("/live", Socket, socketOptions);
+  liveSocket.connect();
+});"#;
+
+        let result = extend_hook_object_to_ast(code, vec!["MyHook"]).unwrap();
+        assert_eq!(
+            list_hooks_from_ast(&result).unwrap(),
+            ["Existing", "MyHook"]
+        );
+
+        let result = remove_objects_of_hooks_from_ast(&result, vec!["Existing"]).unwrap();
+        assert_eq!(list_hooks_from_ast(&result).unwrap(), ["MyHook"]);
+
+        let result = extend_live_socket_params_to_ast(&result, vec![("locale", "\"en\"")]).unwrap();
+        assert!(result.contains("params: {"));
+        assert!(result.contains("locale: \"en\""));
+    }
+
+    #[test]
+    fn test_comment_out_removed_entries() {
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {
+  hooks: {
+    ...Hooks,
+    CopyHook,
+  },
+  uploaders: { S3, Local },
+});
+window.liveSocket = liveSocket;
+"#;
+
+        let log = remove_live_socket_option_entries_with_log_from_ast(
+            code,
+            LiveSocketTarget::Named("liveSocket"),
+            "hooks",
+            vec!["CopyHook"],
+            RemovalMode::CommentOut,
+            false,
+        )
+        .unwrap();
+        assert_eq!(log.actions, [EditAction::RemovedEntry("CopyHook".into())]);
+        assert!(log
+            .code
+            .contains("    ...Hooks,\n    // removed by igniter: CopyHook,\n"));
+        assert_eq!(list_hooks_from_ast(&log.code).unwrap(), ["...Hooks"]);
+
+        let log = remove_live_socket_option_entries_with_log_from_ast(
+            code,
+            LiveSocketTarget::Named("liveSocket"),
+            "uploaders",
+            vec!["S3"],
+            RemovalMode::CommentOut,
+            false,
+        )
+        .unwrap();
+        assert!(log
+            .code
+            .contains("  // removed by igniter: S3\n  uploaders: { Local },\n"));
+
+        let result = remove_live_socket_exposure_from_ast(code, RemovalMode::CommentOut).unwrap();
+        assert!(result.ends_with("});\n// removed by igniter: window.liveSocket = liveSocket;\n"));
+        assert!(!has_live_socket_exposure_from_ast(&result).unwrap());
+    }
+
+    #[test]
+    fn test_remove_entries_pruning_imports() {
+        let code = r#"import Chart, { ChartTooltip } from "../vendor/chart";
+import CopyHook from "./copy";
+let liveSocket = new LiveSocket("/live", Socket, {
+  hooks: { Chart, ChartTooltip, CopyHook },
+});
+function copy(CopyHook) { return CopyHook; }
+"#;
+
+        let remove = |mode| {
+            remove_live_socket_option_entries_with_log_from_ast(
+                code,
+                LiveSocketTarget::Named("liveSocket"),
+                "hooks",
+                vec!["Chart", "CopyHook"],
+                mode,
+                true,
+            )
+            .unwrap()
+        };
+
+        // The parameter of `copy` is not a use of the `CopyHook` import
+        let log = remove(RemovalMode::Delete);
+        assert_eq!(
+            log.actions,
+            [
+                EditAction::RemovedEntry("Chart".into()),
+                EditAction::RemovedEntry("CopyHook".into()),
+                EditAction::PrunedImport("Chart".into()),
+                EditAction::PrunedImport("CopyHook".into()),
+            ]
+        );
+        assert!(log
+            .code
+            .starts_with("import { ChartTooltip } from \"../vendor/chart\";\nlet liveSocket"));
+
+        let log = remove(RemovalMode::CommentOut);
+        assert!(log.code.starts_with(
+            "// removed by igniter: Chart\nimport { ChartTooltip } from \"../vendor/chart\";\n\
+             // removed by igniter: import CopyHook from \"./copy\";\n"
+        ));
+    }
 }
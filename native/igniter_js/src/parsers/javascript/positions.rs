@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Conversions between offsets and line/column positions, so the spans of the ESTree output
+//! can be mapped back to file coordinates on the Elixir side, for display or splicing.
+
+use oxc_ast_visit::utf8_to_utf16::Utf8ToUtf16;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+
+/// The unit of offsets and columns.
+///
+/// On the Elixir side these are the atoms `:utf16` and `:utf8`, like the `spans` option of
+/// the ESTree output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum OffsetEncoding {
+    /// UTF-16 code units, as JavaScript tooling counts them.
+    #[default]
+    Utf16,
+    /// UTF-8 bytes, to slice the source as an Elixir binary.
+    Utf8,
+}
+
+/// A 1-based line and a 0-based column, in the unit of the offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Converts a byte offset to the given encoding.
+fn from_byte_offset(source: &str, offset: usize, encoding: OffsetEncoding) -> usize {
+    match encoding {
+        OffsetEncoding::Utf8 => offset,
+        OffsetEncoding::Utf16 => {
+            let mut offset = offset as u32;
+            if let Some(mut converter) = Utf8ToUtf16::new(source).converter() {
+                converter.convert_offset(&mut offset);
+            }
+            offset as usize
+        }
+    }
+}
+
+/// Converts an offset in the given encoding to a byte offset, if it is within the source
+/// and not in the middle of a character.
+fn to_byte_offset(source: &str, offset: usize, encoding: OffsetEncoding) -> Option<usize> {
+    let byte_offset = match encoding {
+        OffsetEncoding::Utf8 => offset,
+        OffsetEncoding::Utf16 => {
+            if offset > utf16_len(source) {
+                return None;
+            }
+            let mut byte_offset = u32::try_from(offset).ok()?;
+            Utf8ToUtf16::new(source).convert_offset_back(&mut byte_offset);
+            byte_offset as usize
+        }
+    };
+
+    let valid = byte_offset <= source.len()
+        && source.is_char_boundary(byte_offset)
+        && from_byte_offset(source, byte_offset, encoding) == offset;
+    valid.then_some(byte_offset)
+}
+
+/// The column of a byte offset in its line, in the given encoding.
+fn column(line: &str, encoding: OffsetEncoding) -> usize {
+    match encoding {
+        OffsetEncoding::Utf8 => line.len(),
+        OffsetEncoding::Utf16 => utf16_len(line),
+    }
+}
+
+/// Converts an offset, e.g. the `start` of an ESTree node, to its line and column.
+///
+/// # Returns
+/// * `Ok(Position)` - The position, with the column in the unit of the offset.
+/// * `Err(String)` - If the offset is past the end of the source or inside a character.
+///
+/// # Example
+/// ```rust
+/// let source = "let a = \"é\";\nlet b = 1;";
+/// let position = offset_to_position(source, 17, OffsetEncoding::Utf16).unwrap();
+/// assert_eq!(position, Position { line: 2, column: 4 });
+/// ```
+pub fn offset_to_position(
+    source: &str,
+    offset: usize,
+    encoding: OffsetEncoding,
+) -> Result<Position, String> {
+    let byte_offset = to_byte_offset(source, offset, encoding)
+        .ok_or_else(|| format!("The offset {} is not within the source", offset))?;
+    let line_start = source[..byte_offset]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+
+    Ok(Position {
+        line: source[..byte_offset].matches('\n').count() + 1,
+        column: column(&source[line_start..byte_offset], encoding),
+    })
+}
+
+/// Converts a line and column to an offset, the inverse of [`offset_to_position`].
+///
+/// # Returns
+/// * `Ok(usize)` - The offset, in the unit of the column.
+/// * `Err(String)` - If the line does not exist, or the column is past the end of the line
+///   or inside a character.
+///
+/// # Example
+/// ```rust
+/// let source = "let a = \"é\";\nlet b = 1;";
+/// let position = Position { line: 2, column: 4 };
+/// assert_eq!(position_to_offset(source, position, OffsetEncoding::Utf8), Ok(18));
+/// assert_eq!(position_to_offset(source, position, OffsetEncoding::Utf16), Ok(17));
+/// ```
+pub fn position_to_offset(
+    source: &str,
+    position: Position,
+    encoding: OffsetEncoding,
+) -> Result<usize, String> {
+    let invalid = || {
+        format!(
+            "The position {}:{} is not within the source",
+            position.line, position.column
+        )
+    };
+    if position.line == 0 {
+        return Err(invalid());
+    }
+
+    let line_start = if position.line == 1 {
+        0
+    } else {
+        source
+            .match_indices('\n')
+            .nth(position.line - 2)
+            .map(|(index, _)| index + 1)
+            .ok_or_else(invalid)?
+    };
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |index| line_start + index);
+    let line = &source[line_start..line_end];
+
+    let line_start_offset = from_byte_offset(source, line_start, encoding);
+    let offset = line_start_offset + position.column;
+    if position.column > column(line, encoding)
+        || to_byte_offset(source, offset, encoding).is_none()
+    {
+        return Err(invalid());
+    }
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_and_positions() {
+        let source = "// 🚀 launch\nconst name = \"José\";\r\nexport default name;\n";
+
+        for encoding in [OffsetEncoding::Utf8, OffsetEncoding::Utf16] {
+            let offsets = match encoding {
+                OffsetEncoding::Utf8 => source.char_indices().map(|(i, _)| i).collect::<Vec<_>>(),
+                OffsetEncoding::Utf16 => (0..utf16_len(source)).filter(|i| *i != 4).collect(),
+            };
+            for offset in offsets {
+                let position = offset_to_position(source, offset, encoding).unwrap();
+                assert_eq!(position_to_offset(source, position, encoding), Ok(offset));
+            }
+        }
+
+        let export = source.find("export").unwrap();
+        assert_eq!(
+            offset_to_position(source, export, OffsetEncoding::Utf8),
+            Ok(Position { line: 3, column: 0 })
+        );
+        // Before the `\r\n`, with `é` counted as 2 bytes or 1 code unit
+        assert_eq!(
+            offset_to_position(source, export - 2, OffsetEncoding::Utf8),
+            Ok(Position {
+                line: 2,
+                column: 21
+            })
+        );
+        assert_eq!(
+            offset_to_position(source, export - 5, OffsetEncoding::Utf16),
+            Ok(Position {
+                line: 2,
+                column: 20
+            })
+        );
+        assert_eq!(
+            position_to_offset(
+                source,
+                Position { line: 1, column: 5 },
+                OffsetEncoding::Utf16
+            ),
+            Ok(5)
+        );
+
+        // Inside the 🚀, which is 4 bytes and 2 UTF-16 code units
+        assert!(offset_to_position(source, 4, OffsetEncoding::Utf8).is_err());
+        assert!(offset_to_position(source, 4, OffsetEncoding::Utf16).is_err());
+        assert!(offset_to_position(source, source.len() + 1, OffsetEncoding::Utf8).is_err());
+        assert!(position_to_offset(
+            source,
+            Position {
+                line: 1,
+                column: 40
+            },
+            OffsetEncoding::Utf8
+        )
+        .is_err());
+        assert!(position_to_offset(
+            source,
+            Position { line: 9, column: 0 },
+            OffsetEncoding::Utf8
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::positions::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn offset_to_position_nif(
+    env: Env,
+    file_content: String,
+    offset: usize,
+    encoding: OffsetEncoding,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::offset_to_position_nif();
+
+        match offset_to_position(&file_content, offset, encoding) {
+            Ok(position) => encode_response(env, atoms::ok(), fn_atom, position),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn position_to_offset_nif(
+    env: Env,
+    file_content: String,
+    line: usize,
+    column: usize,
+    encoding: OffsetEncoding,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::position_to_offset_nif();
+
+        match position_to_offset(&file_content, Position { line, column }, encoding) {
+            Ok(offset) => encode_response(env, atoms::ok(), fn_atom, offset),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -0,0 +1,350 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Managed regions, i.e. the parts of a file delimited by marker comments that an installer
+//! owns and rewrites on every run:
+//!
+//! ```javascript
+//! // <igniter:hooks>
+//! import Chart from "./hooks/chart";
+//! // </igniter:hooks>
+//! ```
+//!
+//! Only the lines between the markers are rewritten, the rest of the file is left as it is
+//! written, and the new content is checked to parse before the file is touched.
+
+use crate::helpers::SourceStyle;
+use crate::parsers::javascript::ast::{position_index, ImportPosition};
+use crate::parsers::javascript::helpers::{parse, SpanOffsets};
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use swc_common::Spanned;
+
+/// A managed region of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct ManagedRegion {
+    pub name: String,
+    /// The lines between the markers.
+    pub content: String,
+    /// The byte offsets of the region, markers included.
+    pub start: usize,
+    pub end: usize,
+    /// The 1-based line of the opening marker.
+    pub line: usize,
+}
+
+fn open_marker(name: &str) -> String {
+    format!("// <igniter:{}>", name)
+}
+
+fn close_marker(name: &str) -> String {
+    format!("// </igniter:{}>", name)
+}
+
+/// The name of a marker line, and whether it closes a region.
+fn marker_name(line: &str) -> Option<(&str, bool)> {
+    let marker = line.trim().strip_prefix("//")?.trim_start();
+    let marker = marker.strip_prefix('<')?.strip_suffix('>')?;
+    let (marker, closing) = match marker.strip_prefix('/') {
+        Some(marker) => (marker, true),
+        None => (marker, false),
+    };
+    marker
+        .strip_prefix("igniter:")
+        .filter(|name| is_region_name(name))
+        .map(|name| (name, closing))
+}
+
+fn is_region_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Lists the managed regions of a file, in order.
+///
+/// # Returns
+/// * `Ok(Vec<ManagedRegion>)` - The regions.
+/// * `Err(String)` - If a marker is not closed, is closed without being opened, or regions
+///   are nested or share a name.
+///
+/// # Example
+/// ```rust
+/// let code = "// <igniter:hooks>\nimport Chart from \"./chart\";\n// </igniter:hooks>\n";
+/// let regions = list_managed_regions(code).unwrap();
+/// assert_eq!(regions[0].content, "import Chart from \"./chart\";\n");
+/// ```
+pub fn list_managed_regions(file_content: &str) -> Result<Vec<ManagedRegion>, String> {
+    let mut regions: Vec<ManagedRegion> = vec![];
+    // The name, start, content start and line of the open region
+    let mut open: Option<(&str, usize, usize, usize)> = None;
+    let mut offset = 0;
+
+    for (index, line) in file_content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        let Some((name, closing)) = marker_name(line) else {
+            continue;
+        };
+
+        match (open, closing) {
+            (None, false) => {
+                if regions.iter().any(|region| region.name == name) {
+                    return Err(format!("The managed region `{}` is defined twice", name));
+                }
+                open = Some((name, line_start, offset, index + 1));
+            }
+            (Some((open_name, start, content_start, line)), true) if open_name == name => {
+                regions.push(ManagedRegion {
+                    name: name.to_string(),
+                    content: file_content[content_start..line_start].to_string(),
+                    start,
+                    end: offset,
+                    line,
+                });
+                open = None;
+            }
+            (Some((open_name, ..)), false) => {
+                return Err(format!(
+                    "The managed region `{}` is opened inside `{}`",
+                    name, open_name
+                ))
+            }
+            (_, true) => {
+                return Err(format!(
+                    "The managed region `{}` is closed without being opened",
+                    name
+                ))
+            }
+        }
+    }
+
+    match open {
+        Some((name, ..)) => Err(format!("The managed region `{}` is not closed", name)),
+        None => Ok(regions),
+    }
+}
+
+/// Finds the managed region of the given name, see [`list_managed_regions`].
+pub fn find_managed_region(
+    file_content: &str,
+    name: &str,
+) -> Result<Option<ManagedRegion>, String> {
+    Ok(list_managed_regions(file_content)?
+        .into_iter()
+        .find(|region| region.name == name))
+}
+
+/// Indents the lines of `content` with `indent`, ending it with a line break.
+fn indent_lines(content: &str, indent: &str) -> String {
+    content
+        .trim_matches('\n')
+        .lines()
+        .map(|line| match line.trim_end() {
+            "" => "\n".to_string(),
+            line => format!("{}{}\n", indent, line),
+        })
+        .collect()
+}
+
+/// The byte offset of the line start a new region at `position` goes to.
+fn insert_offset(file_content: &str, position: &ImportPosition) -> Result<usize, String> {
+    let (module, _comments, cm) = parse(file_content)?;
+    let index = position_index(&module.body, position);
+    let offsets = SpanOffsets::new(&cm);
+    let bom = offsets.text_start();
+    let line_start = |offset: usize| file_content[..offset].rfind('\n').map_or(bom, |i| i + 1);
+
+    Ok(match (module.body.get(index), index.checked_sub(1)) {
+        (Some(item), _) => line_start(offsets.offset(item.span().lo)),
+        (None, Some(last)) => {
+            let hi = offsets.offset(module.body[last].span().hi);
+            file_content[hi..]
+                .find('\n')
+                .map_or(file_content.len(), |i| hi + i + 1)
+        }
+        (None, None) => bom,
+    })
+}
+
+/// Creates or rewrites the managed region of the given name.
+///
+/// # Arguments
+/// - `file_content`: The JavaScript source code as a string slice.
+/// - `name`: The name of the region, made of letters, digits, `_` and `-`.
+/// - `content`: The code of the region.
+/// - `position`: Where a missing region is created, like new imports.
+///
+/// # Returns
+/// A `Result` containing the updated JavaScript code, or an error message if the name is
+/// invalid, the markers are not balanced, or the content or the resulting file does not
+/// parse.
+///
+/// # Behavior
+/// - An existing region has the lines between its markers replaced, indented like its
+///   opening marker, and the rest of the file is untouched.
+/// - A missing region is created at `position`, separated by a blank line from the code
+///   before it.
+/// - Writing the content a region already has returns the source unchanged.
+pub fn upsert_managed_region(
+    file_content: &str,
+    name: &str,
+    content: &str,
+    position: &ImportPosition,
+) -> Result<String, String> {
+    if !is_region_name(name) {
+        return Err(format!("Invalid managed region name: {}", name));
+    }
+    if content.lines().any(|line| marker_name(line).is_some()) {
+        return Err(format!(
+            "The content of the managed region `{}` contains a region marker",
+            name
+        ));
+    }
+    parse(content).map_err(|error| {
+        format!(
+            "The content of the managed region `{}` is not valid JavaScript: {}",
+            name, error
+        )
+    })?;
+
+    let updated = match find_managed_region(file_content, name)? {
+        Some(region) => {
+            let marker_line = &file_content[region.start..];
+            let indent = &marker_line[..marker_line.len() - marker_line.trim_start().len()];
+            let content_start = region.start
+                + file_content[region.start..]
+                    .find('\n')
+                    .map_or(0, |index| index + 1);
+            let content_end = content_start + region.content.len();
+            format!(
+                "{}{}{}",
+                &file_content[..content_start],
+                indent_lines(content, indent),
+                &file_content[content_end..]
+            )
+        }
+        None => {
+            let offset = insert_offset(file_content, position)?;
+            let before = &file_content[..offset];
+            let after = &file_content[offset..];
+            let separator = match before.trim_end_matches('\u{feff}') {
+                "" => "",
+                before if before.ends_with("\n\n") => "",
+                before if before.ends_with('\n') => "\n",
+                _ => "\n\n",
+            };
+            let trailer = if after.is_empty() || after.starts_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            format!(
+                "{}{}{}\n{}{}\n{}{}",
+                before,
+                separator,
+                open_marker(name),
+                indent_lines(content, ""),
+                close_marker(name),
+                trailer,
+                after
+            )
+        }
+    };
+
+    let updated = SourceStyle::detect(file_content).apply(&updated);
+    parse(&updated).map_err(|error| {
+        format!(
+            "The managed region `{}` does not fit in the file: {}",
+            name, error
+        )
+    })?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_managed_region() {
+        let code = r#"import { Socket } from "phoenix";
+import topbar from "topbar";
+
+let liveSocket = new LiveSocket("/live", Socket, {});
+"#;
+
+        let result = upsert_managed_region(
+            code,
+            "hooks",
+            "import Chart from \"./hooks/chart\";",
+            &ImportPosition::AfterLastImport,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            r#"import { Socket } from "phoenix";
+import topbar from "topbar";
+
+// <igniter:hooks>
+import Chart from "./hooks/chart";
+// </igniter:hooks>
+
+let liveSocket = new LiveSocket("/live", Socket, {});
+"#
+        );
+
+        let region = find_managed_region(&result, "hooks").unwrap().unwrap();
+        assert_eq!(region.content, "import Chart from \"./hooks/chart\";\n");
+        assert_eq!(region.line, 4);
+        assert_eq!(
+            &result[region.start..region.end],
+            "// <igniter:hooks>\nimport Chart from \"./hooks/chart\";\n// </igniter:hooks>\n"
+        );
+
+        // Only the region is rewritten, and rewriting it with its content is a no-op
+        let edited = result.replace(
+            "new LiveSocket(\"/live\", Socket, {})",
+            "new  LiveSocket(\"/live\",Socket,{})",
+        );
+        let updated = upsert_managed_region(
+            &edited,
+            "hooks",
+            "import Chart from \"./hooks/chart\";\nimport Map from \"./hooks/map\";\n",
+            &ImportPosition::AfterLastImport,
+        )
+        .unwrap();
+        assert!(updated.contains("import Map from \"./hooks/map\";\n// </igniter:hooks>"));
+        assert!(updated.contains("new  LiveSocket(\"/live\",Socket,{})"));
+        let again = upsert_managed_region(
+            &updated,
+            "hooks",
+            "import Chart from \"./hooks/chart\";\nimport Map from \"./hooks/map\";",
+            &ImportPosition::Top,
+        )
+        .unwrap();
+        assert_eq!(again, updated);
+
+        // A byte order mark and multibyte characters around the region
+        let code = "\u{feff}import \"🚀\";\nlet title = \"Réunion\";\n";
+        let result = upsert_managed_region(
+            code,
+            "hooks",
+            "import Chart from \"./📈\";",
+            &ImportPosition::AfterLastImport,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "\u{feff}import \"🚀\";\n\n// <igniter:hooks>\nimport Chart from \"./📈\";\n// </igniter:hooks>\n\nlet title = \"Réunion\";\n"
+        );
+
+        assert!(upsert_managed_region(code, "hooks", "import {", &ImportPosition::Top).is_err());
+        assert!(list_managed_regions("// <igniter:hooks>\nlet a = 1;\n").is_err());
+        assert!(list_managed_regions("// </igniter:hooks>\n").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast::ImportPosition;
+use crate::parsers::javascript::region::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn list_managed_regions_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::list_managed_regions_nif();
+        match list_managed_regions(&file_content) {
+            Ok(regions) => encode_response(env, atoms::ok(), fn_atom, regions),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn find_managed_region_nif(
+    env: Env,
+    file_content: String,
+    name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::find_managed_region_nif();
+        match find_managed_region(&file_content, &name) {
+            Ok(region) => encode_response(env, atoms::ok(), fn_atom, region),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn upsert_managed_region_nif(
+    env: Env,
+    file_content: String,
+    name: String,
+    content: String,
+    position: ImportPosition,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::upsert_managed_region_nif();
+        let (status, result) =
+            match upsert_managed_region(&file_content, &name, &content, &position) {
+                Ok(updated_code) => (atoms::ok(), updated_code),
+                Err(error_msg) => (atoms::error(), error_msg),
+            };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
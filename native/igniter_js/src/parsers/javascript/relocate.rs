@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Moving a top-level declaration from a source to another, with the imports it uses, so
+//! an installer can reorganize the assets of a project, e.g. split the hooks out of an
+//! `app.js` into their own files.
+
+use crate::parsers::javascript::ast::insert_import_to_ast;
+use crate::parsers::javascript::extract::{extract_code_block, is_declaration_of};
+use crate::parsers::javascript::helpers::{emit_import, parse, SpanOffsets};
+use crate::parsers::javascript::scope::ScopeCollector;
+use std::collections::BTreeSet;
+use swc_atoms::Atom;
+use swc_common::Spanned;
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitWith;
+
+/// The two sources of a moved declaration, see [`move_declaration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedDeclaration {
+    /// The source the declaration is moved from.
+    pub source: String,
+    /// The source the declaration is moved to.
+    pub target: String,
+}
+
+fn import_local(specifier: &ImportSpecifier) -> &Ident {
+    match specifier {
+        ImportSpecifier::Named(named) => &named.local,
+        ImportSpecifier::Default(default) => &default.local,
+        ImportSpecifier::Namespace(namespace) => &namespace.local,
+    }
+}
+
+/// Removes the text from `start` to `end` with the rest of its last line, and the blank
+/// line that would be left twice.
+fn remove_block(file_content: &str, start: usize, end: usize) -> String {
+    let line_start = file_content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let start = if file_content[line_start..start].trim().is_empty() {
+        line_start
+    } else {
+        start
+    };
+    let rest = &file_content[end..];
+    let mut end = end + rest.find('\n').map_or(rest.len(), |i| i + 1);
+    if file_content[..start].ends_with("\n\n") || start == 0 {
+        end += file_content[end..].len() - file_content[end..].trim_start_matches('\n').len();
+    }
+
+    format!("{}{}", &file_content[..start], &file_content[end..])
+}
+
+/// Moves the top-level declaration of `name` from `source` to the end of `target`.
+///
+/// The declaration keeps its original text and leading comments, see
+/// [`extract_code_block`]. The bindings it imports in `source` are imported in `target`
+/// from the same sources, which are copied as written, so a relative import may have to
+/// be fixed when both files are not in the same directory.
+///
+/// When the rest of `source` still uses the declaration, `import_from` is required: the
+/// declaration is imported in `source` from it, and exported from `target` if it was not.
+///
+/// # Arguments
+/// * `source` - The source the declaration is moved from.
+/// * `target` - The source the declaration is moved to.
+/// * `name` - The name of the function, class or variable declared.
+/// * `import_from` - The source `target` is imported from in `source`, e.g. `"./hooks/copy"`.
+///
+/// # Returns
+/// * `Ok(MovedDeclaration)` - Both updated sources.
+/// * `Err(String)` - If a source can not be parsed, `name` is not declared at the top level
+///   of `source` or is already declared in `target`, the declaration uses another top-level
+///   declaration of `source`, or `source` still uses it and `import_from` is not given.
+///
+/// # Example
+/// ```rust
+/// let source = "import { copy } from \"./clipboard\";\nconst CopyHook = { mounted() { copy(this.el); } };\n";
+/// let moved = move_declaration(source, "", "CopyHook", None).unwrap();
+/// assert!(moved.target.starts_with("import { copy } from \"./clipboard\";\n"));
+/// ```
+pub fn move_declaration(
+    source: &str,
+    target: &str,
+    name: &str,
+    import_from: Option<&str>,
+) -> Result<MovedDeclaration, String> {
+    let block = extract_code_block(source, name)?;
+    let (module, _comments, cm) = parse(source)?;
+    let item = module
+        .body
+        .iter()
+        .find(|item| is_declaration_of(item, name))
+        .ok_or_else(|| format!("`{}` is not declared at the top level", name))?;
+    let span = item.span();
+
+    let mut collector = ScopeCollector::new();
+    module.visit_with(&mut collector);
+    let declared: Vec<&Atom> = collector.scopes[0]
+        .bindings
+        .iter()
+        .filter(|binding| is_declaration_of(item, binding))
+        .collect();
+
+    // The references of the module scope, inside and outside of the declaration
+    let mut used = BTreeSet::new();
+    let mut used_outside = false;
+    for (lo, sym, scope) in &collector.occurrences {
+        if collector.declarations.contains(lo) || collector.resolve(sym, *scope) != Some(0) {
+            continue;
+        }
+        if span.lo <= *lo && *lo < span.hi {
+            if !declared.contains(&sym) {
+                used.insert(sym.clone());
+            }
+        } else if declared.contains(&sym) {
+            used_outside = true;
+        }
+    }
+
+    let mut imports = vec![];
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        let mut import = import.clone();
+        import
+            .specifiers
+            .retain(|specifier| used.remove(&import_local(specifier).sym));
+        if !import.specifiers.is_empty() {
+            imports.push(emit_import(import));
+        }
+    }
+    if let Some(sym) = used.first() {
+        return Err(format!(
+            "`{}` uses `{}`, which is declared in the source it is moved from",
+            name, sym
+        ));
+    }
+
+    let (target_module, _comments, _cm) = parse(target)?;
+    let mut target_collector = ScopeCollector::new();
+    target_module.visit_with(&mut target_collector);
+    if let Some(sym) = declared
+        .iter()
+        .find(|sym| target_collector.scopes[0].bindings.contains(sym))
+    {
+        return Err(format!("`{}` is already declared in the target", sym));
+    }
+
+    let mut code = block.code;
+    let mut updated_source = remove_block(source, block.location.start, block.location.end);
+    if used_outside {
+        let Some(import_from) = import_from else {
+            return Err(format!(
+                "`{}` is still used in the source it is moved from, give the source to \
+                 import it from",
+                name
+            ));
+        };
+
+        let import_line = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(_)) => {
+                format!("import {} from \"{}\";", name, import_from)
+            }
+            item => {
+                if matches!(item, ModuleItem::Stmt(_)) {
+                    let offset = SpanOffsets::new(&cm).offset(span.lo) - block.location.start;
+                    code.insert_str(offset, "export ");
+                }
+                let names: Vec<&str> = declared.iter().map(|sym| sym.as_str()).collect();
+                format!(
+                    "import {{ {} }} from \"{}\";",
+                    names.join(", "),
+                    import_from
+                )
+            }
+        };
+        updated_source = insert_import_to_ast(&updated_source, &import_line)?;
+    }
+
+    let mut updated_target = if imports.is_empty() {
+        target.to_string()
+    } else {
+        insert_import_to_ast(target, &imports.join("\n"))?
+    };
+    if !updated_target.trim().is_empty() {
+        updated_target = format!("{}\n\n", updated_target.trim_end());
+    }
+    updated_target.push_str(&code);
+    updated_target.push('\n');
+
+    parse(&updated_source).map_err(|error| format!("Failed to move `{}`: {}", name, error))?;
+    parse(&updated_target).map_err(|error| format!("Failed to move `{}`: {}", name, error))?;
+
+    Ok(MovedDeclaration {
+        source: updated_source,
+        target: updated_target,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_declaration() {
+        let source = r#"import { Socket } from "phoenix";
+import { copy, paste } from "./clipboard";
+import topbar from "../vendor/topbar";
+
+// Copies the text of the element
+const CopyHook = {
+  mounted() {
+    this.el.addEventListener("click", () => copy(this.el.innerText));
+  },
+};
+
+let liveSocket = new LiveSocket("/live", Socket, { hooks: { CopyHook } });
+"#;
+
+        let target = "import { paste } from \"./clipboard\";\n\nexport const PasteHook = {};\n";
+        let moved = move_declaration(source, target, "CopyHook", Some("./hooks")).unwrap();
+        assert_eq!(
+            moved.target,
+            r#"import { paste } from "./clipboard";
+import { copy } from "./clipboard";
+export const PasteHook = {};
+
+// Copies the text of the element
+export const CopyHook = {
+  mounted() {
+    this.el.addEventListener("click", () => copy(this.el.innerText));
+  },
+};
+"#
+        );
+        assert!(moved
+            .source
+            .contains("import { CopyHook } from \"./hooks\";"));
+        assert!(!moved.source.contains("Copies the text"));
+
+        let error = move_declaration(source, target, "CopyHook", None).unwrap_err();
+        assert!(error.contains("is still used"), "{}", error);
+
+        let source = "function copy(text) {}\nexport function CopyHook() { copy(1); }\n";
+        let error = move_declaration(source, "", "CopyHook", None).unwrap_err();
+        assert_eq!(
+            error,
+            "`CopyHook` uses `copy`, which is declared in the source it is moved from"
+        );
+
+        let source = "let a = 1;\n\nfunction copy(text) { return text; }\n\nlet b = 2;\n";
+        let moved = move_declaration(source, "", "copy", None).unwrap();
+        assert_eq!(moved.source, "let a = 1;\n\nlet b = 2;\n");
+        assert_eq!(moved.target, "function copy(text) { return text; }\n");
+
+        let target = "const copy = 1;";
+        let error = move_declaration(source, target, "copy", None).unwrap_err();
+        assert_eq!(error, "`copy` is already declared in the target");
+    }
+}
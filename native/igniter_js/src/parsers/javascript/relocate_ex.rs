@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::relocate::move_declaration;
+
+use rustler::{Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct MovedDeclarationResult {
+    pub source: String,
+    pub target: String,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn move_declaration_between_sources_nif(
+    env: Env,
+    source: String,
+    target: String,
+    name: String,
+    import_from: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::move_declaration_between_sources_nif();
+
+        match move_declaration(&source, &target, &name, import_from.as_deref()) {
+            Ok(moved) => {
+                let result = MovedDeclarationResult {
+                    source: moved.source,
+                    target: moved.target,
+                };
+                encode_response(env, atoms::ok(), fn_atom, result)
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
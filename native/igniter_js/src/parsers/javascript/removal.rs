@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Commenting out the code a removal drops instead of deleting it, so an uninstall leaves
+//! the user a trace of what was taken out and an easy way to put it back.
+//!
+//! The removals record the spans of the nodes they drop, and in [`RemovalMode::CommentOut`]
+//! the original source is returned with those spans turned into line comments marked with
+//! [`REMOVED_MARKER`], instead of the generated code. The rest of the source is untouched.
+
+use crate::parsers::javascript::helpers::{code_gen_edit, source_style, SpanOffsets};
+#[cfg(feature = "nif")]
+use rustler::NifUnitEnum;
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, SourceMap, Span};
+use swc_ecma_ast::Module;
+
+/// The text starting the comments of the removed code.
+pub const REMOVED_MARKER: &str = "removed by igniter:";
+
+/// What a removal does with the code it drops.
+///
+/// On the Elixir side these are the atoms `:delete` and `:comment_out`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum RemovalMode {
+    #[default]
+    Delete,
+    /// Keeps the code as line comments, e.g. `// removed by igniter: import "topbar";`.
+    CommentOut,
+}
+
+/// Emits the result of a removal: the code generated from `module` when deleting, or the
+/// parsed source with the `removed` spans commented out. The `source` is returned untouched
+/// when `module` is still the `original` parsed from it.
+pub fn emit_removal(
+    source: &str,
+    original: &Module,
+    module: &mut Module,
+    comments: SingleThreadedComments,
+    cm: Lrc<SourceMap>,
+    removed: &[Span],
+    mode: RemovalMode,
+) -> String {
+    match mode {
+        RemovalMode::Delete => code_gen_edit(source, original, module, comments, cm),
+        RemovalMode::CommentOut => {
+            let Some(file) = cm.files().first().cloned() else {
+                return code_gen_edit(source, original, module, comments, cm);
+            };
+            // The ranges index the text of the source file, which has no byte order mark
+            let offsets = SpanOffsets::new(&cm).without_bom();
+            let ranges: Vec<(usize, usize)> = removed
+                .iter()
+                .filter(|span| !span.is_dummy())
+                .map(|span| offsets.range(*span))
+                .collect();
+            source_style(&cm).apply(&comment_out_ranges(&file.src, ranges))
+        }
+    }
+}
+
+/// Comments out the given byte ranges of a source.
+///
+/// A range standing on its own lines, like a statement or an entry of a multiline object,
+/// has its lines commented out in place. A range sharing its line with other code, like an
+/// entry of `{ Old, New }`, is cut out along with its comma and kept in a comment line above.
+///
+/// # Example
+/// ```rust
+/// let source = "import \"topbar\";\nlet hooks = { Old, New };\n";
+/// assert_eq!(
+///     comment_out_ranges(source, vec![(0, 16), (31, 34)]),
+///     "// removed by igniter: import \"topbar\";\n// removed by igniter: Old\nlet hooks = { New };\n"
+/// );
+/// ```
+pub fn comment_out_ranges(source: &str, mut ranges: Vec<(usize, usize)>) -> String {
+    ranges.sort_unstable();
+    ranges.dedup();
+
+    let mut code = source.to_string();
+    let mut bound = source.len();
+    for (lo, hi) in ranges.into_iter().rev() {
+        // A range nested in one already commented out is commented out with it
+        if hi > bound || lo >= hi {
+            continue;
+        }
+        bound = lo;
+
+        let line_start = code[..lo].rfind('\n').map_or(0, |index| index + 1);
+        let (end, has_comma) = match skip_blanks(&code, hi).strip_prefix(',') {
+            Some(rest) => (code.len() - rest.len(), true),
+            None => (hi, false),
+        };
+        let line_end = code[end..]
+            .find('\n')
+            .map_or(code.len(), |index| end + index);
+
+        if code[line_start..lo].trim().is_empty() && code[end..line_end].trim().is_empty() {
+            let commented = code[line_start..line_end]
+                .split('\n')
+                .map(|line| {
+                    let line = line.trim_end_matches('\r');
+                    let content = line.trim_start();
+                    let indent = &line[..line.len() - content.len()];
+                    format!("{}// {} {}", indent, REMOVED_MARKER, content)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            code.replace_range(line_start..line_end, &commented);
+            continue;
+        }
+
+        let removed = code[lo..hi].to_string();
+        let cut = if has_comma {
+            lo..code.len() - skip_blanks(&code, end).len()
+        } else {
+            match code[..lo].trim_end_matches([' ', '\t']).strip_suffix(',') {
+                Some(before) => before.len()..hi,
+                None => lo..hi,
+            }
+        };
+        code.replace_range(cut, "");
+
+        let line = &code[line_start..];
+        let indent = line[..line.len() - line.trim_start_matches([' ', '\t']).len()].to_string();
+        let comment: String = removed
+            .lines()
+            .map(|removed_line| {
+                format!("{}// {} {}\n", indent, REMOVED_MARKER, removed_line.trim())
+            })
+            .collect();
+        code.insert_str(line_start, &comment);
+    }
+    code
+}
+
+/// The rest of `code` from `index`, without the spaces and tabs it starts with.
+fn skip_blanks(code: &str, index: usize) -> &str {
+    code[index..].trim_start_matches([' ', '\t'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_out_ranges() {
+        let source = "import \"topbar\";\nlet hooks = { Old, New };\n";
+        assert_eq!(
+            comment_out_ranges(source, vec![(0, 16), (31, 34)]),
+            "// removed by igniter: import \"topbar\";\n// removed by igniter: Old\nlet hooks = { New };\n"
+        );
+
+        // The last entry takes the comma before it
+        assert_eq!(
+            comment_out_ranges("let hooks = { New, Old };", vec![(19, 22)]),
+            "// removed by igniter: Old\nlet hooks = { New };"
+        );
+
+        // Offsets are in bytes, whatever the characters before them
+        let source = "let hooks = { \"🚀\": Rocket, Old };";
+        assert_eq!(
+            comment_out_ranges(source, vec![(30, 33)]),
+            "// removed by igniter: Old\nlet hooks = { \"🚀\": Rocket };"
+        );
+
+        // Multiline entries keep their indentation and their comma
+        let source = "let hooks = {\n  Old,\n  New\n};\n";
+        assert_eq!(
+            comment_out_ranges(source, vec![(16, 19)]),
+            "let hooks = {\n  // removed by igniter: Old,\n  New\n};\n"
+        );
+    }
+}
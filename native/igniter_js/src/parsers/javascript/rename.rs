@@ -0,0 +1,225 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Scope-aware renaming of a top-level binding, e.g. `liveSocket` to `socket`.
+
+use crate::parsers::javascript::helpers::{code_gen_edit, parse, parse_expr};
+use crate::parsers::javascript::scope::{Renamer, ScopeCollector};
+use std::collections::HashMap;
+use swc_atoms::Atom;
+use swc_ecma_ast::Expr;
+use swc_ecma_visit::{VisitMutWith, VisitWith};
+
+/// The scope of the top-level bindings in [`ScopeCollector::scopes`].
+const TOP_LEVEL: usize = 0;
+
+/// Renames a binding declared at the top level and every reference to it.
+///
+/// References in scopes that shadow the binding are left as is. Shorthand properties and
+/// destructuring keep their key, e.g. `{ old }` becomes `{ old: new }`, and so do named
+/// imports and exports, e.g. `import { old }` becomes `import { old as new }`.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `old_name` - The name of the top-level binding.
+/// * `new_name` - The name to give it.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If the source can not be parsed, `new_name` is not a valid identifier,
+///   `old_name` is not declared at the top level, or `new_name` is already visible where
+///   the binding is used, or used where the renamed binding would capture it.
+///
+/// # Example
+/// ```rust
+/// let code = "let socket = 1; function f(socket) { return socket; } use(socket);";
+/// let result = rename_identifier(code, "socket", "liveSocket").unwrap();
+/// assert!(result.contains("let liveSocket = 1;"));
+/// assert!(result.contains("return socket;"));
+/// assert!(result.contains("use(liveSocket);"));
+/// ```
+pub fn rename_identifier(
+    file_content: &str,
+    old_name: &str,
+    new_name: &str,
+) -> Result<String, String> {
+    match parse_expr(new_name).as_deref() {
+        Ok(Expr::Ident(ident)) if ident.sym == new_name => {}
+        _ => return Err(format!("`{}` is not a valid identifier", new_name)),
+    }
+
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let mut collector = ScopeCollector::new();
+    module.visit_with(&mut collector);
+
+    let (old, new) = (Atom::from(old_name), Atom::from(new_name));
+    if !collector.scopes[TOP_LEVEL].bindings.contains(&old) {
+        return Err(format!("`{}` is not declared at the top level", old_name));
+    }
+    if old == new {
+        return Ok(code_gen_edit(
+            file_content,
+            &original,
+            &mut module,
+            comments,
+            cm,
+        ));
+    }
+
+    let mut renames = HashMap::new();
+    for (lo, sym, scope) in &collector.occurrences {
+        if *sym == old && collector.resolve(sym, *scope) == Some(TOP_LEVEL) {
+            // A binding of the new name in between would capture the reference
+            if collector.resolve(&new, *scope).is_some() {
+                return Err(format!("`{}` is already declared", new_name));
+            }
+            renames.insert((*lo, sym.clone()), new.clone());
+        }
+
+        // A global reference to the new name would be captured by the renamed binding
+        if *sym == new && collector.resolve(sym, *scope).is_none() {
+            return Err(format!("`{}` is already used as a global", new_name));
+        }
+    }
+
+    module.visit_mut_with(&mut Renamer { renames });
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_identifier() {
+        let code = r#"import { Socket } from "phoenix";
+let socket = new Socket("/socket");
+function connect(socket) {
+  return socket.connect();
+}
+const config = { socket, other: socket.id };
+const { socket: alias } = config;
+const read = ({ socket = null }) => socket;
+socket.connect();
+export { socket };
+"#;
+
+        let result = rename_identifier(code, "socket", "liveSocket").unwrap();
+        assert_eq!(
+            result,
+            r#"import { Socket } from "phoenix";
+let liveSocket = new Socket("/socket");
+function connect(socket) {
+    return socket.connect();
+}
+const config = {
+    socket: liveSocket,
+    other: liveSocket.id
+};
+const { socket: alias } = config;
+const read = ({ socket = null })=>socket;
+liveSocket.connect();
+export { liveSocket as socket };
+"#
+        );
+
+        let result = rename_identifier(code, "Socket", "PhoenixSocket").unwrap();
+        assert!(result.contains(r#"import { Socket as PhoenixSocket } from "phoenix";"#));
+        assert!(result.contains(r#"new PhoenixSocket("/socket")"#));
+    }
+
+    #[test]
+    fn test_rename_identifier_scopes() {
+        let code = r#"function open() { return socket.open(); }
+let socket = connect();
+function hoisted() { use(socket); if (ok) { var socket = 1; } }
+function declared() { return socket(); function socket() {} }
+function block() { { let socket = 1; } return socket; }
+function params(a = socket, b) { var socket = b; return a; }
+function redeclared(socket) { var socket; return socket; }
+class Hook {
+  constructor(socket) { this.socket = socket; }
+  set value(socket) { this.v = socket; }
+  static { var socket = 1; }
+}
+try {} catch (socket) { socket; }
+export default function main() { return socket; }
+"#;
+
+        let result = rename_identifier(code, "socket", "liveSocket").unwrap();
+        // Used before its declaration
+        assert!(result.contains("return liveSocket.open();"));
+        // Shadowed by a hoisted `var` or function declaration
+        assert!(result.contains("use(socket);\n    if (ok) {\n        var socket = 1;"));
+        assert!(result.contains("return socket();\n    function socket() {}"));
+        assert!(result.contains("let socket = 1;\n    }\n    return liveSocket;"));
+        // A default value does not see the declarations of the body
+        assert!(result.contains("function params(a = liveSocket, b) {\n    var socket = b;"));
+        assert!(
+            result.contains("function redeclared(socket) {\n    var socket;\n    return socket;")
+        );
+        assert!(result.contains("constructor(socket){\n        this.socket = socket;"));
+        assert!(result.contains("set value(socket) {\n        this.v = socket;"));
+        assert!(result.contains("var socket = 1;\n    }\n}"));
+        assert!(result.contains("catch (socket) {\n    socket;"));
+        assert!(result.contains("export default function main() {\n    return liveSocket;"));
+
+        let result = rename_identifier(code, "main", "start").unwrap();
+        assert!(result.contains("export default function start()"));
+
+        // The hoisted `var` would capture the reference before it
+        let code = "let socket = 1; function f() { use(socket); { var liveSocket; } }";
+        assert!(rename_identifier(code, "socket", "liveSocket").is_err());
+    }
+
+    #[test]
+    fn test_rename_identifier_binding_forms() {
+        let code = r#"let socket = 1;
+const named = function socket() { return socket; };
+const Named = class socket { open() { return socket; } };
+class Field extends socket { socket = socket; }
+try {} catch ({ socket }) { socket; }
+for (let socket of [socket]) { socket; }
+function own(first = socket, socket) { return first; }
+const arrow = ({ socket = socket }) => socket;
+switch (kind) { case 1: let socket = 2; socket; }
+"#;
+
+        let result = rename_identifier(code, "socket", "liveSocket").unwrap();
+        // The name of a function or class expression is only visible inside of it
+        assert!(result.contains("function socket() {\n    return socket;"));
+        assert!(result.contains("class socket {\n    open() {\n        return socket;"));
+        assert!(result.contains("class Field extends liveSocket {\n    socket = liveSocket;"));
+        assert!(result.contains("catch ({ socket }) {\n    socket;"));
+        // The head of a `for` and a default value see the bindings they declare
+        assert!(result.contains("for (let socket of [\n    socket\n])"));
+        assert!(result.contains("function own(first = socket, socket)"));
+        assert!(result.contains("({ socket = socket })=>socket"));
+        assert!(result.contains("let socket = 2;\n        socket;"));
+    }
+
+    #[test]
+    fn test_rename_identifier_errors() {
+        let code = "let a = 1; let b = 2; function f(c) { return a + c; } g(a);";
+
+        assert!(rename_identifier(code, "a", "b").is_err());
+        // `c` would capture the reference in `f`
+        assert!(rename_identifier(code, "a", "c").is_err());
+        // `g` is a global
+        assert!(rename_identifier(code, "a", "g").is_err());
+        assert!(rename_identifier(code, "c", "d").is_err());
+        assert!(rename_identifier(code, "a", "not valid").is_err());
+        assert!(rename_identifier(code, "a", "class").is_err());
+        assert!(rename_identifier("let a = ", "a", "b").is_err());
+        assert!(rename_identifier(code, "a", "d").is_ok());
+    }
+}
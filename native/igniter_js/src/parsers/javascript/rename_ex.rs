@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::rename::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn rename_identifier_nif(
+    env: Env,
+    file_content: String,
+    old_name: String,
+    new_name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::rename_identifier_nif();
+        let result = rename_identifier(&file_content, &old_name, &new_name);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
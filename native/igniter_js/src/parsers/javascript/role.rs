@@ -0,0 +1,351 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Tells what a JavaScript file is for in a Phoenix application, so a task going over
+//! `assets/**/*.js` can pick the transform of each file instead of hardcoding paths.
+//!
+//! The checks run from the most to the least specific: a vendored bundle is never taken for
+//! an entry point, even when it creates a LiveSocket, and an entry point is never taken for
+//! a configuration file.
+
+use crate::parsers::javascript::helpers::{parse, prop_key_name};
+use crate::parsers::javascript::minified::is_probably_minified;
+use crate::parsers::javascript::phoenix::list_live_sockets_in_module;
+#[cfg(feature = "nif")]
+use rustler::{NifMap, NifUnitEnum};
+use swc_ecma_ast::*;
+
+/// What a file is for.
+///
+/// On the Elixir side these are the atoms `:app_entry`, `:hooks_index`, `:vendor`,
+/// `:config` and `:unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum FileRole {
+    /// The entry point creating the LiveSocket, usually `assets/js/app.js`.
+    AppEntry,
+    /// An aggregator exporting the hooks it imports, see
+    /// [`generate_hooks_index`](super::hooks_index::generate_hooks_index).
+    HooksIndex,
+    /// A third-party file, minified or carrying a license banner.
+    Vendor,
+    /// The configuration of a build tool.
+    Config,
+    Unknown,
+}
+
+/// The tool a configuration file is for.
+///
+/// On the Elixir side these are the atoms `:tailwind`, `:postcss` and `:vite`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifUnitEnum))]
+pub enum ConfigTool {
+    Tailwind,
+    Postcss,
+    Vite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct FileRoleReport {
+    pub role: FileRole,
+    /// The tool of a [`FileRole::Config`] file, when it can be told.
+    pub config_tool: Option<ConfigTool>,
+}
+
+impl FileRoleReport {
+    fn new(role: FileRole) -> Self {
+        Self {
+            role,
+            config_tool: None,
+        }
+    }
+}
+
+/// The tool of a configuration file by its name, e.g. `tailwind.config.js`.
+fn config_tool_of_file_name(file_name: &str) -> Option<ConfigTool> {
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    [
+        ("tailwind.config.", ConfigTool::Tailwind),
+        ("postcss.config.", ConfigTool::Postcss),
+        ("vite.config.", ConfigTool::Vite),
+    ]
+    .into_iter()
+    .find_map(|(prefix, tool)| base.starts_with(prefix).then_some(tool))
+}
+
+/// Whether the first comment of the file is a license banner, i.e. `/*! ... */` or a
+/// comment with `@license`.
+fn has_license_banner(file_content: &str) -> bool {
+    let content = file_content.trim_start_matches('\u{feff}').trim_start();
+    let banner = match content.strip_prefix("/*") {
+        Some(rest) => rest.split("*/").next().unwrap_or(rest),
+        None => return false,
+    };
+    banner.starts_with('!') || banner.contains("@license")
+}
+
+fn is_vendor(file_content: &str) -> bool {
+    has_license_banner(file_content)
+        || file_content.contains("define.amd")
+        || is_probably_minified(file_content).minified
+}
+
+/// The sources the module imports or requires at the top level.
+fn module_sources(module: &Module) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                Some(import.src.value.to_string())
+            }
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => {
+                var.decls
+                    .iter()
+                    .find_map(|decl| match decl.init.as_deref()?.unwrap_parens() {
+                        Expr::Call(call) if is_require(call) => {
+                            call.args.first().and_then(|arg| match &*arg.expr {
+                                Expr::Lit(Lit::Str(source)) => Some(source.value.to_string()),
+                                _ => None,
+                            })
+                        }
+                        _ => None,
+                    })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_require(call: &CallExpr) -> bool {
+    matches!(&call.callee, Callee::Expr(callee)
+        if matches!(&**callee, Expr::Ident(ident) if ident.sym == *"require"))
+}
+
+/// The object literal a top-level variable is initialized with.
+fn var_object<'a>(module: &'a Module, name: &str) -> Option<&'a ObjectLit> {
+    module.body.iter().find_map(|item| {
+        let var = match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var),
+                ..
+            })) => var,
+            _ => return None,
+        };
+        var.decls
+            .iter()
+            .find_map(|decl| match (&decl.name, decl.init.as_deref()) {
+                (Pat::Ident(ident), Some(Expr::Object(object))) if ident.id.sym == *name => {
+                    Some(object)
+                }
+                _ => None,
+            })
+    })
+}
+
+/// The expression exported by `export default` or assigned to `module.exports`.
+fn exported_value(module: &Module) -> Option<&Expr> {
+    module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+            Some(export.expr.unwrap_parens())
+        }
+        ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) => match &**expr {
+            Expr::Assign(AssignExpr {
+                op: AssignOp::Assign,
+                left: AssignTarget::Simple(SimpleAssignTarget::Member(member)),
+                right,
+                ..
+            }) if matches!(&*member.obj, Expr::Ident(obj) if obj.sym == *"module")
+                && matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == *"exports") =>
+            {
+                Some(right.unwrap_parens())
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Whether the module exports an object of hooks, every entry of which is an import.
+fn is_hooks_index(module: &Module) -> bool {
+    let object = match exported_value(module) {
+        Some(Expr::Object(object)) => object,
+        Some(Expr::Ident(ident)) => match var_object(module, &ident.sym) {
+            Some(object) => object,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    let imported: Vec<&str> = module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(&import.specifiers),
+            _ => None,
+        })
+        .flatten()
+        .map(|specifier| match specifier {
+            ImportSpecifier::Named(named) => &*named.local.sym,
+            ImportSpecifier::Default(default) => &*default.local.sym,
+            ImportSpecifier::Namespace(namespace) => &*namespace.local.sym,
+        })
+        .collect();
+
+    !object.props.is_empty()
+        && object.props.iter().all(|prop| match prop {
+            PropOrSpread::Spread(spread) => {
+                matches!(&*spread.expr, Expr::Ident(ident) if imported.contains(&&*ident.sym))
+            }
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(ident) => imported.contains(&&*ident.sym),
+                Prop::KeyValue(KeyValueProp { value, .. }) => {
+                    matches!(&**value, Expr::Ident(ident) if imported.contains(&&*ident.sym))
+                }
+                _ => false,
+            },
+        })
+}
+
+/// The tool of a configuration module, from what it imports and the keys it exports.
+fn config_tool_of_module(module: &Module) -> Option<ConfigTool> {
+    let sources = module_sources(module);
+    let object = match exported_value(module)? {
+        Expr::Object(object) => object,
+        Expr::Call(call) => {
+            let is_define_config = matches!(&call.callee, Callee::Expr(callee)
+                if matches!(&**callee, Expr::Ident(ident) if ident.sym == *"defineConfig"));
+            if is_define_config || sources.iter().any(|source| source == "vite") {
+                return Some(ConfigTool::Vite);
+            }
+            match call.args.first().map(|arg| arg.expr.unwrap_parens()) {
+                Some(Expr::Object(object)) => object,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let keys: Vec<String> = object.props.iter().filter_map(prop_key_name).collect();
+    if sources.iter().any(|source| source == "vite") {
+        Some(ConfigTool::Vite)
+    } else if keys
+        .iter()
+        .any(|key| matches!(key.as_str(), "content" | "theme" | "darkMode" | "presets"))
+    {
+        Some(ConfigTool::Tailwind)
+    } else if keys.iter().any(|key| key == "plugins")
+        && keys
+            .iter()
+            .all(|key| matches!(key.as_str(), "plugins" | "map" | "parser" | "syntax"))
+    {
+        Some(ConfigTool::Postcss)
+    } else {
+        None
+    }
+}
+
+/// Tells what a JavaScript file is for.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code.
+/// * `file_name` - The path or name of the file, if known, which tells the tool of a
+///   configuration file like `tailwind.config.js` whatever its content.
+///
+/// # Returns
+/// * `Ok(FileRoleReport)` - The role, [`FileRole::Unknown`] when no check matches.
+/// * `Err(String)` - If the source can not be parsed, unless it is a vendored file.
+///
+/// # Example
+/// ```rust
+/// let code = "import { LiveSocket } from \"phoenix_live_view\";\nlet liveSocket = new LiveSocket(\"/live\", Socket);";
+/// assert_eq!(detect_file_role(code, None).unwrap().role, FileRole::AppEntry);
+/// ```
+pub fn detect_file_role(
+    file_content: &str,
+    file_name: Option<&str>,
+) -> Result<FileRoleReport, String> {
+    if is_vendor(file_content) {
+        return Ok(FileRoleReport::new(FileRole::Vendor));
+    }
+
+    let (module, _comments, _cm) = parse(file_content)?;
+    if !list_live_sockets_in_module(&module).is_empty() {
+        return Ok(FileRoleReport::new(FileRole::AppEntry));
+    }
+    if is_hooks_index(&module) {
+        return Ok(FileRoleReport::new(FileRole::HooksIndex));
+    }
+
+    match file_name
+        .and_then(config_tool_of_file_name)
+        .or_else(|| config_tool_of_module(&module))
+    {
+        Some(tool) => Ok(FileRoleReport {
+            role: FileRole::Config,
+            config_tool: Some(tool),
+        }),
+        None => Ok(FileRoleReport::new(FileRole::Unknown)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(code: &str, file_name: Option<&str>) -> (FileRole, Option<ConfigTool>) {
+        let report = detect_file_role(code, file_name).unwrap();
+        (report.role, report.config_tool)
+    }
+
+    #[test]
+    fn test_detect_file_role() {
+        let app_js = r#"import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+let liveSocket = new LiveSocket("/live", Socket, {});
+"#;
+        assert_eq!(role(app_js, None), (FileRole::AppEntry, None));
+
+        let hooks_index = r#"import Chart from "./chart";
+import { Map } from "./map";
+const hooks = { Chart, Map, Sortable: Chart };
+export default hooks;
+"#;
+        assert_eq!(role(hooks_index, None), (FileRole::HooksIndex, None));
+
+        let vendor = "/*! topbar 2.0.0, 2023-02-04\n * https://buunguyen.github.io/topbar\n */\n(function (window) {})(this);\n";
+        assert_eq!(role(vendor, None), (FileRole::Vendor, None));
+
+        let tailwind = "module.exports = {\n  content: [\"./js/**/*.js\"],\n  plugins: []\n};\n";
+        assert_eq!(
+            role(tailwind, None),
+            (FileRole::Config, Some(ConfigTool::Tailwind))
+        );
+
+        let postcss = "module.exports = {\n  plugins: { tailwindcss: {}, autoprefixer: {} }\n};\n";
+        assert_eq!(
+            role(postcss, None),
+            (FileRole::Config, Some(ConfigTool::Postcss))
+        );
+
+        let vite =
+            "import { defineConfig } from \"vite\";\nexport default defineConfig({ build: {} });\n";
+        assert_eq!(role(vite, None), (FileRole::Config, Some(ConfigTool::Vite)));
+
+        // The file name tells the tool whatever the content
+        assert_eq!(
+            role("export default {};", Some("assets/tailwind.config.js")),
+            (FileRole::Config, Some(ConfigTool::Tailwind))
+        );
+
+        assert_eq!(
+            role("export function copy() {}", None),
+            (FileRole::Unknown, None)
+        );
+        assert!(detect_file_role("let = ;", None).is_err());
+    }
+}
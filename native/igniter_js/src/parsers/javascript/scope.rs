@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Scope analysis of a module: which scope declares the binding every identifier refers
+//! to, and the renaming of bindings on top of it. Used by the minifier to mangle names and
+//! by the identifier renaming.
+//!
+//! The transforms edit the SWC AST, so the scopes are collected on it rather than with
+//! `oxc_semantic` on a second parse. The declarations are collected before any reference is
+//! resolved, so hoisted `var` and function declarations shadow the references before them.
+
+use crate::parsers::javascript::unused::pat_idents;
+use std::collections::{HashMap, HashSet};
+use swc_atoms::Atom;
+use swc_common::BytePos;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+#[derive(Debug)]
+pub(crate) struct Scope {
+    pub(crate) parent: Option<usize>,
+    pub(crate) is_function: bool,
+    /// Whether the scope holds the parameters of a function, its body being a child scope.
+    pub(crate) is_params: bool,
+    pub(crate) bindings: Vec<Atom>,
+}
+
+/// Builds the scope tree and records in which scope every identifier occurs.
+#[derive(Debug)]
+pub(crate) struct ScopeCollector {
+    pub(crate) scopes: Vec<Scope>,
+    current: usize,
+    /// Every identifier, by its position and name, with the scope it occurs in.
+    pub(crate) occurrences: Vec<(BytePos, Atom, usize)>,
+    /// Every identifier name of the module.
+    pub(crate) names: HashSet<Atom>,
+    /// The position of every identifier declaring a binding.
+    pub(crate) declarations: HashSet<BytePos>,
+    /// Every identifier referring to a binding rather than declaring one, by its name, with
+    /// the scope it occurs in. Unlike [`Self::declarations`], this holds for nodes without a
+    /// span too, e.g. the ones a transform inserted.
+    pub(crate) references: Vec<(Atom, usize)>,
+    /// The identifiers declaring a binding, by their address in the visited module.
+    declaring: HashSet<*const Ident>,
+    pub(crate) has_eval: bool,
+}
+
+impl ScopeCollector {
+    pub(crate) fn new() -> Self {
+        Self {
+            scopes: vec![Scope {
+                parent: None,
+                is_function: true,
+                is_params: false,
+                bindings: vec![],
+            }],
+            current: 0,
+            occurrences: vec![],
+            names: HashSet::new(),
+            declarations: HashSet::new(),
+            references: vec![],
+            declaring: HashSet::new(),
+            has_eval: false,
+        }
+    }
+
+    fn with_scope(&mut self, is_function: bool, visit: impl FnOnce(&mut Self)) {
+        let parent = self.current;
+        self.scopes.push(Scope {
+            parent: Some(parent),
+            is_function,
+            is_params: false,
+            bindings: vec![],
+        });
+        self.current = self.scopes.len() - 1;
+        visit(self);
+        self.current = parent;
+    }
+
+    /// Visits a function with its parameters in their own scope and its body in a child of
+    /// it, so a default value does not refer to a declaration of the body.
+    fn with_function_scope(
+        &mut self,
+        params: Vec<&Pat>,
+        visit_params: impl FnOnce(&mut Self),
+        visit_body: impl FnOnce(&mut Self),
+    ) {
+        self.with_scope(true, |this| {
+            let scope = this.current;
+            this.scopes[scope].is_params = true;
+            for param in params {
+                this.declare_pat(scope, param);
+            }
+            visit_params(this);
+            this.with_scope(true, visit_body);
+        });
+    }
+
+    fn declare(&mut self, scope: usize, ident: &Ident) {
+        self.declarations.insert(ident.span.lo);
+        self.declaring.insert(ident);
+        let bindings = &mut self.scopes[scope].bindings;
+        if !bindings.contains(&ident.sym) {
+            bindings.push(ident.sym.clone());
+        }
+    }
+
+    fn declare_pat(&mut self, scope: usize, pat: &Pat) {
+        let mut idents = vec![];
+        pat_idents(pat, &mut idents);
+        for ident in idents {
+            self.declare(scope, ident);
+        }
+    }
+
+    fn function_scope(&self) -> usize {
+        let mut scope = self.current;
+        while !self.scopes[scope].is_function {
+            scope = self.scopes[scope].parent.unwrap_or_default();
+        }
+        scope
+    }
+
+    /// The scope declaring the binding an identifier refers to, if any.
+    pub(crate) fn resolve(&self, name: &Atom, mut scope: usize) -> Option<usize> {
+        loop {
+            if self.scopes[scope].bindings.contains(name) {
+                return Some(scope);
+            }
+            scope = self.scopes[scope].parent?;
+        }
+    }
+}
+
+impl Visit for ScopeCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.sym == "eval" {
+            self.has_eval = true;
+        }
+        self.names.insert(ident.sym.clone());
+        self.occurrences
+            .push((ident.span.lo, ident.sym.clone(), self.current));
+        if !self.declaring.contains(&(ident as *const Ident)) {
+            self.references.push((ident.sym.clone(), self.current));
+        }
+    }
+
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        for specifier in &import.specifiers {
+            let local = match specifier {
+                ImportSpecifier::Named(named) => &named.local,
+                ImportSpecifier::Default(default) => &default.local,
+                ImportSpecifier::Namespace(namespace) => &namespace.local,
+            };
+            self.declare(self.current, local);
+            local.visit_with(self);
+        }
+    }
+
+    // Only the local name of `export { local as exported }` is a reference
+    fn visit_named_export(&mut self, export: &NamedExport) {
+        if export.src.is_some() {
+            return;
+        }
+        for specifier in &export.specifiers {
+            if let ExportSpecifier::Named(named) = specifier {
+                named.orig.visit_with(self);
+            }
+        }
+    }
+
+    // `export default function name() {}` declares `name` in the module
+    fn visit_export_default_decl(&mut self, export: &ExportDefaultDecl) {
+        match &export.decl {
+            DefaultDecl::Fn(FnExpr {
+                ident: Some(ident),
+                function,
+            }) => {
+                self.declare(self.current, ident);
+                ident.visit_with(self);
+                function.visit_with(self);
+            }
+            DefaultDecl::Class(ClassExpr {
+                ident: Some(ident),
+                class,
+            }) => {
+                self.declare(self.current, ident);
+                ident.visit_with(self);
+                class.visit_with(self);
+            }
+            decl => decl.visit_with(self),
+        }
+    }
+
+    fn visit_fn_decl(&mut self, decl: &FnDecl) {
+        self.declare(self.current, &decl.ident);
+        decl.visit_children_with(self);
+    }
+
+    fn visit_fn_expr(&mut self, expr: &FnExpr) {
+        self.with_scope(false, |this| {
+            if let Some(ident) = &expr.ident {
+                this.declare(this.current, ident);
+            }
+            expr.visit_children_with(this);
+        });
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        self.with_function_scope(
+            function.params.iter().map(|param| &param.pat).collect(),
+            |this| {
+                function.decorators.visit_with(this);
+                function.params.visit_with(this);
+            },
+            |this| {
+                if let Some(body) = &function.body {
+                    body.stmts.visit_with(this);
+                }
+            },
+        );
+    }
+
+    fn visit_constructor(&mut self, constructor: &Constructor) {
+        constructor.key.visit_with(self);
+        self.with_function_scope(
+            constructor
+                .params
+                .iter()
+                .filter_map(|param| match param {
+                    ParamOrTsParamProp::Param(param) => Some(&param.pat),
+                    ParamOrTsParamProp::TsParamProp(_) => None,
+                })
+                .collect(),
+            |this| constructor.params.visit_with(this),
+            |this| {
+                if let Some(body) = &constructor.body {
+                    body.stmts.visit_with(this);
+                }
+            },
+        );
+    }
+
+    fn visit_setter_prop(&mut self, prop: &SetterProp) {
+        prop.key.visit_with(self);
+        self.with_function_scope(
+            vec![&*prop.param],
+            |this| prop.param.visit_with(this),
+            |this| {
+                if let Some(body) = &prop.body {
+                    body.stmts.visit_with(this);
+                }
+            },
+        );
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.with_function_scope(
+            arrow.params.iter().collect(),
+            |this| arrow.params.visit_with(this),
+            |this| match &*arrow.body {
+                BlockStmtOrExpr::BlockStmt(body) => body.stmts.visit_with(this),
+                body => body.visit_with(this),
+            },
+        );
+    }
+
+    // `var` in a static block is local to the block
+    fn visit_static_block(&mut self, block: &StaticBlock) {
+        self.with_scope(true, |this| block.body.stmts.visit_with(this));
+    }
+
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        self.declare(self.current, &decl.ident);
+        decl.visit_children_with(self);
+    }
+
+    fn visit_class_expr(&mut self, expr: &ClassExpr) {
+        self.with_scope(false, |this| {
+            if let Some(ident) = &expr.ident {
+                this.declare(this.current, ident);
+            }
+            expr.visit_children_with(this);
+        });
+    }
+
+    fn visit_var_decl(&mut self, decl: &VarDecl) {
+        match decl.kind {
+            VarDeclKind::Var => {
+                let scope = self.function_scope();
+                let params = self.scopes[scope]
+                    .parent
+                    .filter(|&parent| self.scopes[parent].is_params);
+                for declarator in &decl.decls {
+                    let mut idents = vec![];
+                    pat_idents(&declarator.name, &mut idents);
+                    for ident in idents {
+                        // `var` redeclaring a parameter is the parameter
+                        let scope = match params {
+                            Some(params) if self.scopes[params].bindings.contains(&ident.sym) => {
+                                params
+                            }
+                            _ => scope,
+                        };
+                        self.declare(scope, ident);
+                    }
+                }
+            }
+            VarDeclKind::Let | VarDeclKind::Const => {
+                for declarator in &decl.decls {
+                    self.declare_pat(self.current, &declarator.name);
+                }
+            }
+        }
+        decl.visit_children_with(self);
+    }
+
+    fn visit_catch_clause(&mut self, clause: &CatchClause) {
+        self.with_scope(false, |this| {
+            if let Some(param) = &clause.param {
+                this.declare_pat(this.current, param);
+            }
+            clause.visit_children_with(this);
+        });
+    }
+
+    fn visit_block_stmt(&mut self, block: &BlockStmt) {
+        self.with_scope(false, |this| block.visit_children_with(this));
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &ForStmt) {
+        self.with_scope(false, |this| stmt.visit_children_with(this));
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForInStmt) {
+        self.with_scope(false, |this| stmt.visit_children_with(this));
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        self.with_scope(false, |this| stmt.visit_children_with(this));
+    }
+
+    fn visit_switch_stmt(&mut self, stmt: &SwitchStmt) {
+        stmt.discriminant.visit_with(self);
+        self.with_scope(false, |this| stmt.cases.visit_with(this));
+    }
+
+    // Labels are not bindings
+    fn visit_labeled_stmt(&mut self, stmt: &LabeledStmt) {
+        stmt.body.visit_with(self);
+    }
+
+    fn visit_break_stmt(&mut self, _stmt: &BreakStmt) {}
+
+    fn visit_continue_stmt(&mut self, _stmt: &ContinueStmt) {}
+}
+
+/// Renames the identifiers found by [`ScopeCollector`], expanding shorthand properties.
+pub(crate) struct Renamer {
+    pub(crate) renames: HashMap<(BytePos, Atom), Atom>,
+}
+
+impl Renamer {
+    fn new_name(&self, ident: &Ident) -> Option<Atom> {
+        self.renames
+            .get(&(ident.span.lo, ident.sym.clone()))
+            .cloned()
+    }
+}
+
+impl VisitMut for Renamer {
+    fn visit_mut_ident(&mut self, ident: &mut Ident) {
+        if let Some(name) = self.new_name(ident) {
+            ident.sym = name;
+        }
+    }
+
+    // `import { a }` becomes `import { a as b }`, the imported name stays
+    fn visit_mut_import_named_specifier(&mut self, specifier: &mut ImportNamedSpecifier) {
+        let imported = specifier.local.clone();
+        specifier.visit_mut_children_with(self);
+        if specifier.imported.is_none() && specifier.local.sym != imported.sym {
+            specifier.imported = Some(ModuleExportName::Ident(imported));
+        }
+    }
+
+    // `export { a }` becomes `export { b as a }`, the exported name stays
+    fn visit_mut_export_named_specifier(&mut self, specifier: &mut ExportNamedSpecifier) {
+        let exported = specifier.orig.clone();
+        specifier.visit_mut_children_with(self);
+        if specifier.exported.is_none() && specifier.orig != exported {
+            specifier.exported = Some(exported);
+        }
+    }
+
+    fn visit_mut_prop(&mut self, prop: &mut Prop) {
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(name) = self.new_name(ident) {
+                let key = PropName::Ident(IdentName::new(ident.sym.clone(), ident.span));
+                let value = Ident::new(name, ident.span, ident.ctxt);
+                *prop = Prop::KeyValue(KeyValueProp {
+                    key,
+                    value: Box::new(Expr::Ident(value)),
+                });
+                return;
+            }
+        }
+        prop.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_pat_prop(&mut self, prop: &mut ObjectPatProp) {
+        let renamed_key = match prop {
+            ObjectPatProp::Assign(assign) => self
+                .new_name(&assign.key.id)
+                .map(|_| assign.key.sym.clone()),
+            _ => None,
+        };
+        prop.visit_mut_children_with(self);
+
+        // The binding of `{ key }` and `{ key = default }` is renamed, the key is not
+        if let (Some(original), ObjectPatProp::Assign(assign)) = (renamed_key, &mut *prop) {
+            let binding = Pat::Ident(assign.key.clone());
+            let value = match assign.value.take() {
+                Some(default) => Pat::Assign(AssignPat {
+                    span: assign.span,
+                    left: Box::new(binding),
+                    right: default,
+                }),
+                None => binding,
+            };
+            *prop = ObjectPatProp::KeyValue(KeyValuePatProp {
+                key: PropName::Ident(IdentName::new(original, assign.key.span)),
+                value: Box::new(value),
+            });
+        }
+    }
+}
@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Third-party SDK Detection
+//!
+//! This module detects the initialization calls of common third-party SDKs (Sentry, PostHog,
+//! Google Analytics, Stripe, ...) in JavaScript code, so installers can check whether an SDK
+//! is already set up before offering to add their own initialization.
+
+use crate::parsers::javascript::helpers::*;
+use serde_json::{json, Map, Value};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+/// The way an SDK initialization is written in the source.
+enum SdkCallee {
+    /// A method call on a global object, e.g. `Sentry.init(...)`.
+    Member(&'static str, &'static str),
+    /// A plain function call, e.g. `Stripe(...)`.
+    Function(&'static str),
+    /// A plain function call whose first argument is a fixed string, e.g. `gtag("config", ...)`.
+    FunctionWithFirstArg(&'static str, &'static str),
+}
+
+const KNOWN_SDKS: &[(&str, SdkCallee)] = &[
+    ("sentry", SdkCallee::Member("Sentry", "init")),
+    ("posthog", SdkCallee::Member("posthog", "init")),
+    ("gtag", SdkCallee::FunctionWithFirstArg("gtag", "config")),
+    ("stripe", SdkCallee::Function("Stripe")),
+    ("stripe", SdkCallee::Function("loadStripe")),
+    ("mixpanel", SdkCallee::Member("mixpanel", "init")),
+    ("amplitude", SdkCallee::Member("amplitude", "init")),
+    ("logrocket", SdkCallee::Member("LogRocket", "init")),
+    ("datadog", SdkCallee::Member("datadogRum", "init")),
+    ("bugsnag", SdkCallee::Member("Bugsnag", "start")),
+];
+
+#[derive(Debug, Default)]
+struct SdkVisitor {
+    found: Vec<Value>,
+}
+
+impl SdkVisitor {
+    fn match_callee(call: &CallExpr) -> Option<(&'static str, String)> {
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+
+        KNOWN_SDKS.iter().find_map(|(sdk, known)| {
+            let matched = match (known, &**callee) {
+                (SdkCallee::Member(object, method), Expr::Member(member)) => {
+                    matches!(&*member.obj, Expr::Ident(ident) if ident.sym == *object)
+                        && matches!(&member.prop, MemberProp::Ident(prop) if prop.sym == *method)
+                }
+                (SdkCallee::Function(name), Expr::Ident(ident)) => ident.sym == *name,
+                (SdkCallee::FunctionWithFirstArg(name, first), Expr::Ident(ident)) => {
+                    ident.sym == *name
+                        && matches!(
+                            call.args.first().map(|arg| &*arg.expr),
+                            Some(Expr::Lit(Lit::Str(s))) if s.value == *first
+                        )
+                }
+                _ => false,
+            };
+
+            matched.then(|| {
+                let callee = match known {
+                    SdkCallee::Member(object, method) => format!("{}.{}", object, method),
+                    SdkCallee::Function(name) | SdkCallee::FunctionWithFirstArg(name, _) => {
+                        name.to_string()
+                    }
+                };
+                (*sdk, callee)
+            })
+        })
+    }
+}
+
+impl VisitMut for SdkVisitor {
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        if let Some((sdk, callee)) = Self::match_callee(call) {
+            let mut is_static = true;
+            let args: Vec<Value> = call
+                .args
+                .iter()
+                .map(|arg| {
+                    let value = literal_to_json(&arg.expr, &mut is_static);
+                    if arg.spread.is_some() {
+                        is_static = false;
+                    }
+                    value
+                })
+                .collect();
+
+            self.found.push(json!({
+                "sdk": sdk,
+                "callee": callee,
+                "args": args,
+                "static": is_static
+            }));
+        }
+
+        call.visit_mut_children_with(self)
+    }
+}
+
+/// Converts a statically known expression into JSON.
+///
+/// Values that can not be known without running the code (identifiers, calls, ...) become
+/// `null`, and `is_static` is set to `false`.
+fn literal_to_json(expr: &Expr, is_static: &mut bool) -> Value {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => json!(s.value.to_string()),
+        Expr::Lit(Lit::Num(n)) => json!(n.value),
+        Expr::Lit(Lit::Bool(b)) => json!(b.value),
+        Expr::Lit(Lit::Null(_)) => Value::Null,
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => json!(tpl
+            .quasis
+            .iter()
+            .map(|q| q.raw.to_string())
+            .collect::<String>()),
+        Expr::Unary(UnaryExpr {
+            op: UnaryOp::Minus,
+            arg,
+            ..
+        }) => match &**arg {
+            Expr::Lit(Lit::Num(n)) => json!(-n.value),
+            _ => {
+                *is_static = false;
+                Value::Null
+            }
+        },
+        Expr::Paren(paren) => literal_to_json(&paren.expr, is_static),
+        Expr::Array(array) => Value::Array(
+            array
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    Some(ExprOrSpread { spread: None, expr }) => literal_to_json(expr, is_static),
+                    _ => {
+                        *is_static = false;
+                        Value::Null
+                    }
+                })
+                .collect(),
+        ),
+        Expr::Object(object) => {
+            let mut map = Map::new();
+            for prop in &object.props {
+                match prop {
+                    PropOrSpread::Prop(prop) => match &**prop {
+                        Prop::KeyValue(KeyValueProp { key, value }) => match prop_name_key(key) {
+                            Some(key) => {
+                                map.insert(key, literal_to_json(value, is_static));
+                            }
+                            None => *is_static = false,
+                        },
+                        Prop::Shorthand(ident) => {
+                            *is_static = false;
+                            map.insert(ident.sym.to_string(), Value::Null);
+                        }
+                        _ => *is_static = false,
+                    },
+                    PropOrSpread::Spread(_) => *is_static = false,
+                }
+            }
+            Value::Object(map)
+        }
+        _ => {
+            *is_static = false;
+            Value::Null
+        }
+    }
+}
+
+fn prop_name_key(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        PropName::Num(n) => Some(n.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Detects the initialization calls of known third-party SDKs.
+///
+/// Every detected call is reported with the SDK name, the callee as written in the source
+/// and its arguments converted to JSON where they are statically known.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+///
+/// # Returns
+/// * `Ok(String)` - A JSON array, e.g.
+///   `[{"sdk": "sentry", "callee": "Sentry.init", "args": [{"dsn": "..."}], "static": true}]`.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let js_code = r#"Sentry.init({ dsn: "https://key@sentry.io/1" });"#;
+/// let result = find_sdk_initializations_from_ast(js_code).unwrap();
+/// assert!(result.contains("\"sdk\":\"sentry\""));
+/// ```
+pub fn find_sdk_initializations_from_ast(file_content: &str) -> Result<String, String> {
+    let (mut module, _comments, _cm) = parse(file_content)?;
+    let mut visitor = SdkVisitor::default();
+    module.visit_mut_with(&mut visitor);
+
+    serde_json::to_string(&visitor.found).map_err(|e| format!("Failed to serialize JSON: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_sdk_initializations_from_ast() {
+        let code = r#"
+        import * as Sentry from "@sentry/browser";
+        import posthog from "posthog-js";
+
+        Sentry.init({
+          dsn: "https://key@sentry.io/1",
+          tracesSampleRate: 0.5,
+          integrations: [Sentry.browserTracingIntegration()],
+        });
+
+        posthog.init("phc_key", { api_host: "https://eu.posthog.com" });
+        gtag("js", new Date());
+        gtag("config", "G-XXXX");
+
+        document.addEventListener("DOMContentLoaded", () => {
+          const stripe = Stripe(window.stripeKey);
+        });
+        "#;
+
+        let result = find_sdk_initializations_from_ast(code).unwrap();
+        let found: Vec<Value> = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[0]["sdk"], "sentry");
+        assert_eq!(found[0]["args"][0]["dsn"], "https://key@sentry.io/1");
+        assert_eq!(found[0]["args"][0]["tracesSampleRate"], 0.5);
+        assert_eq!(found[0]["static"], false);
+
+        assert_eq!(found[1]["sdk"], "posthog");
+        assert_eq!(found[1]["args"][1]["api_host"], "https://eu.posthog.com");
+        assert_eq!(found[1]["static"], true);
+
+        assert_eq!(found[2]["sdk"], "gtag");
+        assert_eq!(found[2]["args"][1], "G-XXXX");
+
+        assert_eq!(found[3]["sdk"], "stripe");
+        assert_eq!(found[3]["args"][0], Value::Null);
+    }
+
+    #[test]
+    fn test_find_sdk_initializations_from_ast_without_sdks() {
+        let code = r#"let liveSocket = new LiveSocket("/live", Socket, {});"#;
+        assert_eq!(find_sdk_initializations_from_ast(code).unwrap(), "[]");
+        assert!(find_sdk_initializations_from_ast("let = ;").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Service worker registration for PWA installers, i.e. the feature-detected block:
+//!
+//! ```javascript
+//! if ("serviceWorker" in navigator) {
+//!     navigator.serviceWorker.register("/sw.js", { scope: "/" });
+//! }
+//! ```
+//!
+//! A registration is identified by its script URL, wherever it is, e.g. inside a `load`
+//! listener or followed by `.then(...)`.
+
+use crate::parsers::javascript::helpers::*;
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+fn is_navigator_service_worker(expr: &Expr) -> bool {
+    matches!(expr, Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(prop), .. })
+        if prop.sym == "serviceWorker"
+            && matches!(&**obj, Expr::Ident(obj) if obj.sym == "navigator"))
+}
+
+/// The `navigator.serviceWorker.register(...)` call of an expression, looking through
+/// `await` and the `.then(...)`/`.catch(...)` chained on it.
+fn register_call(expr: &Expr) -> Option<&CallExpr> {
+    match expr {
+        Expr::Await(AwaitExpr { arg, .. }) => register_call(arg),
+        Expr::Call(call) => {
+            let Callee::Expr(callee) = &call.callee else {
+                return None;
+            };
+            let Expr::Member(MemberExpr { obj, prop, .. }) = &**callee else {
+                return None;
+            };
+            match prop {
+                MemberProp::Ident(prop)
+                    if prop.sym == "register" && is_navigator_service_worker(obj) =>
+                {
+                    Some(call)
+                }
+                _ => register_call(obj),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The script URL a register call registers, when it is a string.
+fn script_url(call: &CallExpr) -> Option<String> {
+    match call.args.first().map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(url))) => Some(url.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a statement registers the given script, or any when `path` is `None`.
+fn is_registration(stmt: &Stmt, path: Option<&str>) -> bool {
+    let Stmt::Expr(ExprStmt { expr, .. }) = stmt else {
+        return false;
+    };
+    register_call(expr)
+        .is_some_and(|call| path.is_none_or(|path| script_url(call) == Some(path.into())))
+}
+
+/// Whether a statement is the `if ("serviceWorker" in navigator)` feature detection.
+fn is_feature_detection(stmt: &Stmt) -> bool {
+    let Stmt::If(IfStmt { test, .. }) = stmt else {
+        return false;
+    };
+    matches!(&**test, Expr::Bin(BinExpr { op: BinaryOp::In, left, right, .. })
+        if matches!(&**left, Expr::Lit(Lit::Str(name)) if name.value == *"serviceWorker")
+            && matches!(&**right, Expr::Ident(navigator) if navigator.sym == "navigator"))
+}
+
+#[derive(Default)]
+struct RegistrationFinder {
+    paths: Vec<String>,
+}
+
+impl Visit for RegistrationFinder {
+    fn visit_expr_stmt(&mut self, stmt: &ExprStmt) {
+        if let Some(path) = register_call(&stmt.expr).and_then(script_url) {
+            self.paths.push(path);
+        }
+        stmt.visit_children_with(self);
+    }
+}
+
+/// Replaces the options of the registrations of a script.
+struct OptionsUpdater<'a> {
+    path: &'a str,
+    options: &'a Expr,
+}
+
+impl VisitMut for OptionsUpdater<'_> {
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.visit_mut_children_with(self);
+
+        let is_target = matches!(&call.callee, Callee::Expr(callee)
+            if matches!(&**callee, Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(prop), .. })
+                if prop.sym == "register" && is_navigator_service_worker(obj)))
+            && script_url(call).as_deref() == Some(self.path);
+        if is_target {
+            call.args.truncate(1);
+            call.args.push(ExprOrSpread {
+                spread: None,
+                expr: Box::new(self.options.clone()),
+            });
+        }
+    }
+}
+
+/// Removes the registrations of a script, with the feature detection left empty.
+struct RegistrationRemover<'a> {
+    path: Option<&'a str>,
+}
+
+impl RegistrationRemover<'_> {
+    fn keep(&self, stmt: &Stmt) -> bool {
+        if is_registration(stmt, self.path) {
+            return false;
+        }
+        match stmt {
+            Stmt::If(IfStmt {
+                cons, alt: None, ..
+            }) if is_feature_detection(stmt) => {
+                !matches!(&**cons, Stmt::Block(BlockStmt { stmts, .. }) if stmts.is_empty())
+                    && !matches!(&**cons, Stmt::Empty(_))
+            }
+            _ => true,
+        }
+    }
+}
+
+impl VisitMut for RegistrationRemover<'_> {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        items.retain(|item| match item {
+            ModuleItem::Stmt(stmt) => self.keep(stmt),
+            _ => true,
+        });
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        stmts.retain(|stmt| self.keep(stmt));
+    }
+
+    fn visit_mut_if_stmt(&mut self, stmt: &mut IfStmt) {
+        stmt.visit_mut_children_with(self);
+        // `if (...) navigator.serviceWorker.register(...);` without a block
+        if is_registration(&stmt.cons, self.path) {
+            *stmt.cons = Stmt::Empty(EmptyStmt { span: DUMMY_SP });
+        }
+    }
+}
+
+/// Ensures a service worker is registered, inserting the feature-detected registration
+/// block at the end of the module when the script is not registered yet.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `path` - The URL of the service worker script, e.g. `"/sw.js"`.
+/// * `options` - The options of the registration as a JavaScript object, e.g.
+///   `{ scope: "/" }`. The options of an existing registration of the script are replaced
+///   by them, and kept when `None`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code, or the same program when there is nothing to change.
+/// * `Err(String)` - If the source or the options can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let result = ensure_service_worker_registration_to_ast("", "/sw.js", None).unwrap();
+/// assert!(result.contains("navigator.serviceWorker.register(\"/sw.js\");"));
+/// ```
+pub fn ensure_service_worker_registration_to_ast(
+    file_content: &str,
+    path: &str,
+    options: Option<&str>,
+) -> Result<String, String> {
+    let (mut module, comments, cm) = parse(file_content)?;
+    let original = module.clone();
+    let options = options.map(parse_expr).transpose()?;
+
+    let mut finder = RegistrationFinder::default();
+    module.visit_with(&mut finder);
+
+    if finder.paths.iter().any(|registered| registered == path) {
+        if let Some(options) = &options {
+            module.visit_mut_with(&mut OptionsUpdater { path, options });
+        }
+        return Ok(code_gen_edit(
+            file_content,
+            &original,
+            &mut module,
+            comments,
+            cm,
+        ));
+    }
+
+    let url = serde_json::to_string(path).map_err(|error| error.to_string())?;
+    let (mut block, _comments, _cm) = parse(&format!(
+        "if (\"serviceWorker\" in navigator) {{ navigator.serviceWorker.register({}); }}",
+        url
+    ))?;
+    block.visit_mut_with(&mut DropSpan);
+    if let Some(options) = &options {
+        block.visit_mut_with(&mut OptionsUpdater { path, options });
+    }
+
+    module.body.extend(block.body);
+    Ok(code_gen_edit(
+        file_content,
+        &original,
+        &mut module,
+        comments,
+        cm,
+    ))
+}
+
+/// Removes the registrations of a service worker, the counterpart of
+/// [`ensure_service_worker_registration_to_ast`].
+///
+/// A `if ("serviceWorker" in navigator)` block left empty is removed with them, while a
+/// block with other statements is kept.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `path` - The URL of the script whose registrations are removed, or `None` for all.
+///
+/// # Returns
+/// * `Ok(String)` - The updated code.
+/// * `Err(String)` - If the source can not be parsed.
+pub fn remove_service_worker_registration_from_ast(
+    file_content: &str,
+    path: Option<&str>,
+) -> Result<String, String> {
+    code_gen_from_ast_vist(file_content, RegistrationRemover { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP: &str = r#"import { Socket } from "phoenix";
+let liveSocket = new LiveSocket("/live", Socket, {});
+liveSocket.connect();
+"#;
+
+    #[test]
+    fn test_ensure_service_worker_registration() {
+        let once = ensure_service_worker_registration_to_ast(APP, "/sw.js", None).unwrap();
+        assert_eq!(
+            once,
+            format!(
+                "{}if (\"serviceWorker\" in navigator) {{\n    navigator.serviceWorker.register(\"/sw.js\");\n}}\n",
+                APP
+            )
+        );
+        let twice = ensure_service_worker_registration_to_ast(&once, "/sw.js", None).unwrap();
+        assert_eq!(once, twice);
+
+        // The options of an existing registration are replaced
+        let code = r#"window.addEventListener("load", () => {
+    navigator.serviceWorker.register("/sw.js").then((registration) => registration.update());
+});
+"#;
+        let result =
+            ensure_service_worker_registration_to_ast(code, "/sw.js", Some("{ scope: \"/\" }"))
+                .unwrap();
+        assert!(result.contains("register(\"/sw.js\", {\n        scope: \"/\"\n    }).then("));
+        assert!(!result.contains("in navigator"));
+
+        assert!(ensure_service_worker_registration_to_ast("let = ;", "/sw.js", None).is_err());
+        assert!(ensure_service_worker_registration_to_ast(APP, "/sw.js", Some("{")).is_err());
+    }
+
+    #[test]
+    fn test_remove_service_worker_registration() {
+        let code = ensure_service_worker_registration_to_ast(APP, "/sw.js", None).unwrap();
+        let result = remove_service_worker_registration_from_ast(&code, Some("/sw.js")).unwrap();
+        assert_eq!(result, APP);
+
+        let code = r#"if ("serviceWorker" in navigator) {
+    navigator.serviceWorker.register("/sw.js");
+    navigator.serviceWorker.register("/push.js").catch(console.error);
+    console.log("registered");
+}
+"#;
+        let result = remove_service_worker_registration_from_ast(code, Some("/sw.js")).unwrap();
+        assert!(!result.contains("/sw.js"));
+        assert!(result.contains("/push.js"));
+
+        let result = remove_service_worker_registration_from_ast(code, None).unwrap();
+        assert_eq!(
+            result,
+            "if (\"serviceWorker\" in navigator) {\n    console.log(\"registered\");\n}\n"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_edit;
+use crate::parsers::javascript::service_worker::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif]
+pub fn ensure_service_worker_registration_nif(
+    env: Env,
+    file_content: String,
+    path: String,
+    options: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::ensure_service_worker_registration_nif();
+        let result =
+            ensure_service_worker_registration_to_ast(&file_content, &path, options.as_deref());
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn remove_service_worker_registration_nif(
+    env: Env,
+    file_content: String,
+    path: Option<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::remove_service_worker_registration_nif();
+        let result = remove_service_worker_registration_from_ast(&file_content, path.as_deref());
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
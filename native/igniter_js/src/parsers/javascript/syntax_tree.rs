@@ -0,0 +1,500 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Navigable Syntax Trees
+//!
+//! A `SyntaxTree` keeps a source with its parsed module, like a
+//! [`Document`](super::document::Document), and lets the caller walk it node by node: the
+//! children of a node, the source of a node, and the replacement of the source of a node.
+//!
+//! A node is referred to by a [`NodeHandle`], its kind and byte range. The handles belong to
+//! a version of the tree: a replacement makes a new version, and the handles of the previous
+//! ones are stale, since the ranges after the replaced node moved.
+//!
+//! The nodes are the statements, module declarations, variable declarators, expressions,
+//! patterns, object properties and class members. Anything else, e.g. the specifiers of an
+//! import, is part of the source of its node.
+
+use std::sync::Mutex;
+
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use swc_common::{Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::parsers::javascript::helpers::{parse, SpanOffsets};
+
+/// The kind of the root node.
+const MODULE_KIND: &str = "Module";
+
+/// A node of a [`SyntaxTree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct NodeHandle {
+    /// The SWC type of the node, e.g. `ImportDecl`, `CallExpr` or `KeyValueProp`.
+    pub kind: String,
+    /// The byte offsets of the node in the source.
+    pub start: usize,
+    pub end: usize,
+    /// The version of the tree the handle belongs to.
+    pub version: usize,
+}
+
+/// The SWC type of a node, like its struct is named.
+trait NodeKind: Spanned {
+    fn kind(&self) -> &'static str;
+}
+
+impl NodeKind for Stmt {
+    fn kind(&self) -> &'static str {
+        match self {
+            Stmt::Block(_) => "BlockStmt",
+            Stmt::Empty(_) => "EmptyStmt",
+            Stmt::Debugger(_) => "DebuggerStmt",
+            Stmt::With(_) => "WithStmt",
+            Stmt::Return(_) => "ReturnStmt",
+            Stmt::Labeled(_) => "LabeledStmt",
+            Stmt::Break(_) => "BreakStmt",
+            Stmt::Continue(_) => "ContinueStmt",
+            Stmt::If(_) => "IfStmt",
+            Stmt::Switch(_) => "SwitchStmt",
+            Stmt::Throw(_) => "ThrowStmt",
+            Stmt::Try(_) => "TryStmt",
+            Stmt::While(_) => "WhileStmt",
+            Stmt::DoWhile(_) => "DoWhileStmt",
+            Stmt::For(_) => "ForStmt",
+            Stmt::ForIn(_) => "ForInStmt",
+            Stmt::ForOf(_) => "ForOfStmt",
+            Stmt::Expr(_) => "ExprStmt",
+            Stmt::Decl(Decl::Class(_)) => "ClassDecl",
+            Stmt::Decl(Decl::Fn(_)) => "FnDecl",
+            Stmt::Decl(Decl::Var(_)) => "VarDecl",
+            Stmt::Decl(Decl::Using(_)) => "UsingDecl",
+            Stmt::Decl(Decl::TsInterface(_)) => "TsInterfaceDecl",
+            Stmt::Decl(Decl::TsTypeAlias(_)) => "TsTypeAliasDecl",
+            Stmt::Decl(Decl::TsEnum(_)) => "TsEnumDecl",
+            Stmt::Decl(Decl::TsModule(_)) => "TsModuleDecl",
+        }
+    }
+}
+
+impl NodeKind for ModuleDecl {
+    fn kind(&self) -> &'static str {
+        match self {
+            ModuleDecl::Import(_) => "ImportDecl",
+            ModuleDecl::ExportDecl(_) => "ExportDecl",
+            ModuleDecl::ExportNamed(_) => "NamedExport",
+            ModuleDecl::ExportDefaultDecl(_) => "ExportDefaultDecl",
+            ModuleDecl::ExportDefaultExpr(_) => "ExportDefaultExpr",
+            ModuleDecl::ExportAll(_) => "ExportAll",
+            ModuleDecl::TsImportEquals(_) => "TsImportEqualsDecl",
+            ModuleDecl::TsExportAssignment(_) => "TsExportAssignment",
+            ModuleDecl::TsNamespaceExport(_) => "TsNamespaceExportDecl",
+        }
+    }
+}
+
+impl NodeKind for VarDeclarator {
+    fn kind(&self) -> &'static str {
+        "VarDeclarator"
+    }
+}
+
+impl NodeKind for Expr {
+    fn kind(&self) -> &'static str {
+        match self {
+            Expr::This(_) => "ThisExpr",
+            Expr::Array(_) => "ArrayLit",
+            Expr::Object(_) => "ObjectLit",
+            Expr::Fn(_) => "FnExpr",
+            Expr::Unary(_) => "UnaryExpr",
+            Expr::Update(_) => "UpdateExpr",
+            Expr::Bin(_) => "BinExpr",
+            Expr::Assign(_) => "AssignExpr",
+            Expr::Member(_) => "MemberExpr",
+            Expr::SuperProp(_) => "SuperPropExpr",
+            Expr::Cond(_) => "CondExpr",
+            Expr::Call(_) => "CallExpr",
+            Expr::New(_) => "NewExpr",
+            Expr::Seq(_) => "SeqExpr",
+            Expr::Ident(_) => "Ident",
+            Expr::Lit(Lit::Str(_)) => "Str",
+            Expr::Lit(Lit::Bool(_)) => "Bool",
+            Expr::Lit(Lit::Null(_)) => "Null",
+            Expr::Lit(Lit::Num(_)) => "Number",
+            Expr::Lit(Lit::BigInt(_)) => "BigInt",
+            Expr::Lit(Lit::Regex(_)) => "Regex",
+            Expr::Lit(Lit::JSXText(_)) => "JSXText",
+            Expr::Tpl(_) => "Tpl",
+            Expr::TaggedTpl(_) => "TaggedTpl",
+            Expr::Arrow(_) => "ArrowExpr",
+            Expr::Class(_) => "ClassExpr",
+            Expr::Yield(_) => "YieldExpr",
+            Expr::MetaProp(_) => "MetaPropExpr",
+            Expr::Await(_) => "AwaitExpr",
+            Expr::Paren(_) => "ParenExpr",
+            Expr::JSXMember(_) => "JSXMemberExpr",
+            Expr::JSXNamespacedName(_) => "JSXNamespacedName",
+            Expr::JSXEmpty(_) => "JSXEmptyExpr",
+            Expr::JSXElement(_) => "JSXElement",
+            Expr::JSXFragment(_) => "JSXFragment",
+            Expr::TsTypeAssertion(_) => "TsTypeAssertion",
+            Expr::TsConstAssertion(_) => "TsConstAssertion",
+            Expr::TsNonNull(_) => "TsNonNullExpr",
+            Expr::TsAs(_) => "TsAsExpr",
+            Expr::TsInstantiation(_) => "TsInstantiation",
+            Expr::TsSatisfies(_) => "TsSatisfiesExpr",
+            Expr::PrivateName(_) => "PrivateName",
+            Expr::OptChain(_) => "OptChainExpr",
+            Expr::Invalid(_) => "Invalid",
+        }
+    }
+}
+
+impl NodeKind for Pat {
+    fn kind(&self) -> &'static str {
+        match self {
+            Pat::Ident(_) => "BindingIdent",
+            Pat::Array(_) => "ArrayPat",
+            Pat::Rest(_) => "RestPat",
+            Pat::Object(_) => "ObjectPat",
+            Pat::Assign(_) => "AssignPat",
+            Pat::Invalid(_) => "Invalid",
+            Pat::Expr(expr) => expr.kind(),
+        }
+    }
+}
+
+impl NodeKind for Prop {
+    fn kind(&self) -> &'static str {
+        match self {
+            Prop::Shorthand(_) => "ShorthandProp",
+            Prop::KeyValue(_) => "KeyValueProp",
+            Prop::Assign(_) => "AssignProp",
+            Prop::Getter(_) => "GetterProp",
+            Prop::Setter(_) => "SetterProp",
+            Prop::Method(_) => "MethodProp",
+        }
+    }
+}
+
+impl NodeKind for SpreadElement {
+    fn kind(&self) -> &'static str {
+        "SpreadElement"
+    }
+}
+
+impl NodeKind for ClassMember {
+    fn kind(&self) -> &'static str {
+        match self {
+            ClassMember::Constructor(_) => "Constructor",
+            ClassMember::Method(_) => "ClassMethod",
+            ClassMember::PrivateMethod(_) => "PrivateMethod",
+            ClassMember::ClassProp(_) => "ClassProp",
+            ClassMember::PrivateProp(_) => "PrivateProp",
+            ClassMember::TsIndexSignature(_) => "TsIndexSignature",
+            ClassMember::Empty(_) => "EmptyStmt",
+            ClassMember::StaticBlock(_) => "StaticBlock",
+            ClassMember::AutoAccessor(_) => "AutoAccessor",
+        }
+    }
+}
+
+/// Converts spans to the byte offsets of the source.
+#[derive(Debug, Clone, Copy)]
+struct Offsets {
+    spans: SpanOffsets,
+    version: usize,
+}
+
+impl Offsets {
+    fn range(&self, span: Span) -> (usize, usize) {
+        self.spans.range(span)
+    }
+
+    fn handle(&self, node: &impl NodeKind) -> NodeHandle {
+        let (start, end) = self.range(node.span());
+        NodeHandle {
+            kind: node.kind().to_string(),
+            start,
+            end,
+            version: self.version,
+        }
+    }
+}
+
+/// Collects the nodes directly below the node it visits the children of.
+struct ChildCollector {
+    offsets: Offsets,
+    children: Vec<NodeHandle>,
+}
+
+/// Finds the node of a handle and collects its children.
+struct NodeFinder<'a> {
+    offsets: Offsets,
+    handle: &'a NodeHandle,
+    children: Option<Vec<NodeHandle>>,
+}
+
+impl NodeFinder<'_> {
+    fn visit_node<N: NodeKind + VisitWith<Self> + VisitWith<ChildCollector>>(&mut self, node: &N) {
+        if self.children.is_some() {
+            return;
+        }
+        if self.offsets.handle(node) != *self.handle {
+            node.visit_children_with(self);
+            return;
+        }
+
+        let mut collector = ChildCollector {
+            offsets: self.offsets,
+            children: vec![],
+        };
+        node.visit_children_with(&mut collector);
+        self.children = Some(collector.children);
+    }
+}
+
+macro_rules! visit_nodes {
+    ($($visit:ident: $node:ty),* $(,)?) => {
+        impl Visit for ChildCollector {
+            $(fn $visit(&mut self, node: &$node) {
+                self.children.push(self.offsets.handle(node));
+            })*
+        }
+
+        impl Visit for NodeFinder<'_> {
+            $(fn $visit(&mut self, node: &$node) {
+                self.visit_node(node);
+            })*
+        }
+    };
+}
+
+visit_nodes!(
+    visit_stmt: Stmt,
+    visit_module_decl: ModuleDecl,
+    visit_var_declarator: VarDeclarator,
+    visit_expr: Expr,
+    visit_pat: Pat,
+    visit_prop: Prop,
+    visit_spread_element: SpreadElement,
+    visit_class_member: ClassMember,
+);
+
+#[derive(Debug)]
+struct TreeState {
+    code: String,
+    module: Module,
+    spans: SpanOffsets,
+    version: usize,
+}
+
+impl TreeState {
+    fn offsets(&self) -> Offsets {
+        Offsets {
+            spans: self.spans,
+            version: self.version,
+        }
+    }
+
+    fn root(&self) -> NodeHandle {
+        let (start, end) = self.offsets().range(self.module.span);
+        NodeHandle {
+            kind: MODULE_KIND.to_string(),
+            start,
+            end,
+            version: self.version,
+        }
+    }
+
+    /// The children of the node of a handle, failing on a handle of another version or of
+    /// no node.
+    fn children(&self, handle: &NodeHandle) -> Result<Vec<NodeHandle>, String> {
+        if handle.version != self.version {
+            return Err(format!(
+                "The handle belongs to version {} of the tree, which is now at version {}",
+                handle.version, self.version
+            ));
+        }
+
+        if *handle == self.root() {
+            let mut collector = ChildCollector {
+                offsets: self.offsets(),
+                children: vec![],
+            };
+            self.module.visit_children_with(&mut collector);
+            return Ok(collector.children);
+        }
+
+        let mut finder = NodeFinder {
+            offsets: self.offsets(),
+            handle,
+            children: None,
+        };
+        self.module.visit_with(&mut finder);
+        finder.children.ok_or(format!(
+            "No {} was found at {}..{}",
+            handle.kind, handle.start, handle.end
+        ))
+    }
+}
+
+fn parse_state(code: String, version: usize) -> Result<TreeState, String> {
+    let (module, _comments, cm) = parse(&code)?;
+    let spans = SpanOffsets::new(&cm);
+
+    Ok(TreeState {
+        code,
+        module,
+        spans,
+        version,
+    })
+}
+
+/// A JavaScript source and its parsed module, walked node by node.
+#[derive(Debug)]
+pub struct SyntaxTree {
+    state: Mutex<TreeState>,
+}
+
+impl SyntaxTree {
+    /// Parses the source, failing early when it is not valid JavaScript.
+    pub fn parse(code: &str) -> Result<SyntaxTree, String> {
+        Ok(SyntaxTree {
+            state: Mutex::new(parse_state(code.to_string(), 0)?),
+        })
+    }
+
+    fn with_state<T>(
+        &self,
+        f: impl FnOnce(&mut TreeState) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut state = self.state.lock().map_err(|_| "Syntax tree lock poisoned")?;
+        f(&mut state)
+    }
+
+    /// The handle of the module, the root of the tree.
+    pub fn root(&self) -> Result<NodeHandle, String> {
+        self.with_state(|state| Ok(state.root()))
+    }
+
+    /// The nodes directly below a node, in source order, the top-level statements and
+    /// declarations for the root.
+    pub fn children(&self, handle: &NodeHandle) -> Result<Vec<NodeHandle>, String> {
+        self.with_state(|state| state.children(handle))
+    }
+
+    /// The source of a node, as it is written.
+    pub fn source(&self, handle: &NodeHandle) -> Result<String, String> {
+        self.with_state(|state| {
+            // Checks that the handle is of a node of this version
+            state.children(handle)?;
+            Ok(state.code[handle.start..handle.end].to_string())
+        })
+    }
+
+    /// Replaces the source of a node, leaving the rest of the source as it is written, and
+    /// returns the root of the new version of the tree. The tree is left as it was when the
+    /// new source can not be parsed.
+    pub fn replace(&self, handle: &NodeHandle, code: &str) -> Result<NodeHandle, String> {
+        self.with_state(|state| {
+            state.children(handle)?;
+            let updated = format!(
+                "{}{}{}",
+                &state.code[..handle.start],
+                code,
+                &state.code[handle.end..]
+            );
+            *state = parse_state(updated, state.version + 1)?;
+            Ok(state.root())
+        })
+    }
+
+    /// The current source of the tree.
+    pub fn code(&self) -> Result<String, String> {
+        self.with_state(|state| Ok(state.code.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(handles: &[NodeHandle]) -> Vec<&str> {
+        handles.iter().map(|handle| handle.kind.as_str()).collect()
+    }
+
+    #[test]
+    fn test_syntax_tree() {
+        let code = r#"import { Socket } from "phoenix";
+// The hooks
+let Hooks = { Copy: CopyHook, ...Other };
+let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+"#;
+
+        let tree = SyntaxTree::parse(code).unwrap();
+        let root = tree.root().unwrap();
+        let items = tree.children(&root).unwrap();
+        assert_eq!(kinds(&items), ["ImportDecl", "VarDecl", "VarDecl"]);
+        assert_eq!(
+            tree.source(&items[0]).unwrap(),
+            "import { Socket } from \"phoenix\";"
+        );
+
+        let declarators = tree.children(&items[1]).unwrap();
+        assert_eq!(kinds(&declarators), ["VarDeclarator"]);
+        let parts = tree.children(&declarators[0]).unwrap();
+        assert_eq!(kinds(&parts), ["BindingIdent", "ObjectLit"]);
+        let props = tree.children(&parts[1]).unwrap();
+        assert_eq!(kinds(&props), ["KeyValueProp", "SpreadElement"]);
+        assert_eq!(tree.source(&props[0]).unwrap(), "Copy: CopyHook");
+
+        let root = tree.replace(&props[0], "Copy: Clipboard").unwrap();
+        assert_eq!(root.version, 1);
+        assert_eq!(
+            tree.code().unwrap(),
+            code.replace("Copy: CopyHook", "Copy: Clipboard")
+        );
+
+        // The handles of the previous version are stale
+        assert!(tree.children(&items[2]).is_err());
+        let items = tree.children(&root).unwrap();
+        let new_expr = tree
+            .children(&tree.children(&items[2]).unwrap()[0])
+            .unwrap();
+        assert_eq!(kinds(&new_expr), ["BindingIdent", "NewExpr"]);
+        assert_eq!(
+            kinds(&tree.children(&new_expr[1]).unwrap()),
+            ["Ident", "Str", "Ident", "ObjectLit"]
+        );
+
+        // A replacement that does not parse changes nothing
+        assert!(tree.replace(&items[0], "import {").is_err());
+        assert_eq!(tree.root().unwrap().version, 1);
+
+        let missing = NodeHandle {
+            kind: "CallExpr".into(),
+            ..items[0].clone()
+        };
+        assert!(tree.source(&missing).is_err());
+    }
+
+    #[test]
+    fn test_syntax_tree_with_multibyte_content() {
+        let code = "\u{feff}// 🚀 Réunion\nlet Hooks = { Copy: \"📋\", Share: \"🔗\" };\n";
+
+        let tree = SyntaxTree::parse(code).unwrap();
+        let items = tree.children(&tree.root().unwrap()).unwrap();
+        let declarators = tree.children(&items[0]).unwrap();
+        let parts = tree.children(&declarators[0]).unwrap();
+        let props = tree.children(&parts[1]).unwrap();
+        assert_eq!(tree.source(&props[1]).unwrap(), "Share: \"🔗\"");
+
+        tree.replace(&props[1], "Share: \"📤\"").unwrap();
+        assert_eq!(tree.code().unwrap(), code.replace("🔗", "📤"));
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::syntax_tree::*;
+
+use rustler::{Env, NifResult, ResourceArc, Term};
+
+#[rustler::resource_impl]
+impl rustler::Resource for SyntaxTree {}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn parse_to_resource_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::parse_to_resource_nif();
+
+        match SyntaxTree::parse(&file_content) {
+            Ok(tree) => encode_response(env, atoms::ok(), fn_atom, ResourceArc::new(tree)),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn get_node_children_nif(
+    env: Env,
+    tree: ResourceArc<SyntaxTree>,
+    node: Option<NodeHandle>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::get_node_children_nif();
+
+        let children = match node {
+            Some(node) => tree.children(&node),
+            None => tree.root().and_then(|root| tree.children(&root)),
+        };
+
+        match children {
+            Ok(children) => encode_response(env, atoms::ok(), fn_atom, children),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn get_node_source_nif(
+    env: Env,
+    tree: ResourceArc<SyntaxTree>,
+    node: NodeHandle,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::get_node_source_nif();
+
+        let (status, result) = match tree.source(&node) {
+            Ok(source) => (atoms::ok(), source),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn replace_node_source_nif(
+    env: Env,
+    tree: ResourceArc<SyntaxTree>,
+    node: NodeHandle,
+    code: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::replace_node_source_nif();
+
+        match tree.replace(&node, &code) {
+            Ok(_root) => encode_response(env, atoms::ok(), fn_atom, tree),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn serialize_resource_nif(
+    env: Env,
+    tree: ResourceArc<SyntaxTree>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::serialize_resource_nif();
+
+        let (status, result) = match tree.code() {
+            Ok(code) => (atoms::ok(), code),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
@@ -0,0 +1,1132 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! # Transpilation
+//!
+//! Lowers the syntax added after ES2015 to a target ES version, so the code runs in older
+//! browsers without a Node toolchain:
+//!
+//! | Syntax                                    | Lowered below | Into                          |
+//! |-------------------------------------------|---------------|-------------------------------|
+//! | Class fields                              | ES2022        | Constructor assignments       |
+//! | `static` class fields, `static { ... }`   | ES2022        | Statements after the class    |
+//! | `a ||= b`, `a &&= b`, `a ??= b`           | ES2021        | `a || (a = b)`, ...           |
+//! | `a?.b`, `a?.()`, `a ?? b`                 | ES2020        | `a == null ? void 0 : a.b`... |
+//! | `{ ...a }`                                | ES2018        | `Object.assign({}, a)`        |
+//! | `a ** b`, `a **= b`                       | ES2016        | `Math.pow(a, b)`              |
+//!
+//! Values that must only be evaluated once are stored in `_ref` variables declared at the
+//! top of the enclosing function, or of the function enclosing it for the values of its
+//! parameters. The static fields and blocks of a class declaration run after it, in their
+//! order.
+//!
+//! The syntax that is newer than the target and not lowered is an error rather than left in
+//! the output: private class members, the static members of class expressions, static
+//! fields using `this` or `super` and static blocks using `super`, fields with a computed
+//! key, fields of a derived class whose constructor calls `super()` in a nested statement,
+//! async functions and generators, `for await` loops and object rest patterns. Targets
+//! before ES2015 are rejected since classes, arrow functions and `let` are not lowered.
+
+use crate::parsers::javascript::helpers::{
+    code_gen_from_ast_module, code_gen_with_source_map, es_version, parse, GeneratedCode,
+};
+use std::collections::HashSet;
+use swc_atoms::Atom;
+use swc_common::{
+    comments::SingleThreadedComments, sync::Lrc, util::take::Take, SourceMap, DUMMY_SP,
+};
+use swc_ecma_ast::*;
+use swc_ecma_codegen::Config;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+fn ident(name: &str) -> Ident {
+    Ident::new_no_ctxt(name.into(), DUMMY_SP)
+}
+
+fn paren(expr: Expr) -> Expr {
+    Expr::Paren(ParenExpr {
+        span: DUMMY_SP,
+        expr: Box::new(expr),
+    })
+}
+
+fn assign(left: SimpleAssignTarget, right: Expr) -> Expr {
+    Expr::Assign(AssignExpr {
+        span: DUMMY_SP,
+        op: AssignOp::Assign,
+        left: AssignTarget::Simple(left),
+        right: Box::new(right),
+    })
+}
+
+fn assign_to(temp: &Ident, right: Expr) -> Expr {
+    paren(assign(
+        SimpleAssignTarget::Ident(temp.clone().into()),
+        right,
+    ))
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    })
+}
+
+fn null() -> Expr {
+    Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))
+}
+
+fn void_zero() -> Expr {
+    Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: UnaryOp::Void,
+        arg: Box::new(Expr::Lit(Lit::Num(Number {
+            span: DUMMY_SP,
+            value: 0.0,
+            raw: None,
+        }))),
+    })
+}
+
+fn cond(test: Expr, cons: Expr, alt: Expr) -> Expr {
+    paren(Expr::Cond(CondExpr {
+        span: DUMMY_SP,
+        test: Box::new(test),
+        cons: Box::new(cons),
+        alt: Box::new(alt),
+    }))
+}
+
+fn member(obj: Expr, prop: &str) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(obj),
+        prop: MemberProp::Ident(IdentName::new(prop.into(), DUMMY_SP)),
+    })
+}
+
+fn call(callee: Expr, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        callee: Callee::Expr(Box::new(callee)),
+        args: args.into_iter().map(Into::into).collect(),
+        ..Default::default()
+    })
+}
+
+/// Whether reading the expression twice is the same as reading it once.
+fn is_simple(expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(_) | Expr::This(_) | Expr::Lit(_))
+}
+
+/// The property name of a class field as the property of `this.<name>`.
+fn field_prop(key: PropName) -> MemberProp {
+    match key {
+        PropName::Ident(name) => MemberProp::Ident(name),
+        PropName::Str(s) => MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::Str(s))),
+        }),
+        PropName::Num(n) => MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::Num(n))),
+        }),
+        PropName::BigInt(n) => MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::BigInt(n))),
+        }),
+        PropName::Computed(computed) => MemberProp::Computed(computed),
+    }
+}
+
+/// `<obj>.<key> = <value>;` for a class field.
+fn field_assignment(obj: Expr, prop: ClassProp) -> Stmt {
+    let target = SimpleAssignTarget::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(obj),
+        prop: field_prop(prop.key),
+    });
+    let value = prop.value.map_or_else(void_zero, |value| *value);
+
+    Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr: Box::new(assign(target, value)),
+    })
+}
+
+fn is_super_call(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::Expr(ExprStmt { expr, .. })
+            if matches!(&**expr, Expr::Call(CallExpr { callee: Callee::Super(_), .. }))
+    )
+}
+
+/// Finds the `this` and `super` of a static member, which only refer to the class inside
+/// of it.
+#[derive(Default)]
+struct ThisFinder {
+    this: bool,
+    super_: bool,
+}
+
+impl ThisFinder {
+    fn find<N: VisitWith<Self>>(node: &N) -> Self {
+        let mut finder = Self::default();
+        node.visit_with(&mut finder);
+        finder
+    }
+}
+
+impl Visit for ThisFinder {
+    fn visit_this_expr(&mut self, _this: &ThisExpr) {
+        self.this = true;
+    }
+
+    fn visit_super(&mut self, _super: &Super) {
+        self.super_ = true;
+    }
+
+    // `this` inside a nested function is not the class
+    fn visit_function(&mut self, _function: &Function) {}
+
+    fn visit_constructor(&mut self, _constructor: &Constructor) {}
+}
+
+/// Finds the first syntax newer than the target that is not lowered, see the module
+/// documentation.
+struct Unsupported {
+    target: EsVersion,
+    error: Option<String>,
+    /// Whether the class being visited is a declaration, whose static members are moved out.
+    in_class_decl: bool,
+}
+
+impl Unsupported {
+    fn check(&mut self, below: EsVersion, syntax: &str) {
+        if self.error.is_none() && self.target < below {
+            self.error = Some(format!(
+                "{} can not be transpiled below {}",
+                syntax,
+                format!("{:?}", below).to_lowercase()
+            ));
+        }
+    }
+}
+
+impl Visit for Unsupported {
+    fn visit_private_name(&mut self, _name: &PrivateName) {
+        self.check(EsVersion::Es2022, "Private class members");
+    }
+
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        self.in_class_decl = true;
+        decl.class.visit_with(self);
+    }
+
+    fn visit_class(&mut self, class: &Class) {
+        let in_class_decl = std::mem::take(&mut self.in_class_decl);
+        let fields = class.body.iter().filter_map(|member| match member {
+            ClassMember::ClassProp(prop) => Some(prop),
+            _ => None,
+        });
+        // The key of a field moved out of the class would be computed again on every
+        // construction, or after the class
+        if fields.clone().any(
+            |prop| matches!(&prop.key, PropName::Computed(computed) if !computed.expr.is_lit()),
+        ) {
+            self.check(EsVersion::Es2022, "Class fields with a computed key");
+        }
+        // The fields are initialized right after `super()`, which must be a statement of
+        // the constructor itself
+        let super_nested = class.super_class.is_some()
+            && fields.clone().any(|prop| !prop.is_static)
+            && class.body.iter().any(|member| {
+                matches!(
+                    member,
+                    ClassMember::Constructor(Constructor { body: Some(body), .. })
+                        if !body.stmts.iter().any(is_super_call)
+                )
+            });
+        if super_nested {
+            self.check(
+                EsVersion::Es2022,
+                "Class fields in a constructor calling `super()` within a nested statement",
+            );
+        }
+        for member in &class.body {
+            let finder = match member {
+                ClassMember::ClassProp(prop) if prop.is_static => ThisFinder::find(&prop.value),
+                ClassMember::StaticBlock(block) => {
+                    let mut finder = ThisFinder::find(&block.body);
+                    // `this` is the class in the function the block is lowered into
+                    finder.this = false;
+                    finder
+                }
+                _ => continue,
+            };
+            if !in_class_decl {
+                self.check(
+                    EsVersion::Es2022,
+                    "The static fields and blocks of a class expression",
+                );
+            } else if finder.super_ {
+                self.check(EsVersion::Es2022, "The `super` of a static class member");
+            } else if finder.this {
+                self.check(EsVersion::Es2022, "The `this` of a static class field");
+            }
+        }
+        class.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        match (function.is_async, function.is_generator) {
+            (true, true) => self.check(EsVersion::Es2018, "Async generators"),
+            (true, false) => self.check(EsVersion::Es2017, "Async functions"),
+            _ => {}
+        }
+        function.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        if arrow.is_async {
+            self.check(EsVersion::Es2017, "Async functions");
+        }
+        arrow.visit_children_with(self);
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        if stmt.is_await {
+            self.check(EsVersion::Es2018, "`for await` loops");
+        }
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_object_pat(&mut self, pat: &ObjectPat) {
+        if pat
+            .props
+            .iter()
+            .any(|prop| matches!(prop, ObjectPatProp::Rest(_)))
+        {
+            self.check(EsVersion::Es2018, "Object rest patterns");
+        }
+        pat.visit_children_with(self);
+    }
+}
+
+/// Collects every identifier name, so temporary variables never shadow one.
+#[derive(Default)]
+struct NameCollector {
+    names: HashSet<Atom>,
+}
+
+impl Visit for NameCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.clone());
+    }
+}
+
+struct Lowering {
+    target: EsVersion,
+    names: HashSet<Atom>,
+    counter: usize,
+    /// The temporary variables of every enclosing function, the module first.
+    temps: Vec<Vec<Ident>>,
+}
+
+impl Lowering {
+    fn temp(&mut self) -> Ident {
+        let name = loop {
+            self.counter += 1;
+            let name = match self.counter {
+                1 => "_ref".to_string(),
+                counter => format!("_ref{}", counter),
+            };
+            if !self.names.contains(&Atom::from(name.as_str())) {
+                break name;
+            }
+        };
+        let temp = ident(&name);
+        if let Some(temps) = self.temps.last_mut() {
+            temps.push(temp.clone());
+        }
+        temp
+    }
+
+    fn temp_declaration(temps: Vec<Ident>) -> Stmt {
+        Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            kind: VarDeclKind::Var,
+            decls: temps
+                .into_iter()
+                .map(|temp| VarDeclarator {
+                    span: DUMMY_SP,
+                    name: Pat::Ident(temp.into()),
+                    init: None,
+                    definite: false,
+                })
+                .collect(),
+            ..Default::default()
+        })))
+    }
+
+    /// Runs `visit` with a new scope for temporary variables, returning its variables.
+    fn with_temps(&mut self, visit: impl FnOnce(&mut Self)) -> Vec<Ident> {
+        self.temps.push(vec![]);
+        visit(self);
+        self.temps.pop().unwrap_or_default()
+    }
+
+    /// Splits an expression that must be evaluated once into the expression to evaluate
+    /// first and the expression to read its value again.
+    fn once(&mut self, expr: Expr) -> (Expr, Expr) {
+        if is_simple(&expr) {
+            (expr.clone(), expr)
+        } else {
+            let temp = self.temp();
+            (assign_to(&temp, expr), Expr::Ident(temp))
+        }
+    }
+
+    /// Splits the target of `a.b op= c` into the expression to read and the target to write,
+    /// evaluating the object and the computed key only once.
+    fn read_and_write(&mut self, target: SimpleAssignTarget) -> Option<(Expr, SimpleAssignTarget)> {
+        match target {
+            SimpleAssignTarget::Ident(binding) => Some((
+                Expr::Ident(binding.id.clone()),
+                SimpleAssignTarget::Ident(binding),
+            )),
+            SimpleAssignTarget::Member(MemberExpr { span, obj, prop }) => {
+                let (obj_first, obj_again) = self.once(*obj);
+                let (prop_first, prop_again) = match prop {
+                    MemberProp::Computed(ComputedPropName { span, expr }) => {
+                        let (first, again) = self.once(*expr);
+                        let computed = |expr| {
+                            MemberProp::Computed(ComputedPropName {
+                                span,
+                                expr: Box::new(expr),
+                            })
+                        };
+                        (computed(first), computed(again))
+                    }
+                    prop => (prop.clone(), prop),
+                };
+
+                let read = Expr::Member(MemberExpr {
+                    span,
+                    obj: Box::new(obj_first),
+                    prop: prop_first,
+                });
+                let write = SimpleAssignTarget::Member(MemberExpr {
+                    span,
+                    obj: Box::new(obj_again),
+                    prop: prop_again,
+                });
+                Some((read, write))
+            }
+            _ => None,
+        }
+    }
+
+    /// Lowers a whole optional chain, e.g. `a?.b.c()`, or the deletion of its last member,
+    /// which is `true` when the chain is cut short.
+    fn lower_optional_chain(&mut self, expr: Expr, delete: bool) -> Expr {
+        let mut links = vec![];
+        let mut node = expr;
+        let base = loop {
+            match node {
+                Expr::OptChain(OptChainExpr { optional, base, .. }) => match *base {
+                    OptChainBase::Member(MemberExpr { obj, prop, .. }) => {
+                        links.push((optional, LinkKind::Member(prop)));
+                        node = *obj;
+                    }
+                    OptChainBase::Call(OptCall { callee, args, .. }) => {
+                        links.push((optional, LinkKind::Call(args)));
+                        node = *callee;
+                    }
+                },
+                node => break node,
+            }
+        };
+        links.reverse();
+
+        let mut checks = vec![];
+        let mut current = base;
+
+        for (optional, link) in links {
+            if optional {
+                match (link, current) {
+                    // `a.b?.()` must still call `b` with `a` as `this`
+                    (LinkKind::Call(args), Expr::Member(MemberExpr { obj, prop, span })) => {
+                        let (obj_first, obj_again) = self.once(*obj);
+                        let callee = self.temp();
+                        let method = Expr::Member(MemberExpr {
+                            span,
+                            obj: Box::new(obj_first),
+                            prop,
+                        });
+                        checks.push(binary(BinaryOp::EqEq, assign_to(&callee, method), null()));
+
+                        let mut call_args = vec![obj_again.into()];
+                        call_args.extend(args);
+                        current = Expr::Call(CallExpr {
+                            callee: Callee::Expr(Box::new(member(Expr::Ident(callee), "call"))),
+                            args: call_args,
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                    (link, value) => {
+                        let (first, again) = self.once(value);
+                        checks.push(binary(BinaryOp::EqEq, first, null()));
+                        current = Self::apply_link(again, link);
+                        continue;
+                    }
+                }
+            }
+            current = Self::apply_link(current, link);
+        }
+
+        let (short, current) = if delete {
+            let deletion = Expr::Unary(UnaryExpr {
+                span: DUMMY_SP,
+                op: UnaryOp::Delete,
+                arg: Box::new(current),
+            });
+            (Expr::Lit(Lit::Bool(true.into())), deletion)
+        } else {
+            (void_zero(), current)
+        };
+        match checks
+            .into_iter()
+            .reduce(|left, right| binary(BinaryOp::LogicalOr, left, right))
+        {
+            Some(test) => cond(test, short, current),
+            None => current,
+        }
+    }
+
+    fn apply_link(current: Expr, link: LinkKind) -> Expr {
+        match link {
+            LinkKind::Member(prop) => Expr::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(current),
+                prop,
+            }),
+            LinkKind::Call(args) => Expr::Call(CallExpr {
+                callee: Callee::Expr(Box::new(current)),
+                args,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Lowers the expression itself, not its children. Returns whether it was changed.
+    fn lower(&mut self, expr: &mut Expr) -> bool {
+        let target = self.target;
+
+        match expr {
+            Expr::OptChain(_) if target < EsVersion::Es2020 => {
+                let chain = expr.take();
+                *expr = self.lower_optional_chain(chain, false);
+                true
+            }
+            Expr::Unary(UnaryExpr {
+                op: UnaryOp::Delete,
+                arg,
+                ..
+            }) if target < EsVersion::Es2020 && arg.unwrap_parens().is_opt_chain() => {
+                let chain = arg.unwrap_parens_mut().take();
+                *expr = self.lower_optional_chain(chain, true);
+                true
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::NullishCoalescing,
+                left,
+                right,
+                ..
+            }) if target < EsVersion::Es2020 => {
+                let (first, again) = self.once(*left.take());
+                let test = binary(BinaryOp::NotEq, first, null());
+                *expr = cond(test, again, *right.take());
+                true
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::Exp,
+                left,
+                right,
+                ..
+            }) if target < EsVersion::Es2016 => {
+                *expr = call(
+                    member(Expr::Ident(ident("Math")), "pow"),
+                    vec![*left.take(), *right.take()],
+                );
+                true
+            }
+            Expr::Assign(AssignExpr {
+                op: op @ (AssignOp::AndAssign | AssignOp::OrAssign | AssignOp::NullishAssign),
+                left: AssignTarget::Simple(left),
+                right,
+                ..
+            }) if target < EsVersion::Es2021 => {
+                let op = match op {
+                    AssignOp::AndAssign => BinaryOp::LogicalAnd,
+                    AssignOp::OrAssign => BinaryOp::LogicalOr,
+                    _ => BinaryOp::NullishCoalescing,
+                };
+                let Some((read, write)) = self.read_and_write(left.take()) else {
+                    return false;
+                };
+                *expr = paren(binary(op, read, paren(assign(write, *right.take()))));
+                true
+            }
+            Expr::Assign(AssignExpr {
+                op: AssignOp::ExpAssign,
+                left: AssignTarget::Simple(left),
+                right,
+                ..
+            }) if target < EsVersion::Es2016 => {
+                let Some((read, write)) = self.read_and_write(left.take()) else {
+                    return false;
+                };
+                let pow = call(
+                    member(Expr::Ident(ident("Math")), "pow"),
+                    vec![read, *right.take()],
+                );
+                *expr = assign(write, pow);
+                true
+            }
+            Expr::Object(object)
+                if target < EsVersion::Es2018
+                    && object
+                        .props
+                        .iter()
+                        .any(|prop| matches!(prop, PropOrSpread::Spread(_))) =>
+            {
+                let mut args = vec![];
+                let mut props = vec![];
+                for prop in object.props.drain(..) {
+                    match prop {
+                        PropOrSpread::Spread(spread) => {
+                            if !props.is_empty() || args.is_empty() {
+                                args.push(Expr::Object(ObjectLit {
+                                    span: DUMMY_SP,
+                                    props: std::mem::take(&mut props),
+                                }));
+                            }
+                            args.push(*spread.expr);
+                        }
+                        prop => props.push(prop),
+                    }
+                }
+                if !props.is_empty() {
+                    args.push(Expr::Object(ObjectLit {
+                        span: DUMMY_SP,
+                        props,
+                    }));
+                }
+                *expr = call(member(Expr::Ident(ident("Object")), "assign"), args);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves the static fields and blocks of a class declaration out of the class, as
+    /// statements to run right after it in the same order. A block becomes a function called
+    /// with the class as `this`.
+    fn take_statics(&self, decl: &mut ClassDecl) -> Vec<Stmt> {
+        if self.target >= EsVersion::Es2022 {
+            return vec![];
+        }
+
+        let class = Expr::Ident(decl.ident.clone());
+        let mut statics = vec![];
+        decl.class
+            .body
+            .retain_mut(|class_member| match class_member {
+                ClassMember::ClassProp(prop) if prop.is_static => {
+                    statics.push(field_assignment(class.clone(), std::mem::take(prop)));
+                    false
+                }
+                ClassMember::StaticBlock(block) => {
+                    let function = Expr::Fn(FnExpr {
+                        ident: None,
+                        function: Box::new(Function {
+                            body: Some(block.body.take()),
+                            ..Default::default()
+                        }),
+                    });
+                    statics.push(Stmt::Expr(ExprStmt {
+                        span: DUMMY_SP,
+                        expr: Box::new(call(member(paren(function), "call"), vec![class.clone()])),
+                    }));
+                    false
+                }
+                _ => true,
+            });
+        statics
+    }
+
+    fn lower_stmts<T>(
+        &mut self,
+        items: &mut Vec<T>,
+        class_decl: fn(&mut T) -> Option<&mut ClassDecl>,
+    ) where
+        T: From<Stmt>,
+    {
+        let mut index = 0;
+        while index < items.len() {
+            let statics = match class_decl(&mut items[index]) {
+                Some(decl) => self.take_statics(decl),
+                None => vec![],
+            };
+            let count = statics.len();
+            for (offset, stmt) in statics.into_iter().enumerate() {
+                items.insert(index + 1 + offset, stmt.into());
+            }
+            index += 1 + count;
+        }
+    }
+}
+
+/// The kind of an optional chain link, see [`Lowering::lower_optional_chain`].
+enum LinkKind {
+    Member(MemberProp),
+    Call(Vec<ExprOrSpread>),
+}
+
+impl VisitMut for Lowering {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        while self.lower(expr) {}
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        self.lower_stmts(items, |item| match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Class(decl))) => Some(decl),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Class(decl),
+                ..
+            })) => Some(decl),
+            _ => None,
+        });
+        items.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        self.lower_stmts(stmts, |stmt| match stmt {
+            Stmt::Decl(Decl::Class(decl)) => Some(decl),
+            _ => None,
+        });
+        stmts.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        class.visit_mut_children_with(self);
+        if self.target >= EsVersion::Es2022 {
+            return;
+        }
+
+        let mut fields = vec![];
+        class.body.retain_mut(|member| match member {
+            ClassMember::ClassProp(prop) if !prop.is_static => {
+                fields.push(field_assignment(
+                    Expr::This(ThisExpr { span: DUMMY_SP }),
+                    std::mem::take(prop),
+                ));
+                false
+            }
+            _ => true,
+        });
+        if fields.is_empty() {
+            return;
+        }
+
+        let is_derived = class.super_class.is_some();
+        let constructor = class.body.iter_mut().find_map(|member| match member {
+            ClassMember::Constructor(constructor) => Some(constructor),
+            _ => None,
+        });
+
+        match constructor {
+            Some(Constructor {
+                body: Some(body), ..
+            }) => {
+                let index = if is_derived {
+                    body.stmts
+                        .iter()
+                        .position(is_super_call)
+                        .map_or(0, |index| index + 1)
+                } else {
+                    0
+                };
+                body.stmts.splice(index..index, fields);
+            }
+            Some(_) => {}
+            None => {
+                let args = ident("args");
+                let (params, mut stmts) = if is_derived {
+                    let rest = Pat::Rest(RestPat {
+                        span: DUMMY_SP,
+                        dot3_token: DUMMY_SP,
+                        arg: Box::new(Pat::Ident(args.clone().into())),
+                        type_ann: None,
+                    });
+                    let super_call = Stmt::Expr(ExprStmt {
+                        span: DUMMY_SP,
+                        expr: Box::new(Expr::Call(CallExpr {
+                            callee: Callee::Super(Super { span: DUMMY_SP }),
+                            args: vec![ExprOrSpread {
+                                spread: Some(DUMMY_SP),
+                                expr: Box::new(Expr::Ident(args)),
+                            }],
+                            ..Default::default()
+                        })),
+                    });
+                    (
+                        vec![ParamOrTsParamProp::Param(Param {
+                            span: DUMMY_SP,
+                            decorators: vec![],
+                            pat: rest,
+                        })],
+                        vec![super_call],
+                    )
+                } else {
+                    (vec![], vec![])
+                };
+                stmts.extend(fields);
+
+                class.body.insert(
+                    0,
+                    ClassMember::Constructor(Constructor {
+                        key: PropName::Ident(IdentName::new("constructor".into(), DUMMY_SP)),
+                        params,
+                        body: Some(BlockStmt {
+                            stmts,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                );
+            }
+        }
+    }
+
+    // The parameters are evaluated before the body, so their values keep their temporaries
+    // in the enclosing function
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        function.decorators.visit_mut_with(self);
+        function.params.visit_mut_with(self);
+        let temps = self.with_temps(|this| function.body.visit_mut_with(this));
+        if let (false, Some(body)) = (temps.is_empty(), &mut function.body) {
+            let directives = body
+                .stmts
+                .iter()
+                .take_while(|stmt| {
+                    matches!(stmt, Stmt::Expr(ExprStmt { expr, .. }) if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+                })
+                .count();
+            body.stmts.insert(directives, Self::temp_declaration(temps));
+        }
+    }
+
+    fn visit_mut_constructor(&mut self, constructor: &mut Constructor) {
+        constructor.key.visit_mut_with(self);
+        constructor.params.visit_mut_with(self);
+        let temps = self.with_temps(|this| constructor.body.visit_mut_with(this));
+        if let (false, Some(body)) = (temps.is_empty(), &mut constructor.body) {
+            body.stmts.insert(0, Self::temp_declaration(temps));
+        }
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        arrow.params.visit_mut_with(self);
+        let temps = self.with_temps(|this| arrow.body.visit_mut_with(this));
+        if temps.is_empty() {
+            return;
+        }
+
+        let declaration = Self::temp_declaration(temps);
+        match &mut *arrow.body {
+            BlockStmtOrExpr::BlockStmt(body) => body.stmts.insert(0, declaration),
+            BlockStmtOrExpr::Expr(expr) => {
+                let value = Stmt::Return(ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: Some(expr.take()),
+                });
+                *arrow.body = BlockStmtOrExpr::BlockStmt(BlockStmt {
+                    stmts: vec![declaration, value],
+                    ..Default::default()
+                });
+            }
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+    }
+}
+
+type ParsedModule = (Module, SingleThreadedComments, Lrc<SourceMap>);
+
+fn lower_module(file_content: &str, target: &str) -> Result<ParsedModule, String> {
+    let target = es_version(target)?;
+    if target < EsVersion::Es2015 {
+        return Err(
+            "Transpiling to ES5 or older is not supported, the oldest target is es2015".to_string(),
+        );
+    }
+
+    let (mut module, comments, cm) = parse(file_content)?;
+
+    let mut unsupported = Unsupported {
+        target,
+        error: None,
+        in_class_decl: false,
+    };
+    module.visit_with(&mut unsupported);
+    if let Some(error) = unsupported.error {
+        return Err(error);
+    }
+
+    let mut collector = NameCollector::default();
+    module.visit_with(&mut collector);
+
+    let mut lowering = Lowering {
+        target,
+        names: collector.names,
+        counter: 0,
+        temps: vec![],
+    };
+    let temps = lowering.with_temps(|this| module.visit_mut_with(this));
+
+    if !temps.is_empty() {
+        let index = module
+            .body
+            .iter()
+            .take_while(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+            .count();
+        module
+            .body
+            .insert(index, ModuleItem::Stmt(Lowering::temp_declaration(temps)));
+    }
+
+    Ok((module, comments, cm))
+}
+
+/// Lowers the syntax that is newer than the target ES version, see the module documentation.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+/// * `target` - The ES version to lower to, from `es2015` to `esnext`.
+///
+/// # Returns
+/// * `Ok(String)` - The transpiled code.
+/// * `Err(String)` - If the source can not be parsed, the target is not supported or the
+///   source uses syntax that can not be lowered to it.
+///
+/// # Example
+/// ```rust
+/// let js_code = "const port = config?.port ?? 4000;";
+/// let result = transpile_js(js_code, "es2019").unwrap();
+/// assert_eq!(
+///     result,
+///     "var _ref;\nconst port = ((_ref = (config == null ? void 0 : config.port)) != null ? _ref : 4000);\n"
+/// );
+/// ```
+pub fn transpile_js(file_content: &str, target: &str) -> Result<String, String> {
+    let (mut module, comments, cm) = lower_module(file_content, target)?;
+    Ok(code_gen_from_ast_module(&mut module, comments, cm))
+}
+
+/// Like [`transpile_js`], also returning the source map from the transpiled code to the
+/// source, which is named `source_name` in the map.
+pub fn transpile_js_with_source_map(
+    file_content: &str,
+    target: &str,
+    source_name: &str,
+) -> Result<GeneratedCode, String> {
+    let (module, comments, cm) = lower_module(file_content, target)?;
+    code_gen_with_source_map(&module, Some(&comments), cm, Config::default(), source_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transpile_js() {
+        let js_code = r#"
+import { Socket } from "phoenix";
+const port = config?.port ?? 4000;
+const name = user.profile?.name;
+const count = hooks.list?.(1);
+options.timeout ||= 500;
+cache[key()] ??= {};
+const merged = { ...defaults, debug: true };
+const area = side ** 2;
+
+class Hook extends Base {
+  mounted = false;
+  static count = 0;
+}
+"#;
+
+        let result = transpile_js(js_code, "es2015").unwrap();
+        assert!(result.contains("var _ref, _ref2, _ref3, _ref4, _ref5;"));
+        assert!(result.contains(
+            "const port = ((_ref = (config == null ? void 0 : config.port)) != null ? _ref : 4000);"
+        ));
+        assert!(
+            result.contains("const name = ((_ref2 = user.profile) == null ? void 0 : _ref2.name);")
+        );
+        assert!(result.contains(
+            "const count = ((_ref3 = hooks.list) == null ? void 0 : _ref3.call(hooks, 1));"
+        ));
+        assert!(result.contains("(options.timeout || (options.timeout = 500));"));
+        assert!(result.contains(
+            "(((_ref5 = cache[(_ref4 = key())]) != null ? _ref5 : (cache[_ref4] = {})));"
+        ));
+        assert!(
+            result.contains("const merged = Object.assign({}, defaults, {\n    debug: true\n});")
+        );
+        assert!(result.contains("const area = Math.pow(side, 2);"));
+        assert!(result.contains(
+            "constructor(...args){\n        super(...args);\n        this.mounted = false;"
+        ));
+        assert!(result.contains("}\nHook.count = 0;"));
+
+        // Nothing to lower for the newest target
+        let result = transpile_js(js_code, "esnext").unwrap();
+        assert!(result.contains("config?.port ?? 4000"));
+        assert!(result.contains("mounted = false;"));
+
+        let js_code = "const load = async (opts) => opts.data?.url;";
+        assert_eq!(
+            transpile_js(js_code, "es2019").unwrap(),
+            "const load = async (opts)=>{\n    var _ref;\n    return ((_ref = opts.data) == null ? void 0 : _ref.url);\n};\n"
+        );
+
+        let result = transpile_js_with_source_map(js_code, "es2019", "app.js").unwrap();
+        assert_eq!(result.code, transpile_js(js_code, "es2019").unwrap());
+        let source_map: serde_json::Value = serde_json::from_str(&result.source_map).unwrap();
+        assert_eq!(source_map["version"], 3);
+        assert_eq!(source_map["sources"], serde_json::json!(["app.js"]));
+        assert_eq!(source_map["sourcesContent"], serde_json::json!([js_code]));
+        assert!(!source_map["mappings"].as_str().unwrap().is_empty());
+
+        assert!(transpile_js(js_code, "es5").is_err());
+        assert!(transpile_js("let = ;", "es2015").is_err());
+
+        for (js_code, target, error) in [
+            (
+                "class A { #secret = 1; }",
+                "es2021",
+                "Private class members",
+            ),
+            (
+                "class A { static self = this; }",
+                "es2021",
+                "The `this` of a static",
+            ),
+            (
+                "const A = class { static count = 0; };",
+                "es2021",
+                "The static fields and blocks",
+            ),
+            ("class A { [key()] = 1; }", "es2021", "Class fields with a computed key"),
+            (
+                "class B extends A { x = 1; constructor(a) { if (a) { super(a); } else { super(); } } }",
+                "es2021",
+                "Class fields in a constructor",
+            ),
+            ("const load = async () => 1;", "es2016", "Async functions"),
+            ("async function* lines() {}", "es2017", "Async generators"),
+            (
+                "async function f() { for await (const a of b) {} }",
+                "es2017",
+                "`for await`",
+            ),
+            (
+                "const { a, ...rest } = b;",
+                "es2017",
+                "Object rest patterns",
+            ),
+        ] {
+            let result = transpile_js(js_code, target).unwrap_err();
+            assert!(result.starts_with(error), "{result}");
+        }
+        assert_eq!(
+            transpile_js("const { a, ...rest } = b;", "es2018").unwrap(),
+            "const { a, ...rest } = b;\n"
+        );
+    }
+
+    /// Runs the module with node and returns what it prints, or `None` without node.
+    fn run_with_node(code: &str) -> Option<String> {
+        let output = std::process::Command::new("node")
+            .args(["--input-type=module", "-e", code])
+            .output()
+            .ok()?;
+        assert!(
+            output.status.success(),
+            "{}\n{code}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Some(String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[test]
+    fn test_transpile_js_runs_the_same() {
+        let js_code = r#"
+const log = [];
+const config = null;
+function port(value = config?.port ?? 4000) { return value; }
+const url = (opts = {}, value = opts.data?.url) => value;
+log.push(port(), port(8080), url(), url({ data: { url: "/live" } }));
+
+const user = { profile: { name: "ada", role: "admin" } };
+const nobody = null;
+log.push(delete nobody?.profile, delete user?.profile.role, JSON.stringify(user));
+
+class Counter {
+  static log = [];
+  static first = Counter.log.push("first");
+  static { this.log.push("block", this.first); }
+  static last = Counter.log.push("last");
+  count = Counter.last;
+}
+log.push(Counter.log.join(), new Counter().count);
+
+class Base { constructor(v) { this.v = v; } }
+class Derived extends Base {
+  ["label"] = "d";
+  constructor() { const v = 1; super(v); this.w = this.label + this.v; }
+}
+log.push(new Derived().w);
+
+let calls = 0;
+const key = () => { calls += 1; return "key"; };
+const cache = { key: 0 };
+cache[key()] ??= 1;
+cache[key()] ||= 2;
+const hooks = { name: "hooks", list(n) { return this.name + n; } };
+log.push(JSON.stringify(cache), calls, hooks.list?.(1), hooks.missing?.(1), 2 ** 10);
+console.log(JSON.stringify(log));
+"#;
+
+        let Some(expected) = run_with_node(js_code) else {
+            return;
+        };
+        for target in ["es2015", "es2019", "es2021"] {
+            let result = transpile_js(js_code, target).unwrap();
+            assert_eq!(
+                run_with_node(&result).unwrap(),
+                expected,
+                "{target}\n{result}"
+            );
+        }
+    }
+}
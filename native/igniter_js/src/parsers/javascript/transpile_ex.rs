@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::transpile::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn transpile_js_nif(
+    env: Env,
+    file_content: String,
+    target: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::transpile_js_nif();
+        let (status, result) = match transpile_js(&file_content, &target) {
+            Ok(transpiled_code) => (atoms::ok(), transpiled_code),
+            Err(error_msg) => (atoms::error(), error_msg),
+        };
+
+        encode_response(env, status, fn_atom, result)
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn transpile_js_with_source_map_nif(
+    env: Env,
+    file_content: String,
+    target: String,
+    source_name: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::transpile_js_with_source_map_nif();
+
+        match transpile_js_with_source_map(&file_content, &target, &source_name) {
+            Ok(generated) => encode_response(env, atoms::ok(), fn_atom, generated),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -0,0 +1,429 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Report of the imports a module never uses and of the exports it never references
+//! itself, e.g. the imports left behind after removing hooks.
+
+use crate::parsers::javascript::ast::SourceLocation;
+use crate::parsers::javascript::helpers::parse;
+use crate::parsers::javascript::scope::ScopeCollector;
+use std::collections::HashSet;
+use swc_atoms::Atom;
+use swc_common::{BytePos, Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::VisitWith;
+
+/// An import specifier whose binding is never referenced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedImport {
+    /// The name of the binding, e.g. `Hooks` in `import { hooks as Hooks } from "./hooks"`.
+    pub local: String,
+    /// The imported name, `"default"` for default imports and `"*"` for namespace imports.
+    pub imported: String,
+    pub source: String,
+    /// The location of the binding.
+    pub location: SourceLocation,
+}
+
+/// An exported binding the module does not reference besides exporting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreferencedExport {
+    pub exported: String,
+    pub local: String,
+    /// The location of the declaration, or of the export specifier.
+    pub location: SourceLocation,
+}
+
+/// The result of [`analyze_unused`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UnusedAnalysis {
+    pub unused_imports: Vec<UnusedImport>,
+    pub unreferenced_exports: Vec<UnreferencedExport>,
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string(),
+    }
+}
+
+/// The identifiers declared by a pattern, in source order.
+pub(crate) fn pat_idents<'a>(pat: &'a Pat, idents: &mut Vec<&'a Ident>) {
+    match pat {
+        Pat::Ident(binding) => idents.push(&binding.id),
+        Pat::Array(array) => array
+            .elems
+            .iter()
+            .flatten()
+            .for_each(|elem| pat_idents(elem, idents)),
+        Pat::Rest(rest) => pat_idents(&rest.arg, idents),
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(key_value) => pat_idents(&key_value.value, idents),
+                    ObjectPatProp::Assign(assign) => idents.push(&assign.key.id),
+                    ObjectPatProp::Rest(rest) => pat_idents(&rest.arg, idents),
+                }
+            }
+        }
+        Pat::Assign(assign) => pat_idents(&assign.left, idents),
+        Pat::Invalid(_) | Pat::Expr(_) => {}
+    }
+}
+
+/// Finds the unused imports and the unreferenced exports of a module.
+///
+/// A binding is used when an identifier outside of its declaration refers to it, which
+/// the scope analysis resolves, so a reference in a scope shadowing the binding does not
+/// count. Re-exporting an import with `export { name }` uses it. Side-effect imports like
+/// `import "phoenix_html"` have no binding and are never reported, and neither is anything
+/// in a module calling `eval`, whose references can not be known.
+///
+/// # Arguments
+/// * `file_content` - The JavaScript source code as a string.
+///
+/// # Returns
+/// * `Ok(UnusedAnalysis)` - The unused import specifiers and the unreferenced named exports,
+///   each in source order. Default exports are not reported.
+/// * `Err(String)` - If the source can not be parsed.
+///
+/// # Example
+/// ```rust
+/// let code = r#"import { Socket } from "phoenix"; import topbar from "topbar"; new Socket();"#;
+/// let analysis = analyze_unused(code).unwrap();
+/// assert_eq!(analysis.unused_imports[0].local, "topbar");
+/// ```
+pub fn analyze_unused(file_content: &str) -> Result<UnusedAnalysis, String> {
+    let (module, _comments, cm) = parse(file_content)?;
+    let mut collector = ScopeCollector::new();
+    module.visit_with(&mut collector);
+
+    if collector.has_eval {
+        return Ok(UnusedAnalysis::default());
+    }
+
+    // `export { name }` is a reference for imports, but not for exports
+    let export_specifiers: HashSet<BytePos> = module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_none() => {
+                Some(export)
+            }
+            _ => None,
+        })
+        .flat_map(|export| &export.specifiers)
+        .filter_map(|specifier| match specifier {
+            ExportSpecifier::Named(ExportNamedSpecifier {
+                orig: ModuleExportName::Ident(orig),
+                ..
+            }) => Some(orig.span.lo),
+            _ => None,
+        })
+        .collect();
+
+    let is_referenced = |ident: &Ident, ignored: &HashSet<BytePos>| {
+        collector.occurrences.iter().any(|(lo, sym, scope)| {
+            *sym == ident.sym
+                && !collector.declarations.contains(lo)
+                && !ignored.contains(lo)
+                && collector.resolve(sym, *scope) == Some(0)
+        })
+    };
+
+    let mut analysis = UnusedAnalysis::default();
+    let none = HashSet::new();
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+
+        match decl {
+            ModuleDecl::Import(import) => {
+                for specifier in &import.specifiers {
+                    let (local, imported) = match specifier {
+                        ImportSpecifier::Named(named) => (
+                            &named.local,
+                            named
+                                .imported
+                                .as_ref()
+                                .map_or(named.local.sym.to_string(), export_name),
+                        ),
+                        ImportSpecifier::Default(default) => (&default.local, "default".into()),
+                        ImportSpecifier::Namespace(namespace) => (&namespace.local, "*".into()),
+                    };
+
+                    if !is_referenced(local, &none) {
+                        analysis.unused_imports.push(UnusedImport {
+                            local: local.sym.to_string(),
+                            imported,
+                            source: import.src.value.to_string(),
+                            location: SourceLocation::from_span(&cm, local.span),
+                        });
+                    }
+                }
+            }
+            ModuleDecl::ExportDecl(export) => {
+                let mut idents = vec![];
+                match &export.decl {
+                    Decl::Fn(function) => idents.push(&function.ident),
+                    Decl::Class(class) => idents.push(&class.ident),
+                    Decl::Var(var) => var
+                        .decls
+                        .iter()
+                        .for_each(|declarator| pat_idents(&declarator.name, &mut idents)),
+                    _ => {}
+                }
+
+                for ident in idents {
+                    if !is_referenced(ident, &export_specifiers) {
+                        analysis.unreferenced_exports.push(UnreferencedExport {
+                            exported: ident.sym.to_string(),
+                            local: ident.sym.to_string(),
+                            location: SourceLocation::from_span(&cm, ident.span),
+                        });
+                    }
+                }
+            }
+            ModuleDecl::ExportNamed(export) if export.src.is_none() => {
+                for specifier in &export.specifiers {
+                    let ExportSpecifier::Named(named) = specifier else {
+                        continue;
+                    };
+                    let ModuleExportName::Ident(orig) = &named.orig else {
+                        continue;
+                    };
+                    // Exporting an import is a use of the import, not an own binding
+                    if collector.scopes[0].bindings.contains(&orig.sym)
+                        && !is_import(&module, orig)
+                        && !is_referenced(orig, &export_specifiers)
+                    {
+                        analysis.unreferenced_exports.push(UnreferencedExport {
+                            exported: named
+                                .exported
+                                .as_ref()
+                                .map_or(orig.sym.to_string(), export_name),
+                            local: orig.sym.to_string(),
+                            location: SourceLocation::from_span(&cm, named.span),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(analysis)
+}
+
+/// The names of the top-level bindings a module refers to, or `None` when it calls `eval`.
+fn top_level_references(module: &Module) -> Option<HashSet<Atom>> {
+    let mut collector = ScopeCollector::new();
+    module.visit_with(&mut collector);
+    if collector.has_eval {
+        return None;
+    }
+
+    Some(
+        collector
+            .references
+            .iter()
+            .filter(|(sym, scope)| collector.resolve(sym, *scope) == Some(0))
+            .map(|(sym, _)| sym.clone())
+            .collect(),
+    )
+}
+
+/// Removes the import specifiers that `original` refers to and `module`, the result of an
+/// edit of it, no longer does, along with the import declarations left without specifiers.
+///
+/// Side-effect imports like `import "phoenix_html"` and the imports that were already unused
+/// are kept, and so is every import of a module calling `eval`. Returns the local names of
+/// the removed specifiers, in source order, with the span of the code removed: the specifier,
+/// or the whole declaration when it has none left.
+pub(crate) fn prune_imports_unused_after(
+    original: &Module,
+    module: &mut Module,
+) -> Vec<(String, Span)> {
+    let (Some(before), Some(after)) =
+        (top_level_references(original), top_level_references(module))
+    else {
+        return vec![];
+    };
+
+    let mut pruned = vec![];
+    module.body.retain_mut(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return true;
+        };
+        if import.specifiers.is_empty() {
+            return true;
+        }
+
+        let first = pruned.len();
+        import.specifiers.retain(|specifier| {
+            let local = &import_local(specifier).sym;
+            let unused = before.contains(local) && !after.contains(local);
+            if unused {
+                pruned.push((local.to_string(), specifier.span()));
+            }
+            !unused
+        });
+        if import.specifiers.is_empty() {
+            for (_, span) in &mut pruned[first..] {
+                *span = import.span;
+            }
+        }
+        !import.specifiers.is_empty()
+    });
+    pruned
+}
+
+fn import_local(specifier: &ImportSpecifier) -> &Ident {
+    match specifier {
+        ImportSpecifier::Named(named) => &named.local,
+        ImportSpecifier::Default(default) => &default.local,
+        ImportSpecifier::Namespace(namespace) => &namespace.local,
+    }
+}
+
+fn is_import(module: &Module, ident: &Ident) -> bool {
+    module.body.iter().any(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => import
+            .specifiers
+            .iter()
+            .any(|specifier| import_local(specifier).sym == ident.sym),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_unused() {
+        let code = r#"import "phoenix_html";
+import { Socket, Presence as P } from "phoenix";
+import topbar from "../vendor/topbar";
+import * as Hooks from "./hooks";
+import Sortable from "sortablejs";
+const topbarConfig = (topbar) => topbar.config({});
+export const liveSocket = new Socket("/live", { hooks: { ...Hooks } });
+export function connect() { return liveSocket.connect(); }
+export class Chart {}
+const helper = 1;
+const { a, b: [c] } = {};
+export { helper as util, a, c, Sortable };
+"#;
+
+        let analysis = analyze_unused(code).unwrap();
+        let imports: Vec<_> = analysis
+            .unused_imports
+            .iter()
+            .map(|import| {
+                (
+                    import.local.as_str(),
+                    import.imported.as_str(),
+                    import.source.as_str(),
+                )
+            })
+            .collect();
+        // `topbar` is only shadowed by the arrow function parameter
+        assert_eq!(
+            imports,
+            [
+                ("P", "Presence", "phoenix"),
+                ("topbar", "default", "../vendor/topbar")
+            ]
+        );
+
+        let exports: Vec<_> = analysis
+            .unreferenced_exports
+            .iter()
+            .map(|export| (export.exported.as_str(), export.local.as_str()))
+            .collect();
+        assert_eq!(
+            exports,
+            [
+                ("connect", "connect"),
+                ("Chart", "Chart"),
+                ("util", "helper"),
+                ("a", "a"),
+                ("c", "c")
+            ]
+        );
+
+        let location = &analysis.unused_imports[1].location;
+        assert_eq!(&code[location.start..location.end], "topbar");
+        assert_eq!((location.line, location.column), (3, 7));
+    }
+
+    #[test]
+    fn test_analyze_unused_scopes() {
+        let code = r#"import topbar from "topbar";
+import { Socket } from "phoenix";
+import Hooks from "./hooks";
+import { debounce } from "./utils";
+connect();
+class Loader {
+  constructor(topbar) { topbar.show(); }
+}
+function socket() { return new Socket(); var Socket; }
+function input(wait = debounce) { var debounce = 1; return wait; }
+export default function main() { return Hooks; }
+export function connect() {}
+export const Chart = 1;
+function draw(Chart) { return Chart; }
+"#;
+
+        let analysis = analyze_unused(code).unwrap();
+        let imports: Vec<_> = analysis
+            .unused_imports
+            .iter()
+            .map(|import| import.local.as_str())
+            .collect();
+        // Shadowed by a constructor parameter and by a hoisted `var`
+        assert_eq!(imports, ["topbar", "Socket"]);
+
+        let exports: Vec<_> = analysis
+            .unreferenced_exports
+            .iter()
+            .map(|export| export.exported.as_str())
+            .collect();
+        assert_eq!(exports, ["Chart"]);
+    }
+
+    #[test]
+    fn test_analyze_unused_binding_forms() {
+        let code = r#"import topbar from "topbar";
+import { Socket } from "phoenix";
+import { show } from "./show";
+import { hide } from "./hide";
+const named = function topbar() { return topbar; };
+try {} catch ({ Socket }) { Socket; }
+class Loader { show = 1; hide() { return this.show; } }
+window.show = { hide: 1 };
+"#;
+
+        let analysis = analyze_unused(code).unwrap();
+        let imports: Vec<_> = analysis
+            .unused_imports
+            .iter()
+            .map(|import| import.local.as_str())
+            .collect();
+        // A binding of the same name, a class member, a property or a key is no reference
+        assert_eq!(imports, ["topbar", "Socket", "show", "hide"]);
+    }
+
+    #[test]
+    fn test_analyze_unused_with_eval() {
+        let code = r#"import topbar from "topbar"; eval("topbar.show()");"#;
+        assert_eq!(analyze_unused(code).unwrap(), UnusedAnalysis::default());
+        assert!(analyze_unused("import {").is_err());
+    }
+}
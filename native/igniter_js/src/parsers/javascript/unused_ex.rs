@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::javascript::ast_ex::SourceLocationResult;
+use crate::parsers::javascript::unused::*;
+
+use rustler::{Encoder, Env, NifMap, NifResult, Term};
+
+#[derive(Debug, NifMap)]
+pub struct UnusedImportResult {
+    pub local: String,
+    pub imported: String,
+    pub source: String,
+    pub location: SourceLocationResult,
+}
+
+#[derive(Debug, NifMap)]
+pub struct UnreferencedExportResult {
+    pub exported: String,
+    pub local: String,
+    pub location: SourceLocationResult,
+}
+
+#[derive(Debug, NifMap)]
+pub struct UnusedAnalysisResult {
+    pub unused_imports: Vec<UnusedImportResult>,
+    pub unreferenced_exports: Vec<UnreferencedExportResult>,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn analyze_unused_nif(
+    env: Env,
+    file_content: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::analyze_unused_nif();
+
+        match analyze_unused(&file_content) {
+            Ok(analysis) => {
+                let result = UnusedAnalysisResult {
+                    unused_imports: analysis
+                        .unused_imports
+                        .into_iter()
+                        .map(|import| UnusedImportResult {
+                            local: import.local,
+                            imported: import.imported,
+                            source: import.source,
+                            location: import.location.into(),
+                        })
+                        .collect(),
+                    unreferenced_exports: analysis
+                        .unreferenced_exports
+                        .into_iter()
+                        .map(|export| UnreferencedExportResult {
+                            exported: export.exported,
+                            local: export.local,
+                            location: export.location.into(),
+                        })
+                        .collect(),
+                };
+                encode_response(env, atoms::ok(), fn_atom, result.encode(env))
+            }
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
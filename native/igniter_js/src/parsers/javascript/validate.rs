@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Parse-only validation of JavaScript, so a broken file is reported before it is patched.
+
+use crate::diagnostics::Diagnostic;
+use crate::parsers::javascript::engine::Engine;
+
+/// Parses JavaScript source code and returns every syntax error found.
+///
+/// The OXC parser recovers from most errors, so more than one problem can be reported for
+/// a file. Each diagnostic points to the primary label of the error, or to its first one.
+///
+/// # Example
+/// ```rust
+/// let diagnostics = validate_js("let a = 1;\nlet b = ;");
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 8));
+/// assert!(validate_js("let a = 1;").is_empty());
+/// ```
+pub fn validate_js(source_text: &str) -> Vec<Diagnostic> {
+    validate_js_with_engine(source_text, Engine::Oxc)
+}
+
+/// Same as [`validate_js`], with the given parser. SWC stops at the first error it can not
+/// recover from, so it reports fewer problems than OXC.
+pub fn validate_js_with_engine(source_text: &str, engine: Engine) -> Vec<Diagnostic> {
+    engine.backend().visit(source_text, &mut ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::DiagnosticSeverity;
+
+    #[test]
+    fn test_validate_js() {
+        let source = "import { Socket } from \"phoenix\";\nlet liveSocket = new Socket(;\n";
+        let diagnostics = validate_js(source);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 28));
+        assert_eq!(&source[diagnostic.start..diagnostic.end], ";");
+        assert!(diagnostic
+            .code_frame
+            .contains("> 2 | let liveSocket = new Socket(;"));
+
+        assert!(validate_js(source.lines().next().unwrap()).is_empty());
+
+        let diagnostics = validate_js_with_engine(source, Engine::Swc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+    }
+}
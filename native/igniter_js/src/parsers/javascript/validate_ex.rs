@@ -0,0 +1,36 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::diagnostics::DiagnosticSeverity;
+use crate::helpers::encode_response;
+use crate::parsers::javascript::engine::Engine;
+use crate::parsers::javascript::validate::*;
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn validate_js_nif(
+    env: Env,
+    file_content: String,
+    engine: Engine,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::validate_js_nif();
+        let diagnostics = validate_js_with_engine(&file_content, engine);
+        let status = if diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        {
+            atoms::error()
+        } else {
+            atoms::ok()
+        };
+
+        encode_response(env, status, fn_atom, diagnostics)
+    })
+}
@@ -0,0 +1,561 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Edits of JSON with comments and trailing commas, e.g. the `compilerOptions` of
+//! `tsconfig.json` an installer adds `paths` or `types` to.
+//!
+//! The values are found in the Biome JSON syntax tree, which keeps every byte of the source,
+//! and only their ranges are rewritten, so the comments and formatting of the rest of the
+//! file are left untouched.
+
+use crate::limits::{check_bracket_depth, check_input_size, check_node_count};
+use crate::telemetry::{measure, Phase};
+use biome_json_parser::{parse_json, JsonParserOptions};
+use biome_json_syntax::{AnyJsonValue, JsonMember, JsonObjectValue, JsonRoot};
+use biome_rowan::{AstNode, AstSeparatedList, TextRange};
+use serde::Serialize;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use serde_json::{Map, Value};
+
+/// The indentation of the new values when the file has none to follow.
+const DEFAULT_INDENT: &str = "  ";
+
+/// Parses a file under the limits of [`crate::limits`].
+fn parse(source: &str) -> Result<JsonRoot, String> {
+    check_input_size(source.len())?;
+    check_bracket_depth(source, true)?;
+
+    let options = JsonParserOptions::default()
+        .with_allow_comments()
+        .with_allow_trailing_commas();
+    let parsed = measure(Phase::Parse, source.len(), || parse_json(source, options));
+    check_node_count(|| parsed.syntax().descendants().count())?;
+
+    if parsed.has_errors() {
+        return Err("Parsing failed due to syntax errors.".into());
+    }
+    Ok(parsed.tree())
+}
+
+fn range(range: TextRange) -> (usize, usize) {
+    (range.start().into(), range.end().into())
+}
+
+fn newline(source: &str) -> &'static str {
+    if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// The indentation of the line an offset is on.
+fn line_indent(source: &str, offset: usize) -> &str {
+    let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line = &source[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// The key of a member, without its quotes and escapes.
+fn member_key(member: &JsonMember) -> Option<String> {
+    let token = member.name().ok()?.value_token().ok()?;
+    serde_json::from_str(token.text_trimmed()).ok()
+}
+
+fn find_member(object: &JsonObjectValue, key: &str) -> Option<JsonMember> {
+    object
+        .json_member_list()
+        .iter()
+        .flatten()
+        .find(|member| member_key(member).as_deref() == Some(key))
+}
+
+/// Converts a value of the syntax tree to plain JSON, dropping its comments.
+fn to_value(value: &AnyJsonValue) -> Result<Value, String> {
+    let invalid = || "Parsing failed due to syntax errors.".to_string();
+    let token_value = |token: biome_rowan::SyntaxResult<biome_json_syntax::JsonSyntaxToken>| {
+        let token = token.map_err(|_| invalid())?;
+        serde_json::from_str::<Value>(token.text_trimmed()).map_err(|err| err.to_string())
+    };
+
+    match value {
+        AnyJsonValue::JsonObjectValue(object) => {
+            let mut map = Map::new();
+            for member in object.json_member_list().iter() {
+                let member = member.map_err(|_| invalid())?;
+                let key = member_key(&member).ok_or_else(invalid)?;
+                let value = member.value().map_err(|_| invalid())?;
+                map.insert(key, to_value(&value)?);
+            }
+            Ok(Value::Object(map))
+        }
+        AnyJsonValue::JsonArrayValue(array) => array
+            .elements()
+            .iter()
+            .map(|element| to_value(&element.map_err(|_| invalid())?))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        AnyJsonValue::JsonStringValue(string) => token_value(string.value_token()),
+        AnyJsonValue::JsonNumberValue(number) => token_value(number.value_token()),
+        AnyJsonValue::JsonBooleanValue(boolean) => token_value(boolean.value_token()),
+        AnyJsonValue::JsonNullValue(_) => Ok(Value::Null),
+        AnyJsonValue::JsonBogusValue(_) => Err(invalid()),
+    }
+}
+
+/// Where a path leads in a document.
+enum Lookup {
+    /// The value at the path.
+    Found(AnyJsonValue),
+    /// The object missing the key at `path[depth]`.
+    Missing {
+        object: JsonObjectValue,
+        depth: usize,
+    },
+}
+
+fn lookup(root: &JsonRoot, path: &[&str]) -> Result<Lookup, String> {
+    let mut current = root
+        .value()
+        .map_err(|_| "Parsing failed due to syntax errors.".to_string())?;
+
+    for (depth, key) in path.iter().enumerate() {
+        let AnyJsonValue::JsonObjectValue(object) = &current else {
+            let parent = if depth == 0 {
+                "The document".to_string()
+            } else {
+                format!("`{}`", path[..depth].join("."))
+            };
+            return Err(format!("{} is not an object", parent));
+        };
+
+        match find_member(object, key).and_then(|member| member.value().ok()) {
+            Some(value) => current = value,
+            None => {
+                return Ok(Lookup::Missing {
+                    object: object.clone(),
+                    depth,
+                })
+            }
+        }
+    }
+
+    Ok(Lookup::Found(current))
+}
+
+/// The indentation unit of the document, taken from the first member of its root object.
+fn indent_unit<'a>(source: &'a str, root: &JsonRoot) -> &'a str {
+    let first_member = match root.value() {
+        Ok(AnyJsonValue::JsonObjectValue(object)) => object.json_member_list().iter().next(),
+        _ => None,
+    };
+
+    match first_member {
+        Some(Ok(member)) => {
+            let (start, _) = range(member.syntax().text_trimmed_range());
+            let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+            match &source[line_start..start] {
+                indent if !indent.is_empty() && indent.trim().is_empty() => indent,
+                _ => DEFAULT_INDENT,
+            }
+        }
+        _ => DEFAULT_INDENT,
+    }
+}
+
+/// Prints a value with the indentation of the document, its first line starting at a line
+/// indented by `line_indent`.
+fn render(value: &Value, indent_unit: &str, line_indent: &str, newline: &str) -> String {
+    let mut buf = vec![];
+    let formatter = PrettyFormatter::with_indent(indent_unit.as_bytes());
+    let mut serializer = Serializer::with_formatter(&mut buf, formatter);
+    value
+        .serialize(&mut serializer)
+        .expect("a JSON value can always be serialized");
+
+    // Newlines in strings are escaped, so every line break is one of the layout
+    String::from_utf8(buf)
+        .expect("serde_json writes UTF-8")
+        .lines()
+        .collect::<Vec<_>>()
+        .join(&format!("{}{}", newline, line_indent))
+}
+
+/// The offset of the end of the line `offset` is on, before its line break, at most `limit`.
+fn line_end(source: &str, offset: usize, limit: usize) -> usize {
+    let end = source[offset..limit]
+        .find('\n')
+        .map_or(limit, |index| offset + index);
+    if end > offset && source[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Adds a member at the end of an object, following the layout of its other members.
+fn insert_member(
+    source: &str,
+    root: &JsonRoot,
+    object: &JsonObjectValue,
+    key: &str,
+    value: &Value,
+) -> String {
+    let unit = indent_unit(source, root);
+    let newline = newline(source);
+    let key = Value::String(key.to_string()).to_string();
+    let (object_start, object_end) = range(object.syntax().text_trimmed_range());
+    let close = object_end - 1;
+    let object_indent = line_indent(source, object_start);
+
+    let elements: Vec<_> = object.json_member_list().elements().collect();
+    let Some(last) = elements.last() else {
+        let indent = format!("{}{}", object_indent, unit);
+        let member = format!("{}: {}", key, render(value, unit, &indent, newline));
+        let open = object_start + 1;
+        return if source[open..close].trim().is_empty() {
+            format!(
+                "{}{}{}{}{}{}{}",
+                &source[..open],
+                newline,
+                indent,
+                member,
+                newline,
+                object_indent,
+                &source[close..]
+            )
+        } else {
+            format!(
+                "{}{}{}{}{}",
+                &source[..open],
+                newline,
+                indent,
+                member,
+                &source[open..]
+            )
+        };
+    };
+
+    let Ok(last_member) = last.node() else {
+        return source.to_string();
+    };
+    let (_, last_end) = range(last_member.syntax().text_trimmed_range());
+    let separator = last
+        .trailing_separator()
+        .ok()
+        .flatten()
+        .map(|token| range(token.text_trimmed_range()));
+
+    // A single line object stays on one line
+    if !source[object_start..object_end].contains('\n') {
+        let member = format!("{}: {}", key, value);
+        return match separator {
+            Some((_, separator_end)) => format!(
+                "{} {},{}",
+                &source[..separator_end],
+                member,
+                &source[separator_end..]
+            ),
+            None => format!("{}, {}{}", &source[..last_end], member, &source[last_end..]),
+        };
+    }
+
+    let (first_start, _) = elements
+        .first()
+        .and_then(|element| element.node().ok())
+        .map(|member| range(member.syntax().text_trimmed_range()))
+        .unwrap_or((last_end, last_end));
+    let indent = line_indent(source, first_start).to_string();
+    let member = format!("{}: {}", key, render(value, unit, &indent, newline));
+
+    match separator {
+        Some((_, separator_end)) => {
+            let at = line_end(source, separator_end, close);
+            format!(
+                "{}{}{}{},{}",
+                &source[..at],
+                newline,
+                indent,
+                member,
+                &source[at..]
+            )
+        }
+        None => {
+            let at = line_end(source, last_end, close);
+            format!(
+                "{},{}{}{}{}{}",
+                &source[..last_end],
+                &source[last_end..at],
+                newline,
+                indent,
+                member,
+                &source[at..]
+            )
+        }
+    }
+}
+
+/// Returns the value at a path of keys, e.g. `["compilerOptions", "paths"]`.
+///
+/// # Returns
+/// * `Ok(Some(String))` - The value as JSON, without the comments it has.
+/// * `Ok(None)` - If the path is not in the document.
+/// * `Err(String)` - If parsing fails, or a value on the path is not an object.
+///
+/// # Example
+/// ```rust
+/// let source = "{\n  // Compiler\n  \"compilerOptions\": { \"strict\": true, },\n}\n";
+/// assert_eq!(get(source, &["compilerOptions", "strict"]), Ok(Some("true".to_string())));
+/// assert_eq!(get(source, &["include"]), Ok(None));
+/// ```
+pub fn get(source: &str, path: &[&str]) -> Result<Option<String>, String> {
+    let root = parse(source)?;
+    match lookup(&root, path)? {
+        Lookup::Found(value) => Ok(Some(to_value(&value)?.to_string())),
+        Lookup::Missing { .. } => Ok(None),
+    }
+}
+
+/// Sets the value at a path of keys, creating the objects missing on the path.
+///
+/// An existing value is replaced, unless it is already equal to the new one, and a missing
+/// key is added after the last member of its object with the indentation of the file.
+///
+/// # Arguments
+/// * `source` - The JSON document, e.g. `tsconfig.json`.
+/// * `path` - The keys leading to the value, e.g. `["compilerOptions", "types"]`.
+/// * `value` - The new value as JSON, e.g. `["phoenix"]`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated document.
+/// * `Err(String)` - If parsing fails, the path is empty, the value is not JSON, or a value
+///   on the path is not an object.
+///
+/// # Example
+/// ```rust
+/// let source = "{\n  \"compilerOptions\": {\n    \"strict\": true // always\n  }\n}\n";
+/// let result = set(source, &["compilerOptions", "types"], "[\"phoenix\"]").unwrap();
+/// assert_eq!(
+///     result,
+///     "{\n  \"compilerOptions\": {\n    \"strict\": true, // always\n    \"types\": [\n      \"phoenix\"\n    ]\n  }\n}\n"
+/// );
+/// ```
+pub fn set(source: &str, path: &[&str], value: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("The path is empty".into());
+    }
+    let value: Value =
+        serde_json::from_str(value).map_err(|err| format!("Invalid JSON value: {}", err))?;
+    let root = parse(source)?;
+
+    match lookup(&root, path)? {
+        Lookup::Found(current) => {
+            if to_value(&current)? == value {
+                return Ok(source.to_string());
+            }
+            let (start, end) = range(current.syntax().text_trimmed_range());
+            let rendered = render(
+                &value,
+                indent_unit(source, &root),
+                line_indent(source, start),
+                newline(source),
+            );
+            Ok(format!(
+                "{}{}{}",
+                &source[..start],
+                rendered,
+                &source[end..]
+            ))
+        }
+        Lookup::Missing { object, depth } => {
+            let value = path[depth + 1..].iter().rev().fold(value, |value, key| {
+                Value::Object(Map::from_iter([(key.to_string(), value)]))
+            });
+            Ok(insert_member(source, &root, &object, path[depth], &value))
+        }
+    }
+}
+
+/// Removes the member at a path of keys, with its comma and its line when nothing else is
+/// on it. A path that is not in the document leaves it untouched.
+///
+/// # Returns
+/// * `Ok(String)` - The updated document.
+/// * `Err(String)` - If parsing fails, the path is empty, or a value on the path is not an
+///   object.
+///
+/// # Example
+/// ```rust
+/// let source = "{\n  \"strict\": true,\n  \"types\": [\"phoenix\"]\n}\n";
+/// assert_eq!(remove(source, &["types"]), Ok("{\n  \"strict\": true\n}\n".to_string()));
+/// ```
+pub fn remove(source: &str, path: &[&str]) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("The path is empty".into());
+    }
+    let root = parse(source)?;
+    let Lookup::Found(value) = lookup(&root, path)? else {
+        return Ok(source.to_string());
+    };
+    let Some(member) = value.syntax().parent().and_then(JsonMember::cast) else {
+        return Ok(source.to_string());
+    };
+    let Some(object) = member
+        .syntax()
+        .parent()
+        .and_then(|list| list.parent())
+        .and_then(JsonObjectValue::cast)
+    else {
+        return Ok(source.to_string());
+    };
+
+    let elements: Vec<_> = object.json_member_list().elements().collect();
+    let member_range = member.syntax().text_trimmed_range();
+    let Some(index) = elements.iter().position(|element| {
+        element
+            .node()
+            .is_ok_and(|node| node.syntax().text_trimmed_range() == member_range)
+    }) else {
+        return Ok(source.to_string());
+    };
+    let separator = |index: usize| {
+        elements[index]
+            .trailing_separator()
+            .ok()
+            .flatten()
+            .map(|token| range(token.text_trimmed_range()))
+    };
+
+    let (mut start, mut end) = range(member_range);
+    match separator(index) {
+        Some((_, separator_end)) => {
+            end = separator_end;
+            end += source[end..].len() - source[end..].trim_start_matches([' ', '\t']).len();
+        }
+        // The last member, so the comma before it goes instead
+        None if index > 0 => {
+            if let Some((separator_start, _)) = separator(index - 1) {
+                start = separator_start;
+            }
+        }
+        None => {}
+    }
+
+    // Removes the whole line when nothing else is on it
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let alone_on_line = source[line_start..start].trim().is_empty()
+        && (source[end..].starts_with('\n') || source[end..].starts_with("\r\n"));
+    if alone_on_line {
+        start = line_start;
+        end += if source[end..].starts_with('\n') {
+            1
+        } else {
+            2
+        };
+    }
+
+    Ok(format!("{}{}", &source[..start], &source[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tsconfig() -> &'static str {
+        r#"{
+    // Generated by the installer
+    "compilerOptions": {
+        "target": "es2020", /* the esbuild target */
+        "strict": true,
+        "paths": {
+            "*": ["../deps/*"],
+        },
+    },
+    "include": ["js/**/*"]
+}
+"#
+    }
+
+    #[test]
+    fn test_get() {
+        let source = tsconfig();
+        assert_eq!(
+            get(source, &["compilerOptions", "paths"]),
+            Ok(Some(r#"{"*":["../deps/*"]}"#.to_string()))
+        );
+        assert_eq!(
+            get(source, &["compilerOptions", "target"]),
+            Ok(Some("\"es2020\"".to_string()))
+        );
+        assert_eq!(get(source, &["compilerOptions", "types"]), Ok(None));
+        assert!(get(source, &["include", "js"]).is_err());
+        assert!(get("{ \"a\": }", &["a"]).is_err());
+    }
+
+    #[test]
+    fn test_set() {
+        let source = tsconfig();
+
+        let result = set(source, &["compilerOptions", "types"], r#"["phoenix"]"#).unwrap();
+        assert_eq!(
+            result,
+            r#"{
+    // Generated by the installer
+    "compilerOptions": {
+        "target": "es2020", /* the esbuild target */
+        "strict": true,
+        "paths": {
+            "*": ["../deps/*"],
+        },
+        "types": [
+            "phoenix"
+        ],
+    },
+    "include": ["js/**/*"]
+}
+"#
+        );
+        assert_eq!(
+            set(&result, &["compilerOptions", "types"], r#"["phoenix"]"#),
+            Ok(result.clone())
+        );
+
+        let result = set(source, &["compilerOptions", "target"], "\"es2022\"").unwrap();
+        assert!(result.contains("\"target\": \"es2022\", /* the esbuild target */"));
+
+        let result = set(source, &["watchOptions", "excludeDirectories"], "[]").unwrap();
+        assert!(result.ends_with(
+            "    \"include\": [\"js/**/*\"],\n    \"watchOptions\": {\n        \"excludeDirectories\": []\n    }\n}\n"
+        ));
+
+        let result = set("{ \"a\": 1 }", &["b"], "{\"c\": true}").unwrap();
+        assert_eq!(result, "{ \"a\": 1, \"b\": {\"c\":true} }");
+
+        let result = set("{}\r\n", &["a"], "1").unwrap();
+        assert_eq!(result, "{\r\n  \"a\": 1\r\n}\r\n");
+
+        assert!(set(source, &[], "1").is_err());
+        assert!(set(source, &["include"], "not json").is_err());
+        assert!(set(source, &["include", "js"], "1").is_err());
+    }
+
+    #[test]
+    fn test_remove() {
+        let source = tsconfig();
+
+        let result = remove(source, &["compilerOptions", "strict"]).unwrap();
+        assert!(result
+            .contains("\"target\": \"es2020\", /* the esbuild target */\n        \"paths\": {"));
+
+        let result = remove(source, &["include"]).unwrap();
+        assert!(result.ends_with("        },\n    }\n}\n"));
+
+        let result = remove("{ \"a\": 1, \"b\": 2 }", &["a"]).unwrap();
+        assert_eq!(result, "{ \"b\": 2 }");
+
+        assert_eq!(remove(source, &["missing", "key"]), Ok(source.to_string()));
+        assert!(remove(source, &[]).is_err());
+    }
+}
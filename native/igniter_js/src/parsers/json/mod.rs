@@ -0,0 +1,8 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+pub mod jsonc;
+#[cfg(feature = "nif")]
+pub mod jsonc_ex;
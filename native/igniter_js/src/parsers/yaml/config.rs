@@ -0,0 +1,1234 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Edits of the YAML configs of front-end tools, e.g. the `packages` of
+//! `pnpm-workspace.yaml` or the `rules` of `.stylelintrc.yaml`.
+//!
+//! The block style these files are written in is read line by line into the ranges of its
+//! keys and values, and only the ranges of the edited values are rewritten, so the comments
+//! and formatting of the rest of the file are left untouched. Flow collections such as
+//! `[a, b]` and block scalars such as `|` are read as values; anchors, aliases and tags are
+//! read as plain text, and a file with several documents is refused.
+
+use crate::limits::{check_input_size, check_node_count, Depth};
+use crate::telemetry::{measure, Phase};
+use serde_json::{Map, Number, Value};
+
+/// The indentation of the new values when the file has none to follow.
+const DEFAULT_INDENT: usize = 2;
+
+#[derive(Debug, Clone)]
+struct Line<'a> {
+    /// The offset of the line, and of its end without the line break.
+    start: usize,
+    end: usize,
+    /// The column of the content, i.e. the indentation, or the column after the `- ` of a
+    /// sequence item holding a mapping or another sequence.
+    indent: usize,
+    /// The content without the indentation, the comment and the trailing whitespace.
+    content: &'a str,
+    content_start: usize,
+    /// Whether the line has no character at all besides whitespace, comments aside.
+    blank: bool,
+}
+
+impl Line<'_> {
+    fn is_significant(&self) -> bool {
+        !self.content.is_empty()
+    }
+
+    fn content_end(&self) -> usize {
+        self.content_start + self.content.len()
+    }
+
+    fn is_sequence_item(&self) -> bool {
+        self.content == "-" || self.content.starts_with("- ")
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Mapping {
+        indent: usize,
+        entries: Vec<(String, Slot)>,
+        last_line: usize,
+    },
+    Sequence {
+        indent: usize,
+        items: Vec<Slot>,
+        last_line: usize,
+    },
+    /// A value written after its key or dash: a plain, quoted or block scalar, or a flow
+    /// collection. Its range can span several lines.
+    Scalar {
+        start: usize,
+        end: usize,
+    },
+    Null,
+}
+
+impl Node {
+    /// The number of nodes of the tree, checked against the limit of [`check_node_count`].
+    fn count(&self) -> usize {
+        let slots = match self {
+            Node::Mapping { entries, .. } => entries.iter().map(|(_, slot)| slot).collect(),
+            Node::Sequence { items, .. } => items.iter().collect(),
+            Node::Scalar { .. } | Node::Null => vec![],
+        };
+        1 + slots.iter().map(|slot| slot.value.count()).sum::<usize>()
+    }
+}
+
+/// The value of a mapping entry or of a sequence item.
+#[derive(Debug)]
+struct Slot {
+    /// The line of the key or of the dash.
+    line: usize,
+    /// The offset after the `:` of the key or after the dash.
+    marker_end: usize,
+    value: Node,
+    last_line: usize,
+    /// The column of the key or of the dash.
+    indent: usize,
+    /// Whether a collection starts on the line of the marker, like the mapping of a
+    /// sequence item `- name: a`.
+    compact: bool,
+}
+
+/// Cuts the comment of a line, a `#` at its start or after whitespace outside of quotes.
+fn strip_comment(text: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut previous = ' ';
+
+    for (index, c) in text.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'')
+                && (previous.is_whitespace() || matches!(previous, '[' | '{' | ',' | ':')) =>
+            {
+                quote = Some(c)
+            }
+            None if c == '#' && previous.is_whitespace() => return text[..index].trim_end(),
+            None => {}
+        }
+        previous = c;
+    }
+    text.trim_end()
+}
+
+fn split_lines(source: &str) -> Vec<Line<'_>> {
+    let mut lines = vec![];
+    let mut start = 0;
+
+    while start < source.len() {
+        let next = source[start..]
+            .find('\n')
+            .map_or(source.len(), |index| start + index);
+        let end = if next > start && source.as_bytes()[next - 1] == b'\r' {
+            next - 1
+        } else {
+            next
+        };
+        let raw = &source[start..end];
+        let indent = raw.len() - raw.trim_start_matches(' ').len();
+        lines.push(Line {
+            start,
+            end,
+            indent,
+            content: strip_comment(&raw[indent..]),
+            content_start: start + indent,
+            blank: raw.trim().is_empty(),
+        });
+        start = next + 1;
+    }
+    lines
+}
+
+/// The end of a quoted text starting the given text, after its closing quote.
+fn quoted_end(text: &str) -> Option<usize> {
+    let quote = text.chars().next()?;
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote == '"' => escaped = true,
+            c if c == quote => {
+                if quote == '\'' && text[index + 1..].starts_with('\'') {
+                    continue;
+                }
+                return Some(index + 1);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(text: &str) -> Option<String> {
+    if text.starts_with('"') {
+        serde_json::from_str(text).ok()
+    } else if text.starts_with('\'') && text.len() >= 2 && text.ends_with('\'') {
+        Some(text[1..text.len() - 1].replace("''", "'"))
+    } else {
+        None
+    }
+}
+
+/// Splits a `key: value` line content into the key and the offset after the colon.
+fn split_key(content: &str) -> Option<(String, usize)> {
+    if content.is_empty()
+        || content.starts_with(['[', '{', '|', '>'])
+        || content == "-"
+        || content.starts_with("- ")
+    {
+        return None;
+    }
+
+    let (key, key_end) = if content.starts_with(['"', '\'']) {
+        let end = quoted_end(content)?;
+        (unquote(&content[..end])?, end)
+    } else {
+        let colon = content.char_indices().find_map(|(index, c)| {
+            let after = &content[index + c.len_utf8()..];
+            (c == ':' && (after.is_empty() || after.starts_with([' ', '\t']))).then_some(index)
+        })?;
+        (content[..colon].trim_end().to_string(), colon)
+    };
+
+    let rest = &content[key_end..];
+    let colon = key_end + (rest.len() - rest.trim_start().len());
+    let after = &content[colon..];
+    (after.starts_with(':') && (after.len() == 1 || after[1..].starts_with([' ', '\t'])))
+        .then_some((key, colon + 1))
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    lines: Vec<Line<'a>>,
+    /// The depth of the node being parsed, and the error of the first one nested deeper than
+    /// the limit, which ends the parse.
+    depth: Depth,
+    exceeded: Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn next_significant(&self, from: usize) -> Option<usize> {
+        (from..self.lines.len()).find(|index| self.lines[*index].is_significant())
+    }
+
+    /// Parses the node starting at a significant line, returning it with its last line and
+    /// the line after it.
+    fn node(&mut self, index: usize, parent_indent: Option<usize>) -> (Node, usize, usize) {
+        if let Err(error) = self.depth.open() {
+            self.exceeded.get_or_insert(error);
+            return (Node::Null, index, self.lines.len());
+        }
+
+        let line = &self.lines[index];
+        let node = if line.is_sequence_item() {
+            let indent = line.indent;
+            self.sequence(index, indent)
+        } else if split_key(line.content).is_some() {
+            let indent = line.indent;
+            self.mapping(index, indent)
+        } else {
+            let start = line.content_start;
+            self.scalar(index, start, parent_indent)
+        };
+        self.depth.close();
+        node
+    }
+
+    fn mapping(&mut self, mut index: usize, indent: usize) -> (Node, usize, usize) {
+        let mut entries = vec![];
+        let mut last_line = index;
+
+        while let Some(current) = self.next_significant(index) {
+            let line = &self.lines[current];
+            if line.indent != indent {
+                break;
+            }
+            let Some((key, colon_end)) = split_key(line.content) else {
+                break;
+            };
+            let marker_end = line.content_start + colon_end;
+            let rest = &line.content[colon_end..];
+            let value_start = marker_end + (rest.len() - rest.trim_start().len());
+
+            let (value, last, next) = if rest.trim().is_empty() {
+                match self.next_significant(current + 1) {
+                    Some(child)
+                        if self.lines[child].indent > indent
+                            || (self.lines[child].indent == indent
+                                && self.lines[child].is_sequence_item()) =>
+                    {
+                        self.node(child, Some(indent))
+                    }
+                    _ => (Node::Null, current, current + 1),
+                }
+            } else {
+                self.scalar(current, value_start, Some(indent))
+            };
+
+            entries.push((
+                key,
+                Slot {
+                    line: current,
+                    marker_end,
+                    value,
+                    last_line: last,
+                    indent,
+                    compact: false,
+                },
+            ));
+            last_line = last;
+            index = next;
+        }
+
+        let node = Node::Mapping {
+            indent,
+            entries,
+            last_line,
+        };
+        (node, last_line, index)
+    }
+
+    fn sequence(&mut self, mut index: usize, indent: usize) -> (Node, usize, usize) {
+        let mut items = vec![];
+        let mut last_line = index;
+
+        while let Some(current) = self.next_significant(index) {
+            let line = self.lines[current].clone();
+            if line.indent != indent || !line.is_sequence_item() {
+                break;
+            }
+            let marker_end = line.content_start + 1;
+            let rest = &line.content[1..];
+            let offset = 1 + rest.len() - rest.trim_start().len();
+
+            let (value, last, next) = if rest.trim().is_empty() {
+                match self.next_significant(current + 1) {
+                    Some(child) if self.lines[child].indent > indent => {
+                        self.node(child, Some(indent))
+                    }
+                    _ => (Node::Null, current, current + 1),
+                }
+            } else {
+                // The item content is parsed as a line of its own, at its column
+                self.lines[current] = Line {
+                    indent: indent + offset,
+                    content: &line.content[offset..],
+                    content_start: line.content_start + offset,
+                    ..line
+                };
+                self.node(current, Some(indent))
+            };
+
+            items.push(Slot {
+                line: current,
+                marker_end,
+                value,
+                last_line: last,
+                indent,
+                compact: true,
+            });
+            last_line = last;
+            index = next;
+        }
+
+        let node = Node::Sequence {
+            indent,
+            items,
+            last_line,
+        };
+        (node, last_line, index)
+    }
+
+    /// Parses the value starting at an offset of a line, with the lines more indented than
+    /// its parent after it.
+    fn scalar(
+        &mut self,
+        index: usize,
+        start: usize,
+        parent_indent: Option<usize>,
+    ) -> (Node, usize, usize) {
+        let line = &self.lines[index];
+        let is_block = line.content[start - line.content_start..].starts_with(['|', '>']);
+        let continues = |line: &Line| match parent_indent {
+            Some(parent_indent) => line.indent > parent_indent,
+            None => true,
+        };
+
+        let mut last = index;
+        let mut next = index + 1;
+        while next < self.lines.len() {
+            let line = &self.lines[next];
+            if is_block && (line.blank || continues(line)) {
+                if !line.blank {
+                    last = next;
+                }
+            } else if !is_block && (!line.is_significant() || continues(line)) {
+                if line.is_significant() {
+                    last = next;
+                }
+            } else {
+                break;
+            }
+            next += 1;
+        }
+
+        let end = if is_block {
+            self.lines[last].end
+        } else {
+            self.lines[last].content_end()
+        };
+
+        // The values of a flow collection are read recursively too, see `flow_value`
+        let text = &self.source[start..end];
+        if !is_block && text.starts_with(['[', '{']) {
+            if let Err(error) = self.depth.clone().read_brackets(text, false) {
+                self.exceeded.get_or_insert(error);
+                return (Node::Null, last, self.lines.len());
+            }
+        }
+        (Node::Scalar { start, end }, last, last + 1)
+    }
+}
+
+/// Parses the first document of a file under the limits of [`crate::limits`], returning its
+/// lines and its root node.
+fn parse(source: &str) -> Result<(Vec<Line<'_>>, Node), String> {
+    check_input_size(source.len())?;
+
+    let (lines, root) = measure(Phase::Parse, source.len(), || parse_document(source))?;
+    check_node_count(|| root.count())?;
+    Ok((lines, root))
+}
+
+fn parse_document(source: &str) -> Result<(Vec<Line<'_>>, Node), String> {
+    let mut lines = split_lines(source);
+
+    let mut started = false;
+    for line in lines.iter_mut() {
+        let document_marker = line.indent == 0
+            && (line.content == "---" || line.content.starts_with("--- ") || line.content == "...");
+        if document_marker && started {
+            return Err("Several YAML documents are not supported".into());
+        }
+        if document_marker || (!started && line.content.starts_with('%')) {
+            line.content = "";
+        }
+        started |= line.is_significant();
+    }
+
+    let mut parser = Parser {
+        source,
+        lines,
+        depth: Depth::new(),
+        exceeded: None,
+    };
+    let Some(first) = parser.next_significant(0) else {
+        return Ok((parser.lines, Node::Null));
+    };
+    let (root, _last, next) = parser.node(first, None);
+    if let Some(error) = parser.exceeded {
+        return Err(error);
+    }
+
+    if let Some(index) = parser.next_significant(next) {
+        return Err(format!(
+            "Unsupported YAML at line {}: `{}`",
+            index + 1,
+            parser.lines[index].content
+        ));
+    }
+    Ok((parser.lines, root))
+}
+
+/// The smallest indentation of the file, to indent the new values the same way.
+fn indent_unit(lines: &[Line]) -> usize {
+    lines
+        .iter()
+        .filter(|line| {
+            line.is_significant()
+                && line.indent > 0
+                && line.start + line.indent == line.content_start
+        })
+        .map(|line| line.indent)
+        .min()
+        .unwrap_or(DEFAULT_INDENT)
+}
+
+fn newline(source: &str) -> &'static str {
+    if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+fn resolve_plain(text: &str) -> Value {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(integer) = text.parse::<i64>() {
+        return Value::Number(integer.into());
+    }
+    if let Some(hex) = text.strip_prefix("0x") {
+        if let Ok(integer) = i64::from_str_radix(hex, 16) {
+            return Value::Number(integer.into());
+        }
+    }
+    let numeric = text.chars().any(|c| c.is_ascii_digit())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+    if let Some(number) = numeric
+        .then(|| text.parse::<f64>().ok())
+        .flatten()
+        .and_then(Number::from_f64)
+    {
+        return Value::Number(number);
+    }
+    Value::String(text.to_string())
+}
+
+/// Reads a flow value, e.g. `[a, "b", {c: 1}]`, from a position of the text.
+fn flow_value(text: &str, position: &mut usize, in_mapping_key: bool) -> Value {
+    let rest = &text[*position..];
+    *position += rest.len() - rest.trim_start().len();
+    let rest = &text[*position..];
+
+    if rest.starts_with('[') || rest.starts_with('{') {
+        let is_mapping = rest.starts_with('{');
+        let close = if is_mapping { '}' } else { ']' };
+        *position += 1;
+        let mut items = vec![];
+        let mut entries = Map::new();
+
+        loop {
+            let rest = &text[*position..];
+            *position += rest.len() - rest.trim_start().len();
+            match text[*position..].chars().next() {
+                None => break,
+                Some(c) if c == close => {
+                    *position += 1;
+                    break;
+                }
+                // A stray closing bracket of the other kind, which no value starts at
+                Some(',' | ']' | '}') => *position += 1,
+                Some(_) if is_mapping => {
+                    let key = match flow_value(text, position, true) {
+                        Value::String(key) => key,
+                        key => key.to_string(),
+                    };
+                    let rest = &text[*position..];
+                    *position += rest.len() - rest.trim_start().len();
+                    let value = if text[*position..].starts_with(':') {
+                        *position += 1;
+                        flow_value(text, position, false)
+                    } else {
+                        Value::Null
+                    };
+                    entries.insert(key, value);
+                }
+                Some(_) => items.push(flow_value(text, position, false)),
+            }
+        }
+
+        return if is_mapping {
+            Value::Object(entries)
+        } else {
+            Value::Array(items)
+        };
+    }
+
+    if rest.starts_with(['"', '\'']) {
+        let end = quoted_end(rest).unwrap_or(rest.len());
+        *position += end;
+        return unquote(&rest[..end]).map_or(Value::Null, Value::String);
+    }
+
+    let end = rest
+        .char_indices()
+        .find(|(index, c)| {
+            matches!(c, ',' | ']' | '}')
+                || (in_mapping_key
+                    && *c == ':'
+                    && rest[index + 1..].starts_with([' ', ',', '}', ']']))
+        })
+        .map_or(rest.len(), |(index, _)| index);
+    *position += end;
+    resolve_plain(rest[..end].trim())
+}
+
+fn block_scalar_value(raw: &str) -> Value {
+    let mut lines = raw.lines();
+    let header = lines.next().unwrap_or_default();
+    let lines: Vec<&str> = lines.map(|line| line.trim_end_matches('\r')).collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect();
+
+    let text = if header.starts_with('>') {
+        lines.iter().fold(String::new(), |mut text, line| {
+            if line.is_empty() {
+                text.push('\n');
+            } else {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push(' ');
+                }
+                text.push_str(line);
+            }
+            text
+        })
+    } else {
+        lines.join("\n")
+    };
+
+    let header = strip_comment(header);
+    let text = text.trim_end_matches('\n');
+    if header.contains('-') {
+        Value::String(text.to_string())
+    } else {
+        Value::String(format!("{}\n", text))
+    }
+}
+
+fn scalar_value(source: &str, start: usize, end: usize) -> Value {
+    let raw = &source[start..end];
+    if raw.starts_with(['|', '>']) {
+        return block_scalar_value(raw);
+    }
+
+    let text = raw
+        .lines()
+        .map(|line| strip_comment(line).trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.starts_with(['[', '{']) {
+        let mut position = 0;
+        return flow_value(&text, &mut position, false);
+    }
+    if text.starts_with(['"', '\'']) {
+        if let Some(value) = unquote(&text) {
+            return Value::String(value);
+        }
+    }
+    resolve_plain(&text)
+}
+
+fn to_value(source: &str, node: &Node) -> Value {
+    match node {
+        Node::Mapping { entries, .. } => Value::Object(
+            entries
+                .iter()
+                .map(|(key, slot)| (key.clone(), to_value(source, &slot.value)))
+                .collect(),
+        ),
+        Node::Sequence { items, .. } => Value::Array(
+            items
+                .iter()
+                .map(|slot| to_value(source, &slot.value))
+                .collect(),
+        ),
+        Node::Scalar { start, end } => scalar_value(source, *start, *end),
+        Node::Null => Value::Null,
+    }
+}
+
+/// Whether a string has to be quoted to be read back as the same string.
+fn needs_quotes(text: &str) -> bool {
+    text.is_empty()
+        || text.trim() != text
+        || text.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.ends_with(':')
+        || text.chars().any(char::is_control)
+        || !matches!(resolve_plain(text), Value::String(_))
+}
+
+fn render_string(text: &str, quote: Option<char>) -> String {
+    match quote {
+        Some('\'') if !text.chars().any(char::is_control) => {
+            format!("'{}'", text.replace('\'', "''"))
+        }
+        Some(_) => Value::String(text.to_string()).to_string(),
+        None if needs_quotes(text) => Value::String(text.to_string()).to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn render_scalar(value: &Value, quote: Option<char>) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::String(text) => render_string(text, quote),
+        Value::Array(items) if items.is_empty() => "[]".into(),
+        Value::Object(entries) if entries.is_empty() => "{}".into(),
+        value => value.to_string(),
+    }
+}
+
+fn is_collection(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(entries) => !entries.is_empty(),
+        _ => false,
+    }
+}
+
+fn render_entry(key: &str, value: &Value, indent: usize, unit: usize) -> Vec<String> {
+    let key = render_string(key, None);
+    let pad = " ".repeat(indent);
+    if is_collection(value) {
+        let mut lines = vec![format!("{}{}:", pad, key)];
+        lines.extend(render(value, indent + unit, unit, None));
+        lines
+    } else {
+        vec![format!("{}{}: {}", pad, key, render_scalar(value, None))]
+    }
+}
+
+fn render_item(value: &Value, indent: usize, unit: usize, quote: Option<char>) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    if is_collection(value) {
+        let mut lines = render(value, indent + 2, unit, quote);
+        lines[0] = format!("{}- {}", pad, lines[0].trim_start());
+        lines
+    } else {
+        vec![format!("{}- {}", pad, render_scalar(value, quote))]
+    }
+}
+
+/// Renders a collection in block style, one line per entry or item.
+fn render(value: &Value, indent: usize, unit: usize, quote: Option<char>) -> Vec<String> {
+    match value {
+        Value::Object(entries) => entries
+            .iter()
+            .flat_map(|(key, value)| render_entry(key, value, indent, unit))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .flat_map(|item| render_item(item, indent, unit, quote))
+            .collect(),
+        value => vec![format!(
+            "{}{}",
+            " ".repeat(indent),
+            render_scalar(value, quote)
+        )],
+    }
+}
+
+fn apply_edits(source: &str, mut edits: Vec<(usize, usize, String)>) -> String {
+    edits.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    let mut result = source.to_string();
+    for (start, end, text) in edits {
+        result.replace_range(start..end, &text);
+    }
+    result
+}
+
+/// Replaces the value of a slot, keeping the comment on the line of its key or dash.
+fn replace_slot(source: &str, lines: &[Line], slot: &Slot, value: &Value, unit: usize) -> String {
+    let newline = newline(source);
+    let line = &lines[slot.line];
+    let inline_end = match slot.value {
+        Node::Scalar { end, .. } => end,
+        _ => line.content_end().max(slot.marker_end),
+    };
+    let block = match slot.value {
+        Node::Mapping { .. } | Node::Sequence { .. } if slot.last_line > slot.line => {
+            Some((line.end, lines[slot.last_line].end))
+        }
+        _ => None,
+    };
+
+    let (inline, nested) = if is_collection(value) {
+        let indent = if slot.compact {
+            slot.indent + 2
+        } else {
+            slot.indent + unit
+        };
+        let rendered = render(value, indent, unit, None);
+        let (first, rest) = if slot.compact {
+            (format!(" {}", rendered[0].trim_start()), &rendered[1..])
+        } else {
+            (String::new(), &rendered[..])
+        };
+        let nested: String = rest
+            .iter()
+            .map(|line| format!("{}{}", newline, line))
+            .collect();
+        (first, nested)
+    } else {
+        (format!(" {}", render_scalar(value, None)), String::new())
+    };
+
+    let (block_start, block_end) = block.unwrap_or_else(|| {
+        let at = line.end.max(inline_end);
+        (at, at)
+    });
+    apply_edits(
+        source,
+        vec![
+            (slot.marker_end, inline_end, inline),
+            (block_start, block_end, nested),
+        ],
+    )
+}
+
+enum Lookup<'a> {
+    Found(&'a Slot),
+    /// The key at the depth is missing from the mapping, or from the document when it is
+    /// empty.
+    Missing {
+        mapping: Option<&'a Node>,
+        depth: usize,
+    },
+    /// The value of the slot is empty, so the keys from the depth are missing under it.
+    Empty {
+        slot: &'a Slot,
+        depth: usize,
+    },
+}
+
+fn lookup<'a>(root: &'a Node, path: &[&str]) -> Result<Lookup<'a>, String> {
+    let mut node = root;
+    let mut parent: Option<&Slot> = None;
+
+    for (depth, key) in path.iter().enumerate() {
+        let slot = match node {
+            Node::Mapping { entries, .. } => {
+                match entries.iter().find(|(entry_key, _)| entry_key == key) {
+                    Some((_, slot)) => slot,
+                    None => {
+                        return Ok(Lookup::Missing {
+                            mapping: Some(node),
+                            depth,
+                        })
+                    }
+                }
+            }
+            Node::Sequence { items, .. } => {
+                match key.parse::<usize>().ok().and_then(|index| items.get(index)) {
+                    Some(slot) => slot,
+                    None => {
+                        return Err(format!(
+                            "No item `{}` in `{}`",
+                            key,
+                            path[..depth].join(".")
+                        ))
+                    }
+                }
+            }
+            Node::Null => {
+                return Ok(match parent {
+                    Some(slot) => Lookup::Empty { slot, depth },
+                    None => Lookup::Missing {
+                        mapping: None,
+                        depth,
+                    },
+                })
+            }
+            Node::Scalar { .. } => {
+                return Err(format!(
+                    "The value at `{}` is not a mapping",
+                    path[..depth].join(".")
+                ))
+            }
+        };
+
+        if depth == path.len() - 1 {
+            return Ok(Lookup::Found(slot));
+        }
+        node = &slot.value;
+        parent = Some(slot);
+    }
+    unreachable!("the path is not empty")
+}
+
+fn nest(keys: &[&str], value: Value) -> Value {
+    keys.iter().rev().fold(value, |value, key| {
+        Value::Object(Map::from_iter([(key.to_string(), value)]))
+    })
+}
+
+/// Returns the value at a path of keys, e.g. `["packages"]`, as JSON. A key of a sequence is
+/// the index of an item, e.g. `["overrides", "0", "files"]`.
+///
+/// # Returns
+/// * `Ok(Some(String))` - The value as JSON.
+/// * `Ok(None)` - If the path is not in the document.
+/// * `Err(String)` - If the file is not supported, or a value on the path is a scalar.
+///
+/// # Example
+/// ```rust
+/// let source = "packages:\n  # The Phoenix assets\n  - assets\n";
+/// assert_eq!(get(source, &["packages"]), Ok(Some("[\"assets\"]".to_string())));
+/// ```
+pub fn get(source: &str, path: &[&str]) -> Result<Option<String>, String> {
+    let (_lines, root) = parse(source)?;
+    if path.is_empty() {
+        return Ok(Some(to_value(source, &root).to_string()));
+    }
+
+    match lookup(&root, path)? {
+        Lookup::Found(slot) => Ok(Some(to_value(source, &slot.value).to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// Sets the value at a path of keys, creating the mappings missing on the path.
+///
+/// An existing value is replaced, unless it is already equal to the new one, and a missing
+/// key is added after the last entry of its mapping. Collections are written in block style
+/// with the indentation of the file.
+///
+/// # Arguments
+/// * `source` - The YAML document, e.g. `.stylelintrc.yaml`.
+/// * `path` - The keys leading to the value, e.g. `["rules", "color-named"]`.
+/// * `value` - The new value as JSON, e.g. `"never"`.
+///
+/// # Returns
+/// * `Ok(String)` - The updated document.
+/// * `Err(String)` - If the file is not supported, the path is empty, the value is not JSON,
+///   or a value on the path is a scalar.
+///
+/// # Example
+/// ```rust
+/// let source = "# Stylelint\nrules:\n  color-named: always # for now\n";
+/// let result = set(source, &["rules", "color-named"], "\"never\"").unwrap();
+/// assert_eq!(result, "# Stylelint\nrules:\n  color-named: never # for now\n");
+/// ```
+pub fn set(source: &str, path: &[&str], value: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("The path is empty".into());
+    }
+    let value: Value =
+        serde_json::from_str(value).map_err(|err| format!("Invalid JSON value: {}", err))?;
+    let (lines, root) = parse(source)?;
+    let unit = indent_unit(&lines);
+
+    match lookup(&root, path)? {
+        Lookup::Found(slot) => {
+            if to_value(source, &slot.value) == value {
+                return Ok(source.to_string());
+            }
+            Ok(replace_slot(source, &lines, slot, &value, unit))
+        }
+        Lookup::Empty { slot, depth } => {
+            let value = nest(&path[depth..], value);
+            Ok(replace_slot(source, &lines, slot, &value, unit))
+        }
+        Lookup::Missing { mapping, depth } => {
+            let newline = newline(source);
+            let value = nest(&path[depth + 1..], value);
+            match mapping {
+                Some(Node::Mapping {
+                    indent, last_line, ..
+                }) => {
+                    let rendered = render_entry(path[depth], &value, *indent, unit);
+                    let at = lines[*last_line].end;
+                    let text: String = rendered
+                        .iter()
+                        .map(|line| format!("{}{}", newline, line))
+                        .collect();
+                    Ok(apply_edits(source, vec![(at, at, text)]))
+                }
+                _ => {
+                    let rendered = render_entry(path[depth], &value, 0, unit).join(newline);
+                    let separator = if source.is_empty() || source.ends_with('\n') {
+                        ""
+                    } else {
+                        newline
+                    };
+                    Ok(format!("{}{}{}{}", source, separator, rendered, newline))
+                }
+            }
+        }
+    }
+}
+
+/// Appends a value to the sequence at a path of keys, e.g. a package to the `packages` of
+/// `pnpm-workspace.yaml`. The sequence is created when it is missing or empty, and a value
+/// it already has leaves the document untouched.
+///
+/// A new string is quoted like the last item of the sequence, or only when it has to be.
+///
+/// # Returns
+/// * `Ok(String)` - The updated document.
+/// * `Err(String)` - If the file is not supported, the path is empty, the value is not JSON,
+///   or the value at the path is not a sequence.
+///
+/// # Example
+/// ```rust
+/// let source = "packages:\n  - \"assets\" # Phoenix\n";
+/// let result = append(source, &["packages"], "\"deps/*\"").unwrap();
+/// assert_eq!(result, "packages:\n  - \"assets\" # Phoenix\n  - \"deps/*\"\n");
+/// ```
+pub fn append(source: &str, path: &[&str], value: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("The path is empty".into());
+    }
+    let item: Value =
+        serde_json::from_str(value).map_err(|err| format!("Invalid JSON value: {}", err))?;
+    let (lines, root) = parse(source)?;
+    let unit = indent_unit(&lines);
+
+    let Lookup::Found(slot) = lookup(&root, path)? else {
+        return set(source, path, &Value::Array(vec![item]).to_string());
+    };
+
+    match &slot.value {
+        Node::Sequence {
+            indent,
+            items,
+            last_line,
+        } => {
+            if items
+                .iter()
+                .any(|slot| to_value(source, &slot.value) == item)
+            {
+                return Ok(source.to_string());
+            }
+            let quote = items.last().and_then(|slot| match slot.value {
+                Node::Scalar { start, .. } => source[start..]
+                    .chars()
+                    .next()
+                    .filter(|c| matches!(c, '"' | '\'')),
+                _ => None,
+            });
+            let newline = newline(source);
+            let text: String = render_item(&item, *indent, unit, quote)
+                .iter()
+                .map(|line| format!("{}{}", newline, line))
+                .collect();
+            let at = lines[*last_line].end;
+            Ok(apply_edits(source, vec![(at, at, text)]))
+        }
+        Node::Scalar { start, end } if source[*start..].starts_with('[') => {
+            let Value::Array(items) = to_value(source, &slot.value) else {
+                return Err(format!(
+                    "The value at `{}` is not a sequence",
+                    path.join(".")
+                ));
+            };
+            if items.contains(&item) {
+                return Ok(source.to_string());
+            }
+            let close = source[*start..*end]
+                .rfind(']')
+                .map(|index| start + index)
+                .ok_or_else(|| format!("The value at `{}` is not a sequence", path.join(".")))?;
+            let rendered = if is_collection(&item) {
+                item.to_string()
+            } else {
+                render_scalar(&item, None)
+            };
+            let text = if items.is_empty() {
+                rendered
+            } else {
+                let before = source[..close].trim_end();
+                let separator = if before.ends_with(',') { " " } else { ", " };
+                return Ok(apply_edits(
+                    source,
+                    vec![(before.len(), close, format!("{}{}", separator, rendered))],
+                ));
+            };
+            Ok(apply_edits(source, vec![(start + 1, close, text)]))
+        }
+        Node::Null => set(source, path, &Value::Array(vec![item]).to_string()),
+        _ => Err(format!(
+            "The value at `{}` is not a sequence",
+            path.join(".")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::{limits, take_exceeded, Limit};
+
+    fn stylelintrc() -> &'static str {
+        r#"# Generated by the installer
+extends:
+  - stylelint-config-standard # the defaults
+rules:
+  color-named: always
+  selector-class-pattern: "^[a-z]+$"
+  at-rule-no-unknown: [true, {ignoreAtRules: [tailwind, apply]}]
+overrides:
+  - files: ["*.scss"]
+    customSyntax: postcss-scss
+description: |
+  Lints the CSS
+  of the assets
+"#
+    }
+
+    #[test]
+    fn test_get() {
+        let source = stylelintrc();
+        assert_eq!(
+            get(source, &["extends"]),
+            Ok(Some("[\"stylelint-config-standard\"]".to_string()))
+        );
+        assert_eq!(
+            get(source, &["rules", "at-rule-no-unknown"]),
+            Ok(Some(
+                "[true,{\"ignoreAtRules\":[\"tailwind\",\"apply\"]}]".to_string()
+            ))
+        );
+        assert_eq!(
+            get(source, &["overrides", "0", "files"]),
+            Ok(Some("[\"*.scss\"]".to_string()))
+        );
+        assert_eq!(
+            get(source, &["description"]),
+            Ok(Some("\"Lints the CSS\\nof the assets\\n\"".to_string()))
+        );
+        assert_eq!(get(source, &["plugins"]), Ok(None));
+        assert!(get(source, &["rules", "color-named", "value"]).is_err());
+        assert!(get("a: 1\n---\nb: 2\n", &["a"]).is_err());
+
+        // Stray brackets are skipped, and a key may end with any character
+        assert_eq!(get("a: [1, }, 2]\n", &["a"]), Ok(Some("[1,2]".to_string())));
+        assert_eq!(get("é: 1\n", &["é"]), Ok(Some("1".to_string())));
+    }
+
+    #[test]
+    fn test_set() {
+        let source = stylelintrc();
+
+        let result = set(source, &["rules", "color-named"], "\"never\"").unwrap();
+        assert!(result.contains("rules:\n  color-named: never\n  selector-class-pattern"));
+        assert_eq!(
+            set(&result, &["rules", "color-named"], "\"never\"").unwrap(),
+            result
+        );
+
+        let result = set(source, &["rules", "declaration-no-important"], "true").unwrap();
+        assert!(result.contains(
+            "at-rule-no-unknown: [true, {ignoreAtRules: [tailwind, apply]}]\n  declaration-no-important: true\noverrides:"
+        ));
+
+        let result = set(source, &["extends"], "[\"stylelint-config-recommended\"]").unwrap();
+        assert!(result.starts_with(
+            "# Generated by the installer\nextends:\n  - stylelint-config-recommended\nrules:"
+        ));
+
+        let result = set(
+            source,
+            &["overrides", "0", "rules"],
+            "{\"color-named\": null}",
+        )
+        .unwrap();
+        assert!(result.contains(
+            "    customSyntax: postcss-scss\n    rules:\n      color-named: null\ndescription:"
+        ));
+
+        let result = set("", &["plugins", "scss"], "[\"stylelint-scss\"]").unwrap();
+        assert_eq!(result, "plugins:\n  scss:\n    - stylelint-scss\n");
+        assert_eq!(
+            get(&result, &["plugins", "scss", "0"]),
+            Ok(Some("\"stylelint-scss\"".into()))
+        );
+
+        let result = set("plugins:\nrules: {}\n", &["plugins", "scss"], "1").unwrap();
+        assert_eq!(result, "plugins:\n  scss: 1\nrules: {}\n");
+    }
+
+    #[test]
+    fn test_append() {
+        let source =
+            "packages:\n  - 'assets'\n  # Vendored\n  - 'deps/*'\n\ncatalog:\n  react: ^18\n";
+
+        let result = append(source, &["packages"], "\"apps/*\"").unwrap();
+        assert_eq!(
+            result,
+            "packages:\n  - 'assets'\n  # Vendored\n  - 'deps/*'\n  - 'apps/*'\n\ncatalog:\n  react: ^18\n"
+        );
+        assert_eq!(
+            append(&result, &["packages"], "\"apps/*\"").unwrap(),
+            result
+        );
+
+        let result = append(source, &["onlyBuiltDependencies"], "\"esbuild\"").unwrap();
+        assert!(result.ends_with("catalog:\n  react: ^18\nonlyBuiltDependencies:\n  - esbuild\n"));
+
+        let result = append(
+            "plugins: [stylelint-scss]\n",
+            &["plugins"],
+            "\"@stylistic/stylelint-plugin\"",
+        );
+        assert_eq!(
+            result.unwrap(),
+            "plugins: [stylelint-scss, \"@stylistic/stylelint-plugin\"]\n"
+        );
+        assert_eq!(
+            append("plugins: []\n", &["plugins"], "\"a\"").unwrap(),
+            "plugins: [a]\n"
+        );
+
+        let result = append(
+            "overrides:\n  - files: a\n",
+            &["overrides"],
+            "{\"files\": \"b\", \"x\": 1}",
+        );
+        assert_eq!(
+            result.unwrap(),
+            "overrides:\n  - files: a\n  - files: b\n    x: 1\n"
+        );
+        assert!(append(source, &["catalog"], "\"vue\"").is_err());
+    }
+
+    #[test]
+    fn test_depth() {
+        let max = limits().max_depth;
+        let mapping = |depth: usize| -> String {
+            (0..depth)
+                .map(|level| format!("{}a:\n", "  ".repeat(level)))
+                .collect()
+        };
+        let flow = |depth: usize| format!("a: {}1{}\n", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(get(&mapping(max), &["a"]).is_ok());
+        assert_eq!(
+            get(&mapping(max + 1), &["a"]),
+            Err(format!(
+                "The nesting depth of {} exceeds the limit of {}",
+                max + 1,
+                max
+            ))
+        );
+        // The flow collection is nested in the mapping
+        assert!(get(&flow(max - 1), &["a"]).is_ok());
+        assert!(get(&flow(max), &["a"]).is_err());
+        assert_eq!(
+            take_exceeded().map(|exceeded| exceeded.limit),
+            Some(Limit::Depth)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::encode_response;
+use crate::parsers::yaml::config::*;
+
+use rustler::{Env, NifResult, Term};
+
+fn path_of(path: &[String]) -> Vec<&str> {
+    path.iter().map(|key| key.as_str()).collect()
+}
+
+#[rustler::nif]
+pub fn get_yaml_value_nif(
+    env: Env,
+    file_content: String,
+    path: Vec<String>,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::get_yaml_value_nif();
+
+        match get(&file_content, &path_of(&path)) {
+            Ok(Some(value)) => encode_response(env, atoms::ok(), fn_atom, value),
+            Ok(None) => encode_response(env, atoms::error(), fn_atom, "The path was not found"),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn set_yaml_value_nif(
+    env: Env,
+    file_content: String,
+    path: Vec<String>,
+    value: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::set_yaml_value_nif();
+
+        match set(&file_content, &path_of(&path), &value) {
+            Ok(updated) => encode_response(env, atoms::ok(), fn_atom, updated),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
+
+#[rustler::nif]
+pub fn append_yaml_value_nif(
+    env: Env,
+    file_content: String,
+    path: Vec<String>,
+    value: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::append_yaml_value_nif();
+
+        match append(&file_content, &path_of(&path), &value) {
+            Ok(updated) => encode_response(env, atoms::ok(), fn_atom, updated),
+            Err(error_msg) => encode_response(env, atoms::error(), fn_atom, error_msg),
+        }
+    })
+}
@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Transforms compiled in by other crates and called by name.
+//!
+//! With the `plugins` feature, a crate building its NIF on top of this one can implement
+//! [`NamedTransform`] and register it with [`register_transform!`]; the transform is then
+//! callable from Elixir through `run_named_transform_nif`, next to the built-in ones:
+//!
+//! ```rust
+//! struct AddBanner;
+//!
+//! impl NamedTransform for AddBanner {
+//!     fn name(&self) -> &'static str {
+//!         "my_ext:add_banner"
+//!     }
+//!
+//!     fn run(&self, source: &str, args: &serde_json::Value) -> Result<String, String> {
+//!         Ok(format!("// {}\n{}", args["text"].as_str().unwrap_or(""), source))
+//!     }
+//! }
+//!
+//! igniter_js::register_transform!(AddBanner);
+//! ```
+//!
+//! Without the feature the registry is always empty, and calling a transform by name is
+//! an error.
+
+use serde_json::Value;
+
+/// A transform of a source that can be called by name.
+pub trait NamedTransform: Sync {
+    /// The name the transform is called by, prefixed with the name of the crate by
+    /// convention, e.g. `"my_ext:add_banner"`.
+    fn name(&self) -> &'static str;
+
+    /// Transforms `source` with the arguments given on the Elixir side.
+    fn run(&self, source: &str, args: &Value) -> Result<String, String>;
+}
+
+/// A transform submitted to the registry, see [`register_transform!`].
+#[cfg(feature = "plugins")]
+pub struct TransformRegistration(pub &'static dyn NamedTransform);
+
+#[cfg(feature = "plugins")]
+inventory::collect!(TransformRegistration);
+
+#[cfg(feature = "plugins")]
+#[doc(hidden)]
+pub use inventory;
+
+/// Registers a value implementing [`NamedTransform`], from any crate linked in the NIF.
+#[cfg(feature = "plugins")]
+#[macro_export]
+macro_rules! register_transform {
+    ($transform:expr) => {
+        $crate::plugins::inventory::submit! {
+            $crate::plugins::TransformRegistration(&$transform)
+        }
+    };
+}
+
+#[cfg(feature = "plugins")]
+fn registered() -> impl Iterator<Item = &'static dyn NamedTransform> {
+    inventory::iter::<TransformRegistration>
+        .into_iter()
+        .map(|registration| registration.0)
+}
+
+#[cfg(not(feature = "plugins"))]
+fn registered() -> impl Iterator<Item = &'static dyn NamedTransform> {
+    std::iter::empty()
+}
+
+/// The names of the registered transforms, sorted.
+pub fn list_named_transforms() -> Vec<String> {
+    let mut names: Vec<String> = registered().map(|t| t.name().to_string()).collect();
+    names.sort();
+    names
+}
+
+/// Runs the registered transform of the given name.
+///
+/// # Arguments
+/// * `source` - The source to transform.
+/// * `name` - The name of the transform.
+/// * `json_args` - The arguments of the transform, as a JSON document.
+///
+/// # Returns
+/// * `Ok(String)` - The transformed source.
+/// * `Err(String)` - If no transform has this name, the arguments are not valid JSON, or
+///   the transform fails.
+pub fn run_named_transform(source: &str, name: &str, json_args: &str) -> Result<String, String> {
+    let transform = registered()
+        .find(|transform| transform.name() == name)
+        .ok_or_else(|| format!("No transform is registered as `{}`", name))?;
+    let args: Value = match json_args.trim() {
+        "" => Value::Null,
+        json_args => serde_json::from_str(json_args)
+            .map_err(|error| format!("Invalid arguments for `{}`: {}", name, error))?,
+    };
+
+    transform.run(source, &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "plugins")]
+    struct AddBanner;
+
+    #[cfg(feature = "plugins")]
+    impl NamedTransform for AddBanner {
+        fn name(&self) -> &'static str {
+            "test:add_banner"
+        }
+
+        fn run(&self, source: &str, args: &Value) -> Result<String, String> {
+            let text = args["text"]
+                .as_str()
+                .ok_or("The `text` argument is required")?;
+            Ok(format!("// {}\n{}", text, source))
+        }
+    }
+
+    #[cfg(feature = "plugins")]
+    crate::register_transform!(AddBanner);
+
+    #[test]
+    fn test_run_named_transform() {
+        assert!(run_named_transform("let a = 1;", "test:unknown", "{}").is_err());
+
+        #[cfg(feature = "plugins")]
+        {
+            assert!(list_named_transforms().contains(&"test:add_banner".to_string()));
+            assert_eq!(
+                run_named_transform("let a = 1;", "test:add_banner", r#"{"text": "Hi"}"#),
+                Ok("// Hi\nlet a = 1;".to_string())
+            );
+            assert!(run_named_transform("let a = 1;", "test:add_banner", "{").is_err());
+            assert!(run_named_transform("let a = 1;", "test:add_banner", "").is_err());
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::atoms;
+use crate::call_options::{run_call, CallOptions};
+use crate::helpers::{encode_edit, encode_response};
+use crate::plugins::{list_named_transforms, run_named_transform};
+
+use rustler::{Env, NifResult, Term};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn run_named_transform_nif(
+    env: Env,
+    file_content: String,
+    name: String,
+    json_args: String,
+    call_options: CallOptions,
+) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::run_named_transform_nif();
+
+        let result = run_named_transform(&file_content, &name, &json_args);
+
+        encode_edit(env, fn_atom, &file_content, result)
+    })
+}
+
+#[rustler::nif]
+pub fn list_named_transforms_nif(env: Env, call_options: CallOptions) -> NifResult<Term> {
+    run_call(call_options, || {
+        let fn_atom = atoms::list_named_transforms_nif();
+
+        encode_response(env, atoms::ok(), fn_atom, list_named_transforms())
+    })
+}
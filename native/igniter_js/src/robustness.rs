@@ -0,0 +1,357 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Property tests feeding the entry points of the NIFs with arbitrary sources, checking that
+//! they fail with an error. rustler turns a panic in a NIF into an exception, which fails the
+//! caller instead of returning the reason, and a stack overflow, which nothing catches,
+//! aborts the whole VM.
+//!
+//! The sources are generated four ways, as a random string seldom gets past the first
+//! character of a parser:
+//!
+//! - any string, e.g. with control characters or a byte order mark,
+//! - a soup of the tokens of JavaScript, CSS, JSON and YAML, reaching the error recovery of
+//!   the parsers and the unusual but valid sources,
+//! - the source of a case of [`crate::stability`] with a slice replaced by tokens, which
+//!   keeps most of it valid so the transforms themselves run on odd input,
+//! - a source nested about as deep as [`DEFAULT_MAX_DEPTH`] or far deeper, run on a stack
+//!   of [`crate::limits::CALL_STACK_SIZE`] like a NIF call.
+//!
+//! The number of cases of each property is 64, or `PROPTEST_CASES` for a longer run, e.g.
+//! `PROPTEST_CASES=10000 cargo test robustness`. A failure is shrunk to a minimal source,
+//! which belongs in the tests of the transform once it is fixed.
+
+use crate::core::run_transform;
+use crate::limits::{on_call_stack, DEFAULT_MAX_DEPTH};
+use crate::parsers::css::{dialect, formatter as css_formatter};
+use crate::parsers::javascript::{
+    ast, ast_json, bindings, formatter as js_formatter, helpers, minified, phoenix, region, unused,
+};
+use crate::stability::cases;
+use proptest::prelude::*;
+use proptest::test_runner::Config;
+
+/// The name of an entry point, and a call of it on a source.
+type EntryPoint = (&'static str, fn(&str));
+
+/// The entry points of the NIFs beside the ones of [`crate::core::TRANSFORMS`], with fixed
+/// arguments besides the source.
+const SOURCE_ENTRY_POINTS: &[EntryPoint] = &[
+    ("statistics_from_ast", |source| {
+        let _ = ast::statistics_with_locations_from_ast(source);
+    }),
+    ("insert_ast_at_index", |source| {
+        let _ = ast::insert_ast_at_index(source, "const a = \"é\";", 0);
+    }),
+    ("replace_ast_at_index", |source| {
+        let _ = ast::replace_ast_at_index("let a = \"é\";\nlet b = 1;\n", source, 1);
+    }),
+    ("list_top_level_bindings", |source| {
+        let _ = bindings::list_top_level_bindings(source);
+    }),
+    ("list_managed_regions", |source| {
+        let _ = region::list_managed_regions(source);
+    }),
+    ("list_live_sockets", |source| {
+        let _ = phoenix::list_live_sockets_from_ast(source);
+    }),
+    ("list_hooks", |source| {
+        let _ = phoenix::list_hooks_from_ast(source);
+    }),
+    ("analyze_unused", |source| {
+        let _ = unused::analyze_unused(source);
+    }),
+    ("convert_ast_to_estree", |source| {
+        let _ = ast_json::convert_ast_to_estree(source);
+    }),
+    ("detect_module_kind", |source| {
+        helpers::detect_module_kind(source);
+    }),
+    ("is_probably_minified", |source| {
+        minified::is_probably_minified(source);
+    }),
+    ("is_js_formatted", |source| {
+        let _ = js_formatter::is_formatted(source);
+    }),
+    ("is_css_formatted", |source| {
+        let _ = css_formatter::is_formatted(source);
+    }),
+    ("detect_css_dialect", |source| {
+        dialect::detect_dialect(source);
+    }),
+];
+
+const TOKENS: &[&str] = &[
+    // JavaScript
+    "import",
+    "export",
+    "default",
+    "from",
+    "as",
+    "let",
+    "const",
+    "function",
+    "class",
+    "new",
+    "return",
+    "=>",
+    "async",
+    "await",
+    "yield",
+    "liveSocket",
+    "LiveSocket",
+    "Socket",
+    "hooks",
+    "Hooks",
+    "window",
+    "\"phoenix\"",
+    "'./hooks'",
+    "...",
+    "?.",
+    "??",
+    "=",
+    "+",
+    "*",
+    ".",
+    ":",
+    "?",
+    "!",
+    "#",
+    "`",
+    "${",
+    "/",
+    "/*",
+    "*/",
+    "//",
+    "\\",
+    "<div>",
+    "</div>",
+    "0x1f",
+    "1e3",
+    "\"",
+    "'",
+    // CSS
+    "@import",
+    "@media",
+    "@theme",
+    "@layer",
+    "@apply",
+    "@tailwind",
+    ":root",
+    ".btn",
+    "--x",
+    "!important",
+    "url(",
+    "var(--x)",
+    // JSON and YAML
+    "null",
+    "true",
+    "[",
+    "]",
+    "{",
+    "}",
+    "(",
+    ")",
+    ",",
+    ";",
+    "- ",
+    "key: ",
+    "|",
+    ">",
+    "&a",
+    "*a",
+    "---",
+    // Whitespace and text
+    " ",
+    "  ",
+    "\t",
+    "\n",
+    "\r\n",
+    "\r",
+    "\u{feff}",
+    "\u{2028}",
+    "é",
+    "🔥",
+    "\0",
+];
+
+/// The opening and the closing of a level of the nested sources, in the syntax of each parser.
+const LEVELS: &[(&str, &str)] = &[
+    ("[", "]"),
+    ("(", ")"),
+    ("f(", ")"),
+    ("{a: ", "}"),
+    ("{\"a\": ", "}"),
+    ("function f() {", "}"),
+    ("if (a) {", "}"),
+    ("`${", "}`"),
+    ("() => (", ")"),
+    ("a {", "}"),
+    (":is(", ")"),
+    ("@media (a) {", "}"),
+    ("a: ", ""),
+    ("!", ""),
+    ("- ", ""),
+];
+
+fn config() -> Config {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(64);
+    Config {
+        cases,
+        failure_persistence: None,
+        ..Config::default()
+    }
+}
+
+fn token_soup() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(TOKENS), 0..48).prop_map(|tokens| tokens.concat())
+}
+
+/// Any string, or a soup of tokens.
+fn source() -> impl Strategy<Value = String> {
+    prop_oneof![any::<String>(), token_soup()]
+}
+
+/// A source with the given levels, cycling through them, e.g. `[f([f(1)])]`.
+fn nest(levels: &[(&str, &str)], depth: usize, closed: bool) -> String {
+    let mut source = String::new();
+    for level in 0..depth {
+        source.push_str(levels[level % levels.len()].0);
+    }
+    source.push('1');
+    for level in (0..depth).rev().filter(|_| closed) {
+        source.push_str(levels[level % levels.len()].1);
+    }
+    source
+}
+
+/// A YAML mapping with the given depth, each key nested in the previous one.
+fn nested_mapping(depth: usize) -> String {
+    (0..depth)
+        .map(|level| format!("{}a:\n", "  ".repeat(level)))
+        .collect()
+}
+
+/// A source nested about as deep as the limit or far deeper, unclosed at times.
+fn nested() -> impl Strategy<Value = String> {
+    let depth = prop_oneof![
+        DEFAULT_MAX_DEPTH - 4..DEFAULT_MAX_DEPTH + 4,
+        0..8 * DEFAULT_MAX_DEPTH,
+    ];
+    let levels = prop::collection::vec(prop::sample::select(LEVELS), 1..4);
+    prop_oneof![
+        (levels, depth.clone(), any::<bool>())
+            .prop_map(|(levels, depth, closed)| nest(&levels, depth, closed)),
+        depth.prop_map(nested_mapping),
+    ]
+}
+
+/// Runs the entry points of [`SOURCE_ENTRY_POINTS`] and the transforms of the cases, by
+/// index, on a source.
+fn run_entry_point(index: usize, source: &str) -> Result<(), String> {
+    match SOURCE_ENTRY_POINTS.get(index) {
+        Some((_, run)) => run(source),
+        None => {
+            let case = &cases()[index - SOURCE_ENTRY_POINTS.len()];
+            if let Err(error) = run_transform(case.transform, source, &case.args) {
+                if error.is_empty() {
+                    return Err(format!("{} failed without a message", case.transform));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The source of a case with the slice from `start` of `len` chars replaced by tokens.
+fn mutated(source: &str, start: usize, len: usize, tokens: &str) -> String {
+    let mut chars: Vec<char> = source.chars().collect();
+    let start = start % (chars.len() + 1);
+    let end = (start + len).min(chars.len());
+    chars.splice(start..end, tokens.chars());
+    chars.into_iter().collect()
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn test_transforms_do_not_panic(case in 0..cases().len(), source in source()) {
+        let case = &cases()[case];
+        if let Err(error) = run_transform(case.transform, &source, &case.args) {
+            prop_assert!(!error.is_empty(), "{} failed without a message", case.transform);
+        }
+    }
+
+    #[test]
+    fn test_transforms_of_mutated_sources_do_not_panic(
+        case in 0..cases().len(),
+        start in any::<usize>(),
+        len in 0usize..24,
+        tokens in token_soup(),
+    ) {
+        let case = &cases()[case];
+        let source = mutated(case.source, start, len, &tokens);
+        if let Err(error) = run_transform(case.transform, &source, &case.args) {
+            prop_assert!(!error.is_empty(), "{} failed without a message", case.transform);
+        }
+    }
+
+    #[test]
+    fn test_entry_points_do_not_panic(
+        entry_point in 0..SOURCE_ENTRY_POINTS.len(),
+        source in source(),
+        sample in 0..cases().len(),
+        start in any::<usize>(),
+        len in 0usize..24,
+    ) {
+        let (_, run) = SOURCE_ENTRY_POINTS[entry_point];
+        run(&source);
+        run(&mutated(cases()[sample].source, start, len, &source));
+    }
+
+    #[test]
+    fn test_nested_sources_do_not_overflow(
+        entry_point in 0..SOURCE_ENTRY_POINTS.len() + cases().len(),
+        source in nested(),
+    ) {
+        let result = on_call_stack(|| run_entry_point(entry_point, &source));
+        prop_assert!(result.is_ok(), "{:?}", result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every entry point runs on the sources nested as deep as the default limit allows,
+    /// within the stack of a NIF call, in a debug build whose frames are several times the
+    /// size of the ones of a release build.
+    #[test]
+    fn test_depth_fits_the_call_stack() {
+        let sources = LEVELS
+            .iter()
+            .map(|level| nest(&[*level], DEFAULT_MAX_DEPTH, true))
+            .chain([nested_mapping(DEFAULT_MAX_DEPTH)]);
+
+        for source in sources {
+            on_call_stack(|| {
+                for index in 0..SOURCE_ENTRY_POINTS.len() + cases().len() {
+                    assert_eq!(run_entry_point(index, &source), Ok(()));
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn test_mutated() {
+        assert_eq!(mutated("let a = 1;", 4, 1, "b"), "let b = 1;");
+        assert_eq!(mutated("é", 1, 10, "!"), "é!");
+        assert_eq!(mutated("ab", 5, 1, "!"), "ab!");
+    }
+}
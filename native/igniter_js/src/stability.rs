@@ -0,0 +1,437 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Checks that the transforms give byte-identical results when they are run again on the
+//! same input, as Igniter only knows that an installer converged when a second run leaves
+//! the files as they are.
+//!
+//! Each case is run several times on several threads: the hash maps of the standard library
+//! are seeded per thread, so a result depending on their iteration order, or on the time,
+//! differs between the runs. A new transform of [`crate::core::TRANSFORMS`] is added to
+//! [`cases`].
+
+use crate::core::{run_transform, TRANSFORMS};
+use crate::fixtures::fixtures;
+use serde_json::{json, Value};
+
+/// The number of threads a case is run on.
+const THREADS: usize = 4;
+/// The number of runs of a case on each thread.
+const RUNS: usize = 3;
+
+const APP_JS: &str = r#"// Phoenix app
+import "phoenix_html";
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+import topbar from "../vendor/topbar";
+
+const CopyHook = {
+  mounted() {
+    this.el.addEventListener("click", () => navigator.clipboard.writeText(this.el.innerText));
+  },
+};
+
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+let liveSocket = new LiveSocket("/live", Socket, {
+  longPollFallbackMs: 2500,
+  params: { _csrf_token: csrfToken },
+  hooks: { CopyHook },
+});
+
+export { liveSocket };
+"#;
+
+const APP_CSS: &str = r#"@import "tailwindcss";
+
+:root,
+.dark {
+  --primary-color: #fd4f00;
+  --radius: 4px;
+}
+
+.btn { padding: 0; user-select: none; }
+
+@media (min-width: 640px) {
+  .card { display: flex; }
+}
+"#;
+
+const BOILERPLATE_JS: &str = r##"import topbar from "../vendor/topbar";
+
+let Hooks = { Copy: CopyHook, Chart: ChartHook };
+let liveSocket = new LiveSocket("/live", Socket, { hooks: Hooks });
+topbar.config({ barColors: { 0: "#29d" }, shadowColor: "rgba(0, 0, 0, .3)" });
+
+if (process.env.NODE_ENV === "development") {
+  liveSocket.enableDebug();
+}
+if ("serviceWorker" in navigator) {
+  navigator.serviceWorker.register("/sw.js");
+}
+window.liveSocket = liveSocket;
+"##;
+
+const TAILWIND_CSS: &str = r#"@import "tailwindcss";
+@source "../js";
+
+@theme {
+  --color-brand: #fd4f00;
+}
+
+@layer base {
+  h1 { font-size: 2rem; }
+}
+"#;
+
+const PACKAGE_JSON: &str = r#"{
+  // The assets of the app
+  "name": "app",
+  "dependencies": { "topbar": "^3.0.0", "phoenix": "file:../deps/phoenix" }
+}
+"#;
+
+const STYLELINTRC: &str = r#"# Generated by the installer
+extends:
+  - stylelint-config-standard
+rules:
+  color-named: always
+  selector-class-pattern: "^[a-z]+$"
+"#;
+
+/// A transform with its source and arguments, as given to [`run_transform`].
+pub struct Case {
+    pub transform: &'static str,
+    pub source: &'static str,
+    pub args: Value,
+}
+
+fn case(transform: &'static str, source: &'static str, args: Value) -> Case {
+    Case {
+        transform,
+        source,
+        args,
+    }
+}
+
+/// A case of every built-in transform.
+pub fn cases() -> Vec<Case> {
+    vec![
+        case(
+            "add_alpine_data",
+            APP_JS,
+            json!({ "name": "dropdown", "value": "() => ({ open: false })" }),
+        ),
+        case(
+            "add_export_to_ast",
+            APP_JS,
+            json!({ "export_lines": "export { csrfToken, CopyHook };" }),
+        ),
+        case(
+            "append_yaml_value",
+            STYLELINTRC,
+            json!({ "path": ["extends"], "value": "stylelint-config-tailwindcss" }),
+        ),
+        case(
+            "apply_codemod",
+            APP_JS,
+            json!({ "pattern": "navigator.clipboard.writeText($TEXT)", "replacement": "copy($TEXT)" }),
+        ),
+        case(
+            "compose_on_before_el_updated_to_ast",
+            APP_JS,
+            json!({ "snippet": "if (from._x_dataStack) { window.Alpine.clone(from, to); }" }),
+        ),
+        case("ensure_alpine_import", APP_JS, Value::Null),
+        case("ensure_alpine_start", APP_JS, Value::Null),
+        case(
+            "ensure_colocated_hooks_to_ast",
+            APP_JS,
+            json!({ "app_name": "my_app" }),
+        ),
+        case("ensure_live_socket_boilerplate", APP_JS, Value::Null),
+        case("ensure_live_socket_exposed", APP_JS, Value::Null),
+        case(
+            "ensure_service_worker_registration",
+            APP_JS,
+            json!({ "path": "/sw.js", "options": "{ scope: \"/\" }" }),
+        ),
+        case(
+            "extend_hook_object_to_ast",
+            APP_JS,
+            json!({ "names": ["PasteHook", "Chart", "PasteHook", "Modal", "Tooltip"] }),
+        ),
+        case(
+            "extend_live_socket_option_to_ast",
+            APP_JS,
+            json!({ "option": "uploaders", "entries": ["S3", "Local"], "target": "liveSocket" }),
+        ),
+        case(
+            "extend_live_socket_params_to_ast",
+            APP_JS,
+            json!({ "params": { "locale": "navigator.language", "width": "window.innerWidth" } }),
+        ),
+        case(
+            "extend_var_object_property_by_names_to_ast",
+            APP_JS,
+            json!({ "var_name": "CopyHook", "object_names": ["updated", "destroyed"] }),
+        ),
+        case("find_tailwind_layers", TAILWIND_CSS, Value::Null),
+        case("format_css", APP_CSS, Value::Null),
+        case(
+            "format_js",
+            APP_JS,
+            json!({ "template_literals": "embedded" }),
+        ),
+        case(
+            "generate_hooks_index",
+            "",
+            json!({ "file_names": ["copy_hook.js", "charts/line_chart.js"] }),
+        ),
+        case(
+            "get_css_declarations",
+            APP_CSS,
+            json!({ "selector": ".dark" }),
+        ),
+        case(
+            "get_jsonc_value",
+            PACKAGE_JSON,
+            json!({ "path": ["dependencies"] }),
+        ),
+        case("get_yaml_value", STYLELINTRC, json!({ "path": [] })),
+        case("has_css_rule", APP_CSS, json!({ "selector": ".card" })),
+        case(
+            "insert_css_rule_in_media",
+            APP_CSS,
+            json!({ "query": "(min-width: 640px)", "rule": ".nav { gap: 1rem; }" }),
+        ),
+        case(
+            "insert_import_to_ast",
+            APP_JS,
+            json!({
+                "import_lines": "import { Chart, Modal } from \"./hooks\";\nimport \"./app.css\";"
+            }),
+        ),
+        case(
+            "insert_tailwind_directive",
+            TAILWIND_CSS,
+            json!({ "kind": "plugin", "params": "\"@tailwindcss/forms\"" }),
+        ),
+        case(
+            "insert_tailwind_layer_directive",
+            TAILWIND_CSS,
+            json!({ "layer": "components" }),
+        ),
+        case(
+            "insert_tailwind_layer_rule",
+            TAILWIND_CSS,
+            json!({ "layer": "components", "rule": ".btn { padding: 0; }" }),
+        ),
+        case(
+            "merge_object_literal",
+            APP_JS,
+            json!({ "var_name": "CopyHook", "json_spec": { "b": 1, "a": { "d": [true], "c": null } } }),
+        ),
+        case(
+            "merge_topbar_config",
+            BOILERPLATE_JS,
+            json!({ "json_spec": { "barColors": { "0": "#fd4f00", "1": "#000" } }, "overwrite": true }),
+        ),
+        case("minify_css", APP_CSS, Value::Null),
+        case("minify_js", APP_JS, json!({ "target": "es2015" })),
+        case(
+            "move_css_rule",
+            APP_CSS,
+            json!({ "selector": ".btn", "anchor": ":root, .dark", "position": "before" }),
+        ),
+        case(
+            "move_declaration_between_sources",
+            APP_JS,
+            json!({ "target": "", "name": "CopyHook", "import_from": "./hooks/copy" }),
+        ),
+        case(
+            "organize_imports",
+            APP_JS,
+            json!({ "separate_groups": true }),
+        ),
+        case(
+            "put_css_custom_properties",
+            APP_CSS,
+            json!({ "variables": { "--radius": "8px", "--accent": "#000", "--gap": "1rem" } }),
+        ),
+        case(
+            "put_tailwind_theme_variables",
+            TAILWIND_CSS,
+            json!({ "variables": { "--color-brand": "#000", "--font-display": "Inter", "--radius": "4px" } }),
+        ),
+        case(
+            "register_alpine_plugin",
+            APP_JS,
+            json!({ "plugin": "focus", "source": "@alpinejs/focus" }),
+        ),
+        case(
+            "remove_env_guard",
+            BOILERPLATE_JS,
+            json!({ "pattern": "liveSocket.enableDebug();" }),
+        ),
+        case(
+            "remove_export_from_ast",
+            APP_JS,
+            json!({ "export_lines": "export { liveSocket };" }),
+        ),
+        case(
+            "remove_import_from_ast",
+            APP_JS,
+            json!({ "modules": "import topbar from \"../vendor/topbar\";", "mode": "comment_out" }),
+        ),
+        case(
+            "remove_jsonc_value",
+            PACKAGE_JSON,
+            json!({ "path": ["dependencies", "topbar"] }),
+        ),
+        case(
+            "remove_live_socket_exposure",
+            BOILERPLATE_JS,
+            json!({ "mode": "comment_out" }),
+        ),
+        case(
+            "remove_live_socket_option_entries_from_ast",
+            APP_JS,
+            json!({ "option": "hooks", "entries": ["CopyHook"] }),
+        ),
+        case(
+            "remove_objects_of_hooks_from_ast",
+            APP_JS,
+            json!({ "object_names": ["CopyHook"], "mode": "comment_out" }),
+        ),
+        case(
+            "remove_service_worker_registration",
+            BOILERPLATE_JS,
+            json!({ "path": "/sw.js" }),
+        ),
+        case(
+            "remove_tailwind_directive",
+            TAILWIND_CSS,
+            json!({ "kind": "source", "path": "../js" }),
+        ),
+        case(
+            "remove_tailwind_theme_variables",
+            TAILWIND_CSS,
+            json!({ "names": ["--color-brand"] }),
+        ),
+        case(
+            "remove_var_object_properties",
+            BOILERPLATE_JS,
+            json!({ "var_name": "Hooks", "keys": ["Chart"] }),
+        ),
+        case(
+            "rename_css_selector",
+            APP_CSS,
+            json!({ "from": ".btn", "to": ".button" }),
+        ),
+        case(
+            "rename_identifier",
+            APP_JS,
+            json!({ "old_name": "csrfToken", "new_name": "token" }),
+        ),
+        case(
+            "replace_import",
+            APP_JS,
+            json!({ "source": "phoenix", "import_lines": "import { Socket, Presence } from \"phoenix\";" }),
+        ),
+        case(
+            "set_jsonc_value",
+            PACKAGE_JSON,
+            json!({ "path": ["scripts", "deploy"], "value": { "b": 1, "a": [true, null] } }),
+        ),
+        case(
+            "set_yaml_value",
+            STYLELINTRC,
+            json!({ "path": ["rules", "order"], "value": { "b": 1, "a": [true, null] } }),
+        ),
+        case("transpile_js", APP_JS, json!({ "target": "es2015" })),
+        case("validate_css", "a { color: red; ", Value::Null),
+        case(
+            "validate_js",
+            "let a = ;\nlet a = 1;",
+            json!({ "engine": "swc" }),
+        ),
+        case(
+            "wrap_live_socket_hooks_to_ast",
+            APP_JS,
+            json!({ "callee": "withHooks", "args": ["colocatedHooks"] }),
+        ),
+    ]
+}
+
+/// Runs a case [`RUNS`] times on each of [`THREADS`] threads, returning a message for each
+/// result differing from the first one.
+pub fn check_stable(transform: &str, run: impl Fn() -> String + Sync) -> Vec<String> {
+    let results: Vec<String> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| scope.spawn(|| (0..RUNS).map(|_| run()).collect::<Vec<_>>()))
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().expect("the transform does not panic"))
+            .collect()
+    });
+
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| *result != &results[0])
+        .map(|(index, result)| {
+            format!(
+                "{} gave another result on run {}:\n--- first\n{}\n--- run {}\n{}",
+                transform, index, results[0], index, result
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms_are_stable() {
+        let cases = cases();
+        let names: Vec<&str> = cases.iter().map(|case| case.transform).collect();
+        assert_eq!(names, TRANSFORMS, "every built-in transform has a case");
+
+        let failures: Vec<String> = cases
+            .iter()
+            .flat_map(|case| {
+                check_stable(case.transform, || {
+                    format!(
+                        "{:?}",
+                        run_transform(case.transform, case.source, &case.args)
+                    )
+                })
+            })
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    }
+
+    #[test]
+    fn test_fixtures_are_stable() {
+        let failures: Vec<String> = fixtures()
+            .iter()
+            .flat_map(|fixture| check_stable(&fixture.transform, || fixture.run().1))
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    }
+
+    #[test]
+    fn test_check_stable() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let counter = AtomicUsize::new(0);
+        let failures = check_stable("counter", || {
+            counter.fetch_add(1, Ordering::Relaxed).min(1).to_string()
+        });
+        assert_eq!(failures.len(), THREADS * RUNS - 1);
+    }
+}
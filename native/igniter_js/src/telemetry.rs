@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! Timings of the NIF calls, so the Elixir side can emit `:telemetry` events.
+//!
+//! Once enabled with [`set_enabled`], the parsers and code generators record how long they
+//! take with [`measure`] in a thread-local recording, which [`take`] turns into the
+//! [`Metrics`] of the call when its response is encoded. A NIF runs on a single thread, so
+//! the recording of a call never mixes with another one.
+
+#[cfg(feature = "nif")]
+use rustler::NifMap;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// A step of a NIF call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Parsing a source, JavaScript or CSS.
+    Parse,
+    /// Emitting or printing code from a syntax tree.
+    Codegen,
+}
+
+#[derive(Debug)]
+struct Recording {
+    started: Instant,
+    parse: Duration,
+    codegen: Duration,
+    input_size: usize,
+}
+
+/// The timings of a NIF call, in microseconds, and the size of its input in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifMap))]
+pub struct Metrics {
+    pub parse_time: u64,
+    /// The time spent between the first parse and the response, besides parsing and code
+    /// generation, e.g. visiting the syntax tree.
+    pub transform_time: u64,
+    pub codegen_time: u64,
+    /// The size of the first source parsed, i.e. the file content for most NIFs.
+    pub input_size: u64,
+}
+
+/// Turns the recording of the metrics on or off for every NIF call.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        RECORDING.with(|recording| recording.borrow_mut().take());
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, adding its duration to the given phase of the current call when the metrics
+/// are enabled. `input_size` is the size of the parsed source, `0` for the other phases.
+///
+/// # Example
+/// ```rust
+/// let module = measure(Phase::Parse, source.len(), || parse(source));
+/// ```
+pub fn measure<T>(phase: Phase, input_size: usize, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+
+    let started = Instant::now();
+    let result = f();
+    let elapsed = started.elapsed();
+
+    RECORDING.with(|recording| {
+        let mut recording = recording.borrow_mut();
+        let recording = recording.get_or_insert(Recording {
+            started,
+            parse: Duration::ZERO,
+            codegen: Duration::ZERO,
+            input_size,
+        });
+        match phase {
+            Phase::Parse => recording.parse += elapsed,
+            Phase::Codegen => recording.codegen += elapsed,
+        }
+    });
+    result
+}
+
+/// Ends the recording of the current call, returning its metrics when they are enabled.
+/// A call which parsed nothing has all its metrics at zero.
+pub fn take() -> Option<Metrics> {
+    let recording = RECORDING.with(|recording| recording.borrow_mut().take());
+    if !is_enabled() {
+        return None;
+    }
+
+    let Some(recording) = recording else {
+        return Some(Metrics::default());
+    };
+    let transform = recording
+        .started
+        .elapsed()
+        .saturating_sub(recording.parse + recording.codegen);
+    Some(Metrics {
+        parse_time: recording.parse.as_micros() as u64,
+        transform_time: transform.as_micros() as u64,
+        codegen_time: recording.codegen.as_micros() as u64,
+        input_size: recording.input_size as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::javascript::phoenix::extend_hook_object_to_ast;
+
+    #[test]
+    fn test_metrics() {
+        // The other tests run on other threads, so enabling the metrics does not change
+        // their recordings, only whether they are kept.
+        let code = "let liveSocket = new LiveSocket(\"/live\", Socket, {});";
+        set_enabled(true);
+        extend_hook_object_to_ast(code, vec!["CopyHook"]).unwrap();
+        let metrics = take().unwrap();
+        assert_eq!(metrics.input_size, code.len() as u64);
+        assert_eq!(take(), Some(Metrics::default()));
+
+        set_enabled(false);
+        extend_hook_object_to_ast(code, vec!["CopyHook"]).unwrap();
+        assert_eq!(take(), None);
+        assert_eq!(measure(Phase::Parse, 1, || 42), 42);
+    }
+}
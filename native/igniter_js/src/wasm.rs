@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2024 Shahryar Tavakkoli
+// SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs.contributors>
+//
+// SPDX-License-Identifier: MIT
+
+//! The entry point of the WASM build, so the transforms run in a browser playground or a
+//! Node.js based tool, without the BEAM:
+//!
+//! ```sh
+//! cargo build --release --target wasm32-wasip1 --no-default-features --features wasm
+//! ```
+//!
+//! The module exports plain C functions exchanging JSON through its memory, so any WASM
+//! runtime can call it without generated bindings. The host allocates a buffer with
+//! [`igniter_js_alloc`], writes a request to it and calls [`igniter_js_run`]:
+//!
+//! ```json
+//! {"transform": "insert_import_to_ast", "source": "...", "args": {"import_lines": "..."}}
+//! ```
+//!
+//! The response is `{"status": "ok", "result": ...}` or `{"status": "error", "error": "..."}`,
+//! see [`crate::core::run_request`]. The 32-bit pointer and the length of the response
+//! are packed in the returned integer, the pointer in the high bits, and the host frees
+//! both buffers with [`igniter_js_free`]. The pointers of the other targets do not fit, so
+//! the module is only built for `wasm32`.
+
+use crate::core::run_request;
+
+/// Allocates a buffer of `len` bytes for the host to write a request to.
+#[no_mangle]
+pub extern "C" fn igniter_js_alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0u8; len].into_boxed_slice()) as *mut u8
+}
+
+/// Frees a buffer of [`igniter_js_alloc`] or a response of [`igniter_js_run`].
+///
+/// # Safety
+/// `ptr` and `len` must be the ones of a buffer returned by this module, freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn igniter_js_free(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// Runs the JSON request of `len` bytes at `ptr`, and returns the response packed as
+/// `(ptr << 32) | len`.
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, e.g. a buffer of [`igniter_js_alloc`].
+#[no_mangle]
+pub unsafe extern "C" fn igniter_js_run(ptr: *const u8, len: usize) -> u64 {
+    let request = std::slice::from_raw_parts(ptr, len);
+    let response = run_request(request)
+        .to_string()
+        .into_bytes()
+        .into_boxed_slice();
+    let len = response.len() as u64;
+    let ptr = Box::into_raw(response) as *mut u8 as usize as u64;
+    (ptr << 32) | len
+}
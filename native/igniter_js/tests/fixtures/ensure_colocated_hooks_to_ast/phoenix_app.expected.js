@@ -0,0 +1,39 @@
+import "phoenix_html";
+// Establish Phoenix Socket and LiveView configuration.
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+// import { LiveSocket } from "../../../phoenix_live_view";
+import topbar from "../vendor/topbar";
+import { initDarkMode } from "../vendor/darkmode";
+import CopyMixInstallationHook from "../vendor/mixCopy";
+import { hooks as colocatedHooks } from "phoenix-colocated/my_app";
+let Hooks = {};
+let csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+let liveSocket = new LiveSocket("/live", Socket, {
+    hooks: {
+        ...Hooks,
+        CopyMixInstallationHook,
+        ...colocatedHooks
+    },
+    longPollFallbackMs: 2500,
+    params: {
+        _csrf_token: csrfToken
+    }
+});
+// Show progress bar on live navigation and form submits
+topbar.config({
+    barColors: {
+        0: "#29d"
+    },
+    shadowColor: "rgba(0, 0, 0, .3)"
+});
+window.addEventListener("phx:page-loading-start", (_info)=>topbar.show(300));
+window.addEventListener("phx:page-loading-stop", (_info)=>topbar.hide());
+initDarkMode();
+// connect if there are any LiveViews on the page
+liveSocket.connect();
+// expose liveSocket on window for web console debug logs and latency simulation:
+// >> liveSocket.enableDebug()
+// >> liveSocket.enableLatencySim(1000)  // enabled for duration of browser session
+// >> liveSocket.disableLatencySim()
+window.liveSocket = liveSocket;
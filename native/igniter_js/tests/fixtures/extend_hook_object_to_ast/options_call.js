@@ -0,0 +1,6 @@
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+import { buildOpts } from "./socket_options";
+
+const liveSocket = new LiveSocket("/live", Socket, buildOpts());
+liveSocket.connect();
@@ -0,0 +1,13 @@
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+
+const csrfToken = document.querySelector("meta[name='csrf-token']").getAttribute("content");
+
+// Shared with the admin socket
+const socketOptions = {
+  longPollFallbackMs: 2500,
+  params: { _csrf_token: csrfToken },
+};
+
+const liveSocket = new LiveSocket("/live", Socket, socketOptions);
+liveSocket.connect();
@@ -0,0 +1,25 @@
+import { LitElement, html, css } from "lit";
+import { customElement, property } from "lit/decorators.js";
+import { state } from "lit/decorators.js";
+@customElement("copy-button")
+export class CopyButton extends LitElement {
+    static styles = css`
+    button { cursor: pointer; }
+  `;
+    @property({
+        type: String
+    })
+    accessor text = "";
+    @property({
+        type: Boolean,
+        reflect: true
+    })
+    copied = false;
+    render() {
+        return html`<button @click=${this.copy}>${this.copied ? "Copied" : "Copy"}</button>`;
+    }
+    async copy() {
+        await navigator.clipboard.writeText(this.text);
+        this.copied = true;
+    }
+}
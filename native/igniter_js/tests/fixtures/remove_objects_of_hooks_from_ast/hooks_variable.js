@@ -0,0 +1,12 @@
+import { Socket } from "phoenix";
+import { LiveSocket } from "phoenix_live_view";
+import CopyMixInstallationHook from "../vendor/mixCopy";
+import Chart from "./hooks/chart";
+
+let Hooks = { CopyMixInstallationHook, Chart: Chart };
+Hooks.Modal = { mounted() {} };
+
+let liveSocket = new LiveSocket("/live", Socket, {
+  hooks: Hooks,
+  params: { _csrf_token: csrfToken },
+});
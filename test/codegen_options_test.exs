@@ -0,0 +1,33 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.CodegenOptionsTest do
+  use ExUnit.Case, async: false
+  alias IgniterJs.CodegenOptions
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  test "Keep the quotes and semicolons of the source :: preserve_code_style" do
+    js_code = """
+    import { Socket } from 'phoenix'
+
+    let liveSocket = new LiveSocket('/live', Socket)
+    """
+
+    new_import = ~s(import topbar from "../vendor/topbar";)
+
+    {:ok, :insert_imports, updated} = assert Parser.insert_imports(js_code, new_import)
+    assert updated =~ ~s(import topbar from "../vendor/topbar";)
+
+    try do
+      :ok = CodegenOptions.set(preserve_code_style: true)
+
+      {:ok, :insert_imports, updated} = assert Parser.insert_imports(js_code, new_import)
+      assert updated =~ "import topbar from '../vendor/topbar'\n"
+      assert updated =~ "new LiveSocket('/live', Socket)\n"
+      refute updated =~ ";"
+    after
+      :ok = CodegenOptions.set(preserve_code_style: false)
+    end
+  end
+end
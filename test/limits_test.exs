@@ -0,0 +1,133 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.LimitsTest do
+  use ExUnit.Case, async: false
+  alias IgniterJs.Limits
+  alias IgniterJs.Parsers.CSS.Selectors
+  alias IgniterJs.Parsers.Javascript.{Document, Parser, SyntaxTree}
+  alias IgniterJs.Parsers.JSON.JSONC
+  alias IgniterJs.Parsers.YAML.Config
+
+  test "Refuse the sources exceeding the limits :: too_large" do
+    js_code = ~s(let liveSocket = new LiveSocket("/live", Socket, {hooks: {}});\n)
+    limits = Limits.get()
+
+    try do
+      :ok = Limits.set(max_input_size: 16)
+
+      {:error, :extend_hook_object, {:too_large, reason}} =
+        assert Parser.extend_hook_object(js_code, "CopyHook")
+
+      assert reason == %{limit: :input_size, size: byte_size(js_code), max: 16}
+
+      :ok = Limits.set(max_input_size: 0, max_node_count: 5)
+
+      {:error, :extend_hook_object, {:too_large, %{limit: :node_count, max: 5}}} =
+        assert Parser.extend_hook_object(js_code, "CopyHook")
+    after
+      :ok = Limits.set(limits)
+    end
+
+    {:ok, :extend_hook_object, _} = assert Parser.extend_hook_object(js_code, "CopyHook")
+  end
+
+  test "Refuse the sources exceeding the limits in every parser :: too_large" do
+    limits = Limits.get()
+
+    try do
+      :ok = Limits.set(max_node_count: 3)
+
+      {:error, :ast_to_estree, {:too_large, %{limit: :node_count}}} =
+        assert Parser.ast_to_estree("let a = [1, 2];")
+
+      {:error, :rename, {:too_large, %{limit: :node_count}}} =
+        assert Selectors.rename(".modal { color: red; }", ".modal", ".dialog")
+
+      {:error, :get, {:too_large, %{limit: :node_count}}} =
+        assert JSONC.get(~s({"include": ["a", "b"]}), ["include"])
+
+      {:error, :get, {:too_large, %{limit: :node_count}}} =
+        assert Config.get("packages:\n  - a\n  - b\n", ["packages"])
+    after
+      :ok = Limits.set(limits)
+    end
+  end
+
+  test "Refuse the sources nested deeper than the limit in every parser :: too_large" do
+    nested = fn open, close -> String.duplicate(open, 300) <> String.duplicate(close, 300) end
+    js_code = "let a = #{nested.("[", "]")};"
+
+    {:error, :extend_hook_object, {:too_large, %{limit: :depth, size: 257, max: 256}}} =
+      assert Parser.extend_hook_object(js_code, "CopyHook")
+
+    {:error, :ast_to_estree, {:too_large, %{limit: :depth}}} =
+      assert Parser.ast_to_estree(js_code)
+
+    {:error, :rename, {:too_large, %{limit: :depth}}} =
+      assert Selectors.rename(nested.(".modal:is(", ")") <> " {}", ".modal", ".dialog")
+
+    {:error, :get, {:too_large, %{limit: :depth}}} =
+      assert JSONC.get(nested.("[", "]"), ["include"])
+
+    {:error, :get, {:too_large, %{limit: :depth}}} =
+      assert Config.get("packages: #{nested.("[", "]")}\n", ["packages"])
+  end
+
+  test "Run the functions taking a parsed source under the limits :: too_large" do
+    js_code = ~s(import { Socket } from "phoenix";\nlet liveSocket = new LiveSocket();\n)
+    limits = Limits.get()
+    {:ok, :parse, document} = assert Document.parse(js_code)
+    {:ok, :parse, tree} = assert SyntaxTree.parse(js_code)
+
+    try do
+      :ok = Limits.set(max_input_size: 16)
+
+      {:ok, :query, ["phoenix"]} = assert Document.query(document, :imports)
+      {:ok, :to_source, _code} = assert Document.to_source(document)
+      {:ok, :children, [_import | _]} = assert SyntaxTree.children(tree)
+    after
+      :ok = Limits.set(limits)
+    end
+  end
+
+  test "Stop the calls exceeding the timeout :: timeout" do
+    js_code = String.duplicate("let list = [1, 2, 3].map((n) => n * 2);\n", 20_000)
+    limits = Limits.get()
+
+    try do
+      :ok = Limits.set(timeout: 1)
+
+      {:error, :insert_imports, :timeout} =
+        assert Parser.insert_imports(js_code, ~s(import { Socket } from "phoenix";))
+
+      assert Limits.get().timeout == 1
+    after
+      :ok = Limits.set(limits)
+    end
+
+    {:ok, :insert_imports, _} =
+      assert Parser.insert_imports(js_code, ~s(import { Socket } from "phoenix";))
+  end
+
+  test "Stop the calls exceeding their own timeout :: with_timeout" do
+    js_code = String.duplicate("let list = [1, 2, 3].map((n) => n * 2);\n", 20_000)
+    import_line = ~s(import { Socket } from "phoenix";)
+
+    {:error, :insert_imports, :timeout} =
+      assert Limits.with_timeout(1, fn -> Parser.insert_imports(js_code, import_line) end)
+
+    # The calls after it are back to the timeout of the limits, none by default
+    {:ok, :insert_imports, _} = assert Parser.insert_imports(js_code, import_line)
+
+    task = Task.async(fn -> Parser.insert_imports(js_code, import_line) end)
+
+    Limits.with_timeout(1, fn ->
+      assert Limits.call_timeout() == 1
+      {:ok, :insert_imports, _} = assert Task.await(task)
+    end)
+
+    assert Limits.call_timeout() == nil
+  end
+end
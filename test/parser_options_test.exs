@@ -0,0 +1,35 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.ParserOptionsTest do
+  use ExUnit.Case, async: false
+  alias IgniterJs.ParserOptions
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  test "Parse decorators unless they are disabled :: decorators" do
+    js_code = """
+    import { LitElement } from "lit";
+
+    @customElement("copy-button")
+    export class CopyButton extends LitElement {
+      @property({ type: String }) accessor text = "";
+    }
+    """
+
+    {:ok, :insert_imports, updated} =
+      assert Parser.insert_imports(js_code, ~s(import { state } from "lit/decorators.js";))
+
+    assert updated =~ ~s(@customElement("copy-button"))
+    assert updated =~ "accessor text"
+
+    try do
+      :ok = ParserOptions.set(decorators: false)
+
+      {:error, :insert_imports, _} =
+        assert Parser.insert_imports(js_code, ~s(import { state } from "lit/decorators.js";))
+    after
+      :ok = ParserOptions.set(decorators: true)
+    end
+  end
+end
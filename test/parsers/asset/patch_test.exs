@@ -0,0 +1,61 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.Asset.PatchTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.Asset.Patch
+
+  @valid_app_js "test/assets/validApp.js"
+
+  test "Apply JS and CSS patches as a pair :: apply_patch" do
+    js_ops = [{:extend_hook_object, ["Tooltip"]}]
+    css_ops = [{:append, ".tooltip { color: red; }"}]
+
+    {:ok, :apply_patch, {js, css}} =
+      assert Patch.apply_patch(File.read!(@valid_app_js), js_ops, "body { margin: 0; }", css_ops)
+
+    assert js =~ "Tooltip"
+    assert css =~ ".tooltip { color: red; }"
+
+    {:error, :apply_patch, "css:" <> _} =
+      assert Patch.apply_patch(File.read!(@valid_app_js), js_ops, "body { margin: 0; }", [
+               {:append, ".tooltip { color: red;"}
+             ])
+  end
+
+  test "Check a patch is idempotent :: check_idempotent" do
+    plan = {:js, [{:extend_hook_object, ["Tooltip"]}]}
+
+    {:ok, :check_idempotent, %{idempotent: true, difference: nil}} =
+      assert Patch.check_idempotent(@valid_app_js, plan, :path)
+
+    {:ok, :check_idempotent, %{idempotent: false, difference: %{line: 3}}} =
+      assert Patch.check_idempotent("body {}", {:css, [{:append, ".btn {}"}]})
+  end
+
+  test "Integrate a package in one pass :: install" do
+    package_json = ~s({\n  "dependencies": {\n    "phoenix": "file:../deps/phoenix"\n  }\n}\n)
+
+    spec = [
+      imports: [~s(import Sortable from "sortablejs";)],
+      hooks: ["Sortable"],
+      dependencies: %{"sortablejs" => "^1.15.0", "phoenix" => "^1.7.0"}
+    ]
+
+    {:ok, :install, %{js: js, package_json: package_json, changes: changes}} =
+      assert Patch.install(File.read!(@valid_app_js), package_json, spec)
+
+    assert js =~ ~s(import Sortable from "sortablejs";)
+    assert package_json =~ ~s("sortablejs": "^1.15.0")
+    assert package_json =~ ~s("phoenix": "file:../deps/phoenix")
+    assert {:added_hook, "Sortable"} in changes
+    assert {:skipped_dependency, "phoenix"} in changes
+
+    {:ok, :install, %{js: ^js, changes: again}} = assert Patch.install(js, package_json, spec)
+    skipped = [:skipped_import, :skipped_hook, :skipped_dependency]
+    assert Enum.all?(again, &(elem(&1, 0) in skipped))
+
+    {:error, :install, _} = assert Patch.install(js, nil, spec)
+  end
+end
@@ -17,4 +17,30 @@ defmodule IgniterJSTest.Parsers.CSS.FormatterTest do
     {:ok, _, formatted} = assert Formatter.format("body { color: red; }")
     ^formatted = assert "body {\n  color: red;\n}\n"
   end
+
+  test "Report the first unformatted line :: check_format" do
+    {:ok, :check_format, %{formatted: true}} =
+      assert Formatter.check_format("a {\r\n  color: red;\r\n}\r\n")
+
+    {:ok, :check_format, %{formatted: false, difference: %{line: 4, actual: "b{}"}}} =
+      assert Formatter.check_format("a {\n  color: red;\n}\nb{}\n")
+
+    {:ok, :check_format, %{formatted: false}} =
+      assert Formatter.check_format("a {\n  color: red;\r\n}\n", :content,
+               normalize_newlines: false
+             )
+  end
+
+  test "Report SCSS and Less instead of a syntax error :: format" do
+    scss = "$primary: #fd4f00;\n.btn {\n  &-primary { color: $primary; }\n}\n"
+    {:error, :format, {:unsupported_dialect, :scss}} = assert Formatter.format(scss)
+
+    less = "@primary: #fd4f00;\n.btn {\n  .rounded(4px);\n}\n"
+    {:error, :check_format, {:unsupported_dialect, :less}} = assert Formatter.check_format(less)
+
+    {:error, :format, "Parsing failed due to syntax errors." <> details} =
+      assert Formatter.format("a { color: red;")
+
+    assert details =~ "1:15: expected `}` but instead the file ends"
+  end
 end
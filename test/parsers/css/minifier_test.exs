@@ -0,0 +1,40 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.CSS.MinifierTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.CSS.Minifier
+
+  @css """
+  @import "tailwindcss" source(none);
+
+  /* Buttons */
+  .btn > span {
+    user-select: none;
+    width: calc(100% - 2rem);
+  }
+  """
+
+  test "Minify the CSS considered :: minify" do
+    {:ok, :minify, minified} = assert Minifier.minify(@css)
+
+    assert minified ==
+             ~s|@import "tailwindcss" source(none);| <>
+               ".btn>span{user-select:none;width:calc(100% - 2rem)}"
+
+    {:ok, :minify, minified} = assert Minifier.minify(@css, :content, targets: %{safari: 14})
+    assert minified =~ "{-webkit-user-select:none;user-select:none;"
+
+    {:ok, :minify, ".card :hover{margin:0}"} =
+      assert Minifier.minify(".card :hover {\n  margin : 0;\n}\n")
+  end
+
+  test "Add vendor prefixes for the target browsers :: autoprefix" do
+    {:ok, :autoprefix, prefixed} = assert Minifier.autoprefix(@css, %{safari: 14})
+    assert prefixed =~ "  -webkit-user-select: none;\n  user-select: none;"
+
+    {:ok, :autoprefix, ^prefixed} = assert Minifier.autoprefix(prefixed, %{safari: 14})
+    {:ok, :autoprefix, @css} = assert Minifier.autoprefix(@css, %{chrome: 120})
+  end
+end
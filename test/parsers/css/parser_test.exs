@@ -0,0 +1,18 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.CSS.ParserTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.CSS.Parser
+
+  test "Report the syntax errors of a stylesheet :: validate" do
+    {:ok, :validate, []} = assert Parser.validate("body { color: red; }")
+    assert Parser.valid?("body { color: red; }")
+
+    {:error, :validate, [diagnostic]} = assert Parser.validate("body {\n  color: red;\n")
+    %{severity: :error, line: 3, code_frame: code_frame} = diagnostic
+    assert code_frame =~ "  2 |   color: red;"
+    refute Parser.valid?("body {\n  color: red;\n")
+  end
+end
@@ -0,0 +1,59 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.CSS.SelectorsTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.CSS.Selectors
+
+  test "Rename a selector :: rename" do
+    css = ".phx-modal { color: red; }\n.phx-modal:hover, .phx-modal-content {}\n"
+
+    {:ok, :rename, output} = assert Selectors.rename(css, ".phx-modal", ".modal")
+    assert output == ".modal { color: red; }\n.modal:hover, .phx-modal-content {}\n"
+
+    {:error, :rename, _} = assert Selectors.rename(css, ".missing", ".modal")
+
+    {:ok, :rename, ".sm\\:grid:hover {}"} =
+      assert Selectors.rename(~S(.sm\:flex:hover {}), ~S(.sm\3a flex), ~S(.sm\:grid))
+  end
+
+  test "Move a rule next to another :: move_rule" do
+    css = ".a {}\n.b {}\n.c {}\n"
+
+    {:ok, :move_rule, ".c {}\n.a {}\n.b {}\n"} =
+      assert Selectors.move_rule(css, ".c", ".a", :before)
+
+    {:error, :move_rule, _} = assert Selectors.move_rule(css, ".c", ".missing", :after)
+  end
+
+  test "Insert a rule into a media block :: insert_rule_in_media" do
+    css = ".btn { color: black; }\n"
+    dark = "(prefers-color-scheme: dark)"
+
+    {:ok, :insert_rule_in_media, output} =
+      assert Selectors.insert_rule_in_media(css, dark, ".btn { color: white; }")
+
+    assert output ==
+             ".btn { color: black; }\n\n@media #{dark} {\n  .btn { color: white; }\n}\n"
+
+    {:ok, :insert_rule_in_media, ^output} =
+      assert Selectors.insert_rule_in_media(output, dark, ".btn { color: gray; }")
+
+    {:error, :insert_rule_in_media, _} =
+      assert Selectors.insert_rule_in_media(css, dark, "color: white;")
+  end
+
+  test "Query the declarations of a rule :: declarations" do
+    css = ":root,\n.dark {\n  --primary-color: #fd4f00;\n  --font: serif !important\n}\n"
+
+    {:ok, :declarations, [primary, font]} = assert Selectors.declarations(css, ".dark")
+    assert %{property: "--primary-color", value: "#fd4f00", important: false} = primary
+    assert binary_part(css, primary.start, primary.end - primary.start) =~ "--primary-color"
+    assert %{property: "--font", value: "serif", important: true} = font
+
+    {:ok, :declarations, []} = assert Selectors.declarations(css, ".missing")
+    assert Selectors.has_rule?(css, ":root")
+    refute Selectors.has_rule?(css, ".btn")
+  end
+end
@@ -0,0 +1,62 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.CSS.TailwindTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.CSS.Tailwind
+
+  @app_css """
+  @import "tailwindcss" source(none);
+  @source "../js";
+
+  /* Heroicons */
+  @plugin "../vendor/heroicons";
+  """
+
+  test "Insert and remove directives :: insert_directive" do
+    {:ok, :insert_directive, css} =
+      assert Tailwind.insert_directive(@app_css, :plugin, "../vendor/daisyui")
+
+    assert css =~ "@plugin \"../vendor/heroicons\";\n@plugin \"../vendor/daisyui\";"
+    {:ok, :insert_directive, ^css} =
+      assert Tailwind.insert_directive(css, :plugin, "../vendor/daisyui")
+
+    {:ok, :remove_directive, @app_css} =
+      assert Tailwind.remove_directive(css, :plugin, "../vendor/daisyui")
+  end
+
+  test "Add and remove theme variables :: put_theme_variables" do
+    {:ok, :put_theme_variables, css} =
+      assert Tailwind.put_theme_variables(@app_css, %{"--color-brand" => "#fd4f00"})
+
+    assert css =~ "@theme {\n  --color-brand: #fd4f00;\n}"
+
+    {:ok, :remove_theme_variables, output} =
+      assert Tailwind.remove_theme_variables(css, ["--color-brand"])
+
+    refute output =~ "@theme"
+  end
+
+  test "Manage Tailwind layers :: insert_layer_rule" do
+    css = "@tailwind base;\n@tailwind utilities;\n"
+
+    {:ok, :insert_layer_directive, css} =
+      assert Tailwind.insert_layer_directive(css, :components)
+
+    assert css == "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n"
+    assert Tailwind.has_layer_directive?(css, :components)
+    refute Tailwind.has_layer_block?(css, :components)
+
+    {:ok, :insert_layer_rule, css} =
+      assert Tailwind.insert_layer_rule(css, :components, ".btn { padding: 0; }")
+
+    assert css =~ "@layer components {\n  .btn { padding: 0; }\n}\n"
+    assert Tailwind.has_layer_block?(css, :components)
+
+    {:ok, :insert_layer_rule, ^css} =
+      assert Tailwind.insert_layer_rule(css, :components, ".btn { padding: 0; }")
+
+    {:error, :insert_layer_rule, _} = assert Tailwind.insert_layer_rule(css, :base, "color: red;")
+  end
+end
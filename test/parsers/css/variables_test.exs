@@ -0,0 +1,31 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.CSS.VariablesTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.CSS.Variables
+
+  test "Set custom properties in the :root block :: put" do
+    css = """
+    @import "tailwindcss";
+
+    :root {
+      --color-brand: #000; /* brand */
+    }
+    """
+
+    {:ok, :put, output} =
+      assert Variables.put(css, %{"--color-brand" => "#fd4f00", "--radius" => "4px"})
+
+    assert output =~ "--color-brand: #fd4f00; /* brand */"
+    assert output =~ "--radius: 4px;"
+
+    {:ok, :put, output} =
+      assert Variables.put(css, [{"--brand", "#fff"}], :content, selector: ".dark")
+
+    assert output =~ ".dark {\n  --brand: #fff;\n}"
+
+    {:error, :put, _} = assert Variables.put(css, %{"color" => "red"})
+  end
+end
@@ -0,0 +1,36 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.HTML.ScriptTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.HTML.Script
+
+  @root_html """
+  <html>
+    <head>
+      <script defer phx-track-static type="text/javascript" src={~p"/assets/app.js"}>
+      </script>
+      <script>
+        window.dataLayer = window.dataLayer || [];
+      </script>
+    </head>
+  </html>
+  """
+
+  test "Find the inline script blocks :: find_blocks" do
+    {:ok, :find_blocks, [block]} = assert Script.find_blocks(@root_html)
+    assert block.index == 0
+    assert block.content =~ "window.dataLayer"
+  end
+
+  test "Patch an inline script block :: patch_blocks" do
+    ops = [{:insert_at_index, "gtag(\"js\", new Date());", 1}]
+    {:ok, :patch_blocks, html} = assert Script.patch_blocks(@root_html, 0, ops)
+
+    assert html =~ "gtag(\"js\", new Date());"
+    assert html =~ ~s(src={~p"/assets/app.js"})
+
+    {:error, :patch_blocks, _} = assert Script.patch_blocks(@root_html, 3, [:format])
+  end
+end
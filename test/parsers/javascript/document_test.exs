@@ -0,0 +1,30 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.Javascript.DocumentTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.Javascript.Document
+
+  @valid_app_js "test/assets/validApp.js"
+
+  test "Parse once, query and mutate a document :: Document" do
+    {:ok, :parse, document} = assert Document.parse(@valid_app_js, :path)
+    {:ok, :query, imports} = assert Document.query(document, :imports)
+    assert "phoenix_live_view" in imports
+
+    {:ok, :apply, _document} =
+      assert Document.apply(document, [{:extend_hook_object, ["TooltipHook"]}])
+
+    {:ok, :query, hooks} = assert Document.query(document, :hooks)
+    assert "TooltipHook" in hooks
+
+    {:ok, :query, %{kind: :let}} =
+      assert Document.query(document, {:find_variable, "liveSocket"})
+    {:ok, :to_source, code} = assert Document.to_source(document)
+    assert code =~ "TooltipHook"
+
+    {:error, :apply, _} = assert Document.apply(document, [{:insert_at_index, "let = ;", 0}])
+    {:error, :parse, _} = assert Document.parse("let = ;")
+  end
+end
@@ -34,4 +34,63 @@ defmodule IgniterJSTest.Parsers.Javascript.FormatterTest do
     ^formatted =
       assert "function test() {\n  // expose liveSocket on window for web console debug logs and latency simulation:\n  console.log(\"hello world\");\n  // expose liveSocket on window for web console debug logs and latency simulation:\n}\n"
   end
+
+  test "Format the JS with a source map :: format" do
+    {:ok, :format, %{code: "let a = 1;\n", source_map: source_map}} =
+      assert Formatter.format("let a=1", :content, source_map: true, source_name: "app.js")
+
+    assert source_map =~ ~s("version":3)
+    assert source_map =~ ~s("sources":["app.js"])
+  end
+
+  test "Format a CommonJS config :: format" do
+    config = "const legacy = 010;\nmodule.exports = {plugins: {tailwindcss: {}}}"
+
+    {:ok, :format, formatted} = assert Formatter.format(config)
+    assert formatted =~ "module.exports = { plugins: { tailwindcss: {} } };"
+
+    {:ok, :format, ^formatted} = assert Formatter.format(config, :content, module_kind: :script)
+    {:error, :format, _} = assert Formatter.format(config, :content, module_kind: :module)
+  end
+
+  test "Format the template literals :: format" do
+    js_code = "const label = `Hello ${ name }`;\nconst Button = styled.button`color:red`;\n"
+
+    {:ok, :format, formatted} = assert Formatter.format(js_code)
+    assert formatted =~ "`Hello ${name}`"
+
+    {:ok, :format, preserved} =
+      assert Formatter.format(js_code, :content, template_literals: :preserve)
+
+    assert preserved =~ "`Hello ${ name }`"
+    assert preserved =~ "styled.button`color:red`"
+
+    {:ok, :format, embedded} =
+      assert Formatter.format(js_code, :content, template_literals: :embedded)
+
+    assert embedded =~ "`Hello ${ name }`"
+    assert embedded =~ "styled.button`\n  color: red;\n`;"
+  end
+
+  test "Report the first unformatted line :: check_format" do
+    {:ok, :check_format, %{formatted: true, difference: nil}} =
+      assert Formatter.check_format("let a = 1;\r\nlet b = 2;\r\n")
+
+    {:ok, :check_format, %{formatted: false, difference: difference}} =
+      assert Formatter.check_format("let a = 1;\nlet b=2;\n")
+
+    %{line: 2, expected: "let b = 2;", actual: "let b=2;"} = difference
+
+    {:ok, :check_format, %{formatted: false}} =
+      assert Formatter.check_format("let a = 1;", :content, final_newline: :require)
+  end
+
+  test "Format only a range of the JS :: format_range" do
+    code = "let   a=1\nlet b={c:1}\n"
+
+    {:ok, :format_range, "let   a=1\nlet b = { c: 1 };\n"} =
+      assert Formatter.format_range(code, 10, byte_size(code))
+
+    {:error, :format_range, _msg} = assert Formatter.format_range(code, 0, 100)
+  end
 end
@@ -0,0 +1,45 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.Javascript.MinifierTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.Javascript.Minifier
+
+  test "Minify the JS considered :: minify" do
+    js_code = """
+    // Adds the two numbers
+    export function add(first, second) {
+      const total = first + second;
+      debugger;
+      return total;
+    }
+    """
+
+    {:ok, :minify, "export function add(a,b){const c=a+b;return c;}"} =
+      assert Minifier.minify(js_code)
+
+    {:ok, :minify, minified} = assert Minifier.minify(js_code, :content, mangle: false)
+    assert minified =~ "const total=first+second;"
+    refute minified =~ "debugger"
+
+    {:ok, :minify, minified} = assert Minifier.minify(js_code, :content, compress: false)
+    assert minified =~ "debugger;"
+
+    {:ok, :minify, "let a=6e4;"} = assert Minifier.minify("let a = 60 * 1000;")
+
+    {:ok, :minify, "let a=60*1e3;"} =
+      assert Minifier.minify("let a = 60 * 1000;", :content, level: 1)
+
+    {:error, :minify, _} = assert Minifier.minify(js_code, :content, level: 4)
+    {:error, :minify, _} = assert Minifier.minify(js_code, :content, target: :es1999)
+    {:error, :minify, _} = assert Minifier.minify("let = ;")
+  end
+
+  test "Minify the JS with a source map :: minify" do
+    {:ok, :minify, %{code: "let a=1;", source_map: source_map}} =
+      assert Minifier.minify("let a = 1;", :content, source_map: true)
+
+    assert source_map =~ ~s("sources":["input.js"])
+  end
+end
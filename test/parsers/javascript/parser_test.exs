@@ -354,6 +354,14 @@ defmodule IgniterJSTest.Parsers.Javascript.ParserTest do
     2 = assert statistics.imports
     0 = assert statistics.trys
     0 = assert statistics.throws
+    3 = assert statistics.console_calls
+    nil = assert statistics.locations
+
+    {:ok, :statistics, statistics} =
+      assert Parser.statistics(@valid_ast_statistics, :path, locations: true)
+
+    [%{line: 3, column: 0} | _] = assert statistics.locations.console_calls
+    2 = assert length(statistics.locations.debuggers)
   end
 
   test "Extend some objects inside a var object :: extend_var_object_by_object_names" do
@@ -407,6 +415,24 @@ defmodule IgniterJSTest.Parsers.Javascript.ParserTest do
     1 = assert string_counter(considerd_output, "(^|[^.])NoneComponent")
   end
 
+  test "Remove properties of a var object :: remove_var_object_properties" do
+    code = """
+    const Components = { ...Base, Modal, Chart: LazyChart };
+    export default Components;
+    """
+
+    {:ok, :remove_var_object_properties, output} =
+      assert Parser.remove_var_object_properties(code, "Components", ["...Base", "Chart"])
+
+    assert output =~ "const Components = {\n    Modal\n};"
+
+    {:ok, :remove_var_object_properties, ^code} =
+      assert Parser.remove_var_object_properties(code, "Components", "Missing")
+
+    {:error, :remove_var_object_properties, _} =
+      assert Parser.remove_var_object_properties(code, "Hooks", "Modal")
+  end
+
   test "Check existing vars :: exist_var" do
     code = """
     import { foo } from "module-name";
@@ -429,6 +455,31 @@ defmodule IgniterJSTest.Parsers.Javascript.ParserTest do
     {:ok, :exist_var, true} = assert Parser.exist_var(code, "igniterJS")
   end
 
+  test "Find a declaration with scope and kind restrictions :: var_info" do
+    code = """
+    export const Hooks = {};
+
+    function setup() {
+      let hooks = buildHooks();
+    }
+    """
+
+    {:ok, :var_info, %{kind: :const, top_level: true, init: :object}} =
+      assert Parser.var_info(code, "Hooks")
+
+    {:ok, :var_info, %{kind: :let, top_level: false, init: :call}} =
+      assert Parser.var_info(code, "hooks")
+
+    {:error, :var_info, false} = assert Parser.var_info(code, "hooks", :content, top_level: true)
+    {:error, :exist_var, false} = assert Parser.exist_var(code, "Hooks", :content, kinds: [:let])
+    {:ok, :exist_var, true} = assert Parser.exist_var(code, "Hooks", :content, kinds: [:const])
+
+    {:error, :exist_var, reason} =
+      assert Parser.exist_var("const Hooks = ", "Hooks", :content, top_level: true)
+
+    assert reason =~ "Failed to parse"
+  end
+
   test "Convert JS AST to estree" do
     code = """
     let Hooks = {};
@@ -458,6 +509,144 @@ defmodule IgniterJSTest.Parsers.Javascript.ParserTest do
     1 = assert length(parsed["errors"])
   end
 
+  test "Convert JS AST to compact and per-statement estree :: ast_to_estree_statements" do
+    code = """
+    import { LiveSocket } from "phoenix_live_view";
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    {:ok, :ast_to_estree, pretty} = assert Parser.ast_to_estree(code)
+    {:ok, :ast_to_estree, ^pretty} = assert Parser.ast_to_estree(code, :content, compact: true)
+
+    {:ok, :ast_to_estree_statements, [import, declaration]} =
+      assert Parser.ast_to_estree_statements(code)
+
+    "ImportDeclaration" = assert import["type"]
+    "VariableDeclaration" = assert declaration["type"]
+
+    {:ok, :ast_to_estree_statements, [json | _]} =
+      assert Parser.ast_to_estree_statements(code, :content, compact: true, decode: false)
+
+    assert is_binary(json)
+    {:error, :ast_to_estree_statements, _} = assert Parser.ast_to_estree_statements("let = ;")
+  end
+
+  test "Convert JS AST to estree with span formats and node type filters" do
+    code = """
+    const title = "é";
+    import { LiveSocket } from "phoenix_live_view";
+    """
+
+    {:ok, :ast_to_estree, utf16} = assert Parser.ast_to_estree(code)
+    {:ok, :ast_to_estree, utf8} = assert Parser.ast_to_estree(code, :content, spans: :utf8)
+
+    19 = assert Enum.at(utf16["program"]["body"], 1)["start"]
+    20 = assert Enum.at(utf8["program"]["body"], 1)["start"]
+
+    {:ok, :ast_to_estree, filtered} =
+      assert Parser.ast_to_estree(code, :content, spans: :none, node_types: ["ImportDeclaration"])
+
+    [import] = assert filtered["program"]["body"]
+    "ImportDeclaration" = assert import["type"]
+    refute Map.has_key?(import, "start")
+  end
+
+  test "Generate JS code from estree :: estree_to_source" do
+    code = """
+    import { LiveSocket } from "phoenix_live_view";
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    {:ok, :ast_to_estree, estree} = assert Parser.ast_to_estree(code)
+    {:ok, :estree_to_source, ^code} = assert Parser.estree_to_source(estree)
+
+    {:ok, :ast_to_estree_statements, [import, declaration]} =
+      assert Parser.ast_to_estree_statements(code, :content, spans: :none)
+
+    {:ok, :estree_to_source, swapped} = assert Parser.estree_to_source([declaration, import])
+    assert String.starts_with?(swapped, "let liveSocket")
+
+    {:error, :estree_to_source, _} = assert Parser.estree_to_source(%{"type" => "Unknown"})
+  end
+
+  test "Compare two sources structurally :: ast_diff" do
+    old = """
+    import { Socket } from "phoenix";
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    liveSocket.connect();
+    """
+
+    new = """
+    import {Socket} from 'phoenix'
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: Hooks})
+    liveSocket.connect()
+    window.liveSocket = liveSocket
+    """
+
+    {:ok, :ast_diff, [changed, added]} = assert Parser.ast_diff(old, new)
+    %{kind: :changed, category: :variable, name: "liveSocket"} = assert changed
+    %{kind: :added, name: "window.liveSocket", old_location: nil} = assert added
+    %{line: 4} = assert added.new_location
+
+    {:ok, :ast_diff, []} = assert Parser.ast_diff(old, old)
+    {:error, :ast_diff, _} = assert Parser.ast_diff(old, "let = ;")
+  end
+
+  test "Merge a generated file with the user's file :: merge_generated" do
+    base = """
+    import { Socket } from "phoenix";
+    let delay = 200;
+    topbar.config({ barColors: { 0: "#29d" } });
+    """
+
+    generated = """
+    import { Socket } from "phoenix";
+    import topbar from "topbar";
+    let delay = 200;
+    topbar.config({ barColors: { 0: "#00f" } });
+    """
+
+    user = """
+    import { Socket } from "phoenix";
+    let delay = 500;
+    topbar.config({ barColors: { 0: "#f00" } });
+    """
+
+    {:ok, :merge_generated, %{code: code, conflicts: [conflict]}} =
+      assert Parser.merge_generated(base, generated, user)
+
+    assert code =~ ~s(import topbar from "topbar";\nlet delay = 500;)
+    assert code =~ "<<<<<<< user"
+    %{category: :expression, name: "topbar.config"} = assert conflict
+
+    {:ok, :merge_generated, %{code: ^user, conflicts: []}} =
+      assert Parser.merge_generated(base, base, user)
+
+    {:error, :merge_generated, _} = assert Parser.merge_generated(base, generated, "let = ;")
+  end
+
+  test "Convert offsets and positions :: offset_to_position, position_to_offset" do
+    code = """
+    const title = "é";
+    import { LiveSocket } from "phoenix_live_view";
+    """
+
+    {:ok, :ast_to_estree, estree} = assert Parser.ast_to_estree(code)
+    start = Enum.at(estree["program"]["body"], 1)["start"]
+
+    {:ok, :offset_to_position, %{line: 2, column: 0}} =
+      assert Parser.offset_to_position(code, start)
+
+    {:ok, :position_to_offset, ^start} = assert Parser.position_to_offset(code, {2, 0})
+
+    {:ok, :position_to_offset, offset} =
+      assert Parser.position_to_offset(code, %{line: 2, column: 0}, :content, encoding: :utf8)
+
+    "import" <> _ = assert binary_part(code, offset, byte_size(code) - offset)
+    {:error, :offset_to_position, _} = assert Parser.offset_to_position(code, 1000)
+    {:error, :position_to_offset, _} = assert Parser.position_to_offset(code, {9, 0})
+  end
+
   test "inserts JavaScript code at a specific index" do
     js_code = """
     function a() {}
@@ -759,4 +948,956 @@ defmodule IgniterJSTest.Parsers.Javascript.ParserTest do
     Regex.scan(Regex.compile!(pattern), string)
     |> length()
   end
+
+  test "Detect third-party SDK initializations :: sdk_initializations" do
+    code = """
+    Sentry.init({ dsn: "https://key@sentry.io/1" });
+    posthog.init("phc_key", { api_host: window.host });
+    """
+
+    {:ok, :sdk_initializations, [sentry, posthog]} = assert Parser.sdk_initializations(code)
+    assert sentry["sdk"] == "sentry"
+    assert sentry["args"] == [%{"dsn" => "https://key@sentry.io/1"}]
+    assert posthog["static"] == false
+
+    {:ok, :sdk_initializations, []} = assert Parser.sdk_initializations(@valid_app_js, :path)
+  end
+
+  test "Prune imports that became unused :: remove_objects_from_hooks" do
+    code = """
+    import Chart from "../vendor/chart";
+    import Tooltip from "../vendor/tooltip";
+    let liveSocket = new LiveSocket("/live", Socket, {
+      hooks: { Chart, Tooltip },
+    });
+    """
+
+    {:ok, :remove_objects_from_hooks, {updated, ["Chart"]}} =
+      assert Parser.remove_objects_from_hooks(code, "Chart", :content, prune_unused_imports: true)
+
+    refute updated =~ "../vendor/chart"
+    assert updated =~ "../vendor/tooltip"
+  end
+
+  test "Prune imports that became unused :: replace_at_index" do
+    code = """
+    import Chart from "../vendor/chart";
+    let hooks = { Chart };
+    """
+
+    {:ok, :replace_at_index, {updated, ["Chart"]}} =
+      assert Parser.replace_at_index(code, "const draw = (Chart) => Chart;", 1, :content,
+               prune_unused_imports: true
+             )
+
+    refute updated =~ "../vendor/chart"
+
+    {:ok, :replace_at_index, updated} =
+      assert Parser.replace_at_index(code, "const draw = (Chart) => Chart;", 1)
+
+    assert updated =~ "../vendor/chart"
+  end
+
+  test "Wire colocated hooks :: ensure_colocated_hooks" do
+    {:ok, :ensure_colocated_hooks, output} =
+      assert Parser.ensure_colocated_hooks(@valid_app_js, :my_app, :path)
+
+    assert output =~ ~s(import { hooks as colocatedHooks } from "phoenix-colocated/my_app";)
+    assert output =~ "...colocatedHooks"
+
+    {:ok, :ensure_colocated_hooks, ^output} =
+      assert Parser.ensure_colocated_hooks(output, "my_app")
+
+    {:error, :ensure_colocated_hooks, _} =
+      assert Parser.ensure_colocated_hooks(@invalid_app_without_live_socket, "my_app", :path)
+  end
+
+  test "List the registered hooks :: list_hooks" do
+    {:ok, :list_hooks, ["...Hooks", "CopyMixInstallationHook"]} =
+      assert Parser.list_hooks(@valid_app_js, :path)
+
+    {:error, :list_hooks, _} = assert Parser.list_hooks(@invalid_app_without_live_socket, :path)
+  end
+
+  test "Merge keys into the LiveSocket params :: extend_live_socket_params" do
+    {:ok, :extend_live_socket_params, output} =
+      assert Parser.extend_live_socket_params(
+               @valid_app_js,
+               [locale: "document.documentElement.lang"],
+               :path
+             )
+
+    assert output =~ "_csrf_token: csrfToken"
+    assert output =~ "locale: document.documentElement.lang"
+
+    {:ok, :extend_live_socket_params, ^output} =
+      assert Parser.extend_live_socket_params(output, %{"locale" => "navigator.language"})
+  end
+
+  test "Deep-merge a spec into an object literal :: merge_object_literal" do
+    code = """
+    const chartOptions = {
+      responsive: true,
+      plugins: { legend: { display: false } }
+    };
+    """
+
+    spec = %{responsive: false, plugins: %{legend: %{position: "top"}}}
+
+    {:ok, :merge_object_literal, output} =
+      assert Parser.merge_object_literal(code, "chartOptions", spec)
+    assert output =~ "responsive: true"
+    assert output =~ "position: \"top\""
+
+    {:ok, :merge_object_literal, ^output} =
+      assert Parser.merge_object_literal(output, "chartOptions", spec)
+
+    {:ok, :merge_object_literal, output} =
+      assert Parser.merge_object_literal(code, "chartOptions", spec, :content, overwrite: true)
+
+    assert output =~ "responsive: false"
+
+    {:error, :merge_object_literal, _} = assert Parser.merge_object_literal(code, "missing", spec)
+  end
+
+  test "Extend export default and module.exports objects :: extend_object_by_object_names" do
+    code = "module.exports = { content: [\"./js/**/*.js\"] };"
+
+    {:ok, :extend_object_by_object_names, output} =
+      assert Parser.extend_object_by_object_names(code, :module_exports, ["plugins"])
+
+    assert output =~ "plugins"
+
+    {:ok, :extend_object_by_object_names, output} =
+      assert Parser.extend_object_by_object_names("export default {};", :export_default, "Copy")
+
+    assert output =~ "Copy"
+
+    {:error, :extend_object_by_object_names, _} =
+      assert Parser.extend_object_by_object_names(code, :export_default, "Copy")
+  end
+
+  test "List the imports of an entrypoint :: analyze_imports" do
+    app_js = """
+    import "phoenix_html";
+    import topbar from "../vendor/topbar";
+    """
+
+    files = %{"assets/vendor/topbar.js" => ~s(import "./nprogress.css";)}
+
+    {:ok, :analyze_imports, [html, topbar, css]} =
+      assert Parser.analyze_imports(app_js, :content, path: "assets/js/app.js", files: files)
+
+    %{source: "phoenix_html", kind: :package, statement: :import, resolved: nil} = html
+
+    %{
+      source: "../vendor/topbar",
+      kind: :file,
+      resolved: "assets/vendor/topbar.js",
+      location: %{line: 2, column: 19}
+    } = topbar
+
+    %{source: "./nprogress.css", importer: "assets/vendor/topbar.js", resolved: nil} = css
+
+    {:error, :analyze_imports, _} = assert Parser.analyze_imports("import {")
+  end
+
+  test "Extend hook objects with value expressions :: extend_hook_object" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {...Hooks}});
+    """
+
+    {:ok, :extend_hook_object, output} =
+      assert Parser.extend_hook_object(js_code, ["Sortable: window.Sortable", Chart: "Charts.Line"])
+
+    assert output =~ "Sortable: window.Sortable"
+    assert output =~ "Chart: Charts.Line"
+
+    {:error, :extend_hook_object, _} =
+      assert Parser.extend_hook_object(js_code, Broken: "window.")
+  end
+
+  test "Extend hook objects with inline definitions :: extend_hook_object" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {Copy}});
+    """
+
+    hooks = [
+      {"ScrollTop", "{ mounted() { window.scrollTo(0, 0); } }"},
+      {"phx:focus", "{ mounted: () => this.el.focus() }"}
+    ]
+
+    {:ok, :extend_hook_object, output} = assert Parser.extend_hook_object(js_code, hooks)
+
+    assert output =~ "ScrollTop: {\n"
+    assert output =~ "mounted () {"
+    assert output =~ ~s("phx:focus": {)
+
+    {:ok, :extend_hook_object, ^output} = assert Parser.extend_hook_object(output, hooks)
+  end
+
+  test "Extend and remove LiveSocket option entries :: extend_live_socket_option" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {Copy}});
+    """
+
+    {:ok, :extend_live_socket_option, output} =
+      assert Parser.extend_live_socket_option(js_code, :uploaders, ["S3", "UpChunk"])
+
+    assert output =~ "uploaders: {"
+    {:ok, _, ^output} = assert Parser.extend_live_socket_option(output, :uploaders, "S3")
+
+    {:ok, :remove_live_socket_option_entries, output} =
+      assert Parser.remove_live_socket_option_entries(output, :uploaders, "S3")
+
+    refute output =~ "S3"
+    assert output =~ "UpChunk"
+
+    {:ok, _, output} =
+      assert Parser.extend_live_socket_option(js_code, :dom, onBeforeElUpdated: "(from, to) => {}")
+
+    assert output =~ "onBeforeElUpdated: (from, to)=>{}"
+  end
+
+  test "Restore the app.js boilerplate :: ensure_live_socket_boilerplate" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    refute Parser.live_socket_connect?(js_code)
+    refute Parser.topbar_config?(js_code)
+
+    {:ok, :ensure_live_socket_boilerplate, output} =
+      assert Parser.ensure_live_socket_boilerplate(js_code)
+
+    assert Parser.live_socket_connect?(output)
+    assert Parser.topbar_config?(output)
+    assert output =~ ~s(import topbar from "../vendor/topbar";)
+    assert output =~ "window.liveSocket = liveSocket"
+    {:ok, _, ^output} = assert Parser.ensure_live_socket_boilerplate(output)
+
+    {:error, :ensure_live_socket_boilerplate, _} =
+      assert Parser.ensure_live_socket_boilerplate("let socket = 1;")
+  end
+
+  test "Set the progress bar colors :: merge_topbar_config" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    topbar.config({barColors: {0: "#29d"}, shadowColor: "rgba(0, 0, 0, .3)"});
+    """
+
+    spec = %{barColors: %{"0" => "#fd4f00"}}
+    {:ok, :merge_topbar_config, output} = assert Parser.merge_topbar_config(js_code, spec)
+    assert output =~ ~s(0: "#fd4f00")
+    assert output =~ ~s[shadowColor: "rgba(0, 0, 0, .3)"]
+    {:ok, _, ^output} = assert Parser.merge_topbar_config(output, spec)
+
+    {:ok, _, ^js_code} =
+      assert Parser.merge_topbar_config(js_code, spec, :content, overwrite: false)
+
+    js_code = ~s(let liveSocket = new LiveSocket("/live", Socket);)
+    {:ok, _, output} = assert Parser.merge_topbar_config(js_code, spec)
+
+    assert Parser.topbar_config?(output)
+    assert output =~ ~s(import topbar from "../vendor/topbar";)
+  end
+
+  test "Toggle the window.liveSocket exposure :: ensure_live_socket_exposed" do
+    js_code = """
+    let adminSocket = new LiveSocket("/live", Socket, {});
+    adminSocket.connect();
+    """
+
+    refute Parser.live_socket_exposed?(js_code)
+
+    {:ok, :ensure_live_socket_exposed, output} =
+      assert Parser.ensure_live_socket_exposed(js_code)
+
+    assert Parser.live_socket_exposed?(output)
+    assert output =~ "window.liveSocket = adminSocket"
+    {:ok, _, ^output} = assert Parser.ensure_live_socket_exposed(output)
+
+    {:ok, :remove_live_socket_exposure, removed} =
+      assert Parser.remove_live_socket_exposure(output)
+
+    refute Parser.live_socket_exposed?(removed)
+    assert removed =~ "adminSocket.connect()"
+
+    {:error, :ensure_live_socket_exposed, _} =
+      assert Parser.ensure_live_socket_exposed("let socket = 1;")
+  end
+
+  test "Wrap and unwrap debug code :: wrap_in_env_guard" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    liveSocket.connect();
+    liveSocket.enableDebug();
+    """
+
+    {:ok, :wrap_in_env_guard, output} =
+      assert Parser.wrap_in_env_guard(js_code, "liveSocket.enableDebug()")
+
+    assert output =~ ~s(if (process.env.NODE_ENV === "development"\) {)
+    {:ok, _, ^output} = assert Parser.wrap_in_env_guard(output, ["liveSocket.enableDebug()"])
+
+    {:ok, :wrap_in_env_guard, output} =
+      assert Parser.wrap_in_env_guard(js_code, "liveSocket.enableDebug()", :content,
+               guard: {:import_meta_env, "DEV"}
+             )
+
+    assert output =~ "if (import.meta.env.DEV) {"
+
+    {:ok, :remove_env_guard, removed} =
+      assert Parser.remove_env_guard(output, "liveSocket.enableDebug()")
+
+    refute removed =~ "import.meta.env"
+    assert removed =~ "liveSocket.enableDebug();"
+
+    {:error, :wrap_in_env_guard, _} =
+      assert Parser.wrap_in_env_guard(js_code, "liveSocket.disconnect()")
+  end
+
+  test "Rename a top-level binding :: rename_identifier" do
+    js_code = """
+    let socket = 1;
+    function connect(socket) { return socket; }
+    const config = { socket };
+    """
+
+    {:ok, :rename_identifier, output} =
+      assert Parser.rename_identifier(js_code, "socket", "liveSocket")
+
+    assert output =~ "let liveSocket = 1;"
+    assert output =~ "return socket;"
+    assert output =~ "socket: liveSocket"
+
+    {:error, :rename_identifier, _} = assert Parser.rename_identifier(js_code, "other", "name")
+    {:error, :rename_identifier, _} = assert Parser.rename_identifier(js_code, "socket", "connect")
+  end
+
+  test "Report unused imports and unreferenced exports :: analyze_unused" do
+    js_code = """
+    import { Socket } from "phoenix";
+    import topbar from "../vendor/topbar";
+    export const liveSocket = new Socket("/live");
+    export function connect() {}
+    """
+
+    {:ok, :analyze_unused, %{unused_imports: [import], unreferenced_exports: [export]}} =
+      assert Parser.analyze_unused(js_code)
+
+    %{local: "topbar", imported: "default", source: "../vendor/topbar"} = import
+    %{exported: "connect", location: %{line: 4}} = export
+
+    {:error, :analyze_unused, _} = assert Parser.analyze_unused("import {")
+  end
+
+  test "List the top-level declarations :: list_top_level_bindings" do
+    js_code = """
+    import { Socket } from "phoenix";
+    let csrfToken = document.querySelector("meta").getAttribute("content");
+    export const { hooks, uploaders } = config;
+    function connect() {}
+    """
+
+    {:ok, :list_top_level_bindings, bindings} = assert Parser.list_top_level_bindings(js_code)
+
+    assert Enum.map(bindings, &{&1.name, &1.kind, &1.exported}) == [
+             {"csrfToken", :let, false},
+             {"hooks", :const, true},
+             {"uploaders", :const, true},
+             {"connect", :function, false}
+           ]
+
+    %{location: %{line: 2, column: 4}} = hd(bindings)
+
+    {:error, :list_top_level_bindings, _} = assert Parser.list_top_level_bindings("let = ;")
+  end
+
+  test "Report the syntax errors of a file :: validate" do
+    {:ok, :validate, []} = assert Parser.validate("let a = 1;")
+    assert Parser.valid?("let a = 1;")
+
+    {:error, :validate, [diagnostic]} = assert Parser.validate("let a = 1;\nlet b = ;")
+    %{severity: :error, line: 2, column: 8, code_frame: code_frame} = diagnostic
+    assert code_frame =~ "> 2 | let b = ;"
+    refute Parser.valid?("let b = ;")
+
+    {:error, :validate, [%{line: 2}]} =
+      assert Parser.validate("let a = 1;\nlet b = ;", :content, engine: :swc)
+  end
+
+  test "Report the same tree whatever the parser :: parse_with_engine, codegen_with_engine" do
+    js_code = """
+    // Phoenix
+    import { Socket } from "phoenix";
+    let socket = new Socket("/socket", {});
+    """
+
+    {:ok, :parse_with_engine, swc} = assert Parser.parse_with_engine(js_code)
+
+    {:ok, :parse_with_engine, oxc} =
+      assert Parser.parse_with_engine(js_code, :content, engine: :oxc)
+
+    assert Map.delete(swc, :engine) == Map.delete(oxc, :engine)
+    assert Enum.map(swc.statements, & &1.kind) == ["ImportDeclaration", "VariableDeclaration"]
+    [%{kind: :line, text: " Phoenix", start: 0, end: 10}] = assert swc.comments
+
+    {:error, :parse_with_engine, %{statements: [], diagnostics: [_ | _]}} =
+      assert Parser.parse_with_engine("let a = ;", :content, engine: :oxc)
+
+    {:ok, :codegen_with_engine, ^js_code} = assert Parser.codegen_with_engine(js_code)
+
+    {:ok, :codegen_with_engine, output} =
+      assert Parser.codegen_with_engine(js_code, :content, engine: :oxc)
+
+    refute output =~ "Phoenix"
+  end
+
+  test "Set up Alpine.js :: register_alpine_plugin, add_alpine_data, ensure_alpine_start" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    Alpine.start();
+    """
+
+    {:ok, :register_alpine_plugin, output} =
+      assert Parser.register_alpine_plugin(js_code, "focus", :content, from: "@alpinejs/focus")
+
+    assert output =~ ~s(import Alpine from "alpinejs";)
+    assert output =~ ~s(import focus from "@alpinejs/focus";)
+
+    {:ok, :add_alpine_data, output} =
+      assert Parser.add_alpine_data(output, "dropdown", "() => ({ open: false })")
+
+    {:ok, :ensure_alpine_start, output} = assert Parser.ensure_alpine_start(output)
+
+    assert output =~ "window.Alpine = Alpine;\nAlpine.plugin(focus);\nAlpine.data(\"dropdown\""
+    assert output =~ "}));\nAlpine.start();\nlet liveSocket"
+
+    {:ok, _, ^output} = assert Parser.ensure_alpine_import(output)
+    {:ok, _, ^output} = assert Parser.add_alpine_data(output, "dropdown", "() => ({})")
+    {:ok, _, ^output} = assert Parser.ensure_alpine_start(output)
+  end
+
+  test "Register a service worker :: ensure_service_worker_registration" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    {:ok, :ensure_service_worker_registration, output} =
+      assert Parser.ensure_service_worker_registration(js_code, "/sw.js", :content,
+               options: ~s({ scope: "/" })
+             )
+
+    assert output =~ ~s(if ("serviceWorker" in navigator\) {)
+    assert output =~ ~s(navigator.serviceWorker.register("/sw.js", {)
+
+    {:ok, _, ^output} = assert Parser.ensure_service_worker_registration(output, "/sw.js")
+
+    {:ok, :remove_service_worker_registration, removed} =
+      assert Parser.remove_service_worker_registration(output, "/sw.js")
+
+    refute removed =~ "serviceWorker"
+  end
+
+  test "Compose a snippet into dom.onBeforeElUpdated :: compose_on_before_el_updated" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {
+      dom: { onBeforeElUpdated(from, to) { morph(from, to); } }
+    });
+    """
+
+    snippet = "if (from._x_dataStack) { window.Alpine.clone(from, to); }"
+
+    {:ok, :compose_on_before_el_updated, output} =
+      assert Parser.compose_on_before_el_updated(js_code, snippet)
+
+    assert output =~ "window.Alpine.clone(from, to);"
+    assert output =~ "morph(from, to);"
+    {:ok, _, ^output} = assert Parser.compose_on_before_el_updated(output, snippet)
+
+    {:error, :compose_on_before_el_updated, _} =
+      assert Parser.compose_on_before_el_updated("let socket = 1;", snippet)
+  end
+
+  test "Target one or all LiveSockets :: list_live_sockets, extend_live_socket_option" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {Copy}});
+    let adminSocket = new LiveSocket("/admin/live", Socket, {hooks: {Copy}});
+    """
+
+    {:ok, :list_live_sockets, ["liveSocket", "adminSocket"]} =
+      assert Parser.list_live_sockets(js_code)
+
+    {:ok, :extend_live_socket_option, output} =
+      assert Parser.extend_live_socket_option(js_code, :hooks, "Admin", :content,
+               socket: "adminSocket"
+             )
+
+    assert length(String.split(output, "Admin\n")) == 2
+
+    {:ok, :remove_live_socket_option_entries, output} =
+      assert Parser.remove_live_socket_option_entries(js_code, :hooks, "Copy", :content,
+               socket: :all
+             )
+
+    refute output =~ "Copy"
+  end
+
+  test "Tell no-op edits from changes :: with_changed" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {CopyHook}});
+    """
+
+    {:ok, :extend_hook_object, %{changed: false, code: ^js_code}} =
+      IgniterJs.Helpers.with_changed(fn -> Parser.extend_hook_object(js_code, "CopyHook") end)
+
+    {:ok, :extend_hook_object, %{changed: true, code: code}} =
+      IgniterJs.Helpers.with_changed(fn -> Parser.extend_hook_object(js_code, "OtherHook") end)
+
+    assert code =~ "OtherHook"
+
+    {:ok, :extend_hook_object, ^code} = Parser.extend_hook_object(js_code, "OtherHook")
+
+    {:error, :extend_hook_object, _} =
+      IgniterJs.Helpers.with_changed(fn ->
+        Parser.extend_hook_object("let socket = 1;", "CopyHook")
+      end)
+  end
+
+  test "Log the actions of an edit :: extend_live_socket_option" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: hooks});
+    """
+
+    {:ok, :extend_live_socket_option, %{code: code, actions: actions}} =
+      assert Parser.extend_live_socket_option(js_code, :hooks, ["MyHook"], :content, log: true)
+
+    assert actions == [{:spread_reference, "hooks"}, {:added_entry, "MyHook"}]
+
+    {:ok, :remove_live_socket_option_entries, %{actions: [{:removed_entry, "MyHook"}]}} =
+      assert Parser.remove_live_socket_option_entries(code, :hooks, "MyHook", :content, log: true)
+  end
+
+  test "Insert imports at a position :: insert_imports" do
+    js_code = """
+    import { Socket } from "phoenix";
+    import * as Hooks from "./hooks";
+    """
+
+    {:ok, :insert_imports, output} =
+      assert Parser.insert_imports(js_code, ~s(import "./polyfill";), :content, position: :top)
+
+    assert output =~ ~r/^import "\.\/polyfill";\nimport \{ Socket \}/
+
+    topbar = ~s(import topbar from "topbar";)
+
+    {:ok, :insert_imports, output} =
+      assert Parser.insert_imports(js_code, topbar, :content, position: {:before, "./hooks"})
+
+    assert output =~ ~s(import topbar from "topbar";\nimport * as Hooks)
+
+    # Namespace and side-effect imports of imported sources are duplicates
+    duplicates = ~s(import * as Hooks from "./hooks";\nimport "phoenix";)
+    {:ok, :insert_imports, ^js_code} = assert Parser.insert_imports(js_code, duplicates)
+  end
+
+  test "Detect and insert dynamic imports :: dynamically_imported, insert_dynamic_import" do
+    js_code = """
+    import { Socket } from "phoenix";
+    let Hooks = {};
+    """
+
+    refute Parser.dynamically_imported?(js_code, "chart.js")
+
+    {:ok, :insert_dynamic_import, output} =
+      assert Parser.insert_dynamic_import(js_code, "Chart", "chart.js", :content,
+               style: :then,
+               anchor: {:after, "Hooks"}
+             )
+
+    assert output =~ ~s(let Hooks = {};\nconst Chart = import("chart.js").then)
+    assert Parser.dynamically_imported?(output, "chart.js")
+    {:ok, :dynamically_imported, true} = assert Parser.dynamically_imported(output, "chart.js")
+
+    {:ok, :insert_dynamic_import, ^output} =
+      assert Parser.insert_dynamic_import(output, "Chart", "chart.js")
+
+    {:error, :insert_dynamic_import, _} =
+      assert Parser.insert_dynamic_import(js_code, "Hooks", "chart.js")
+  end
+
+  test "Add and remove exports :: add_exports, remove_exports" do
+    js_code = """
+    export { default as Copy } from "./copy";
+    """
+
+    chart = ~s(export { default as Chart } from "./chart";)
+    {:ok, :add_exports, output} = assert Parser.add_exports(js_code, chart)
+    assert output =~ chart
+
+    {:ok, :add_exports, ^output} = assert Parser.add_exports(output, chart)
+    {:error, :add_exports, _} = assert Parser.add_exports(output, ~s(export { Chart };))
+
+    {:ok, :remove_exports, ^js_code} =
+      assert Parser.remove_exports(output, ~s(export { Chart } from "./chart";))
+  end
+
+  test "Generate a hooks aggregator :: generate_hooks_index" do
+    {:ok, :generate_hooks_index, output} =
+      assert Parser.generate_hooks_index("", ["copy_to_clipboard.js", "index.js"])
+
+    assert output =~ ~s(import CopyToClipboard from "./copy_to_clipboard";)
+    assert output =~ "export default hooks;"
+
+    {:ok, :generate_hooks_index, ^output} =
+      assert Parser.generate_hooks_index(output, ["copy_to_clipboard.js"])
+  end
+
+  test "Query and patch with a spec :: query_and_patch" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {Copy: CopyHook}});
+    """
+
+    {:ok, :query_and_patch, %{matches: ["{\n    Copy: CopyHook\n}"]}} =
+      assert Parser.query_and_patch(js_code, find: {:var, "liveSocket"}, path: [:hooks])
+
+    {:ok, :query_and_patch, %{code: code}} =
+      assert Parser.query_and_patch(js_code,
+               find: {:var, "liveSocket"},
+               path: ["hooks"],
+               operation: {:insert, "Chart", "ChartHook"}
+             )
+
+    assert code =~ "Chart: ChartHook"
+
+    {:error, :query_and_patch, _} =
+      assert Parser.query_and_patch(js_code, find: {:var, "socket"})
+  end
+
+  test "Replace the expressions matching a pattern :: codemod" do
+    js_code = """
+    topbar.config({barColors: {0: "#29d"}});
+    window.addEventListener("phx:page-loading-start", _info => topbar.show(300));
+    """
+
+    {:ok, :codemod, %{code: code, replacements: 1}} =
+      assert Parser.codemod(js_code, "topbar.config($OPTS)", "topbar.setup($OPTS)")
+
+    assert code =~ "topbar.setup({"
+
+    {:ok, :codemod, %{code: ^js_code, replacements: 0}} =
+      assert Parser.codemod(js_code, "topbar.hide()", "topbar.close()")
+
+    {:error, :codemod, _} = assert Parser.codemod(js_code, "topbar.show($A)", "f($B)")
+  end
+
+  test "Remove key-value and computed entries of hooks :: remove_objects_from_hooks" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {
+      hooks: {Copy, Drag: DragHook, "phx-drop": DropHook, ["phx-sort"]: SortHook}
+    });
+    """
+
+    {:ok, :remove_objects_from_hooks, output} =
+      assert Parser.remove_objects_from_hooks(js_code, ["Drag", "phx-drop", "phx-sort"])
+
+    assert output =~ "hooks: {\n        Copy\n    }"
+  end
+
+  test "Follow LiveSocket options passed as a variable :: extend_hook_object" do
+    code = """
+    const socketOptions = {params: {_csrf_token: csrfToken}, hooks: {Existing}};
+    let liveSocket = new LiveSocket("/live", Socket, socketOptions);
+    """
+
+    {:ok, :extend_hook_object, updated} = assert Parser.extend_hook_object(code, "MyHook")
+    {:ok, :list_hooks, ["Existing", "MyHook"]} = assert Parser.list_hooks(updated)
+
+    code = ~s(let liveSocket = new LiveSocket("/live", Socket, buildOpts());)
+
+    {:error, :extend_hook_object, {:unsupported_options_shape, "buildOpts"}} =
+      assert Parser.extend_hook_object(code, "MyHook")
+
+    {:error, :remove_objects_from_hooks, {:unsupported_options_shape, "buildOpts"}} =
+      assert Parser.remove_objects_from_hooks(code, "MyHook")
+  end
+
+  test "Insert hooks in sorted order :: extend_hook_object" do
+    code = ~s(let liveSocket = new LiveSocket("/live", Socket, {hooks: {...Hooks, Chart, Tooltip}});)
+
+    {:ok, :extend_hook_object, updated} =
+      assert Parser.extend_hook_object(code, ["Zoom", "Dropdown"], :content, sorted: true)
+
+    {:ok, :list_hooks, ["...Hooks", "Chart", "Dropdown", "Tooltip", "Zoom"]} =
+      assert Parser.list_hooks(updated)
+  end
+
+  test "Replace imports with attributes :: replace_import" do
+    js_code = """
+    import data from "./data.json" assert { type: "json" };
+    import styles from "./app.module.css";
+    """
+
+    {:ok, :insert_imports, updated} =
+      assert Parser.insert_imports(js_code, ~s(import topbar from "topbar";))
+
+    assert updated =~ ~s(import data from "./data.json" assert {)
+
+    {:ok, :replace_import, replaced} =
+      assert Parser.replace_import(js_code, "./app.module.css", ~s(import "./theme.css";))
+
+    assert replaced =~ ~s(import "./theme.css";)
+    refute replaced =~ "app.module.css"
+
+    {:error, :replace_import, "The module `./missing.css` is not imported"} =
+      assert Parser.replace_import(js_code, "./missing.css", ~s(import "./theme.css";))
+  end
+
+  test "Create hooks after params :: extend_hook_object" do
+    code = """
+    let liveSocket = new LiveSocket("/live", Socket, {
+      longPollFallbackMs: 2500,
+      params: {_csrf_token: csrfToken},
+      dom: {}
+    });
+    """
+
+    {:ok, :extend_hook_object, updated} =
+      assert Parser.extend_hook_object(code, ["Chart"], :content, key_position: :after_params)
+
+    assert updated =~ ~r/params: \{[^}]*\},\s*hooks: \{\s*Chart\s*\},\s*dom: \{\}/
+  end
+
+  test "Detect minified bundles :: is_probably_minified" do
+    js_code = """
+    import { Socket } from "phoenix";
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {}});
+    liveSocket.connect();
+    """
+
+    {:ok, :is_probably_minified, %{minified: false, signals: []}} =
+      assert Parser.is_probably_minified(js_code)
+
+    refute Parser.probably_minified?(js_code)
+
+    body = Enum.map_join(0..29, fn i -> "var a#{i}=function(e,t){return e+t*#{i}};" end)
+    bundle = "(()=>{#{body}})();\n//# sourceMappingURL=app.js.map\n"
+
+    {:ok, :is_probably_minified, report} = assert Parser.is_probably_minified(bundle)
+    assert report.minified
+    assert :bundle_wrapper in report.signals
+    assert report.source_map_url == "app.js.map"
+    assert Parser.probably_minified?(bundle)
+  end
+
+  test "Find the LiveSocket inside a DOMContentLoaded handler :: extend_hook_object" do
+    code = """
+    window.addEventListener("DOMContentLoaded", function () {
+      let liveSocket = new LiveSocket("/live", Socket, {hooks: {Existing}});
+      liveSocket.connect();
+    });
+    """
+
+    {:ok, :extend_hook_object, updated} = assert Parser.extend_hook_object(code, "MyHook")
+    {:ok, :list_hooks, ["Existing", "MyHook"]} = assert Parser.list_hooks(updated)
+  end
+
+  test "Comment out instead of deleting :: remove_imports, remove_objects_from_hooks" do
+    code = """
+    import "topbar";
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {Old, New}});
+    """
+
+    {:ok, :remove_imports, updated} =
+      assert Parser.remove_imports(code, ~s(import "topbar";), :content, comment_out: true)
+
+    assert updated =~ ~s(// removed by igniter: import "topbar";)
+
+    {:ok, :remove_objects_from_hooks, updated} =
+      assert Parser.remove_objects_from_hooks(updated, "Old", :content, comment_out: true)
+
+    assert updated =~ "// removed by igniter: Old"
+    {:ok, :list_hooks, ["New"]} = assert Parser.list_hooks(updated)
+  end
+
+  test "Tell what a file is for :: file_role" do
+    app_js = """
+    import { LiveSocket } from "phoenix_live_view";
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    {:ok, :file_role, %{role: :app_entry, config_tool: nil}} = assert Parser.file_role(app_js)
+
+    hooks_index = """
+    import Chart from "./chart";
+    export default { Chart };
+    """
+
+    {:ok, :file_role, %{role: :hooks_index}} = assert Parser.file_role(hooks_index)
+
+    {:ok, :file_role, %{role: :config, config_tool: :postcss}} =
+      assert Parser.file_role("module.exports = {};", :content, file_name: "postcss.config.js")
+
+    {:error, :file_role, _} = assert Parser.file_role("let = ;")
+  end
+
+  test "Create, find and rewrite managed regions :: upsert_managed_region" do
+    code = """
+    import { Socket } from "phoenix";
+
+    let liveSocket = new LiveSocket("/live", Socket, {});
+    """
+
+    {:ok, :upsert_managed_region, updated} =
+      assert Parser.upsert_managed_region(code, :hooks, ~s(import Chart from "./chart";))
+
+    assert updated =~ "// <igniter:hooks>\nimport Chart from \"./chart\";\n// </igniter:hooks>\n"
+
+    {:ok, :find_managed_region, %{name: "hooks", line: 3}} =
+      assert Parser.find_managed_region(updated, "hooks")
+
+    {:ok, :upsert_managed_region, rewritten} =
+      assert Parser.upsert_managed_region(updated, "hooks", ~s(import Map from "./map";))
+
+    {:ok, :list_managed_regions, [%{content: ~s(import Map from "./map";\n)}]} =
+      assert Parser.list_managed_regions(rewritten)
+
+    {:error, :upsert_managed_region, _} = assert Parser.upsert_managed_region(code, "hooks", "{")
+  end
+
+  test "Read the initializer of a variable :: variable_initializer" do
+    code = """
+    let liveSocket = new LiveSocket("/live", Socket, {longPollFallbackMs: 2500, hooks: {Chart}});
+    """
+
+    {:ok, :variable_initializer, %{kind: :literal, source: "2500", top_level: true}} =
+      assert Parser.variable_initializer(code, "liveSocket.longPollFallbackMs")
+
+    {:ok, :variable_initializer, %{kind: :object, source: "{Chart}"}} =
+      assert Parser.variable_initializer(code, "liveSocket.hooks")
+
+    {:error, :variable_initializer, false} = assert Parser.variable_initializer(code, "topbar")
+  end
+
+  test "Call transforms registered by other crates :: run_named_transform" do
+    assert is_list(Parser.named_transforms())
+
+    {:error, :run_named_transform, "No transform is registered as `unknown:transform`"} =
+      assert Parser.run_named_transform("let a = 1;", "unknown:transform", %{})
+  end
+
+  test "Sort imports :: organize_imports" do
+    js_code = """
+    import "phoenix_html"
+    import topbar from "../vendor/topbar"
+    import {Socket} from "phoenix"
+    """
+
+    {:ok, :organize_imports, organized} = assert Parser.organize_imports(js_code)
+
+    assert organized == """
+           import "phoenix_html"
+           import {Socket} from "phoenix"
+           import topbar from "../vendor/topbar"
+           """
+
+    {:ok, :organize_imports, ^organized} = assert Parser.organize_imports(organized)
+
+    {:ok, :organize_imports, separated} =
+      assert Parser.organize_imports(js_code, :content, separate_groups: true)
+
+    assert separated =~ ~s(from "phoenix"\n\nimport topbar)
+  end
+
+  test "Wrap the LiveSocket hooks :: wrap_live_socket_hooks" do
+    js_code = """
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: Hooks});
+    """
+
+    {:ok, :wrap_live_socket_hooks, wrapped} =
+      assert Parser.wrap_live_socket_hooks(js_code, "composeHooks", ["Colocated"])
+
+    assert wrapped =~ "hooks: composeHooks(Hooks, Colocated)"
+
+    {:ok, :wrap_live_socket_hooks, ^wrapped} =
+      assert Parser.wrap_live_socket_hooks(wrapped, "composeHooks", ["Colocated"])
+  end
+
+  test "Extend exported hook registries :: extend_var_object_by_object_names" do
+    js_code = """
+    export const Hooks = {Copy};
+    """
+
+    {:ok, :extend_var_object_by_object_names, updated} =
+      assert Parser.extend_var_object_by_object_names(js_code, "Hooks", "Chart")
+
+    assert updated =~ ~r/export const Hooks = \{\s*Copy,\s*Chart\s*\}/
+
+    js_code = """
+    const Hooks = {Copy};
+    export default Hooks;
+    """
+
+    {:ok, :extend_var_object_by_object_names, updated} =
+      assert Parser.extend_var_object_by_object_names(js_code, :default_export, "Chart")
+
+    assert updated =~ ~r/const Hooks = \{\s*Copy,\s*Chart\s*\}/
+
+    js_code = "export default {};"
+
+    {:ok, :extend_var_object_by_object_names, updated} =
+      assert Parser.extend_var_object_by_object_names(js_code, :default_export, "Chart")
+
+    assert updated =~ "Chart"
+  end
+
+  test "Extract a declaration as written :: extract_code_block" do
+    js_code = """
+    let csrfToken = token();
+
+    // Copies the text of the element
+    const CopyHook = {mounted() { copy(this.el) }};
+    """
+
+    {:ok, :extract_code_block, %{code: code, location: location}} =
+      assert Parser.extract_code_block(js_code, "CopyHook")
+
+    assert code == """
+           // Copies the text of the element
+           const CopyHook = {mounted() { copy(this.el) }};\
+           """
+
+    assert %{line: 3, column: 0} = location
+
+    {:error, :extract_code_block, "`Missing` is not declared at the top level"} =
+      assert Parser.extract_code_block(js_code, "Missing")
+  end
+
+  test "Move a declaration to another file :: move_declaration" do
+    app_js = """
+    import {Socket} from "phoenix"
+    import {copy} from "./clipboard"
+
+    // Copies the text of the element
+    const CopyHook = {mounted() { copy(this.el.innerText) }}
+
+    let liveSocket = new LiveSocket("/live", Socket, {hooks: {CopyHook}})
+    """
+
+    {:error, :move_declaration, _} = assert Parser.move_declaration(app_js, "", "CopyHook")
+
+    {:ok, :move_declaration, %{source: source, target: target}} =
+      assert Parser.move_declaration(app_js, "", "CopyHook", import_from: "./hooks/copy")
+
+    assert source =~ ~s(import { CopyHook } from "./hooks/copy";)
+    refute source =~ "Copies the text"
+
+    assert target == """
+           import { copy } from "./clipboard";
+
+           // Copies the text of the element
+           export const CopyHook = {mounted() { copy(this.el.innerText) }}
+           """
+  end
 end
@@ -0,0 +1,30 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.Javascript.SyntaxTreeTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.Javascript.SyntaxTree
+
+  test "Walk and patch a tree node by node :: SyntaxTree" do
+    js_code = """
+    import "phoenix_html";
+    let Hooks = {Copy: CopyHook};
+    """
+
+    {:ok, :parse, tree} = assert SyntaxTree.parse(js_code)
+
+    {:ok, :children, [%{kind: "ImportDecl"} = import, %{kind: "VarDecl"} = hooks]} =
+      assert SyntaxTree.children(tree)
+
+    {:ok, :source, ~s(import "phoenix_html";)} = assert SyntaxTree.source(tree, import)
+    {:ok, :children, [%{kind: "VarDeclarator"}]} = assert SyntaxTree.children(tree, hooks)
+
+    {:ok, :replace, tree} = assert SyntaxTree.replace(tree, import, ~s(import "./html";))
+    {:ok, :to_source, code} = assert SyntaxTree.to_source(tree)
+    assert code == String.replace(js_code, "phoenix_html", "./html")
+
+    {:error, :children, _} = assert SyntaxTree.children(tree, hooks)
+    {:error, :parse, _} = assert SyntaxTree.parse("let = ;")
+  end
+end
@@ -0,0 +1,62 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.Javascript.TranspilerTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.Javascript.Transpiler
+
+  test "Transpile the JS considered :: transpile" do
+    js_code = """
+    const port = config?.port ?? 4000;
+    class Hook {
+      mounted = false;
+    }
+    """
+
+    {:ok, :transpile, transpiled} = assert Transpiler.transpile(js_code, :es2019)
+    assert transpiled =~ "config == null ? void 0 : config.port"
+    assert transpiled =~ "this.mounted = false;"
+
+    {:ok, :transpile, transpiled} = assert Transpiler.transpile(js_code, "esnext")
+    assert transpiled =~ "config?.port ?? 4000"
+
+    {:error, :transpile, _} = assert Transpiler.transpile(js_code, :es5)
+    {:error, :transpile, _} = assert Transpiler.transpile("let = ;", :es2015)
+  end
+
+  test "Transpile the JS with a source map :: transpile" do
+    {:ok, :transpile, %{code: code, source_map: source_map}} =
+      assert Transpiler.transpile("const area = side ** 2;", :es2015, :content,
+               source_map: true,
+               source_name: "area.js"
+             )
+
+    assert code == "const area = Math.pow(side, 2);\n"
+    assert source_map =~ ~s("sources":["area.js"])
+  end
+
+  test "Report the ES features of the JS :: check_compatibility" do
+    js_code = """
+    const {LiveSocket} = await import("phoenix_live_view");
+    let port = config?.port;
+    """
+
+    {:ok, :check_compatibility, result} =
+      assert Transpiler.check_compatibility(js_code, :content, target: :es2020)
+
+    assert result.min_target == "es2022"
+    assert result.exceeds_target
+
+    assert Enum.map(result.features, & &1.feature) == [
+             :block_scoping,
+             :destructuring,
+             :top_level_await,
+             :dynamic_import,
+             :optional_chaining
+           ]
+
+    {:ok, :check_compatibility, %{min_target: "es5", exceeds_target: false}} =
+      assert Transpiler.check_compatibility("var a = 1;")
+  end
+end
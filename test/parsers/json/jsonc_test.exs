@@ -0,0 +1,46 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.JSON.JSONCTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.JSON.JSONC
+
+  @tsconfig """
+  {
+    // Generated by the installer
+    "compilerOptions": {
+      "strict": true,
+    },
+  }
+  """
+
+  test "Get a value :: get" do
+    {:ok, :get, %{"strict" => true}} = assert JSONC.get(@tsconfig, ["compilerOptions"])
+    {:error, :get, _} = assert JSONC.get(@tsconfig, ["include"])
+  end
+
+  test "Set a value keeping the comments :: set" do
+    {:ok, :set, output} = assert JSONC.set(@tsconfig, ["compilerOptions", "types"], ["phoenix"])
+
+    assert output == """
+           {
+             // Generated by the installer
+             "compilerOptions": {
+               "strict": true,
+               "types": [
+                 "phoenix"
+               ],
+             },
+           }
+           """
+
+    {:ok, :set, ^output} = assert JSONC.set(output, ["compilerOptions", "types"], ["phoenix"])
+  end
+
+  test "Remove a value :: remove" do
+    {:ok, :remove, output} = assert JSONC.remove(@tsconfig, ["compilerOptions", "strict"])
+    assert output =~ "\"compilerOptions\": {\n  },"
+    {:error, :remove, _} = assert JSONC.remove("{", ["a"])
+  end
+end
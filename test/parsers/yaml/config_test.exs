@@ -0,0 +1,57 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.Parsers.YAML.ConfigTest do
+  use ExUnit.Case
+  alias IgniterJs.Parsers.YAML.Config
+
+  @workspace """
+  # The pnpm workspace
+  packages:
+    - "assets" # Phoenix
+  """
+
+  @stylelintrc """
+  extends: stylelint-config-standard
+  rules:
+    color-named: always
+  overrides:
+    - files: ["*.scss"]
+  """
+
+  test "Get a value :: get" do
+    {:ok, :get, ["assets"]} = assert Config.get(@workspace, ["packages"])
+    {:ok, :get, ["*.scss"]} = assert Config.get(@stylelintrc, ["overrides", 0, "files"])
+    {:error, :get, _} = assert Config.get(@workspace, ["catalog"])
+  end
+
+  test "Set a value keeping the comments :: set" do
+    {:ok, :set, output} = assert Config.set(@stylelintrc, ["rules", "color-named"], "never")
+    assert output =~ "rules:\n  color-named: never\noverrides:"
+
+    {:ok, :set, output} =
+      assert Config.set(@stylelintrc, ["overrides", 0, "customSyntax"], "postcss-scss")
+
+    assert output =~ ~s(  - files: ["*.scss"]\n    customSyntax: postcss-scss\n)
+    {:ok, :set, ^output} =
+      assert Config.set(output, ["overrides", 0, "customSyntax"], "postcss-scss")
+  end
+
+  test "Append to a sequence :: append" do
+    {:ok, :append, output} = assert Config.append(@workspace, ["packages"], "deps/*")
+
+    assert output == """
+           # The pnpm workspace
+           packages:
+             - "assets" # Phoenix
+             - "deps/*"
+           """
+
+    {:ok, :append, ^output} = assert Config.append(output, ["packages"], "deps/*")
+
+    {:ok, :append, output} =
+      assert Config.append(@workspace, ["onlyBuiltDependencies"], "esbuild")
+    assert output =~ "onlyBuiltDependencies:\n  - esbuild\n"
+  end
+end
@@ -0,0 +1,32 @@
+# SPDX-FileCopyrightText: 2024 igniter_js contributors <https://github.com/ash-project/igniter_js/graphs/contributors>
+#
+# SPDX-License-Identifier: MIT
+
+defmodule IgniterJSTest.TelemetryTest do
+  use ExUnit.Case, async: false
+  alias IgniterJs.{Native, Telemetry}
+  alias IgniterJs.Parsers.Javascript.Parser
+
+  test "Return the metrics of the NIF calls :: enable" do
+    js_code = ~s(let liveSocket = new LiveSocket("/live", Socket, {});\n)
+
+    :ok = Telemetry.enable()
+
+    try do
+      {:ok, _, _, metrics} =
+        Native.extend_hook_object_to_ast_nif(js_code, ["CopyHook"], :append, :end)
+
+      %{parse_time: _, transform_time: _, codegen_time: _, input_size: input_size} = metrics
+      assert input_size == byte_size(js_code)
+
+      # The results of the functions keep their shape
+      {:ok, :extend_hook_object, output} = assert Parser.extend_hook_object(js_code, "CopyHook")
+      assert output =~ "CopyHook"
+      {:ok, :ast_diff, []} = assert Parser.ast_diff(js_code, js_code)
+    after
+      :ok = Telemetry.disable()
+    end
+
+    {:ok, _, _} = Native.extend_hook_object_to_ast_nif(js_code, ["CopyHook"], :append, :end)
+  end
+end